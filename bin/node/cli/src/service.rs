@@ -294,6 +294,39 @@ pub fn new_partial(
 	})
 }
 
+/// Report the state of the fast-unstake pallet to telemetry at every new best block.
+#[cfg(feature = "fast-unstake-telemetry")]
+async fn fast_unstake_telemetry(
+	client: Arc<FullClient>,
+	telemetry: Option<sc_telemetry::TelemetryHandle>,
+) {
+	use pallet_fast_unstake_runtime_api::FastUnstakeApi;
+	use sc_client_api::BlockchainEvents;
+	use sc_telemetry::{telemetry, SUBSTRATE_INFO};
+
+	client
+		.import_notification_stream()
+		.filter(|notification| future::ready(notification.is_new_best))
+		.for_each(move |notification| {
+			let at = generic::BlockId::Hash(notification.hash);
+			match client.runtime_api().telemetry(&at) {
+				Ok(state) => telemetry!(
+					telemetry;
+					SUBSTRATE_INFO;
+					"fast_unstake.interval";
+					"queue_len" => state.queue_len,
+					"head_checked" => state.head_progress.map(|(checked, _)| checked),
+					"head_total" => state.head_progress.map(|(_, total)| total),
+					"eras_to_check" => state.eras_to_check,
+					"can_renominate_later" => state.can_renominate_later,
+				),
+				Err(e) => log::debug!("failed to query fast-unstake telemetry: {}", e),
+			}
+			future::ready(())
+		})
+		.await
+}
+
 /// Result of [`new_full_base`].
 pub struct NewFullBase {
 	/// The task manager of the node.
@@ -409,6 +442,15 @@ pub fn new_full_base(
 		}
 	}
 
+	#[cfg(feature = "fast-unstake-telemetry")]
+	if let Some(ref telemetry) = telemetry {
+		task_manager.spawn_handle().spawn(
+			"telemetry_fast_unstake",
+			None,
+			fast_unstake_telemetry(client.clone(), Some(telemetry.handle())),
+		);
+	}
+
 	let (block_import, grandpa_link, babe_link) = import_setup;
 
 	(with_startup_data)(&block_import, &babe_link);
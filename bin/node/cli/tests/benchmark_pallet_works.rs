@@ -17,7 +17,10 @@
 // along with this program. If not, see <https://www.gnu.org/licenses/>.
 
 use assert_cmd::cargo::cargo_bin;
-use std::process::Command;
+use std::{
+	path::Path,
+	process::{Command, Output},
+};
 
 pub mod common;
 
@@ -47,3 +50,70 @@ fn benchmark_pallet(steps: u32, repeat: u32, should_work: bool) {
 		panic!("Test failed:\n{}", log);
 	}
 }
+
+/// Runs the `addition` benchmark of `frame_benchmarking` and the `set` benchmark of
+/// `pallet_timestamp`, writing the weight files to `output`.
+fn benchmark_two_pallets(output: &Path, args: &[&str]) -> Output {
+	Command::new(cargo_bin("substrate"))
+		.args(["benchmark", "pallet", "--dev"])
+		.args(["--pallet", "*", "--extrinsic", "addition,set"])
+		.args(["--steps", "2", "--repeat", "1"])
+		.args(args)
+		.arg("--output")
+		.arg(output)
+		.output()
+		.unwrap()
+}
+
+/// The names of the files in `dir`.
+fn files_in(dir: &Path) -> Vec<String> {
+	let mut files: Vec<_> = std::fs::read_dir(dir)
+		.unwrap()
+		.map(|entry| entry.unwrap().file_name().into_string().unwrap())
+		.collect();
+	files.sort();
+	files
+}
+
+/// `benchmark pallet` skips the pallets passed with `--exclude-pallets`.
+#[test]
+fn benchmark_pallet_exclude_pallets_works() {
+	let tmp_dir = tempfile::tempdir().expect("could not create a temp dir");
+
+	let output = benchmark_two_pallets(tmp_dir.path(), &["--exclude-pallets", "pallet-timestamp"]);
+	assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+	assert_eq!(files_in(tmp_dir.path()), vec!["frame_benchmarking.rs"]);
+}
+
+/// A failing benchmark does not stop `benchmark pallet` from running the others.
+#[test]
+fn benchmark_pallet_continues_after_failure() {
+	let tmp_dir = tempfile::tempdir().expect("could not create a temp dir");
+
+	// `addition` fails since its component range is empty, `set` has no components.
+	let output = benchmark_two_pallets(tmp_dir.path(), &["--low", "2", "--high", "1"]);
+	assert!(!output.status.success());
+	let stdout = String::from_utf8_lossy(&output.stdout);
+	assert!(stdout.contains("1 benchmark(s) failed"), "{}", stdout);
+	assert!(stdout.contains("frame_benchmarking::addition"), "{}", stdout);
+	assert_eq!(files_in(tmp_dir.path()), vec!["pallet_timestamp.rs"]);
+}
+
+/// `benchmark pallet --list` leaves out the pallets passed with `--exclude-pallets`.
+#[test]
+fn benchmark_pallet_list_excludes_pallets() {
+	let list = |exclude: &[&str]| {
+		let output = Command::new(cargo_bin("substrate"))
+			.args(["benchmark", "pallet", "--dev", "--list"])
+			.args(exclude)
+			.output()
+			.unwrap();
+		assert!(output.status.success());
+		String::from_utf8_lossy(&output.stdout).to_string()
+	};
+
+	assert!(list(&[]).contains("frame_benchmarking, addition"));
+	let listed = list(&["--exclude-pallets", "frame-benchmarking"]);
+	assert!(!listed.contains("frame_benchmarking, "));
+	assert!(listed.contains("pallet_balances, transfer"));
+}
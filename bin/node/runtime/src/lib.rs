@@ -74,7 +74,8 @@ use sp_runtime::{
 		SaturatedConversion, StaticLookup,
 	},
 	transaction_validity::{TransactionPriority, TransactionSource, TransactionValidity},
-	ApplyExtrinsicResult, FixedPointNumber, FixedU128, Perbill, Percent, Permill, Perquintill,
+	ApplyExtrinsicResult, FixedPointNumber, FixedU128, FixedU128E24, Perbill, Percent, Permill,
+	Perquintill,
 };
 use sp_std::prelude::*;
 #[cfg(any(feature = "std", test))]
@@ -134,7 +135,7 @@ pub const VERSION: RuntimeVersion = RuntimeVersion {
 	spec_version: 268,
 	impl_version: 0,
 	apis: RUNTIME_API_VERSIONS,
-	transaction_version: 2,
+	transaction_version: 3,
 	state_version: 1,
 };
 
@@ -542,9 +543,12 @@ parameter_types! {
 	pub const SlashDeferDuration: sp_staking::EraIndex = 24 * 7; // 1/4 the bonding duration.
 	pub const RewardCurve: &'static PiecewiseLinear<'static> = &REWARD_CURVE;
 	pub const MaxNominatorRewardedPerValidator: u32 = 256;
+	pub const MaxExposurePageSize: u32 = 64;
 	pub const OffendingValidatorsThreshold: Perbill = Perbill::from_percent(17);
 	pub OffchainRepeat: BlockNumber = 5;
 	pub HistoryDepth: u32 = 84;
+	pub StakingKickRewardPool: Option<AccountId> = Some(Treasury::account_id());
+	pub const StakingKickReward: Balance = 1 * DOLLARS;
 }
 
 pub struct StakingBenchmarkingConfig;
@@ -575,6 +579,7 @@ impl pallet_staking::Config for Runtime {
 	type EraPayout = pallet_staking::ConvertCurve<RewardCurve>;
 	type NextNewSession = Session;
 	type MaxNominatorRewardedPerValidator = MaxNominatorRewardedPerValidator;
+	type MaxExposurePageSize = MaxExposurePageSize;
 	type OffendingValidatorsThreshold = OffendingValidatorsThreshold;
 	type ElectionProvider = ElectionProviderMultiPhase;
 	type GenesisElectionProvider = onchain::OnChainExecution<OnChainSeqPhragmen>;
@@ -584,17 +589,75 @@ impl pallet_staking::Config for Runtime {
 	type MaxUnlockingChunks = ConstU32<32>;
 	type HistoryDepth = HistoryDepth;
 	type OnStakerSlash = NominationPools;
+	type OnStakingOperation = FastUnstake;
+	type KickRewardPool = StakingKickRewardPool;
+	type KickReward = StakingKickReward;
+	type MaxCommissionBatch = ConstU32<64>;
 	type WeightInfo = pallet_staking::weights::SubstrateWeight<Runtime>;
 	type BenchmarkingConfig = StakingBenchmarkingConfig;
 }
 
+/// Lets fast-unstaked funds join a nomination pool.
+pub struct FastUnstakePools;
+impl pallet_fast_unstake::types::PoolJoiner<AccountId, Balance> for FastUnstakePools {
+	fn join(
+		who: &AccountId,
+		amount: Balance,
+		pool_id: pallet_fast_unstake::types::PoolId,
+	) -> sp_runtime::DispatchResult {
+		NominationPools::join(RuntimeOrigin::signed(who.clone()), amount, pool_id, None)
+	}
+
+	fn join_error(error: sp_runtime::DispatchError) -> pallet_fast_unstake::types::PoolJoinError {
+		use pallet_fast_unstake::types::PoolJoinError;
+		use pallet_nomination_pools::Error;
+		match error {
+			e if e == Error::<Runtime>::PoolNotFound.into() => PoolJoinError::PoolNotFound,
+			e if e == Error::<Runtime>::MinimumBondNotMet.into() =>
+				PoolJoinError::MinimumBondNotMet,
+			e if e == Error::<Runtime>::MaxPoolMembers.into() => PoolJoinError::MaxPoolMembers,
+			e => PoolJoinError::Other(e),
+		}
+	}
+
+	#[cfg(feature = "runtime-benchmarks")]
+	fn setup_open_pool() -> pallet_fast_unstake::types::PoolId {
+		let depositor: AccountId = frame_benchmarking::account("fast_unstake_pool", 0, 0);
+		let amount = NominationPools::depositor_min_bond();
+		let _ = Balances::deposit_creating(&depositor, amount * 2);
+		let lookup = sp_runtime::MultiAddress::Id(depositor.clone());
+		NominationPools::create(
+			RuntimeOrigin::signed(depositor),
+			amount,
+			lookup.clone(),
+			lookup.clone(),
+			lookup,
+		)
+		.expect("depositor is funded; qed");
+		pallet_nomination_pools::LastPoolId::<Runtime>::get()
+	}
+}
+
+parameter_types! {
+	// the head of the fast-unstake queue is aborted after 28 eras.
+	pub const FastUnstakeMaxHeadBlocks: BlockNumber =
+		28 * SessionsPerEra::get() * EPOCH_DURATION_IN_BLOCKS;
+}
+
 impl pallet_fast_unstake::Config for Runtime {
 	type RuntimeEvent = RuntimeEvent;
 	type ControlOrigin = frame_system::EnsureRoot<AccountId>;
 	type BatchSize = ConstU32<128>;
+	type MaxErasToCheckPerBlock = ConstU32<128>;
 	type Deposit = ConstU128<{ DOLLARS }>;
 	type Currency = Balances;
 	type Staking = Staking;
+	type Pools = FastUnstakePools;
+	type OnUnstaked = ();
+	type RejectExposedOnRegister = ConstBool<true>;
+	type MaxHeadBlocks = FastUnstakeMaxHeadBlocks;
+	type RequeueExpired = ConstBool<true>;
+	type MandatoryWeightPerBlock = ();
 	type WeightInfo = ();
 }
 
@@ -768,6 +831,11 @@ parameter_types! {
 	pub const PostUnbondPoolsWindow: u32 = 4;
 	pub const NominationPoolsPalletId: PalletId = PalletId(*b"py/nopls");
 	pub const MaxPointsToBalance: u8 = 10;
+	pub const PoolsReferrerCommission: Perbill = Perbill::from_percent(5);
+	pub const PoolsMaxCommission: Perbill = Perbill::from_percent(20);
+	pub const PoolsMinMembersForDepositorUnbond: u32 = 8;
+	pub const PoolsMaxMinMembershipDuration: BlockNumber = 28 * DAYS;
+	pub PoolsDustReceiver: Option<AccountId> = Some(Treasury::account_id());
 }
 
 use sp_runtime::traits::Convert;
@@ -788,15 +856,21 @@ impl pallet_nomination_pools::Config for Runtime {
 	type WeightInfo = ();
 	type RuntimeEvent = RuntimeEvent;
 	type Currency = Balances;
-	type RewardCounter = FixedU128;
+	type RewardCounter = FixedU128E24;
 	type BalanceToU256 = BalanceToU256;
 	type U256ToBalance = U256ToBalance;
 	type Staking = Staking;
+	type StakeAdapter = pallet_nomination_pools::adapter::TransferStake<Self>;
 	type PostUnbondingPoolsWindow = PostUnbondPoolsWindow;
 	type MaxMetadataLen = ConstU32<256>;
 	type MaxUnbonding = ConstU32<8>;
 	type PalletId = NominationPoolsPalletId;
+	type ReferrerCommission = PoolsReferrerCommission;
+	type MaxCommission = PoolsMaxCommission;
+	type MinMembersForDepositorUnbond = PoolsMinMembersForDepositorUnbond;
+	type MaxMinMembershipDuration = PoolsMaxMinMembershipDuration;
 	type MaxPointsToBalance = MaxPointsToBalance;
+	type DustReceiver = PoolsDustReceiver;
 }
 
 parameter_types! {
@@ -1190,6 +1264,8 @@ parameter_types! {
 		.max_total
 		.unwrap_or(RuntimeBlockWeights::get().max_block);
 	pub Schedule: pallet_contracts::Schedule<Runtime> = Default::default();
+	pub const ContractsReinstrumentPolicy: pallet_contracts::ReinstrumentPolicy =
+		pallet_contracts::ReinstrumentPolicy::ChargeCaller;
 }
 
 impl pallet_contracts::Config for Runtime {
@@ -1205,6 +1281,7 @@ impl pallet_contracts::Config for Runtime {
 	/// change because that would break already deployed contracts. The `Call` structure itself
 	/// is not allowed to change the indices of existing pallets, too.
 	type CallFilter = Nothing;
+	type InstantiateFilter = Everything;
 	type DepositPerItem = DepositPerItem;
 	type DepositPerByte = DepositPerByte;
 	type CallStack = [pallet_contracts::Frame<Self>; 31];
@@ -1218,7 +1295,15 @@ impl pallet_contracts::Config for Runtime {
 	type MaxCodeLen = ConstU32<{ 128 * 1024 }>;
 	type MaxStorageKeyLen = ConstU32<128>;
 	type UnsafeUnstableInterface = ConstBool<false>;
+	type AllowStorageImport = ConstBool<false>;
+	type EmitTrapEvents = ConstBool<false>;
+	type EmitCallSelectors = ConstBool<false>;
 	type MaxDebugBufferLen = ConstU32<{ 2 * 1024 * 1024 }>;
+	type TerminationDustHandler = Treasury;
+	type MaxBatchCalls = ConstU32<16>;
+	type ReinstrumentPolicy = ContractsReinstrumentPolicy;
+	type Xcm = pallet_contracts::xcm::Deny;
+	type Debugger = ();
 }
 
 impl pallet_sudo::Config for Runtime {
@@ -1808,6 +1893,7 @@ pub type Executive = frame_executive::Executive<
 // `OnRuntimeUpgrade`.
 type Migrations = (
 	pallet_nomination_pools::migration::v2::MigrateToV2<Runtime>,
+	pallet_nomination_pools::migration::v10::MigrateToV10<Runtime, FixedU128>,
 	pallet_alliance::migration::Migration<Runtime>,
 	pallet_contracts::Migration<Runtime>,
 );
@@ -1975,6 +2061,85 @@ impl_runtime_apis! {
 		fn pending_rewards(member_account: AccountId) -> Balance {
 			NominationPools::pending_rewards(member_account).unwrap_or_default()
 		}
+
+		fn pool_nominations(pool_id: pallet_nomination_pools::PoolId) -> Option<Vec<AccountId>> {
+			NominationPools::pool_nominations(pool_id)
+		}
+
+		fn total_value_locked() -> Balance {
+			NominationPools::total_value_locked()
+		}
+
+		fn below_minimum_active_stake(pool_id: pallet_nomination_pools::PoolId) -> Option<bool> {
+			NominationPools::below_minimum_active_stake(pool_id)
+		}
+
+		fn pool_members(
+			pool_id: pallet_nomination_pools::PoolId,
+			start: u32,
+			len: u32,
+		) -> Vec<AccountId> {
+			NominationPools::pool_members(pool_id, start, len)
+		}
+
+		fn points_to_balance(
+			pool_id: pallet_nomination_pools::PoolId,
+			points: Balance,
+		) -> Option<Balance> {
+			NominationPools::points_to_balance(pool_id, points)
+		}
+
+		fn member_balance(member: AccountId) -> Option<Balance> {
+			NominationPools::member_balance(member)
+		}
+	}
+
+	impl pallet_fast_unstake_runtime_api::FastUnstakeApi<
+		Block,
+		AccountId,
+		pallet_fast_unstake::types::QueueEntry<Runtime>,
+		pallet_fast_unstake::types::FastUnstakeTelemetry,
+		BlockNumber,
+	> for Runtime {
+		fn queue_page(
+			start: u32,
+			len: u32,
+		) -> Vec<(AccountId, pallet_fast_unstake::types::QueueEntry<Runtime>)> {
+			FastUnstake::queue_page(start, len)
+		}
+
+		fn telemetry() -> pallet_fast_unstake::types::FastUnstakeTelemetry {
+			FastUnstake::telemetry()
+		}
+
+		fn estimated_blocks_remaining(stash: AccountId) -> Option<BlockNumber> {
+			if !FastUnstake::is_registered(&stash) {
+				return None
+			}
+			Staking::unbonding_blocks_remaining(&stash)
+		}
+	}
+
+	impl pallet_staking_runtime_api::StakingApi<Block, AccountId, BlockNumber> for Runtime {
+		fn exposed_in_era(stash: AccountId, era: sp_staking::EraIndex) -> bool {
+			Staking::exposed_in_era(stash, era)
+		}
+
+		fn eras_of_exposure(
+			stash: AccountId,
+			from: sp_staking::EraIndex,
+			to: sp_staking::EraIndex,
+		) -> Vec<sp_staking::EraIndex> {
+			Staking::eras_of_exposure(stash, from, to)
+		}
+
+		fn expected_era_duration() -> BlockNumber {
+			Staking::expected_era_duration()
+		}
+
+		fn eras_remaining_blocks(era: sp_staking::EraIndex) -> Option<BlockNumber> {
+			Staking::eras_remaining_blocks(era)
+		}
 	}
 
 	impl sp_consensus_babe::BabeApi<Block> for Runtime {
@@ -2047,6 +2212,7 @@ impl_runtime_apis! {
 			gas_limit: Option<Weight>,
 			storage_deposit_limit: Option<Balance>,
 			input_data: Vec<u8>,
+			options: pallet_contracts_primitives::DryRunOptions,
 		) -> pallet_contracts_primitives::ContractExecResult<Balance> {
 			let gas_limit = gas_limit.unwrap_or(RuntimeBlockWeights::get().max_block);
 			Contracts::bare_call(
@@ -2058,6 +2224,20 @@ impl_runtime_apis! {
 				input_data,
 				true,
 				pallet_contracts::Determinism::Deterministic,
+				options,
+			)
+		}
+
+		fn batch_call(
+			calls: Vec<pallet_contracts_primitives::CallRequest<AccountId, Balance>>,
+			options: pallet_contracts_primitives::DryRunOptions,
+		) -> Vec<pallet_contracts_primitives::ContractExecResult<Balance>> {
+			Contracts::bare_batch_call(
+				calls,
+				RuntimeBlockWeights::get().max_block,
+				true,
+				pallet_contracts::Determinism::Deterministic,
+				options,
 			)
 		}
 
@@ -2069,6 +2249,7 @@ impl_runtime_apis! {
 			code: pallet_contracts_primitives::Code<Hash>,
 			data: Vec<u8>,
 			salt: Vec<u8>,
+			options: pallet_contracts_primitives::DryRunOptions,
 		) -> pallet_contracts_primitives::ContractInstantiateResult<AccountId, Balance>
 		{
 			let gas_limit = gas_limit.unwrap_or(RuntimeBlockWeights::get().max_block);
@@ -2080,10 +2261,20 @@ impl_runtime_apis! {
 				code,
 				data,
 				salt,
-				true
+				true,
+				options,
 			)
 		}
 
+		fn compute_contract_address(
+			deployer: AccountId,
+			code_hash: Hash,
+			input_data: Vec<u8>,
+			salt: Vec<u8>,
+		) -> AccountId {
+			Contracts::contract_address(&deployer, &code_hash, &input_data, &salt)
+		}
+
 		fn upload_code(
 			origin: AccountId,
 			code: Vec<u8>,
@@ -2108,6 +2299,24 @@ impl_runtime_apis! {
 				key
 			)
 		}
+
+		fn export_contract(
+			address: AccountId,
+			start_key: Option<Vec<u8>>,
+			limit: u32,
+		) -> pallet_contracts_primitives::ExportContractResult {
+			Contracts::export_contract(address, start_key, limit)
+		}
+
+		fn code_metadata(
+			code_hash: Hash,
+		) -> Option<pallet_contracts::CodeMetadata<AccountId, Balance>> {
+			Contracts::code_metadata(code_hash)
+		}
+
+		fn supported_host_functions() -> Vec<pallet_contracts_primitives::HostFunction> {
+			Contracts::supported_host_functions()
+		}
 	}
 
 	impl pallet_transaction_payment_rpc_runtime_api::TransactionPaymentApi<
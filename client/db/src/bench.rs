@@ -670,4 +670,24 @@ mod test {
 			bench_state.wipe().unwrap();
 		}
 	}
+
+	#[test]
+	fn whitelisted_keys_are_not_counted() {
+		let bench_state =
+			BenchmarkingState::<crate::tests::Block>::new(Default::default(), None, false, true)
+				.unwrap();
+
+		bench_state.storage(b"foo").unwrap();
+		bench_state.storage(b"bar").unwrap();
+		assert_eq!(bench_state.read_write_count().0, 2);
+
+		// Whitelisting takes effect once the tracker is reset.
+		bench_state.set_whitelist(vec![b"foo".to_vec().into()]);
+		bench_state.reset_read_write_count();
+
+		bench_state.storage(b"foo").unwrap();
+		bench_state.storage(b"bar").unwrap();
+		assert_eq!(bench_state.read_write_count().0, 1);
+		assert_eq!(bench_state.get_read_and_written_keys(), vec![(b"bar".to_vec(), 1, 0, false)]);
+	}
 }
@@ -185,6 +185,24 @@ macro_rules! whitelist {
 ///
 /// These `verify` blocks will not affect your benchmark results!
 ///
+/// Storage values that a benchmark accesses, but which are expected to be in the storage cache
+/// whenever the benchmarked code runs, can be whitelisted for this benchmark only. Their reads and
+/// writes are then not counted, but they are still listed in the generated weight file. The
+/// `#[extra_whitelist]` annotation has to come before any other annotation:
+///
+/// ```ignore
+/// #[extra_whitelist(Head<T>, ErasToCheckPerBlock<T>)]
+/// on_idle_check {
+/// 	let v in 1 .. 256;
+/// 	setup_head::<T>(v)?;
+/// }: {
+/// 	on_idle_full_block::<T>();
+/// }
+/// ```
+///
+/// Whitelisting applies to the whole storage value, hence storage maps are not supported. Keys of
+/// maps can be whitelisted with [`benchmarking::add_to_whitelist`] in the setup code instead.
+///
 /// You can construct benchmark by using the `impl_benchmark_test_suite` macro or
 /// by manually implementing them like so:
 ///
@@ -331,6 +349,40 @@ macro_rules! benchmarks_iter {
 			$( $rest )*
 		}
 	};
+	// detect and expand `#[extra_whitelist(...)]` tag:
+	(
+		{ $($bench_module:ident, $new_test_ext:expr, $test:path $(, $( $args:tt )* )?)? }
+		{ $( $instance:ident: $instance_bound:tt )? }
+		{ $( $where_clause:tt )* }
+		( $( $names:tt )* )
+		( $( $names_extra:tt )* )
+		( $( $names_skip_meta:tt )* )
+		#[extra_whitelist( $( $item:ty ),+ $(,)? )]
+		$( #[$tag:ident] )*
+		$name:ident { $( $code:tt )* }
+		$( $rest:tt )*
+	) => {
+		$crate::benchmarks_iter! {
+			{ $($bench_module, $new_test_ext, $test $(, $( $args )* )?)? }
+			{ $( $instance: $instance_bound )? }
+			{ $( $where_clause )* }
+			( $( $names )* )
+			( $( $names_extra )* )
+			( $( $names_skip_meta )* )
+			$( #[$tag] )*
+			$name {
+				$( $code )*
+				$(
+					$crate::benchmarking::add_to_whitelist(
+						<$item as $crate::frame_support::storage::StorageValue<_>>::hashed_key()
+							.to_vec()
+							.into()
+					);
+				)+
+			}
+			$( $rest )*
+		}
+	};
 	// detect and extract `#[skip_meta]` tag:
 	(
 		{ $($bench_module:ident, $new_test_ext:expr, $test:path $(, $( $args:tt )* )?)? }
@@ -1038,7 +1090,7 @@ macro_rules! impl_benchmark {
 				);
 				whitelist.push(transactional_layer_key);
 
-				$crate::benchmarking::set_whitelist(whitelist);
+				$crate::benchmarking::set_whitelist(whitelist.clone());
 
 				let mut results: $crate::Vec<$crate::BenchmarkResult> = $crate::Vec::new();
 
@@ -1110,7 +1162,15 @@ macro_rules! impl_benchmark {
 					let read_and_written_keys = if skip_meta.contains(&extrinsic) {
 						$crate::vec![(b"Skipped Metadata".to_vec(), 0, 0, false)]
 					} else {
-						$crate::benchmarking::get_read_and_written_keys()
+						let mut keys = $crate::benchmarking::get_read_and_written_keys();
+						// Keys whitelisted by this benchmark only are not counted, but still listed.
+						keys.extend(
+							$crate::benchmarking::get_whitelist()
+								.into_iter()
+								.filter(|k| !whitelist.iter().any(|w| w.key == k.key))
+								.map(|k| (k.key, 0, 0, true))
+						);
+						keys
 					};
 
 					results.push($crate::BenchmarkResult {
@@ -139,10 +139,14 @@ thread_local! {
 #[allow(unreachable_code)]
 mod benchmarks {
 	use super::{new_test_ext, pallet_test::Value, Test, VALUES_PER_COMPONENT};
-	use crate::{account, BenchmarkError, BenchmarkParameter, BenchmarkResult, BenchmarkingSetup};
+	use crate::{
+		account, BenchmarkError, BenchmarkParameter, BenchmarkResult, Benchmarking,
+		BenchmarkingSetup,
+	};
 	use frame_support::{assert_err, assert_ok, ensure, traits::Get};
 	use frame_system::RawOrigin;
 	use rusty_fork::rusty_fork_test;
+	use sp_runtime::{traits::BlakeTwo256, BuildStorage};
 	use sp_std::prelude::*;
 
 	// Additional used internally by the benchmark macro.
@@ -220,6 +224,29 @@ mod benchmarks {
 			assert_eq!(Value::<T>::get(), Some(b));
 		}
 
+		#[extra]
+		read_write_benchmark {
+			let b in 1 .. 1000;
+			Value::<T>::put(b);
+		}: {
+			Value::<T>::mutate(|v| *v = v.map(|v| v + 1));
+		}
+		verify {
+			assert_eq!(Value::<T>::get(), Some(b + 1));
+		}
+
+		#[extra_whitelist(Value<T>)]
+		#[extra]
+		extra_whitelist_benchmark {
+			let b in 1 .. 1000;
+			Value::<T>::put(b);
+		}: {
+			Value::<T>::mutate(|v| *v = v.map(|v| v + 1));
+		}
+		verify {
+			assert_eq!(Value::<T>::get(), Some(b + 1));
+		}
+
 		override_benchmark {
 			let b in 1 .. 1000;
 			let caller = account::<T::AccountId>("caller", 0, 0);
@@ -354,6 +381,59 @@ mod benchmarks {
 		});
 	}
 
+	#[test]
+	fn benchmarks_macro_extra_whitelist_works() {
+		// The annotation neither affects the components nor the other annotations.
+		let selected = SelectedBenchmark::extra_whitelist_benchmark;
+		let components = <SelectedBenchmark as BenchmarkingSetup<Test>>::components(&selected);
+		assert_eq!(components, vec![(BenchmarkParameter::b, 1, 1000)]);
+		assert!(Pallet::<Test>::benchmarks(true)
+			.iter()
+			.any(|b| b.name == b"extra_whitelist_benchmark".to_vec()));
+		assert!(!Pallet::<Test>::benchmarks(false)
+			.iter()
+			.any(|b| b.name == b"extra_whitelist_benchmark".to_vec()));
+
+		new_test_ext().execute_with(|| {
+			assert_ok!(Pallet::<Test>::test_benchmark_extra_whitelist_benchmark());
+		});
+	}
+
+	#[test]
+	fn extra_whitelist_is_not_counted() {
+		// Run the benchmark against a state which tracks the storage accesses, like the CLI does.
+		let run = |name: &[u8]| {
+			let storage = super::GenesisConfig::default().build_storage().unwrap();
+			let state =
+				sc_client_db::BenchmarkingState::<super::Block>::new(storage, None, false, true)
+					.unwrap();
+			let mut overlay = Default::default();
+			let mut cache = Default::default();
+			let mut ext = sp_state_machine::Ext::<BlakeTwo256, _>::new(
+				&mut overlay,
+				&mut cache,
+				&state,
+				None,
+			);
+			sp_externalities::set_and_run_with_externalities(&mut ext, || {
+				Pallet::<Test>::run_benchmark(name, &[(BenchmarkParameter::b, 10)], &[], true, 1)
+			})
+			.unwrap()
+			.pop()
+			.unwrap()
+		};
+		let value_key = Value::<Test>::hashed_key().to_vec();
+
+		let counted = run(b"read_write_benchmark");
+		assert_eq!((counted.reads, counted.writes), (1, 1));
+		assert_eq!(counted.keys, vec![(value_key.clone(), 1, 1, false)]);
+
+		// The same accesses are not counted once the value is whitelisted, but still listed.
+		let whitelisted = run(b"extra_whitelist_benchmark");
+		assert_eq!((whitelisted.reads, whitelisted.writes), (0, 0));
+		assert_eq!(whitelisted.keys, vec![(value_key, 0, 0, true)]);
+	}
+
 	#[test]
 	fn benchmark_override_works() {
 		let selected = SelectedBenchmark::override_benchmark;
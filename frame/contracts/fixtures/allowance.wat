@@ -0,0 +1,97 @@
+;; A minimal ERC20-style allowance.
+;;
+;; Input is `selector: u8 ++ spender: [u8; 32] ++ amount: u64`:
+;; - selector 0 (approve): sets the allowance of `spender` to `amount`.
+;; - selector 1 (transfer_from): spends `amount` of the allowance of the caller, `spender`
+;;   is ignored. Traps if the allowance is insufficient.
+(module
+	(import "seal0" "seal_input" (func $seal_input (param i32 i32)))
+	(import "seal0" "seal_caller" (func $seal_caller (param i32 i32)))
+	(import "seal0" "seal_set_storage" (func $seal_set_storage (param i32 i32 i32)))
+	(import "seal0" "seal_get_storage" (func $seal_get_storage (param i32 i32 i32) (result i32)))
+	(import "env" "memory" (memory 1 1))
+
+	;; [0, 4) size of the input buffer
+	(data (i32.const 0) "\29")
+
+	;; [4, 45) input buffer
+
+	;; [48, 52) size of the caller buffer
+	(data (i32.const 48) "\20")
+
+	;; [52, 84) caller buffer
+
+	;; [84, 88) size of the allowance buffer
+	(data (i32.const 84) "\08")
+
+	;; [88, 96) allowance buffer
+
+	(func $assert (param i32)
+		(block $ok
+			(br_if $ok
+				(get_local 0)
+			)
+			(unreachable)
+		)
+	)
+
+	(func (export "call")
+		(call $seal_input (i32.const 4) (i32.const 0))
+
+		;; assert input size == 41
+		(call $assert
+			(i32.eq
+				(i32.load (i32.const 0))
+				(i32.const 41)
+			)
+		)
+
+		;; approve: the allowance is stored under the spender
+		(if (i32.eqz (i32.load8_u (i32.const 4)))
+			(then
+				(call $seal_set_storage
+					(i32.const 5)	;; Pointer to the spender
+					(i32.const 37)	;; Pointer to the amount
+					(i32.const 8)	;; Size of the amount
+				)
+				(return)
+			)
+		)
+
+		;; transfer_from: load the allowance of the caller
+		(call $seal_caller (i32.const 52) (i32.const 48))
+		(call $assert
+			(i32.eqz
+				(call $seal_get_storage
+					(i32.const 52)	;; Pointer to the caller
+					(i32.const 88)	;; Pointer to the allowance buffer
+					(i32.const 84)	;; Pointer to the size of the allowance buffer
+				)
+			)
+		)
+
+		;; assert allowance >= amount
+		(call $assert
+			(i64.ge_u
+				(i64.load (i32.const 88))
+				(i64.load (i32.const 37))
+			)
+		)
+
+		;; spend the allowance
+		(i64.store
+			(i32.const 88)
+			(i64.sub
+				(i64.load (i32.const 88))
+				(i64.load (i32.const 37))
+			)
+		)
+		(call $seal_set_storage
+			(i32.const 52)	;; Pointer to the caller
+			(i32.const 88)	;; Pointer to the allowance
+			(i32.const 8)	;; Size of the allowance
+		)
+	)
+
+	(func (export "deploy"))
+)
@@ -0,0 +1,38 @@
+;; Returns the block number, the timestamp and the randomness for an empty subject
+;; as observed by the contract.
+(module
+	(import "seal0" "seal_block_number" (func $seal_block_number (param i32 i32)))
+	(import "seal0" "seal_now" (func $seal_now (param i32 i32)))
+	(import "seal1" "seal_random" (func $seal_random (param i32 i32 i32 i32)))
+	(import "seal0" "seal_return" (func $seal_return (param i32 i32 i32)))
+	(import "env" "memory" (memory 1 1))
+
+	;; [0, 4) size of the block number buffer
+	(data (i32.const 0) "\08")
+
+	;; [4, 8) size of the timestamp buffer
+	(data (i32.const 4) "\08")
+
+	;; [8, 12) size of the randomness buffer
+	(data (i32.const 8) "\28")
+
+	;; [16, 24) block number
+
+	;; [24, 32) timestamp
+
+	;; [32, 72) randomness: seed and block number
+
+	(func (export "deploy"))
+
+	(func (export "call")
+		(call $seal_block_number (i32.const 16) (i32.const 0))
+		(call $seal_now (i32.const 24) (i32.const 4))
+		(call $seal_random
+			(i32.const 0)	;; Pointer to the subject (ignored because it is empty)
+			(i32.const 0)	;; The size of the subject
+			(i32.const 32)	;; Pointer to the output buffer
+			(i32.const 8)	;; Pointer to the size of the output buffer
+		)
+		(call $seal_return (i32.const 0) (i32.const 16) (i32.const 56))
+	)
+)
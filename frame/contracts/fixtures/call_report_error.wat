@@ -0,0 +1,53 @@
+;; This calls the contract found in the input with the flags and gas limit found in the input,
+;; passing it the rest of the input. It returns the return code of that call followed by whatever
+;; was written to its output buffer.
+;;
+;; The input is laid out as: [0, 4) call flags, [4, 12) gas limit, [12, 44) callee,
+;; [44, ..) input of the callee.
+(module
+	(import "seal0" "seal_input" (func $seal_input (param i32 i32)))
+	(import "seal1" "seal_call" (func $seal_call (param i32 i32 i64 i32 i32 i32 i32 i32) (result i32)))
+	(import "seal0" "seal_return" (func $seal_return (param i32 i32 i32)))
+	(import "env" "memory" (memory 1 1))
+
+	;; [0, 16) zero balance to transfer
+
+	;; [16, 20) size of the input buffer
+	(data (i32.const 16) "\00\01")
+
+	;; [20, 24) size of the output buffer
+	(data (i32.const 20) "\00\01")
+
+	;; [24, 28) here we store the return code of the call
+
+	;; [28, 284) output buffer
+
+	;; [284, 540) input buffer
+
+	(func (export "deploy"))
+
+	(func (export "call")
+		(call $seal_input (i32.const 284) (i32.const 16))
+
+		(i32.store
+			(i32.const 24)
+			(call $seal_call
+				(i32.load (i32.const 284)) ;; Flags passed in the input
+				(i32.const 296) ;; Pointer to "callee" address.
+				(i64.load (i32.const 288)) ;; How much gas to devote for the execution. 0 = all.
+				(i32.const 0) ;; Pointer to the buffer with value to transfer
+				(i32.const 328) ;; Pointer to input data buffer address
+				(i32.sub (i32.load (i32.const 16)) (i32.const 44)) ;; Length of input data buffer
+				(i32.const 28) ;; Pointer to the output buffer
+				(i32.const 20) ;; Pointer to the output buffer length
+			)
+		)
+
+		;; exit with success and take the return code and the output to the output buffer
+		(call $seal_return
+			(i32.const 0)
+			(i32.const 24)
+			(i32.add (i32.load (i32.const 20)) (i32.const 4))
+		)
+	)
+)
@@ -0,0 +1,96 @@
+;; This fixture recursively calls itself until no more nested frames are available.
+;; If the passed input is non-zero it ignores `call_stack_remaining` and calls itself until
+;; the call fails. An input of 2 makes it use the stable `seal_call` which traps instead of
+;; returning `MaxCallDepthReached`.
+;;
+;; Returns the number of frames in the chain and the return code of the failed call, if any.
+(module
+	(import "seal0" "seal_input" (func $seal_input (param i32 i32)))
+	(import "seal0" "seal_address" (func $seal_address (param i32 i32)))
+	(import "seal0" "seal_return" (func $seal_return (param i32 i32 i32)))
+	(import "seal1" "seal_call" (func $seal_call_v1 (param i32 i32 i64 i32 i32 i32 i32 i32) (result i32)))
+	(import "seal2" "call" (func $seal_call (param i32 i32 i64 i32 i32 i32 i32 i32) (result i32)))
+	(import "seal0" "call_stack_remaining" (func $call_stack_remaining (result i32)))
+	(import "env" "memory" (memory 1 1))
+
+	;; [0, 32) reserved for $seal_address output
+
+	;; [32, 36) input: whether to ignore the remaining call stack
+
+	;; [36, 40) size of the input buffer
+	(data (i32.const 36) "\04")
+
+	;; [40, 44) length of the buffer for $seal_address
+	(data (i32.const 40) "\20")
+
+	;; [44, 52) output: number of frames, return code of the failed call
+
+	;; [52, 56) size of the output buffer
+	(data (i32.const 52) "\08")
+
+	;; [56, 64) value to transfer
+
+	(func $call_self (result i32)
+		(if (result i32) (i32.eq (i32.load (i32.const 32)) (i32.const 2))
+			(then
+				(call $seal_call_v1
+					(i32.const 8)	;; Allow reentrancy flag set
+					(i32.const 0)	;; Pointer to "callee" address
+					(i64.const 0)	;; How much gas to devote for the execution. 0 = all.
+					(i32.const 56)	;; Pointer to the buffer with value to transfer
+					(i32.const 32)	;; Pointer to input data buffer address
+					(i32.const 4)	;; Length of input data buffer
+					(i32.const 44)	;; Pointer to the output buffer
+					(i32.const 52)	;; Pointer to the output buffer len
+				)
+			)
+			(else
+				(call $seal_call
+					(i32.const 8)	;; Allow reentrancy flag set
+					(i32.const 0)	;; Pointer to "callee" address
+					(i64.const 0)	;; How much gas to devote for the execution. 0 = all.
+					(i32.const 56)	;; Pointer to the buffer with value to transfer
+					(i32.const 32)	;; Pointer to input data buffer address
+					(i32.const 4)	;; Length of input data buffer
+					(i32.const 44)	;; Pointer to the output buffer
+					(i32.const 52)	;; Pointer to the output buffer len
+				)
+			)
+		)
+	)
+
+	(func (export "call")
+		(local $exit_code i32)
+
+		(call $seal_address (i32.const 0) (i32.const 40))
+		(call $seal_input (i32.const 32) (i32.const 36))
+
+		(if
+			(i32.and
+				(i32.eqz (i32.load (i32.const 32)))
+				(i32.eqz (call $call_stack_remaining))
+			)
+			(then
+				;; recursion exit case
+				(i32.store (i32.const 44) (i32.const 1))
+				(i32.store (i32.const 48) (i32.const 0))
+			)
+			(else
+				(set_local $exit_code (call $call_self))
+				(if (get_local $exit_code)
+					(then
+						(i32.store (i32.const 44) (i32.const 1))
+						(i32.store (i32.const 48) (get_local $exit_code))
+					)
+					(else
+						(i32.store (i32.const 44) (i32.add (i32.load (i32.const 44)) (i32.const 1)))
+					)
+				)
+			)
+		)
+
+		(call $seal_return (i32.const 0) (i32.const 44) (i32.const 8))
+	)
+
+	(func (export "deploy"))
+)
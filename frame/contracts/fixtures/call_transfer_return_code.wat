@@ -0,0 +1,38 @@
+;; This transfers 100 balance to the zero account by calling it without any input and the
+;; `TRANSFER_TO_ACCOUNT` flag set and copies the return code of this call to the output buffer.
+(module
+	(import "seal1" "seal_call" (func $seal_call (param i32 i32 i64 i32 i32 i32 i32 i32) (result i32)))
+	(import "seal0" "seal_return" (func $seal_return (param i32 i32 i32)))
+	(import "env" "memory" (memory 1 1))
+
+	;; [0, 32) zero-adress
+	(data (i32.const 0)
+		"\00\00\00\00\00\00\00\00\00\00\00\00\00\00\00\00"
+		"\00\00\00\00\00\00\00\00\00\00\00\00\00\00\00\00"
+	)
+
+	;; [32, 40) 100 balance
+	(data (i32.const 32) "\64\00\00\00\00\00\00\00")
+
+	;; [40, 44) here we store the return code of the call
+
+	(func (export "deploy"))
+
+	(func (export "call")
+		(i32.store
+			(i32.const 40)
+			(call $seal_call
+				(i32.const 0x40) ;; TRANSFER_TO_ACCOUNT
+				(i32.const 0) ;; Pointer to "callee" address.
+				(i64.const 0) ;; How much gas to devote for the execution. 0 = all.
+				(i32.const 32) ;; Pointer to the buffer with value to transfer
+				(i32.const 0) ;; Pointer to input data buffer address
+				(i32.const 0) ;; Length of input data buffer: no input
+				(i32.const 0xffffffff) ;; u32 max sentinel value: do not copy output
+				(i32.const 0) ;; Ptr to output buffer len
+			)
+		)
+		;; exit with success and take call return code to the output buffer
+		(call $seal_return (i32.const 0) (i32.const 40) (i32.const 4))
+	)
+)
@@ -0,0 +1,47 @@
+;; This calls the contract found in the input with the flags found in the input, passing it the
+;; rest of the input. It returns the return code of that call. It does nothing on an empty input.
+;;
+;; The input is laid out as: [0, 4) call flags, [4, 36) callee, [36, ..) input of the callee.
+(module
+	(import "seal0" "seal_input" (func $seal_input (param i32 i32)))
+	(import "seal1" "seal_call" (func $seal_call (param i32 i32 i64 i32 i32 i32 i32 i32) (result i32)))
+	(import "seal0" "seal_return" (func $seal_return (param i32 i32 i32)))
+	(import "env" "memory" (memory 1 1))
+
+	;; [0, 16) zero balance to transfer
+
+	;; [16, 20) size of the input buffer
+	(data (i32.const 16) "\00\04")
+
+	;; [20, 24) here we store the return code of the call
+
+	;; [24, 1048) input buffer
+
+	(func (export "deploy"))
+
+	(func (export "call")
+		(call $seal_input (i32.const 24) (i32.const 16))
+
+		;; nothing to call
+		(if (i32.eqz (i32.load (i32.const 16)))
+			(then (return))
+		)
+
+		(i32.store
+			(i32.const 20)
+			(call $seal_call
+				(i32.load (i32.const 24)) ;; Flags passed in the input
+				(i32.const 28) ;; Pointer to "callee" address.
+				(i64.const 0) ;; How much gas to devote for the execution. 0 = all.
+				(i32.const 0) ;; Pointer to the buffer with value to transfer
+				(i32.const 60) ;; Pointer to input data buffer address
+				(i32.sub (i32.load (i32.const 16)) (i32.const 36)) ;; Length of input data buffer
+				(i32.const 0xffffffff) ;; u32 max sentinel value: do not copy output
+				(i32.const 0) ;; Ptr to output buffer len
+			)
+		)
+
+		;; exit with success and take the call return code to the output buffer
+		(call $seal_return (i32.const 0) (i32.const 20) (i32.const 4))
+	)
+)
@@ -0,0 +1,68 @@
+;; This fixture returns whether its caller is a contract.
+;;
+;; If it receives the address of another contract as input, it calls that contract instead and
+;; returns its output. This happens in the constructor as well as in a call.
+(module
+	(import "seal0" "seal_input" (func $seal_input (param i32 i32)))
+	(import "seal0" "seal_return" (func $seal_return (param i32 i32 i32)))
+	(import "seal1" "seal_call" (func $seal_call (param i32 i32 i64 i32 i32 i32 i32 i32) (result i32)))
+	(import "seal0" "caller_is_contract" (func $caller_is_contract (result i32)))
+	(import "env" "memory" (memory 1 1))
+
+	;; [0, 32) input: address of the contract to call, if any
+
+	;; [32, 36) size of the input buffer
+	(data (i32.const 32) "\20")
+
+	;; [36, 40) output: u32-encoded boolean
+
+	;; [40, 44) size of the output buffer
+	(data (i32.const 40) "\04")
+
+	;; [44, 52) value to transfer
+
+	(func $assert (param i32)
+		(block $ok
+			(br_if $ok
+				(get_local 0)
+			)
+			(unreachable)
+		)
+	)
+
+	(func $run
+		(call $seal_input (i32.const 0) (i32.const 32))
+
+		(if (i32.eqz (i32.load (i32.const 32)))
+			(then
+				(i32.store (i32.const 36) (call $caller_is_contract))
+			)
+			(else
+				(call $assert
+					(i32.eqz
+						(call $seal_call
+							(i32.const 0)	;; No flags are set
+							(i32.const 0)	;; Pointer to "callee" address
+							(i64.const 0)	;; How much gas to devote for the execution. 0 = all.
+							(i32.const 44)	;; Pointer to the buffer with value to transfer
+							(i32.const 0)	;; Pointer to input data buffer address
+							(i32.const 0)	;; Length of input data buffer
+							(i32.const 36)	;; Pointer to the output buffer
+							(i32.const 40)	;; Pointer to the output buffer len
+						)
+					)
+				)
+			)
+		)
+
+		(call $seal_return (i32.const 0) (i32.const 36) (i32.const 4))
+	)
+
+	(func (export "deploy")
+		(call $run)
+	)
+
+	(func (export "call")
+		(call $run)
+	)
+)
@@ -0,0 +1,48 @@
+;; This fixture returns a return code followed by a code hash.
+;;
+;; If it receives an address as input, it returns the code hash of that address as reported by
+;; `seal_code_hash`. Otherwise it returns its own code hash.
+(module
+	(import "seal0" "seal_input" (func $seal_input (param i32 i32)))
+	(import "seal0" "seal_return" (func $seal_return (param i32 i32 i32)))
+	(import "seal0" "seal_code_hash" (func $seal_code_hash (param i32 i32 i32) (result i32)))
+	(import "seal0" "seal_own_code_hash" (func $seal_own_code_hash (param i32 i32)))
+	(import "env" "memory" (memory 1 1))
+
+	;; [0, 32) input: address to look up, if any
+
+	;; [32, 36) size of the input buffer
+	(data (i32.const 32) "\20")
+
+	;; [36, 40) size of the code hash buffer
+	(data (i32.const 36) "\20")
+
+	;; [40, 44) output: return code
+
+	;; [44, 76) output: code hash
+
+	(func (export "deploy"))
+
+	(func (export "call")
+		(call $seal_input (i32.const 0) (i32.const 32))
+		(if (i32.eqz (i32.load (i32.const 32)))
+			(then
+				(call $seal_own_code_hash
+					(i32.const 44)	;; Pointer to the output buffer
+					(i32.const 36)	;; Pointer to the size of the output buffer
+				)
+			)
+			(else
+				(i32.store
+					(i32.const 40)
+					(call $seal_code_hash
+						(i32.const 0)	;; Pointer to the address
+						(i32.const 44)	;; Pointer to the output buffer
+						(i32.const 36)	;; Pointer to the size of the output buffer
+					)
+				)
+			)
+		)
+		(call $seal_return (i32.const 0) (i32.const 40) (i32.const 36))
+	)
+)
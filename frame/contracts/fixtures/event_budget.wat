@@ -0,0 +1,67 @@
+;; This deposits a number of events of the same size, and then calls the contract found in the input
+;; with the rest of the input, if any. It returns the return code of that call.
+;;
+;; The input is laid out as: [0, 4) number of events, [4, 8) size of each event, [8, 40) callee,
+;; [40, ..) input of the callee.
+(module
+	(import "seal0" "seal_input" (func $seal_input (param i32 i32)))
+	(import "seal0" "seal_deposit_event" (func $seal_deposit_event (param i32 i32 i32 i32)))
+	(import "seal1" "seal_call" (func $seal_call (param i32 i32 i64 i32 i32 i32 i32 i32) (result i32)))
+	(import "seal0" "seal_return" (func $seal_return (param i32 i32 i32)))
+	(import "env" "memory" (memory 1 1))
+
+	;; [0, 4) size of the input buffer
+	(data (i32.const 0) "\80")
+
+	;; [4, 132) input buffer
+
+	;; [132, 136) here we store the return code of the call, 0 if there is no callee
+
+	;; [136, 152) zero balance to transfer
+
+	;; [152, ..) event data
+
+	(func (export "deploy"))
+
+	(func (export "call")
+		(local $remaining i32)
+
+		(call $seal_input (i32.const 4) (i32.const 0))
+
+		(set_local $remaining (i32.load (i32.const 4)))
+		(block $done
+			(loop $deposit
+				(br_if $done (i32.eqz (get_local $remaining)))
+				(call $seal_deposit_event
+					(i32.const 0) ;; topics_ptr
+					(i32.const 0) ;; topics_len
+					(i32.const 152) ;; data_ptr
+					(i32.load (i32.const 8)) ;; data_len
+				)
+				(set_local $remaining (i32.sub (get_local $remaining) (i32.const 1)))
+				(br $deposit)
+			)
+		)
+
+		(if (i32.gt_u (i32.load (i32.const 0)) (i32.const 8))
+			(then
+				(i32.store
+					(i32.const 132)
+					(call $seal_call
+						(i32.const 0) ;; Set no flag
+						(i32.const 12) ;; Pointer to "callee" address.
+						(i64.const 0) ;; How much gas to devote for the execution. 0 = all.
+						(i32.const 136) ;; Pointer to the buffer with value to transfer
+						(i32.const 44) ;; Pointer to input data buffer address
+						(i32.sub (i32.load (i32.const 0)) (i32.const 40)) ;; Length of input data buffer
+						(i32.const 0xffffffff) ;; u32 max sentinel value: do not copy output
+						(i32.const 0) ;; Ptr to output buffer len
+					)
+				)
+			)
+		)
+
+		;; exit with success and take the call return code to the output buffer
+		(call $seal_return (i32.const 0) (i32.const 132) (i32.const 4))
+	)
+)
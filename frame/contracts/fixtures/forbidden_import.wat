@@ -0,0 +1,6 @@
+;; Module that imports the `gas` function which is reserved for instrumentation
+(module
+	(import "seal0" "gas" (func $gas (param i32)))
+	(func (export "deploy"))
+	(func (export "call"))
+)
@@ -0,0 +1,71 @@
+;; Writes `writes` storage items of 32 bytes each and hashes 32 bytes `hashes` times.
+;; The input is expected to be `(writes: u32, hashes: u32)`.
+(module
+	(import "seal0" "seal_input" (func $seal_input (param i32 i32)))
+	(import "seal0" "seal_set_storage" (func $seal_set_storage (param i32 i32 i32)))
+	(import "seal0" "seal_hash_blake2_256" (func $seal_hash_blake2_256 (param i32 i32 i32)))
+	(import "env" "memory" (memory 1 1))
+
+	;; [0, 8) buffer where input is copied
+
+	;; [8, 12) size of the input buffer
+	(data (i32.const 8) "\08")
+
+	;; [12, 44) storage key, the first four bytes are set to the index of the item
+
+	;; [44, 76) value written to storage and hashed in place
+
+	(func $assert (param i32)
+		(block $ok
+			(br_if $ok
+				(get_local 0)
+			)
+			(unreachable)
+		)
+	)
+
+	(func (export "call")
+		(local $i i32)
+
+		(call $seal_input (i32.const 0) (i32.const 8))
+
+		;; assert input size == 8
+		(call $assert
+			(i32.eq
+				(i32.load (i32.const 8))
+				(i32.const 8)
+			)
+		)
+
+		(set_local $i (i32.const 0))
+		(block $writes_done
+			(loop $write
+				(br_if $writes_done (i32.ge_u (get_local $i) (i32.load (i32.const 0))))
+				(i32.store (i32.const 12) (get_local $i))
+				(call $seal_set_storage
+					(i32.const 12) ;; Pointer to the storage key
+					(i32.const 44) ;; Pointer to the value
+					(i32.const 32) ;; Length of the value
+				)
+				(set_local $i (i32.add (get_local $i) (i32.const 1)))
+				(br $write)
+			)
+		)
+
+		(set_local $i (i32.const 0))
+		(block $hashes_done
+			(loop $hash
+				(br_if $hashes_done (i32.ge_u (get_local $i) (i32.load (i32.const 4))))
+				(call $seal_hash_blake2_256
+					(i32.const 44) ;; Pointer to the data to hash
+					(i32.const 32) ;; Length of the data to hash
+					(i32.const 44) ;; Pointer to the output buffer
+				)
+				(set_local $i (i32.add (get_local $i) (i32.const 1)))
+				(br $hash)
+			)
+		)
+	)
+
+	(func (export "deploy"))
+)
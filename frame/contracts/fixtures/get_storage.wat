@@ -0,0 +1,36 @@
+;; Read the storage item at key [0; 32] into a buffer of the maximum value size
+(module
+	(import "seal0" "seal_get_storage" (func $seal_get_storage (param i32 i32 i32) (result i32)))
+	(import "env" "memory" (memory 1 1))
+
+	;; [0, 32) storage key
+
+	;; [32, 36) size of the output buffer (16 KiB)
+	(data (i32.const 32) "\00\40")
+
+	;; [36, inf) output buffer
+
+	(func $assert (param i32)
+		(block $ok
+			(br_if $ok
+				(get_local 0)
+			)
+			(unreachable)
+		)
+	)
+
+	(func (export "call")
+		(call $assert
+			(i32.eq
+				(call $seal_get_storage
+					(i32.const 0)		;; Pointer to storage key
+					(i32.const 36)		;; Pointer to the output buffer
+					(i32.const 32)		;; Pointer to the size of the output buffer
+				)
+				(i32.const 0)	;; success return code
+			)
+		)
+	)
+
+	(func (export "deploy"))
+)
@@ -0,0 +1,33 @@
+;; Grows the memory by the amount of pages passed as input.
+(module
+	(import "seal0" "seal_input" (func $seal_input (param i32 i32)))
+	(import "env" "memory" (memory 1 16))
+
+	;; [0, 4) number of pages to grow
+
+	;; [4, 8) size of the input buffer
+	(data (i32.const 4) "\04")
+
+	(func $assert (param i32)
+		(block $ok
+			(br_if $ok
+				(get_local 0)
+			)
+			(unreachable)
+		)
+	)
+
+	(func (export "deploy"))
+
+	(func (export "call")
+		(call $seal_input (i32.const 0) (i32.const 4))
+
+		;; memory.grow returns the previous size which is the initial page.
+		(call $assert
+			(i32.eq
+				(memory.grow (i32.load (i32.const 0)))
+				(i32.const 1)
+			)
+		)
+	)
+)
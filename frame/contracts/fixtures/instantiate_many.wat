@@ -0,0 +1,70 @@
+;; Instantiates the same code over and over again, using the iteration as salt.
+;; The first 32 byte of input is the code hash to instantiate, followed by the number of
+;; instantiations as u32. Traps if any of them fails.
+(module
+	(import "seal0" "seal_input" (func $seal_input (param i32 i32)))
+	(import "seal1" "seal_instantiate" (func $seal_instantiate
+		(param i32 i64 i32 i32 i32 i32 i32 i32 i32 i32 i32) (result i32)
+	))
+	(import "env" "memory" (memory 1 1))
+
+	;; [0, 8) 10_000 balance
+	(data (i32.const 0) "\10\27\00\00\00\00\00\00")
+
+	;; [8, 12) salt: the number of the current instantiation
+
+	;; [12, 16) size of the input buffer
+	(data (i32.const 12) "\24")
+
+	;; [16, 48) code hash
+
+	;; [48, 52) number of instantiations
+
+	(func $assert (param i32)
+		(block $ok
+			(br_if $ok
+				(get_local 0)
+			)
+			(unreachable)
+		)
+	)
+
+	(func (export "deploy"))
+
+	(func (export "call")
+		(call $seal_input (i32.const 16) (i32.const 12))
+
+		;; assert input size == 36
+		(call $assert
+			(i32.eq
+				(i32.load (i32.const 12))
+				(i32.const 36)
+			)
+		)
+
+		(block $done
+			(loop $instantiate
+				(br_if $done (i32.ge_u (i32.load (i32.const 8)) (i32.load (i32.const 48))))
+				(call $assert
+					(i32.eqz
+						(call $seal_instantiate
+							(i32.const 16) ;; Pointer to the code hash.
+							(i64.const 0) ;; How much gas to devote for the execution. 0 = all.
+							(i32.const 0) ;; Pointer to the buffer with value to transfer
+							(i32.const 0) ;; Pointer to input data buffer address
+							(i32.const 0) ;; Length of input data buffer
+							(i32.const 0xffffffff) ;; u32 max sentinel value: do not copy address
+							(i32.const 0) ;; Length is ignored in this case
+							(i32.const 0xffffffff) ;; u32 max sentinel value: do not copy output
+							(i32.const 0) ;; Length is ignored in this case
+							(i32.const 8) ;; salt_ptr
+							(i32.const 4) ;; salt_len
+						)
+					)
+				)
+				(i32.store (i32.const 8) (i32.add (i32.load (i32.const 8)) (i32.const 1)))
+				(br $instantiate)
+			)
+		)
+	)
+)
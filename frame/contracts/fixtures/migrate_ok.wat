@@ -0,0 +1,29 @@
+;; A contract whose `migrate` export writes a storage item that is returned by `call`.
+(module
+	(import "seal0" "seal_set_storage" (func $seal_set_storage (param i32 i32 i32)))
+	(import "seal0" "seal_get_storage" (func $seal_get_storage (param i32 i32 i32) (result i32)))
+	(import "seal0" "seal_return" (func $seal_return (param i32 i32 i32)))
+	(import "env" "memory" (memory 1 1))
+
+	;; [0, 32) storage key
+
+	;; [32, 36) value written by the migration
+	(data (i32.const 32) "\03")
+
+	;; [36, 40) size of the output buffer
+	(data (i32.const 36) "\04")
+
+	;; [40, 44) output buffer
+
+	(func (export "deploy"))
+
+	(func (export "migrate")
+		(call $seal_set_storage (i32.const 0) (i32.const 32) (i32.const 4))
+	)
+
+	;; Returns the value written by the migration or 0 if it didn't run.
+	(func (export "call")
+		(drop (call $seal_get_storage (i32.const 0) (i32.const 40) (i32.const 36)))
+		(call $seal_return (i32.const 0) (i32.const 40) (i32.const 4))
+	)
+)
@@ -0,0 +1,13 @@
+;; A contract whose `migrate` export always reverts.
+(module
+	(import "seal0" "seal_return" (func $seal_return (param i32 i32 i32)))
+	(import "env" "memory" (memory 1 1))
+
+	(func (export "deploy"))
+
+	(func (export "migrate")
+		(call $seal_return (i32.const 1) (i32.const 0) (i32.const 0))
+	)
+
+	(func (export "call"))
+)
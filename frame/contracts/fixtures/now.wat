@@ -0,0 +1,26 @@
+;; Returns the timestamp of the current block as reported by `seal_now`.
+(module
+	(import "seal0" "seal_now" (func $seal_now (param i32 i32)))
+	(import "seal0" "seal_return" (func $seal_return (param i32 i32 i32)))
+	(import "env" "memory" (memory 1 1))
+
+	;; [0, 8) output buffer
+
+	;; [8, 12) size of the output buffer
+	(data (i32.const 8) "\08")
+
+	(func (export "deploy"))
+
+	(func (export "call")
+		(call $seal_now
+			(i32.const 0)	;; Pointer to the output buffer
+			(i32.const 8)	;; Pointer to the size of the output buffer
+		)
+
+		(call $seal_return
+			(i32.const 0)	;; flags
+			(i32.const 0)	;; Pointer to the timestamp
+			(i32.load (i32.const 8))	;; Size of the timestamp
+		)
+	)
+)
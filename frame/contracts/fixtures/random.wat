@@ -0,0 +1,27 @@
+;; Call the deprecated `seal_random` twice with an empty subject
+(module
+	(import "seal1" "seal_random" (func $seal_random (param i32 i32 i32 i32)))
+	(import "env" "memory" (memory 1 1))
+
+	;; [0, 4) size of the output buffer
+	(data (i32.const 0) "\40")
+
+	;; [4, 68) output buffer
+
+	(func (export "call")
+		(call $seal_random
+			(i32.const 0)	;; Pointer to the subject (ignored because it is empty)
+			(i32.const 0)	;; The size of the subject
+			(i32.const 4)	;; Pointer to the output buffer
+			(i32.const 0)	;; Pointer to the size of the output buffer
+		)
+		(call $seal_random
+			(i32.const 0)	;; Pointer to the subject (ignored because it is empty)
+			(i32.const 0)	;; The size of the subject
+			(i32.const 4)	;; Pointer to the output buffer
+			(i32.const 0)	;; Pointer to the size of the output buffer
+		)
+	)
+
+	(func (export "deploy"))
+)
@@ -0,0 +1,134 @@
+;; Reads a storage item and calls the contract passed as input with output buffers that are too
+;; small, and retries with the length reported back. Does the same for a call that reverts.
+;; Returns the storage item followed by the output of the callee.
+;;
+;; The callee is expected to be the `return_with_data` fixture.
+(module
+	(import "seal0" "seal_input" (func $seal_input (param i32 i32)))
+	(import "seal0" "seal_return" (func $seal_return (param i32 i32 i32)))
+	(import "seal1" "seal_call" (func $seal_call (param i32 i32 i64 i32 i32 i32 i32 i32) (result i32)))
+	(import "seal2" "set_storage" (func $set_storage (param i32 i32 i32 i32) (result i32)))
+	(import "seal2" "get_storage" (func $get_storage (param i32 i32 i32 i32) (result i32)))
+	(import "env" "memory" (memory 1 1))
+
+	;; [0, 32) address of the callee, copied from the input
+
+	;; [32, 36) size of the input buffer
+	(data (i32.const 32) "\20")
+
+	;; [36, 40) storage key
+	(data (i32.const 36) "\01\02\03\04")
+
+	;; [40, 56) storage value
+	(data (i32.const 40) "\10\11\12\13\14\15\16\17\18\19\1a\1b\1c\1d\1e\1f")
+
+	;; [56, 60) size of the storage output buffer
+
+	;; [60, 76) storage output buffer
+
+	;; [76, 84) call output buffer
+
+	;; [84, 88) size of the call output buffer
+
+	;; [88, 96) value to transfer: zero
+
+	;; [96, 108) call input: exit status zero, followed by the data to return
+	(data (i32.const 100) "\21\22\23\24\25\26\27\28")
+
+	;; [108, 120) call input: exit status one (revert), followed by the data to return
+	(data (i32.const 108) "\01\00\00\00\21\22\23\24\25\26\27\28")
+
+	(func $assert (param i32)
+		(block $ok
+			(br_if $ok
+				(get_local 0)
+			)
+			(unreachable)
+		)
+	)
+
+	(func $get_storage_into_buffer (result i32)
+		(call $get_storage
+			(i32.const 36) ;; Pointer to the storage key
+			(i32.const 4) ;; Length of the storage key
+			(i32.const 60) ;; Pointer to the output buffer
+			(i32.const 56) ;; Pointer to the size of the output buffer
+		)
+	)
+
+	(func $call_into_buffer (param $input_ptr i32) (result i32)
+		(call $seal_call
+			(i32.const 16) ;; Set REPORT_OUTPUT_LEN
+			(i32.const 0) ;; Pointer to "callee" address.
+			(i64.const 0) ;; How much gas to devote for the execution. 0 = all.
+			(i32.const 88) ;; Pointer to the buffer with value to transfer
+			(get_local $input_ptr) ;; Pointer to input data buffer address
+			(i32.const 12) ;; Length of input data buffer
+			(i32.const 76) ;; Pointer to output data buffer address
+			(i32.const 84) ;; Pointer to output data buffer size
+		)
+	)
+
+	(func (export "deploy")
+		(drop
+			(call $set_storage
+				(i32.const 36) ;; Pointer to the storage key
+				(i32.const 4) ;; Length of the storage key
+				(i32.const 40) ;; Pointer to the value
+				(i32.const 16) ;; Length of the value
+			)
+		)
+	)
+
+	(func (export "call")
+		(call $seal_input (i32.const 0) (i32.const 32))
+
+		;; An empty buffer is too small, the length of the value is reported instead.
+		(call $assert
+			(i32.eq (call $get_storage_into_buffer) (i32.const 13))
+		)
+		(call $assert
+			(i32.eq (i32.load (i32.const 56)) (i32.const 16))
+		)
+
+		;; Retrying with the reported length succeeds.
+		(call $assert
+			(i32.eqz (call $get_storage_into_buffer))
+		)
+		(call $assert
+			(i32.eq (i32.load (i32.const 56)) (i32.const 16))
+		)
+
+		;; A call still returns its outcome. The output is missing because the reported length
+		;; exceeds the buffer.
+		(call $assert
+			(i32.eq (call $call_into_buffer (i32.const 108)) (i32.const 3)) ;; CalleeReverted
+		)
+		(call $assert
+			(i32.eq (i32.load (i32.const 84)) (i32.const 8))
+		)
+		(call $assert
+			(i32.eqz (i32.load (i32.const 76)))
+		)
+		(i32.store (i32.const 84) (i32.const 0))
+		(call $assert
+			(i32.eqz (call $call_into_buffer (i32.const 96)))
+		)
+		(call $assert
+			(i32.eq (i32.load (i32.const 84)) (i32.const 8))
+		)
+		(call $assert
+			(i32.eqz (i32.load (i32.const 76)))
+		)
+
+		;; Retrying with the reported length copies the output.
+		(call $assert
+			(i32.eqz (call $call_into_buffer (i32.const 96)))
+		)
+		(call $assert
+			(i32.eq (i32.load (i32.const 84)) (i32.const 8))
+		)
+
+		(call $seal_return (i32.const 0) (i32.const 60) (i32.const 24))
+	)
+)
@@ -0,0 +1,40 @@
+;; Returns as many bytes as requested by the input, which is expected to be a `u32`.
+(module
+	(import "seal0" "seal_input" (func $seal_input (param i32 i32)))
+	(import "seal0" "seal_return" (func $seal_return (param i32 i32 i32)))
+	(import "env" "memory" (memory 16 16))
+
+	;; [0, 4) buffer where input is copied
+
+	;; [4, 8) size of the input buffer
+	(data (i32.const 4) "\04")
+
+	(func $assert (param i32)
+		(block $ok
+			(br_if $ok
+				(get_local 0)
+			)
+			(unreachable)
+		)
+	)
+
+	(func (export "call")
+		(call $seal_input (i32.const 0) (i32.const 4))
+
+		;; assert input size == 4
+		(call $assert
+			(i32.eq
+				(i32.load (i32.const 4))
+				(i32.const 4)
+			)
+		)
+
+		(call $seal_return
+			(i32.const 0) ;; Flags
+			(i32.const 0) ;; Pointer to the data to return
+			(i32.load (i32.const 0)) ;; Length of the data to return
+		)
+	)
+
+	(func (export "deploy"))
+)
@@ -0,0 +1,31 @@
+;; Expects [code_hash, flags] as input and calls `set_code_hash` with them.
+;; It returns the return code of `set_code_hash` as output data.
+(module
+	(import "seal0" "seal_input" (func $seal_input (param i32 i32)))
+	(import "seal0" "seal_return" (func $seal_return (param i32 i32 i32)))
+	(import "seal1" "set_code_hash" (func $set_code_hash (param i32 i32) (result i32)))
+	(import "env" "memory" (memory 1 1))
+
+	;; [0, 32) code hash to switch to
+
+	;; [32, 36) flags passed to `set_code_hash`
+
+	;; [36, 40) input size
+	(data (i32.const 36) "\24")
+
+	;; [40, 44) return code of `set_code_hash`
+
+	(func (export "deploy"))
+
+	(func (export "call")
+		(call $seal_input (i32.const 0) (i32.const 36))
+		(i32.store
+			(i32.const 40)
+			(call $set_code_hash
+				(i32.const 0) ;; Pointer to the code hash.
+				(i32.load (i32.const 32)) ;; Flags.
+			)
+		)
+		(call $seal_return (i32.const 0) (i32.const 40) (i32.const 4))
+	)
+)
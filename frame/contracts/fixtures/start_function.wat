@@ -0,0 +1,7 @@
+;; Module that declares a start function which is not allowed for contracts
+(module
+	(start $start)
+	(func $start)
+	(func (export "deploy"))
+	(func (export "call"))
+)
@@ -0,0 +1,93 @@
+;; Writes `n` storage items of `size` bytes each, reads them back and emits a single event.
+;; The input is expected to be `(n: u32, size: u32)`.
+(module
+	(import "seal0" "seal_input" (func $seal_input (param i32 i32)))
+	(import "seal0" "seal_set_storage" (func $seal_set_storage (param i32 i32 i32)))
+	(import "seal0" "seal_get_storage" (func $seal_get_storage (param i32 i32 i32) (result i32)))
+	(import "seal0" "seal_deposit_event" (func $seal_deposit_event (param i32 i32 i32 i32)))
+	(import "env" "memory" (memory 16 16))
+
+	;; [0, 8) buffer where input is copied
+
+	;; [8, 12) size of the input buffer
+	(data (i32.const 8) "\08")
+
+	;; [12, 16) size of the buffer for seal_get_storage
+
+	;; [16, 48) storage key, the first four bytes are set to the index of the item
+
+	;; [48, inf) value written to storage and buffer for seal_get_storage
+
+	(func $assert (param i32)
+		(block $ok
+			(br_if $ok
+				(get_local 0)
+			)
+			(unreachable)
+		)
+	)
+
+	(func (export "call")
+		(local $i i32)
+		(call $seal_input (i32.const 0) (i32.const 8))
+
+		;; assert input size == 8
+		(call $assert
+			(i32.eq
+				(i32.load (i32.const 8))
+				(i32.const 8)
+			)
+		)
+
+		(set_local $i (i32.const 0))
+		(block $write_done
+			(loop $write
+				(br_if $write_done (i32.ge_u (get_local $i) (i32.load (i32.const 0))))
+				(i32.store (i32.const 16) (get_local $i))
+				(call $seal_set_storage
+					(i32.const 16)				;; Pointer to storage key
+					(i32.const 48)				;; Pointer to value
+					(i32.load (i32.const 4))	;; Size of value
+				)
+				(set_local $i (i32.add (get_local $i) (i32.const 1)))
+				(br $write)
+			)
+		)
+
+		(set_local $i (i32.const 0))
+		(block $read_done
+			(loop $read
+				(br_if $read_done (i32.ge_u (get_local $i) (i32.load (i32.const 0))))
+				(i32.store (i32.const 16) (get_local $i))
+				(i32.store (i32.const 12) (i32.const 65536))
+				(call $assert
+					(i32.eq
+						(call $seal_get_storage
+							(i32.const 16)		;; Pointer to storage key
+							(i32.const 48)		;; buffer where to copy result
+							(i32.const 12)		;; pointer to size of buffer
+						)
+						(i32.const 0)
+					)
+				)
+				(call $assert
+					(i32.eq
+						(i32.load (i32.const 12))
+						(i32.load (i32.const 4))
+					)
+				)
+				(set_local $i (i32.add (get_local $i) (i32.const 1)))
+				(br $read)
+			)
+		)
+
+		(call $seal_deposit_event
+			(i32.const 0) ;; topics_ptr
+			(i32.const 0) ;; topics_len
+			(i32.const 0) ;; data_ptr
+			(i32.const 8) ;; data_len
+		)
+	)
+
+	(func (export "deploy"))
+)
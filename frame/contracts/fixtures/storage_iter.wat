@@ -0,0 +1,100 @@
+;; Writes `count` storage items under the keys `0 .. count`, encoded as 4 byte little endian
+;; integers, and then returns all keys of the contract in the order `storage_iter_next` yields them.
+;; The input is expected to be `count: u32`.
+(module
+	(import "seal0" "seal_input" (func $seal_input (param i32 i32)))
+	(import "seal0" "seal_return" (func $seal_return (param i32 i32 i32)))
+	(import "seal2" "set_storage" (func $set_storage (param i32 i32 i32 i32) (result i32)))
+	(import "seal0" "storage_iter_next" (func $storage_iter_next (param i32 i32 i32 i32) (result i32)))
+	(import "env" "memory" (memory 1 1))
+
+	;; [0, 4) buffer where input is copied
+
+	;; [4, 8) size of the input buffer
+	(data (i32.const 4) "\04")
+
+	;; [8, 12) storage key that is written
+
+	;; [12, 13) value that is written
+	(data (i32.const 12) "\01")
+
+	;; [16, 20) length of the output buffer for the next key
+
+	;; [20, ...) keys yielded by the iterator, 4 bytes each
+
+	(func $assert (param i32)
+		(block $ok
+			(br_if $ok
+				(get_local 0)
+			)
+			(unreachable)
+		)
+	)
+
+	(func (export "call")
+		(local $i i32)
+		(local $prev_ptr i32)
+		(local $next_ptr i32)
+		(local $rc i32)
+
+		(call $seal_input (i32.const 0) (i32.const 4))
+
+		;; assert input size == 4
+		(call $assert
+			(i32.eq
+				(i32.load (i32.const 4))
+				(i32.const 4)
+			)
+		)
+
+		(set_local $i (i32.const 0))
+		(block $writes_done
+			(loop $write
+				(br_if $writes_done (i32.ge_u (get_local $i) (i32.load (i32.const 0))))
+				(i32.store (i32.const 8) (get_local $i))
+				(drop
+					(call $set_storage
+						(i32.const 8) ;; Pointer to the storage key
+						(i32.const 4) ;; Length of the storage key
+						(i32.const 12) ;; Pointer to the value
+						(i32.const 1) ;; Length of the value
+					)
+				)
+				(set_local $i (i32.add (get_local $i) (i32.const 1)))
+				(br $write)
+			)
+		)
+
+		(set_local $prev_ptr (i32.const 0xffffffff)) ;; u32 max sentinel value: start at the first key
+		(set_local $next_ptr (i32.const 20))
+		(block $iter_done
+			(loop $iter
+				(i32.store (i32.const 16) (i32.const 4))
+				(set_local $rc
+					(call $storage_iter_next
+						(get_local $prev_ptr) ;; Pointer to the previous key
+						(i32.const 4) ;; Length of the previous key
+						(get_local $next_ptr) ;; Pointer to the output buffer
+						(i32.const 16) ;; Pointer to the output buffer length
+					)
+				)
+
+				;; ReturnCode::KeyNotFound means that there are no more keys
+				(br_if $iter_done (i32.eq (get_local $rc) (i32.const 3)))
+				(call $assert (i32.eqz (get_local $rc)))
+
+				(set_local $prev_ptr (get_local $next_ptr))
+				(set_local $next_ptr (i32.add (get_local $next_ptr) (i32.const 4)))
+				(br $iter)
+			)
+		)
+
+		(call $seal_return
+			(i32.const 0) ;; flags
+			(i32.const 20) ;; Pointer to the yielded keys
+			(i32.sub (get_local $next_ptr) (i32.const 20)) ;; Length of the yielded keys
+		)
+	)
+
+	(func (export "deploy"))
+)
@@ -0,0 +1,41 @@
+;; Stores a value under a key which is derived from the passed u32.
+(module
+	(import "seal0" "seal_set_storage" (func $seal_set_storage (param i32 i32 i32)))
+	(import "seal0" "seal_input" (func $seal_input (param i32 i32)))
+	(import "env" "memory" (memory 16 16))
+
+	;; [0, 32) storage key, the first four bytes of which are copied from the input
+
+	;; [32, 36) size of the input buffer
+	(data (i32.const 32) "\04")
+
+	(func $assert (param i32)
+		(block $ok
+			(br_if $ok
+				(get_local 0)
+			)
+			(unreachable)
+		)
+	)
+
+	(func (export "call")
+		(call $seal_input (i32.const 0) (i32.const 32))
+
+		;; assert input size == 4
+		(call $assert
+			(i32.eq
+				(i32.load (i32.const 32))
+				(i32.const 4)
+			)
+		)
+
+		;; we don't care about the contents of the storage item
+		(call $seal_set_storage
+			(i32.const 0)		;; Pointer to storage key
+			(i32.const 0)		;; Pointer to value
+			(i32.const 4)		;; Size of value
+		)
+	)
+
+	(func (export "deploy"))
+)
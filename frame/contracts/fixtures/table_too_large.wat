@@ -0,0 +1,6 @@
+;; Module that declares a table which exceeds the default `table_size` limit
+(module
+	(table 4097 funcref)
+	(func (export "deploy"))
+	(func (export "call"))
+)
@@ -0,0 +1,69 @@
+;; This expects [account_id, size] as input. It calls the account_id without any input data
+;; which makes the `self_destruct` fixture terminate itself. Afterwards it places a value
+;; of the passed size in its own storage.
+(module
+	(import "seal0" "seal_input" (func $seal_input (param i32 i32)))
+	(import "seal1" "seal_call" (func $seal_call (param i32 i32 i64 i32 i32 i32 i32 i32) (result i32)))
+	(import "seal0" "seal_set_storage" (func $seal_set_storage (param i32 i32 i32)))
+	(import "env" "memory" (memory 1 1))
+
+	;; [0, 4) size of the input buffer
+	(data (i32.const 0) "\24")
+
+	;; [4, 36) address of the contract to terminate
+
+	;; [36, 40) size of the storage item to place afterwards
+
+	;; [40, 48) value to transfer: zero
+
+	;; [48, 80) storage key
+	(data (i32.const 48) "\01")
+
+	;; [80, inf) zero initialized storage value
+
+	(func $assert (param i32)
+		(block $ok
+			(br_if $ok
+				(get_local 0)
+			)
+			(unreachable)
+		)
+	)
+
+	(func (export "deploy"))
+
+	(func (export "call")
+		(call $seal_input (i32.const 4) (i32.const 0))
+
+		;; assert input size == 36
+		(call $assert
+			(i32.eq
+				(i32.load (i32.const 0))
+				(i32.const 36)
+			)
+		)
+
+		;; Call the destination contract, forcing it to self-destruct.
+		(call $assert
+			(i32.eqz
+				(call $seal_call
+					(i32.const 0) ;; Set no flag
+					(i32.const 4) ;; Pointer to "callee" address.
+					(i64.const 0) ;; How much gas to devote for the execution. 0 = all.
+					(i32.const 40) ;; Pointer to the buffer with value to transfer
+					(i32.const 0) ;; Pointer to input data buffer address
+					(i32.const 0) ;; Length of input data buffer
+					(i32.const 0xffffffff) ;; u32 max sentinel value: do not copy output
+					(i32.const 0) ;; Ptr to output buffer len
+				)
+			)
+		)
+
+		;; Place a value in storage, the size of which is specified by the call input.
+		(call $seal_set_storage
+			(i32.const 48) ;; Pointer to storage key
+			(i32.const 80) ;; Pointer to value
+			(i32.load (i32.const 36)) ;; Size of value
+		)
+	)
+)
@@ -0,0 +1,6 @@
+;; Module that imports a memory without declaring its maximum size
+(module
+	(import "env" "memory" (memory 1))
+	(func (export "deploy"))
+	(func (export "call"))
+)
@@ -0,0 +1,54 @@
+;; Returns the fee for the weight passed as input. The input is expected to be the
+;; `ref_time` followed by the `proof_size` as little endian u64 values.
+(module
+	(import "seal0" "seal_input" (func $seal_input (param i32 i32)))
+	(import "seal0" "seal_return" (func $seal_return (param i32 i32 i32)))
+	(import "seal1" "weight_to_fee" (func $weight_to_fee (param i64 i64 i32 i32)))
+	(import "env" "memory" (memory 1 1))
+
+	;; [0, 16) input buffer
+
+	;; [16, 20) size of the input buffer
+	(data (i32.const 16) "\10")
+
+	;; [20, 52) output buffer
+
+	;; [52, 56) size of the output buffer
+	(data (i32.const 52) "\20")
+
+	(func $assert (param i32)
+		(block $ok
+			(br_if $ok
+				(get_local 0)
+			)
+			(unreachable)
+		)
+	)
+
+	(func (export "deploy"))
+
+	(func (export "call")
+		(call $seal_input (i32.const 0) (i32.const 16))
+
+		;; assert input size == 16
+		(call $assert
+			(i32.eq
+				(i32.load (i32.const 16))
+				(i32.const 16)
+			)
+		)
+
+		(call $weight_to_fee
+			(i64.load (i32.const 0))	;; ref_time
+			(i64.load (i32.const 8))	;; proof_size
+			(i32.const 20)	;; Pointer to the output buffer
+			(i32.const 52)	;; Pointer to the size of the output buffer
+		)
+
+		(call $seal_return
+			(i32.const 0)	;; flags
+			(i32.const 20)	;; Pointer to the fee
+			(i32.load (i32.const 52))	;; Size of the fee
+		)
+	)
+)
@@ -0,0 +1,46 @@
+;; This passes its input to `xcm_send` and returns the return value to its caller.
+;;
+;; The first byte of the input is the length of the destination, which follows it. The rest of
+;; the input is the message.
+(module
+	(import "seal0" "xcm_send" (func $xcm_send (param i32 i32 i32 i32 i64) (result i32)))
+	(import "seal0" "seal_input" (func $seal_input (param i32 i32)))
+	(import "seal0" "seal_return" (func $seal_return (param i32 i32 i32)))
+	(import "env" "memory" (memory 1 1))
+
+	;; 0x1000 = 4k in little endian
+	;; size of input buffer
+	(data (i32.const 0) "\00\10")
+
+	(func (export "call")
+		(local $dest_len i32)
+
+		;; Receive the destination and the message
+		(call $seal_input
+			(i32.const 4)	;; Pointer to the input buffer
+			(i32.const 0)	;; Size of the length buffer
+		)
+		(set_local $dest_len (i32.load8_u (i32.const 4)))
+
+		;; Send the message and store the result to memory
+		(i32.store (i32.const 0)
+			(call $xcm_send
+				(i32.const 5)								;; Pointer to the destination
+				(get_local $dest_len)						;; Size of the destination
+				(i32.add (i32.const 5) (get_local $dest_len))	;; Pointer to the message
+				(i32.sub									;; Size of the message
+					(i32.load (i32.const 0))
+					(i32.add (i32.const 1) (get_local $dest_len))
+				)
+				(i64.const 1000000000)						;; Weight limit
+			)
+		)
+		(call $seal_return
+			(i32.const 0)	;; flags
+			(i32.const 0)	;; returned value
+			(i32.const 4)	;; length of returned value
+		)
+	)
+
+	(func (export "deploy"))
+)
@@ -66,10 +66,83 @@ pub struct ContractResult<R, Balance> {
 	/// The debug message is never generated during on-chain execution. It is reserved for
 	/// RPC calls.
 	pub debug_message: Vec<u8>,
+	/// Storage access statistics collected across all frames of the call stack.
+	///
+	/// This is only `Some` when explicitly requested by the code that calls into the contract.
+	/// It is never collected during on-chain execution and always `None` for instantiations.
+	pub storage_stats: Option<StorageAccessStats>,
+	/// The consumed weight split up by what it was spent on.
+	///
+	/// Contains an entry for every [`GasCategory`] in the order of [`GasCategory::ALL`]. It
+	/// is only collected when debugging is requested for a call and is always `None` for
+	/// on-chain execution and instantiations.
+	pub gas_breakdown: Option<Vec<(GasCategory, Weight)>>,
+	/// Whether the execution took place in the deterministic sandbox.
+	///
+	/// Inside the sandbox contracts observe the block number and timestamp as zero and
+	/// receive randomness that only depends on the subject, regardless of the block the
+	/// execution is based on. This is only ever requested by the code that calls into the
+	/// contract and is always `false` for on-chain execution.
+	pub deterministic_sandbox: bool,
+	/// Whether the output data in [`Self::result`] was cut short.
+	///
+	/// Callers of a dry-run can cap the output data they receive with
+	/// [`DryRunOptions::max_output_len`], independently of the limit contracts are subject to
+	/// when returning data on-chain. The data is truncated to that cap if the contract returned
+	/// more. This is always `false` for on-chain execution.
+	pub data_truncated: bool,
 	/// The execution result of the wasm code.
 	pub result: R,
 }
 
+/// Counters of the storage accesses and events performed during a contract execution.
+///
+/// They are meant to help contract developers estimate the proof size of a call. Accesses
+/// of frames that were later reverted are still counted because they still need to be
+/// part of the proof.
+#[derive(Clone, Default, Eq, PartialEq, Encode, Decode, RuntimeDebug)]
+pub struct StorageAccessStats {
+	/// How many times contract storage was read, including size queries and writes which
+	/// return the value they replace.
+	pub reads: u32,
+	/// How many times contract storage was written to or cleared.
+	pub writes: u32,
+	/// The sum of the sizes of all values read from contract storage.
+	pub bytes_read: u32,
+	/// The sum of the sizes of all values written to contract storage.
+	pub bytes_written: u32,
+	/// How many events were emitted by contracts.
+	pub events: u32,
+}
+
+/// The classes of operations that a contract execution is charged for.
+///
+/// See [`ContractResult::gas_breakdown`].
+#[derive(Clone, Copy, Eq, PartialEq, Encode, Decode, RuntimeDebug)]
+pub enum GasCategory {
+	/// Executing wasm instructions.
+	Instructions,
+	/// Accessing contract storage.
+	Storage,
+	/// Hashing and signature recovery.
+	Crypto,
+	/// Calling and instantiating contracts, including value transfers.
+	Calls,
+	/// Everything else, e.g. loading code, emitting events or dispatching runtime calls.
+	Other,
+}
+
+impl GasCategory {
+	/// All categories, in the order in which they appear in a breakdown.
+	pub const ALL: [GasCategory; 5] = [
+		GasCategory::Instructions,
+		GasCategory::Storage,
+		GasCategory::Crypto,
+		GasCategory::Calls,
+		GasCategory::Other,
+	];
+}
+
 /// Result type of a `bare_call` call.
 pub type ContractExecResult<Balance> =
 	ContractResult<Result<ExecReturnValue, DispatchError>, Balance>;
@@ -80,11 +153,57 @@ pub type ContractInstantiateResult<AccountId, Balance> =
 
 /// Result type of a `bare_code_upload` call.
 pub type CodeUploadResult<CodeHash, Balance> =
-	Result<CodeUploadReturnValue<CodeHash, Balance>, DispatchError>;
+	Result<CodeUploadReturnValue<CodeHash, Balance>, CodeUploadError>;
+
+/// A call to a contract which is dry-run as part of a batch of calls.
+#[derive(Clone, Eq, PartialEq, Encode, Decode, RuntimeDebug)]
+pub struct CallRequest<AccountId, Balance> {
+	/// The account on whose behalf the call is made.
+	pub origin: AccountId,
+	/// The contract to be called.
+	pub dest: AccountId,
+	/// The balance transferred to the contract.
+	pub value: Balance,
+	/// The maximum amount of gas the call may consume.
+	///
+	/// `None` lets it use whatever is left of the gas of the batch.
+	pub gas_limit: Option<Weight>,
+	/// The maximum amount of balance that can be charged from `origin` for storage deposits.
+	pub storage_deposit_limit: Option<Balance>,
+	/// The input data passed to the contract.
+	pub input_data: Vec<u8>,
+}
+
+/// Options that only apply to dry-runs of contract calls and instantiations.
+///
+/// Dispatchables always execute with the default options.
+#[derive(Clone, Copy, Default, Eq, PartialEq, Encode, Decode, RuntimeDebug)]
+pub struct DryRunOptions {
+	/// Count the storage accesses and emitted events of the whole call stack and return them
+	/// in [`ContractExecResult::storage_stats`].
+	///
+	/// Only supported for calls. It is ignored for instantiations.
+	pub storage_stats: bool,
+	/// Replace the block number, the timestamp and the randomness observed by the contracts
+	/// with canonical values, see [`ContractExecResult::deterministic_sandbox`].
+	pub deterministic_sandbox: bool,
+	/// Truncate the output data of the contract to this many bytes, see
+	/// [`ContractExecResult::data_truncated`].
+	///
+	/// `None` hands out the output data in full. The contract itself is not affected.
+	pub max_output_len: Option<u32>,
+}
 
 /// Result type of a `get_storage` call.
 pub type GetStorageResult = Result<Option<Vec<u8>>, ContractAccessError>;
 
+/// Result type of an `export_contract` call.
+///
+/// Contains the exported `(hashed_key, value)` pairs and, if there are more entries, the key
+/// where the next page starts.
+pub type ExportContractResult =
+	Result<(Vec<(Vec<u8>, Vec<u8>)>, Option<Vec<u8>>), ContractAccessError>;
+
 /// The possible errors that can happen querying the storage of a contract.
 #[derive(Eq, PartialEq, Encode, Decode, RuntimeDebug)]
 pub enum ContractAccessError {
@@ -123,11 +242,37 @@ impl ExecReturnValue {
 #[derive(PartialEq, Eq, Encode, Decode, RuntimeDebug)]
 pub struct InstantiateReturnValue<AccountId> {
 	/// The output of the called constructor.
+	///
+	/// When the constructor reverted this contains the flags and data passed to `seal_return`.
+	/// Clients are expected to decode the contract specific error from [`ExecReturnValue::data`].
 	pub result: ExecReturnValue,
 	/// The account id of the new contract.
+	///
+	/// This is the address the contract would have been deployed to. No contract exists at
+	/// this address in case the constructor reverted. Use [`Self::contract_address`] in order
+	/// to only get the address of an actually deployed contract.
 	pub account_id: AccountId,
 }
 
+impl<AccountId> InstantiateReturnValue<AccountId> {
+	/// The constructor did revert all storage changes.
+	pub fn did_revert(&self) -> bool {
+		self.result.did_revert()
+	}
+
+	/// The account id of the new contract or `None` if the constructor reverted.
+	///
+	/// A trapping constructor never gets here as it is reported as an `Err` by the caller.
+	/// Hence `None` unambiguously marks a revert.
+	pub fn contract_address(&self) -> Option<&AccountId> {
+		if self.did_revert() {
+			None
+		} else {
+			Some(&self.account_id)
+		}
+	}
+}
+
 /// The result of succesfully uploading a contract.
 #[derive(PartialEq, Eq, Encode, Decode, RuntimeDebug)]
 pub struct CodeUploadReturnValue<CodeHash, Balance> {
@@ -137,6 +282,62 @@ pub struct CodeUploadReturnValue<CodeHash, Balance> {
 	pub deposit: Balance,
 }
 
+/// The error of an unsuccessful contract upload.
+#[derive(PartialEq, Eq, Encode, Decode, RuntimeDebug)]
+pub struct CodeUploadError {
+	/// The error which the upload fails with when it is dispatched on-chain.
+	pub error: DispatchError,
+	/// Why the module was rejected by the validation.
+	///
+	/// Only set when `error` is the pallet's `CodeRejected` error.
+	pub validation_error: Option<ValidationError>,
+}
+
+impl From<DispatchError> for CodeUploadError {
+	fn from(error: DispatchError) -> Self {
+		Self { error, validation_error: None }
+	}
+}
+
+/// The reason why a wasm module was rejected during validation.
+#[derive(Clone, PartialEq, Eq, Encode, Decode, RuntimeDebug)]
+pub enum ValidationError {
+	/// The module declares a start function.
+	///
+	/// Contracts are initialized through their `deploy` export instead.
+	StartFunction,
+	/// The module uses floating point types or instructions in deterministic mode.
+	FloatInstruction,
+	/// The imported memory declares no maximum or exceeds the configured limits.
+	MemoryLimits,
+	/// The module contains an import which is not allowed.
+	ForbiddenImport {
+		/// The field name of the offending import.
+		name: Vec<u8>,
+	},
+	/// The module declares a table which is larger than allowed.
+	TableSize,
+	/// The module declares more globals than allowed.
+	GlobalCount,
+	/// The module was rejected for any other reason.
+	///
+	/// The details are written to the `runtime::contracts` debug log.
+	Other,
+}
+
+/// A host function which contracts can import.
+#[derive(Clone, PartialEq, Eq, Encode, Decode, RuntimeDebug)]
+pub struct HostFunction {
+	/// The name of the function, e.g. `seal_call`.
+	pub name: Vec<u8>,
+	/// The version of the function, i.e. the `N` of the `sealN` module it is imported from.
+	pub version: u8,
+	/// Whether the function is part of the stable interface.
+	///
+	/// Unstable functions are only available on chains which enable them.
+	pub stable: bool,
+}
+
 /// Reference to an existing code hash or a new wasm module.
 #[derive(Eq, PartialEq, Encode, Decode, RuntimeDebug)]
 pub enum Code<Hash> {
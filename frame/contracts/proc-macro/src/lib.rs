@@ -154,6 +154,7 @@ struct EnvDef {
 /// Parsed host function definition.
 struct HostFn {
 	item: syn::ItemFn,
+	version: u8,
 	module: String,
 	name: String,
 	returns: HostFnReturn,
@@ -200,18 +201,18 @@ impl HostFn {
 		let mut attrs = item.attrs.clone();
 		attrs.retain(|a| !(a.path.is_ident("doc") || a.path.is_ident("prefixed_alias")));
 		let name = item.sig.ident.to_string();
-		let mut maybe_module = None;
+		let mut maybe_version = None;
 		let mut is_stable = true;
 		while let Some(attr) = attrs.pop() {
 			let ident = attr.path.get_ident().ok_or(err(span, msg))?.to_string();
 			match ident.as_str() {
 				"version" => {
-					if maybe_module.is_some() {
+					if maybe_version.is_some() {
 						return Err(err(span, "#[version] can only be specified once"))
 					}
 					let ver: u8 =
 						attr.parse_args::<syn::LitInt>().and_then(|lit| lit.base10_parse())?;
-					maybe_module = Some(format!("seal{}", ver));
+					maybe_version = Some(ver);
 				},
 				"unstable" => {
 					if !is_stable {
@@ -311,9 +312,11 @@ impl HostFn {
 							_ => Err(err(arg1.span(), &msg)),
 						}?;
 
+						let version = maybe_version.unwrap_or_default();
 						Ok(Self {
 							item,
-							module: maybe_module.unwrap_or_else(|| "seal0".to_string()),
+							version,
+							module: format!("seal{}", version),
 							name,
 							returns,
 							is_stable,
@@ -386,13 +389,35 @@ fn is_valid_special_arg(idx: usize, arg: &FnArg) -> bool {
 /// Expands environment definiton.
 /// Should generate source code for:
 ///  - implementations of the host functions to be added to the wasm runtime environment (see
-///    `expand_impls()`).
+///    `expand_impls()`);
+///  - a table listing all host functions (see `expand_host_functions()`).
 fn expand_env(def: &mut EnvDef) -> TokenStream2 {
 	let impls = expand_impls(def);
+	let host_functions = expand_host_functions(def);
 
 	quote! {
 		pub struct Env;
 		#impls
+		#host_functions
+	}
+}
+
+/// Generates `Env::HOST_FUNCTIONS`, listing the version, name and stability of every host
+/// function, in the order in which they are defined.
+fn expand_host_functions(def: &EnvDef) -> TokenStream2 {
+	let entries = def.host_funcs.iter().map(|f| {
+		let (version, name, is_stable) = (f.version, &f.name, f.is_stable);
+		quote! { (#version, #name, #is_stable) }
+	});
+
+	quote! {
+		impl Env {
+			/// The `(version, name, is_stable)` of every host function of this environment,
+			/// including the unstable ones.
+			pub const HOST_FUNCTIONS: &'static [(::core::primitive::u8, &'static ::core::primitive::str, bool)] = &[
+				#( #entries, )*
+			];
+		}
 	}
 }
 
@@ -579,6 +604,9 @@ fn expand_functions(
 ///
 /// The implementation on `()` can be used in places where no `Ext` exists, yet. This is useful
 /// when only checking whether a code can be instantiated without actually executing any code.
+///
+/// `Env` additionally gets an associated `HOST_FUNCTIONS` constant, which lists the version, name
+/// and stability of every defined function.
 #[proc_macro_attribute]
 pub fn define_env(attr: TokenStream, item: TokenStream) -> TokenStream {
 	if !attr.is_empty() {
@@ -38,7 +38,7 @@ use crate::{
 };
 use codec::{Encode, MaxEncodedLen};
 use frame_benchmarking::{account, benchmarks, whitelisted_caller};
-use frame_support::weights::Weight;
+use frame_support::{weights::Weight, Blake2_128Concat, StorageHasher};
 use frame_system::RawOrigin;
 use sp_runtime::{
 	traits::{Bounded, Hash},
@@ -181,6 +181,16 @@ fn caller_funding<T: Config>() -> BalanceOf<T> {
 	BalanceOf::<T>::max_value() / 2u32.into()
 }
 
+/// The largest event payload that lets a batch of events with the maximum number of topics fit
+/// into [`Limits::event_bytes_per_call`](crate::Limits).
+fn max_batched_event_payload<T: Config>() -> u32 {
+	let limits = T::Schedule::get().limits;
+	let topics_len = limits.event_topics * sp_std::mem::size_of::<T::Hash>() as u32;
+	(limits.event_bytes_per_call / API_BENCHMARK_BATCH_SIZE)
+		.saturating_sub(topics_len)
+		.min(limits.payload_len)
+}
+
 /// Load the specified contract file from disk by including it into the runtime.
 ///
 /// We need to load a different version of ink! contracts when the benchmark is run as
@@ -388,7 +398,8 @@ benchmarks! {
 		T::Currency::make_free_balance_be(&caller, caller_funding::<T>());
 		let WasmModule { code, hash, .. } = WasmModule::<T>::dummy();
 		let origin = RawOrigin::Signed(caller.clone());
-		let uploaded = <Contracts<T>>::bare_upload_code(caller.clone(), code, None, Determinism::Deterministic)?;
+		let uploaded = <Contracts<T>>::bare_upload_code(caller.clone(), code, None, Determinism::Deterministic)
+			.map_err(|err| err.error)?;
 		assert_eq!(uploaded.code_hash, hash);
 		assert_eq!(uploaded.deposit, T::Currency::reserved_balance(&caller));
 		assert!(<Contract<T>>::code_exists(&hash));
@@ -413,6 +424,33 @@ benchmarks! {
 		assert_eq!(instance.info()?.code_hash, hash);
 	}
 
+	fund_reinstrument_pot {
+		let source = whitelisted_caller();
+		let amount = caller_funding::<T>() / 2u32.into();
+		T::Currency::make_free_balance_be(&source, caller_funding::<T>());
+		let source_lookup = T::Lookup::unlookup(source.clone());
+	}: _(RawOrigin::Root, source_lookup, amount)
+	verify {
+		assert_eq!(T::Currency::free_balance(&Contracts::<T>::reinstrument_pot()), amount);
+	}
+
+	// Every entry creates a new item of the maximum size. We benchmark the import itself as
+	// the extrinsic is only available if `AllowStorageImport` is set.
+	import_contract_storage {
+		let n in 0 .. 128;
+		let instance = Contract::<T>::new(WasmModule::dummy(), vec![])?;
+		let value_len = T::Schedule::get().limits.payload_len as usize;
+		let entries = (0..n)
+			.map(|i| (Blake2_128Concat::hash(&i.to_le_bytes()), vec![42u8; value_len]))
+			.collect::<Vec<_>>();
+		let depositor = instance.caller.clone();
+	}: {
+		Contracts::<T>::do_import_contract_storage(&instance.account_id, &depositor, entries)?;
+	}
+	verify {
+		assert_eq!(instance.info()?.storage_items, n);
+	}
+
 	seal_caller {
 		let r in 0 .. API_BENCHMARK_BATCHES;
 		let instance = Contract::<T>::new(WasmModule::getter(
@@ -716,8 +754,9 @@ benchmarks! {
 		let origin = RawOrigin::Signed(instance.caller.clone());
 	}: call(origin, instance.addr, 0u32.into(), Weight::MAX, None, vec![])
 
+	// Contracts cannot return more than `max_return_len`, hence we copy up to that limit.
 	seal_return_per_kb {
-		let n in 0 .. code::max_pages::<T>() * 64;
+		let n in 0 .. T::Schedule::get().limits.max_return_len / 1024;
 		let code = WasmModule::<T>::from(ModuleDefinition {
 			memory: Some(ImportedMemory::max::<T>()),
 			imported_functions: vec![ImportedFunction {
@@ -842,9 +881,11 @@ benchmarks! {
 	// Benchmark the overhead that topics generate.
 	// `t`: Number of topics
 	// `n`: Size of event payload in kb
+	//
+	// The payload is bounded so that the whole batch fits into the event budget of the call.
 	seal_deposit_event_per_topic_and_kb {
 		let t in 0 .. T::Schedule::get().limits.event_topics;
-		let n in 0 .. T::Schedule::get().limits.payload_len / 1024;
+		let n in 0 .. max_batched_event_payload::<T>() / 1024;
 		let mut topics = (0..API_BENCHMARK_BATCH_SIZE)
 			.map(|n| (n * t..n * t + t).map(|i| T::Hashing::hash_of(&i)).collect::<Vec<_>>().encode())
 			.peekable();
@@ -1205,6 +1246,59 @@ benchmarks! {
 		let origin = RawOrigin::Signed(instance.caller.clone());
 	}: call(origin, instance.addr, 0u32.into(), Weight::MAX, None, vec![])
 
+	// Same as `seal_get_storage` but every call supplies an empty buffer of its own, so that the
+	// length of the item is reported instead of copied. The difference to `seal_get_storage` is
+	// the cost of reporting the length.
+	#[skip_meta]
+	seal_get_storage_report_len {
+		let r in 0 .. API_BENCHMARK_BATCHES/2;
+		let max_key_len = T::MaxStorageKeyLen::get();
+		let keys = (0 .. r * API_BENCHMARK_BATCH_SIZE)
+				.map(|n| { let mut h = T::Hashing::hash_of(&n).as_ref().to_vec();
+						h.resize(max_key_len.try_into().unwrap(), n.to_le_bytes()[0]); h })
+		.collect::<Vec<_>>();
+		let key_bytes = keys.iter().flatten().cloned().collect::<Vec<_>>();
+		let key_bytes_len = key_bytes.len();
+		let code = WasmModule::<T>::from(ModuleDefinition {
+			memory: Some(ImportedMemory::max::<T>()),
+			imported_functions: vec![ImportedFunction {
+				module: "seal2",
+				name: "get_storage",
+				params: vec![ValueType::I32, ValueType::I32, ValueType::I32, ValueType::I32],
+				return_type: Some(ValueType::I32),
+			}],
+			data_segments: vec![
+				DataSegment {
+					offset: 0,
+					value: key_bytes,
+				},
+			],
+			call_body: Some(body::repeated_dyn(r * API_BENCHMARK_BATCH_SIZE, vec![
+				Counter(0, max_key_len as u32), // key_ptr
+				Regular(Instruction::I32Const(max_key_len as i32)), // key_len
+				Regular(Instruction::I32Const(0)), // out_ptr
+				Counter(key_bytes_len as u32, 4), // out_len_ptr: zero initialized
+				Regular(Instruction::Call(0)),
+				Regular(Instruction::Drop),
+			])),
+			.. Default::default()
+		});
+		let instance = Contract::<T>::new(code, vec![])?;
+		let info = instance.info()?;
+		for key in keys {
+			Storage::<T>::write(
+				&info.trie_id,
+				&VarSizedKey::<T>::try_from(key).map_err(|e| "Key has wrong length")?,
+				Some(vec![0]),
+				None,
+				false,
+			)
+			.map_err(|_| "Failed to write to storage during setup.")?;
+		}
+		<ContractInfoOf<T>>::insert(&instance.account_id, info);
+		let origin = RawOrigin::Signed(instance.caller.clone());
+	}: call(origin, instance.addr, 0u32.into(), Weight::MAX, None, vec![])
+
 	#[skip_meta]
 	seal_get_storage_per_kb {
 		let n in 0 .. T::Schedule::get().limits.payload_len / 2048; // half of the max payload_len in kb
@@ -1463,6 +1557,62 @@ benchmarks! {
 		let origin = RawOrigin::Signed(instance.caller.clone());
 	}: call(origin, instance.addr, 0u32.into(), Weight::MAX, None, vec![])
 
+	// The keys are laid out in iteration order so that every call visits a different entry.
+	#[skip_meta]
+	seal_storage_iter_next {
+		let r in 0 .. API_BENCHMARK_BATCHES/2;
+		let max_key_len = T::MaxStorageKeyLen::get();
+		let mut keys = (0 .. r * API_BENCHMARK_BATCH_SIZE)
+				.map(|n| { let mut h = T::Hashing::hash_of(&n).as_ref().to_vec();
+						h.resize(max_key_len.try_into().unwrap(), n.to_le_bytes()[0]); h })
+		.collect::<Vec<_>>();
+		keys.sort_by_key(|key| Blake2_128Concat::hash(key));
+		let key_bytes = keys.iter().flatten().cloned().collect::<Vec<_>>();
+		let key_bytes_len = key_bytes.len();
+		let code = WasmModule::<T>::from(ModuleDefinition {
+			memory: Some(ImportedMemory::max::<T>()),
+			imported_functions: vec![ImportedFunction {
+				module: "seal0",
+				name: "storage_iter_next",
+				params: vec![ValueType::I32, ValueType::I32, ValueType::I32, ValueType::I32],
+				return_type: Some(ValueType::I32),
+			}],
+			data_segments: vec![
+				DataSegment {
+					offset: 0,
+					value: key_bytes,
+				},
+				DataSegment {
+					offset: key_bytes_len as u32,
+					value: max_key_len.to_le_bytes().into(),
+				},
+			],
+			call_body: Some(body::repeated_dyn(r * API_BENCHMARK_BATCH_SIZE, vec![
+				Counter(0, max_key_len as u32), // prev_key_ptr
+				Regular(Instruction::I32Const(max_key_len as i32)), // prev_key_len
+				Regular(Instruction::I32Const((key_bytes_len + 4) as i32)), // out_ptr
+				Regular(Instruction::I32Const(key_bytes_len as i32)), // out_len_ptr
+				Regular(Instruction::Call(0)),
+				Regular(Instruction::Drop),
+			])),
+			.. Default::default()
+		});
+		let instance = Contract::<T>::new(code, vec![])?;
+		let info = instance.info()?;
+		for key in keys {
+			Storage::<T>::write(
+				&info.trie_id,
+				&VarSizedKey::<T>::try_from(key).map_err(|e| "Key has wrong length")?,
+				Some(vec![]),
+				None,
+				false,
+			)
+			.map_err(|_| "Failed to write to storage during setup.")?;
+		}
+		<ContractInfoOf<T>>::insert(&instance.account_id, info);
+		let origin = RawOrigin::Signed(instance.caller.clone());
+	}: call(origin, instance.addr, 0u32.into(), Weight::MAX, None, vec![])
+
 	// We transfer to unique accounts.
 	seal_transfer {
 		let r in 0 .. API_BENCHMARK_BATCHES;
@@ -2161,6 +2311,105 @@ benchmarks! {
 		let origin = RawOrigin::Signed(instance.caller.clone());
 	}: call(origin, instance.addr, 0u32.into(), Weight::MAX, None, vec![])
 
+	seal_call_stack_remaining {
+		let r in 0 .. API_BENCHMARK_BATCHES;
+		let code = WasmModule::<T>::from(ModuleDefinition {
+			memory: Some(ImportedMemory::max::<T>()),
+			imported_functions: vec![ImportedFunction {
+				module: "seal0",
+				name: "call_stack_remaining",
+				params: vec![],
+				return_type: Some(ValueType::I32),
+			}],
+			call_body: Some(body::repeated(r * API_BENCHMARK_BATCH_SIZE, &[
+				Instruction::Call(0),
+				Instruction::Drop,
+			])),
+			.. Default::default()
+		});
+		let instance = Contract::<T>::new(code, vec![])?;
+		let origin = RawOrigin::Signed(instance.caller.clone());
+	}: call(origin, instance.addr, 0u32.into(), Weight::MAX, None, vec![])
+
+	// The caller is a plain account, which is the worst case: It is not found on the call stack
+	// and has to be looked up in storage. We only call the function once because every further
+	// call would read the caller from the storage cache and hence understate the cost.
+	seal_caller_is_contract {
+		let r in 0 .. 1;
+		let code = WasmModule::<T>::from(ModuleDefinition {
+			memory: Some(ImportedMemory::max::<T>()),
+			imported_functions: vec![ImportedFunction {
+				module: "seal0",
+				name: "caller_is_contract",
+				params: vec![],
+				return_type: Some(ValueType::I32),
+			}],
+			call_body: Some(body::repeated(r, &[
+				Instruction::Call(0),
+				Instruction::Drop,
+			])),
+			.. Default::default()
+		});
+		let instance = Contract::<T>::new(code, vec![])?;
+		let origin = RawOrigin::Signed(instance.caller.clone());
+	}: call(origin, instance.addr, 0u32.into(), Weight::MAX, None, vec![])
+
+	// The messages are empty and limited to zero weight, so that only the host function and the
+	// configured `Config::Xcm` are measured. The weight of the messages is charged separately.
+	seal_xcm_execute {
+		let r in 0 .. API_BENCHMARK_BATCHES;
+		let code = WasmModule::<T>::from(ModuleDefinition {
+			memory: Some(ImportedMemory::max::<T>()),
+			imported_functions: vec![ImportedFunction {
+				module: "seal0",
+				name: "xcm_execute",
+				params: vec![ValueType::I32, ValueType::I32, ValueType::I64],
+				return_type: Some(ValueType::I32),
+			}],
+			call_body: Some(body::repeated(r * API_BENCHMARK_BATCH_SIZE, &[
+				Instruction::I32Const(0), // msg_ptr
+				Instruction::I32Const(0), // msg_len
+				Instruction::I64Const(0), // ref_time_limit
+				Instruction::Call(0),
+				Instruction::Drop,
+			])),
+			.. Default::default()
+		});
+		let instance = Contract::<T>::new(code, vec![])?;
+		let origin = RawOrigin::Signed(instance.caller.clone());
+	}: call(origin, instance.addr, 0u32.into(), Weight::MAX, None, vec![])
+
+	seal_xcm_send {
+		let r in 0 .. API_BENCHMARK_BATCHES;
+		let code = WasmModule::<T>::from(ModuleDefinition {
+			memory: Some(ImportedMemory::max::<T>()),
+			imported_functions: vec![ImportedFunction {
+				module: "seal0",
+				name: "xcm_send",
+				params: vec![
+					ValueType::I32,
+					ValueType::I32,
+					ValueType::I32,
+					ValueType::I32,
+					ValueType::I64,
+				],
+				return_type: Some(ValueType::I32),
+			}],
+			call_body: Some(body::repeated(r * API_BENCHMARK_BATCH_SIZE, &[
+				Instruction::I32Const(0), // dest_ptr
+				Instruction::I32Const(0), // dest_len
+				Instruction::I32Const(0), // msg_ptr
+				Instruction::I32Const(0), // msg_len
+				Instruction::I64Const(0), // ref_time_limit
+				Instruction::Call(0),
+				Instruction::Drop,
+			])),
+			.. Default::default()
+		});
+		let instance = Contract::<T>::new(code, vec![])?;
+		let origin = RawOrigin::Signed(instance.caller.clone());
+	}: call(origin, instance.addr, 0u32.into(), Weight::MAX, None, vec![])
+
 	// We make the assumption that pushing a constant and dropping a value takes roughly
 	// the same amount of time. We follow that `t.load` and `drop` both have the weight
 	// of this benchmark / 2. We need to make this assumption because there is no way
@@ -2985,6 +3234,7 @@ benchmarks! {
 			data,
 			false,
 			Determinism::Deterministic,
+			DryRunOptions::default(),
 		)
 		.result?;
 	}
@@ -3033,6 +3283,7 @@ benchmarks! {
 			data,
 			false,
 			Determinism::Deterministic,
+			DryRunOptions::default(),
 		)
 		.result?;
 	}
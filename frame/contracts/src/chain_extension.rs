@@ -56,6 +56,21 @@
 //! is. Check whether using [`bare_call`](crate::Pallet::bare_call) suffices for the
 //! use case at hand.
 //!
+//! When the exact weight of an action is only known after carrying it out, the worst case
+//! must be charged upfront. The [`ChargedAmount`] returned by
+//! [`charge_weight`](Environment::charge_weight) can then be passed to
+//! [`adjust_weight`](Environment::adjust_weight) together with the actual weight in order
+//! to refund the difference:
+//!
+//! ```ignore
+//! let charged = env.charge_weight(worst_case_weight)?;
+//! let actual_weight = do_the_work()?;
+//! env.adjust_weight(charged, actual_weight);
+//! ```
+//!
+//! The adjustment can only lower the charged amount. Passing an `actual_weight` that is
+//! larger than the charged one is treated as if nothing was refunded.
+//!
 //! # Benchmarking
 //!
 //! The builtin contract callable functions that pallet-contracts provides all have
@@ -71,7 +86,6 @@
 //! on how to use a chain extension in order to provide new features to ink! contracts.
 
 use crate::{
-	gas::ChargedAmount,
 	wasm::{Runtime, RuntimeCosts},
 	Error,
 };
@@ -80,7 +94,7 @@ use frame_support::weights::Weight;
 use sp_runtime::DispatchError;
 use sp_std::{marker::PhantomData, vec::Vec};
 
-pub use crate::{exec::Ext, Config};
+pub use crate::{exec::Ext, gas::ChargedAmount, Config};
 pub use frame_system::Config as SysConfig;
 pub use pallet_contracts_primitives::ReturnFlags;
 
@@ -233,7 +247,8 @@ impl<'a, 'b, E: Ext, S: State> Environment<'a, 'b, E, S> {
 	/// Adjust a previously charged amount down to its actual amount.
 	///
 	/// This is when a maximum a priori amount was charged and then should be partially
-	/// refunded to match the actual amount.
+	/// refunded to match the actual amount. The charge is never increased: An `actual_weight`
+	/// larger than the charged amount leaves the charge as is.
 	pub fn adjust_weight(&mut self, charged: ChargedAmount, actual_weight: Weight) {
 		self.inner
 			.runtime
@@ -0,0 +1,70 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2022 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! An integration point for streaming the debug messages of contracts out of the runtime.
+//!
+//! The messages that contracts emit through `seal_debug_message` are collected into the debug
+//! buffer when a dry-run asks for it. Without a debug buffer, i.e. when executed on-chain, they
+//! are handed to the [`ContractDebugSink`] declared as [`Config::Debugger`] instead. This is meant
+//! for development chains whose operators want to follow contract logs in the node logs.
+//!
+//! # Determinism
+//!
+//! The sink only ever observes execution. A contract cannot tell whether its messages are
+//! forwarded: `seal_debug_message` keeps returning `LoggingDisabled` and never traps because of a
+//! message that cannot be forwarded. Implementations must not touch storage either, as the
+//! messages are produced by dry-runs without a debug buffer as well.
+//!
+//! # Weight
+//!
+//! Reading a message out of the memory of the contract is charged per byte when a sink is
+//! enabled, which makes every debug message more expensive on-chain. Production chains should use
+//! `()`, which does not read the messages at all.
+
+use crate::Config;
+
+/// Receives the debug messages that contracts emit while executed without a debug buffer.
+///
+/// Use `()` to drop them without reading them from the memory of the contract.
+pub trait ContractDebugSink<T: Config> {
+	/// Whether messages are forwarded to [`Self::on_debug_message`] at all.
+	const ENABLED: bool;
+
+	/// Called with the `contract` that emitted `message`.
+	///
+	/// Only messages which are valid UTF-8 and fit into the debug buffer are forwarded.
+	fn on_debug_message(contract: &T::AccountId, message: &str);
+}
+
+impl<T: Config> ContractDebugSink<T> for () {
+	const ENABLED: bool = false;
+
+	fn on_debug_message(_: &T::AccountId, _: &str) {}
+}
+
+/// Writes all messages to the node log under the `runtime::contracts::debug` target.
+///
+/// They are logged at debug level, e.g. enable them with `-lruntime::contracts::debug=debug`.
+pub struct LogSink;
+
+impl<T: Config> ContractDebugSink<T> for LogSink {
+	const ENABLED: bool = true;
+
+	fn on_debug_message(contract: &T::AccountId, message: &str) {
+		log::debug!(target: "runtime::contracts::debug", "{:?}: {}", contract, message);
+	}
+}
@@ -16,25 +16,36 @@
 // limitations under the License.
 
 use crate::{
-	gas::GasMeter,
+	gas::{GasMeter, Token},
 	storage::{self, Storage, WriteOutcome},
+	xcm::XcmInterface,
 	BalanceOf, CodeHash, Config, ContractInfo, ContractInfoOf, DebugBufferVec, Determinism, Error,
-	Event, Nonce, Pallet as Contracts, Schedule,
+	Event, Nonce, Pallet as Contracts, RandomnessCallsInBlock, Schedule, TerminationOutcome,
 };
+use codec::{Decode, Encode, MaxEncodedLen};
 use frame_support::{
 	crypto::ecdsa::ECDSAExt,
 	dispatch::{DispatchError, DispatchResult, DispatchResultWithPostInfo, Dispatchable},
 	storage::{with_transaction, TransactionOutcome},
-	traits::{Contains, Currency, ExistenceRequirement, OriginTrait, Randomness, Time},
+	traits::{
+		Contains, Currency, ExistenceRequirement, Get, OnUnbalanced, OriginTrait, Randomness, Time,
+		WithdrawReasons,
+	},
 	weights::Weight,
 	Blake2_128Concat, BoundedVec, StorageHasher,
 };
 use frame_system::RawOrigin;
-use pallet_contracts_primitives::ExecReturnValue;
+use pallet_contracts_primitives::{ExecReturnValue, StorageAccessStats};
 use smallvec::{Array, SmallVec};
 use sp_core::ecdsa::Public as ECDSAPublic;
-use sp_io::{crypto::secp256k1_ecdsa_recover_compressed, hashing::blake2_256};
-use sp_runtime::traits::{Convert, Hash};
+use sp_io::{
+	crypto::secp256k1_ecdsa_recover_compressed,
+	hashing::{blake2_128, blake2_256},
+};
+use sp_runtime::{
+	traits::{Convert, Hash, Saturating, Zero},
+	RuntimeDebug, SaturatedConversion,
+};
 use sp_std::{marker::PhantomData, mem, prelude::*};
 
 pub type AccountIdOf<T> = <T as frame_system::Config>::AccountId;
@@ -75,6 +86,31 @@ where
 	}
 }
 
+/// Recovers a transparently hashed key from its hashed representation in the child trie.
+///
+/// Returns `None` for keys that were written as [`FixSizedKey`] because those can't be reversed.
+fn unhash_key<T: Config>(hashed: &[u8]) -> Option<VarSizedKey<T>> {
+	if hashed.len() < 16 {
+		return None
+	}
+	let (hash, key) = hashed.split_at(16);
+	if blake2_128(key) != hash {
+		return None
+	}
+	key.to_vec().try_into().ok()
+}
+
+/// A single step of iterating the storage keys of a contract.
+#[cfg_attr(test, derive(Debug, PartialEq))]
+pub enum StorageIterStep<T: Config> {
+	/// The next key in iteration order.
+	Key(VarSizedKey<T>),
+	/// The next entry can't be returned and is skipped.
+	Skipped,
+	/// There are no more keys.
+	End,
+}
+
 /// Origin of the error.
 ///
 /// Call or instantiate both called into other contracts and pass through errors happening
@@ -91,6 +127,59 @@ pub enum ErrorOrigin {
 	Callee,
 }
 
+/// Category of the reason why a contract execution failed.
+///
+/// This is deliberately coarse so that [`crate::Event::ContractTrapped`] stays small and does
+/// not leak any data returned by the failed contract.
+#[derive(
+	Clone, Copy, Encode, Decode, scale_info::TypeInfo, MaxEncodedLen, RuntimeDebug, PartialEq, Eq,
+)]
+pub enum TrapCategory {
+	/// The execution ran out of gas.
+	OutOfGas,
+	/// The storage deposit could not be charged or exceeded its limit.
+	StorageDeposit,
+	/// The contract trapped during its execution.
+	Trapped,
+	/// Any other error.
+	Other,
+}
+
+impl TrapCategory {
+	/// Categorize the error that caused an execution to fail.
+	pub(crate) fn from_error<T: Config>(error: &DispatchError) -> Self {
+		if *error == Error::<T>::OutOfGas.into() {
+			Self::OutOfGas
+		} else if *error == Error::<T>::StorageDepositLimitExhausted.into() ||
+			*error == Error::<T>::StorageDepositNotEnoughFunds.into()
+		{
+			Self::StorageDeposit
+		} else if *error == Error::<T>::ContractTrapped.into() {
+			Self::Trapped
+		} else {
+			Self::Other
+		}
+	}
+}
+
+/// The weight of depositing a [`crate::Event::ContractTrapped`] of the supplied length.
+///
+/// It is the same as depositing an event with one topic from within a contract.
+#[cfg_attr(test, derive(Debug, PartialEq, Eq))]
+#[derive(Clone, Copy)]
+struct TrapEventToken(u32);
+
+impl<T: Config> Token<T> for TrapEventToken {
+	fn weight(&self) -> Weight {
+		let s = T::Schedule::get().host_fn_weights;
+		Weight::from_ref_time(
+			s.deposit_event
+				.saturating_add(s.deposit_event_per_topic)
+				.saturating_add(s.deposit_event_per_byte.saturating_mul(self.0.into())),
+		)
+	}
+}
+
 /// Error returned by contract exection.
 #[cfg_attr(test, derive(Debug, PartialEq))]
 pub struct ExecError {
@@ -130,6 +219,16 @@ pub trait Ext: sealing::Sealed {
 		allows_reentry: bool,
 	) -> Result<ExecReturnValue, ExecError>;
 
+	/// Transfer some amount of funds into `to` without calling it, unless it is a contract.
+	///
+	/// Returns `false` without transferring anything if `to` is a contract. Accounts on the
+	/// call stack count as contracts so that contracts under construction are not skipped.
+	fn transfer_to_account(
+		&mut self,
+		to: &AccountIdOf<Self::T>,
+		value: BalanceOf<Self::T>,
+	) -> Result<bool, DispatchError>;
+
 	/// Execute code in the current frame.
 	///
 	/// Returns the original code size of the called contract.
@@ -155,6 +254,9 @@ pub trait Ext: sealing::Sealed {
 
 	/// Transfer all funds to `beneficiary` and delete the contract.
 	///
+	/// If `beneficiary` does not exist and the funds are below the existential deposit they are
+	/// handed to [`Config::TerminationDustHandler`] instead.
+	///
 	/// Since this function removes the self contract eagerly, if succeeded, no further actions
 	/// should be performed on this `Ext` instance.
 	///
@@ -211,6 +313,15 @@ pub trait Ext: sealing::Sealed {
 		take_old: bool,
 	) -> Result<WriteOutcome, DispatchError>;
 
+	/// Visits the storage entry of the executing account that follows `cursor`, or the first one
+	/// if `cursor` is empty, and moves `cursor` to it.
+	///
+	/// `cursor` is a key as laid out in the child trie, see [`StorageKey::hash`]. Keys are ordered
+	/// by this transparently hashed representation, which is unrelated to the order in which they
+	/// were written. Changes made by the current call are visible. Entries written with a
+	/// [`FixSizedKey`] can't be returned and are [`StorageIterStep::Skipped`].
+	fn next_storage_key(&mut self, cursor: &mut Vec<u8>) -> StorageIterStep<Self::T>;
+
 	/// Returns a reference to the account id of the caller.
 	fn caller(&self) -> &AccountIdOf<Self::T>;
 
@@ -231,6 +342,12 @@ pub trait Ext: sealing::Sealed {
 	/// However, this function does not require any storage lookup and therefore uses less weight.
 	fn caller_is_origin(&self) -> bool;
 
+	/// Check if the caller of the current contract is a contract itself.
+	///
+	/// This is the case for contracts that are being instantiated as well, even though they are
+	/// not yet in storage.
+	fn caller_is_contract(&self) -> bool;
+
 	/// Returns a reference to the account id of the current contract.
 	fn address(&self) -> &AccountIdOf<Self::T>;
 
@@ -249,12 +366,22 @@ pub trait Ext: sealing::Sealed {
 	fn minimum_balance(&self) -> BalanceOf<Self::T>;
 
 	/// Returns a random number for the current block with the given subject.
-	fn random(&self, subject: &[u8]) -> (SeedOf<Self::T>, BlockNumberOf<Self::T>);
+	///
+	/// Every call is counted in [`RandomnessCallCount`](crate::RandomnessCallCount) because the
+	/// randomness source exposed to contracts is deprecated.
+	fn random(&mut self, subject: &[u8]) -> (SeedOf<Self::T>, BlockNumberOf<Self::T>);
 
 	/// Deposit an event with the given topics.
 	///
 	/// There should not be any duplicates in `topics`.
-	fn deposit_event(&mut self, topics: Vec<TopicOf<Self::T>>, data: Vec<u8>);
+	///
+	/// Fails with [`Error::EventBudgetExhausted`] if the events deposited by this call stack
+	/// would exceed [`Limits::event_bytes_per_call`](crate::Limits).
+	fn deposit_event(
+		&mut self,
+		topics: Vec<TopicOf<Self::T>>,
+		data: Vec<u8>,
+	) -> Result<(), DispatchError>;
 
 	/// Returns the current block number.
 	fn block_number(&self) -> BlockNumberOf<Self::T>;
@@ -284,6 +411,18 @@ pub trait Ext: sealing::Sealed {
 	/// Call some dispatchable and return the result.
 	fn call_runtime(&self, call: <Self::T as Config>::RuntimeCall) -> DispatchResultWithPostInfo;
 
+	/// Execute an encoded XCM message through [`Config::Xcm`] with the current contract as origin.
+	fn xcm_execute(&self, message: &[u8], weight_limit: Weight) -> DispatchResultWithPostInfo;
+
+	/// Send an encoded XCM message to the encoded `dest` through [`Config::Xcm`] with the current
+	/// contract as origin.
+	fn xcm_send(
+		&self,
+		dest: &[u8],
+		message: &[u8],
+		weight_limit: Weight,
+	) -> DispatchResultWithPostInfo;
+
 	/// Recovers ECDSA compressed public key based on signature and message hash.
 	fn ecdsa_recover(&self, signature: &[u8; 65], message_hash: &[u8; 32]) -> Result<[u8; 33], ()>;
 
@@ -297,6 +436,12 @@ pub trait Ext: sealing::Sealed {
 	/// Sets new code hash for existing contract.
 	fn set_code_hash(&mut self, hash: CodeHash<Self::T>) -> Result<(), DispatchError>;
 
+	/// Sets new code hash for existing contract and runs the `migrate` export of the new code.
+	///
+	/// The migration is executed as a call of the contract into itself using all the remaining
+	/// gas. If it fails or reverts, the code hash is switched back to the previous one.
+	fn set_code_hash_and_migrate(&mut self, hash: CodeHash<Self::T>) -> ExecResult;
+
 	/// Returns the number of times the currently executing contract exists on the call stack in
 	/// addition to the calling instance. A value of 0 means no reentrancy.
 	fn reentrance_count(&self) -> u32;
@@ -306,6 +451,12 @@ pub trait Ext: sealing::Sealed {
 	/// A value of 0 means it does not exist on the call stack.
 	fn account_reentrance_count(&self, account_id: &AccountIdOf<Self::T>) -> u32;
 
+	/// Returns how many more nested frames can be pushed onto the call stack.
+	///
+	/// A value of 0 means that any further call or instantiation fails with
+	/// [`Error::MaxCallDepthReached`].
+	fn call_stack_remaining(&self) -> u32;
+
 	/// Returns a nonce that is incremented for every instantiated contract.
 	fn nonce(&mut self) -> u64;
 }
@@ -317,6 +468,8 @@ pub enum ExportedFunction {
 	Constructor,
 	/// The function which is executed when a contract is called.
 	Call,
+	/// The function which is executed after a contract changed its code with a migration.
+	Migrate,
 }
 
 /// A trait that represents something that can be executed.
@@ -412,6 +565,24 @@ pub struct Stack<'a, T: Config, E> {
 	debug_message: Option<&'a mut DebugBufferVec<T>>,
 	/// The determinism requirement of this call stack.
 	determinism: Determinism,
+	/// Counters of the storage accesses of all frames. Only collected when requested.
+	storage_stats: Option<&'a mut StorageAccessStats>,
+	/// Whether block dependent inputs are replaced by canonical values.
+	///
+	/// Only ever set for dry-runs, see [`Pallet::bare_call`].
+	deterministic_sandbox: bool,
+	/// The number of contracts successfully instantiated by contracts of this call stack so far.
+	///
+	/// Limited by [`Limits::max_instantiations_per_call`](crate::Limits).
+	instantiations: u32,
+	/// The number of bytes deposited as events by contracts of this call stack so far.
+	///
+	/// Limited by [`Limits::event_bytes_per_call`](crate::Limits).
+	event_bytes: u32,
+	/// The number of calls to `seal_random` by contracts of this call stack so far.
+	///
+	/// It is added to [`RandomnessCallsInBlock`] when the call stack finishes executing.
+	randomness_calls: u64,
 	/// No executable is held by the struct but influences its behaviour.
 	_phantom: PhantomData<E>,
 }
@@ -442,6 +613,8 @@ pub struct Frame<T: Config> {
 	allows_reentry: bool,
 	/// The caller of the currently executing frame which was spawned by `delegate_call`.
 	delegate_caller: Option<T::AccountId>,
+	/// Whether the code executed by this frame is deterministic.
+	deterministic: bool,
 }
 
 /// Used in a delegate call frame arguments in order to override the executable and caller.
@@ -466,6 +639,12 @@ enum FrameArgs<'a, T: Config, E> {
 		/// account which called the caller contract
 		delegated_call: Option<DelegatedCall<T, E>>,
 	},
+	Migrate {
+		/// The account id of the contract whose code has been replaced.
+		dest: T::AccountId,
+		/// The contract info including the new code hash.
+		contract: ContractInfo<T>,
+	},
 	Instantiate {
 		/// The contract or signed origin which instantiates the new contract.
 		sender: T::AccountId,
@@ -606,6 +785,7 @@ where
 	///
 	/// `debug_message` should only ever be set to `Some` when executing as an RPC because
 	/// it adds allocations and could be abused to drive the runtime into an OOM panic.
+	/// The same holds for `storage_stats` which is only of interest to RPC clients.
 	///
 	/// # Return Value
 	///
@@ -620,6 +800,8 @@ where
 		input_data: Vec<u8>,
 		debug_message: Option<&'a mut DebugBufferVec<T>>,
 		determinism: Determinism,
+		storage_stats: Option<&'a mut StorageAccessStats>,
+		deterministic_sandbox: bool,
 	) -> Result<ExecReturnValue, ExecError> {
 		let (mut stack, executable) = Self::new(
 			FrameArgs::Call { dest, cached_info: None, delegated_call: None },
@@ -630,6 +812,8 @@ where
 			value,
 			debug_message,
 			determinism,
+			storage_stats,
+			deterministic_sandbox,
 		)?;
 		stack.run(executable, input_data)
 	}
@@ -654,6 +838,7 @@ where
 		input_data: Vec<u8>,
 		salt: &[u8],
 		debug_message: Option<&'a mut DebugBufferVec<T>>,
+		deterministic_sandbox: bool,
 	) -> Result<(T::AccountId, ExecReturnValue), ExecError> {
 		let (mut stack, executable) = Self::new(
 			FrameArgs::Instantiate {
@@ -670,6 +855,8 @@ where
 			value,
 			debug_message,
 			Determinism::Deterministic,
+			None,
+			deterministic_sandbox,
 		)?;
 		let account_id = stack.top_frame().account_id.clone();
 		stack.run(executable, input_data).map(|ret| (account_id, ret))
@@ -685,6 +872,8 @@ where
 		value: BalanceOf<T>,
 		debug_message: Option<&'a mut DebugBufferVec<T>>,
 		determinism: Determinism,
+		storage_stats: Option<&'a mut StorageAccessStats>,
+		deterministic_sandbox: bool,
 	) -> Result<(Self, E), ExecError> {
 		let (first_frame, executable, nonce) = Self::new_frame(
 			args,
@@ -694,19 +883,30 @@ where
 			Weight::zero(),
 			schedule,
 			determinism,
+			None,
 		)?;
+		let (timestamp, block_number) = if deterministic_sandbox {
+			(Zero::zero(), Zero::zero())
+		} else {
+			(T::Time::now(), <frame_system::Pallet<T>>::block_number())
+		};
 		let stack = Self {
 			origin,
 			schedule,
 			gas_meter,
 			storage_meter,
-			timestamp: T::Time::now(),
-			block_number: <frame_system::Pallet<T>>::block_number(),
+			timestamp,
+			block_number,
 			nonce,
 			first_frame,
 			frames: Default::default(),
 			debug_message,
 			determinism,
+			storage_stats,
+			deterministic_sandbox,
+			instantiations: 0,
+			event_bytes: 0,
+			randomness_calls: 0,
 			_phantom: Default::default(),
 		};
 
@@ -717,6 +917,10 @@ where
 	///
 	/// This does not take `self` because when constructing the first frame `self` is
 	/// not initialized, yet.
+	///
+	/// This is the single place where the [`Determinism`] of the executed code is enforced.
+	/// `caller_deterministic` is `None` for the first frame of a call stack. Otherwise it tells
+	/// whether the code executed by the calling frame is deterministic.
 	fn new_frame<S: storage::meter::State>(
 		frame_args: FrameArgs<T, E>,
 		value_transferred: BalanceOf<T>,
@@ -725,6 +929,7 @@ where
 		gas_limit: Weight,
 		schedule: &Schedule<T>,
 		determinism: Determinism,
+		caller_deterministic: Option<bool>,
 	) -> Result<(Frame<T>, E, Option<u64>), ExecError> {
 		let (account_id, contract_info, executable, delegate_caller, entry_point, nonce) =
 			match frame_args {
@@ -744,7 +949,14 @@ where
 
 					(dest, contract, executable, delegate_caller, ExportedFunction::Call, None)
 				},
+				FrameArgs::Migrate { dest, contract } => {
+					let executable = E::from_storage(contract.code_hash, schedule, gas_meter)?;
+					(dest, contract, executable, None, ExportedFunction::Migrate, None)
+				},
 				FrameArgs::Instantiate { sender, nonce, executable, salt, input_data } => {
+					if !T::InstantiateFilter::contains(executable.code_hash()) {
+						return Err(Error::<T>::CodeNotAllowed.into())
+					}
 					let account_id = Contracts::<T>::contract_address(
 						&sender,
 						executable.code_hash(),
@@ -765,13 +977,25 @@ where
 				},
 			};
 
-		// `AllowIndeterminism` will only be ever set in case of off-chain execution.
-		// Instantiations are never allowed even when executing off-chain.
-		if !(executable.is_deterministic() ||
-			(matches!(determinism, Determinism::AllowIndeterminism) &&
-				matches!(entry_point, ExportedFunction::Call)))
-		{
-			return Err(Error::<T>::Indeterministic.into())
+		// Indeterministic code can only ever run as the top frame of a call that explicitly
+		// allows it, or be delegated to from indeterministic code. `AllowIndeterminism` will only
+		// be ever set in case of off-chain execution.
+		let deterministic = executable.is_deterministic();
+		if !deterministic {
+			let error = match (entry_point, caller_deterministic) {
+				// Instantiations are never allowed even when executing off-chain.
+				(ExportedFunction::Constructor, _) =>
+					Some(Error::<T>::IndeterministicInstantiation),
+				(_, Some(_)) if delegate_caller.is_none() =>
+					Some(Error::<T>::IndeterministicNestedCall),
+				(_, Some(true)) => Some(Error::<T>::IndeterministicDelegateCall),
+				_ if !matches!(determinism, Determinism::AllowIndeterminism) =>
+					Some(Error::<T>::Indeterministic),
+				_ => None,
+			};
+			if let Some(error) = error {
+				return Err(error.into())
+			}
 		}
 
 		let frame = Frame {
@@ -783,6 +1007,7 @@ where
 			nested_gas: gas_meter.nested(gas_limit)?,
 			nested_storage: storage_meter.nested(),
 			allows_reentry: true,
+			deterministic,
 		};
 
 		Ok((frame, executable, nonce))
@@ -804,13 +1029,16 @@ where
 		// We do not store on instantiate because we do not allow to call into a contract
 		// from its own constructor.
 		let frame = self.top_frame();
-		if let (CachedContract::Cached(contract), ExportedFunction::Call) =
-			(&frame.contract_info, frame.entry_point)
+		if let (
+			CachedContract::Cached(contract),
+			ExportedFunction::Call | ExportedFunction::Migrate,
+		) = (&frame.contract_info, frame.entry_point)
 		{
 			<ContractInfoOf<T>>::insert(frame.account_id.clone(), contract.clone());
 		}
 
 		let frame = top_frame_mut!(self);
+		let caller_deterministic = frame.deterministic;
 		let nested_gas = &mut frame.nested_gas;
 		let nested_storage = &mut frame.nested_storage;
 		let (frame, executable, _) = Self::new_frame(
@@ -821,6 +1049,7 @@ where
 			gas_limit,
 			self.schedule,
 			self.determinism,
+			Some(caller_deterministic),
 		)?;
 		self.frames.push(frame);
 		Ok(executable)
@@ -834,6 +1063,11 @@ where
 		let entry_point = frame.entry_point;
 		let delegated_code_hash =
 			if frame.delegate_caller.is_some() { Some(*executable.code_hash()) } else { None };
+		let selector = if T::EmitCallSelectors::get() {
+			input_data.get(..4).and_then(|selector| selector.try_into().ok())
+		} else {
+			None
+		};
 		let do_transaction = || {
 			// We need to charge the storage deposit before the initial transfer so that
 			// it can create the account in case the initial transfer is < ed.
@@ -896,9 +1130,15 @@ where
 					let caller = self.caller();
 					Contracts::<T>::deposit_event(
 						vec![T::Hashing::hash_of(caller), T::Hashing::hash_of(account_id)],
-						Event::Called { caller: caller.clone(), contract: account_id.clone() },
+						Event::Called {
+							caller: caller.clone(),
+							contract: account_id.clone(),
+							selector,
+						},
 					);
 				},
+				// The code update is already announced by `ContractCodeUpdated`.
+				(ExportedFunction::Migrate, _) => (),
 			}
 
 			Ok(output)
@@ -928,7 +1168,30 @@ where
 			Err(error) => (false, Err(error.into())),
 		};
 
+		let trap = match &output {
+			Err(ExecError { error, .. }) if T::EmitTrapEvents::get() =>
+				Some((self.top_frame().account_id.clone(), TrapCategory::from_error::<T>(error))),
+			_ => None,
+		};
+		let is_first_frame = self.frames.is_empty();
+
 		self.pop_frame(success);
+
+		// The failed frame might have run out of gas. Hence the event is paid for by its caller,
+		// which is the origin in case of the first frame.
+		if let Some((contract, reason)) = trap {
+			let topics = vec![T::Hashing::hash_of(&contract)];
+			let event = Event::ContractTrapped { contract, reason };
+			let gas_meter = if is_first_frame {
+				&mut *self.gas_meter
+			} else {
+				&mut self.top_frame_mut().nested_gas
+			};
+			if gas_meter.charge(TrapEventToken(event.encoded_size() as u32)).is_ok() {
+				Contracts::<T>::deposit_event(topics, event);
+			}
+		}
+
 		output
 	}
 
@@ -1013,6 +1276,11 @@ where
 			if let Some(nonce) = self.nonce {
 				<Nonce<T>>::set(nonce);
 			}
+			if self.randomness_calls > 0 {
+				<RandomnessCallsInBlock<T>>::mutate(|calls| {
+					*calls = calls.saturating_add(self.randomness_calls)
+				});
+			}
 		}
 	}
 
@@ -1075,12 +1343,56 @@ where
 		!self.frames().any(|f| &f.account_id == id && !f.allows_reentry)
 	}
 
+	/// Fails if a write with `outcome` created a new item in the storage of the current contract
+	/// which took it over [`Limits::storage_items`](crate::Limits::storage_items).
+	///
+	/// `is_set` is `false` for removals, which never create an item. The write is not undone
+	/// here as the error traps the contract, which reverts the frame.
+	fn check_storage_items(&mut self, is_set: bool, outcome: &WriteOutcome) -> DispatchResult {
+		let limit = match (is_set, outcome, self.schedule.limits.storage_items) {
+			(true, WriteOutcome::New, Some(limit)) => limit,
+			_ => return Ok(()),
+		};
+		let frame = self.top_frame_mut();
+		let account_id = frame.account_id.clone();
+		let stored = frame.contract_info.get(&account_id).storage_items;
+		// The contract might be on the stack multiple times due to reentrancy or delegate calls.
+		// The changes of all these frames are not yet absorbed into the contract info.
+		let (added, removed) = self.frames().filter(|f| f.account_id == account_id).fold(
+			(0u32, 0u32),
+			|(added, removed), f| {
+				let (f_added, f_removed) = f.nested_storage.storage_items_diff();
+				(added.saturating_add(f_added), removed.saturating_add(f_removed))
+			},
+		);
+		if stored.saturating_add(added).saturating_sub(removed) > limit {
+			return Err(Error::<T>::StorageItemLimitExceeded.into())
+		}
+		Ok(())
+	}
+
 	/// Increments and returns the next nonce. Pulls it from storage if it isn't in cache.
 	fn next_nonce(&mut self) -> u64 {
 		let next = self.nonce().wrapping_add(1);
 		self.nonce = Some(next);
 		next
 	}
+
+	/// Count a read of contract storage which returned a value of `len` bytes.
+	fn record_read(&mut self, len: Option<usize>) {
+		if let Some(stats) = &mut self.storage_stats {
+			stats.reads.saturating_inc();
+			stats.bytes_read.saturating_accrue(len.unwrap_or(0).saturated_into());
+		}
+	}
+
+	/// Count a write of `len` bytes to contract storage.
+	fn record_write(&mut self, len: usize) {
+		if let Some(stats) = &mut self.storage_stats {
+			stats.writes.saturating_inc();
+			stats.bytes_written.saturating_accrue(len.saturated_into());
+		}
+	}
 }
 
 impl<'a, T, E> Ext for Stack<'a, T, E>
@@ -1134,6 +1446,18 @@ where
 		result
 	}
 
+	fn transfer_to_account(
+		&mut self,
+		to: &T::AccountId,
+		value: BalanceOf<T>,
+	) -> Result<bool, DispatchError> {
+		if <ContractInfoOf<T>>::contains_key(to) || self.frames().any(|f| f.account_id == *to) {
+			return Ok(false)
+		}
+		Self::transfer(ExistenceRequirement::KeepAlive, &self.top_frame().account_id, to, value)?;
+		Ok(true)
+	}
+
 	fn delegate_call(
 		&mut self,
 		code_hash: CodeHash<Self::T>,
@@ -1164,6 +1488,9 @@ where
 		input_data: Vec<u8>,
 		salt: &[u8],
 	) -> Result<(AccountIdOf<T>, ExecReturnValue), ExecError> {
+		if self.instantiations >= self.schedule.limits.max_instantiations_per_call {
+			return Err(Error::<T>::TooManyInstantiations.into())
+		}
 		let executable = E::from_storage(code_hash, self.schedule, self.gas_meter())?;
 		let nonce = self.next_nonce();
 		let executable = self.push_frame(
@@ -1178,7 +1505,12 @@ where
 			gas_limit,
 		)?;
 		let account_id = self.top_frame().account_id.clone();
-		self.run(executable, input_data).map(|ret| (account_id, ret))
+		let ret = self.run(executable, input_data)?;
+		// Only an instantiation which created a contract counts against the limit.
+		if !ret.did_revert() {
+			self.instantiations.saturating_inc();
+		}
+		Ok((account_id, ret))
 	}
 
 	fn terminate(&mut self, beneficiary: &AccountIdOf<Self::T>) -> Result<(), DispatchError> {
@@ -1187,14 +1519,31 @@ where
 		}
 		let frame = self.top_frame_mut();
 		let info = frame.terminate();
-		frame.nested_storage.terminate(&info);
+		frame.nested_storage.terminate(&frame.account_id, &info);
 		Storage::<T>::queue_trie_for_deletion(&info)?;
-		<Stack<'a, T, E>>::transfer(
-			ExistenceRequirement::AllowDeath,
-			&frame.account_id,
-			beneficiary,
-			T::Currency::free_balance(&frame.account_id),
-		)?;
+		let value = T::Currency::free_balance(&frame.account_id);
+		// A transfer to a new account that is below the existential deposit would fail.
+		let outcome = if value < T::Currency::minimum_balance() &&
+			!frame_system::Pallet::<T>::account_exists(beneficiary)
+		{
+			let dust = T::Currency::withdraw(
+				&frame.account_id,
+				value,
+				WithdrawReasons::TRANSFER,
+				ExistenceRequirement::AllowDeath,
+			)
+			.map_err(|_| Error::<T>::TransferFailed)?;
+			T::TerminationDustHandler::on_unbalanced(dust);
+			TerminationOutcome::Dust(value)
+		} else {
+			<Stack<'a, T, E>>::transfer(
+				ExistenceRequirement::AllowDeath,
+				&frame.account_id,
+				beneficiary,
+				value,
+			)?;
+			TerminationOutcome::Transferred(value)
+		};
 		ContractInfoOf::<T>::remove(&frame.account_id);
 		E::remove_user(info.code_hash);
 		Contracts::<T>::deposit_event(
@@ -1202,6 +1551,7 @@ where
 			Event::Terminated {
 				contract: frame.account_id.clone(),
 				beneficiary: beneficiary.clone(),
+				outcome,
 			},
 		);
 		Ok(())
@@ -1212,19 +1562,27 @@ where
 	}
 
 	fn get_storage(&mut self, key: &FixSizedKey) -> Option<Vec<u8>> {
-		Storage::<T>::read(&self.top_frame_mut().contract_info().trie_id, key)
+		let value = Storage::<T>::read(&self.top_frame_mut().contract_info().trie_id, key);
+		self.record_read(value.as_ref().map(Vec::len));
+		value
 	}
 
 	fn get_storage_transparent(&mut self, key: &VarSizedKey<T>) -> Option<Vec<u8>> {
-		Storage::<T>::read(&self.top_frame_mut().contract_info().trie_id, key)
+		let value = Storage::<T>::read(&self.top_frame_mut().contract_info().trie_id, key);
+		self.record_read(value.as_ref().map(Vec::len));
+		value
 	}
 
 	fn get_storage_size(&mut self, key: &FixSizedKey) -> Option<u32> {
-		Storage::<T>::size(&self.top_frame_mut().contract_info().trie_id, key)
+		let size = Storage::<T>::size(&self.top_frame_mut().contract_info().trie_id, key);
+		self.record_read(None);
+		size
 	}
 
 	fn get_storage_size_transparent(&mut self, key: &VarSizedKey<T>) -> Option<u32> {
-		Storage::<T>::size(&self.top_frame_mut().contract_info().trie_id, key)
+		let size = Storage::<T>::size(&self.top_frame_mut().contract_info().trie_id, key);
+		self.record_read(None);
+		size
 	}
 
 	fn set_storage(
@@ -1233,14 +1591,23 @@ where
 		value: Option<Vec<u8>>,
 		take_old: bool,
 	) -> Result<WriteOutcome, DispatchError> {
+		let len = value.as_ref().map_or(0, Vec::len);
+		let is_set = value.is_some();
 		let frame = self.top_frame_mut();
-		Storage::<T>::write(
+		let outcome = Storage::<T>::write(
 			&frame.contract_info.get(&frame.account_id).trie_id,
 			key,
 			value,
 			Some(&mut frame.nested_storage),
 			take_old,
-		)
+		)?;
+		self.record_write(len);
+		self.check_storage_items(is_set, &outcome)?;
+		// The old value was read in order to return it.
+		if take_old {
+			self.record_read(Some(outcome.old_len() as usize));
+		}
+		Ok(outcome)
 	}
 
 	fn set_storage_transparent(
@@ -1249,14 +1616,39 @@ where
 		value: Option<Vec<u8>>,
 		take_old: bool,
 	) -> Result<WriteOutcome, DispatchError> {
+		let len = value.as_ref().map_or(0, Vec::len);
+		let is_set = value.is_some();
 		let frame = self.top_frame_mut();
-		Storage::<T>::write(
+		let outcome = Storage::<T>::write(
 			&frame.contract_info.get(&frame.account_id).trie_id,
 			key,
 			value,
 			Some(&mut frame.nested_storage),
 			take_old,
-		)
+		)?;
+		self.record_write(len);
+		self.check_storage_items(is_set, &outcome)?;
+		// The old value was read in order to return it.
+		if take_old {
+			self.record_read(Some(outcome.old_len() as usize));
+		}
+		Ok(outcome)
+	}
+
+	fn next_storage_key(&mut self, cursor: &mut Vec<u8>) -> StorageIterStep<T> {
+		self.record_read(None);
+		let next = Storage::<T>::next_key(&self.top_frame_mut().contract_info().trie_id, cursor);
+		match next {
+			Some(next) => {
+				let step = match unhash_key::<T>(&next) {
+					Some(key) => StorageIterStep::Key(key),
+					None => StorageIterStep::Skipped,
+				};
+				*cursor = next;
+				step
+			},
+			None => StorageIterStep::End,
+		}
 	}
 
 	fn address(&self) -> &T::AccountId {
@@ -1287,6 +1679,12 @@ where
 		self.caller() == &self.origin
 	}
 
+	fn caller_is_contract(&self) -> bool {
+		let caller = self.caller();
+		// contracts on the stack might not be in storage yet if they are being instantiated.
+		self.frames().skip(1).any(|f| &f.account_id == caller) || self.is_contract(caller)
+	}
+
 	fn balance(&self) -> BalanceOf<T> {
 		T::Currency::free_balance(&self.top_frame().account_id)
 	}
@@ -1295,7 +1693,17 @@ where
 		self.top_frame().value_transferred
 	}
 
-	fn random(&self, subject: &[u8]) -> (SeedOf<T>, BlockNumberOf<T>) {
+	fn random(&mut self, subject: &[u8]) -> (SeedOf<T>, BlockNumberOf<T>) {
+		// warning once per call stack is enough.
+		if self.randomness_calls == 0 {
+			self.append_debug_buffer(
+				"WARNING: `seal_random` is deprecated and will be removed; do not rely on it.\n",
+			);
+		}
+		self.randomness_calls.saturating_inc();
+		if self.deterministic_sandbox {
+			return (T::Hashing::hash(subject), Zero::zero())
+		}
 		T::Randomness::random(subject)
 	}
 
@@ -1307,11 +1715,21 @@ where
 		T::Currency::minimum_balance()
 	}
 
-	fn deposit_event(&mut self, topics: Vec<T::Hash>, data: Vec<u8>) {
+	fn deposit_event(&mut self, topics: Vec<T::Hash>, data: Vec<u8>) -> Result<(), DispatchError> {
+		let len = (topics.len() * sp_std::mem::size_of::<T::Hash>()).saturating_add(data.len());
+		let event_bytes = self.event_bytes.saturating_add(len as u32);
+		if event_bytes > self.schedule.limits.event_bytes_per_call {
+			return Err(Error::<T>::EventBudgetExhausted.into())
+		}
+		self.event_bytes = event_bytes;
+		if let Some(stats) = &mut self.storage_stats {
+			stats.events.saturating_inc();
+		}
 		Contracts::<Self::T>::deposit_event(
 			topics,
 			Event::ContractEmitted { contract: self.top_frame().account_id.clone(), data },
 		);
+		Ok(())
 	}
 
 	fn block_number(&self) -> T::BlockNumber {
@@ -1371,6 +1789,21 @@ where
 		call.dispatch(origin)
 	}
 
+	fn xcm_execute(&self, message: &[u8], weight_limit: Weight) -> DispatchResultWithPostInfo {
+		let origin = RawOrigin::Signed(self.address().clone()).into();
+		T::Xcm::execute(origin, message, weight_limit)
+	}
+
+	fn xcm_send(
+		&self,
+		dest: &[u8],
+		message: &[u8],
+		weight_limit: Weight,
+	) -> DispatchResultWithPostInfo {
+		let origin = RawOrigin::Signed(self.address().clone()).into();
+		T::Xcm::send(origin, dest, message, weight_limit)
+	}
+
 	fn ecdsa_recover(&self, signature: &[u8; 65], message_hash: &[u8; 32]) -> Result<[u8; 33], ()> {
 		secp256k1_ecdsa_recover_compressed(signature, message_hash).map_err(|_| ())
 	}
@@ -1404,6 +1837,39 @@ where
 		Ok(())
 	}
 
+	fn set_code_hash_and_migrate(&mut self, hash: CodeHash<Self::T>) -> ExecResult {
+		let prev_contract = top_frame_mut!(self).contract_info().clone();
+
+		// The code hash swap and the migration are reverted together.
+		let transaction_outcome =
+			with_transaction(|| -> TransactionOutcome<Result<_, DispatchError>> {
+				let output = self.set_code_hash(hash).map_err(ExecError::from).and_then(|_| {
+					let frame = top_frame_mut!(self);
+					let dest = frame.account_id.clone();
+					let contract = frame.contract_info().clone();
+					let executable = self.push_frame(
+						FrameArgs::Migrate { dest, contract },
+						Zero::zero(),
+						Weight::zero(),
+					)?;
+					self.run(executable, Vec::new())
+				});
+				match &output {
+					Ok(result) if !result.did_revert() => TransactionOutcome::Commit(Ok(output)),
+					_ => TransactionOutcome::Rollback(Ok(output)),
+				}
+			});
+
+		let output = match transaction_outcome {
+			Ok(output) => output,
+			Err(error) => Err(error.into()),
+		};
+		if !matches!(&output, Ok(result) if !result.did_revert()) {
+			*top_frame_mut!(self).contract_info() = prev_contract;
+		}
+		output
+	}
+
 	fn reentrance_count(&self) -> u32 {
 		let id: &AccountIdOf<Self::T> = &self.top_frame().account_id;
 		self.account_reentrance_count(id).saturating_sub(1)
@@ -1415,6 +1881,10 @@ where
 			.count() as u32
 	}
 
+	fn call_stack_remaining(&self) -> u32 {
+		T::CallStack::size().saturating_sub(self.frames.len()) as u32
+	}
+
 	fn nonce(&mut self) -> u64 {
 		if let Some(current) = self.nonce {
 			current
@@ -1455,7 +1925,7 @@ mod tests {
 		tests::{
 			test_utils::{get_balance, hash, place_contract, set_balance},
 			ExtBuilder, RuntimeCall, RuntimeEvent as MetaEvent, Test, TestFilter, ALICE, BOB,
-			CHARLIE, GAS_LIMIT,
+			CHARLIE, DJANGO, GAS_LIMIT,
 		},
 		Error,
 	};
@@ -1637,6 +2107,8 @@ mod tests {
 					vec![],
 					None,
 					Determinism::Deterministic,
+					None,
+					false,
 				),
 				Ok(_)
 			);
@@ -1691,6 +2163,8 @@ mod tests {
 				vec![],
 				None,
 				Determinism::Deterministic,
+				None,
+				false,
 			)
 			.unwrap();
 
@@ -1733,6 +2207,8 @@ mod tests {
 				vec![],
 				None,
 				Determinism::Deterministic,
+				None,
+				false,
 			)
 			.unwrap();
 
@@ -1769,6 +2245,8 @@ mod tests {
 				vec![],
 				None,
 				Determinism::Deterministic,
+				None,
+				false,
 			)
 			.unwrap();
 
@@ -1821,6 +2299,8 @@ mod tests {
 				vec![],
 				None,
 				Determinism::Deterministic,
+				None,
+				false,
 			);
 
 			let output = result.unwrap();
@@ -1854,6 +2334,8 @@ mod tests {
 				vec![],
 				None,
 				Determinism::Deterministic,
+				None,
+				false,
 			);
 
 			let output = result.unwrap();
@@ -1885,6 +2367,8 @@ mod tests {
 				vec![1, 2, 3, 4],
 				None,
 				Determinism::Deterministic,
+				None,
+				false,
 			);
 			assert_matches!(result, Ok(_));
 		});
@@ -1918,6 +2402,7 @@ mod tests {
 				vec![1, 2, 3, 4],
 				&[],
 				None,
+				false,
 			);
 			assert_matches!(result, Ok(_));
 		});
@@ -1940,6 +2425,7 @@ mod tests {
 					// We are first time here, it means we just reached bottom.
 					// Verify that we've got proper error and set `reached_bottom`.
 					assert_eq!(r, Err(Error::<Test>::MaxCallDepthReached.into()));
+					assert_eq!(ctx.ext.call_stack_remaining(), 0);
 					*reached_bottom = true;
 				} else {
 					// We just unwinding stack here.
@@ -1966,6 +2452,8 @@ mod tests {
 				vec![],
 				None,
 				Determinism::Deterministic,
+				None,
+				false,
 			);
 
 			assert_matches!(result, Ok(_));
@@ -2012,6 +2500,8 @@ mod tests {
 				vec![],
 				None,
 				Determinism::Deterministic,
+				None,
+				false,
 			);
 
 			assert_matches!(result, Ok(_));
@@ -2046,11 +2536,52 @@ mod tests {
 				vec![],
 				None,
 				Determinism::Deterministic,
+				None,
+				false,
 			);
 			assert_matches!(result, Ok(_));
 		});
 	}
 
+	#[test]
+	fn transfer_to_account_skips_contracts() {
+		let bob_ch = MockLoader::insert(Call, |ctx, _| {
+			// CHARLIE is a contract and BOB is on the call stack: Nothing is transferred.
+			assert_eq!(ctx.ext.transfer_to_account(&CHARLIE, 10), Ok(false));
+			assert_eq!(ctx.ext.transfer_to_account(&BOB, 10), Ok(false));
+			// DJANGO is a plain account.
+			assert_eq!(ctx.ext.transfer_to_account(&DJANGO, 10), Ok(true));
+			exec_success()
+		});
+
+		ExtBuilder::default().build().execute_with(|| {
+			let schedule = <Test as Config>::Schedule::get();
+			place_contract(&BOB, bob_ch);
+			place_contract(&CHARLIE, bob_ch);
+			set_balance(&BOB, 100);
+			set_balance(&DJANGO, 100);
+
+			let mut storage_meter = storage::meter::Meter::new(&ALICE, Some(0), 0).unwrap();
+			let result = MockStack::run_call(
+				ALICE,
+				BOB,
+				&mut GasMeter::<Test>::new(GAS_LIMIT),
+				&mut storage_meter,
+				&schedule,
+				0,
+				vec![],
+				None,
+				Determinism::Deterministic,
+				None,
+				false,
+			);
+			assert_matches!(result, Ok(_));
+			assert_eq!(get_balance(&BOB), 90);
+			assert_eq!(get_balance(&CHARLIE), 0);
+			assert_eq!(get_balance(&DJANGO), 110);
+		});
+	}
+
 	#[test]
 	fn code_hash_returns_proper_values() {
 		let code_bob = MockLoader::insert(Call, |ctx, _| {
@@ -2076,6 +2607,8 @@ mod tests {
 				vec![0],
 				None,
 				Determinism::Deterministic,
+				None,
+				false,
 			);
 			assert_matches!(result, Ok(_));
 		});
@@ -2104,6 +2637,8 @@ mod tests {
 				vec![0],
 				None,
 				Determinism::Deterministic,
+				None,
+				false,
 			);
 			assert_matches!(result, Ok(_));
 		});
@@ -2140,6 +2675,8 @@ mod tests {
 				vec![0],
 				None,
 				Determinism::Deterministic,
+				None,
+				false,
 			);
 			assert_matches!(result, Ok(_));
 		});
@@ -2176,6 +2713,8 @@ mod tests {
 				vec![],
 				None,
 				Determinism::Deterministic,
+				None,
+				false,
 			);
 
 			assert_matches!(result, Ok(_));
@@ -2204,6 +2743,7 @@ mod tests {
 					vec![],
 					&[],
 					None,
+					false,
 				),
 				Err(_)
 			);
@@ -2237,6 +2777,7 @@ mod tests {
 					vec![],
 					&[],
 					None,
+					false,
 				),
 				Ok((address, ref output)) if output.data == vec![80, 65, 83, 83] => address
 			);
@@ -2281,6 +2822,7 @@ mod tests {
 					vec![],
 					&[],
 					None,
+					false,
 				),
 				Ok((address, ref output)) if output.data == vec![70, 65, 73, 76] => address
 			);
@@ -2335,6 +2877,8 @@ mod tests {
 					vec![],
 					None,
 					Determinism::Deterministic,
+					None,
+					false,
 				),
 				Ok(_)
 			);
@@ -2352,7 +2896,7 @@ mod tests {
 				&events(),
 				&[
 					Event::Instantiated { deployer: BOB, contract: instantiated_contract_address },
-					Event::Called { caller: ALICE, contract: BOB },
+					Event::Called { caller: ALICE, contract: BOB, selector: None },
 				]
 			);
 		});
@@ -2400,13 +2944,68 @@ mod tests {
 					vec![],
 					None,
 					Determinism::Deterministic,
+					None,
+					false,
 				),
 				Ok(_)
 			);
 
 			// The contract wasn't instantiated so we don't expect to see an instantiation
 			// event here.
-			assert_eq!(&events(), &[Event::Called { caller: ALICE, contract: BOB },]);
+			assert_eq!(
+				&events(),
+				&[Event::Called { caller: ALICE, contract: BOB, selector: None },]
+			);
+		});
+	}
+
+	#[test]
+	fn only_successful_instantiations_are_limited() {
+		let trap_ch = MockLoader::insert(Constructor, |_, _| Err("It's a trap!".into()));
+		let dummy_ch = MockLoader::insert(Constructor, |_, _| exec_success());
+		let missing_ch = <Test as frame_system::Config>::Hash::from_low_u64_be(u64::MAX);
+		let instantiator_ch = MockLoader::insert(Call, move |ctx, _| {
+			let mut instantiate = |code_hash| {
+				ctx.ext
+					.instantiate(
+						Weight::zero(),
+						code_hash,
+						<Test as Config>::Currency::minimum_balance(),
+						vec![],
+						&[],
+					)
+					.map(|_| ())
+					.map_err(|e| e.error)
+			};
+			// neither missing code nor a trapping constructor count against the limit.
+			assert_eq!(instantiate(missing_ch), Err(Error::<Test>::CodeNotFound.into()));
+			assert_eq!(instantiate(trap_ch), Err(DispatchError::Other("It's a trap!")));
+			assert_eq!(instantiate(dummy_ch), Ok(()));
+			assert_eq!(instantiate(dummy_ch), Err(Error::<Test>::TooManyInstantiations.into()));
+			exec_success()
+		});
+
+		ExtBuilder::default().existential_deposit(15).build().execute_with(|| {
+			let mut schedule = <Test as Config>::Schedule::get();
+			schedule.limits.max_instantiations_per_call = 1;
+			set_balance(&ALICE, 1000);
+			set_balance(&BOB, 100);
+			place_contract(&BOB, instantiator_ch);
+			let mut storage_meter = storage::meter::Meter::new(&ALICE, Some(100), 0).unwrap();
+
+			assert_ok!(MockStack::run_call(
+				ALICE,
+				BOB,
+				&mut GasMeter::<Test>::new(GAS_LIMIT),
+				&mut storage_meter,
+				&schedule,
+				0,
+				vec![],
+				None,
+				Determinism::Deterministic,
+				None,
+				false,
+			));
 		});
 	}
 
@@ -2436,6 +3035,7 @@ mod tests {
 					vec![],
 					&[],
 					None,
+					false,
 				),
 				Err(Error::<Test>::TerminatedInConstructor.into())
 			);
@@ -2486,6 +3086,8 @@ mod tests {
 				vec![0],
 				None,
 				Determinism::Deterministic,
+				None,
+				false,
 			);
 			assert_matches!(result, Ok(_));
 		});
@@ -2521,6 +3123,7 @@ mod tests {
 				vec![],
 				&[],
 				None,
+				false,
 			);
 			assert_matches!(result, Ok(_));
 		});
@@ -2553,6 +3156,8 @@ mod tests {
 				vec![],
 				Some(&mut debug_buffer),
 				Determinism::Deterministic,
+				None,
+				false,
 			)
 			.unwrap();
 		});
@@ -2587,6 +3192,8 @@ mod tests {
 				vec![],
 				Some(&mut debug_buffer),
 				Determinism::Deterministic,
+				None,
+				false,
 			);
 			assert!(result.is_err());
 		});
@@ -2622,6 +3229,8 @@ mod tests {
 				vec![],
 				Some(&mut debug_buffer),
 				Determinism::Deterministic,
+				None,
+				false,
 			)
 			.unwrap();
 			assert_eq!(
@@ -2658,7 +3267,9 @@ mod tests {
 				0,
 				CHARLIE.encode(),
 				None,
-				Determinism::Deterministic
+				Determinism::Deterministic,
+				None,
+				false
 			));
 
 			// Calling into oneself fails
@@ -2672,7 +3283,9 @@ mod tests {
 					0,
 					BOB.encode(),
 					None,
-					Determinism::Deterministic
+					Determinism::Deterministic,
+					None,
+					false
 				)
 				.map_err(|e| e.error),
 				<Error<Test>>::ReentranceDenied,
@@ -2711,7 +3324,9 @@ mod tests {
 					0,
 					vec![0],
 					None,
-					Determinism::Deterministic
+					Determinism::Deterministic,
+					None,
+					false
 				)
 				.map_err(|e| e.error),
 				<Error<Test>>::ReentranceDenied,
@@ -2747,6 +3362,8 @@ mod tests {
 				vec![],
 				None,
 				Determinism::Deterministic,
+				None,
+				false,
 			)
 			.unwrap();
 
@@ -2767,6 +3384,7 @@ mod tests {
 						event: MetaEvent::Contracts(crate::Event::Called {
 							caller: ALICE,
 							contract: BOB,
+							selector: None,
 						}),
 						topics: vec![hash(&ALICE), hash(&BOB)],
 					},
@@ -2830,6 +3448,8 @@ mod tests {
 				vec![],
 				None,
 				Determinism::Deterministic,
+				None,
+				false,
 			)
 			.unwrap();
 
@@ -2863,6 +3483,7 @@ mod tests {
 						event: MetaEvent::Contracts(crate::Event::Called {
 							caller: ALICE,
 							contract: BOB,
+							selector: None,
 						}),
 						topics: vec![hash(&ALICE), hash(&BOB)],
 					},
@@ -2932,6 +3553,7 @@ mod tests {
 				vec![],
 				&[],
 				None,
+				false,
 			)
 			.ok();
 			assert_eq!(<Nonce<Test>>::get(), 0);
@@ -2946,6 +3568,7 @@ mod tests {
 				vec![],
 				&[],
 				None,
+				false,
 			));
 			assert_eq!(<Nonce<Test>>::get(), 1);
 
@@ -2959,6 +3582,7 @@ mod tests {
 				vec![],
 				&[],
 				None,
+				false,
 			));
 			assert_eq!(<Nonce<Test>>::get(), 2);
 
@@ -2972,6 +3596,7 @@ mod tests {
 				vec![],
 				&[],
 				None,
+				false,
 			));
 			assert_eq!(<Nonce<Test>>::get(), 4);
 		});
@@ -3033,7 +3658,105 @@ mod tests {
 				0,
 				vec![],
 				None,
-				Determinism::Deterministic
+				Determinism::Deterministic,
+				None,
+				false
+			));
+		});
+	}
+
+	#[test]
+	fn storage_stats_count_taken_values() {
+		let code_hash = MockLoader::insert(Call, |ctx, _| {
+			assert_eq!(
+				ctx.ext.set_storage(&[1; 32], Some(vec![1, 2, 3]), false),
+				Ok(WriteOutcome::New)
+			);
+			assert_eq!(ctx.ext.set_storage(&[2; 32], Some(vec![4]), true), Ok(WriteOutcome::New));
+			assert_eq!(
+				ctx.ext.set_storage(&[1; 32], None, true),
+				Ok(WriteOutcome::Taken(vec![1, 2, 3]))
+			);
+			exec_success()
+		});
+
+		ExtBuilder::default().build().execute_with(|| {
+			let min_balance = <Test as Config>::Currency::minimum_balance();
+			let schedule = <Test as Config>::Schedule::get();
+			let mut gas_meter = GasMeter::<Test>::new(GAS_LIMIT);
+			set_balance(&ALICE, min_balance * 1000);
+			place_contract(&BOB, code_hash);
+			let mut storage_meter = storage::meter::Meter::new(&ALICE, None, 0).unwrap();
+			let mut stats = StorageAccessStats::default();
+			assert_ok!(MockStack::run_call(
+				ALICE,
+				BOB,
+				&mut gas_meter,
+				&mut storage_meter,
+				&schedule,
+				0,
+				vec![],
+				None,
+				Determinism::Deterministic,
+				Some(&mut stats),
+				false
+			));
+
+			// Every write that takes the old value also reads it.
+			assert_eq!(
+				stats,
+				StorageAccessStats {
+					reads: 2,
+					writes: 3,
+					bytes_read: 3,
+					bytes_written: 4,
+					events: 0,
+				}
+			);
+		});
+	}
+
+	#[test]
+	fn storage_item_limit_counts_reentrant_frames() {
+		let code_hash = MockLoader::insert(Call, |ctx, _| {
+			if ctx.input_data[0] == 0 {
+				assert_eq!(
+					ctx.ext.set_storage(&[1; 32], Some(vec![1]), false),
+					Ok(WriteOutcome::New)
+				);
+				// The item created by the outer frame is counted by the reentrant one.
+				let r = ctx.ext.call(Weight::zero(), BOB, 0, vec![1], true);
+				assert_eq!(
+					r.map_err(|e| e.error),
+					Err(Error::<Test>::StorageItemLimitExceeded.into())
+				);
+				exec_success()
+			} else {
+				ctx.ext.set_storage(&[2; 32], Some(vec![2]), false)?;
+				exec_success()
+			}
+		});
+
+		ExtBuilder::default().build().execute_with(|| {
+			let min_balance = <Test as Config>::Currency::minimum_balance();
+			let mut schedule = <Test as Config>::Schedule::get();
+			schedule.limits.storage_items = Some(1);
+			let mut gas_meter = GasMeter::<Test>::new(GAS_LIMIT);
+			set_balance(&ALICE, min_balance * 1000);
+			place_contract(&BOB, code_hash);
+			let mut storage_meter = storage::meter::Meter::new(&ALICE, None, 0).unwrap();
+			assert_ok!(MockStack::run_call(
+				ALICE,
+				BOB,
+				&mut gas_meter,
+				&mut storage_meter,
+				&schedule,
+				0,
+				vec![0],
+				None,
+				Determinism::Deterministic,
+				None,
+				false
 			));
 		});
 	}
@@ -3160,7 +3883,9 @@ mod tests {
 				0,
 				vec![],
 				None,
-				Determinism::Deterministic
+				Determinism::Deterministic,
+				None,
+				false
 			));
 		});
 	}
@@ -3196,7 +3921,9 @@ mod tests {
 				0,
 				vec![],
 				None,
-				Determinism::Deterministic
+				Determinism::Deterministic,
+				None,
+				false
 			));
 		});
 	}
@@ -3232,7 +3959,9 @@ mod tests {
 				0,
 				vec![],
 				None,
-				Determinism::Deterministic
+				Determinism::Deterministic,
+				None,
+				false
 			));
 		});
 	}
@@ -3294,7 +4023,9 @@ mod tests {
 				0,
 				vec![],
 				None,
-				Determinism::Deterministic
+				Determinism::Deterministic,
+				None,
+				false
 			));
 		});
 	}
@@ -3356,7 +4087,9 @@ mod tests {
 				0,
 				vec![],
 				None,
-				Determinism::Deterministic
+				Determinism::Deterministic,
+				None,
+				false
 			));
 		});
 	}
@@ -3389,6 +4122,8 @@ mod tests {
 				vec![],
 				None,
 				Determinism::Deterministic,
+				None,
+				false,
 			);
 			assert_matches!(result, Ok(_));
 		});
@@ -3434,7 +4169,9 @@ mod tests {
 				0,
 				vec![],
 				None,
-				Determinism::Deterministic
+				Determinism::Deterministic,
+				None,
+				false
 			));
 		});
 	}
@@ -23,16 +23,22 @@ use frame_support::{
 	weights::Weight,
 	DefaultNoBound,
 };
+use pallet_contracts_primitives::GasCategory;
 use sp_runtime::traits::Zero;
-use sp_std::marker::PhantomData;
+use sp_std::{marker::PhantomData, prelude::*};
 
 #[cfg(test)]
 use std::{any::Any, fmt::Debug};
 
+/// The amount of weight that was charged by [`GasMeter::charge`].
+///
+/// It can only be obtained by charging and is consumed by [`GasMeter::adjust_gas`], so that a
+/// charge can be refunded at most once.
 #[derive(Debug, PartialEq, Eq)]
 pub struct ChargedAmount(Weight);
 
 impl ChargedAmount {
+	/// The charged amount of weight.
 	pub fn amount(&self) -> Weight {
 		self.0
 	}
@@ -64,6 +70,11 @@ pub trait Token<T: Config>: Copy + Clone + TestAuxiliaries {
 	/// while calculating the amount. In this case it is ok to use saturating operations
 	/// since on overflow they will return `max_value` which should consume all gas.
 	fn weight(&self) -> Weight;
+
+	/// The category the weight of this token is accounted to in a [`GasMeter::breakdown`].
+	fn category(&self) -> GasCategory {
+		GasCategory::Other
+	}
 }
 
 /// A wrapper around a type-erased trait object of what used to be a `Token`.
@@ -80,6 +91,10 @@ pub struct GasMeter<T: Config> {
 	gas_left: Weight,
 	/// Due to `adjust_gas` and `nested` the `gas_left` can temporarily dip below its final value.
 	gas_left_lowest: Weight,
+	/// The consumed weight per [`GasCategory`], indexed like [`GasCategory::ALL`].
+	///
+	/// Only collected if enabled with [`GasMeter::collect_breakdown`].
+	breakdown: Option<[Weight; GasCategory::ALL.len()]>,
 	_phantom: PhantomData<T>,
 	#[cfg(test)]
 	tokens: Vec<ErasedToken>,
@@ -91,6 +106,7 @@ impl<T: Config> GasMeter<T> {
 			gas_limit,
 			gas_left: gas_limit,
 			gas_left_lowest: gas_limit,
+			breakdown: None,
 			_phantom: PhantomData,
 			#[cfg(test)]
 			tokens: Vec::new(),
@@ -118,7 +134,11 @@ impl<T: Config> GasMeter<T> {
 			},
 		);
 		self.gas_left = self.gas_left.checked_sub(&amount).ok_or_else(|| <Error<T>>::OutOfGas)?;
-		Ok(GasMeter::new(amount))
+		let mut nested = GasMeter::new(amount);
+		if self.breakdown.is_some() {
+			nested.collect_breakdown();
+		}
+		Ok(nested)
 	}
 
 	/// Absorb the remaining gas of a nested meter after we are done using it.
@@ -144,6 +164,29 @@ impl<T: Config> GasMeter<T> {
 			*self.gas_left_lowest.proof_size_mut() = self.gas_left_lowest().proof_size();
 		}
 		self.gas_left += nested.gas_left;
+		if let (Some(breakdown), Some(nested)) = (&mut self.breakdown, nested.breakdown) {
+			breakdown.iter_mut().zip(nested).for_each(|(w, n)| *w = w.saturating_add(n));
+		}
+	}
+
+	/// Start collecting the consumed weight per [`GasCategory`], see [`Self::breakdown`].
+	///
+	/// Nested meters created afterwards collect it as well, and contribute to it once they are
+	/// absorbed. This does not influence the amount of gas that is charged.
+	pub fn collect_breakdown(&mut self) {
+		self.breakdown = Some(Default::default());
+	}
+
+	/// The consumed weight of every [`GasCategory`], if collected.
+	pub fn breakdown(&self) -> Option<Vec<(GasCategory, Weight)>> {
+		self.breakdown
+			.map(|breakdown| GasCategory::ALL.into_iter().zip(breakdown).collect())
+	}
+
+	/// The weight consumed by the given `category`, if collected.
+	fn category_mut(&mut self, category: GasCategory) -> Option<&mut Weight> {
+		let index = GasCategory::ALL.iter().position(|c| *c == category)?;
+		self.breakdown.as_mut().map(|breakdown| &mut breakdown[index])
 	}
 
 	/// Account for used gas.
@@ -168,6 +211,9 @@ impl<T: Config> GasMeter<T> {
 		// It is OK to not charge anything on failure because we always charge _before_ we perform
 		// any action
 		self.gas_left = self.gas_left.checked_sub(&amount).ok_or_else(|| Error::<T>::OutOfGas)?;
+		if let Some(consumed) = self.category_mut(token.category()) {
+			*consumed = consumed.saturating_add(amount);
+		}
 		Ok(ChargedAmount(amount))
 	}
 
@@ -179,6 +225,9 @@ impl<T: Config> GasMeter<T> {
 		self.gas_left_lowest = self.gas_left_lowest();
 		let adjustment = charged_amount.0.saturating_sub(token.weight());
 		self.gas_left = self.gas_left.saturating_add(adjustment).min(self.gas_limit);
+		if let Some(consumed) = self.category_mut(token.category()) {
+			*consumed = consumed.saturating_sub(adjustment);
+		}
 	}
 
 	/// Returns the amount of gas that is required to run the same call.
@@ -320,4 +369,19 @@ mod tests {
 		let mut gas_meter = GasMeter::<Test>::new(Weight::from_ref_time(25));
 		assert!(!gas_meter.charge(SimpleToken(25)).is_err());
 	}
+
+	// Adjusting refunds the difference but never charges more than initially charged.
+	#[test]
+	fn adjust_only_refunds() {
+		let mut gas_meter = GasMeter::<Test>::new(Weight::from_ref_time(2000));
+
+		let charged = gas_meter.charge(SimpleToken(1000)).unwrap();
+		gas_meter.adjust_gas(charged, SimpleToken(100));
+		assert_eq!(gas_meter.gas_consumed(), Weight::from_ref_time(100));
+		assert_eq!(gas_meter.gas_required(), Weight::from_ref_time(1000));
+
+		let charged = gas_meter.charge(SimpleToken(100)).unwrap();
+		gas_meter.adjust_gas(charged, SimpleToken(500));
+		assert_eq!(gas_meter.gas_consumed(), Weight::from_ref_time(200));
+	}
 }
@@ -93,7 +93,9 @@ mod storage;
 mod wasm;
 
 pub mod chain_extension;
+pub mod debug;
 pub mod weights;
+pub mod xcm;
 
 #[cfg(test)]
 mod tests;
@@ -102,42 +104,54 @@ use crate::{
 	exec::{AccountIdOf, ExecError, Executable, Stack as ExecStack},
 	gas::GasMeter,
 	storage::{meter::Meter as StorageMeter, ContractInfo, DeletedContract, Storage},
-	wasm::{OwnerInfo, PrefabWasmModule, TryInstantiate},
+	wasm::{
+		AllowDeprecatedInterface, ExplainRejection, OwnerInfo, PrefabWasmModule, TryInstantiate,
+	},
 	weights::WeightInfo,
 };
 use codec::{Codec, Decode, Encode, HasCompact};
 use frame_support::{
 	dispatch::{Dispatchable, GetDispatchInfo, Pays, PostDispatchInfo},
 	ensure,
+	storage::{with_transaction, TransactionOutcome},
 	traits::{
-		tokens::fungible::Inspect, ConstU32, Contains, Currency, Get, Randomness,
-		ReservableCurrency, Time,
+		tokens::fungible::Inspect, ConstU32, Contains, Currency, ExistenceRequirement, Get,
+		OnUnbalanced, Randomness, ReservableCurrency, Time,
 	},
 	weights::{OldWeight, Weight},
-	BoundedVec, WeakBoundedVec,
+	BoundedVec, PalletId, WeakBoundedVec,
 };
 use frame_system::Pallet as System;
 use pallet_contracts_primitives::{
-	Code, CodeUploadResult, CodeUploadReturnValue, ContractAccessError, ContractExecResult,
-	ContractInstantiateResult, ExecReturnValue, GetStorageResult, InstantiateReturnValue,
-	StorageDeposit,
+	CallRequest, Code, CodeUploadError, CodeUploadResult, CodeUploadReturnValue,
+	ContractAccessError, ContractExecResult, ContractInstantiateResult, DryRunOptions,
+	ExecReturnValue, ExportContractResult, GetStorageResult, HostFunction, InstantiateReturnValue,
+	StorageAccessStats, StorageDeposit,
 };
 use scale_info::TypeInfo;
-use sp_runtime::traits::{Convert, Hash, Saturating, StaticLookup, TrailingZeroInput};
+use sp_runtime::{
+	traits::{
+		AccountIdConversion, Convert, Hash, Saturating, StaticLookup, TrailingZeroInput, Zero,
+	},
+	DispatchError, RuntimeDebug,
+};
 use sp_std::{fmt::Debug, marker::PhantomData, prelude::*};
 
 pub use crate::{
-	exec::{Frame, VarSizedKey as StorageKey},
+	exec::{Frame, TrapCategory, VarSizedKey as StorageKey},
 	migration::Migration,
 	pallet::*,
 	schedule::{HostFnWeights, InstructionWeights, Limits, Schedule},
-	wasm::Determinism,
+	wasm::{CalleeError, CodeMetadata, Determinism},
 };
 
 type CodeHash<T> = <T as frame_system::Config>::Hash;
 type TrieId = BoundedVec<u8, ConstU32<128>>;
 type BalanceOf<T> =
 	<<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::Balance;
+type NegativeImbalanceOf<T> = <<T as Config>::Currency as Currency<
+	<T as frame_system::Config>::AccountId,
+>>::NegativeImbalance;
 type CodeVec<T> = BoundedVec<u8, <T as Config>::MaxCodeLen>;
 type RelaxedCodeVec<T> = WeakBoundedVec<u8, <T as Config>::MaxCodeLen>;
 type AccountIdLookupOf<T> = <<T as frame_system::Config>::Lookup as StaticLookup>::Source;
@@ -151,6 +165,17 @@ type DebugBufferVec<T> = BoundedVec<u8, <T as Config>::MaxDebugBufferLen>;
 /// that this value makes sense for a memory location or length.
 const SENTINEL: u32 = u32::MAX;
 
+/// The identifier of the account that holds the funds of the re-instrumentation pot.
+///
+/// See [`ReinstrumentPolicy::Subsidized`].
+const REINSTRUMENT_POT_ID: PalletId = PalletId(*b"py/cntrs");
+
+/// The maximum number of storage entries returned by a single [`Pallet::export_contract`] call.
+///
+/// Larger limits are capped to this value, such that a single runtime API call can't walk the
+/// whole child trie of a contract.
+pub const MAX_EXPORT_ENTRIES: u32 = 1024;
+
 /// Provides the contract address generation method.
 ///
 /// See [`DefaultAddressGenerator`] for the default implementation.
@@ -194,6 +219,27 @@ impl<T: Config> AddressGenerator<T> for DefaultAddressGenerator {
 	}
 }
 
+/// Who bears the cost of re-instrumenting code after the instruction weights of the
+/// [`Schedule`] have changed.
+///
+/// Code is re-instrumented lazily, by the first call or instantiation that loads it after the
+/// change. Unless the caller pays, the fee equivalent of the re-instrumentation weight according
+/// to [`Config::WeightPrice`] is burned from the chosen source. Should that source not be able
+/// to afford it, the caller pays after all and [`Event::ReinstrumentChargedToCaller`] is emitted.
+///
+/// The caller always needs enough gas to cover the re-instrumentation. It is refunded if the
+/// chosen source pays.
+#[derive(Clone, Copy, Eq, PartialEq, Encode, Decode, RuntimeDebug, TypeInfo)]
+pub enum ReinstrumentPolicy {
+	/// The caller pays with its gas, like for any other operation.
+	ChargeCaller,
+	/// The fee is deducted from the deposit that the owner of the code has reserved when
+	/// uploading it.
+	ChargeCodeOwnerDeposit,
+	/// The fee is paid out of a pot held by this pallet, see [`Pallet::reinstrument_pot`].
+	Subsidized,
+}
+
 #[frame_support::pallet]
 pub mod pallet {
 	use super::*;
@@ -210,6 +256,8 @@ pub mod pallet {
 	#[pallet::config]
 	pub trait Config: frame_system::Config {
 		/// The time implementation used to supply timestamps to contracts through `seal_now`.
+		///
+		/// This is usually `pallet_timestamp`, but any other source of time works as well.
 		type Time: Time;
 
 		/// The generator used to supply randomness to contracts through `seal_random`
@@ -249,6 +297,17 @@ pub mod pallet {
 		/// be exploited to drive the runtime into a panic.
 		type CallFilter: Contains<<Self as frame_system::Config>::RuntimeCall>;
 
+		/// Filter that is applied to the code hash of every contract that is about to be
+		/// instantiated.
+		///
+		/// Instantiating code whose hash is not contained in this filter fails with
+		/// [`Error::CodeNotAllowed`]. This applies to instantiations via the dispatchables as well
+		/// as to instantiations by other contracts. Uploading code is not affected.
+		///
+		/// Use `Everything` in order to allow the instantiation of any code. Permissioned chains
+		/// can use this to only allow the deployment of audited code.
+		type InstantiateFilter: Contains<CodeHash<Self>>;
+
 		/// Used to answer contracts' queries regarding the current weight price. This is **not**
 		/// used to calculate the actual fee and is only for informational purposes.
 		type WeightPrice: Convert<Weight, BalanceOf<Self>>;
@@ -339,9 +398,118 @@ pub mod pallet {
 		#[pallet::constant]
 		type UnsafeUnstableInterface: Get<bool>;
 
+		/// Allow [`Pallet::import_contract_storage`] to overwrite the storage of contracts.
+		///
+		/// This is meant for dev nodes that want to reproduce the state of a contract found on
+		/// another chain, see [`Pallet::export_contract`].
+		///
+		/// # Warning
+		///
+		/// Do **not** set to `true` on productions chains.
+		#[pallet::constant]
+		type AllowStorageImport: Get<bool>;
+
+		/// Deposit [`Event::ContractTrapped`] whenever a contract execution fails.
+		///
+		/// This makes the reason of failed sub-calls observable on-chain without the need for a
+		/// dry-run. Chains that are concerned about the additional event volume should set this
+		/// to `false`.
+		#[pallet::constant]
+		type EmitTrapEvents: Get<bool>;
+
+		/// Include the selector of the called message in [`Event::Called`].
+		///
+		/// The selector is taken to be the first four bytes of the input data, which is the
+		/// convention of ink! but not necessarily of other contract languages. Hence it is up to
+		/// the chain to decide whether it is worth the additional bytes per event.
+		#[pallet::constant]
+		type EmitCallSelectors: Get<bool>;
+
 		/// The maximum length of the debug buffer in bytes.
 		#[pallet::constant]
 		type MaxDebugBufferLen: Get<u32>;
+
+		/// Handler for the remaining balance of a terminated contract that cannot be transferred
+		/// to its beneficiary.
+		///
+		/// This is the case when the beneficiary does not exist and the remaining balance is below
+		/// the existential deposit. Use `()` to burn it.
+		type TerminationDustHandler: OnUnbalanced<NegativeImbalanceOf<Self>>;
+
+		/// The maximum number of calls that can be simulated by a single
+		/// [`Pallet::bare_batch_call`].
+		#[pallet::constant]
+		type MaxBatchCalls: Get<u32>;
+
+		/// Who bears the cost of re-instrumenting code after a schedule change.
+		///
+		/// Use [`ReinstrumentPolicy::ChargeCaller`] to keep charging whoever happens to run the
+		/// code first.
+		#[pallet::constant]
+		type ReinstrumentPolicy: Get<ReinstrumentPolicy>;
+
+		/// Lets contracts send and execute XCM messages, see [`xcm`].
+		///
+		/// Use [`xcm::Deny`] to not offer this to contracts. Note that calls dispatched by the
+		/// messages bypass [`Config::CallFilter`], the implementation has to filter them.
+		type Xcm: xcm::XcmInterface<Self>;
+
+		/// Receives the debug messages of contracts executed without a debug buffer, see
+		/// [`debug`].
+		///
+		/// Use `()` to discard them, which is what production chains should do.
+		type Debugger: debug::ContractDebugSink<Self>;
+	}
+
+	#[pallet::extra_constants]
+	impl<T: Config> Pallet<T> {
+		/// The maximum nesting depth of contract calls including the root contract.
+		///
+		/// This is derived from [`Config::CallStack`].
+		#[pallet::constant_name(MaxCallDepth)]
+		fn max_call_depth() -> u32 {
+			<T::CallStack as smallvec::Array>::size() as u32 + 1
+		}
+
+		/// The maximum size of the input data passed to a contract in bytes.
+		///
+		/// This is [`Limits::input_len`] of [`Config::Schedule`].
+		#[pallet::constant_name(MaxInputLen)]
+		fn max_input_len() -> u32 {
+			T::Schedule::get().limits.input_len
+		}
+
+		/// The maximum number of bytes the contracts of a single call can emit as events.
+		///
+		/// This is [`Limits::event_bytes_per_call`] of [`Config::Schedule`].
+		#[pallet::constant_name(MaxEventBytesPerCall)]
+		fn max_event_bytes_per_call() -> u32 {
+			T::Schedule::get().limits.event_bytes_per_call
+		}
+
+		/// The maximum number of contracts that contracts can instantiate during a single call.
+		///
+		/// This is [`Limits::max_instantiations_per_call`] of [`Config::Schedule`].
+		#[pallet::constant_name(MaxInstantiationsPerCall)]
+		fn max_instantiations_per_call() -> u32 {
+			T::Schedule::get().limits.max_instantiations_per_call
+		}
+
+		/// The maximum size of the data a contract can return in bytes.
+		///
+		/// This is [`Limits::max_return_len`] of [`Config::Schedule`].
+		#[pallet::constant_name(MaxReturnLen)]
+		fn max_return_len() -> u32 {
+			T::Schedule::get().limits.max_return_len
+		}
+
+		/// The maximum number of storage entries a single call can visit while iterating its keys.
+		///
+		/// This is [`Limits::storage_iterations_per_call`] of [`Config::Schedule`].
+		#[pallet::constant_name(MaxStorageIterationsPerCall)]
+		fn storage_iterations_per_call() -> u32 {
+			T::Schedule::get().limits.storage_iterations_per_call
+		}
 	}
 
 	#[pallet::hooks]
@@ -352,11 +520,13 @@ pub mod pallet {
 		}
 
 		fn on_initialize(_block: T::BlockNumber) -> Weight {
+			// `on_finalize` adds the calls to `seal_random` of the block to `RandomnessCallCount`.
+			let finalize_weight = T::DbWeight::get().reads_writes(1, 1);
 			// We want to process the deletion_queue in the on_idle hook. Only in the case
 			// that the queue length has reached its maximal depth, we process it here.
 			let max_len = T::DeletionQueueDepth::get() as usize;
 			let queue_len = <DeletionQueue<T>>::decode_len().unwrap_or(0);
-			if queue_len >= max_len {
+			let deletion_weight = if queue_len >= max_len {
 				// We do not want to go above the block limit and rather avoid lazy deletion
 				// in that case. This should only happen on runtime upgrades.
 				let weight_limit = T::BlockWeights::get()
@@ -367,6 +537,14 @@ pub mod pallet {
 					.saturating_add(T::WeightInfo::on_process_deletion_queue_batch())
 			} else {
 				T::WeightInfo::on_process_deletion_queue_batch()
+			};
+			deletion_weight.saturating_add(finalize_weight)
+		}
+
+		fn on_finalize(_block: T::BlockNumber) {
+			let calls = <RandomnessCallsInBlock<T>>::take();
+			if calls > 0 {
+				<RandomnessCallCount<T>>::mutate(|count| *count = count.saturating_add(calls));
 			}
 		}
 	}
@@ -403,6 +581,7 @@ pub mod pallet {
 		#[pallet::call_index(1)]
 		#[pallet::weight(
 			T::WeightInfo::instantiate_with_code(code.len() as u32, data.len() as u32, salt.len() as u32)
+			.saturating_add(<Pallet<T>>::code_lookup_weight(code.len() as u32))
 			.saturating_add(<Pallet<T>>::compat_weight(*gas_limit))
 		)]
 		#[allow(deprecated)]
@@ -485,8 +664,15 @@ pub mod pallet {
 			determinism: Determinism,
 		) -> DispatchResult {
 			let origin = ensure_signed(origin)?;
-			Self::bare_upload_code(origin, code, storage_deposit_limit.map(Into::into), determinism)
-				.map(|_| ())
+			Self::do_upload_code(
+				origin,
+				code,
+				storage_deposit_limit.map(Into::into),
+				determinism,
+				ExplainRejection::No,
+			)
+			.map(|_| ())
+			.map_err(|err| err.error)
 		}
 
 		/// Remove the code stored under `code_hash` and refund the deposit to its owner.
@@ -583,6 +769,8 @@ pub mod pallet {
 				data,
 				None,
 				Determinism::Deterministic,
+				None,
+				false,
 			);
 			if let Ok(retval) = &output.result {
 				if retval.did_revert() {
@@ -614,6 +802,9 @@ pub mod pallet {
 		/// - The supplied `code` is instrumented, deployed, and a `code_hash` is created for that
 		///   code.
 		/// - If the `code_hash` already exists on the chain the underlying `code` will be shared.
+		///   In this case, validation, instrumentation and the upload deposit are skipped and only
+		///   the weight of [`Self::instantiate`] is charged. The owner and deposit of the existing
+		///   code are left untouched. Hashing the `code` to find out is charged in either case.
 		/// - The destination address is computed based on the sender, code_hash and the salt.
 		/// - The smart-contract account is created at the computed address.
 		/// - The `value` is transferred to the new account.
@@ -621,6 +812,7 @@ pub mod pallet {
 		#[pallet::call_index(7)]
 		#[pallet::weight(
 			T::WeightInfo::instantiate_with_code(code.len() as u32, data.len() as u32, salt.len() as u32)
+			.saturating_add(<Pallet<T>>::code_lookup_weight(code.len() as u32))
 			.saturating_add(*gas_limit)
 		)]
 		pub fn instantiate_with_code(
@@ -636,25 +828,35 @@ pub mod pallet {
 			let code_len = code.len() as u32;
 			let data_len = data.len() as u32;
 			let salt_len = salt.len() as u32;
+			let code_hash = T::Hashing::hash(&code);
+			let (code, base_weight) = if <CodeStorage<T>>::contains_key(&code_hash) {
+				(Code::Existing(code_hash), T::WeightInfo::instantiate(data_len, salt_len))
+			} else {
+				(
+					Code::Upload(code),
+					T::WeightInfo::instantiate_with_code(code_len, data_len, salt_len),
+				)
+			};
+			let base_weight = base_weight.saturating_add(Self::code_lookup_weight(code_len));
 			let mut output = Self::internal_instantiate(
 				origin,
 				value,
 				gas_limit,
 				storage_deposit_limit.map(Into::into),
-				Code::Upload(code),
+				code,
 				data,
 				salt,
 				None,
+				false,
 			);
 			if let Ok(retval) = &output.result {
 				if retval.1.did_revert() {
 					output.result = Err(<Error<T>>::ContractReverted.into());
 				}
 			}
-			output.gas_meter.into_dispatch_result(
-				output.result.map(|(_address, result)| result),
-				T::WeightInfo::instantiate_with_code(code_len, data_len, salt_len),
-			)
+			output
+				.gas_meter
+				.into_dispatch_result(output.result.map(|(_address, result)| result), base_weight)
 		}
 
 		/// Instantiates a contract from a previously deployed wasm binary.
@@ -687,6 +889,7 @@ pub mod pallet {
 				data,
 				salt,
 				None,
+				false,
 			);
 			if let Ok(retval) = &output.result {
 				if retval.1.did_revert() {
@@ -698,6 +901,59 @@ pub mod pallet {
 				T::WeightInfo::instantiate(data_len, salt_len),
 			)
 		}
+
+		/// Transfer `amount` from `source` into the pot that pays for re-instrumentation under
+		/// [`ReinstrumentPolicy::Subsidized`].
+		///
+		/// The dispatch origin of this call must be _Root_. `source` is kept alive.
+		#[pallet::call_index(9)]
+		#[pallet::weight(T::WeightInfo::fund_reinstrument_pot())]
+		pub fn fund_reinstrument_pot(
+			origin: OriginFor<T>,
+			source: AccountIdLookupOf<T>,
+			#[pallet::compact] amount: BalanceOf<T>,
+		) -> DispatchResult {
+			ensure_root(origin)?;
+			let source = T::Lookup::lookup(source)?;
+			T::Currency::transfer(
+				&source,
+				&Self::reinstrument_pot(),
+				amount,
+				ExistenceRequirement::KeepAlive,
+			)?;
+			Self::deposit_event(vec![], Event::ReinstrumentPotFunded { source, amount });
+			Ok(())
+		}
+
+		/// Write `entries` into the storage of `contract`, as returned by
+		/// [`Pallet::export_contract`].
+		///
+		/// The keys are the hashed keys of the child trie of the contract. Existing entries are
+		/// overwritten. The storage deposit is charged to `depositor` as if the contract had
+		/// written the entries itself.
+		///
+		/// The dispatch origin of this call must be _Root_ and [`Config::AllowStorageImport`] must
+		/// be set.
+		#[pallet::call_index(10)]
+		#[pallet::weight(T::WeightInfo::import_contract_storage(entries.len() as u32))]
+		pub fn import_contract_storage(
+			origin: OriginFor<T>,
+			contract: AccountIdLookupOf<T>,
+			depositor: AccountIdLookupOf<T>,
+			entries: Vec<(Vec<u8>, Vec<u8>)>,
+		) -> DispatchResult {
+			ensure_root(origin)?;
+			ensure!(T::AllowStorageImport::get(), <Error<T>>::StorageImportDisabled);
+			let contract = T::Lookup::lookup(contract)?;
+			let depositor = T::Lookup::lookup(depositor)?;
+			let items = entries.len() as u32;
+			Self::do_import_contract_storage(&contract, &depositor, entries)?;
+			Self::deposit_event(
+				vec![T::Hashing::hash_of(&contract)],
+				Event::ContractStorageImported { contract, depositor, items },
+			);
+			Ok(())
+		}
 	}
 
 	#[pallet::event]
@@ -716,6 +972,8 @@ pub mod pallet {
 			contract: T::AccountId,
 			/// The account that received the contracts remaining balance
 			beneficiary: T::AccountId,
+			/// What happened to the remaining balance of the contract.
+			outcome: TerminationOutcome<BalanceOf<T>>,
 		},
 
 		/// Code with the specified hash has been stored.
@@ -755,6 +1013,11 @@ pub mod pallet {
 			caller: T::AccountId,
 			/// The contract that was called.
 			contract: T::AccountId,
+			/// The first four bytes of the input data.
+			///
+			/// Only set if [`Config::EmitCallSelectors`] is enabled and the input data is at
+			/// least four bytes long.
+			selector: Option<[u8; 4]>,
 		},
 
 		/// A contract delegate called a code hash.
@@ -771,6 +1034,44 @@ pub mod pallet {
 			/// The code hash that was delegate called.
 			code_hash: CodeHash<T>,
 		},
+
+		/// A contract execution failed.
+		///
+		/// Only emitted if [`Config::EmitTrapEvents`] is enabled. Please keep in mind that like
+		/// all events this is rolled back together with a failing extrinsic. Hence it is only
+		/// observable for failed sub-calls whose caller carries on. The event is paid for by the
+		/// caller of the failed contract and omitted if it cannot afford it.
+		ContractTrapped {
+			/// The contract whose execution failed.
+			contract: T::AccountId,
+			/// The category of the reason why the execution failed.
+			reason: TrapCategory,
+		},
+
+		/// The source selected by [`Config::ReinstrumentPolicy`] could not afford to pay for
+		/// re-instrumenting a code. The caller has been charged instead.
+		ReinstrumentChargedToCaller {
+			/// The code hash that has been re-instrumented.
+			code_hash: CodeHash<T>,
+		},
+
+		/// The pot that pays for re-instrumentation has been funded.
+		ReinstrumentPotFunded {
+			/// The account the funds have been transferred from.
+			source: T::AccountId,
+			/// The amount that has been transferred.
+			amount: BalanceOf<T>,
+		},
+
+		/// Storage entries have been imported into a contract.
+		ContractStorageImported {
+			/// The contract whose storage has been written.
+			contract: T::AccountId,
+			/// The account that has been charged the storage deposit.
+			depositor: T::AccountId,
+			/// The number of entries that have been written.
+			items: u32,
+		},
 	}
 
 	#[pallet::error]
@@ -787,7 +1088,7 @@ pub mod pallet {
 		/// free balance in the sender's account.
 		TransferFailed,
 		/// Performing a call was denied because the calling depth reached the limit
-		/// of what is specified in the schedule.
+		/// of what is specified by [`Config::CallStack`].
 		MaxCallDepthReached,
 		/// No contract was found at the specified address.
 		ContractNotFound,
@@ -856,7 +1157,42 @@ pub mod pallet {
 		/// by supplying `-lruntime::contracts=debug`.
 		CodeRejected,
 		/// An indetermistic code was used in a context where this is not permitted.
+		///
+		/// Only [`Pallet::bare_call`] with [`Determinism::AllowIndeterminism`] may run it.
 		Indeterministic,
+		/// The code is not allowed to be instantiated by [`Config::InstantiateFilter`].
+		CodeNotAllowed,
+		/// An indeterministic code was called from another contract.
+		///
+		/// It can only be the target of a delegate call from indeterministic code.
+		IndeterministicNestedCall,
+		/// An indeterministic code was used to instantiate a contract.
+		IndeterministicInstantiation,
+		/// The contract tried to create a new storage item while already holding the maximum
+		/// number of items allowed by [`Limits::storage_items`].
+		StorageItemLimitExceeded,
+		/// The call was not executed because it exceeds [`Config::MaxBatchCalls`].
+		BatchTooLarge,
+		/// The input data passed to a contract exceeds [`Limits::input_len`].
+		InputTooLarge,
+		/// The events emitted by the contracts of a call exceed [`Limits::event_bytes_per_call`].
+		EventBudgetExhausted,
+		/// Contracts are not allowed to use XCM, see [`Config::Xcm`].
+		XcmDenied,
+		/// The contracts of this call tried to instantiate more contracts than allowed by
+		/// [`Limits::max_instantiations_per_call`].
+		TooManyInstantiations,
+		/// A contract tried to return more data than allowed by [`Limits::max_return_len`].
+		ReturnDataTooLarge,
+		/// A contract visited more storage entries than allowed by
+		/// [`Limits::storage_iterations_per_call`].
+		TooManyStorageIterations,
+		/// Importing contract storage is not allowed, see [`Config::AllowStorageImport`].
+		StorageImportDisabled,
+		/// An imported storage key is not a valid hashed key of a contract's child trie.
+		InvalidStorageKey,
+		/// A deterministic code delegated to an indeterministic code.
+		IndeterministicDelegateCall,
 	}
 
 	/// A mapping from an original code hash to the original code, untouched by instrumentation.
@@ -904,6 +1240,22 @@ pub mod pallet {
 	pub(crate) type ContractInfoOf<T: Config> =
 		StorageMap<_, Twox64Concat, T::AccountId, ContractInfo<T>>;
 
+	/// The number of times contracts called `seal_random`.
+	///
+	/// The randomness exposed through `seal_random` is insecure and slated for removal. This
+	/// counter lets chains find out how much contracts still depend on it before they disable it.
+	/// It is updated once per block, from [`RandomnessCallsInBlock`].
+	#[pallet::storage]
+	pub(crate) type RandomnessCallCount<T: Config> = StorageValue<_, u64, ValueQuery>;
+
+	/// The number of times contracts called `seal_random` in the current block.
+	///
+	/// Every call stack counts its calls in memory and adds them here once it finishes. This is
+	/// moved to [`RandomnessCallCount`] in `on_finalize`, hence it never reaches the database.
+	#[pallet::storage]
+	#[pallet::whitelist_storage]
+	pub(crate) type RandomnessCallsInBlock<T: Config> = StorageValue<_, u64, ValueQuery>;
+
 	/// Evicted contracts that await child trie deletion.
 	///
 	/// Child trie deletion is a heavy operation depending on the amount of storage items
@@ -913,6 +1265,17 @@ pub mod pallet {
 		StorageValue<_, BoundedVec<DeletedContract, T::DeletionQueueDepth>, ValueQuery>;
 }
 
+/// What happened to the remaining free balance of a terminated contract.
+#[derive(Clone, Copy, Eq, PartialEq, Encode, Decode, RuntimeDebug, TypeInfo)]
+pub enum TerminationOutcome<Balance> {
+	/// The balance was transferred to the beneficiary.
+	Transferred(Balance),
+	/// The balance was below the existential deposit and the beneficiary did not exist.
+	///
+	/// It was handed to [`Config::TerminationDustHandler`] instead.
+	Dust(Balance),
+}
+
 /// Return type of the private [`Pallet::internal_call`] function.
 type InternalCallOutput<T> = InternalOutput<T, ExecReturnValue>;
 
@@ -939,7 +1302,22 @@ impl<T: Config> Pallet<T> {
 	///
 	/// `debug` should only ever be set to `true` when executing as an RPC because
 	/// it adds allocations and could be abused to drive the runtime into an OOM panic.
-	/// If set to `true` it returns additional human readable debugging information.
+	/// If set to `true` it returns additional human readable debugging information, and the
+	/// consumed weight split up by category in [`ContractExecResult::gas_breakdown`].
+	///
+	/// `determinism` must be set to [`Determinism::AllowIndeterminism`] for any indeterministic
+	/// code to run. Even then it only runs when `dest` itself uses it or delegate calls into it,
+	/// never when called or instantiated from another contract. This is the only way to run such
+	/// code, since dispatchables are always deterministic.
+	///
+	/// With [`DryRunOptions::storage_stats`] the storage accesses and emitted events of the whole
+	/// call stack are counted and returned in [`ContractExecResult::storage_stats`]. This does
+	/// not influence the execution itself.
+	///
+	/// With [`DryRunOptions::deterministic_sandbox`] the block number, the timestamp and the
+	/// randomness observed by the contracts are replaced by canonical values, see
+	/// [`ContractExecResult::deterministic_sandbox`]. This makes dry-runs reproducible
+	/// regardless of the block they are executed on. Dispatchables never run in the sandbox.
 	///
 	/// It returns the execution result and the amount of used weight.
 	pub fn bare_call(
@@ -951,8 +1329,11 @@ impl<T: Config> Pallet<T> {
 		data: Vec<u8>,
 		debug: bool,
 		determinism: Determinism,
+		options: DryRunOptions,
 	) -> ContractExecResult<BalanceOf<T>> {
 		let mut debug_message = if debug { Some(DebugBufferVec::<T>::default()) } else { None };
+		let mut storage_stats =
+			if options.storage_stats { Some(StorageAccessStats::default()) } else { None };
 		let output = Self::internal_call(
 			origin,
 			dest,
@@ -962,16 +1343,81 @@ impl<T: Config> Pallet<T> {
 			data,
 			debug_message.as_mut(),
 			determinism,
+			storage_stats.as_mut(),
+			options.deterministic_sandbox,
 		);
+		let mut result = output.result.map_err(|r| r.error);
+		let data_truncated = result.as_mut().map_or(false, |ret| {
+			Self::truncate_dry_run_data(&mut ret.data, options.max_output_len)
+		});
 		ContractExecResult {
-			result: output.result.map_err(|r| r.error),
+			result,
 			gas_consumed: output.gas_meter.gas_consumed(),
 			gas_required: output.gas_meter.gas_required(),
 			storage_deposit: output.storage_deposit,
 			debug_message: debug_message.unwrap_or_default().to_vec(),
+			storage_stats,
+			gas_breakdown: output.gas_meter.breakdown(),
+			deterministic_sandbox: options.deterministic_sandbox,
+			data_truncated,
 		}
 	}
 
+	/// Perform a sequence of calls where each call observes the state changes of the previous ones.
+	///
+	/// All state changes are discarded once the last call is done. Hence, this is only useful to
+	/// dry-run dependent calls, e.g. an approval followed by a transfer which uses it.
+	///
+	/// The calls share `gas_limit`: every call can at most consume what was left of it by the
+	/// previous calls. Calls beyond [`Config::MaxBatchCalls`] are not executed and fail with
+	/// [`Error::BatchTooLarge`].
+	pub fn bare_batch_call(
+		calls: Vec<CallRequest<T::AccountId, BalanceOf<T>>>,
+		gas_limit: Weight,
+		debug: bool,
+		determinism: Determinism,
+		options: DryRunOptions,
+	) -> Vec<ContractExecResult<BalanceOf<T>>> {
+		let max_calls = T::MaxBatchCalls::get() as usize;
+		let mut remaining_gas = gas_limit;
+		let mut results = Vec::with_capacity(calls.len());
+		let _ = with_transaction(|| {
+			for (index, call) in calls.into_iter().enumerate() {
+				if index >= max_calls {
+					results.push(ContractExecResult {
+						result: Err(<Error<T>>::BatchTooLarge.into()),
+						gas_consumed: Weight::zero(),
+						gas_required: Weight::zero(),
+						storage_deposit: Default::default(),
+						debug_message: Vec::new(),
+						storage_stats: None,
+						gas_breakdown: None,
+						deterministic_sandbox: options.deterministic_sandbox,
+						data_truncated: false,
+					});
+					continue
+				}
+				let call_gas_limit =
+					call.gas_limit.map_or(remaining_gas, |limit| limit.min(remaining_gas));
+				let result = Self::bare_call(
+					call.origin,
+					call.dest,
+					call.value,
+					call_gas_limit,
+					call.storage_deposit_limit,
+					call.input_data,
+					debug,
+					determinism,
+					options,
+				);
+				remaining_gas = remaining_gas.saturating_sub(result.gas_consumed);
+				results.push(result);
+			}
+			TransactionOutcome::Rollback(Ok::<_, DispatchError>(()))
+		});
+		results
+	}
+
 	/// Instantiate a new contract.
 	///
 	/// This function is similar to [`Self::instantiate`], but doesn't perform any address lookups
@@ -979,11 +1425,20 @@ impl<T: Config> Pallet<T> {
 	///
 	/// It returns the execution result, account id and the amount of used weight.
 	///
+	/// A constructor that reverts is **not** mapped to an error. Instead, `Ok` is returned with
+	/// the flags and output data of the constructor so that the caller can decode the reason
+	/// of the revert. [`InstantiateReturnValue::contract_address`] is `None` in this case which
+	/// distinguishes a revert from a successful instantiation. A trapping constructor results
+	/// in an `Err`.
+	///
 	/// # Note
 	///
 	/// `debug` should only ever be set to `true` when executing as an RPC because
 	/// it adds allocations and could be abused to drive the runtime into an OOM panic.
 	/// If set to `true` it returns additional human readable debugging information.
+	///
+	/// `options` have the same effect as for [`Self::bare_call`], except that
+	/// [`DryRunOptions::storage_stats`] is not supported for instantiations.
 	pub fn bare_instantiate(
 		origin: T::AccountId,
 		value: BalanceOf<T>,
@@ -993,6 +1448,7 @@ impl<T: Config> Pallet<T> {
 		data: Vec<u8>,
 		salt: Vec<u8>,
 		debug: bool,
+		options: DryRunOptions,
 	) -> ContractInstantiateResult<T::AccountId, BalanceOf<T>> {
 		let mut debug_message = if debug { Some(DebugBufferVec::<T>::default()) } else { None };
 		let output = Self::internal_instantiate(
@@ -1004,16 +1460,25 @@ impl<T: Config> Pallet<T> {
 			data,
 			salt,
 			debug_message.as_mut(),
+			options.deterministic_sandbox,
 		);
+		let mut result = output
+			.result
+			.map(|(account_id, result)| InstantiateReturnValue { result, account_id })
+			.map_err(|e| e.error);
+		let data_truncated = result.as_mut().map_or(false, |ret| {
+			Self::truncate_dry_run_data(&mut ret.result.data, options.max_output_len)
+		});
 		ContractInstantiateResult {
-			result: output
-				.result
-				.map(|(account_id, result)| InstantiateReturnValue { result, account_id })
-				.map_err(|e| e.error),
+			result,
 			gas_consumed: output.gas_meter.gas_consumed(),
 			gas_required: output.gas_meter.gas_required(),
 			storage_deposit: output.storage_deposit,
 			debug_message: debug_message.unwrap_or_default().to_vec(),
+			storage_stats: None,
+			gas_breakdown: None,
+			deterministic_sandbox: options.deterministic_sandbox,
+			data_truncated,
 		}
 	}
 
@@ -1021,11 +1486,31 @@ impl<T: Config> Pallet<T> {
 	///
 	/// This function is similar to [`Self::upload_code`], but doesn't perform any address lookups
 	/// and better suitable for calling directly from Rust.
+	///
+	/// It is meant to be used as a dry-run: A rejected code is analysed further to report why
+	/// it was rejected. This work is not accounted for in the weight of [`Self::upload_code`].
 	pub fn bare_upload_code(
 		origin: T::AccountId,
 		code: Vec<u8>,
 		storage_deposit_limit: Option<BalanceOf<T>>,
 		determinism: Determinism,
+	) -> CodeUploadResult<CodeHash<T>, BalanceOf<T>> {
+		Self::do_upload_code(
+			origin,
+			code,
+			storage_deposit_limit,
+			determinism,
+			ExplainRejection::Yes,
+		)
+	}
+
+	/// Shared implementation of [`Self::upload_code`] and [`Self::bare_upload_code`].
+	fn do_upload_code(
+		origin: T::AccountId,
+		code: Vec<u8>,
+		storage_deposit_limit: Option<BalanceOf<T>>,
+		determinism: Determinism,
+		explain_rejection: ExplainRejection,
 	) -> CodeUploadResult<CodeHash<T>, BalanceOf<T>> {
 		let schedule = T::Schedule::get();
 		let module = PrefabWasmModule::from_code(
@@ -1034,11 +1519,19 @@ impl<T: Config> Pallet<T> {
 			origin,
 			determinism,
 			TryInstantiate::Instantiate,
+			AllowDeprecatedInterface::No,
+			explain_rejection,
 		)
-		.map_err(|(err, _)| err)?;
+		.map_err(|err| CodeUploadError {
+			error: err.error,
+			validation_error: err.validation_error,
+		})?;
 		let deposit = module.open_deposit();
 		if let Some(storage_deposit_limit) = storage_deposit_limit {
-			ensure!(storage_deposit_limit >= deposit, <Error<T>>::StorageDepositLimitExhausted);
+			ensure!(
+				storage_deposit_limit >= deposit,
+				DispatchError::from(<Error<T>>::StorageDepositLimitExhausted)
+			);
 		}
 		let result = CodeUploadReturnValue { code_hash: *module.code_hash(), deposit };
 		module.store()?;
@@ -1057,6 +1550,39 @@ impl<T: Config> Pallet<T> {
 		Ok(maybe_value)
 	}
 
+	/// Export the storage of a contract, page by page.
+	///
+	/// Returns up to `limit` entries starting at `start_key` (or at the first entry if `None`)
+	/// together with the key to start the next page at, if any. `limit` is capped to
+	/// [`MAX_EXPORT_ENTRIES`]. The entries can be written into a contract on another chain with
+	/// [`Pallet::import_contract_storage`].
+	pub fn export_contract(
+		address: T::AccountId,
+		start_key: Option<Vec<u8>>,
+		limit: u32,
+	) -> ExportContractResult {
+		let contract_info =
+			ContractInfoOf::<T>::get(&address).ok_or(ContractAccessError::DoesntExist)?;
+		let limit = limit.min(MAX_EXPORT_ENTRIES);
+		Ok(Storage::<T>::export(&contract_info.trie_id, start_key.as_deref(), limit))
+	}
+
+	/// The account that pays for re-instrumentation under [`ReinstrumentPolicy::Subsidized`].
+	///
+	/// It is funded through [`Pallet::fund_reinstrument_pot`].
+	pub fn reinstrument_pot() -> T::AccountId {
+		REINSTRUMENT_POT_ID.into_account_truncating()
+	}
+
+	/// Query the metadata of the code stored under `code_hash`.
+	///
+	/// Returns `None` if no code is stored under this hash.
+	pub fn code_metadata(
+		code_hash: CodeHash<T>,
+	) -> Option<CodeMetadata<T::AccountId, BalanceOf<T>>> {
+		PrefabWasmModule::<T>::metadata(&code_hash)
+	}
+
 	/// Determine the address of a contract.
 	///
 	/// This is the address generation function used by contract instantiation. See
@@ -1070,6 +1596,13 @@ impl<T: Config> Pallet<T> {
 		T::AddressGenerator::generate_address(deploying_address, code_hash, input_data, salt)
 	}
 
+	/// The host functions which contracts can import on this chain.
+	///
+	/// Unstable functions are only listed if [`Config::UnsafeUnstableInterface`] is enabled.
+	pub fn supported_host_functions() -> Vec<HostFunction> {
+		wasm::host_functions(T::UnsafeUnstableInterface::get())
+	}
+
 	/// Returns the code hash of the contract specified by `account` ID.
 	pub fn code_hash(account: &AccountIdOf<T>) -> Option<CodeHash<T>> {
 		Storage::<T>::code_hash(account)
@@ -1107,8 +1640,21 @@ impl<T: Config> Pallet<T> {
 		data: Vec<u8>,
 		debug_message: Option<&mut DebugBufferVec<T>>,
 		determinism: Determinism,
+		storage_stats: Option<&mut StorageAccessStats>,
+		deterministic_sandbox: bool,
 	) -> InternalCallOutput<T> {
 		let mut gas_meter = GasMeter::new(gas_limit);
+		if debug_message.is_some() {
+			gas_meter.collect_breakdown();
+		}
+		let schedule = T::Schedule::get();
+		if data.len() > schedule.limits.input_len as usize {
+			return InternalCallOutput {
+				result: Err(Error::<T>::InputTooLarge.into()),
+				gas_meter,
+				storage_deposit: Default::default(),
+			}
+		}
 		let mut storage_meter = match StorageMeter::new(&origin, storage_deposit_limit, value) {
 			Ok(meter) => meter,
 			Err(err) =>
@@ -1118,7 +1664,6 @@ impl<T: Config> Pallet<T> {
 					storage_deposit: Default::default(),
 				},
 		};
-		let schedule = T::Schedule::get();
 		let result = ExecStack::<T, PrefabWasmModule<T>>::run_call(
 			origin.clone(),
 			dest,
@@ -1129,6 +1674,8 @@ impl<T: Config> Pallet<T> {
 			data,
 			debug_message,
 			determinism,
+			storage_stats,
+			deterministic_sandbox,
 		);
 		InternalCallOutput {
 			result,
@@ -1149,11 +1696,14 @@ impl<T: Config> Pallet<T> {
 		data: Vec<u8>,
 		salt: Vec<u8>,
 		mut debug_message: Option<&mut DebugBufferVec<T>>,
+		deterministic_sandbox: bool,
 	) -> InternalInstantiateOutput<T> {
 		let mut storage_deposit = Default::default();
 		let mut gas_meter = GasMeter::new(gas_limit);
 		let try_exec = || {
 			let schedule = T::Schedule::get();
+			// checked before the code is decoded or instrumented.
+			ensure!(data.len() <= schedule.limits.input_len as usize, Error::<T>::InputTooLarge);
 			let (extra_deposit, executable) = match code {
 				Code::Upload(binary) => {
 					let executable = PrefabWasmModule::from_code(
@@ -1162,10 +1712,14 @@ impl<T: Config> Pallet<T> {
 						origin.clone(),
 						Determinism::Deterministic,
 						TryInstantiate::Skip,
+						AllowDeprecatedInterface::No,
+						ExplainRejection::No,
 					)
-					.map_err(|(err, msg)| {
-						debug_message.as_mut().map(|buffer| buffer.try_extend(&mut msg.bytes()));
-						err
+					.map_err(|err| {
+						debug_message
+							.as_mut()
+							.map(|buffer| buffer.try_extend(&mut err.msg.bytes()));
+						err.error
 					})?;
 					// The open deposit will be charged during execution when the
 					// uploaded module does not already exist. This deposit is not part of the
@@ -1193,6 +1747,7 @@ impl<T: Config> Pallet<T> {
 				data,
 				&salt,
 				debug_message,
+				deterministic_sandbox,
 			);
 			storage_deposit = storage_meter
 				.into_deposit(&origin)
@@ -1202,6 +1757,50 @@ impl<T: Config> Pallet<T> {
 		InternalInstantiateOutput { result: try_exec(), gas_meter, storage_deposit }
 	}
 
+	/// Write `entries` into the storage of `contract`, charging the storage deposit to
+	/// `depositor`.
+	///
+	/// See [`Pallet::import_contract_storage`].
+	fn do_import_contract_storage(
+		contract: &T::AccountId,
+		depositor: &T::AccountId,
+		entries: Vec<(Vec<u8>, Vec<u8>)>,
+	) -> DispatchResult {
+		let mut contract_info =
+			ContractInfoOf::<T>::get(contract).ok_or(<Error<T>>::ContractNotFound)?;
+		let limits = T::Schedule::get().limits;
+		// Fixed size keys are hashed to 32 bytes and variable sized keys are prefixed with their
+		// 16 byte `Blake2_128` hash.
+		let max_key_len = T::MaxStorageKeyLen::get().saturating_add(16).max(32);
+		let mut storage_meter = StorageMeter::new(depositor, None, Zero::zero())?;
+		let mut nested_meter = storage_meter.nested();
+		for (key, value) in entries {
+			ensure!(
+				!key.is_empty() && key.len() as u32 <= max_key_len,
+				<Error<T>>::InvalidStorageKey
+			);
+			ensure!(value.len() as u32 <= limits.payload_len, <Error<T>>::ValueTooLarge);
+			Storage::<T>::write_raw(
+				&contract_info.trie_id,
+				&key,
+				Some(value),
+				Some(&mut nested_meter),
+				false,
+			)?;
+		}
+		if let Some(limit) = limits.storage_items {
+			ensure!(
+				nested_meter.storage_items(&contract_info) <= limit,
+				<Error<T>>::StorageItemLimitExceeded
+			);
+		}
+		nested_meter.enforce_limit(Some(&mut contract_info))?;
+		storage_meter.absorb(nested_meter, contract, Some(&mut contract_info));
+		storage_meter.into_deposit(depositor);
+		ContractInfoOf::<T>::insert(contract, contract_info);
+		Ok(())
+	}
+
 	/// Deposit a pallet contracts event. Handles the conversion to the overarching event type.
 	fn deposit_event(topics: Vec<T::Hash>, event: Event<T>) {
 		<frame_system::Pallet<T>>::deposit_event_indexed(
@@ -1215,6 +1814,19 @@ impl<T: Config> Pallet<T> {
 		<T::Currency as Inspect<AccountIdOf<T>>>::minimum_balance()
 	}
 
+	/// Truncate the output `data` of a dry-run to `max_len` bytes, if any.
+	///
+	/// Returns whether any data was cut off.
+	fn truncate_dry_run_data(data: &mut Vec<u8>, max_len: Option<u32>) -> bool {
+		match max_len {
+			Some(max_len) if data.len() > max_len as usize => {
+				data.truncate(max_len as usize);
+				true
+			},
+			_ => false,
+		}
+	}
+
 	/// Convert a 1D Weight to a 2D weight.
 	///
 	/// Used by backwards compatible extrinsics. We cannot just set the proof to zero
@@ -1222,11 +1834,33 @@ impl<T: Config> Pallet<T> {
 	fn compat_weight(gas_limit: OldWeight) -> Weight {
 		Weight::from(gas_limit).set_proof_size(u64::from(T::MaxCodeLen::get()) * 2)
 	}
+
+	/// The weight of hashing `code_len` bytes of code and checking whether they are on chain.
+	///
+	/// Charged by [`Pallet::instantiate_with_code`] on top of the weight of the instantiation.
+	fn code_lookup_weight(code_len: u32) -> Weight {
+		let hashing = T::Schedule::get()
+			.host_fn_weights
+			.hash_blake2_256_per_byte
+			.saturating_mul(code_len.into());
+		Weight::from_ref_time(hashing).saturating_add(T::DbWeight::get().reads(1))
+	}
 }
 
 sp_api::decl_runtime_apis! {
 	/// The API used to dry-run contract interactions.
-	#[api_version(2)]
+	///
+	/// Version 3 added `code_metadata`. Version 4 added [`ContractExecResult::storage_stats`] to
+	/// the results of `call` and `instantiate`. Version 5 reports the [`ValidationError`] of
+	/// rejected code as part of the [`CodeUploadError`] returned by `upload_code`. Version 6 added
+	/// `batch_call`. Version 7 added `compute_contract_address`. Version 8 added
+	/// `supported_host_functions`. Version 9 added [`ContractExecResult::gas_breakdown`].
+	/// Version 10 added [`ContractExecResult::data_truncated`]. Version 11 added
+	/// `export_contract`. Version 12 added [`ContractExecResult::deterministic_sandbox`] and the
+	/// [`DryRunOptions`] of `call`, `batch_call` and `instantiate`.
+	///
+	/// [`ValidationError`]: pallet_contracts_primitives::ValidationError
+	#[api_version(12)]
 	pub trait ContractsApi<AccountId, Balance, BlockNumber, Hash> where
 		AccountId: Codec,
 		Balance: Codec,
@@ -1236,6 +1870,7 @@ sp_api::decl_runtime_apis! {
 		/// Perform a call from a specified account to a given contract.
 		///
 		/// See [`crate::Pallet::bare_call`].
+		#[changed_in(12)]
 		fn call(
 			origin: AccountId,
 			dest: AccountId,
@@ -1245,9 +1880,39 @@ sp_api::decl_runtime_apis! {
 			input_data: Vec<u8>,
 		) -> ContractExecResult<Balance>;
 
+		/// Perform a call from a specified account to a given contract.
+		///
+		/// See [`crate::Pallet::bare_call`].
+		fn call(
+			origin: AccountId,
+			dest: AccountId,
+			value: Balance,
+			gas_limit: Option<Weight>,
+			storage_deposit_limit: Option<Balance>,
+			input_data: Vec<u8>,
+			options: DryRunOptions,
+		) -> ContractExecResult<Balance>;
+
+		/// Perform a sequence of calls where each call observes the state changes of the
+		/// previous ones.
+		///
+		/// See [`crate::Pallet::bare_batch_call`].
+		#[changed_in(12)]
+		fn batch_call(calls: Vec<CallRequest<AccountId, Balance>>) -> Vec<ContractExecResult<Balance>>;
+
+		/// Perform a sequence of calls where each call observes the state changes of the
+		/// previous ones.
+		///
+		/// See [`crate::Pallet::bare_batch_call`].
+		fn batch_call(
+			calls: Vec<CallRequest<AccountId, Balance>>,
+			options: DryRunOptions,
+		) -> Vec<ContractExecResult<Balance>>;
+
 		/// Instantiate a new contract.
 		///
 		/// See `[crate::Pallet::bare_instantiate]`.
+		#[changed_in(12)]
 		fn instantiate(
 			origin: AccountId,
 			value: Balance,
@@ -1258,6 +1923,30 @@ sp_api::decl_runtime_apis! {
 			salt: Vec<u8>,
 		) -> ContractInstantiateResult<AccountId, Balance>;
 
+		/// Instantiate a new contract.
+		///
+		/// See `[crate::Pallet::bare_instantiate]`.
+		fn instantiate(
+			origin: AccountId,
+			value: Balance,
+			gas_limit: Option<Weight>,
+			storage_deposit_limit: Option<Balance>,
+			code: Code<Hash>,
+			data: Vec<u8>,
+			salt: Vec<u8>,
+			options: DryRunOptions,
+		) -> ContractInstantiateResult<AccountId, Balance>;
+
+		/// Compute the address a contract would be instantiated at, without executing its
+		/// constructor.
+		///
+		/// The code doesn't need to exist. See [`crate::Pallet::contract_address`].
+		fn compute_contract_address(
+			deployer: AccountId,
+			code_hash: Hash,
+			input_data: Vec<u8>,
+			salt: Vec<u8>,
+		) -> AccountId;
 
 		/// Upload new code without instantiating a contract from it.
 		///
@@ -1278,5 +1967,28 @@ sp_api::decl_runtime_apis! {
 			address: AccountId,
 			key: Vec<u8>,
 		) -> GetStorageResult;
+
+		/// Export up to `limit` storage entries of a given contract, starting at `start_key`.
+		///
+		/// At most [`crate::MAX_EXPORT_ENTRIES`] entries are returned per call.
+		///
+		/// See [`crate::Pallet::export_contract`].
+		fn export_contract(
+			address: AccountId,
+			start_key: Option<Vec<u8>>,
+			limit: u32,
+		) -> ExportContractResult;
+
+		/// Query the metadata of the code stored under the given hash.
+		///
+		/// Returns `None` if no code is stored under this hash.
+		///
+		/// See [`crate::Pallet::code_metadata`].
+		fn code_metadata(code_hash: Hash) -> Option<CodeMetadata<AccountId, Balance>>;
+
+		/// List the host functions which contracts can import on this chain.
+		///
+		/// See [`crate::Pallet::supported_host_functions`].
+		fn supported_host_functions() -> Vec<HostFunction>;
 	}
 }
@@ -27,7 +27,7 @@ use scale_info::TypeInfo;
 #[cfg(feature = "std")]
 use serde::{Deserialize, Serialize};
 use sp_runtime::RuntimeDebug;
-use sp_std::{marker::PhantomData, vec::Vec};
+use sp_std::{marker::PhantomData, num::NonZeroU32, vec::Vec};
 use wasm_instrument::{gas_metering, parity_wasm::elements};
 
 /// How many API calls are executed in a single batch. The reason for increasing the amount
@@ -139,6 +139,52 @@ pub struct Limits {
 
 	/// The maximum size of a storage value and event payload in bytes.
 	pub payload_len: u32,
+
+	/// The maximum number of storage items a single contract can hold, if any.
+	///
+	/// The child trie of a terminated contract is removed lazily by the deletion queue, which
+	/// would otherwise be dominated by a single contract with a huge number of items.
+	///
+	/// Contracts which already hold more items keep working, but cannot create new ones.
+	pub storage_items: Option<u32>,
+
+	/// The maximum size of the input data passed to a contract in bytes.
+	///
+	/// This applies to the `data` of the `call` and `instantiate` extrinsics as well as to the
+	/// input data of `seal_call` and `seal_instantiate`. It is checked before the input is copied
+	/// or handed to the callee.
+	pub input_len: u32,
+
+	/// The maximum number of bytes the contracts of a single call can emit as events, counting
+	/// both their data and topics.
+	///
+	/// Event bytes end up in the block, so they are limited in addition to being paid for with
+	/// gas. The budget is shared by all nested calls. Events of calls that are reverted later
+	/// are counted as well.
+	pub event_bytes_per_call: u32,
+
+	/// The maximum number of contracts that can be instantiated by contracts during a single
+	/// call, counting all nested calls.
+	///
+	/// The call depth does not limit how many contracts a single frame instantiates one after
+	/// another, each of which creates an account and storage. Only instantiations that
+	/// succeed are counted.
+	pub max_instantiations_per_call: u32,
+
+	/// The maximum size of the data a contract can return in bytes.
+	///
+	/// This applies to the data passed to `seal_return`, regardless of the flags, and hence
+	/// also to the output data that a caller receives from `seal_call` and `seal_instantiate`.
+	/// Returning more data traps the callee.
+	pub max_return_len: u32,
+
+	/// The maximum number of storage entries a single call can visit through
+	/// `storage_iter_next`.
+	///
+	/// Every call frame has a budget of its own. Each invocation visits at least one entry and
+	/// more if it needs to skip entries written with a fixed sized key. Exceeding the budget
+	/// traps the contract.
+	pub storage_iterations_per_call: u32,
 }
 
 impl Limits {
@@ -212,6 +258,12 @@ pub struct InstructionWeights<T: Config> {
 	pub global_set: u32,
 	pub memory_current: u32,
 	pub memory_grow: u32,
+	/// Weight charged for each page that is added to the linear memory by `memory.grow`.
+	///
+	/// This is charged in addition to [`Self::memory_grow`] which only covers the instruction
+	/// itself. The maximum number of pages that can be grown is bounded by
+	/// [`Limits::memory_pages`].
+	pub memory_grow_per_page: u32,
 	pub i64clz: u32,
 	pub i64ctz: u32,
 	pub i64popcnt: u32,
@@ -291,6 +343,8 @@ pub struct HostFnWeights<T: Config> {
 	pub now: u64,
 
 	/// Weight of calling `seal_weight_to_fee`.
+	///
+	/// This applies to all versions of the function as they only differ in the passed weight.
 	pub weight_to_fee: u64,
 
 	/// Weight of calling `gas`.
@@ -354,14 +408,24 @@ pub struct HostFnWeights<T: Config> {
 	pub get_storage: u64,
 
 	/// Weight per byte of an item received via `seal_get_storage`.
+	///
+	/// This covers reading the item from the trie as well as copying it into the contract's
+	/// memory. The maximum value size is charged upfront and refunded down to the actual size.
 	pub get_storage_per_byte: u64,
 
+	/// Weight of reporting the length of an output instead of copying it because the buffer
+	/// supplied by the contract is too small.
+	pub report_output_len: u64,
+
 	/// Weight of calling `seal_take_storage`.
 	pub take_storage: u64,
 
 	/// Weight per byte of an item received via `seal_take_storage`.
 	pub take_storage_per_byte: u64,
 
+	/// Weight of visiting a single storage entry via `storage_iter_next`.
+	pub storage_iter_next: u64,
+
 	/// Weight of calling `seal_transfer`.
 	pub transfer: u64,
 
@@ -428,6 +492,18 @@ pub struct HostFnWeights<T: Config> {
 	/// Weight of calling `instantiation_nonce`.
 	pub instantiation_nonce: u64,
 
+	/// Weight of calling `call_stack_remaining`.
+	pub call_stack_remaining: u64,
+
+	/// Weight of calling `caller_is_contract`.
+	pub caller_is_contract: u64,
+
+	/// Weight of calling `xcm_execute`, excluding the weight limit of the message.
+	pub xcm_execute: u64,
+
+	/// Weight of calling `xcm_send`, excluding the weight limit of the message.
+	pub xcm_send: u64,
+
 	/// The type parameter is used in the default implementation.
 	#[codec(skip)]
 	pub _phantom: PhantomData<T>,
@@ -528,6 +604,16 @@ impl Default for Limits {
 			subject_len: 32,
 			call_depth: 32,
 			payload_len: 16 * 1024,
+			storage_items: None,
+			// The whole linear memory of a contract with the default `memory_pages`.
+			input_len: 16 * 64 * 1024,
+			// 16 events with the maximum payload.
+			event_bytes_per_call: 16 * 16 * 1024,
+			max_instantiations_per_call: 32,
+			// The whole linear memory of a contract with the default `memory_pages` so that
+			// deployed contracts are not cut off.
+			max_return_len: 16 * 64 * 1024,
+			storage_iterations_per_call: 1024,
 		}
 	}
 }
@@ -536,7 +622,7 @@ impl<T: Config> Default for InstructionWeights<T> {
 	fn default() -> Self {
 		let max_pages = Limits::default().memory_pages;
 		Self {
-			version: 4,
+			version: 5,
 			fallback: 0,
 			i64const: cost_instr!(instr_i64const, 1),
 			i64load: cost_instr!(instr_i64load, 2),
@@ -557,7 +643,11 @@ impl<T: Config> Default for InstructionWeights<T> {
 			global_get: cost_instr!(instr_global_get, 1),
 			global_set: cost_instr!(instr_global_set, 1),
 			memory_current: cost_instr!(instr_memory_current, 1),
-			memory_grow: cost_instr_with_batch_size!(instr_memory_grow, 1, max_pages),
+			// The instruction itself costs about the same as `memory.size`. The actual growth
+			// is charged per page via `memory_grow_per_page`.
+			memory_grow: cost_instr!(instr_memory_current, 1),
+			// Every instruction in this benchmark grows the memory by exactly one page.
+			memory_grow_per_page: cost_instr_with_batch_size!(instr_memory_grow, 1, max_pages),
 			i64clz: cost_instr!(instr_i64clz, 2),
 			i64ctz: cost_instr!(instr_i64ctz, 2),
 			i64popcnt: cost_instr!(instr_i64popcnt, 2),
@@ -636,8 +726,11 @@ impl<T: Config> Default for HostFnWeights<T> {
 			contains_storage_per_byte: cost_byte_batched!(seal_contains_storage_per_kb),
 			get_storage: cost_batched!(seal_get_storage),
 			get_storage_per_byte: cost_byte_batched!(seal_get_storage_per_kb),
+			report_output_len: cost_batched!(seal_get_storage_report_len)
+				.saturating_sub(cost_batched!(seal_get_storage)),
 			take_storage: cost_batched!(seal_take_storage),
 			take_storage_per_byte: cost_byte_batched!(seal_take_storage_per_kb),
+			storage_iter_next: cost_batched!(seal_storage_iter_next),
 			transfer: cost_batched!(seal_transfer),
 			call: cost_batched!(seal_call),
 			delegate_call: cost_batched!(seal_delegate_call),
@@ -675,6 +768,10 @@ impl<T: Config> Default for HostFnWeights<T> {
 			reentrance_count: cost_batched!(seal_reentrance_count),
 			account_reentrance_count: cost_batched!(seal_account_reentrance_count),
 			instantiation_nonce: cost_batched!(seal_instantiation_nonce),
+			call_stack_remaining: cost_batched!(seal_call_stack_remaining),
+			caller_is_contract: cost!(seal_caller_is_contract),
+			xcm_execute: cost_batched!(seal_xcm_execute),
+			xcm_send: cost_batched!(seal_xcm_send),
 			_phantom: PhantomData,
 		}
 	}
@@ -796,9 +893,10 @@ impl<'a, T: Config> gas_metering::Rules for ScheduleRules<'a, T> {
 	}
 
 	fn memory_grow_cost(&self) -> gas_metering::MemoryGrowCost {
-		// We benchmarked the memory.grow instruction with the maximum allowed pages.
-		// The cost for growing is therefore already included in the instruction cost.
-		gas_metering::MemoryGrowCost::Free
+		// The instrumentation charges `memory_grow_per_page` times the number of requested
+		// pages right before any `memory.grow` is executed.
+		NonZeroU32::new(self.schedule.instruction_weights.memory_grow_per_page)
+			.map_or(gas_metering::MemoryGrowCost::Free, gas_metering::MemoryGrowCost::Linear)
 	}
 
 	fn call_per_local_cost(&self) -> u32 {
@@ -151,6 +151,42 @@ impl<T: Config> Storage<T> {
 		child::len(&child_trie_info(trie_id), key.hash().as_slice())
 	}
 
+	/// Returns the hashed key that directly follows `key` in a contract's child trie.
+	///
+	/// The keys are ordered lexicographically by their hashed representation. Pending changes of
+	/// the current transaction are taken into account.
+	pub fn next_key(trie_id: &TrieId, key: &[u8]) -> Option<Vec<u8>> {
+		sp_io::default_child_storage::next_key(child_trie_info(trie_id).storage_key(), key)
+	}
+
+	/// Returns up to `limit` entries of a contract's child trie, starting at `start_key`.
+	///
+	/// The entries are returned with their hashed keys, in the order of [`Self::next_key`]. If
+	/// there are more entries, the key to start the next page at is returned as well.
+	pub fn export(
+		trie_id: &TrieId,
+		start_key: Option<&[u8]>,
+		limit: u32,
+	) -> (Vec<(Vec<u8>, Vec<u8>)>, Option<Vec<u8>>) {
+		let child_trie_info = &child_trie_info(trie_id);
+		let mut next = match start_key {
+			Some(key) if child::exists(child_trie_info, key) => Some(key.to_vec()),
+			Some(key) => Self::next_key(trie_id, key),
+			None => Self::next_key(trie_id, &[]),
+		};
+		let mut entries = Vec::new();
+		while let Some(key) = next {
+			if entries.len() as u32 >= limit {
+				return (entries, Some(key))
+			}
+			next = Self::next_key(trie_id, &key);
+			if let Some(value) = child::get_raw(child_trie_info, &key) {
+				entries.push((key, value));
+			}
+		}
+		(entries, None)
+	}
+
 	/// Update a storage entry into a contract's kv storage.
 	///
 	/// If the `new_value` is `None` then the kv pair is removed. If `take` is true
@@ -164,14 +200,24 @@ impl<T: Config> Storage<T> {
 		new_value: Option<Vec<u8>>,
 		storage_meter: Option<&mut meter::NestedMeter<T>>,
 		take: bool,
+	) -> Result<WriteOutcome, DispatchError> {
+		Self::write_raw(trie_id, &key.hash(), new_value, storage_meter, take)
+	}
+
+	/// Same as [`Self::write`] but with a key that is already hashed.
+	pub fn write_raw(
+		trie_id: &TrieId,
+		hashed_key: &[u8],
+		new_value: Option<Vec<u8>>,
+		storage_meter: Option<&mut meter::NestedMeter<T>>,
+		take: bool,
 	) -> Result<WriteOutcome, DispatchError> {
 		let child_trie_info = &child_trie_info(trie_id);
-		let hashed_key = key.hash();
 		let (old_len, old_value) = if take {
-			let val = child::get_raw(child_trie_info, &hashed_key);
+			let val = child::get_raw(child_trie_info, hashed_key);
 			(val.as_ref().map(|v| v.len() as u32), val)
 		} else {
-			(child::len(child_trie_info, &hashed_key), None)
+			(child::len(child_trie_info, hashed_key), None)
 		};
 
 		if let Some(storage_meter) = storage_meter {
@@ -197,8 +243,8 @@ impl<T: Config> Storage<T> {
 		}
 
 		match &new_value {
-			Some(new_value) => child::put_raw(child_trie_info, &hashed_key, new_value),
-			None => child::kill(child_trie_info, &hashed_key),
+			Some(new_value) => child::put_raw(child_trie_info, hashed_key, new_value),
+			None => child::kill(child_trie_info, hashed_key),
 		}
 
 		Ok(match (old_len, old_value) {
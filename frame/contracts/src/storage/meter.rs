@@ -76,13 +76,13 @@ pub trait Ext<T: Config> {
 	/// around depending on whether `amount` constitutes a `Charge` or a `Refund`.
 	/// It is guaranteed that that this succeeds because no more balance than returned by
 	/// `check_limit` is ever charged. This is why this function is infallible.
-	/// `terminated` designates whether the `contract` was terminated.
-	fn charge(
-		origin: &T::AccountId,
-		contract: &T::AccountId,
-		amount: &DepositOf<T>,
-		terminated: bool,
-	);
+	fn charge(origin: &T::AccountId, contract: &T::AccountId, amount: &DepositOf<T>);
+	/// This is called to release the storage deposit held by a `contract` that is terminated.
+	///
+	/// The deposit is not refunded to the `origin` but released within the contract's own
+	/// account. From there it is handed to the beneficiary of the termination together with
+	/// the rest of the contract's balance.
+	fn release(contract: &T::AccountId, amount: BalanceOf<T>);
 }
 
 /// This [`Ext`] is used for actual on-chain execution when balance needs to be charged.
@@ -218,7 +218,6 @@ impl Diff {
 struct Charge<T: Config> {
 	contract: T::AccountId,
 	amount: DepositOf<T>,
-	terminated: bool,
 }
 
 /// Records the storage changes of a storage meter.
@@ -229,9 +228,9 @@ enum Contribution<T: Config> {
 	/// The meter was checked against its limit using [`RawMeter::enforce_limit`] at the end of
 	/// its execution. In this process the [`Diff`] was converted into a [`Deposit`].
 	Checked(DepositOf<T>),
-	/// The contract was terminated. In this process the [`Diff`] was converted into a [`Deposit`]
-	/// in order to calculate the refund.
-	Terminated(DepositOf<T>),
+	/// The contract was terminated. Its deposit was already released by [`RawMeter::terminate`]
+	/// and hence it does not contribute to the meter anymore.
+	Terminated,
 }
 
 impl<T: Config> Contribution<T> {
@@ -239,7 +238,8 @@ impl<T: Config> Contribution<T> {
 	fn update_contract(&self, info: Option<&mut ContractInfo<T>>) -> DepositOf<T> {
 		match self {
 			Self::Alive(diff) => diff.update_contract::<T>(info),
-			Self::Terminated(deposit) | Self::Checked(deposit) => deposit.clone(),
+			Self::Checked(deposit) => deposit.clone(),
+			Self::Terminated => Deposit::Charge(Zero::zero()),
 		}
 	}
 }
@@ -293,13 +293,11 @@ where
 			.total_deposit
 			.saturating_add(&absorbed.total_deposit)
 			.saturating_add(&own_deposit);
+		// The charges of the children need to be kept even if the absorbed contract itself
+		// did not change its deposit. This is the case when it was terminated.
+		self.charges.extend_from_slice(&absorbed.charges);
 		if !own_deposit.is_zero() {
-			self.charges.extend_from_slice(&absorbed.charges);
-			self.charges.push(Charge {
-				contract: contract.clone(),
-				amount: own_deposit,
-				terminated: absorbed.is_terminated(),
-			});
+			self.charges.push(Charge { contract: contract.clone(), amount: own_deposit });
 		}
 	}
 
@@ -312,11 +310,6 @@ where
 	fn is_alive(&self) -> bool {
 		matches!(self.own_contribution, Contribution::Alive(_))
 	}
-
-	/// True if the contract is terminated.
-	fn is_terminated(&self) -> bool {
-		matches!(self.own_contribution, Contribution::Terminated(_))
-	}
 }
 
 /// Functions that only apply to the root state.
@@ -345,10 +338,10 @@ where
 	/// execution did finish.
 	pub fn into_deposit(self, origin: &T::AccountId) -> DepositOf<T> {
 		for charge in self.charges.iter().filter(|c| matches!(c.amount, Deposit::Refund(_))) {
-			E::charge(origin, &charge.contract, &charge.amount, charge.terminated);
+			E::charge(origin, &charge.contract, &charge.amount);
 		}
 		for charge in self.charges.iter().filter(|c| matches!(c.amount, Deposit::Charge(_))) {
-			E::charge(origin, &charge.contract, &charge.amount, charge.terminated);
+			E::charge(origin, &charge.contract, &charge.amount);
 		}
 		self.total_deposit
 	}
@@ -369,6 +362,27 @@ where
 		};
 	}
 
+	/// The number of storage items held by the contract of `info`, including the changes
+	/// recorded in this meter that were not yet absorbed into `info`.
+	pub fn storage_items(&self, info: &ContractInfo<T>) -> u32 {
+		match &self.own_contribution {
+			Contribution::Alive(diff) => info
+				.storage_items
+				.saturating_add(diff.items_added)
+				.saturating_sub(diff.items_removed),
+			_ => info.storage_items,
+		}
+	}
+
+	/// The number of storage items added and removed by the contract of this meter, which were
+	/// not yet absorbed into its [`ContractInfo`].
+	pub fn storage_items_diff(&self) -> (u32, u32) {
+		match &self.own_contribution {
+			Contribution::Alive(diff) => (diff.items_added, diff.items_removed),
+			_ => (0, 0),
+		}
+	}
+
 	/// Charge from `origin` a storage deposit for contract instantiation.
 	///
 	/// This immediately transfers the balance in order to create the account.
@@ -397,18 +411,20 @@ where
 		if !deposit.is_zero() {
 			// We need to charge immediately so that the account is created before the `value`
 			// is transferred from the caller to the contract.
-			E::charge(origin, contract, &deposit, false);
+			E::charge(origin, contract, &deposit);
 		}
 		Ok(deposit)
 	}
 
-	/// Call to tell the meter that the currently executing contract was executed.
+	/// Call to tell the meter that the currently executing contract was terminated.
 	///
-	/// This will manipulate the meter so that all storage deposit accumulated in
-	/// `contract_info` will be refunded to the `origin` of the meter.
-	pub fn terminate(&mut self, info: &ContractInfo<T>) {
+	/// This immediately releases all storage deposit accumulated in `info` within the account
+	/// of `contract` using [`Ext::release`]. The released deposit is neither refunded to the
+	/// `origin` of the meter nor does it raise the limit that is available to it.
+	pub fn terminate(&mut self, contract: &T::AccountId, info: &ContractInfo<T>) {
 		debug_assert!(self.is_alive());
-		self.own_contribution = Contribution::Terminated(Deposit::Refund(info.total_deposit()));
+		E::release(contract, info.total_deposit());
+		self.own_contribution = Contribution::Terminated;
 	}
 
 	/// [`Self::charge`] does not enforce the storage limit since we want to do this check as late
@@ -453,12 +469,7 @@ impl<T: Config> Ext<T> for ReservingExt {
 		Ok(limit)
 	}
 
-	fn charge(
-		origin: &T::AccountId,
-		contract: &T::AccountId,
-		amount: &DepositOf<T>,
-		terminated: bool,
-	) {
+	fn charge(origin: &T::AccountId, contract: &T::AccountId, amount: &DepositOf<T>) {
 		// There is nothing we can do when this fails as this constitutes a bug in the runtime:
 		// Either the runtime does not hold up the invariant of never deleting a contract's account
 		// or it does not honor reserved balances. We need to settle for emitting an error log
@@ -497,18 +508,14 @@ impl<T: Config> Ext<T> for ReservingExt {
 			// enough reserved balance because we track it in the `ContractInfo` and never send more
 			// back than we have.
 			Deposit::Refund(amount) => {
-				let amount = if terminated {
-					*amount
-				} else {
-					// This is necessary when the `storage_deposit` tracked inside the account
-					// info is out of sync with the actual balance. That can only happen due to
-					// slashing. We make sure to never dust the contract's account through a
-					// refund because we consider this unexpected behaviour.
-					*amount.min(
-						&T::Currency::reserved_balance(contract)
-							.saturating_sub(Pallet::<T>::min_balance()),
-					)
-				};
+				// This is necessary when the `storage_deposit` tracked inside the account
+				// info is out of sync with the actual balance. That can only happen due to
+				// slashing. We make sure to never dust the contract's account through a
+				// refund because we consider this unexpected behaviour.
+				let amount = *amount.min(
+					&T::Currency::reserved_balance(contract)
+						.saturating_sub(Pallet::<T>::min_balance()),
+				);
 				let result =
 					T::Currency::repatriate_reserved(contract, origin, amount, BalanceStatus::Free);
 				if matches!(result, Ok(val) if !val.is_zero()) || matches!(result, Err(_)) {
@@ -524,6 +531,13 @@ impl<T: Config> Ext<T> for ReservingExt {
 			},
 		};
 	}
+
+	fn release(contract: &T::AccountId, amount: BalanceOf<T>) {
+		// The contract is about to be removed. Hence we don't need to keep the minimum balance
+		// reserved and can release everything. Should the tracked deposit be out of sync with
+		// the actual reserve due to slashing we simply release what is left of it.
+		T::Currency::unreserve(contract, amount);
+	}
 }
 
 mod private {
@@ -537,7 +551,7 @@ mod tests {
 	use super::*;
 	use crate::{
 		exec::AccountIdOf,
-		tests::{Test, ALICE, BOB, CHARLIE},
+		tests::{Test, ALICE, BOB, CHARLIE, DJANGO},
 	};
 	use frame_support::parameter_types;
 	use pretty_assertions::assert_eq;
@@ -560,19 +574,26 @@ mod tests {
 		origin: AccountIdOf<Test>,
 		contract: AccountIdOf<Test>,
 		amount: DepositOf<Test>,
-		terminated: bool,
+	}
+
+	#[derive(Debug, PartialEq, Eq, Clone)]
+	struct Release {
+		contract: AccountIdOf<Test>,
+		amount: BalanceOf<Test>,
 	}
 
 	#[derive(Default, Debug, PartialEq, Eq, Clone)]
 	pub struct TestExt {
 		limit_checks: Vec<LimitCheck>,
 		charges: Vec<Charge>,
+		releases: Vec<Release>,
 	}
 
 	impl TestExt {
 		fn clear(&mut self) {
 			self.limit_checks.clear();
 			self.charges.clear();
+			self.releases.clear();
 		}
 	}
 
@@ -594,17 +615,21 @@ mod tests {
 			origin: &AccountIdOf<Test>,
 			contract: &AccountIdOf<Test>,
 			amount: &DepositOf<Test>,
-			terminated: bool,
 		) {
 			TestExtTestValue::mutate(|ext| {
 				ext.charges.push(Charge {
 					origin: origin.clone(),
 					contract: contract.clone(),
 					amount: amount.clone(),
-					terminated,
 				})
 			});
 		}
+
+		fn release(contract: &AccountIdOf<Test>, amount: BalanceOf<Test>) {
+			TestExtTestValue::mutate(|ext| {
+				ext.releases.push(Release { contract: contract.clone(), amount })
+			});
+		}
 	}
 
 	fn clear_ext() {
@@ -714,25 +739,11 @@ mod tests {
 			TestExt {
 				limit_checks: vec![LimitCheck { origin: ALICE, limit: 100, min_leftover: 0 }],
 				charges: vec![
-					Charge {
-						origin: ALICE,
-						contract: CHARLIE,
-						amount: Deposit::Refund(10),
-						terminated: false
-					},
-					Charge {
-						origin: ALICE,
-						contract: CHARLIE,
-						amount: Deposit::Refund(20),
-						terminated: false
-					},
-					Charge {
-						origin: ALICE,
-						contract: BOB,
-						amount: Deposit::Charge(2),
-						terminated: false
-					}
-				]
+					Charge { origin: ALICE, contract: CHARLIE, amount: Deposit::Refund(10) },
+					Charge { origin: ALICE, contract: CHARLIE, amount: Deposit::Refund(20) },
+					Charge { origin: ALICE, contract: BOB, amount: Deposit::Charge(2) }
+				],
+				..Default::default()
 			}
 		)
 	}
@@ -758,31 +769,56 @@ mod tests {
 		let mut nested1 = nested0.nested();
 		nested1.charge(&Diff { items_removed: 5, ..Default::default() });
 		nested1.charge(&Diff { bytes_added: 20, ..Default::default() });
-		nested1.terminate(&nested1_info);
+		nested1.terminate(&CHARLIE, &nested1_info);
 		nested0.enforce_limit(Some(&mut nested1_info)).unwrap();
 		nested0.absorb(nested1, &CHARLIE, None);
 
 		meter.absorb(nested0, &BOB, None);
-		meter.into_deposit(&ALICE);
+		assert_eq!(meter.into_deposit(&ALICE), Deposit::Charge(12));
 
 		assert_eq!(
 			TestExtTestValue::get(),
 			TestExt {
 				limit_checks: vec![LimitCheck { origin: ALICE, limit: 1_000, min_leftover: 0 }],
+				charges: vec![Charge { origin: ALICE, contract: BOB, amount: Deposit::Charge(12) }],
+				releases: vec![Release { contract: CHARLIE, amount: 120 }],
+			}
+		)
+	}
+
+	#[test]
+	fn termination_does_not_raise_limit() {
+		clear_ext();
+
+		let mut meter = TestMeter::new(&ALICE, Some(10), 0).unwrap();
+		let mut nested0 = meter.nested();
+
+		// CHARLIE is called by BOB and calls DJANGO before terminating itself.
+		let nested1_info =
+			new_info(StorageInfo { bytes: 100, items: 10, bytes_deposit: 100, items_deposit: 20 });
+		let mut nested1 = nested0.nested();
+		let mut nested2 = nested1.nested();
+		nested2.charge(&Diff { items_added: 1, ..Default::default() });
+		nested1.absorb(nested2, &DJANGO, None);
+		nested1.terminate(&CHARLIE, &nested1_info);
+		nested0.absorb(nested1, &CHARLIE, None);
+
+		// The deposit released by CHARLIE is not available to BOB.
+		assert_eq!(nested0.available(), 8);
+		nested0.charge(&Diff { bytes_added: 8, ..Default::default() });
+		nested0.enforce_limit(None).unwrap();
+		meter.absorb(nested0, &BOB, None);
+		assert_eq!(meter.into_deposit(&ALICE), Deposit::Charge(10));
+
+		assert_eq!(
+			TestExtTestValue::get(),
+			TestExt {
+				limit_checks: vec![LimitCheck { origin: ALICE, limit: 10, min_leftover: 0 }],
 				charges: vec![
-					Charge {
-						origin: ALICE,
-						contract: CHARLIE,
-						amount: Deposit::Refund(120),
-						terminated: true
-					},
-					Charge {
-						origin: ALICE,
-						contract: BOB,
-						amount: Deposit::Charge(12),
-						terminated: false
-					}
-				]
+					Charge { origin: ALICE, contract: DJANGO, amount: Deposit::Charge(2) },
+					Charge { origin: ALICE, contract: BOB, amount: Deposit::Charge(8) },
+				],
+				releases: vec![Release { contract: CHARLIE, amount: 120 }],
 			}
 		)
 	}
@@ -21,35 +21,49 @@ use crate::{
 		ChainExtension, Environment, Ext, InitState, RegisteredChainExtension,
 		Result as ExtensionResult, RetVal, ReturnFlags, SysConfig,
 	},
-	exec::{FixSizedKey, Frame},
+	debug::ContractDebugSink,
+	exec::{Executable, FixSizedKey, Frame, TrapCategory},
 	storage::Storage,
-	tests::test_utils::{get_contract, get_contract_checked},
-	wasm::{Determinism, PrefabWasmModule, ReturnCode as RuntimeReturnCode},
+	tests::test_utils::{get_contract, get_contract_checked, upload_legacy_code},
+	wasm::{
+		CallFlags, CalleeError, CodeMetadata, Determinism, PrefabWasmModule,
+		ReturnCode as RuntimeReturnCode,
+	},
 	weights::WeightInfo,
-	BalanceOf, Code, CodeStorage, Config, ContractInfoOf, DefaultAddressGenerator, DeletionQueue,
-	Error, Pallet, Schedule,
+	xcm::{self, XcmInterface},
+	BalanceOf, Code, CodeHash, CodeStorage, Config, ContractInfoOf, DefaultAddressGenerator,
+	DeletionQueue, Error, NegativeImbalanceOf, Pallet, RandomnessCallCount, RandomnessCallsInBlock,
+	ReinstrumentPolicy, Schedule, TerminationOutcome,
 };
 use assert_matches::assert_matches;
 use codec::Encode;
 use frame_support::{
 	assert_err, assert_err_ignore_postinfo, assert_noop, assert_ok,
-	dispatch::{DispatchClass, DispatchErrorWithPostInfo, PostDispatchInfo},
+	dispatch::{
+		DispatchClass, DispatchErrorWithPostInfo, DispatchResultWithPostInfo, PostDispatchInfo,
+		WithPostDispatchInfo,
+	},
 	parameter_types,
 	storage::child,
 	traits::{
-		BalanceStatus, ConstU32, ConstU64, Contains, Currency, Get, LockableCurrency, OnIdle,
-		OnInitialize, ReservableCurrency, WithdrawReasons,
+		BalanceStatus, ConstU32, ConstU64, Contains, Currency, Get, Imbalance, LockableCurrency,
+		OnFinalize, OnIdle, OnInitialize, OnUnbalanced, ReservableCurrency, Time, WithdrawReasons,
 	},
 	weights::{constants::WEIGHT_REF_TIME_PER_SECOND, Weight},
+	Blake2_128Concat, StorageHasher,
 };
 use frame_system::{self as system, EventRecord, Phase};
+use pallet_contracts_primitives::{
+	CallRequest, CodeUploadError, DryRunOptions, ExecReturnValue, GasCategory, HostFunction,
+	StorageAccessStats, StorageDeposit, ValidationError,
+};
 use pretty_assertions::{assert_eq, assert_ne};
 use sp_io::hashing::blake2_256;
 use sp_keystore::{testing::KeyStore, KeystoreExt};
 use sp_runtime::{
 	testing::{Header, H256},
 	traits::{BlakeTwo256, Convert, Hash, IdentityLookup},
-	AccountId32,
+	AccountId32, DispatchError,
 };
 use std::sync::Arc;
 
@@ -66,7 +80,6 @@ frame_support::construct_runtime!(
 	{
 		System: frame_system::{Pallet, Call, Config, Storage, Event<T>},
 		Balances: pallet_balances::{Pallet, Call, Storage, Config<T>, Event<T>},
-		Timestamp: pallet_timestamp::{Pallet, Call, Storage, Inherent},
 		Randomness: pallet_randomness_collective_flip::{Pallet, Storage},
 		Utility: pallet_utility::{Pallet, Call, Storage, Event},
 		Contracts: pallet_contracts::{Pallet, Call, Storage, Event<T>},
@@ -77,10 +90,16 @@ frame_support::construct_runtime!(
 pub mod test_utils {
 	use super::{Balances, Hash, SysConfig, Test};
 	use crate::{
-		exec::AccountIdOf, storage::Storage, CodeHash, Config, ContractInfo, ContractInfoOf, Nonce,
+		exec::{AccountIdOf, Executable},
+		storage::Storage,
+		wasm::{
+			AllowDeprecatedInterface, Determinism, ExplainRejection, PrefabWasmModule,
+			TryInstantiate,
+		},
+		CodeHash, Config, ContractInfo, ContractInfoOf, Nonce,
 	};
 	use codec::Encode;
-	use frame_support::traits::Currency;
+	use frame_support::traits::{Currency, Get};
 
 	pub fn place_contract(address: &AccountIdOf<Test>, code_hash: CodeHash<Test>) {
 		let nonce = <Nonce<Test>>::mutate(|counter| {
@@ -105,6 +124,23 @@ pub mod test_utils {
 	pub fn get_contract_checked(addr: &AccountIdOf<Test>) -> Option<ContractInfo<Test>> {
 		ContractInfoOf::<Test>::get(addr)
 	}
+	/// Store `code` as if it was uploaded before the host functions it imports got deprecated.
+	pub fn upload_legacy_code(owner: &AccountIdOf<Test>, code: Vec<u8>) -> CodeHash<Test> {
+		let module = PrefabWasmModule::<Test>::from_code(
+			code,
+			&<Test as Config>::Schedule::get(),
+			owner.clone(),
+			Determinism::Deterministic,
+			TryInstantiate::Instantiate,
+			AllowDeprecatedInterface::Yes,
+			ExplainRejection::No,
+		)
+		.map_err(|err| err.error)
+		.unwrap();
+		let code_hash = *module.code_hash();
+		module.store().unwrap();
+		code_hash
+	}
 	pub fn hash<S: Encode>(s: &S) -> <<Test as SysConfig>::Hashing as Hash>::Output {
 		<<Test as SysConfig>::Hashing as Hash>::hash_of(s)
 	}
@@ -199,6 +235,13 @@ impl ChainExtension<Test> for TestExtension {
 				Ok(RetVal::Converging(id))
 			},
 			3 => Ok(RetVal::Diverging { flags: ReturnFlags::REVERT, data: vec![42, 99] }),
+			4 => {
+				let mut env = env.buf_in_buf_out();
+				let charged = env.charge_weight(Weight::from_ref_time(1000))?;
+				let actual = Weight::from_ref_time(env.read(5)?[4].into());
+				env.adjust_weight(charged, actual);
+				Ok(RetVal::Converging(id))
+			},
 			_ => {
 				panic!("Passed unknown id to test chain extension: {}", func_id);
 			},
@@ -324,12 +367,6 @@ impl pallet_balances::Config for Test {
 	type WeightInfo = ();
 }
 
-impl pallet_timestamp::Config for Test {
-	type Moment = u64;
-	type OnTimestampSet = ();
-	type MinimumPeriod = ConstU64<1>;
-	type WeightInfo = ();
-}
 impl pallet_utility::Config for Test {
 	type RuntimeEvent = RuntimeEvent;
 	type RuntimeCall = RuntimeCall;
@@ -340,15 +377,21 @@ parameter_types! {
 	pub MySchedule: Schedule<Test> = {
 		let mut schedule = <Schedule<Test>>::default();
 		schedule.instruction_weights.fallback = 1;
+		schedule.limits.storage_items = MaxStorageItems::get();
+		schedule.limits.max_instantiations_per_call = MaxInstantiations::get();
+		schedule.limits.storage_iterations_per_call = MaxStorageIterations::get();
 		schedule
 	};
+	pub static MaxStorageItems: Option<u32> = None;
+	pub static MaxInstantiations: u32 = crate::Limits::default().max_instantiations_per_call;
+	pub static MaxStorageIterations: u32 = crate::Limits::default().storage_iterations_per_call;
 	pub static DepositPerByte: BalanceOf<Test> = 1;
 	pub const DepositPerItem: BalanceOf<Test> = 2;
 }
 
 impl Convert<Weight, BalanceOf<Self>> for Test {
 	fn convert(w: Weight) -> BalanceOf<Self> {
-		w.ref_time()
+		w.ref_time().saturating_add(w.proof_size().saturating_mul(2))
 	}
 }
 
@@ -382,18 +425,150 @@ impl Contains<RuntimeCall> for TestFilter {
 	}
 }
 
+parameter_types! {
+	/// The code hashes that are allowed to be instantiated. `None` allows all of them.
+	static InstantiateAllowList: Option<Vec<CodeHash<Test>>> = None;
+}
+
+pub struct TestInstantiateFilter;
+
+impl Contains<CodeHash<Test>> for TestInstantiateFilter {
+	fn contains(code_hash: &CodeHash<Test>) -> bool {
+		InstantiateAllowList::get().map_or(true, |allowed| allowed.contains(code_hash))
+	}
+}
+
+parameter_types! {
+	/// The sum of all balances handed to [`TestDustHandler`].
+	static TerminationDust: BalanceOf<Test> = 0;
+}
+
+pub struct TestDustHandler;
+
+impl OnUnbalanced<NegativeImbalanceOf<Test>> for TestDustHandler {
+	fn on_nonzero_unbalanced(amount: NegativeImbalanceOf<Test>) {
+		TerminationDust::mutate(|dust| *dust += amount.peek());
+	}
+}
+
+parameter_types! {
+	/// The timestamp reported by [`MockTime`].
+	static Now: u64 = 0;
+}
+
+/// A clock that only moves when a test tells it to.
+pub struct MockTime;
+
+impl MockTime {
+	pub fn set(now: u64) {
+		Now::set(now);
+	}
+
+	pub fn advance(by: u64) {
+		Now::mutate(|now| *now += by);
+	}
+}
+
+impl Time for MockTime {
+	type Moment = u64;
+
+	fn now() -> u64 {
+		Now::get()
+	}
+}
+
+/// A message that was handed to [`MockXcm`].
+#[derive(Clone, Debug, PartialEq)]
+pub enum XcmMessage {
+	Execute { origin: AccountId32, message: Vec<u8>, weight_limit: Weight },
+	Send { origin: AccountId32, dest: Vec<u8>, message: Vec<u8>, weight_limit: Weight },
+}
+
+parameter_types! {
+	/// The messages that were accepted by [`MockXcm`].
+	static XcmMessages: Vec<XcmMessage> = vec![];
+	/// Whether [`MockXcm`] rejects all messages.
+	static XcmRejects: bool = false;
+}
+
+/// Records all messages and reports half of their weight limit as used.
+pub struct MockXcm;
+
+impl MockXcm {
+	fn accept(message: XcmMessage, weight_limit: Weight) -> DispatchResultWithPostInfo {
+		if XcmRejects::get() {
+			return Err(DispatchError::Other("rejected").into())
+		}
+		XcmMessages::mutate(|messages| messages.push(message));
+		Ok(Some(Weight::from_ref_time(weight_limit.ref_time() / 2)).into())
+	}
+}
+
+impl XcmInterface<Test> for MockXcm {
+	fn execute(
+		origin: RuntimeOrigin,
+		message: &[u8],
+		weight_limit: Weight,
+	) -> DispatchResultWithPostInfo {
+		let origin = frame_system::ensure_signed(origin)?;
+		Self::accept(
+			XcmMessage::Execute { origin, message: message.to_vec(), weight_limit },
+			weight_limit,
+		)
+	}
+
+	fn send(
+		origin: RuntimeOrigin,
+		dest: &[u8],
+		message: &[u8],
+		weight_limit: Weight,
+	) -> DispatchResultWithPostInfo {
+		let origin = frame_system::ensure_signed(origin)?;
+		Self::accept(
+			XcmMessage::Send {
+				origin,
+				dest: dest.to_vec(),
+				message: message.to_vec(),
+				weight_limit,
+			},
+			weight_limit,
+		)
+	}
+}
+
+parameter_types! {
+	/// The messages that were handed to [`MockDebugSink`].
+	static DebugMessages: Vec<(AccountId32, String)> = vec![];
+}
+
+/// Records all debug messages of contracts executed without a debug buffer.
+pub struct MockDebugSink;
+
+impl ContractDebugSink<Test> for MockDebugSink {
+	const ENABLED: bool = true;
+
+	fn on_debug_message(contract: &AccountId32, message: &str) {
+		DebugMessages::mutate(|messages| messages.push((contract.clone(), message.to_string())));
+	}
+}
+
 parameter_types! {
 	pub const DeletionWeightLimit: Weight = Weight::from_ref_time(500_000_000_000);
 	pub static UnstableInterface: bool = true;
+	pub static EmitTrapEvents: bool = false;
+	pub static EmitCallSelectors: bool = false;
+	pub static Reinstrument: ReinstrumentPolicy = ReinstrumentPolicy::ChargeCaller;
+	pub static AllowStorageImport: bool = false;
 }
 
 impl Config for Test {
-	type Time = Timestamp;
+	type Time = MockTime;
 	type Randomness = Randomness;
 	type Currency = Balances;
 	type RuntimeEvent = RuntimeEvent;
 	type RuntimeCall = RuntimeCall;
 	type CallFilter = TestFilter;
+	type InstantiateFilter = TestInstantiateFilter;
 	type CallStack = [Frame<Self>; 31];
 	type WeightPrice = Self;
 	type WeightInfo = ();
@@ -408,7 +583,15 @@ impl Config for Test {
 	type MaxCodeLen = ConstU32<{ 128 * 1024 }>;
 	type MaxStorageKeyLen = ConstU32<128>;
 	type UnsafeUnstableInterface = UnstableInterface;
+	type AllowStorageImport = AllowStorageImport;
+	type EmitTrapEvents = EmitTrapEvents;
+	type EmitCallSelectors = EmitCallSelectors;
 	type MaxDebugBufferLen = ConstU32<{ 2 * 1024 * 1024 }>;
+	type TerminationDustHandler = TestDustHandler;
+	type MaxBatchCalls = ConstU32<3>;
+	type ReinstrumentPolicy = Reinstrument;
+	type Xcm = MockXcm;
+	type Debugger = MockDebugSink;
 }
 
 pub const ALICE: AccountId32 = AccountId32::new([1u8; 32]);
@@ -545,6 +728,7 @@ fn instantiate_and_call_and_deposit_event() {
 			vec![],
 			vec![],
 			false,
+			DryRunOptions::default(),
 		)
 		.result
 		.unwrap()
@@ -632,6 +816,7 @@ fn deposit_event_max_value_limit() {
 			vec![],
 			vec![],
 			false,
+			DryRunOptions::default(),
 		)
 		.result
 		.unwrap()
@@ -678,6 +863,7 @@ fn run_out_of_gas() {
 			vec![],
 			vec![],
 			false,
+			DryRunOptions::default(),
 		)
 		.result
 		.unwrap()
@@ -725,6 +911,7 @@ fn instantiate_unique_trie_id() {
 			vec![],
 			vec![],
 			false,
+			DryRunOptions::default(),
 		)
 		.result
 		.unwrap()
@@ -787,6 +974,7 @@ fn storage_max_value_limit() {
 			vec![],
 			vec![],
 			false,
+			DryRunOptions::default(),
 		)
 		.result
 		.unwrap()
@@ -818,6 +1006,279 @@ fn storage_max_value_limit() {
 	});
 }
 
+#[test]
+fn storage_read_gas_scales_with_value_size() {
+	let (wasm, _code_hash) = compile_module::<Test>("get_storage").unwrap();
+
+	ExtBuilder::default().existential_deposit(50).build().execute_with(|| {
+		let _ = Balances::deposit_creating(&ALICE, 1_000_000);
+		let addr = Contracts::bare_instantiate(
+			ALICE,
+			30_000,
+			GAS_LIMIT,
+			None,
+			Code::Upload(wasm),
+			vec![],
+			vec![],
+			false,
+			DryRunOptions::default(),
+		)
+		.result
+		.unwrap()
+		.account_id;
+		let max_len = <Test as Config>::Schedule::get().limits.payload_len;
+		let read_value_of_len = |len: u32| {
+			let info = get_contract(&addr);
+			Storage::<Test>::write(
+				&info.trie_id,
+				&[0u8; 32] as &FixSizedKey,
+				Some(vec![42u8; len as usize]),
+				None,
+				false,
+			)
+			.unwrap();
+			let result = Contracts::bare_call(
+				ALICE,
+				addr.clone(),
+				0,
+				GAS_LIMIT.set_ref_time(GAS_LIMIT.ref_time() * 2),
+				None,
+				vec![],
+				false,
+				Determinism::Deterministic,
+				DryRunOptions::default(),
+			);
+			assert_matches!(result.result, Ok(_));
+			result
+		};
+
+		let tiny = read_value_of_len(1);
+		let max = read_value_of_len(max_len);
+
+		// the read is charged per byte of the value actually stored.
+		let per_byte = <Test as Config>::Schedule::get().host_fn_weights.get_storage_per_byte;
+		assert_eq!(
+			max.gas_consumed.ref_time() - tiny.gas_consumed.ref_time(),
+			per_byte * u64::from(max_len - 1),
+		);
+
+		// the maximum value size is charged upfront and the difference refunded afterwards.
+		assert!(tiny.gas_required.ref_time() > tiny.gas_consumed.ref_time());
+	});
+}
+
+#[test]
+fn storage_access_stats_are_reported() {
+	let (wasm, _code_hash) = compile_module::<Test>("storage_access_stats").unwrap();
+
+	ExtBuilder::default().existential_deposit(50).build().execute_with(|| {
+		let _ = Balances::deposit_creating(&ALICE, 1_000_000);
+		let addr = Contracts::bare_instantiate(
+			ALICE,
+			30_000,
+			GAS_LIMIT,
+			None,
+			Code::Upload(wasm),
+			vec![],
+			vec![],
+			false,
+			DryRunOptions::default(),
+		)
+		.result
+		.unwrap()
+		.account_id;
+
+		// Write and read back 3 items of 100 bytes each.
+		let input = (3u32, 100u32).encode();
+
+		// Nothing is collected unless requested.
+		let result = Contracts::bare_call(
+			ALICE,
+			addr.clone(),
+			0,
+			GAS_LIMIT,
+			None,
+			input.clone(),
+			false,
+			Determinism::Deterministic,
+			DryRunOptions::default(),
+		);
+		assert_ok!(result.result);
+		assert_eq!(result.storage_stats, None);
+
+		let result = Contracts::bare_call(
+			ALICE,
+			addr,
+			0,
+			GAS_LIMIT,
+			None,
+			input,
+			false,
+			Determinism::Deterministic,
+			DryRunOptions { storage_stats: true, ..Default::default() },
+		);
+		assert_ok!(result.result);
+		assert_eq!(
+			result.storage_stats,
+			Some(StorageAccessStats {
+				reads: 3,
+				writes: 3,
+				bytes_read: 300,
+				bytes_written: 300,
+				events: 1,
+			}),
+		);
+	});
+}
+
+#[test]
+fn gas_breakdown_is_reported_when_debugging() {
+	let (wasm, _code_hash) = compile_module::<Test>("gas_breakdown").unwrap();
+
+	ExtBuilder::default().existential_deposit(50).build().execute_with(|| {
+		let _ = Balances::deposit_creating(&ALICE, 1_000_000);
+		let addr = Contracts::bare_instantiate(
+			ALICE,
+			30_000,
+			GAS_LIMIT,
+			None,
+			Code::Upload(wasm),
+			vec![],
+			vec![],
+			false,
+			DryRunOptions::default(),
+		)
+		.result
+		.unwrap()
+		.account_id;
+
+		let call = |writes: u32, hashes: u32, debug: bool| {
+			let result = Contracts::bare_call(
+				ALICE,
+				addr.clone(),
+				0,
+				GAS_LIMIT,
+				None,
+				(writes, hashes).encode(),
+				debug,
+				Determinism::Deterministic,
+				DryRunOptions::default(),
+			);
+			assert_ok!(&result.result);
+			result
+		};
+		let consumed = |breakdown: &[(GasCategory, Weight)], category| {
+			breakdown.iter().find(|(c, _)| *c == category).unwrap().1.ref_time()
+		};
+
+		// Nothing is collected unless debugging. This also creates the storage items, so that
+		// the following calls overwrite them and are charged the same.
+		let result = call(2, 0, false);
+		assert_eq!(result.gas_breakdown, None);
+
+		let storage_only = call(2, 0, true);
+		let breakdown = storage_only.gas_breakdown.unwrap();
+		assert_eq!(breakdown.iter().map(|(c, _)| *c).collect::<Vec<_>>(), GasCategory::ALL);
+		assert!(consumed(&breakdown, GasCategory::Instructions) > 0);
+		assert!(consumed(&breakdown, GasCategory::Storage) > 0);
+		assert_eq!(consumed(&breakdown, GasCategory::Crypto), 0);
+		assert_eq!(consumed(&breakdown, GasCategory::Calls), 0);
+		assert!(consumed(&breakdown, GasCategory::Other) > 0);
+
+		// the breakdown accounts for all of the consumed gas, and collecting it is free.
+		assert_eq!(
+			breakdown.iter().map(|(_, w)| w.ref_time()).sum::<u64>(),
+			storage_only.gas_consumed.ref_time(),
+		);
+		assert_eq!(call(2, 0, false).gas_consumed, storage_only.gas_consumed);
+
+		// hashing is accounted to its own category, and does not change the storage costs.
+		let hashing = call(2, 3, true).gas_breakdown.unwrap();
+		let more_hashing = call(2, 6, true).gas_breakdown.unwrap();
+		assert_eq!(
+			consumed(&hashing, GasCategory::Storage),
+			consumed(&breakdown, GasCategory::Storage),
+		);
+		assert!(consumed(&hashing, GasCategory::Crypto) > 0);
+		assert_eq!(
+			consumed(&more_hashing, GasCategory::Crypto),
+			2 * consumed(&hashing, GasCategory::Crypto),
+		);
+		assert!(
+			consumed(&hashing, GasCategory::Instructions) >
+				consumed(&breakdown, GasCategory::Instructions)
+		);
+	});
+}
+
+#[test]
+fn contract_address_is_computed_without_instantiating() {
+	let (wasm, code_hash) = compile_module::<Test>("dummy").unwrap();
+
+	ExtBuilder::default().existential_deposit(100).build().execute_with(|| {
+		let _ = Balances::deposit_creating(&ALICE, 1_000_000);
+		let input = vec![1, 2, 3];
+		let salt = vec![4, 5, 6];
+
+		// The code doesn't need to exist and no state is touched.
+		let root = sp_io::storage::root(sp_runtime::StateVersion::V1);
+		let addr = Contracts::contract_address(&ALICE, &code_hash, &input, &salt);
+		assert_eq!(sp_io::storage::root(sp_runtime::StateVersion::V1), root);
+		assert!(!crate::PristineCode::<Test>::contains_key(&code_hash));
+
+		// It is the address the contract is actually instantiated at.
+		let result = Contracts::bare_instantiate(
+			ALICE,
+			0,
+			GAS_LIMIT,
+			None,
+			Code::Upload(wasm),
+			input.clone(),
+			salt.clone(),
+			false,
+			DryRunOptions::default(),
+		)
+		.result
+		.unwrap();
+		assert_eq!(result.account_id, addr);
+
+		// Which depends on the input and salt.
+		assert_ne!(Contracts::contract_address(&ALICE, &code_hash, &[], &salt), addr);
+		assert_ne!(Contracts::contract_address(&ALICE, &code_hash, &input, &[]), addr);
+	});
+}
+
+#[test]
+fn supported_host_functions_follow_unstable_interface() {
+	let supported = |name: &str, version, stable| {
+		Contracts::supported_host_functions().contains(&HostFunction {
+			name: name.as_bytes().to_vec(),
+			version,
+			stable,
+		})
+	};
+
+	ExtBuilder::default().build().execute_with(|| {
+		// Both versions are listed, along with the prefixed alias of the stable one.
+		assert!(supported("weight_to_fee", 0, true));
+		assert!(supported("seal_weight_to_fee", 0, true));
+		assert!(supported("weight_to_fee", 1, false));
+		assert!(!supported("seal_weight_to_fee", 1, false));
+
+		// Functions that uploaded code may not import are never listed.
+		assert!(!supported("gas", 0, true));
+		assert!(!supported("transfer", 0, true));
+		assert!(!supported("seal_transfer", 0, true));
+
+		// Unstable functions are gone once the unstable interface is disabled.
+		UnstableInterface::set(false);
+		assert!(supported("weight_to_fee", 0, true));
+		assert!(supported("seal_weight_to_fee", 0, true));
+		assert!(!supported("weight_to_fee", 1, false));
+		assert!(Contracts::supported_host_functions().iter().all(|f| f.stable));
+	});
+}
+
 #[test]
 fn deploy_and_call_other_contract() {
 	let (caller_wasm, _caller_code_hash) = compile_module::<Test>("caller_contract").unwrap();
@@ -837,6 +1298,7 @@ fn deploy_and_call_other_contract() {
 			vec![],
 			vec![],
 			false,
+			DryRunOptions::default(),
 		)
 		.result
 		.unwrap()
@@ -850,6 +1312,7 @@ fn deploy_and_call_other_contract() {
 			0u32.to_le_bytes().encode(),
 			vec![42],
 			false,
+			DryRunOptions::default(),
 		)
 		.result
 		.unwrap();
@@ -941,6 +1404,7 @@ fn deploy_and_call_other_contract() {
 					event: RuntimeEvent::Contracts(crate::Event::Called {
 						caller: caller_addr.clone(),
 						contract: callee_addr.clone(),
+						selector: None,
 					}),
 					topics: vec![hash(&caller_addr), hash(&callee_addr)],
 				},
@@ -949,6 +1413,7 @@ fn deploy_and_call_other_contract() {
 					event: RuntimeEvent::Contracts(crate::Event::Called {
 						caller: ALICE,
 						contract: caller_addr.clone(),
+						selector: None,
 					}),
 					topics: vec![hash(&ALICE), hash(&caller_addr)],
 				},
@@ -975,6 +1440,7 @@ fn delegate_call() {
 			vec![],
 			vec![],
 			false,
+			DryRunOptions::default(),
 		)
 		.result
 		.unwrap()
@@ -1003,6 +1469,8 @@ fn cannot_self_destruct_through_draning() {
 	let (wasm, _code_hash) = compile_module::<Test>("drain").unwrap();
 	ExtBuilder::default().existential_deposit(200).build().execute_with(|| {
 		let _ = Balances::deposit_creating(&ALICE, 1_000_000);
+		// `drain` uses the deprecated `seal_transfer`.
+		let code_hash = upload_legacy_code(&ALICE, wasm);
 
 		// Instantiate the BOB contract.
 		let addr = Contracts::bare_instantiate(
@@ -1010,10 +1478,11 @@ fn cannot_self_destruct_through_draning() {
 			1_000,
 			GAS_LIMIT,
 			None,
-			Code::Upload(wasm),
+			Code::Existing(code_hash),
 			vec![],
 			vec![],
 			false,
+			DryRunOptions::default(),
 		)
 		.result
 		.unwrap()
@@ -1058,6 +1527,7 @@ fn cannot_self_destruct_through_storage_refund_after_price_change() {
 			vec![],
 			vec![],
 			false,
+			DryRunOptions::default(),
 		)
 		.result
 		.unwrap()
@@ -1116,6 +1586,7 @@ fn cannot_self_destruct_by_refund_after_slash() {
 			vec![],
 			vec![],
 			false,
+			DryRunOptions::default(),
 		)
 		.result
 		.unwrap()
@@ -1168,6 +1639,7 @@ fn cannot_self_destruct_by_refund_after_slash() {
 					event: RuntimeEvent::Contracts(crate::Event::Called {
 						caller: ALICE,
 						contract: addr.clone(),
+						selector: None,
 					}),
 					topics: vec![hash(&ALICE), hash(&addr)],
 				},
@@ -1202,6 +1674,7 @@ fn cannot_self_destruct_while_live() {
 			vec![],
 			vec![],
 			false,
+			DryRunOptions::default(),
 		)
 		.result
 		.unwrap()
@@ -1246,6 +1719,7 @@ fn self_destruct_works() {
 			vec![],
 			vec![],
 			false,
+			DryRunOptions::default(),
 		)
 		.result
 		.unwrap()
@@ -1270,18 +1744,34 @@ fn self_destruct_works() {
 		assert!(get_contract_checked(&addr).is_none());
 		assert_eq!(Balances::total_balance(&addr), 0);
 
-		// check that the beneficiary (django) got remaining balance
-		assert_eq!(Balances::free_balance(DJANGO), 1_000_000 + 100_000);
+		// check that the beneficiary (django) got the remaining balance including the
+		// storage deposit that was released on termination
+		assert_eq!(Balances::free_balance(DJANGO), 1_000_000 + 100_000 + 1_000);
 
 		pretty_assertions::assert_eq!(
 			System::events(),
 			vec![
+				EventRecord {
+					phase: Phase::Initialization,
+					event: RuntimeEvent::Balances(pallet_balances::Event::Unreserved {
+						who: addr.clone(),
+						amount: 1_000,
+					}),
+					topics: vec![],
+				},
+				EventRecord {
+					phase: Phase::Initialization,
+					event: RuntimeEvent::System(frame_system::Event::KilledAccount {
+						account: addr.clone()
+					}),
+					topics: vec![],
+				},
 				EventRecord {
 					phase: Phase::Initialization,
 					event: RuntimeEvent::Balances(pallet_balances::Event::Transfer {
 						from: addr.clone(),
 						to: DJANGO,
-						amount: 100_000,
+						amount: 101_000,
 					}),
 					topics: vec![],
 				},
@@ -1289,7 +1779,8 @@ fn self_destruct_works() {
 					phase: Phase::Initialization,
 					event: RuntimeEvent::Contracts(crate::Event::Terminated {
 						contract: addr.clone(),
-						beneficiary: DJANGO
+						beneficiary: DJANGO,
+						outcome: TerminationOutcome::Transferred(101_000),
 					}),
 					topics: vec![hash(&addr), hash(&DJANGO)],
 				},
@@ -1298,42 +1789,123 @@ fn self_destruct_works() {
 					event: RuntimeEvent::Contracts(crate::Event::Called {
 						caller: ALICE,
 						contract: addr.clone(),
+						selector: None,
 					}),
 					topics: vec![hash(&ALICE), hash(&addr)],
 				},
-				EventRecord {
-					phase: Phase::Initialization,
-					event: RuntimeEvent::System(frame_system::Event::KilledAccount {
-						account: addr.clone()
-					}),
-					topics: vec![],
-				},
-				EventRecord {
-					phase: Phase::Initialization,
-					event: RuntimeEvent::Balances(pallet_balances::Event::ReserveRepatriated {
-						from: addr.clone(),
-						to: ALICE,
-						amount: 1_000,
-						destination_status: BalanceStatus::Free,
-					}),
-					topics: vec![],
-				},
 			],
 		);
 	});
 }
 
-// This tests that one contract cannot prevent another from self-destructing by sending it
-// additional funds after it has been drained.
 #[test]
-fn destroy_contract_and_transfer_funds() {
-	let (callee_wasm, callee_code_hash) = compile_module::<Test>("self_destruct").unwrap();
-	let (caller_wasm, _caller_code_hash) = compile_module::<Test>("destroy_and_transfer").unwrap();
+fn self_destruct_to_new_beneficiary_above_ed_works() {
+	let (wasm, _code_hash) = compile_module::<Test>("self_destruct").unwrap();
+	ExtBuilder::default().existential_deposit(1_000).build().execute_with(|| {
+		let _ = Balances::deposit_creating(&ALICE, 1_000_000);
+		let addr = Contracts::bare_instantiate(
+			ALICE,
+			100_000,
+			GAS_LIMIT,
+			None,
+			Code::Upload(wasm),
+			vec![],
+			vec![],
+			false,
+			DryRunOptions::default(),
+		)
+		.result
+		.unwrap()
+		.account_id;
+
+		// django doesn't exist but receives more than the existential deposit.
+		assert!(!System::account_exists(&DJANGO));
+		assert_ok!(Contracts::call(
+			RuntimeOrigin::signed(ALICE),
+			addr.clone(),
+			0,
+			GAS_LIMIT,
+			None,
+			vec![]
+		));
+
+		assert!(get_contract_checked(&addr).is_none());
+		assert_eq!(Balances::free_balance(DJANGO), 101_000);
+		assert_eq!(TerminationDust::get(), 0);
+		assert!(System::events().iter().any(|r| r.event ==
+			RuntimeEvent::Contracts(crate::Event::Terminated {
+				contract: addr.clone(),
+				beneficiary: DJANGO,
+				outcome: TerminationOutcome::Transferred(101_000),
+			})));
+	});
+}
+
+#[test]
+fn self_destruct_to_new_beneficiary_below_ed_is_dust() {
+	let (wasm, _code_hash) = compile_module::<Test>("self_destruct").unwrap();
+	ExtBuilder::default().existential_deposit(1).build().execute_with(|| {
+		let _ = Balances::deposit_creating(&ALICE, 1_000_000);
+		let addr = Contracts::bare_instantiate(
+			ALICE,
+			5,
+			GAS_LIMIT,
+			None,
+			Code::Upload(wasm),
+			vec![],
+			vec![],
+			false,
+			DryRunOptions::default(),
+		)
+		.result
+		.unwrap()
+		.account_id;
+
+		// The existential deposit was raised above the total balance of the contract which is
+		// kept alive by its storage deposit. Releasing the deposit on termination is therefore
+		// not enough to pull the beneficiary into existence.
+		let total = Balances::total_balance(&addr);
+		ExistentialDeposit::set(total + 1);
+		assert_eq!(Balances::free_balance(&addr), 5);
+		assert!(Balances::reserved_balance(&addr) >= 10);
+
+		// django doesn't exist and would receive less than the existential deposit.
+		assert!(!System::account_exists(&DJANGO));
+		assert_ok!(Contracts::call(
+			RuntimeOrigin::signed(ALICE),
+			addr.clone(),
+			0,
+			GAS_LIMIT,
+			None,
+			vec![]
+		));
+
+		// The remaining balance was handed to the dust handler instead.
+		assert!(get_contract_checked(&addr).is_none());
+		assert!(!System::account_exists(&DJANGO));
+		assert_eq!(TerminationDust::get(), total);
+		assert!(System::events().iter().any(|r| r.event ==
+			RuntimeEvent::Contracts(crate::Event::Terminated {
+				contract: addr.clone(),
+				beneficiary: DJANGO,
+				outcome: TerminationOutcome::Dust(total),
+			})));
+	});
+}
+
+// This tests that one contract cannot prevent another from self-destructing by sending it
+// additional funds after it has been drained.
+#[test]
+fn destroy_contract_and_transfer_funds() {
+	let (callee_wasm, callee_code_hash) = compile_module::<Test>("self_destruct").unwrap();
+	let (caller_wasm, _caller_code_hash) = compile_module::<Test>("destroy_and_transfer").unwrap();
 
 	ExtBuilder::default().existential_deposit(50).build().execute_with(|| {
 		// Create code hash for bob to instantiate
 		let _ = Balances::deposit_creating(&ALICE, 1_000_000);
 		Contracts::bare_upload_code(ALICE, callee_wasm, None, Determinism::Deterministic).unwrap();
+		// `destroy_and_transfer` uses the deprecated `seal_transfer`.
+		let caller_code_hash = upload_legacy_code(&ALICE, caller_wasm);
 
 		// This deploys the BOB contract, which in turn deploys the CHARLIE contract during
 		// construction.
@@ -1342,10 +1914,11 @@ fn destroy_contract_and_transfer_funds() {
 			200_000,
 			GAS_LIMIT,
 			None,
-			Code::Upload(caller_wasm),
+			Code::Existing(caller_code_hash),
 			callee_code_hash.as_ref().to_vec(),
 			vec![],
 			false,
+			DryRunOptions::default(),
 		)
 		.result
 		.unwrap()
@@ -1410,6 +1983,7 @@ fn crypto_hashes() {
 			vec![],
 			vec![],
 			false,
+			DryRunOptions::default(),
 		)
 		.result
 		.unwrap()
@@ -1444,6 +2018,7 @@ fn crypto_hashes() {
 				params,
 				false,
 				Determinism::Deterministic,
+				DryRunOptions::default(),
 			)
 			.result
 			.unwrap();
@@ -1460,16 +2035,19 @@ fn transfer_return_code() {
 	ExtBuilder::default().existential_deposit(50).build().execute_with(|| {
 		let min_balance = <Test as Config>::Currency::minimum_balance();
 		let _ = Balances::deposit_creating(&ALICE, 1000 * min_balance);
+		// `seal_transfer` is deprecated, but code that was uploaded before can still call it.
+		let code_hash = upload_legacy_code(&ALICE, wasm);
 
 		let addr = Contracts::bare_instantiate(
 			ALICE,
 			min_balance * 100,
 			GAS_LIMIT,
 			None,
-			Code::Upload(wasm),
+			Code::Existing(code_hash),
 			vec![],
 			vec![],
 			false,
+			DryRunOptions::default(),
 		)
 		.result
 		.unwrap()
@@ -1486,6 +2064,7 @@ fn transfer_return_code() {
 			vec![],
 			false,
 			Determinism::Deterministic,
+			DryRunOptions::default(),
 		)
 		.result
 		.unwrap();
@@ -1505,10 +2084,102 @@ fn transfer_return_code() {
 			vec![],
 			false,
 			Determinism::Deterministic,
+			DryRunOptions::default(),
+		)
+		.result
+		.unwrap();
+		assert_return_code!(result, RuntimeReturnCode::TransferFailed);
+	});
+}
+
+#[test]
+fn new_code_cannot_import_transfer() {
+	let (wasm, _code_hash) = compile_module::<Test>("transfer_return_code").unwrap();
+	ExtBuilder::default().existential_deposit(50).build().execute_with(|| {
+		let min_balance = <Test as Config>::Currency::minimum_balance();
+		let _ = Balances::deposit_creating(&ALICE, 1000 * min_balance);
+
+		assert_eq!(
+			Contracts::bare_upload_code(ALICE, wasm.clone(), None, Determinism::Deterministic),
+			Err(CodeUploadError {
+				error: <Error<Test>>::CodeRejected.into(),
+				validation_error: Some(ValidationError::ForbiddenImport {
+					name: b"seal_transfer".to_vec(),
+				}),
+			}),
+		);
+		assert_err_ignore_postinfo!(
+			Contracts::instantiate_with_code(
+				RuntimeOrigin::signed(ALICE),
+				min_balance * 100,
+				GAS_LIMIT,
+				None,
+				wasm,
+				vec![],
+				vec![],
+			),
+			<Error<Test>>::CodeRejected,
+		);
+	});
+}
+
+#[test]
+fn call_without_input_transfers_to_plain_account() {
+	let (wasm, _code_hash) = compile_module::<Test>("call_transfer_return_code").unwrap();
+	ExtBuilder::default().existential_deposit(50).build().execute_with(|| {
+		let min_balance = <Test as Config>::Currency::minimum_balance();
+		let _ = Balances::deposit_creating(&ALICE, 1000 * min_balance);
+		let zero = AccountId32::from([0u8; 32]);
+
+		let addr = Contracts::bare_instantiate(
+			ALICE,
+			min_balance * 100,
+			GAS_LIMIT,
+			None,
+			Code::Upload(wasm),
+			vec![],
+			vec![],
+			false,
+			DryRunOptions::default(),
+		)
+		.result
+		.unwrap()
+		.account_id;
+
+		// The zero account is no contract: The value is transferred to it.
+		let result = Contracts::bare_call(
+			ALICE,
+			addr.clone(),
+			0,
+			GAS_LIMIT,
+			None,
+			vec![],
+			false,
+			Determinism::Deterministic,
+			DryRunOptions::default(),
+		)
+		.result
+		.unwrap();
+		assert_return_code!(result, RuntimeReturnCode::Success);
+		assert_eq!(<Test as Config>::Currency::free_balance(&zero), 100);
+
+		// Contract has only the minimal balance so the transfer fails like `seal_transfer` would.
+		Balances::make_free_balance_be(&addr, min_balance);
+		let result = Contracts::bare_call(
+			ALICE,
+			addr,
+			0,
+			GAS_LIMIT,
+			None,
+			vec![],
+			false,
+			Determinism::Deterministic,
+			DryRunOptions::default(),
 		)
 		.result
 		.unwrap();
 		assert_return_code!(result, RuntimeReturnCode::TransferFailed);
+		assert_eq!(<Test as Config>::Currency::free_balance(&zero), 100);
 	});
 }
 
@@ -1530,6 +2201,7 @@ fn call_return_code() {
 			vec![0],
 			vec![],
 			false,
+			DryRunOptions::default(),
 		)
 		.result
 		.unwrap()
@@ -1546,6 +2218,7 @@ fn call_return_code() {
 			AsRef::<[u8]>::as_ref(&DJANGO).to_vec(),
 			false,
 			Determinism::Deterministic,
+			DryRunOptions::default(),
 		)
 		.result
 		.unwrap();
@@ -1560,6 +2233,7 @@ fn call_return_code() {
 			vec![0],
 			vec![],
 			false,
+			DryRunOptions::default(),
 		)
 		.result
 		.unwrap()
@@ -1580,6 +2254,7 @@ fn call_return_code() {
 				.collect(),
 			false,
 			Determinism::Deterministic,
+			DryRunOptions::default(),
 		)
 		.result
 		.unwrap();
@@ -1603,6 +2278,7 @@ fn call_return_code() {
 				.collect(),
 			false,
 			Determinism::Deterministic,
+			DryRunOptions::default(),
 		)
 		.result
 		.unwrap();
@@ -1623,6 +2299,7 @@ fn call_return_code() {
 				.collect(),
 			false,
 			Determinism::Deterministic,
+			DryRunOptions::default(),
 		)
 		.result
 		.unwrap();
@@ -1642,6 +2319,7 @@ fn call_return_code() {
 				.collect(),
 			false,
 			Determinism::Deterministic,
+			DryRunOptions::default(),
 		)
 		.result
 		.unwrap();
@@ -1650,41 +2328,309 @@ fn call_return_code() {
 }
 
 #[test]
-fn instantiate_return_code() {
-	let (caller_code, _caller_hash) = compile_module::<Test>("instantiate_return_code").unwrap();
-	let (callee_code, callee_hash) = compile_module::<Test>("ok_trap_revert").unwrap();
+fn trap_events_work() {
+	let (caller_code, _caller_hash) = compile_module::<Test>("call_return_code").unwrap();
+	let (callee_code, _callee_hash) = compile_module::<Test>("ok_trap_revert").unwrap();
+	let (limit_code, _limit_hash) = compile_module::<Test>("call_with_limit").unwrap();
+	let (oog_code, _oog_hash) = compile_module::<Test>("run_out_of_gas").unwrap();
 	ExtBuilder::default().existential_deposit(50).build().execute_with(|| {
 		let min_balance = <Test as Config>::Currency::minimum_balance();
 		let _ = Balances::deposit_creating(&ALICE, 1000 * min_balance);
-		let _ = Balances::deposit_creating(&CHARLIE, 1000 * min_balance);
-		let callee_hash = callee_hash.as_ref().to_vec();
 
-		assert_ok!(Contracts::instantiate_with_code(
-			RuntimeOrigin::signed(ALICE),
-			min_balance * 100,
-			GAS_LIMIT,
-			None,
-			callee_code,
-			vec![],
-			vec![],
-		));
+		let instantiate = |code, salt| {
+			Contracts::bare_instantiate(
+				ALICE,
+				min_balance * 100,
+				GAS_LIMIT,
+				None,
+				Code::Upload(code),
+				vec![0],
+				vec![salt],
+				false,
+				DryRunOptions::default(),
+			)
+			.result
+			.unwrap()
+			.account_id
+		};
+		let addr_caller = instantiate(caller_code, 0);
+		let addr_callee = instantiate(callee_code, 1);
+		let addr_limit = instantiate(limit_code, 2);
+		let addr_oog = instantiate(oog_code, 3);
+
+		let trap_events = || {
+			System::events()
+				.into_iter()
+				.filter_map(|record| match record.event {
+					RuntimeEvent::Contracts(crate::Event::ContractTrapped { contract, reason }) =>
+						Some((contract, reason)),
+					_ => None,
+				})
+				.collect::<Vec<_>>()
+		};
+		let call = |dest: &AccountId32, input: Vec<u8>| {
+			Contracts::bare_call(
+				ALICE,
+				dest.clone(),
+				0,
+				GAS_LIMIT,
+				None,
+				input,
+				false,
+				Determinism::Deterministic,
+				DryRunOptions::default(),
+			)
+			.result
+			.unwrap()
+		};
+		// The callee traps because "2" is passed.
+		let trap_input: Vec<u8> = AsRef::<[u8]>::as_ref(&addr_callee)
+			.iter()
+			.chain(&2u32.to_le_bytes())
+			.cloned()
+			.collect();
+		// The callee runs out of the gas that is devoted to the sub-call.
+		let oog_input: Vec<u8> = AsRef::<[u8]>::as_ref(&addr_oog)
+			.iter()
+			.cloned()
+			.chain((GAS_LIMIT / 5).ref_time().to_le_bytes())
+			.collect();
+
+		// No events are emitted if disabled.
+		assert!(!EmitTrapEvents::get());
+		assert_return_code!(
+			call(&addr_caller, trap_input.clone()),
+			RuntimeReturnCode::CalleeTrapped
+		);
+		assert_return_code!(call(&addr_limit, oog_input.clone()), RuntimeReturnCode::CalleeTrapped);
+		assert_eq!(trap_events(), vec![]);
+
+		// Once enabled, the failed sub-calls are reported with their reason.
+		EmitTrapEvents::set(true);
+		assert_return_code!(
+			call(&addr_caller, trap_input.clone()),
+			RuntimeReturnCode::CalleeTrapped
+		);
+		assert_eq!(trap_events(), vec![(addr_callee.clone(), TrapCategory::Trapped)]);
+		assert_return_code!(call(&addr_limit, oog_input), RuntimeReturnCode::CalleeTrapped);
+		assert_eq!(
+			trap_events(),
+			vec![(addr_callee, TrapCategory::Trapped), (addr_oog, TrapCategory::OutOfGas)]
+		);
+
+		// The event is paid for by the caller of the failed contract.
+		let gas_consumed = |input| {
+			Contracts::bare_call(
+				ALICE,
+				addr_caller.clone(),
+				0,
+				GAS_LIMIT,
+				None,
+				input,
+				false,
+				Determinism::Deterministic,
+			)
+			.gas_consumed
+		};
+		let with_event = gas_consumed(trap_input.clone());
+		EmitTrapEvents::set(false);
+		let without_event = gas_consumed(trap_input);
+		assert!(with_event.ref_time() > without_event.ref_time());
+	});
+}
+
+#[test]
+fn call_reports_callee_error() {
+	use codec::Decode;
+
+	let (caller_code, _caller_hash) = compile_module::<Test>("call_report_error").unwrap();
+	let (trap_code, _trap_hash) = compile_module::<Test>("ok_trap_revert").unwrap();
+	let (revert_code, _revert_hash) = compile_module::<Test>("return_with_data").unwrap();
+	let (oog_code, _oog_hash) = compile_module::<Test>("run_out_of_gas").unwrap();
+	ExtBuilder::default().existential_deposit(50).build().execute_with(|| {
+		let min_balance = <Test as Config>::Currency::minimum_balance();
+		let _ = Balances::deposit_creating(&ALICE, 1000 * min_balance);
+
+		let instantiate = |code, salt| {
+			Contracts::bare_instantiate(
+				ALICE,
+				min_balance * 100,
+				GAS_LIMIT,
+				None,
+				Code::Upload(code),
+				vec![0; 4],
+				vec![salt],
+				false,
+				DryRunOptions::default(),
+			)
+			.result
+			.unwrap()
+			.account_id
+		};
+		let addr_caller = instantiate(caller_code, 0);
+		let addr_trap = instantiate(trap_code, 1);
+		let addr_revert = instantiate(revert_code, 2);
+		let addr_oog = instantiate(oog_code, 3);
+
+		// Returns the return code of the call and the contents of the output buffer.
+		let call = |flags: CallFlags, gas: u64, callee: &AccountId32, input: &[u8]| {
+			let input =
+				(flags.bits(), gas, callee).encode().into_iter().chain(input.iter().cloned());
+			let data = Contracts::bare_call(
+				ALICE,
+				addr_caller.clone(),
+				0,
+				GAS_LIMIT,
+				None,
+				input.collect(),
+				false,
+				Determinism::Deterministic,
+				DryRunOptions::default(),
+			)
+			.result
+			.unwrap()
+			.data;
+			(u32::from_le_bytes(data[..4].try_into().unwrap()), data[4..].to_vec())
+		};
+		let module_error = |error: Error<Test>| match DispatchError::from(error) {
+			DispatchError::Module(error) => error,
+			_ => unreachable!(),
+		};
+		let report = CallFlags::REPORT_CALLEE_ERROR;
+
+		// A revert passes the data returned by the callee, regardless of the flag.
+		for flags in [CallFlags::empty(), report] {
+			assert_eq!(
+				call(flags, 0, &addr_revert, &[1, 0, 0, 0, 42, 43]),
+				(RuntimeReturnCode::CalleeReverted as u32, vec![42, 43])
+			);
+		}
+
+		// A trap leaves the output buffer untouched without the flag.
+		assert_eq!(
+			call(CallFlags::empty(), 0, &addr_trap, &[2]),
+			(RuntimeReturnCode::CalleeTrapped as u32, vec![0; 256])
+		);
+
+		// With the flag the error of the callee is reported.
+		let (code, output) = call(report, 0, &addr_trap, &[2]);
+		assert_eq!(code, RuntimeReturnCode::CalleeTrapped as u32);
+		assert_eq!(
+			CalleeError::decode(&mut &output[..]).unwrap(),
+			CalleeError {
+				category: TrapCategory::Trapped,
+				module_error: Some(module_error(Error::<Test>::ContractTrapped)),
+			}
+		);
+
+		// Running out of the gas devoted to the call is reported as well.
+		let (code, output) = call(report, (GAS_LIMIT / 5).ref_time(), &addr_oog, &[]);
+		assert_eq!(code, RuntimeReturnCode::CalleeTrapped as u32);
+		assert_eq!(
+			CalleeError::decode(&mut &output[..]).unwrap(),
+			CalleeError {
+				category: TrapCategory::OutOfGas,
+				module_error: Some(module_error(Error::<Test>::OutOfGas)),
+			}
+		);
+
+		// A successful call is not affected.
+		assert_eq!(call(report, 0, &addr_trap, &[0]), (RuntimeReturnCode::Success as u32, vec![]));
+	});
+}
 
+#[test]
+fn call_selectors_in_events_work() {
+	let (wasm, _code_hash) = compile_module::<Test>("dummy").unwrap();
+	ExtBuilder::default().existential_deposit(50).build().execute_with(|| {
+		let _ = Balances::deposit_creating(&ALICE, 1_000_000);
 		let addr = Contracts::bare_instantiate(
 			ALICE,
-			min_balance * 100,
+			0,
 			GAS_LIMIT,
 			None,
-			Code::Upload(caller_code),
+			Code::Upload(wasm),
 			vec![],
 			vec![],
 			false,
+			DryRunOptions::default(),
 		)
 		.result
 		.unwrap()
 		.account_id;
 
-		// Contract has only the minimal balance so any transfer will fail.
-		Balances::make_free_balance_be(&addr, min_balance);
+		let called = |input: Vec<u8>| {
+			System::reset_events();
+			assert_ok!(Contracts::call(
+				RuntimeOrigin::signed(ALICE),
+				addr.clone(),
+				0,
+				GAS_LIMIT,
+				None,
+				input,
+			));
+			System::events()
+				.into_iter()
+				.filter_map(|record| match record.event {
+					RuntimeEvent::Contracts(crate::Event::Called { selector, .. }) =>
+						Some(selector),
+					_ => None,
+				})
+				.collect::<Vec<_>>()
+		};
+
+		// No selector is reported if disabled.
+		assert!(!EmitCallSelectors::get());
+		assert_eq!(called(vec![1, 2, 3, 4, 5]), vec![None]);
+
+		// Once enabled, the first four bytes of the input are reported.
+		EmitCallSelectors::set(true);
+		assert_eq!(called(vec![1, 2, 3, 4, 5]), vec![Some([1, 2, 3, 4])]);
+		assert_eq!(called(vec![1, 2, 3, 4]), vec![Some([1, 2, 3, 4])]);
+
+		// Unless the input is too short to contain a selector.
+		assert_eq!(called(vec![1, 2, 3]), vec![None]);
+		assert_eq!(called(vec![]), vec![None]);
+	});
+}
+
+#[test]
+fn instantiate_return_code() {
+	let (caller_code, _caller_hash) = compile_module::<Test>("instantiate_return_code").unwrap();
+	let (callee_code, callee_hash) = compile_module::<Test>("ok_trap_revert").unwrap();
+	ExtBuilder::default().existential_deposit(50).build().execute_with(|| {
+		let min_balance = <Test as Config>::Currency::minimum_balance();
+		let _ = Balances::deposit_creating(&ALICE, 1000 * min_balance);
+		let _ = Balances::deposit_creating(&CHARLIE, 1000 * min_balance);
+		let callee_hash = callee_hash.as_ref().to_vec();
+
+		assert_ok!(Contracts::instantiate_with_code(
+			RuntimeOrigin::signed(ALICE),
+			min_balance * 100,
+			GAS_LIMIT,
+			None,
+			callee_code,
+			vec![],
+			vec![],
+		));
+
+		let addr = Contracts::bare_instantiate(
+			ALICE,
+			min_balance * 100,
+			GAS_LIMIT,
+			None,
+			Code::Upload(caller_code),
+			vec![],
+			vec![],
+			false,
+			DryRunOptions::default(),
+		)
+		.result
+		.unwrap()
+		.account_id;
+
+		// Contract has only the minimal balance so any transfer will fail.
+		Balances::make_free_balance_be(&addr, min_balance);
 		let result = Contracts::bare_call(
 			ALICE,
 			addr.clone(),
@@ -1694,6 +2640,7 @@ fn instantiate_return_code() {
 			callee_hash.clone(),
 			false,
 			Determinism::Deterministic,
+			DryRunOptions::default(),
 		)
 		.result
 		.unwrap();
@@ -1713,6 +2660,7 @@ fn instantiate_return_code() {
 			callee_hash.clone(),
 			false,
 			Determinism::Deterministic,
+			DryRunOptions::default(),
 		)
 		.result
 		.unwrap();
@@ -1729,6 +2677,7 @@ fn instantiate_return_code() {
 			vec![0; 33],
 			false,
 			Determinism::Deterministic,
+			DryRunOptions::default(),
 		)
 		.result
 		.unwrap();
@@ -1744,6 +2693,7 @@ fn instantiate_return_code() {
 			callee_hash.iter().chain(&1u32.to_le_bytes()).cloned().collect(),
 			false,
 			Determinism::Deterministic,
+			DryRunOptions::default(),
 		)
 		.result
 		.unwrap();
@@ -1759,6 +2709,7 @@ fn instantiate_return_code() {
 			callee_hash.iter().chain(&2u32.to_le_bytes()).cloned().collect(),
 			false,
 			Determinism::Deterministic,
+			DryRunOptions::default(),
 		)
 		.result
 		.unwrap();
@@ -1766,6 +2717,154 @@ fn instantiate_return_code() {
 	});
 }
 
+#[test]
+fn bare_instantiate_returns_revert_data() {
+	let (wasm, _code_hash) = compile_module::<Test>("return_with_data").unwrap();
+	let (trap_wasm, _code_hash) = compile_module::<Test>("ok_trap_revert").unwrap();
+	ExtBuilder::default().existential_deposit(50).build().execute_with(|| {
+		let _ = Balances::deposit_creating(&ALICE, 1_000_000);
+
+		// Constructor reverts with the data following the exit status.
+		let result = Contracts::bare_instantiate(
+			ALICE,
+			0,
+			GAS_LIMIT,
+			None,
+			Code::Upload(wasm.clone()),
+			(1u32, [0xde, 0xad, 0xbe, 0xefu8]).encode(),
+			vec![],
+			false,
+			DryRunOptions::default(),
+		)
+		.result
+		.unwrap();
+		assert!(result.did_revert());
+		assert_eq!(result.result.data, vec![0xde, 0xad, 0xbe, 0xef]);
+		assert_eq!(result.contract_address(), None);
+		assert!(get_contract_checked(&result.account_id).is_none());
+
+		// A trapping constructor is reported as an error.
+		let result = Contracts::bare_instantiate(
+			ALICE,
+			0,
+			GAS_LIMIT,
+			None,
+			Code::Upload(trap_wasm),
+			2u32.encode(),
+			vec![],
+			false,
+			DryRunOptions::default(),
+		)
+		.result;
+		assert_err!(result, <Error<Test>>::ContractTrapped);
+
+		// Constructor succeeds and the contract exists at the returned address.
+		let result = Contracts::bare_instantiate(
+			ALICE,
+			0,
+			GAS_LIMIT,
+			None,
+			Code::Upload(wasm),
+			(0u32, [0xde, 0xad, 0xbe, 0xefu8]).encode(),
+			vec![],
+			false,
+			DryRunOptions::default(),
+		)
+		.result
+		.unwrap();
+		assert!(!result.did_revert());
+		assert_eq!(result.contract_address(), Some(&result.account_id));
+		assert!(get_contract_checked(&result.account_id).is_some());
+	});
+}
+
+#[test]
+fn instantiate_filter_works() {
+	let (caller_code, caller_hash) = compile_module::<Test>("instantiate_return_code").unwrap();
+	let (callee_code, callee_hash) = compile_module::<Test>("ok_trap_revert").unwrap();
+	ExtBuilder::default().existential_deposit(50).build().execute_with(|| {
+		let min_balance = <Test as Config>::Currency::minimum_balance();
+		let _ = Balances::deposit_creating(&ALICE, 1000 * min_balance);
+
+		// only the callee is allowed to be instantiated.
+		InstantiateAllowList::set(Some(vec![callee_hash]));
+		assert_ok!(Contracts::instantiate_with_code(
+			RuntimeOrigin::signed(ALICE),
+			min_balance * 100,
+			GAS_LIMIT,
+			None,
+			callee_code,
+			vec![],
+			vec![],
+		));
+		assert_err_ignore_postinfo!(
+			Contracts::instantiate_with_code(
+				RuntimeOrigin::signed(ALICE),
+				min_balance * 100,
+				GAS_LIMIT,
+				None,
+				caller_code.clone(),
+				vec![],
+				vec![],
+			),
+			<Error<Test>>::CodeNotAllowed,
+		);
+		// the code upload is reverted together with the failed instantiation.
+		assert!(!<CodeStorage<Test>>::contains_key(caller_hash));
+
+		// now only the caller is allowed to be instantiated.
+		InstantiateAllowList::set(Some(vec![caller_hash]));
+		let addr = Contracts::bare_instantiate(
+			ALICE,
+			min_balance * 100,
+			GAS_LIMIT,
+			None,
+			Code::Upload(caller_code),
+			vec![],
+			vec![],
+			false,
+			DryRunOptions::default(),
+		)
+		.result
+		.unwrap()
+		.account_id;
+		Balances::make_free_balance_be(&addr, min_balance + 10_000);
+
+		// the caller cannot instantiate the callee anymore.
+		let input: Vec<u8> =
+			callee_hash.as_ref().iter().chain(&0u32.to_le_bytes()).cloned().collect();
+		let result = Contracts::bare_call(
+			ALICE,
+			addr.clone(),
+			0,
+			GAS_LIMIT,
+			None,
+			input.clone(),
+			false,
+			Determinism::Deterministic,
+			DryRunOptions::default(),
+		);
+		assert_err!(result.result, <Error<Test>>::CodeNotAllowed);
+
+		// until both are allowed.
+		InstantiateAllowList::set(Some(vec![caller_hash, callee_hash]));
+		let result = Contracts::bare_call(
+			ALICE,
+			addr,
+			0,
+			GAS_LIMIT,
+			None,
+			input,
+			false,
+			Determinism::Deterministic,
+			DryRunOptions::default(),
+		)
+		.result
+		.unwrap();
+		assert_return_code!(result, RuntimeReturnCode::Success);
+	});
+}
+
 #[test]
 fn disabled_chain_extension_wont_deploy() {
 	let (code, _hash) = compile_module::<Test>("chain_extension").unwrap();
@@ -1803,6 +2902,7 @@ fn disabled_chain_extension_errors_on_call() {
 			vec![],
 			vec![],
 			false,
+			DryRunOptions::default(),
 		)
 		.result
 		.unwrap()
@@ -1830,6 +2930,7 @@ fn chain_extension_works() {
 			vec![],
 			vec![],
 			false,
+			DryRunOptions::default(),
 		)
 		.result
 		.unwrap()
@@ -1846,6 +2947,7 @@ fn chain_extension_works() {
 			input.clone(),
 			false,
 			Determinism::Deterministic,
+			DryRunOptions::default(),
 		);
 		assert_eq!(TestExtension::last_seen_buffer(), input);
 		assert_eq!(result.result.unwrap().data, input);
@@ -1860,6 +2962,7 @@ fn chain_extension_works() {
 			ExtensionInput { extension_id: 0, func_id: 1, extra: &[] }.into(),
 			false,
 			Determinism::Deterministic,
+			DryRunOptions::default(),
 		)
 		.result
 		.unwrap();
@@ -1876,6 +2979,7 @@ fn chain_extension_works() {
 			ExtensionInput { extension_id: 0, func_id: 2, extra: &[0] }.into(),
 			false,
 			Determinism::Deterministic,
+			DryRunOptions::default(),
 		);
 		assert_ok!(result.result);
 		let gas_consumed = result.gas_consumed;
@@ -1888,6 +2992,7 @@ fn chain_extension_works() {
 			ExtensionInput { extension_id: 0, func_id: 2, extra: &[42] }.into(),
 			false,
 			Determinism::Deterministic,
+			DryRunOptions::default(),
 		);
 		assert_ok!(result.result);
 		assert_eq!(result.gas_consumed.ref_time(), gas_consumed.ref_time() + 42);
@@ -1900,10 +3005,29 @@ fn chain_extension_works() {
 			ExtensionInput { extension_id: 0, func_id: 2, extra: &[95] }.into(),
 			false,
 			Determinism::Deterministic,
+			DryRunOptions::default(),
 		);
 		assert_ok!(result.result);
 		assert_eq!(result.gas_consumed.ref_time(), gas_consumed.ref_time() + 95);
 
+		// 4 = pre-charge 1000 of weight and adjust it down to the amount supplied in the fifth
+		// byte afterwards
+		let result = Contracts::bare_call(
+			ALICE,
+			addr.clone(),
+			0,
+			GAS_LIMIT,
+			None,
+			ExtensionInput { extension_id: 0, func_id: 4, extra: &[100] }.into(),
+			false,
+			Determinism::Deterministic,
+			DryRunOptions::default(),
+		);
+		assert_ok!(result.result);
+		assert_eq!(result.gas_consumed.ref_time(), gas_consumed.ref_time() + 100);
+		// the pre-charged amount still needs to be available when executing
+		assert!(result.gas_required.ref_time() >= gas_consumed.ref_time() + 1000);
+
 		// 3 = diverging chain extension call that sets flags to 0x1 and returns a fixed buffer
 		let result = Contracts::bare_call(
 			ALICE,
@@ -1914,6 +3038,7 @@ fn chain_extension_works() {
 			ExtensionInput { extension_id: 0, func_id: 3, extra: &[] }.into(),
 			false,
 			Determinism::Deterministic,
+			DryRunOptions::default(),
 		)
 		.result
 		.unwrap();
@@ -1932,6 +3057,7 @@ fn chain_extension_works() {
 			ExtensionInput { extension_id: 1, func_id: 0, extra: &[] }.into(),
 			false,
 			Determinism::Deterministic,
+			DryRunOptions::default(),
 		)
 		.result
 		.unwrap();
@@ -1969,6 +3095,7 @@ fn chain_extension_temp_storage_works() {
 			vec![],
 			vec![],
 			false,
+			DryRunOptions::default(),
 		)
 		.result
 		.unwrap()
@@ -1992,7 +3119,8 @@ fn chain_extension_temp_storage_works() {
 				None,
 				input.clone(),
 				false,
-				Determinism::Deterministic
+				Determinism::Deterministic,
+				DryRunOptions::default()
 			)
 			.result
 		);
@@ -2015,6 +3143,7 @@ fn lazy_removal_works() {
 			vec![],
 			vec![],
 			false,
+			DryRunOptions::default(),
 		)
 		.result
 		.unwrap()
@@ -2087,6 +3216,7 @@ fn lazy_batch_removal_works() {
 				vec![],
 				vec![i],
 				false,
+				DryRunOptions::default(),
 			)
 			.result
 			.unwrap()
@@ -2152,6 +3282,7 @@ fn lazy_removal_partial_remove_works() {
 			vec![],
 			vec![],
 			false,
+			DryRunOptions::default(),
 		)
 		.result
 		.unwrap()
@@ -2267,6 +3398,7 @@ fn lazy_removal_does_no_run_on_low_remaining_weight() {
 			vec![],
 			vec![],
 			false,
+			DryRunOptions::default(),
 		)
 		.result
 		.unwrap()
@@ -2338,6 +3470,7 @@ fn lazy_removal_does_not_use_all_weight() {
 			vec![],
 			vec![],
 			false,
+			DryRunOptions::default(),
 		)
 		.result
 		.unwrap()
@@ -2421,6 +3554,7 @@ fn deletion_queue_full() {
 			vec![],
 			vec![],
 			false,
+			DryRunOptions::default(),
 		)
 		.result
 		.unwrap()
@@ -2440,6 +3574,56 @@ fn deletion_queue_full() {
 	});
 }
 
+#[test]
+fn instantiate_with_code_reuses_existing_code() {
+	let (wasm, code_hash) = compile_module::<Test>("dummy").unwrap();
+	let wasm_len = wasm.len() as u32;
+	ExtBuilder::default().existential_deposit(50).build().execute_with(|| {
+		let _ = Balances::deposit_creating(&ALICE, 1_000_000);
+		let _ = Balances::deposit_creating(&BOB, 1_000_000);
+
+		let first = Contracts::instantiate_with_code(
+			RuntimeOrigin::signed(ALICE),
+			0,
+			GAS_LIMIT,
+			None,
+			wasm.clone(),
+			vec![],
+			vec![],
+		)
+		.unwrap();
+		let metadata = Contracts::code_metadata(code_hash).unwrap();
+		assert_eq!(metadata.owner, ALICE);
+		let alice_reserved = Balances::reserved_balance(&ALICE);
+
+		// The same blob is instantiated by someone else.
+		let second = Contracts::instantiate_with_code(
+			RuntimeOrigin::signed(BOB),
+			0,
+			GAS_LIMIT,
+			None,
+			wasm,
+			vec![],
+			vec![],
+		)
+		.unwrap();
+
+		// The upload is skipped: it is cheaper and no deposit is taken from BOB.
+		assert!(second.actual_weight.unwrap().ref_time() < first.actual_weight.unwrap().ref_time());
+		// Finding out that the code exists is still paid for.
+		let lookup = Contracts::code_lookup_weight(wasm_len);
+		assert!(second.actual_weight.unwrap().ref_time() > lookup.ref_time());
+		assert_eq!(Balances::reserved_balance(&BOB), 0);
+		assert_refcount!(code_hash, 2);
+
+		// The existing code is left untouched.
+		let new_metadata = Contracts::code_metadata(code_hash).unwrap();
+		assert_eq!(new_metadata.owner, ALICE);
+		assert_eq!(new_metadata.deposit, metadata.deposit);
+		assert_eq!(Balances::reserved_balance(&ALICE), alice_reserved);
+	});
+}
+
 #[test]
 fn refcounter() {
 	let (wasm, code_hash) = compile_module::<Test>("self_destruct").unwrap();
@@ -2457,6 +3641,7 @@ fn refcounter() {
 			vec![],
 			vec![0],
 			false,
+			DryRunOptions::default(),
 		)
 		.result
 		.unwrap()
@@ -2470,6 +3655,7 @@ fn refcounter() {
 			vec![],
 			vec![1],
 			false,
+			DryRunOptions::default(),
 		)
 		.result
 		.unwrap()
@@ -2486,6 +3672,7 @@ fn refcounter() {
 			vec![],
 			vec![2],
 			false,
+			DryRunOptions::default(),
 		)
 		.result
 		.unwrap()
@@ -2552,6 +3739,7 @@ fn reinstrument_does_charge() {
 			zero.clone(),
 			vec![],
 			false,
+			DryRunOptions::default(),
 		)
 		.result
 		.unwrap()
@@ -2568,6 +3756,7 @@ fn reinstrument_does_charge() {
 			zero.clone(),
 			false,
 			Determinism::Deterministic,
+			DryRunOptions::default(),
 		);
 		assert!(!result0.result.unwrap().did_revert());
 
@@ -2580,6 +3769,7 @@ fn reinstrument_does_charge() {
 			zero.clone(),
 			false,
 			Determinism::Deterministic,
+			DryRunOptions::default(),
 		);
 		assert!(!result1.result.unwrap().did_revert());
 
@@ -2602,6 +3792,7 @@ fn reinstrument_does_charge() {
 			zero.clone(),
 			false,
 			Determinism::Deterministic,
+			DryRunOptions::default(),
 		);
 		assert!(!result2.result.unwrap().did_revert());
 		assert!(result2.gas_consumed.ref_time() > result1.gas_consumed.ref_time());
@@ -2613,6 +3804,181 @@ fn reinstrument_does_charge() {
 	});
 }
 
+/// Instantiates the `dummy` fixture as `ALICE` and returns its address and code hash together
+/// with the length of its code.
+fn instantiate_dummy() -> (AccountId32, CodeHash<Test>, u32) {
+	let (wasm, code_hash) = compile_module::<Test>("dummy").unwrap();
+	let code_len = wasm.len() as u32;
+	let min_balance = <Test as Config>::Currency::minimum_balance();
+	let addr = Contracts::bare_instantiate(
+		ALICE,
+		min_balance * 100,
+		GAS_LIMIT,
+		None,
+		Code::Upload(wasm),
+		vec![],
+		vec![],
+		false,
+		DryRunOptions::default(),
+	)
+	.result
+	.unwrap()
+	.account_id;
+	(addr, code_hash, code_len)
+}
+
+/// Calls `addr` as `BOB` and returns the consumed gas.
+fn call_dummy(addr: &AccountId32) -> Weight {
+	let result = Contracts::bare_call(
+		BOB,
+		addr.clone(),
+		0,
+		GAS_LIMIT,
+		None,
+		vec![],
+		false,
+		Determinism::Deterministic,
+		DryRunOptions::default(),
+	);
+	assert!(!result.result.unwrap().did_revert());
+	result.gas_consumed
+}
+
+/// Pretends that the schedule changed since `code_hash` was instrumented.
+fn outdate_code(code_hash: &CodeHash<Test>) {
+	crate::CodeStorage::mutate(code_hash, |code: &mut Option<PrefabWasmModule<Test>>| {
+		code.as_mut().unwrap().decrement_version();
+	});
+}
+
+/// Counts how often re-instrumenting `code_hash` was charged to the caller.
+fn charged_to_caller_events(code_hash: CodeHash<Test>) -> usize {
+	System::events()
+		.into_iter()
+		.filter(|record| {
+			record.event ==
+				RuntimeEvent::Contracts(crate::Event::ReinstrumentChargedToCaller { code_hash })
+		})
+		.count()
+}
+
+#[test]
+fn reinstrument_can_be_paid_from_code_deposit() {
+	ExtBuilder::default().existential_deposit(50).build().execute_with(|| {
+		Reinstrument::set(ReinstrumentPolicy::ChargeCodeOwnerDeposit);
+		let fee = <Test as Convert<Weight, BalanceOf<Test>>>::convert(
+			<Test as Config>::WeightInfo::reinstrument(
+				compile_module::<Test>("dummy").unwrap().0.len() as u32,
+			),
+		);
+		// Make the code deposit large enough to pay for the re-instrumentation.
+		DepositPerByte::set(fee);
+		let _ = Balances::deposit_creating(&ALICE, 10_000 * fee);
+		let _ = Balances::deposit_creating(&BOB, 1_000_000);
+		let (addr, code_hash, _) = instantiate_dummy();
+
+		let gas = call_dummy(&addr);
+		let deposit = Contracts::code_metadata(code_hash).unwrap().deposit;
+		let reserved = Balances::reserved_balance(&ALICE);
+
+		// The caller does not pay for the re-instrumentation but the owner does.
+		outdate_code(&code_hash);
+		assert_eq!(call_dummy(&addr), gas);
+		assert_eq!(Contracts::code_metadata(code_hash).unwrap().deposit, deposit - fee);
+		assert_eq!(Balances::reserved_balance(&ALICE), reserved - fee);
+		assert_eq!(charged_to_caller_events(code_hash), 0);
+	});
+}
+
+#[test]
+fn reinstrument_falls_back_to_caller_if_code_deposit_is_too_small() {
+	ExtBuilder::default().existential_deposit(50).build().execute_with(|| {
+		Reinstrument::set(ReinstrumentPolicy::ChargeCodeOwnerDeposit);
+		let _ = Balances::deposit_creating(&ALICE, 1_000_000);
+		let _ = Balances::deposit_creating(&BOB, 1_000_000);
+		let (addr, code_hash, code_len) = instantiate_dummy();
+
+		let gas = call_dummy(&addr);
+		let deposit = Contracts::code_metadata(code_hash).unwrap().deposit;
+
+		outdate_code(&code_hash);
+		assert_eq!(
+			call_dummy(&addr).ref_time(),
+			gas.ref_time() + <Test as Config>::WeightInfo::reinstrument(code_len).ref_time(),
+		);
+		assert_eq!(Contracts::code_metadata(code_hash).unwrap().deposit, deposit);
+		assert_eq!(charged_to_caller_events(code_hash), 1);
+	});
+}
+
+#[test]
+fn reinstrument_falls_back_to_caller_if_code_deposit_is_no_longer_reserved() {
+	ExtBuilder::default().existential_deposit(50).build().execute_with(|| {
+		Reinstrument::set(ReinstrumentPolicy::ChargeCodeOwnerDeposit);
+		let fee = <Test as Convert<Weight, BalanceOf<Test>>>::convert(
+			<Test as Config>::WeightInfo::reinstrument(
+				compile_module::<Test>("dummy").unwrap().0.len() as u32,
+			),
+		);
+		DepositPerByte::set(fee);
+		let _ = Balances::deposit_creating(&ALICE, 10_000 * fee);
+		let _ = Balances::deposit_creating(&BOB, 1_000_000);
+		let (addr, code_hash, code_len) = instantiate_dummy();
+
+		let gas = call_dummy(&addr);
+		let deposit = Contracts::code_metadata(code_hash).unwrap().deposit;
+
+		// Some other pallet took the reserved balance of the owner.
+		let _ = Balances::slash_reserved(&ALICE, Balances::reserved_balance(&ALICE));
+
+		outdate_code(&code_hash);
+		assert_eq!(
+			call_dummy(&addr).ref_time(),
+			gas.ref_time() + <Test as Config>::WeightInfo::reinstrument(code_len).ref_time(),
+		);
+		assert_eq!(Contracts::code_metadata(code_hash).unwrap().deposit, deposit);
+		assert_eq!(charged_to_caller_events(code_hash), 1);
+	});
+}
+
+#[test]
+fn reinstrument_can_be_subsidized() {
+	ExtBuilder::default().existential_deposit(50).build().execute_with(|| {
+		Reinstrument::set(ReinstrumentPolicy::Subsidized);
+		let fee = <Test as Convert<Weight, BalanceOf<Test>>>::convert(
+			<Test as Config>::WeightInfo::reinstrument(
+				compile_module::<Test>("dummy").unwrap().0.len() as u32,
+			),
+		);
+		let _ = Balances::deposit_creating(&ALICE, 100 * fee);
+		let _ = Balances::deposit_creating(&BOB, 1_000_000);
+		let (addr, code_hash, code_len) = instantiate_dummy();
+		let pot = Contracts::reinstrument_pot();
+
+		// An empty pot cannot pay: the caller is charged instead.
+		let gas = call_dummy(&addr);
+		outdate_code(&code_hash);
+		assert_eq!(
+			call_dummy(&addr).ref_time(),
+			gas.ref_time() + <Test as Config>::WeightInfo::reinstrument(code_len).ref_time(),
+		);
+		assert_eq!(charged_to_caller_events(code_hash), 1);
+
+		// Only root can fund the pot.
+		assert_noop!(
+			Contracts::fund_reinstrument_pot(RuntimeOrigin::signed(ALICE), ALICE, 10 * fee),
+			sp_runtime::traits::BadOrigin,
+		);
+		assert_ok!(Contracts::fund_reinstrument_pot(RuntimeOrigin::root(), ALICE, 10 * fee));
+		assert_eq!(Balances::free_balance(&pot), 10 * fee);
+
+		outdate_code(&code_hash);
+		assert_eq!(call_dummy(&addr), gas);
+		assert_eq!(Balances::free_balance(&pot), 9 * fee);
+		assert_eq!(charged_to_caller_events(code_hash), 1);
+	});
+}
+
 #[test]
 fn debug_message_works() {
 	let (wasm, _code_hash) = compile_module::<Test>("debug_message_works").unwrap();
@@ -2628,6 +3994,7 @@ fn debug_message_works() {
 			vec![],
 			vec![],
 			false,
+			DryRunOptions::default(),
 		)
 		.result
 		.unwrap()
@@ -2641,6 +4008,7 @@ fn debug_message_works() {
 			vec![],
 			true,
 			Determinism::Deterministic,
+			DryRunOptions::default(),
 		);
 
 		assert_matches!(result.result, Ok(_));
@@ -2649,8 +4017,8 @@ fn debug_message_works() {
 }
 
 #[test]
-fn debug_message_logging_disabled() {
-	let (wasm, _code_hash) = compile_module::<Test>("debug_message_logging_disabled").unwrap();
+fn seal_random_is_counted() {
+	let (wasm, _code_hash) = compile_module::<Test>("random").unwrap();
 
 	ExtBuilder::default().existential_deposit(50).build().execute_with(|| {
 		let _ = Balances::deposit_creating(&ALICE, 1_000_000);
@@ -2663,11 +4031,14 @@ fn debug_message_logging_disabled() {
 			vec![],
 			vec![],
 			false,
+			DryRunOptions::default(),
 		)
 		.result
 		.unwrap()
 		.account_id;
-		// disable logging by passing `false`
+		assert_eq!(<RandomnessCallCount<Test>>::get(), 0);
+
+		// a dry-run warns about the deprecation, once per call stack.
 		let result = Contracts::bare_call(
 			ALICE,
 			addr.clone(),
@@ -2675,16 +4046,188 @@ fn debug_message_logging_disabled() {
 			GAS_LIMIT,
 			None,
 			vec![],
-			false,
+			true,
 			Determinism::Deterministic,
+			DryRunOptions::default(),
 		);
 		assert_matches!(result.result, Ok(_));
-		// the dispatchables always run without debugging
+		assert_eq!(
+			std::str::from_utf8(&result.debug_message)
+				.unwrap()
+				.matches("`seal_random` is deprecated")
+				.count(),
+			1
+		);
+		assert_eq!(<RandomnessCallsInBlock<Test>>::get(), 2);
+		assert_eq!(<RandomnessCallCount<Test>>::get(), 0);
+
+		// a regular call is counted as well.
+		assert_ok!(Contracts::call(RuntimeOrigin::signed(ALICE), addr, 0, GAS_LIMIT, None, vec![]));
+		assert_eq!(<RandomnessCallsInBlock<Test>>::get(), 4);
+
+		// the calls of the block are only stored at its end.
+		Contracts::on_finalize(System::block_number());
+		assert_eq!(<RandomnessCallCount<Test>>::get(), 4);
+		assert_eq!(<RandomnessCallsInBlock<Test>>::get(), 0);
+	});
+}
+
+#[test]
+fn deterministic_sandbox_works() {
+	let (wasm, _code_hash) = compile_module::<Test>("block_info").unwrap();
+	ExtBuilder::default().existential_deposit(50).build().execute_with(|| {
+		let _ = Balances::deposit_creating(&ALICE, 1_000_000);
+		let addr = Contracts::bare_instantiate(
+			ALICE,
+			0,
+			GAS_LIMIT,
+			None,
+			Code::Upload(wasm),
+			vec![],
+			vec![],
+			false,
+			DryRunOptions::default(),
+		)
+		.result
+		.unwrap()
+		.account_id;
+
+		let dry_run = |deterministic_sandbox: bool| {
+			let result = Contracts::bare_call(
+				ALICE,
+				addr.clone(),
+				0,
+				GAS_LIMIT,
+				None,
+				vec![],
+				false,
+				Determinism::Deterministic,
+				DryRunOptions { deterministic_sandbox, ..Default::default() },
+			);
+			assert_eq!(result.deterministic_sandbox, deterministic_sandbox);
+			result.result.unwrap().data
+		};
+
+		initialize_block(2);
+		MockTime::set(1_000);
+		let outside = dry_run(false);
+		let inside = dry_run(true);
+		assert_eq!(outside[..16], (2u64, 1_000u64).encode());
+		assert_eq!(inside[..16], [0u8; 16]);
+
+		// Only the output of the sandboxed dry-run is independent of the block.
+		initialize_block(7);
+		MockTime::set(5_000);
+		assert_ne!(dry_run(false), outside);
+		assert_eq!(dry_run(true), inside);
+	});
+}
+
+#[test]
+fn debug_message_logging_disabled() {
+	let (wasm, _code_hash) = compile_module::<Test>("debug_message_logging_disabled").unwrap();
+
+	ExtBuilder::default().existential_deposit(50).build().execute_with(|| {
+		let _ = Balances::deposit_creating(&ALICE, 1_000_000);
+		let addr = Contracts::bare_instantiate(
+			ALICE,
+			30_000,
+			GAS_LIMIT,
+			None,
+			Code::Upload(wasm),
+			vec![],
+			vec![],
+			false,
+			DryRunOptions::default(),
+		)
+		.result
+		.unwrap()
+		.account_id;
+		// disable logging by passing `false`
+		let result = Contracts::bare_call(
+			ALICE,
+			addr.clone(),
+			0,
+			GAS_LIMIT,
+			None,
+			vec![],
+			false,
+			Determinism::Deterministic,
+			DryRunOptions::default(),
+		);
+		assert_matches!(result.result, Ok(_));
+		// the dispatchables always run without debugging
 		assert_ok!(Contracts::call(RuntimeOrigin::signed(ALICE), addr, 0, GAS_LIMIT, None, vec![]));
 		assert!(result.debug_message.is_empty());
 	});
 }
 
+#[test]
+fn debug_message_is_forwarded_to_sink_without_buffer() {
+	let (wasm, _code_hash) = compile_module::<Test>("debug_message_logging_disabled").unwrap();
+	let (wasm_works, _code_hash) = compile_module::<Test>("debug_message_works").unwrap();
+
+	ExtBuilder::default().existential_deposit(50).build().execute_with(|| {
+		let _ = Balances::deposit_creating(&ALICE, 1_000_000);
+		let addr = Contracts::bare_instantiate(
+			ALICE,
+			30_000,
+			GAS_LIMIT,
+			None,
+			Code::Upload(wasm),
+			vec![],
+			vec![],
+			false,
+			DryRunOptions::default(),
+		)
+		.result
+		.unwrap()
+		.account_id;
+		let addr_works = Contracts::bare_instantiate(
+			ALICE,
+			30_000,
+			GAS_LIMIT,
+			None,
+			Code::Upload(wasm_works),
+			vec![],
+			vec![],
+			false,
+			DryRunOptions::default(),
+		)
+		.result
+		.unwrap()
+		.account_id;
+		DebugMessages::take();
+
+		// the contract still observes that logging is disabled.
+		assert_ok!(Contracts::call(
+			RuntimeOrigin::signed(ALICE),
+			addr.clone(),
+			0,
+			GAS_LIMIT,
+			None,
+			vec![]
+		));
+		assert_eq!(DebugMessages::take(), vec![(addr, "Hello World!".to_string())]);
+
+		// messages that end up in a debug buffer are not forwarded.
+		let result = Contracts::bare_call(
+			ALICE,
+			addr_works,
+			0,
+			GAS_LIMIT,
+			None,
+			vec![],
+			true,
+			Determinism::Deterministic,
+			DryRunOptions::default(),
+		);
+		assert_matches!(result.result, Ok(_));
+		assert_eq!(std::str::from_utf8(&result.debug_message).unwrap(), "Hello World!");
+		assert!(DebugMessages::get().is_empty());
+	});
+}
+
 #[test]
 fn debug_message_invalid_utf8() {
 	let (wasm, _code_hash) = compile_module::<Test>("debug_message_invalid_utf8").unwrap();
@@ -2700,6 +4243,7 @@ fn debug_message_invalid_utf8() {
 			vec![],
 			vec![],
 			false,
+			DryRunOptions::default(),
 		)
 		.result
 		.unwrap()
@@ -2713,6 +4257,7 @@ fn debug_message_invalid_utf8() {
 			vec![],
 			true,
 			Determinism::Deterministic,
+			DryRunOptions::default(),
 		);
 		assert_err!(result.result, <Error<Test>>::DebugMessageInvalidUTF8);
 	});
@@ -2736,6 +4281,7 @@ fn gas_estimation_nested_call_fixed_limit() {
 			vec![],
 			vec![0],
 			false,
+			DryRunOptions::default(),
 		)
 		.result
 		.unwrap()
@@ -2750,6 +4296,7 @@ fn gas_estimation_nested_call_fixed_limit() {
 			vec![],
 			vec![1],
 			false,
+			DryRunOptions::default(),
 		)
 		.result
 		.unwrap()
@@ -2771,6 +4318,7 @@ fn gas_estimation_nested_call_fixed_limit() {
 			input.clone(),
 			false,
 			Determinism::Deterministic,
+			DryRunOptions::default(),
 		);
 		assert_ok!(&result.result);
 
@@ -2788,9 +4336,163 @@ fn gas_estimation_nested_call_fixed_limit() {
 				input,
 				false,
 				Determinism::Deterministic,
+				DryRunOptions::default(),
+			)
+			.result
+		);
+	});
+}
+
+#[test]
+fn memory_growth_is_charged_per_page() {
+	let (code, _code_hash) = compile_module::<Test>("grow_memory").unwrap();
+	ExtBuilder::default().existential_deposit(50).build().execute_with(|| {
+		let min_balance = <Test as Config>::Currency::minimum_balance();
+		let _ = Balances::deposit_creating(&ALICE, 1000 * min_balance);
+
+		let addr = Contracts::bare_instantiate(
+			ALICE,
+			min_balance * 100,
+			GAS_LIMIT,
+			None,
+			Code::Upload(code),
+			vec![],
+			vec![],
+			false,
+			DryRunOptions::default(),
+		)
+		.result
+		.unwrap()
+		.account_id;
+
+		let grow = |pages: u32| {
+			let result = Contracts::bare_call(
+				ALICE,
+				addr.clone(),
+				0,
+				GAS_LIMIT,
+				None,
+				pages.encode(),
+				false,
+				Determinism::Deterministic,
+				DryRunOptions::default(),
+			);
+			assert_ok!(result.result);
+			result.gas_consumed.ref_time()
+		};
+
+		let per_page =
+			u64::from(<Test as Config>::Schedule::get().instruction_weights.memory_grow_per_page);
+		assert!(per_page > 0);
+
+		// The contract starts with one page so this grows it to the maximum.
+		let max_pages = <Test as Config>::Schedule::get().limits.memory_pages - 1;
+		let one = grow(1);
+		let eight = grow(8);
+		let max = grow(max_pages);
+		assert_eq!(eight - one, 7 * per_page);
+		assert_eq!(max - one, u64::from(max_pages - 1) * per_page);
+	});
+}
+
+#[test]
+fn weight_to_fee_takes_both_weight_components() {
+	use codec::Decode;
+	let (code, _code_hash) = compile_module::<Test>("weight_to_fee").unwrap();
+	ExtBuilder::default().existential_deposit(50).build().execute_with(|| {
+		let min_balance = <Test as Config>::Currency::minimum_balance();
+		let _ = Balances::deposit_creating(&ALICE, 1000 * min_balance);
+
+		let addr = Contracts::bare_instantiate(
+			ALICE,
+			min_balance * 100,
+			GAS_LIMIT,
+			None,
+			Code::Upload(code),
+			vec![],
+			vec![],
+			false,
+			DryRunOptions::default(),
+		)
+		.result
+		.unwrap()
+		.account_id;
+
+		let fee = |ref_time: u64, proof_size: u64| {
+			let result = Contracts::bare_call(
+				ALICE,
+				addr.clone(),
+				0,
+				GAS_LIMIT,
+				None,
+				(ref_time, proof_size).encode(),
+				false,
+				Determinism::Deterministic,
+				DryRunOptions::default(),
 			)
 			.result
+			.unwrap();
+			assert!(!result.did_revert());
+			BalanceOf::<Test>::decode(&mut &result.data[..]).unwrap()
+		};
+
+		// the mock runtime charges one per `ref_time` and two per `proof_size`.
+		assert_eq!(fee(0, 0), 0);
+		assert_eq!(fee(1312, 0), 1312);
+		assert_eq!(fee(0, 1312), 2624);
+		assert_eq!(
+			fee(1000, 42),
+			<Test as Config>::WeightPrice::convert(Weight::from_parts(1000, 42))
 		);
+		assert_eq!(fee(1000, 42), 1084);
+	});
+}
+
+#[test]
+fn seal_now_reads_the_configured_time_source() {
+	use codec::Decode;
+	let (code, _code_hash) = compile_module::<Test>("now").unwrap();
+	ExtBuilder::default().existential_deposit(50).build().execute_with(|| {
+		let min_balance = <Test as Config>::Currency::minimum_balance();
+		let _ = Balances::deposit_creating(&ALICE, 1000 * min_balance);
+		MockTime::set(1_000);
+
+		let addr = Contracts::bare_instantiate(
+			ALICE,
+			min_balance * 100,
+			GAS_LIMIT,
+			None,
+			Code::Upload(code),
+			vec![],
+			vec![],
+			false,
+			DryRunOptions::default(),
+		)
+		.result
+		.unwrap()
+		.account_id;
+
+		let now = || {
+			let result = Contracts::bare_call(
+				ALICE,
+				addr.clone(),
+				0,
+				GAS_LIMIT,
+				None,
+				vec![],
+				false,
+				Determinism::Deterministic,
+				DryRunOptions::default(),
+			)
+			.result
+			.unwrap();
+			assert!(!result.did_revert());
+			u64::decode(&mut &result.data[..]).unwrap()
+		};
+
+		assert_eq!(now(), 1_000);
+		MockTime::advance(500);
+		assert_eq!(now(), 1_500);
 	});
 }
 
@@ -2813,6 +4515,7 @@ fn gas_estimation_call_runtime() {
 			vec![],
 			vec![0],
 			false,
+			DryRunOptions::default(),
 		)
 		.result
 		.unwrap()
@@ -2827,6 +4530,7 @@ fn gas_estimation_call_runtime() {
 			vec![],
 			vec![1],
 			false,
+			DryRunOptions::default(),
 		)
 		.result
 		.unwrap()
@@ -2850,6 +4554,7 @@ fn gas_estimation_call_runtime() {
 			call.encode(),
 			false,
 			Determinism::Deterministic,
+			DryRunOptions::default(),
 		);
 		// contract encodes the result of the dispatch runtime
 		let outcome = u32::decode(&mut result.result.unwrap().data.as_ref()).unwrap();
@@ -2867,6 +4572,7 @@ fn gas_estimation_call_runtime() {
 				call.encode(),
 				false,
 				Determinism::Deterministic,
+				DryRunOptions::default(),
 			)
 			.result
 		);
@@ -2890,6 +4596,7 @@ fn ecdsa_recover() {
 			vec![],
 			vec![],
 			false,
+			DryRunOptions::default(),
 		)
 		.result
 		.unwrap()
@@ -2927,6 +4634,7 @@ fn ecdsa_recover() {
 			params,
 			false,
 			Determinism::Deterministic,
+			DryRunOptions::default(),
 		)
 		.result
 		.unwrap();
@@ -3023,6 +4731,48 @@ fn upload_code_not_enough_balance() {
 	});
 }
 
+#[test]
+fn upload_code_reports_validation_error() {
+	let cases = [
+		("start_function", ValidationError::StartFunction),
+		("float_instruction", ValidationError::FloatInstruction),
+		("unbounded_memory", ValidationError::MemoryLimits),
+		("forbidden_import", ValidationError::ForbiddenImport { name: b"gas".to_vec() }),
+		("table_too_large", ValidationError::TableSize),
+		("too_many_globals", ValidationError::GlobalCount),
+	];
+
+	ExtBuilder::default().existential_deposit(100).build().execute_with(|| {
+		let _ = Balances::deposit_creating(&ALICE, 1_000_000);
+
+		for (fixture, validation_error) in cases {
+			let (wasm, _code_hash) = compile_module::<Test>(fixture).unwrap();
+
+			// The dry-run reports why the code was rejected.
+			assert_eq!(
+				Contracts::bare_upload_code(ALICE, wasm.clone(), None, Determinism::Deterministic),
+				Err(CodeUploadError {
+					error: <Error<Test>>::CodeRejected.into(),
+					validation_error: Some(validation_error),
+				}),
+				"unexpected result for fixture {}",
+				fixture,
+			);
+
+			// On-chain all reasons map to the same dispatch error.
+			assert_noop!(
+				Contracts::upload_code(
+					RuntimeOrigin::signed(ALICE),
+					wasm,
+					None,
+					Determinism::Deterministic
+				),
+				<Error<Test>>::CodeRejected,
+			);
+		}
+	});
+}
+
 #[test]
 fn remove_code_works() {
 	let (wasm, code_hash) = compile_module::<Test>("dummy").unwrap();
@@ -3170,26 +4920,82 @@ fn remove_code_not_found() {
 }
 
 #[test]
-fn instantiate_with_zero_balance_works() {
+fn code_metadata_works() {
 	let (wasm, code_hash) = compile_module::<Test>("dummy").unwrap();
-	ExtBuilder::default().existential_deposit(200).build().execute_with(|| {
+
+	ExtBuilder::default().existential_deposit(100).build().execute_with(|| {
 		let _ = Balances::deposit_creating(&ALICE, 1_000_000);
-		let min_balance = <Test as Config>::Currency::minimum_balance();
 
-		// Drop previous events
-		initialize_block(2);
+		// Unknown code has no metadata.
+		assert_eq!(Contracts::code_metadata(code_hash), None);
 
-		// Instantiate the BOB contract.
-		let addr = Contracts::bare_instantiate(
-			ALICE,
-			0,
-			GAS_LIMIT,
+		assert_ok!(Contracts::upload_code(
+			RuntimeOrigin::signed(ALICE),
+			wasm.clone(),
 			None,
-			Code::Upload(wasm),
-			vec![],
-			vec![],
-			false,
-		)
+			Determinism::Deterministic,
+		));
+
+		let metadata = Contracts::code_metadata(code_hash).unwrap();
+		assert_eq!(metadata.owner, ALICE);
+		assert_eq!(metadata.deposit, 173);
+		assert_eq!(metadata.refcount, 0);
+		assert_eq!(metadata.original_size, wasm.len() as u32);
+		assert_eq!(
+			metadata.instrumented_size,
+			<CodeStorage<Test>>::get(code_hash).unwrap().code_len(),
+		);
+		assert_eq!(metadata.determinism, Determinism::Deterministic);
+
+		// Removing the code removes its metadata.
+		assert_ok!(Contracts::remove_code(RuntimeOrigin::signed(ALICE), code_hash));
+		assert_eq!(Contracts::code_metadata(code_hash), None);
+
+		// Instantiating a contract from the code increments the refcount.
+		assert_ok!(Contracts::upload_code(
+			RuntimeOrigin::signed(ALICE),
+			wasm,
+			None,
+			Determinism::Deterministic,
+		));
+		assert_ok!(Contracts::instantiate(
+			RuntimeOrigin::signed(ALICE),
+			0,
+			GAS_LIMIT,
+			None,
+			code_hash,
+			vec![],
+			vec![],
+		));
+		assert_eq!(
+			Contracts::code_metadata(code_hash),
+			Some(CodeMetadata { refcount: 1, ..metadata }),
+		);
+	});
+}
+
+#[test]
+fn instantiate_with_zero_balance_works() {
+	let (wasm, code_hash) = compile_module::<Test>("dummy").unwrap();
+	ExtBuilder::default().existential_deposit(200).build().execute_with(|| {
+		let _ = Balances::deposit_creating(&ALICE, 1_000_000);
+		let min_balance = <Test as Config>::Currency::minimum_balance();
+
+		// Drop previous events
+		initialize_block(2);
+
+		// Instantiate the BOB contract.
+		let addr = Contracts::bare_instantiate(
+			ALICE,
+			0,
+			GAS_LIMIT,
+			None,
+			Code::Upload(wasm),
+			vec![],
+			vec![],
+			false,
+			DryRunOptions::default(),
+		)
 		.result
 		.unwrap()
 		.account_id;
@@ -3285,6 +5091,7 @@ fn instantiate_with_below_existential_deposit_works() {
 			vec![],
 			vec![],
 			false,
+			DryRunOptions::default(),
 		)
 		.result
 		.unwrap()
@@ -3386,6 +5193,7 @@ fn storage_deposit_works() {
 			vec![],
 			vec![],
 			false,
+			DryRunOptions::default(),
 		)
 		.result
 		.unwrap()
@@ -3452,6 +5260,7 @@ fn storage_deposit_works() {
 					event: RuntimeEvent::Contracts(crate::Event::Called {
 						caller: ALICE,
 						contract: addr.clone(),
+						selector: None,
 					}),
 					topics: vec![hash(&ALICE), hash(&addr)],
 				},
@@ -3477,6 +5286,7 @@ fn storage_deposit_works() {
 					event: RuntimeEvent::Contracts(crate::Event::Called {
 						caller: ALICE,
 						contract: addr.clone(),
+						selector: None,
 					}),
 					topics: vec![hash(&ALICE), hash(&addr)],
 				},
@@ -3502,6 +5312,7 @@ fn storage_deposit_works() {
 					event: RuntimeEvent::Contracts(crate::Event::Called {
 						caller: ALICE,
 						contract: addr.clone(),
+						selector: None,
 					}),
 					topics: vec![hash(&ALICE), hash(&addr)],
 				},
@@ -3520,6 +5331,103 @@ fn storage_deposit_works() {
 	});
 }
 
+#[test]
+fn storage_item_limit_works() {
+	let (wasm, _code_hash) = compile_module::<Test>("store_key").unwrap();
+	ExtBuilder::default().existential_deposit(200).build().execute_with(|| {
+		let _ = Balances::deposit_creating(&ALICE, 1_000_000);
+		MaxStorageItems::set(Some(2));
+
+		let addr = Contracts::bare_instantiate(
+			ALICE,
+			0,
+			GAS_LIMIT,
+			None,
+			Code::Upload(wasm),
+			vec![],
+			vec![],
+			false,
+			DryRunOptions::default(),
+		)
+		.result
+		.unwrap()
+		.account_id;
+		let store = |key: u32| {
+			Contracts::call(
+				RuntimeOrigin::signed(ALICE),
+				addr.clone(),
+				0,
+				GAS_LIMIT,
+				None,
+				key.encode(),
+			)
+		};
+
+		// Up to the limit items can be created.
+		assert_ok!(store(1));
+		assert_ok!(store(2));
+		assert_eq!(get_contract(&addr).storage_items, 2);
+
+		// One more is rejected.
+		assert_err_ignore_postinfo!(store(3), <Error<Test>>::StorageItemLimitExceeded);
+		assert_eq!(get_contract(&addr).storage_items, 2);
+
+		// Existing items can still be overwritten.
+		assert_ok!(store(1));
+		assert_eq!(get_contract(&addr).storage_items, 2);
+	});
+}
+
+#[test]
+fn storage_item_limit_grandfathers_existing_items() {
+	let (wasm, _code_hash) = compile_module::<Test>("store_key").unwrap();
+	ExtBuilder::default().existential_deposit(200).build().execute_with(|| {
+		let _ = Balances::deposit_creating(&ALICE, 1_000_000);
+
+		let addr = Contracts::bare_instantiate(
+			ALICE,
+			0,
+			GAS_LIMIT,
+			None,
+			Code::Upload(wasm),
+			vec![],
+			vec![],
+			false,
+			DryRunOptions::default(),
+		)
+		.result
+		.unwrap()
+		.account_id;
+		let store = |key: u32| {
+			Contracts::call(
+				RuntimeOrigin::signed(ALICE),
+				addr.clone(),
+				0,
+				GAS_LIMIT,
+				None,
+				key.encode(),
+			)
+		};
+
+		// Without a limit any number of items can be created.
+		for key in 1..=3 {
+			assert_ok!(store(key));
+		}
+		assert_eq!(get_contract(&addr).storage_items, 3);
+
+		// The limit is introduced below what the contract already holds.
+		MaxStorageItems::set(Some(1));
+
+		// The contract keeps working with its existing items.
+		assert_ok!(store(2));
+		assert_eq!(get_contract(&addr).storage_items, 3);
+
+		// But it can't create new ones.
+		assert_err_ignore_postinfo!(store(4), <Error<Test>>::StorageItemLimitExceeded);
+		assert_eq!(get_contract(&addr).storage_items, 3);
+	});
+}
+
 #[test]
 fn set_code_extrinsic() {
 	let (wasm, code_hash) = compile_module::<Test>("dummy").unwrap();
@@ -3539,6 +5447,7 @@ fn set_code_extrinsic() {
 			vec![],
 			vec![],
 			false,
+			DryRunOptions::default(),
 		)
 		.result
 		.unwrap()
@@ -3624,6 +5533,7 @@ fn call_after_killed_account_needs_funding() {
 			vec![],
 			vec![],
 			false,
+			DryRunOptions::default(),
 		)
 		.result
 		.unwrap()
@@ -3697,6 +5607,7 @@ fn call_after_killed_account_needs_funding() {
 					event: RuntimeEvent::Contracts(crate::Event::Called {
 						caller: ALICE,
 						contract: addr.clone(),
+						selector: None,
 					}),
 					topics: vec![hash(&ALICE), hash(&addr)],
 				},
@@ -3729,6 +5640,7 @@ fn call_after_killed_account_needs_funding() {
 					event: RuntimeEvent::Contracts(crate::Event::Called {
 						caller: ALICE,
 						contract: addr.clone(),
+						selector: None,
 					}),
 					topics: vec![hash(&ALICE), hash(&addr)],
 				},
@@ -3796,6 +5708,7 @@ fn contract_reverted() {
 			input.clone(),
 			vec![],
 			false,
+			DryRunOptions::default(),
 		)
 		.result
 		.unwrap();
@@ -3813,6 +5726,7 @@ fn contract_reverted() {
 			ReturnFlags::empty().bits().encode(),
 			vec![],
 			false,
+			DryRunOptions::default(),
 		)
 		.result
 		.unwrap()
@@ -3841,6 +5755,7 @@ fn contract_reverted() {
 			input,
 			false,
 			Determinism::Deterministic,
+			DryRunOptions::default(),
 		)
 		.result
 		.unwrap();
@@ -3873,6 +5788,7 @@ fn code_rejected_error_works() {
 			vec![],
 			vec![],
 			true,
+			DryRunOptions::default(),
 		);
 		assert_err!(result.result, <Error<Test>>::CodeRejected);
 		assert_eq!(
@@ -3900,6 +5816,7 @@ fn code_rejected_error_works() {
 			vec![],
 			vec![],
 			true,
+			DryRunOptions::default(),
 		);
 		assert_err!(result.result, <Error<Test>>::CodeRejected);
 		assert_eq!(
@@ -3927,6 +5844,7 @@ fn set_code_hash() {
 			vec![],
 			vec![],
 			false,
+			DryRunOptions::default(),
 		)
 		.result
 		.unwrap()
@@ -3951,6 +5869,7 @@ fn set_code_hash() {
 			new_code_hash.as_ref().to_vec(),
 			true,
 			Determinism::Deterministic,
+			DryRunOptions::default(),
 		)
 		.result
 		.unwrap();
@@ -3966,6 +5885,7 @@ fn set_code_hash() {
 			vec![],
 			true,
 			Determinism::Deterministic,
+			DryRunOptions::default(),
 		)
 		.result
 		.unwrap();
@@ -3989,6 +5909,7 @@ fn set_code_hash() {
 					event: RuntimeEvent::Contracts(crate::Event::Called {
 						caller: ALICE,
 						contract: contract_addr.clone(),
+						selector: None,
 					}),
 					topics: vec![hash(&ALICE), hash(&contract_addr)],
 				},
@@ -3997,6 +5918,7 @@ fn set_code_hash() {
 					event: RuntimeEvent::Contracts(crate::Event::Called {
 						caller: ALICE,
 						contract: contract_addr.clone(),
+						selector: None,
 					}),
 					topics: vec![hash(&ALICE), hash(&contract_addr)],
 				},
@@ -4006,82 +5928,315 @@ fn set_code_hash() {
 }
 
 #[test]
-fn storage_deposit_limit_is_enforced() {
-	let (wasm, _code_hash) = compile_module::<Test>("store").unwrap();
-	ExtBuilder::default().existential_deposit(200).build().execute_with(|| {
+fn set_code_hash_with_migrate_works() {
+	let (wasm, code_hash) = compile_module::<Test>("set_code_hash_migrate").unwrap();
+	let (new_wasm, new_code_hash) = compile_module::<Test>("migrate_ok").unwrap();
+
+	ExtBuilder::default().existential_deposit(100).build().execute_with(|| {
 		let _ = Balances::deposit_creating(&ALICE, 1_000_000);
-		let min_balance = <Test as Config>::Currency::minimum_balance();
 
-		// Instantiate the BOB contract.
-		let addr = Contracts::bare_instantiate(
+		let contract_addr = Contracts::bare_instantiate(
 			ALICE,
-			0,
+			300_000,
 			GAS_LIMIT,
 			None,
 			Code::Upload(wasm),
 			vec![],
 			vec![],
 			false,
+			DryRunOptions::default(),
 		)
 		.result
 		.unwrap()
 		.account_id;
+		assert_ok!(Contracts::upload_code(
+			RuntimeOrigin::signed(ALICE),
+			new_wasm,
+			None,
+			Determinism::Deterministic
+		));
 
-		// Check that the BOB contract has been instantiated and has the minimum balance
-		assert_eq!(get_contract(&addr).total_deposit(), min_balance);
-		assert_eq!(<Test as Config>::Currency::total_balance(&addr), min_balance);
+		System::reset_events();
 
-		// Create 100 bytes of storage with a price of per byte
-		assert_err_ignore_postinfo!(
-			Contracts::call(
-				RuntimeOrigin::signed(ALICE),
-				addr.clone(),
-				0,
-				GAS_LIMIT,
-				Some(codec::Compact(1)),
-				100u32.to_le_bytes().to_vec()
-			),
-			<Error<Test>>::StorageDepositLimitExhausted,
+		// Switching the code runs the `migrate` export of the new code
+		let result = Contracts::bare_call(
+			ALICE,
+			contract_addr.clone(),
+			0,
+			GAS_LIMIT,
+			None,
+			(new_code_hash, 1u32).encode(),
+			true,
+			Determinism::Deterministic,
+			DryRunOptions::default(),
+		)
+		.result
+		.unwrap();
+		assert_return_code!(result, RuntimeReturnCode::Success);
+		assert_eq!(Contracts::code_hash(&contract_addr), Some(new_code_hash));
+		assert_refcount!(code_hash, 0);
+		assert_refcount!(new_code_hash, 1);
+
+		// The new code returns the value written by the migration
+		let result = Contracts::bare_call(
+			ALICE,
+			contract_addr.clone(),
+			0,
+			GAS_LIMIT,
+			None,
+			vec![],
+			true,
+			Determinism::Deterministic,
+			DryRunOptions::default(),
+		)
+		.result
+		.unwrap();
+		assert_return_code!(result, 3);
+
+		// The migration frame does not emit a `Called` event
+		assert_eq!(
+			&System::events(),
+			&[
+				EventRecord {
+					phase: Phase::Initialization,
+					event: RuntimeEvent::Contracts(crate::Event::ContractCodeUpdated {
+						contract: contract_addr.clone(),
+						new_code_hash,
+						old_code_hash: code_hash,
+					}),
+					topics: vec![hash(&contract_addr), new_code_hash, code_hash],
+				},
+				EventRecord {
+					phase: Phase::Initialization,
+					event: RuntimeEvent::Contracts(crate::Event::Called {
+						caller: ALICE,
+						contract: contract_addr.clone(),
+						selector: None,
+					}),
+					topics: vec![hash(&ALICE), hash(&contract_addr)],
+				},
+				EventRecord {
+					phase: Phase::Initialization,
+					event: RuntimeEvent::Contracts(crate::Event::Called {
+						caller: ALICE,
+						contract: contract_addr.clone(),
+						selector: None,
+					}),
+					topics: vec![hash(&ALICE), hash(&contract_addr)],
+				},
+			],
 		);
 	});
 }
 
 #[test]
-fn storage_deposit_limit_is_enforced_late() {
-	let (wasm_caller, _code_hash_caller) =
-		compile_module::<Test>("create_storage_and_call").unwrap();
-	let (wasm_callee, _code_hash_callee) = compile_module::<Test>("store").unwrap();
-	ExtBuilder::default().existential_deposit(200).build().execute_with(|| {
+fn set_code_hash_without_migrate_flag_skips_migration() {
+	let (wasm, _code_hash) = compile_module::<Test>("set_code_hash_migrate").unwrap();
+	let (new_wasm, new_code_hash) = compile_module::<Test>("migrate_ok").unwrap();
+
+	ExtBuilder::default().existential_deposit(100).build().execute_with(|| {
 		let _ = Balances::deposit_creating(&ALICE, 1_000_000);
 
-		// Create both contracts: Constructors do nothing.
-		let addr_caller = Contracts::bare_instantiate(
+		let contract_addr = Contracts::bare_instantiate(
 			ALICE,
-			0,
+			300_000,
 			GAS_LIMIT,
 			None,
-			Code::Upload(wasm_caller),
+			Code::Upload(wasm),
 			vec![],
 			vec![],
 			false,
+			DryRunOptions::default(),
 		)
 		.result
 		.unwrap()
 		.account_id;
-		let addr_callee = Contracts::bare_instantiate(
+		assert_ok!(Contracts::upload_code(
+			RuntimeOrigin::signed(ALICE),
+			new_wasm,
+			None,
+			Determinism::Deterministic
+		));
+
+		let result = Contracts::bare_call(
 			ALICE,
+			contract_addr.clone(),
 			0,
 			GAS_LIMIT,
 			None,
-			Code::Upload(wasm_callee),
+			(new_code_hash, 0u32).encode(),
+			true,
+			Determinism::Deterministic,
+			DryRunOptions::default(),
+		)
+		.result
+		.unwrap();
+		assert_return_code!(result, RuntimeReturnCode::Success);
+		assert_eq!(Contracts::code_hash(&contract_addr), Some(new_code_hash));
+
+		// The migration did not run so nothing was written
+		let result = Contracts::bare_call(
+			ALICE,
+			contract_addr.clone(),
+			0,
+			GAS_LIMIT,
+			None,
+			vec![],
+			true,
+			Determinism::Deterministic,
+			DryRunOptions::default(),
+		)
+		.result
+		.unwrap();
+		assert_return_code!(result, 0);
+	});
+}
+
+#[test]
+fn set_code_hash_with_reverting_migrate_is_rolled_back() {
+	let (wasm, code_hash) = compile_module::<Test>("set_code_hash_migrate").unwrap();
+	let (new_wasm, new_code_hash) = compile_module::<Test>("migrate_revert").unwrap();
+
+	ExtBuilder::default().existential_deposit(100).build().execute_with(|| {
+		let _ = Balances::deposit_creating(&ALICE, 1_000_000);
+
+		let contract_addr = Contracts::bare_instantiate(
+			ALICE,
+			300_000,
+			GAS_LIMIT,
+			None,
+			Code::Upload(wasm),
 			vec![],
 			vec![],
 			false,
+			DryRunOptions::default(),
 		)
 		.result
 		.unwrap()
 		.account_id;
-
+		assert_ok!(Contracts::upload_code(
+			RuntimeOrigin::signed(ALICE),
+			new_wasm,
+			None,
+			Determinism::Deterministic
+		));
+
+		System::reset_events();
+
+		let result = Contracts::bare_call(
+			ALICE,
+			contract_addr.clone(),
+			0,
+			GAS_LIMIT,
+			None,
+			(new_code_hash, 1u32).encode(),
+			true,
+			Determinism::Deterministic,
+			DryRunOptions::default(),
+		)
+		.result
+		.unwrap();
+		assert_return_code!(result, RuntimeReturnCode::CalleeReverted);
+
+		// The code switch was rolled back together with the migration
+		assert_eq!(Contracts::code_hash(&contract_addr), Some(code_hash));
+		assert_refcount!(code_hash, 1);
+		assert_refcount!(new_code_hash, 0);
+		assert_eq!(
+			&System::events(),
+			&[EventRecord {
+				phase: Phase::Initialization,
+				event: RuntimeEvent::Contracts(crate::Event::Called {
+					caller: ALICE,
+					contract: contract_addr.clone(),
+					selector: None,
+				}),
+				topics: vec![hash(&ALICE), hash(&contract_addr)],
+			}],
+		);
+	});
+}
+
+#[test]
+fn storage_deposit_limit_is_enforced() {
+	let (wasm, _code_hash) = compile_module::<Test>("store").unwrap();
+	ExtBuilder::default().existential_deposit(200).build().execute_with(|| {
+		let _ = Balances::deposit_creating(&ALICE, 1_000_000);
+		let min_balance = <Test as Config>::Currency::minimum_balance();
+
+		// Instantiate the BOB contract.
+		let addr = Contracts::bare_instantiate(
+			ALICE,
+			0,
+			GAS_LIMIT,
+			None,
+			Code::Upload(wasm),
+			vec![],
+			vec![],
+			false,
+			DryRunOptions::default(),
+		)
+		.result
+		.unwrap()
+		.account_id;
+
+		// Check that the BOB contract has been instantiated and has the minimum balance
+		assert_eq!(get_contract(&addr).total_deposit(), min_balance);
+		assert_eq!(<Test as Config>::Currency::total_balance(&addr), min_balance);
+
+		// Create 100 bytes of storage with a price of per byte
+		assert_err_ignore_postinfo!(
+			Contracts::call(
+				RuntimeOrigin::signed(ALICE),
+				addr.clone(),
+				0,
+				GAS_LIMIT,
+				Some(codec::Compact(1)),
+				100u32.to_le_bytes().to_vec()
+			),
+			<Error<Test>>::StorageDepositLimitExhausted,
+		);
+	});
+}
+
+#[test]
+fn storage_deposit_limit_is_enforced_late() {
+	let (wasm_caller, _code_hash_caller) =
+		compile_module::<Test>("create_storage_and_call").unwrap();
+	let (wasm_callee, _code_hash_callee) = compile_module::<Test>("store").unwrap();
+	ExtBuilder::default().existential_deposit(200).build().execute_with(|| {
+		let _ = Balances::deposit_creating(&ALICE, 1_000_000);
+
+		// Create both contracts: Constructors do nothing.
+		let addr_caller = Contracts::bare_instantiate(
+			ALICE,
+			0,
+			GAS_LIMIT,
+			None,
+			Code::Upload(wasm_caller),
+			vec![],
+			vec![],
+			false,
+			DryRunOptions::default(),
+		)
+		.result
+		.unwrap()
+		.account_id;
+		let addr_callee = Contracts::bare_instantiate(
+			ALICE,
+			0,
+			GAS_LIMIT,
+			None,
+			Code::Upload(wasm_callee),
+			vec![],
+			vec![],
+			false,
+			DryRunOptions::default(),
+		)
+		.result
+		.unwrap()
+		.account_id;
+
 		// Create 100 bytes of storage with a price of per byte
 		// This is 100 Balance + 2 Balance for the item
 		assert_ok!(Contracts::call(
@@ -4209,6 +6364,7 @@ fn deposit_limit_honors_liquidity_restrictions() {
 			vec![],
 			vec![],
 			false,
+			DryRunOptions::default(),
 		)
 		.result
 		.unwrap()
@@ -4253,6 +6409,7 @@ fn deposit_limit_honors_existential_deposit() {
 			vec![],
 			vec![],
 			false,
+			DryRunOptions::default(),
 		)
 		.result
 		.unwrap()
@@ -4296,6 +6453,7 @@ fn deposit_limit_honors_min_leftover() {
 			vec![],
 			vec![],
 			false,
+			DryRunOptions::default(),
 		)
 		.result
 		.unwrap()
@@ -4321,6 +6479,84 @@ fn deposit_limit_honors_min_leftover() {
 	});
 }
 
+#[test]
+fn nested_termination_does_not_raise_deposit_limit() {
+	let (caller_wasm, _caller_code_hash) = compile_module::<Test>("terminate_and_store").unwrap();
+	let (callee_wasm, _callee_code_hash) = compile_module::<Test>("self_destruct").unwrap();
+	ExtBuilder::default().existential_deposit(200).build().execute_with(|| {
+		let _ = Balances::deposit_creating(&ALICE, 1_000_000);
+		let min_balance = <Test as Config>::Currency::minimum_balance();
+
+		let addr_caller = Contracts::bare_instantiate(
+			ALICE,
+			0,
+			GAS_LIMIT,
+			None,
+			Code::Upload(caller_wasm),
+			vec![],
+			vec![],
+			false,
+			DryRunOptions::default(),
+		)
+		.result
+		.unwrap()
+		.account_id;
+		let addr_callee = Contracts::bare_instantiate(
+			ALICE,
+			0,
+			GAS_LIMIT,
+			None,
+			Code::Upload(callee_wasm),
+			vec![],
+			vec![],
+			false,
+			DryRunOptions::default(),
+		)
+		.result
+		.unwrap()
+		.account_id;
+		assert_eq!(get_contract(&addr_callee).total_deposit(), min_balance);
+
+		// Storing 100 bytes costs 100 for the bytes and 2 for the item. The deposit released
+		// by the terminated callee must not be used to pay for it.
+		let input = (&addr_callee, 100u32).encode();
+		assert_err_ignore_postinfo!(
+			Contracts::call(
+				RuntimeOrigin::signed(ALICE),
+				addr_caller.clone(),
+				0,
+				GAS_LIMIT,
+				Some(codec::Compact(101)),
+				input.clone(),
+			),
+			<Error<Test>>::StorageDepositLimitExhausted,
+		);
+		assert!(get_contract_checked(&addr_callee).is_some());
+
+		// With a sufficient limit the origin is only charged for its own storage.
+		let alice_balance = Balances::free_balance(&ALICE);
+		let result = Contracts::bare_call(
+			ALICE,
+			addr_caller,
+			0,
+			GAS_LIMIT,
+			Some(102),
+			input,
+			false,
+			Determinism::Deterministic,
+			DryRunOptions::default(),
+		);
+		assert_ok!(&result.result);
+		assert_eq!(result.storage_deposit, StorageDeposit::Charge(102));
+		assert_eq!(Balances::free_balance(&ALICE), alice_balance - 102);
+
+		// The deposit of the callee went to its beneficiary along with its balance.
+		assert!(get_contract_checked(&addr_callee).is_none());
+		assert_eq!(Balances::total_balance(&addr_callee), 0);
+		assert_eq!(Balances::free_balance(&DJANGO), min_balance);
+	});
+}
+
 #[test]
 fn cannot_instantiate_indeterministic_code() {
 	let (wasm, code_hash) = compile_module::<Test>("float_instruction").unwrap();
@@ -4351,6 +6587,7 @@ fn cannot_instantiate_indeterministic_code() {
 				vec![],
 				vec![],
 				false,
+				DryRunOptions::default(),
 			)
 			.result,
 			<Error<Test>>::CodeRejected,
@@ -4384,7 +6621,7 @@ fn cannot_instantiate_indeterministic_code() {
 				vec![],
 				vec![],
 			),
-			<Error<Test>>::Indeterministic,
+			<Error<Test>>::IndeterministicInstantiation,
 		);
 		assert_err!(
 			Contracts::bare_instantiate(
@@ -4396,9 +6633,10 @@ fn cannot_instantiate_indeterministic_code() {
 				vec![],
 				vec![],
 				false,
+				DryRunOptions::default(),
 			)
 			.result,
-			<Error<Test>>::Indeterministic,
+			<Error<Test>>::IndeterministicInstantiation,
 		);
 
 		// Deploy contract which instantiates another contract
@@ -4411,6 +6649,7 @@ fn cannot_instantiate_indeterministic_code() {
 			vec![],
 			vec![],
 			false,
+			DryRunOptions::default(),
 		)
 		.result
 		.unwrap()
@@ -4427,9 +6666,10 @@ fn cannot_instantiate_indeterministic_code() {
 				code_hash.encode(),
 				false,
 				Determinism::Deterministic,
+				DryRunOptions::default(),
 			)
 			.result,
-			<Error<Test>>::Indeterministic,
+			<Error<Test>>::IndeterministicInstantiation,
 		);
 
 		// Instantiations are not allowed even in non determinism mode
@@ -4443,9 +6683,10 @@ fn cannot_instantiate_indeterministic_code() {
 				code_hash.encode(),
 				false,
 				Determinism::AllowIndeterminism,
+				DryRunOptions::default(),
 			)
 			.result,
-			<Error<Test>>::Indeterministic,
+			<Error<Test>>::IndeterministicInstantiation,
 		);
 	});
 }
@@ -4475,6 +6716,7 @@ fn cannot_set_code_indeterministic_code() {
 			vec![],
 			vec![],
 			false,
+			DryRunOptions::default(),
 		)
 		.result
 		.unwrap()
@@ -4491,6 +6733,7 @@ fn cannot_set_code_indeterministic_code() {
 				code_hash.encode(),
 				false,
 				Determinism::AllowIndeterminism,
+				DryRunOptions::default(),
 			)
 			.result,
 			<Error<Test>>::Indeterministic,
@@ -4523,41 +6766,142 @@ fn delegate_call_indeterministic_code() {
 			vec![],
 			vec![],
 			false,
+			DryRunOptions::default(),
+		)
+		.result
+		.unwrap()
+		.account_id;
+
+		// A deterministic contract can never delegate to it, not even in non deterministic mode
+		for determinism in [Determinism::Deterministic, Determinism::AllowIndeterminism] {
+			assert_err!(
+				<Pallet<Test>>::bare_call(
+					ALICE,
+					caller_addr.clone(),
+					0,
+					GAS_LIMIT,
+					None,
+					code_hash.encode(),
+					false,
+					determinism,
+					DryRunOptions::default(),
+				)
+				.result,
+				<Error<Test>>::IndeterministicDelegateCall,
+			);
+		}
+	});
+}
+
+#[test]
+fn indeterministic_code_only_runs_from_bare_call() {
+	let (wasm, code_hash) = compile_module::<Test>("float_instruction").unwrap();
+	let (dummy_wasm, _) = compile_module::<Test>("dummy").unwrap();
+	let (caller_wasm, _) = compile_module::<Test>("call_return_code").unwrap();
+	ExtBuilder::default().existential_deposit(200).build().execute_with(|| {
+		let _ = Balances::deposit_creating(&ALICE, 1_000_000);
+
+		// Put the non deterministic contract on-chain
+		assert_ok!(Contracts::upload_code(
+			RuntimeOrigin::signed(ALICE),
+			wasm,
+			None,
+			Determinism::AllowIndeterminism,
+		));
+
+		// Such a contract cannot be instantiated, so we swap the code of an existing one
+		let addr = Contracts::bare_instantiate(
+			ALICE,
+			0,
+			GAS_LIMIT,
+			None,
+			Code::Upload(dummy_wasm),
+			vec![],
+			vec![],
+			false,
+			DryRunOptions::default(),
+		)
+		.result
+		.unwrap()
+		.account_id;
+		<ContractInfoOf<Test>>::mutate(&addr, |info| info.as_mut().unwrap().code_hash = code_hash);
+
+		// Create the contract that will call `seal_call`
+		let caller_addr = Contracts::bare_instantiate(
+			ALICE,
+			1_000,
+			GAS_LIMIT,
+			None,
+			Code::Upload(caller_wasm),
+			vec![],
+			vec![],
+			false,
+			DryRunOptions::default(),
 		)
 		.result
 		.unwrap()
 		.account_id;
 
-		// The delegate call will fail in deterministic mode
+		// The extrinsic is always deterministic
+		assert_err_ignore_postinfo!(
+			Contracts::call(RuntimeOrigin::signed(ALICE), addr.clone(), 0, GAS_LIMIT, None, vec![]),
+			<Error<Test>>::Indeterministic,
+		);
+
+		// So is `bare_call` unless explicitly told otherwise
 		assert_err!(
 			<Pallet<Test>>::bare_call(
 				ALICE,
-				caller_addr.clone(),
+				addr.clone(),
 				0,
 				GAS_LIMIT,
 				None,
-				code_hash.encode(),
+				vec![],
 				false,
 				Determinism::Deterministic,
+				DryRunOptions::default(),
 			)
 			.result,
 			<Error<Test>>::Indeterministic,
 		);
-
-		// The delegate call will work on non deterministic mode
 		assert_ok!(
 			<Pallet<Test>>::bare_call(
 				ALICE,
-				caller_addr.clone(),
+				addr.clone(),
 				0,
 				GAS_LIMIT,
 				None,
-				code_hash.encode(),
+				vec![],
 				false,
 				Determinism::AllowIndeterminism,
+				DryRunOptions::default(),
 			)
 			.result
 		);
+
+		// Calling it from another contract is never allowed
+		let input: Vec<u8> = AsRef::<[u8]>::as_ref(&addr)
+			.iter()
+			.chain(&0u32.to_le_bytes())
+			.cloned()
+			.collect();
+		for determinism in [Determinism::Deterministic, Determinism::AllowIndeterminism] {
+			assert_err!(
+				<Pallet<Test>>::bare_call(
+					ALICE,
+					caller_addr.clone(),
+					0,
+					GAS_LIMIT,
+					None,
+					input.clone(),
+					false,
+					determinism,
+					DryRunOptions::default(),
+				)
+				.result,
+				<Error<Test>>::IndeterministicNestedCall,
+			);
+		}
 	});
 }
 
@@ -4577,6 +6921,7 @@ fn reentrance_count_works_with_call() {
 			vec![],
 			vec![],
 			false,
+			DryRunOptions::default(),
 		)
 		.result
 		.unwrap()
@@ -4594,6 +6939,7 @@ fn reentrance_count_works_with_call() {
 			input,
 			true,
 			Determinism::Deterministic,
+			DryRunOptions::default(),
 		)
 		.result
 		.unwrap();
@@ -4616,6 +6962,7 @@ fn reentrance_count_works_with_delegated_call() {
 			vec![],
 			vec![],
 			false,
+			DryRunOptions::default(),
 		)
 		.result
 		.unwrap()
@@ -4633,6 +6980,7 @@ fn reentrance_count_works_with_delegated_call() {
 			input,
 			true,
 			Determinism::Deterministic,
+			DryRunOptions::default(),
 		)
 		.result
 		.unwrap();
@@ -4640,10 +6988,9 @@ fn reentrance_count_works_with_delegated_call() {
 }
 
 #[test]
-fn account_reentrance_count_works() {
-	let (wasm, _code_hash) = compile_module::<Test>("account_reentrance_count_call").unwrap();
-	let (wasm_reentrance_count, _code_hash_reentrance_count) =
-		compile_module::<Test>("reentrance_count_call").unwrap();
+fn call_stack_remaining_stops_at_max_depth() {
+	let (wasm, _code_hash) = compile_module::<Test>("call_stack_remaining").unwrap();
+	let max_call_depth = <<Test as Config>::CallStack as smallvec::Array>::size() as u32 + 1;
 
 	ExtBuilder::default().existential_deposit(100).build().execute_with(|| {
 		let _ = Balances::deposit_creating(&ALICE, 1_000_000);
@@ -4657,52 +7004,1198 @@ fn account_reentrance_count_works() {
 			vec![],
 			vec![],
 			false,
+			DryRunOptions::default(),
 		)
 		.result
 		.unwrap()
 		.account_id;
 
-		let another_contract_addr = Contracts::bare_instantiate(
-			ALICE,
-			300_000,
-			GAS_LIMIT,
-			None,
-			Code::Upload(wasm_reentrance_count),
-			vec![],
-			vec![],
-			false,
-		)
-		.result
-		.unwrap()
-		.account_id;
-
-		let result1 = Contracts::bare_call(
+		// Respecting `call_stack_remaining` fills the whole call stack without any failure.
+		let result = Contracts::bare_call(
 			ALICE,
 			contract_addr.clone(),
 			0,
 			GAS_LIMIT,
 			None,
-			contract_addr.encode(),
+			0u32.encode(),
 			true,
 			Determinism::Deterministic,
+			DryRunOptions::default(),
 		)
 		.result
 		.unwrap();
+		assert!(!result.did_revert());
+		assert_eq!(u32::from_le_bytes(result.data[0..4].try_into().unwrap()), max_call_depth);
+		assert_eq!(u32::from_le_bytes(result.data[4..8].try_into().unwrap()), 0);
 
-		let result2 = Contracts::bare_call(
+		// Ignoring it surfaces a dedicated return code to the deepest contract.
+		let result = Contracts::bare_call(
 			ALICE,
 			contract_addr.clone(),
 			0,
 			GAS_LIMIT,
 			None,
-			another_contract_addr.encode(),
+			1u32.encode(),
 			true,
 			Determinism::Deterministic,
+			DryRunOptions::default(),
 		)
 		.result
 		.unwrap();
-
+		assert!(!result.did_revert());
+		assert_eq!(u32::from_le_bytes(result.data[0..4].try_into().unwrap()), max_call_depth);
+		assert_eq!(
+			u32::from_le_bytes(result.data[4..8].try_into().unwrap()),
+			RuntimeReturnCode::MaxCallDepthReached as u32,
+		);
+
+		// The stable `seal_call` keeps trapping the deepest contract instead.
+		let result = Contracts::bare_call(
+			ALICE,
+			contract_addr,
+			0,
+			GAS_LIMIT,
+			None,
+			2u32.encode(),
+			true,
+			Determinism::Deterministic,
+			DryRunOptions::default(),
+		)
+		.result
+		.unwrap();
+		assert!(!result.did_revert());
+		assert_eq!(u32::from_le_bytes(result.data[0..4].try_into().unwrap()), max_call_depth - 1);
+		assert_eq!(
+			u32::from_le_bytes(result.data[4..8].try_into().unwrap()),
+			RuntimeReturnCode::CalleeTrapped as u32,
+		);
+	});
+}
+
+#[test]
+fn account_reentrance_count_works() {
+	let (wasm, _code_hash) = compile_module::<Test>("account_reentrance_count_call").unwrap();
+	let (wasm_reentrance_count, _code_hash_reentrance_count) =
+		compile_module::<Test>("reentrance_count_call").unwrap();
+
+	ExtBuilder::default().existential_deposit(100).build().execute_with(|| {
+		let _ = Balances::deposit_creating(&ALICE, 1_000_000);
+
+		let contract_addr = Contracts::bare_instantiate(
+			ALICE,
+			300_000,
+			GAS_LIMIT,
+			None,
+			Code::Upload(wasm),
+			vec![],
+			vec![],
+			false,
+			DryRunOptions::default(),
+		)
+		.result
+		.unwrap()
+		.account_id;
+
+		let another_contract_addr = Contracts::bare_instantiate(
+			ALICE,
+			300_000,
+			GAS_LIMIT,
+			None,
+			Code::Upload(wasm_reentrance_count),
+			vec![],
+			vec![],
+			false,
+			DryRunOptions::default(),
+		)
+		.result
+		.unwrap()
+		.account_id;
+
+		let result1 = Contracts::bare_call(
+			ALICE,
+			contract_addr.clone(),
+			0,
+			GAS_LIMIT,
+			None,
+			contract_addr.encode(),
+			true,
+			Determinism::Deterministic,
+			DryRunOptions::default(),
+		)
+		.result
+		.unwrap();
+
+		let result2 = Contracts::bare_call(
+			ALICE,
+			contract_addr.clone(),
+			0,
+			GAS_LIMIT,
+			None,
+			another_contract_addr.encode(),
+			true,
+			Determinism::Deterministic,
+			DryRunOptions::default(),
+		)
+		.result
+		.unwrap();
+
 		assert_eq!(result1.data, 1.encode());
 		assert_eq!(result2.data, 0.encode());
 	});
 }
+
+#[test]
+fn reentry_is_denied_unless_allowed_by_the_reentered_contract() {
+	let (wasm, code_hash) = compile_module::<Test>("call_with_flags").unwrap();
+
+	ExtBuilder::default().existential_deposit(100).build().execute_with(|| {
+		let _ = Balances::deposit_creating(&ALICE, 1_000_000);
+
+		let instantiate = |code, salt| {
+			Contracts::bare_instantiate(
+				ALICE,
+				300_000,
+				GAS_LIMIT,
+				None,
+				code,
+				vec![],
+				salt,
+				false,
+				DryRunOptions::default(),
+			)
+			.result
+			.unwrap()
+			.account_id
+		};
+		let addr_a = instantiate(Code::Upload(wasm), vec![0]);
+		let addr_b = instantiate(Code::Existing(code_hash), vec![1]);
+		let call_a = |input: Vec<u8>| {
+			Contracts::bare_call(
+				ALICE,
+				addr_a.clone(),
+				0,
+				GAS_LIMIT,
+				None,
+				input,
+				false,
+				Determinism::Deterministic,
+				DryRunOptions::default(),
+			)
+			.result
+		};
+		let allow_reentry = CallFlags::ALLOW_REENTRY.bits();
+
+		// A -> B -> A succeeds when A allows the reentry.
+		let result = call_a((allow_reentry, &addr_b, 0u32, &addr_a).encode()).unwrap();
+		assert_return_code!(result, RuntimeReturnCode::Success);
+
+		// A -> B -> A traps B when A doesn't allow it, whatever B allows.
+		let result = call_a((0u32, &addr_b, allow_reentry, &addr_a).encode()).unwrap();
+		assert_return_code!(result, RuntimeReturnCode::CalleeTrapped);
+
+		// A -> A is denied outright without the flag.
+		assert_err!(call_a((0u32, &addr_a).encode()), <Error<Test>>::ReentranceDenied);
+
+		// A -> A is fine with it.
+		let result = call_a((allow_reentry, &addr_a).encode()).unwrap();
+		assert_return_code!(result, RuntimeReturnCode::Success);
+	});
+}
+
+#[test]
+fn event_budget_is_shared_by_nested_calls() {
+	let (wasm, code_hash) = compile_module::<Test>("event_budget").unwrap();
+
+	ExtBuilder::default().existential_deposit(100).build().execute_with(|| {
+		let _ = Balances::deposit_creating(&ALICE, 1_000_000);
+
+		let instantiate = |code, salt| {
+			Contracts::bare_instantiate(
+				ALICE,
+				300_000,
+				GAS_LIMIT,
+				None,
+				code,
+				vec![],
+				salt,
+				false,
+				DryRunOptions::default(),
+			)
+			.result
+			.unwrap()
+			.account_id
+		};
+		let addr_a = instantiate(Code::Upload(wasm), vec![0]);
+		let addr_b = instantiate(Code::Existing(code_hash), vec![1]);
+		let call_a = |input: Vec<u8>| {
+			Contracts::bare_call(
+				ALICE,
+				addr_a.clone(),
+				0,
+				GAS_LIMIT,
+				None,
+				input,
+				false,
+				Determinism::Deterministic,
+				DryRunOptions::default(),
+			)
+			.result
+		};
+		let limits = <Test as Config>::Schedule::get().limits;
+		let event_len = limits.payload_len;
+		let max_events = limits.event_bytes_per_call / event_len;
+		assert_eq!(max_events * event_len, limits.event_bytes_per_call);
+		let half = max_events / 2;
+
+		// The whole budget can be used.
+		let result = call_a((max_events, event_len).encode()).unwrap();
+		assert_return_code!(result, RuntimeReturnCode::Success);
+
+		// But not a byte more.
+		assert_err!(
+			call_a((max_events + 1, event_len).encode()),
+			<Error<Test>>::EventBudgetExhausted
+		);
+
+		// A nested call can use what is left of the budget.
+		let result =
+			call_a((half, event_len, &addr_b, max_events - half, event_len).encode()).unwrap();
+		assert_return_code!(result, RuntimeReturnCode::Success);
+
+		// But it doesn't get a budget of its own.
+		let result = call_a((max_events, event_len, &addr_b, 1u32, 0u32).encode()).unwrap();
+		assert_return_code!(result, RuntimeReturnCode::Success);
+		let result = call_a((max_events, event_len, &addr_b, 1u32, 1u32).encode()).unwrap();
+		assert_return_code!(result, RuntimeReturnCode::CalleeTrapped);
+	});
+}
+
+#[test]
+fn batch_call_observes_previous_calls() {
+	let (wasm, _code_hash) = compile_module::<Test>("allowance").unwrap();
+
+	ExtBuilder::default().existential_deposit(100).build().execute_with(|| {
+		let _ = Balances::deposit_creating(&ALICE, 1_000_000);
+		let _ = Balances::deposit_creating(&BOB, 1_000_000);
+		let addr = Contracts::bare_instantiate(
+			ALICE,
+			0,
+			GAS_LIMIT,
+			None,
+			Code::Upload(wasm),
+			vec![],
+			vec![],
+			false,
+			DryRunOptions::default(),
+		)
+		.result
+		.unwrap()
+		.account_id;
+
+		let request = |origin, input_data| CallRequest {
+			origin,
+			dest: addr.clone(),
+			value: 0,
+			gas_limit: None,
+			storage_deposit_limit: None,
+			input_data,
+		};
+		let approve = request(ALICE, (0u8, BOB, 100u64).encode());
+		let transfer_from = request(BOB, (1u8, BOB, 60u64).encode());
+		let batch_call = |calls, gas_limit| {
+			Contracts::bare_batch_call(
+				calls,
+				gas_limit,
+				false,
+				Determinism::Deterministic,
+				DryRunOptions::default(),
+			)
+		};
+		let success = Ok(ExecReturnValue { flags: ReturnFlags::empty(), data: vec![] });
+
+		// without an allowance the transfer fails.
+		let results = batch_call(vec![transfer_from.clone()], GAS_LIMIT);
+		assert_eq!(results.len(), 1);
+		assert_eq!(results[0].result, Err(<Error<Test>>::ContractTrapped.into()));
+
+		// the transfer sees the allowance of the approval before it, but can only spend it once.
+		// calls beyond `MaxBatchCalls` are not executed.
+		let results = batch_call(
+			vec![
+				approve.clone(),
+				transfer_from.clone(),
+				transfer_from.clone(),
+				transfer_from.clone(),
+			],
+			GAS_LIMIT,
+		);
+		assert_eq!(results.len(), 4);
+		assert_eq!(results[0].result, success);
+		assert_eq!(results[1].result, success);
+		assert_eq!(results[2].result, Err(<Error<Test>>::ContractTrapped.into()));
+		assert_eq!(results[3].result, Err(<Error<Test>>::BatchTooLarge.into()));
+		assert_eq!(results[3].gas_consumed, Weight::zero());
+
+		// the gas limit is shared by all calls of the batch.
+		let results =
+			batch_call(vec![approve.clone(), transfer_from.clone()], results[0].gas_required);
+		assert_eq!(results[0].result, success);
+		assert_eq!(results[1].result, Err(<Error<Test>>::OutOfGas.into()));
+
+		// all state changes of a batch are discarded.
+		let results = batch_call(vec![transfer_from], GAS_LIMIT);
+		assert_eq!(results[0].result, Err(<Error<Test>>::ContractTrapped.into()));
+	});
+}
+
+#[test]
+fn caller_is_contract_works() {
+	let (wasm, code_hash) = compile_module::<Test>("caller_is_contract").unwrap();
+
+	ExtBuilder::default().existential_deposit(100).build().execute_with(|| {
+		let _ = Balances::deposit_creating(&ALICE, 1_000_000);
+		let is_contract = |data: &[u8]| u32::from_le_bytes(data.try_into().unwrap()) == 1;
+
+		// The constructor of a contract instantiated by a plain account.
+		let result = Contracts::bare_instantiate(
+			ALICE,
+			300_000,
+			GAS_LIMIT,
+			None,
+			Code::Upload(wasm),
+			vec![],
+			vec![0],
+			false,
+			DryRunOptions::default(),
+		)
+		.result
+		.unwrap();
+		assert!(!is_contract(&result.result.data));
+		let callee = result.account_id;
+
+		// A contract that is still being instantiated is a contract as well.
+		let result = Contracts::bare_instantiate(
+			ALICE,
+			300_000,
+			GAS_LIMIT,
+			None,
+			Code::Existing(code_hash),
+			callee.encode(),
+			vec![1],
+			false,
+			DryRunOptions::default(),
+		)
+		.result
+		.unwrap();
+		assert!(is_contract(&result.result.data));
+		let caller = result.account_id;
+
+		// A plain account calls the contract.
+		let result = Contracts::bare_call(
+			ALICE,
+			callee.clone(),
+			0,
+			GAS_LIMIT,
+			None,
+			vec![],
+			false,
+			Determinism::Deterministic,
+			DryRunOptions::default(),
+		)
+		.result
+		.unwrap();
+		assert!(!is_contract(&result.data));
+
+		// A contract calls the contract.
+		let result = Contracts::bare_call(
+			ALICE,
+			caller,
+			0,
+			GAS_LIMIT,
+			None,
+			callee.encode(),
+			false,
+			Determinism::Deterministic,
+			DryRunOptions::default(),
+		)
+		.result
+		.unwrap();
+		assert!(is_contract(&result.data));
+	});
+}
+
+#[test]
+fn code_hash_works() {
+	use codec::Decode;
+	let (wasm, code_hash) = compile_module::<Test>("code_hash").unwrap();
+	let (dummy_wasm, dummy_code_hash) = compile_module::<Test>("dummy").unwrap();
+
+	ExtBuilder::default().existential_deposit(100).build().execute_with(|| {
+		let _ = Balances::deposit_creating(&ALICE, 1_000_000);
+		let instantiate = |code, salt| {
+			Contracts::bare_instantiate(
+				ALICE,
+				300_000,
+				GAS_LIMIT,
+				None,
+				code,
+				vec![],
+				salt,
+				false,
+				DryRunOptions::default(),
+			)
+			.result
+			.unwrap()
+			.account_id
+		};
+		let addr = instantiate(Code::Upload(wasm), vec![0]);
+		let other = instantiate(Code::Upload(dummy_wasm), vec![1]);
+		let code_hash_of = |input: Vec<u8>| {
+			let data = Contracts::bare_call(
+				ALICE,
+				addr.clone(),
+				0,
+				GAS_LIMIT,
+				None,
+				input,
+				false,
+				Determinism::Deterministic,
+				DryRunOptions::default(),
+			)
+			.result
+			.unwrap()
+			.data;
+			let code = u32::from_le_bytes(data[..4].try_into().unwrap());
+			(code, <Test as frame_system::Config>::Hash::decode(&mut &data[4..]).unwrap())
+		};
+
+		// The contract itself.
+		assert_eq!(code_hash_of(vec![]), (RuntimeReturnCode::Success as u32, code_hash));
+		assert_eq!(code_hash_of(addr.encode()), (RuntimeReturnCode::Success as u32, code_hash));
+
+		// Another contract.
+		assert_eq!(
+			code_hash_of(other.encode()),
+			(RuntimeReturnCode::Success as u32, dummy_code_hash)
+		);
+
+		// A plain account has no code hash.
+		assert_eq!(
+			code_hash_of(BOB.encode()),
+			(RuntimeReturnCode::KeyNotFound as u32, Default::default())
+		);
+	});
+}
+
+#[test]
+fn output_buffer_len_is_reported_on_request() {
+	let (wasm, _code_hash) = compile_module::<Test>("report_output_len").unwrap();
+	let (callee_wasm, _callee_code_hash) = compile_module::<Test>("return_with_data").unwrap();
+
+	ExtBuilder::default().existential_deposit(100).build().execute_with(|| {
+		let _ = Balances::deposit_creating(&ALICE, 1_000_000);
+		let instantiate = |code, data| {
+			Contracts::bare_instantiate(
+				ALICE,
+				300_000,
+				GAS_LIMIT,
+				None,
+				Code::Upload(code),
+				data,
+				vec![],
+				false,
+				DryRunOptions::default(),
+			)
+			.result
+			.unwrap()
+			.account_id
+		};
+		let addr = instantiate(wasm, vec![]);
+		let callee = instantiate(callee_wasm, 0u32.to_le_bytes().to_vec());
+
+		// The contract asserts that it is told the lengths it needs, and retries with them.
+		let result = Contracts::bare_call(
+			ALICE,
+			addr,
+			0,
+			GAS_LIMIT,
+			None,
+			callee.encode(),
+			false,
+			Determinism::Deterministic,
+			DryRunOptions::default(),
+		)
+		.result
+		.unwrap();
+		assert!(!result.did_revert());
+		assert_eq!(
+			result.data,
+			[(0x10..=0x1f).collect::<Vec<u8>>(), (0x21..=0x28).collect()].concat()
+		);
+	});
+}
+
+#[test]
+fn input_len_is_limited() {
+	let (wasm, code_hash) = compile_module::<Test>("dummy").unwrap();
+	let max_len = <Test as Config>::Schedule::get().limits.input_len as usize;
+
+	ExtBuilder::default().existential_deposit(100).build().execute_with(|| {
+		let _ = Balances::deposit_creating(&ALICE, 1_000_000);
+		assert_ok!(Contracts::upload_code(
+			RuntimeOrigin::signed(ALICE),
+			wasm,
+			None,
+			Determinism::Deterministic
+		));
+
+		// Instantiate with input data exceeding the limit.
+		assert_err_ignore_postinfo!(
+			Contracts::instantiate(
+				RuntimeOrigin::signed(ALICE),
+				0,
+				GAS_LIMIT,
+				None,
+				code_hash,
+				vec![0; max_len + 1],
+				vec![],
+			),
+			<Error<Test>>::InputTooLarge,
+		);
+
+		// Instantiate with input data at the limit.
+		assert_ok!(Contracts::instantiate(
+			RuntimeOrigin::signed(ALICE),
+			0,
+			GAS_LIMIT,
+			None,
+			code_hash,
+			vec![0; max_len],
+			vec![],
+		));
+		let addr = Contracts::contract_address(&ALICE, &code_hash, &vec![0; max_len], &[]);
+
+		// Call with input data exceeding the limit.
+		assert_err_ignore_postinfo!(
+			Contracts::call(
+				RuntimeOrigin::signed(ALICE),
+				addr.clone(),
+				0,
+				GAS_LIMIT,
+				None,
+				vec![0; max_len + 1],
+			),
+			<Error<Test>>::InputTooLarge,
+		);
+
+		// Call with input data at the limit.
+		assert_ok!(Contracts::call(
+			RuntimeOrigin::signed(ALICE),
+			addr,
+			0,
+			GAS_LIMIT,
+			None,
+			vec![0; max_len],
+		));
+	});
+}
+
+#[test]
+fn xcm_execute_forwards_message_with_contract_origin() {
+	let (wasm, _code_hash) = compile_module::<Test>("xcm_execute").unwrap();
+	ExtBuilder::default().existential_deposit(50).build().execute_with(|| {
+		let _ = Balances::deposit_creating(&ALICE, 1_000_000);
+		let addr = Contracts::bare_instantiate(
+			ALICE,
+			30_000,
+			GAS_LIMIT,
+			None,
+			Code::Upload(wasm),
+			vec![],
+			vec![],
+			false,
+			DryRunOptions::default(),
+		)
+		.result
+		.unwrap()
+		.account_id;
+		let call = || {
+			Contracts::bare_call(
+				ALICE,
+				addr.clone(),
+				0,
+				GAS_LIMIT,
+				None,
+				vec![1, 2, 3],
+				false,
+				Determinism::Deterministic,
+				DryRunOptions::default(),
+			)
+		};
+
+		let accepted = call();
+		assert_return_code!(accepted.result.unwrap(), RuntimeReturnCode::Success);
+		assert_eq!(
+			XcmMessages::get(),
+			vec![XcmMessage::Execute {
+				origin: addr.clone(),
+				message: vec![1, 2, 3],
+				weight_limit: Weight::from_ref_time(1_000_000_000),
+			}],
+		);
+
+		// a rejected message does not report any weight, so the whole limit is charged.
+		XcmRejects::set(true);
+		let rejected = call();
+		assert_return_code!(rejected.result.unwrap(), RuntimeReturnCode::XcmExecutionFailed);
+		assert_eq!(XcmMessages::get().len(), 1);
+		assert_eq!(
+			rejected.gas_consumed.ref_time() - accepted.gas_consumed.ref_time(),
+			500_000_000,
+		);
+	});
+}
+
+#[test]
+fn xcm_send_forwards_message_with_contract_origin() {
+	let (wasm, _code_hash) = compile_module::<Test>("xcm_send").unwrap();
+	ExtBuilder::default().existential_deposit(50).build().execute_with(|| {
+		let _ = Balances::deposit_creating(&ALICE, 1_000_000);
+		let addr = Contracts::bare_instantiate(
+			ALICE,
+			30_000,
+			GAS_LIMIT,
+			None,
+			Code::Upload(wasm),
+			vec![],
+			vec![],
+			false,
+			DryRunOptions::default(),
+		)
+		.result
+		.unwrap()
+		.account_id;
+		let call = || {
+			Contracts::bare_call(
+				ALICE,
+				addr.clone(),
+				0,
+				GAS_LIMIT,
+				None,
+				// a destination of two bytes, followed by the message.
+				vec![2, 7, 8, 1, 2, 3],
+				false,
+				Determinism::Deterministic,
+				DryRunOptions::default(),
+			)
+			.result
+			.unwrap()
+		};
+
+		assert_return_code!(call(), RuntimeReturnCode::Success);
+		assert_eq!(
+			XcmMessages::get(),
+			vec![XcmMessage::Send {
+				origin: addr.clone(),
+				dest: vec![7, 8],
+				message: vec![1, 2, 3],
+				weight_limit: Weight::from_ref_time(1_000_000_000),
+			}],
+		);
+
+		XcmRejects::set(true);
+		assert_return_code!(call(), RuntimeReturnCode::XcmSendFailed);
+		assert_eq!(XcmMessages::get().len(), 1);
+	});
+}
+
+#[test]
+fn xcm_deny_rejects_everything_for_free() {
+	ExtBuilder::default().build().execute_with(|| {
+		let denied = Err(Error::<Test>::XcmDenied.with_weight(Weight::zero()));
+		assert_eq!(
+			<xcm::Deny as XcmInterface<Test>>::execute(
+				RuntimeOrigin::signed(ALICE),
+				&[1, 2, 3],
+				GAS_LIMIT,
+			),
+			denied,
+		);
+		assert_eq!(
+			<xcm::Deny as XcmInterface<Test>>::send(
+				RuntimeOrigin::signed(ALICE),
+				&[7, 8],
+				&[1, 2, 3],
+				GAS_LIMIT,
+			),
+			denied,
+		);
+	});
+}
+
+#[test]
+fn instantiations_per_call_are_limited() {
+	let (factory_code, _) = compile_module::<Test>("instantiate_many").unwrap();
+	let (dummy_code, dummy_hash) = compile_module::<Test>("dummy").unwrap();
+	ExtBuilder::default().existential_deposit(50).build().execute_with(|| {
+		let _ = Balances::deposit_creating(&ALICE, 1_000_000);
+		MaxInstantiations::set(2);
+		assert_ok!(Contracts::upload_code(
+			RuntimeOrigin::signed(ALICE),
+			dummy_code,
+			None,
+			Determinism::Deterministic
+		));
+		let factory = Contracts::bare_instantiate(
+			ALICE,
+			100_000,
+			GAS_LIMIT,
+			None,
+			Code::Upload(factory_code),
+			vec![],
+			vec![],
+			false,
+			DryRunOptions::default(),
+		)
+		.result
+		.unwrap()
+		.account_id;
+		let call = |count: u32| {
+			Contracts::bare_call(
+				ALICE,
+				factory.clone(),
+				0,
+				GAS_LIMIT,
+				None,
+				(dummy_hash, count).encode(),
+				false,
+				Determinism::Deterministic,
+				DryRunOptions::default(),
+			)
+			.result
+		};
+
+		// one more than allowed traps and reverts all of them.
+		assert_err!(call(3), <Error<Test>>::TooManyInstantiations);
+		assert_eq!(ContractInfoOf::<Test>::iter().count(), 1);
+
+		// up to the limit is fine.
+		assert_ok!(call(2));
+		assert_eq!(ContractInfoOf::<Test>::iter().count(), 3);
+	});
+}
+
+#[test]
+fn return_data_is_limited() {
+	let (wasm, _code_hash) = compile_module::<Test>("return_sized").unwrap();
+
+	ExtBuilder::default().existential_deposit(50).build().execute_with(|| {
+		let _ = Balances::deposit_creating(&ALICE, 1_000_000);
+		let addr = Contracts::bare_instantiate(
+			ALICE,
+			30_000,
+			GAS_LIMIT,
+			None,
+			Code::Upload(wasm),
+			vec![],
+			vec![],
+			false,
+			DryRunOptions::default(),
+		)
+		.result
+		.unwrap()
+		.account_id;
+		let call = |len: u32| {
+			Contracts::bare_call(
+				ALICE,
+				addr.clone(),
+				0,
+				GAS_LIMIT,
+				None,
+				len.encode(),
+				false,
+				Determinism::Deterministic,
+				DryRunOptions::default(),
+			)
+		};
+		let max_len = <Test as Config>::Schedule::get().limits.max_return_len;
+
+		// up to the limit is fine.
+		let result = call(max_len);
+		assert_eq!(result.result.unwrap().data.len(), max_len as usize);
+		assert!(!result.data_truncated);
+
+		// one more byte traps the contract.
+		assert_err!(call(max_len + 1).result, <Error<Test>>::ReturnDataTooLarge);
+	});
+}
+
+#[test]
+fn dry_run_return_data_is_truncated() {
+	let (wasm, _code_hash) = compile_module::<Test>("return_sized").unwrap();
+
+	ExtBuilder::default().existential_deposit(50).build().execute_with(|| {
+		let _ = Balances::deposit_creating(&ALICE, 1_000_000);
+		let addr = Contracts::bare_instantiate(
+			ALICE,
+			30_000,
+			GAS_LIMIT,
+			None,
+			Code::Upload(wasm),
+			vec![],
+			vec![],
+			false,
+			DryRunOptions::default(),
+		)
+		.result
+		.unwrap()
+		.account_id;
+		let call = |len: u32| {
+			Contracts::bare_call(
+				ALICE,
+				addr.clone(),
+				0,
+				GAS_LIMIT,
+				None,
+				len.encode(),
+				false,
+				Determinism::Deterministic,
+				DryRunOptions { max_output_len: Some(8), ..Default::default() },
+			)
+		};
+
+		let result = call(8);
+		assert_eq!(result.result.unwrap().data.len(), 8);
+		assert!(!result.data_truncated);
+
+		// the contract is not limited by the cap, only its output is cut short.
+		let result = call(9);
+		assert_eq!(result.result.unwrap().data.len(), 8);
+		assert!(result.data_truncated);
+	});
+}
+
+#[test]
+fn storage_iter_yields_keys_in_trie_order() {
+	let (wasm, _code_hash) = compile_module::<Test>("storage_iter").unwrap();
+
+	ExtBuilder::default().existential_deposit(50).build().execute_with(|| {
+		let _ = Balances::deposit_creating(&ALICE, 1_000_000);
+		let addr = Contracts::bare_instantiate(
+			ALICE,
+			30_000,
+			GAS_LIMIT,
+			None,
+			Code::Upload(wasm),
+			vec![],
+			vec![],
+			false,
+			DryRunOptions::default(),
+		)
+		.result
+		.unwrap()
+		.account_id;
+		let call = |count: u32| {
+			Contracts::bare_call(
+				ALICE,
+				addr.clone(),
+				0,
+				GAS_LIMIT,
+				None,
+				count.encode(),
+				false,
+				Determinism::Deterministic,
+				DryRunOptions::default(),
+			)
+			.result
+			.unwrap()
+			.data
+		};
+		// The keys `0 .. count` in the order they are laid out in the child trie.
+		let ordered = |count: u32| {
+			let mut keys = (0..count).map(u32::to_le_bytes).collect::<Vec<_>>();
+			keys.sort_by_key(|key| Blake2_128Concat::hash(key));
+			keys.concat()
+		};
+
+		// a contract without any storage yields nothing.
+		assert_eq!(call(0), Vec::<u8>::new());
+
+		// a key written earlier in the same call is yielded.
+		assert_eq!(call(1), ordered(1));
+		assert_eq!(call(0), ordered(1));
+
+		// items stored under a fixed sized key are skipped.
+		Storage::<Test>::write(
+			&get_contract(&addr).trie_id,
+			&[1u8; 32],
+			Some(vec![1]),
+			None,
+			false,
+		)
+		.unwrap();
+		assert_eq!(call(0), ordered(1));
+
+		// many keys are yielded by hash rather than by insertion order.
+		assert_eq!(call(20), ordered(20));
+		assert_eq!(call(0), ordered(20));
+	});
+}
+
+#[test]
+fn storage_iter_is_limited_per_call() {
+	let (wasm, _code_hash) = compile_module::<Test>("storage_iter").unwrap();
+
+	ExtBuilder::default().existential_deposit(50).build().execute_with(|| {
+		MaxStorageIterations::set(5);
+		let _ = Balances::deposit_creating(&ALICE, 1_000_000);
+		let addr = Contracts::bare_instantiate(
+			ALICE,
+			30_000,
+			GAS_LIMIT,
+			None,
+			Code::Upload(wasm),
+			vec![],
+			vec![],
+			false,
+			DryRunOptions::default(),
+		)
+		.result
+		.unwrap()
+		.account_id;
+		let call = |count: u32| {
+			Contracts::bare_call(
+				ALICE,
+				addr.clone(),
+				0,
+				GAS_LIMIT,
+				None,
+				count.encode(),
+				false,
+				Determinism::Deterministic,
+				DryRunOptions::default(),
+			)
+			.result
+		};
+
+		// yielding four keys and detecting the end visits five entries.
+		assert_eq!(call(4).unwrap().data.len(), 16);
+
+		// a fifth key exceeds the budget of the call.
+		assert_err!(call(5), <Error<Test>>::TooManyStorageIterations);
+	});
+}
+
+#[test]
+fn storage_iter_charges_visited_entries_only() {
+	let (wasm, _code_hash) = compile_module::<Test>("storage_iter").unwrap();
+
+	ExtBuilder::default().existential_deposit(50).build().execute_with(|| {
+		// far more entries than the gas limit could pay for.
+		MaxStorageIterations::set(u32::MAX);
+		let _ = Balances::deposit_creating(&ALICE, 1_000_000);
+		let addr = Contracts::bare_instantiate(
+			ALICE,
+			30_000,
+			GAS_LIMIT,
+			None,
+			Code::Upload(wasm),
+			vec![],
+			vec![],
+			false,
+			DryRunOptions::default(),
+		)
+		.result
+		.unwrap()
+		.account_id;
+
+		// only the five visited entries are charged for, not the whole budget.
+		let result = Contracts::bare_call(
+			ALICE,
+			addr,
+			0,
+			GAS_LIMIT,
+			None,
+			4u32.encode(),
+			false,
+			Determinism::Deterministic,
+			DryRunOptions::default(),
+		)
+		.result
+		.unwrap();
+		assert_eq!(result.data.len(), 16);
+	});
+}
+
+#[test]
+fn export_contract_pages_through_storage() {
+	let (wasm, _code_hash) = compile_module::<Test>("dummy").unwrap();
+
+	ExtBuilder::default().existential_deposit(50).build().execute_with(|| {
+		let _ = Balances::deposit_creating(&ALICE, 1_000_000);
+		let addr = Contracts::bare_instantiate(
+			ALICE,
+			30_000,
+			GAS_LIMIT,
+			None,
+			Code::Upload(wasm),
+			vec![],
+			vec![],
+			false,
+			DryRunOptions::default(),
+		)
+		.result
+		.unwrap()
+		.account_id;
+		let trie_id = get_contract(&addr).trie_id;
+		for i in 0..10u8 {
+			Storage::<Test>::write(&trie_id, &[i; 32], Some(vec![i; 4]), None, false).unwrap();
+		}
+
+		let (all, next) = Contracts::export_contract(addr.clone(), None, 100).unwrap();
+		assert_eq!(all.len(), 10);
+		assert_eq!(next, None);
+
+		// the pages add up to the whole storage.
+		let mut paged = Vec::new();
+		let mut start_key = None;
+		loop {
+			let (entries, next) = Contracts::export_contract(addr.clone(), start_key, 4).unwrap();
+			assert!(entries.len() <= 4);
+			paged.extend(entries);
+			match next {
+				Some(key) => start_key = Some(key),
+				None => break,
+			}
+		}
+		assert_eq!(paged, all);
+
+		// a start key which is not stored starts at the next entry.
+		let mut start_key = all[3].0.clone();
+		start_key.push(0);
+		let (entries, _) = Contracts::export_contract(addr.clone(), Some(start_key), 100).unwrap();
+		assert_eq!(entries, all[4..]);
+
+		// a page never exceeds the maximum, whatever the requested limit.
+		for i in 10..=crate::MAX_EXPORT_ENTRIES {
+			Storage::<Test>::write_raw(&trie_id, &i.to_le_bytes(), Some(vec![]), None, false)
+				.unwrap();
+		}
+		let (entries, next) = Contracts::export_contract(addr, None, u32::MAX).unwrap();
+		assert_eq!(entries.len(), crate::MAX_EXPORT_ENTRIES as usize);
+		assert!(next.is_some());
+
+		assert_eq!(
+			Contracts::export_contract(BOB, None, 100),
+			Err(pallet_contracts_primitives::ContractAccessError::DoesntExist),
+		);
+	});
+}
+
+#[test]
+fn import_contract_storage_works() {
+	let (wasm, _code_hash) = compile_module::<Test>("storage_iter").unwrap();
+	let instantiate = || {
+		let _ = Balances::deposit_creating(&ALICE, 1_000_000);
+		Contracts::bare_instantiate(
+			ALICE,
+			30_000,
+			GAS_LIMIT,
+			None,
+			Code::Upload(wasm.clone()),
+			vec![],
+			vec![],
+			false,
+			DryRunOptions::default(),
+		)
+		.result
+		.unwrap()
+		.account_id
+	};
+	// Writes the keys `0 .. count` and returns all keys of the contract.
+	let call = |addr: &AccountId32, count: u32| {
+		Contracts::bare_call(
+			ALICE,
+			addr.clone(),
+			0,
+			GAS_LIMIT,
+			None,
+			count.encode(),
+			false,
+			Determinism::Deterministic,
+			DryRunOptions::default(),
+		)
+		.result
+		.unwrap()
+		.data
+	};
+
+	// Export the storage of a contract on one chain ...
+	let (exported, keys) =
+		ExtBuilder::default().existential_deposit(50).build().execute_with(|| {
+			let addr = instantiate();
+			let keys = call(&addr, 40);
+			let (exported, next) = Contracts::export_contract(addr, None, 100).unwrap();
+			assert_eq!(exported.len(), 40);
+			assert_eq!(next, None);
+			(exported, keys)
+		});
+
+	// ... and import it into the same contract on another one.
+	ExtBuilder::default().existential_deposit(50).build().execute_with(|| {
+		let addr = instantiate();
+		let import = |origin, entries| {
+			Contracts::import_contract_storage(origin, addr.clone(), ALICE, entries)
+		};
+
+		assert_noop!(
+			import(RuntimeOrigin::root(), exported.clone()),
+			<Error<Test>>::StorageImportDisabled,
+		);
+		AllowStorageImport::set(true);
+		assert_noop!(
+			import(RuntimeOrigin::signed(ALICE), exported.clone()),
+			sp_runtime::traits::BadOrigin,
+		);
+		assert_noop!(
+			import(RuntimeOrigin::root(), vec![(vec![], vec![1])]),
+			<Error<Test>>::InvalidStorageKey,
+		);
+		assert_noop!(
+			import(RuntimeOrigin::root(), vec![(vec![1; 16 + 128 + 1], vec![1])]),
+			<Error<Test>>::InvalidStorageKey,
+		);
+		assert_noop!(
+			Contracts::import_contract_storage(RuntimeOrigin::root(), BOB, ALICE, exported.clone()),
+			<Error<Test>>::ContractNotFound,
+		);
+
+		let info_before = get_contract(&addr);
+		let balance_before = Balances::free_balance(&ALICE);
+		assert_ok!(import(RuntimeOrigin::root(), exported.clone()));
+		assert_eq!(Contracts::export_contract(addr.clone(), None, 100).unwrap().0, exported);
+		System::assert_last_event(
+			crate::Event::ContractStorageImported {
+				contract: addr.clone(),
+				depositor: ALICE,
+				items: 40,
+			}
+			.into(),
+		);
+
+		// the depositor paid for the new items like the contract had written them itself.
+		let info = get_contract(&addr);
+		let deposit = 40 * DepositPerByte::get() + 40 * DepositPerItem::get();
+		assert_eq!(info.storage_items, info_before.storage_items + 40);
+		assert_eq!(info.storage_bytes, info_before.storage_bytes + 40);
+		assert_eq!(Balances::free_balance(&ALICE), balance_before - deposit);
+
+		// importing again overwrites the entries without charging for them again.
+		assert_ok!(import(RuntimeOrigin::root(), exported.clone()));
+		assert_eq!(get_contract(&addr).storage_items, info.storage_items);
+		assert_eq!(Balances::free_balance(&ALICE), balance_before - deposit);
+
+		// the contract finds its storage as it left it on the other chain.
+		assert_eq!(call(&addr, 0), keys);
+	});
+}
@@ -32,16 +32,16 @@ use crate::{
 	gas::{GasMeter, Token},
 	wasm::{prepare, PrefabWasmModule},
 	weights::WeightInfo,
-	CodeHash, CodeStorage, Config, Error, Event, OwnerInfoOf, Pallet, PristineCode, Schedule,
-	Weight,
+	CodeHash, CodeStorage, Config, Error, Event, OwnerInfoOf, Pallet, PristineCode,
+	ReinstrumentPolicy, Schedule, Weight,
 };
 use frame_support::{
-	dispatch::{DispatchError, DispatchResult},
+	dispatch::{DispatchClass, DispatchError, DispatchResult},
 	ensure,
-	traits::{Get, ReservableCurrency},
+	traits::{Currency, ExistenceRequirement, Get, Imbalance, ReservableCurrency, WithdrawReasons},
 	WeakBoundedVec,
 };
-use sp_runtime::traits::BadOrigin;
+use sp_runtime::traits::{BadOrigin, Convert, Saturating};
 use sp_std::vec;
 
 /// Put the instrumented module in storage.
@@ -168,14 +168,69 @@ pub fn load<T: Config>(
 	if prefab_module.instruction_weights_version < schedule.instruction_weights.version {
 		// The instruction weights have changed.
 		// We need to re-instrument the code with the new instruction weights.
+		// The caller needs to have enough gas for it in any case so that the weight is bounded
+		// by its gas limit. It is refunded if someone else pays for it.
 		let charged = gas_meter.charge(CodeToken::Reinstrument(max_code_len))?;
 		let code_size = reinstrument(&mut prefab_module, schedule)?;
-		gas_meter.adjust_gas(charged, CodeToken::Reinstrument(code_size));
+		if pay_reinstrument::<T>(code_hash, code_size) {
+			gas_meter.adjust_gas(charged, CodeToken::Subsidized);
+		} else {
+			gas_meter.adjust_gas(charged, CodeToken::Reinstrument(code_size));
+		}
 	}
 
 	Ok(prefab_module)
 }
 
+/// Pay for re-instrumenting `code_hash` on behalf of the caller, as configured by
+/// [`Config::ReinstrumentPolicy`].
+///
+/// The fee equivalent of the weight is burned from the configured source, and the weight is
+/// registered with the block since it is refunded from the gas of the caller.
+///
+/// Returns `false` if the caller has to pay with its gas instead.
+fn pay_reinstrument<T: Config>(code_hash: CodeHash<T>, code_len: u32) -> bool {
+	let weight = T::WeightInfo::reinstrument(code_len);
+	let fee = T::WeightPrice::convert(weight);
+	let paid = match T::ReinstrumentPolicy::get() {
+		ReinstrumentPolicy::ChargeCaller => return false,
+		ReinstrumentPolicy::ChargeCodeOwnerDeposit =>
+			<OwnerInfoOf<T>>::mutate(&code_hash, |owner_info| match owner_info {
+				// The reserved balance can be lower than the recorded deposit if the owner was
+				// slashed by some other pallet in the meantime.
+				Some(owner_info)
+					if owner_info.deposit >= fee &&
+						T::Currency::reserved_balance(&owner_info.owner) >= fee =>
+				{
+					let (slashed, _) = T::Currency::slash_reserved(&owner_info.owner, fee);
+					owner_info.deposit = owner_info.deposit.saturating_sub(slashed.peek());
+					true
+				},
+				_ => false,
+			}),
+		ReinstrumentPolicy::Subsidized => T::Currency::withdraw(
+			&<Pallet<T>>::reinstrument_pot(),
+			fee,
+			WithdrawReasons::FEE,
+			ExistenceRequirement::KeepAlive,
+		)
+		.is_ok(),
+	};
+
+	if paid {
+		// The weight can't be charged to the gas meter, as the caller would pay for it when its
+		// gas is settled. Registering it unchecked can't exceed the block limits: the caller held
+		// it as part of its gas limit, which the block admitted, before it was refunded.
+		<frame_system::Pallet<T>>::register_extra_weight_unchecked(weight, DispatchClass::Normal);
+	} else {
+		<Pallet<T>>::deposit_event(
+			vec![code_hash],
+			Event::ReinstrumentChargedToCaller { code_hash },
+		);
+	}
+	paid
+}
+
 /// Instruments the passed prefab wasm module with the supplied schedule.
 ///
 /// Returns the size in bytes of the uninstrumented code.
@@ -211,6 +266,8 @@ enum CodeToken {
 	Reinstrument(u32),
 	/// Weight for loading a contract per byte.
 	Load(u32),
+	/// Re-instrumentation that is paid for by someone else than the caller.
+	Subsidized,
 }
 
 impl<T: Config> Token<T> for CodeToken {
@@ -226,6 +283,7 @@ impl<T: Config> Token<T> for CodeToken {
 			Load(len) => T::WeightInfo::call_with_code_per_byte(len)
 				.saturating_sub(T::WeightInfo::call_with_code_per_byte(0))
 				.set_proof_size(len.into()),
+			Subsidized => Weight::zero(),
 		}
 	}
 }
@@ -25,17 +25,18 @@ mod runtime;
 #[cfg(feature = "runtime-benchmarks")]
 pub use crate::wasm::code_cache::reinstrument;
 pub use crate::wasm::{
-	prepare::TryInstantiate,
-	runtime::{CallFlags, Environment, ReturnCode, Runtime, RuntimeCosts},
+	prepare::{AllowDeprecatedInterface, ExplainRejection, PrepareError, TryInstantiate},
+	runtime::{CallFlags, CalleeError, Environment, ReturnCode, Runtime, RuntimeCosts},
 };
 use crate::{
 	exec::{ExecResult, Executable, ExportedFunction, Ext},
 	gas::GasMeter,
-	AccountIdOf, BalanceOf, CodeHash, CodeStorage, CodeVec, Config, Error, RelaxedCodeVec,
-	Schedule,
+	AccountIdOf, BalanceOf, CodeHash, CodeStorage, CodeVec, Config, Error, OwnerInfoOf,
+	PristineCode, RelaxedCodeVec, Schedule,
 };
 use codec::{Decode, Encode, MaxEncodedLen};
 use frame_support::dispatch::{DispatchError, DispatchResult};
+use pallet_contracts_primitives::HostFunction;
 use sp_core::Get;
 use sp_runtime::RuntimeDebug;
 use sp_std::prelude::*;
@@ -109,6 +110,23 @@ pub struct OwnerInfo<T: Config> {
 	refcount: u64,
 }
 
+/// Metadata about a stored code blob as returned by [`crate::Pallet::code_metadata`].
+#[derive(Clone, Encode, Decode, scale_info::TypeInfo, RuntimeDebug, PartialEq, Eq)]
+pub struct CodeMetadata<AccountId, Balance> {
+	/// The account that has uploaded the code and hence is allowed to remove it.
+	pub owner: AccountId,
+	/// The amount of balance that was deposited by the owner in order to store the code.
+	pub deposit: Balance,
+	/// The number of contracts that use this as their code.
+	pub refcount: u64,
+	/// The size in bytes of the code after instrumentation.
+	pub instrumented_size: u32,
+	/// The size in bytes of the code as it was uploaded.
+	pub original_size: u32,
+	/// The determinism level the code was uploaded with.
+	pub determinism: Determinism,
+}
+
 /// Defines the required determinism level of a wasm blob when either running or uploading code.
 #[derive(
 	Clone, Copy, Encode, Decode, scale_info::TypeInfo, MaxEncodedLen, RuntimeDebug, PartialEq, Eq,
@@ -136,6 +154,7 @@ impl ExportedFunction {
 		match self {
 			Self::Constructor => "deploy",
 			Self::Call => "call",
+			Self::Migrate => "migrate",
 		}
 	}
 }
@@ -151,13 +170,21 @@ impl<T: Config> PrefabWasmModule<T> {
 		owner: AccountIdOf<T>,
 		determinism: Determinism,
 		try_instantiate: TryInstantiate,
-	) -> Result<Self, (DispatchError, &'static str)> {
+		allow_deprecated: AllowDeprecatedInterface,
+		explain_rejection: ExplainRejection,
+	) -> Result<Self, PrepareError> {
 		let module = prepare::prepare::<runtime::Env, T>(
-			original_code.try_into().map_err(|_| (<Error<T>>::CodeTooLarge.into(), ""))?,
+			original_code.try_into().map_err(|_| PrepareError {
+				error: <Error<T>>::CodeTooLarge.into(),
+				validation_error: None,
+				msg: "",
+			})?,
 			schedule,
 			owner,
 			determinism,
 			try_instantiate,
+			allow_deprecated,
+			explain_rejection,
 		)?;
 		Ok(module)
 	}
@@ -176,6 +203,25 @@ impl<T: Config> PrefabWasmModule<T> {
 		code_cache::try_remove::<T>(origin, code_hash)
 	}
 
+	/// Returns the metadata of the code stored under `code_hash`.
+	///
+	/// Returns `None` if no code is stored under this hash.
+	pub fn metadata(
+		code_hash: &CodeHash<T>,
+	) -> Option<CodeMetadata<AccountIdOf<T>, BalanceOf<T>>> {
+		let owner_info = <OwnerInfoOf<T>>::get(code_hash)?;
+		let module = <CodeStorage<T>>::get(code_hash)?;
+		let original_size = <PristineCode<T>>::decode_len(code_hash)?;
+		Some(CodeMetadata {
+			owner: owner_info.owner,
+			deposit: owner_info.deposit,
+			refcount: owner_info.refcount,
+			instrumented_size: module.code.len() as u32,
+			original_size: original_size as u32,
+			determinism: module.determinism,
+		})
+	}
+
 	/// Returns whether there is a deposit to be payed for this module.
 	///
 	/// Returns `0` if the module is already in storage and hence no deposit will
@@ -260,6 +306,29 @@ impl<T: Config> OwnerInfo<T> {
 	}
 }
 
+/// The host functions which newly uploaded contracts can import, leaving out the unstable ones
+/// unless `allow_unstable` is set.
+///
+/// Functions that uploaded code is never allowed to import, like `gas` or the deprecated
+/// transfer functions, are not part of the list.
+pub fn host_functions(allow_unstable: bool) -> Vec<HostFunction> {
+	let importable = |name: &str| {
+		!prepare::DISALLOWED_IMPORTS
+			.iter()
+			.chain(prepare::DEPRECATED_IMPORTS.iter())
+			.any(|f| name.as_bytes() == *f)
+	};
+	runtime::Env::HOST_FUNCTIONS
+		.iter()
+		.filter(|(_, name, is_stable)| (*is_stable || allow_unstable) && importable(name))
+		.map(|&(version, name, stable)| HostFunction {
+			name: name.as_bytes().to_vec(),
+			version,
+			stable,
+		})
+		.collect()
+}
+
 impl<T: Config> Executable<T> for PrefabWasmModule<T> {
 	fn from_storage(
 		code_hash: CodeHash<T>,
@@ -333,7 +402,7 @@ mod tests {
 	use crate::{
 		exec::{
 			AccountIdOf, BlockNumberOf, ErrorOrigin, ExecError, Executable, Ext, FixSizedKey,
-			SeedOf, VarSizedKey,
+			SeedOf, StorageIterStep, VarSizedKey,
 		},
 		gas::GasMeter,
 		storage::WriteOutcome,
@@ -345,6 +414,7 @@ mod tests {
 		assert_err, assert_ok,
 		dispatch::DispatchResultWithPostInfo,
 		weights::{OldWeight, Weight},
+		Blake2_128Concat, StorageHasher,
 	};
 	use pallet_contracts_primitives::{ExecReturnValue, ReturnFlags};
 	use pretty_assertions::assert_eq;
@@ -446,6 +516,14 @@ mod tests {
 			self.calls.push(CallEntry { to, value, data, allows_reentry });
 			Ok(ExecReturnValue { flags: ReturnFlags::empty(), data: call_return_data() })
 		}
+		fn transfer_to_account(
+			&mut self,
+			to: &AccountIdOf<Self::T>,
+			value: u64,
+		) -> Result<bool, DispatchError> {
+			self.transfers.push(TransferEntry { to: to.clone(), value });
+			Ok(true)
+		}
 		fn delegate_call(
 			&mut self,
 			code_hash: CodeHash<Self::T>,
@@ -478,6 +556,10 @@ mod tests {
 			self.code_hashes.push(hash);
 			Ok(())
 		}
+		fn set_code_hash_and_migrate(&mut self, hash: CodeHash<Self::T>) -> ExecResult {
+			self.code_hashes.push(hash);
+			Ok(ExecReturnValue { flags: ReturnFlags::empty(), data: Vec::new() })
+		}
 		fn transfer(&mut self, to: &AccountIdOf<Self::T>, value: u64) -> Result<(), DispatchError> {
 			self.transfers.push(TransferEntry { to: to.clone(), value });
 			Ok(())
@@ -536,6 +618,21 @@ mod tests {
 			}
 			Ok(result)
 		}
+		fn next_storage_key(&mut self, cursor: &mut Vec<u8>) -> StorageIterStep<Self::T> {
+			let next = self
+				.storage
+				.keys()
+				.map(|key| (Blake2_128Concat::hash(key), key))
+				.filter(|(hashed, _)| *hashed > *cursor)
+				.min();
+			match next {
+				Some((hashed, key)) => {
+					*cursor = hashed;
+					StorageIterStep::Key(key.clone().try_into().unwrap())
+				},
+				None => StorageIterStep::End,
+			}
+		}
 		fn caller(&self) -> &AccountIdOf<Self::T> {
 			&ALICE
 		}
@@ -552,6 +649,9 @@ mod tests {
 		fn caller_is_origin(&self) -> bool {
 			false
 		}
+		fn caller_is_contract(&self) -> bool {
+			true
+		}
 		fn address(&self) -> &AccountIdOf<Self::T> {
 			&BOB
 		}
@@ -567,11 +667,12 @@ mod tests {
 		fn minimum_balance(&self) -> u64 {
 			666
 		}
-		fn random(&self, subject: &[u8]) -> (SeedOf<Self::T>, BlockNumberOf<Self::T>) {
+		fn random(&mut self, subject: &[u8]) -> (SeedOf<Self::T>, BlockNumberOf<Self::T>) {
 			(H256::from_slice(subject), 42)
 		}
-		fn deposit_event(&mut self, topics: Vec<H256>, data: Vec<u8>) {
-			self.events.push((topics, data))
+		fn deposit_event(&mut self, topics: Vec<H256>, data: Vec<u8>) -> Result<(), DispatchError> {
+			self.events.push((topics, data));
+			Ok(())
 		}
 		fn block_number(&self) -> u64 {
 			121
@@ -599,6 +700,21 @@ mod tests {
 			self.runtime_calls.borrow_mut().push(call);
 			Ok(Default::default())
 		}
+		fn xcm_execute(
+			&self,
+			_message: &[u8],
+			_weight_limit: Weight,
+		) -> DispatchResultWithPostInfo {
+			Ok(Default::default())
+		}
+		fn xcm_send(
+			&self,
+			_dest: &[u8],
+			_message: &[u8],
+			_weight_limit: Weight,
+		) -> DispatchResultWithPostInfo {
+			Ok(Default::default())
+		}
 		fn ecdsa_recover(
 			&self,
 			signature: &[u8; 65],
@@ -619,6 +735,9 @@ mod tests {
 		fn account_reentrance_count(&self, _account_id: &AccountIdOf<Self::T>) -> u32 {
 			12
 		}
+		fn call_stack_remaining(&self) -> u32 {
+			7
+		}
 		fn nonce(&mut self) -> u64 {
 			995
 		}
@@ -629,6 +748,7 @@ mod tests {
 		input_data: Vec<u8>,
 		mut ext: E,
 		unstable_interface: bool,
+		allow_deprecated: AllowDeprecatedInterface,
 	) -> ExecResult {
 		type RuntimeConfig = <MockExt as Ext>::T;
 		RuntimeConfig::set_unstable_interface(unstable_interface);
@@ -640,13 +760,24 @@ mod tests {
 			ALICE,
 			Determinism::Deterministic,
 			TryInstantiate::Skip,
+			allow_deprecated,
+			ExplainRejection::No,
 		)
-		.map_err(|err| err.0)?;
+		.map_err(|err| err.error)?;
 		executable.execute(ext.borrow_mut(), &ExportedFunction::Call, input_data)
 	}
 
 	fn execute<E: BorrowMut<MockExt>>(wat: &str, input_data: Vec<u8>, ext: E) -> ExecResult {
-		execute_internal(wat, input_data, ext, true)
+		execute_internal(wat, input_data, ext, true, AllowDeprecatedInterface::No)
+	}
+
+	/// Same as [`execute`] but for code that was uploaded before its imports got deprecated.
+	fn execute_legacy<E: BorrowMut<MockExt>>(
+		wat: &str,
+		input_data: Vec<u8>,
+		ext: E,
+	) -> ExecResult {
+		execute_internal(wat, input_data, ext, true, AllowDeprecatedInterface::Yes)
 	}
 
 	#[cfg(not(feature = "runtime-benchmarks"))]
@@ -655,7 +786,7 @@ mod tests {
 		input_data: Vec<u8>,
 		ext: E,
 	) -> ExecResult {
-		execute_internal(wat, input_data, ext, false)
+		execute_internal(wat, input_data, ext, false, AllowDeprecatedInterface::No)
 	}
 
 	const CODE_TRANSFER: &str = r#"
@@ -695,11 +826,21 @@ mod tests {
 	#[test]
 	fn contract_transfer() {
 		let mut mock_ext = MockExt::default();
-		assert_ok!(execute(CODE_TRANSFER, vec![], &mut mock_ext));
+		assert_ok!(execute_legacy(CODE_TRANSFER, vec![], &mut mock_ext));
 
 		assert_eq!(&mock_ext.transfers, &[TransferEntry { to: ALICE, value: 153 }]);
 	}
 
+	#[test]
+	fn contract_transfer_is_deprecated() {
+		let mut mock_ext = MockExt::default();
+		assert_err!(
+			execute(CODE_TRANSFER, vec![], &mut mock_ext),
+			<Error<Test>>::CodeRejected,
+		);
+		assert!(mock_ext.transfers.is_empty());
+	}
+
 	const CODE_CALL: &str = r#"
 (module
 	;; seal_call(
@@ -757,6 +898,66 @@ mod tests {
 		);
 	}
 
+	const CODE_CALL_TRANSFER_TO_ACCOUNT: &str = r#"
+(module
+	(import "seal1" "seal_call" (func $seal_call (param i32 i32 i64 i32 i32 i32 i32 i32) (result i32)))
+	(import "env" "memory" (memory 1 1))
+	(func (export "call")
+		(drop
+			(call $seal_call
+				(i32.const 0x40) ;; TRANSFER_TO_ACCOUNT
+				(i32.const 4)  ;; Pointer to "callee" address.
+				(i64.const 0)  ;; How much gas to devote for the execution. 0 = all.
+				(i32.const 36) ;; Pointer to the buffer with value to transfer
+				(i32.const 0)  ;; Pointer to input data buffer address
+				(i32.const 0)  ;; Length of input data buffer: no input
+				(i32.const 4294967295) ;; u32 max value is the sentinel value: do not copy output
+				(i32.const 0) ;; Length is ignored in this case
+			)
+		)
+	)
+	(func (export "deploy"))
+
+	;; Destination AccountId (ALICE)
+	(data (i32.const 4)
+		"\01\01\01\01\01\01\01\01\01\01\01\01\01\01\01\01"
+		"\01\01\01\01\01\01\01\01\01\01\01\01\01\01\01\01"
+	)
+
+	;; Amount of value to transfer.
+	;; Represented by u64 (8 bytes long) in little endian.
+	(data (i32.const 36) "\06\00\00\00\00\00\00\00")
+)
+"#;
+
+	#[test]
+	fn contract_call_transfer_to_account() {
+		let mut mock_ext = MockExt::default();
+		assert_ok!(execute(CODE_CALL_TRANSFER_TO_ACCOUNT, vec![], &mut mock_ext));
+
+		assert_eq!(&mock_ext.transfers, &[TransferEntry { to: ALICE, value: 6 }]);
+		assert!(mock_ext.calls.is_empty());
+	}
+
+	#[test]
+	fn contract_call_input_len_is_limited() {
+		// `CODE_CALL` passes 4 bytes of input data.
+		let mut mock_ext = MockExt::default();
+		mock_ext.schedule.limits.input_len = 3;
+		assert_eq!(
+			execute(CODE_CALL, vec![], &mut mock_ext),
+			Err(ExecError {
+				error: Error::<Test>::InputTooLarge.into(),
+				origin: ErrorOrigin::Caller,
+			})
+		);
+		assert!(mock_ext.calls.is_empty());
+
+		mock_ext.schedule.limits.input_len = 4;
+		assert_ok!(execute(CODE_CALL, vec![], &mut mock_ext));
+		assert_eq!(mock_ext.calls.len(), 1);
+	}
+
 	#[test]
 	fn contract_delegate_call() {
 		const CODE: &str = r#"
@@ -1109,6 +1310,25 @@ mod tests {
 		);
 	}
 
+	#[test]
+	fn contract_instantiate_input_len_is_limited() {
+		// `CODE_INSTANTIATE` passes 4 bytes of input data.
+		let mut mock_ext = MockExt::default();
+		mock_ext.schedule.limits.input_len = 3;
+		assert_eq!(
+			execute(CODE_INSTANTIATE, vec![], &mut mock_ext),
+			Err(ExecError {
+				error: Error::<Test>::InputTooLarge.into(),
+				origin: ErrorOrigin::Caller,
+			})
+		);
+		assert!(mock_ext.instantiates.is_empty());
+
+		mock_ext.schedule.limits.input_len = 4;
+		assert_ok!(execute(CODE_INSTANTIATE, vec![], &mut mock_ext));
+		assert_eq!(mock_ext.instantiates.len(), 1);
+	}
+
 	const CODE_TERMINATE: &str = r#"
 (module
 	;; seal_terminate(
@@ -2968,6 +3188,62 @@ mod tests {
 		execute(CODE, vec![], &mut mock_ext).unwrap();
 	}
 
+	#[test]
+	fn call_stack_remaining_works() {
+		const CODE: &str = r#"
+(module
+	(import "seal0" "call_stack_remaining" (func $call_stack_remaining (result i32)))
+	(import "env" "memory" (memory 1 1))
+	(func $assert (param i32)
+		(block $ok
+			(br_if $ok
+				(get_local 0)
+			)
+			(unreachable)
+		)
+	)
+	(func (export "call")
+		(call $assert
+			(i32.eq (call $call_stack_remaining) (i32.const 7))
+		)
+	)
+
+	(func (export "deploy"))
+)
+"#;
+
+		let mut mock_ext = MockExt::default();
+		execute(CODE, vec![], &mut mock_ext).unwrap();
+	}
+
+	#[test]
+	fn caller_is_contract_works() {
+		const CODE: &str = r#"
+(module
+	(import "seal0" "caller_is_contract" (func $caller_is_contract (result i32)))
+	(import "env" "memory" (memory 1 1))
+	(func $assert (param i32)
+		(block $ok
+			(br_if $ok
+				(get_local 0)
+			)
+			(unreachable)
+		)
+	)
+	(func (export "call")
+		(call $assert
+			(i32.eq (call $caller_is_contract) (i32.const 1))
+		)
+	)
+
+	(func (export "deploy"))
+)
+"#;
+
+		let mut mock_ext = MockExt::default();
+		execute(CODE, vec![], &mut mock_ext).unwrap();
+	}
+
 	#[test]
 	fn instantiation_nonce_works() {
 		const CODE: &str = r#"
@@ -26,6 +26,7 @@ use crate::{
 	AccountIdOf, CodeVec, Config, Error, Schedule,
 };
 use codec::{Encode, MaxEncodedLen};
+use pallet_contracts_primitives::ValidationError;
 use sp_runtime::{traits::Hash, DispatchError};
 use sp_std::prelude::*;
 use wasm_instrument::{
@@ -33,7 +34,7 @@ use wasm_instrument::{
 	parity_wasm::elements::{self, External, Internal, MemoryType, Type, ValueType},
 };
 use wasmi::StackLimits;
-use wasmparser::{Validator, WasmFeatures};
+use wasmparser::{BinaryReaderError, Validator, WasmFeatures};
 
 /// Imported memory must be located inside this module. The reason for hardcoding is that current
 /// compiler toolchains might not support specifying other modules than "env" for memory imports.
@@ -54,6 +55,60 @@ pub enum TryInstantiate {
 	Skip,
 }
 
+/// Determines whether a module may import deprecated host functions.
+pub enum AllowDeprecatedInterface {
+	/// Reject the module if it imports any of the [`DEPRECATED_IMPORTS`].
+	///
+	/// This applies to all newly uploaded code.
+	No,
+	/// Accept imports of deprecated host functions.
+	///
+	/// Code that was validated before a host function got deprecated must keep working.
+	/// This is why it is allowed to import them when it is re-instrumented.
+	Yes,
+}
+
+/// Determines whether a validation failure is analysed further to report its reason.
+pub enum ExplainRejection {
+	/// Report [`ValidationError::Other`] for every module the validator rejects.
+	///
+	/// This applies to code that is uploaded on-chain.
+	No,
+	/// Validate a rejected module a second time to find out why it was rejected.
+	///
+	/// This additional work is not accounted for in the weight of any dispatchable.
+	/// It must therefore only be requested by dry-runs.
+	Yes,
+}
+
+/// Host functions that newly uploaded code is no longer allowed to import.
+///
+/// `seal_transfer` is superseded by `seal_call` with an empty input.
+pub const DEPRECATED_IMPORTS: [&[u8]; 2] = [b"transfer", b"seal_transfer"];
+
+/// Host functions that no code is allowed to import.
+///
+/// `gas` is injected by the gas metering and therefore an implementation detail.
+pub const DISALLOWED_IMPORTS: [&[u8]; 1] = [b"gas"];
+
+/// The error returned when a module could not be prepared.
+pub struct PrepareError {
+	/// The error with which the dispatchable fails.
+	pub error: DispatchError,
+	/// Why the module was rejected in case it failed validation.
+	pub validation_error: Option<ValidationError>,
+	/// A human readable description of the failure.
+	pub msg: &'static str,
+}
+
+/// A validation failure together with a human readable description.
+type Rejection = (ValidationError, &'static str);
+
+/// Returns a closure which tags a validation failure message with `reason`.
+fn rejected(reason: ValidationError) -> impl FnOnce(&'static str) -> Rejection {
+	move |msg| (reason, msg)
+}
+
 struct ContractModule<'a, T: Config> {
 	/// A deserialized module. The module is valid (this is Guaranteed by `new` method).
 	module: elements::Module,
@@ -74,6 +129,17 @@ impl<'a, T: Config> ContractModule<'a, T> {
 		Ok(ContractModule { module, schedule })
 	}
 
+	/// Ensures that the module doesn't declare a start function.
+	///
+	/// A start function would be executed on instantiation of the module which
+	/// would bypass the `deploy` and `call` exports.
+	fn ensure_no_start_function(&self) -> Result<(), &'static str> {
+		if self.module.start_section().is_some() {
+			return Err("module declares a start function")
+		}
+		Ok(())
+	}
+
 	/// Ensures that module doesn't declare internal memories.
 	///
 	/// In this runtime we only allow wasm module to import memory from the environment.
@@ -222,6 +288,9 @@ impl<'a, T: Config> ContractModule<'a, T> {
 	/// - 'call'
 	/// - 'deploy'
 	///
+	/// Additionally 'migrate' may be exported, which is executed after the contract replaced
+	/// its code using `seal_set_code_hash` with a migration.
+	///
 	/// Any other exports are not allowed.
 	fn scan_exports(&self) -> Result<(), &'static str> {
 		let mut deploy_found = false;
@@ -248,7 +317,9 @@ impl<'a, T: Config> ContractModule<'a, T> {
 			match export.field() {
 				"call" => call_found = true,
 				"deploy" => deploy_found = true,
-				_ => return Err("unknown export: expecting only deploy and call functions"),
+				"migrate" => (),
+				_ =>
+					return Err("unknown export: expecting only deploy, call and migrate functions"),
 			}
 
 			// Then check the export kind. "call" and "deploy" are
@@ -299,38 +370,49 @@ impl<'a, T: Config> ContractModule<'a, T> {
 	/// and enforces and returns the memory type declared by the contract if any.
 	///
 	/// `import_fn_banlist`: list of function names that are disallowed to be imported
+	/// `import_fn_deprecated`: list of deprecated function names that are disallowed to be imported
 	fn scan_imports(
 		&self,
 		import_fn_banlist: &[&[u8]],
-	) -> Result<Option<&MemoryType>, &'static str> {
+		import_fn_deprecated: &[&[u8]],
+	) -> Result<Option<&MemoryType>, Rejection> {
 		let module = &self.module;
 		let import_entries = module.import_section().map(|is| is.entries()).unwrap_or(&[]);
 		let mut imported_mem_type = None;
 
 		for import in import_entries {
+			let forbidden = |msg| {
+				(ValidationError::ForbiddenImport { name: import.field().as_bytes().to_vec() }, msg)
+			};
 			match *import.external() {
-				External::Table(_) => return Err("Cannot import tables"),
-				External::Global(_) => return Err("Cannot import globals"),
+				External::Table(_) => return Err(forbidden("Cannot import tables")),
+				External::Global(_) => return Err(forbidden("Cannot import globals")),
 				External::Function(_) => {
 					if !T::ChainExtension::enabled() &&
 						import.field().as_bytes() == b"seal_call_chain_extension"
 					{
-						return Err("module uses chain extensions but chain extensions are disabled")
+						return Err(forbidden(
+							"module uses chain extensions but chain extensions are disabled",
+						))
 					}
 
 					if import_fn_banlist.iter().any(|f| import.field().as_bytes() == *f) {
-						return Err("module imports a banned function")
+						return Err(forbidden("module imports a banned function"))
+					}
+
+					if import_fn_deprecated.iter().any(|f| import.field().as_bytes() == *f) {
+						return Err(forbidden("module imports a deprecated function"))
 					}
 				},
 				External::Memory(ref memory_type) => {
 					if import.module() != IMPORT_MODULE_MEMORY {
-						return Err("Invalid module for imported memory")
+						return Err(forbidden("Invalid module for imported memory"))
 					}
 					if import.field() != "memory" {
-						return Err("Memory import must have the field name 'memory'")
+						return Err(forbidden("Memory import must have the field name 'memory'"))
 					}
 					if imported_mem_type.is_some() {
-						return Err("Multiple memory imports defined")
+						return Err(forbidden("Multiple memory imports defined"))
 					}
 					imported_mem_type = Some(memory_type);
 					continue
@@ -372,20 +454,8 @@ fn get_memory_limits<T: Config>(
 	}
 }
 
-/// Check and instrument the given `original_code`.
-///
-/// On success it returns the instrumented versions together with its `(initial, maximum)`
-/// error requirement. The memory requirement was also validated against the `schedule`.
-fn instrument<E, T>(
-	original_code: &[u8],
-	schedule: &Schedule<T>,
-	determinism: Determinism,
-	try_instantiate: TryInstantiate,
-) -> Result<(Vec<u8>, (u32, u32)), (DispatchError, &'static str)>
-where
-	E: Environment<()>,
-	T: Config,
-{
+/// Validate `original_code` with the set of wasm features we support.
+fn validate(original_code: &[u8], deterministic_only: bool) -> Result<(), BinaryReaderError> {
 	// Do not enable any features here. Any additional feature needs to be carefully
 	// checked for potential security issues. For example, enabling multi value could lead
 	// to a DoS vector: It breaks our assumption that branch instructions are of constant time.
@@ -403,7 +473,7 @@ where
 		// This is not our only defense: We check for float types later in the preparation
 		// process. Additionally, all instructions explictily  need to have weights assigned
 		// or the deployment will fail. We have none assigned for float instructions.
-		deterministic_only: matches!(determinism, Determinism::Deterministic),
+		deterministic_only,
 		mutable_global: false,
 		saturating_float_to_int: false,
 		sign_extension: false,
@@ -413,37 +483,92 @@ where
 		simd: false,
 	})
 	.validate_all(original_code)
-	.map_err(|err| {
-		log::debug!(target: "runtime::contracts", "{}", err);
-		(Error::<T>::CodeRejected.into(), "validation of new code failed")
-	})?;
+	.map(|_| ())
+}
 
+/// Check and instrument the given `original_code`.
+///
+/// On success it returns the instrumented versions together with its `(initial, maximum)`
+/// error requirement. The memory requirement was also validated against the `schedule`.
+fn instrument<E, T>(
+	original_code: &[u8],
+	schedule: &Schedule<T>,
+	determinism: Determinism,
+	try_instantiate: TryInstantiate,
+	allow_deprecated: AllowDeprecatedInterface,
+	explain_rejection: ExplainRejection,
+) -> Result<(Vec<u8>, (u32, u32)), PrepareError>
+where
+	E: Environment<()>,
+	T: Config,
+{
+	let deterministic_only = matches!(determinism, Determinism::Deterministic);
 	let (code, (initial, maximum)) = (|| {
-		let contract_module = ContractModule::new(original_code, schedule)?;
-		contract_module.scan_exports()?;
-		contract_module.ensure_no_internal_memory()?;
-		contract_module.ensure_table_size_limit(schedule.limits.table_size)?;
-		contract_module.ensure_global_variable_limit(schedule.limits.globals)?;
-		contract_module.ensure_local_variable_limit(schedule.limits.locals)?;
-		contract_module.ensure_parameter_limit(schedule.limits.parameters)?;
-		contract_module.ensure_br_table_size_limit(schedule.limits.br_table_size)?;
-
-		if matches!(determinism, Determinism::Deterministic) {
-			contract_module.ensure_no_floating_types()?;
+		validate(original_code, deterministic_only).map_err(|err| {
+			log::debug!(target: "runtime::contracts", "{}", err);
+			// The only difference in deterministic mode is that float instructions are
+			// rejected. Hence, if the code validates without it, this is why it failed.
+			let explain = matches!(explain_rejection, ExplainRejection::Yes);
+			let reason = if explain && deterministic_only && validate(original_code, false).is_ok()
+			{
+				ValidationError::FloatInstruction
+			} else {
+				ValidationError::Other
+			};
+			(reason, "validation of new code failed")
+		})?;
+
+		let contract_module = ContractModule::new(original_code, schedule)
+			.map_err(rejected(ValidationError::Other))?;
+		contract_module
+			.ensure_no_start_function()
+			.map_err(rejected(ValidationError::StartFunction))?;
+		contract_module.scan_exports().map_err(rejected(ValidationError::Other))?;
+		contract_module
+			.ensure_no_internal_memory()
+			.map_err(rejected(ValidationError::Other))?;
+		contract_module
+			.ensure_table_size_limit(schedule.limits.table_size)
+			.map_err(rejected(ValidationError::TableSize))?;
+		contract_module
+			.ensure_global_variable_limit(schedule.limits.globals)
+			.map_err(rejected(ValidationError::GlobalCount))?;
+		contract_module
+			.ensure_local_variable_limit(schedule.limits.locals)
+			.map_err(rejected(ValidationError::Other))?;
+		contract_module
+			.ensure_parameter_limit(schedule.limits.parameters)
+			.map_err(rejected(ValidationError::Other))?;
+		contract_module
+			.ensure_br_table_size_limit(schedule.limits.br_table_size)
+			.map_err(rejected(ValidationError::Other))?;
+
+		if deterministic_only {
+			contract_module
+				.ensure_no_floating_types()
+				.map_err(rejected(ValidationError::FloatInstruction))?;
 		}
 
-		// We disallow importing `gas` function here since it is treated as implementation detail.
-		let disallowed_imports = [b"gas".as_ref()];
-		let memory_limits =
-			get_memory_limits(contract_module.scan_imports(&disallowed_imports)?, schedule)?;
+		let deprecated_imports = match allow_deprecated {
+			AllowDeprecatedInterface::No => &DEPRECATED_IMPORTS[..],
+			AllowDeprecatedInterface::Yes => &[],
+		};
+		let memory_limits = get_memory_limits(
+			contract_module.scan_imports(&DISALLOWED_IMPORTS, deprecated_imports)?,
+			schedule,
+		)
+		.map_err(rejected(ValidationError::MemoryLimits))?;
 
-		let code = contract_module.inject_gas_metering(determinism)?.into_wasm_code()?;
+		let code = contract_module
+			.inject_gas_metering(determinism)
+			.and_then(|module| module.into_wasm_code())
+			.map_err(rejected(ValidationError::Other))?;
 
 		Ok((code, memory_limits))
 	})()
-	.map_err(|msg: &str| {
-		log::debug!(target: "runtime::contracts", "new code rejected: {}", msg);
-		(Error::<T>::CodeRejected.into(), msg)
+	.map_err(|(reason, msg): Rejection| {
+		log::debug!(target: "runtime::contracts", "new code rejected: {} ({:?})", msg, reason);
+		PrepareError { error: Error::<T>::CodeRejected.into(), validation_error: Some(reason), msg }
 	})?;
 
 	// This will make sure that the module can be actually run within wasmi:
@@ -457,7 +582,11 @@ where
 		PrefabWasmModule::<T>::instantiate::<E, _>(&code, (), (initial, maximum), stack_limits)
 			.map_err(|err| {
 				log::debug!(target: "runtime::contracts", "{}", err);
-				(Error::<T>::CodeRejected.into(), "new code rejected after instrumentation")
+				PrepareError {
+					error: Error::<T>::CodeRejected.into(),
+					validation_error: Some(ValidationError::Other),
+					msg: "new code rejected after instrumentation",
+				}
 			})?;
 	}
 
@@ -473,6 +602,10 @@ where
 /// - the module doesn't define an internal memory instance
 /// - imported memory (if any) doesn't reserve more memory than permitted by the `schedule`
 /// - all imported functions from the external environment matches defined by `env` module
+/// - no deprecated function is imported unless `allow_deprecated` says otherwise
+///
+/// A rejected module is only validated again to report the reason if `explain_rejection`
+/// asks for it.
 ///
 /// The preprocessing includes injecting code for gas metering and metering the height of stack.
 pub fn prepare<E, T>(
@@ -481,13 +614,21 @@ pub fn prepare<E, T>(
 	owner: AccountIdOf<T>,
 	determinism: Determinism,
 	try_instantiate: TryInstantiate,
-) -> Result<PrefabWasmModule<T>, (DispatchError, &'static str)>
+	allow_deprecated: AllowDeprecatedInterface,
+	explain_rejection: ExplainRejection,
+) -> Result<PrefabWasmModule<T>, PrepareError>
 where
 	E: Environment<()>,
 	T: Config,
 {
-	let (code, (initial, maximum)) =
-		instrument::<E, T>(original_code.as_ref(), schedule, determinism, try_instantiate)?;
+	let (code, (initial, maximum)) = instrument::<E, T>(
+		original_code.as_ref(),
+		schedule,
+		determinism,
+		try_instantiate,
+		allow_deprecated,
+		explain_rejection,
+	)?;
 
 	let original_code_len = original_code.len();
 
@@ -495,7 +636,11 @@ where
 		instruction_weights_version: schedule.instruction_weights.version,
 		initial,
 		maximum,
-		code: code.try_into().map_err(|_| (<Error<T>>::CodeTooLarge.into(), ""))?,
+		code: code.try_into().map_err(|_| PrepareError {
+			error: <Error<T>>::CodeTooLarge.into(),
+			validation_error: None,
+			msg: "",
+		})?,
 		code_hash: T::Hashing::hash(&original_code),
 		original_code: Some(original_code),
 		owner_info: None,
@@ -523,6 +668,9 @@ where
 /// Stictly speaking is not necessary to check the existing code before reinstrumenting because
 /// it can't change in the meantime. However, since we recently switched the validation library
 /// we want to re-validate to weed out any bugs that were lurking in the old version.
+///
+/// The existing code was accepted when it was uploaded. It is therefore allowed to keep
+/// importing host functions that have been deprecated since.
 pub fn reinstrument<E, T>(
 	original_code: &[u8],
 	schedule: &Schedule<T>,
@@ -532,12 +680,19 @@ where
 	E: Environment<()>,
 	T: Config,
 {
-	instrument::<E, T>(original_code, schedule, determinism, TryInstantiate::Skip)
-		.map_err(|(err, msg)| {
-			log::error!(target: "runtime::contracts", "CodeRejected during reinstrument: {}", msg);
-			err
-		})
-		.map(|(code, _)| code)
+	instrument::<E, T>(
+		original_code,
+		schedule,
+		determinism,
+		TryInstantiate::Skip,
+		AllowDeprecatedInterface::Yes,
+		ExplainRejection::No,
+	)
+	.map_err(|err| {
+		log::error!(target: "runtime::contracts", "CodeRejected during reinstrument: {}", err.msg);
+		err.error
+	})
+	.map(|(code, _)| code)
 }
 
 /// Alternate (possibly unsafe) preparation functions used only for benchmarking.
@@ -557,7 +712,10 @@ pub mod benchmarking {
 		owner: AccountIdOf<T>,
 	) -> Result<PrefabWasmModule<T>, &'static str> {
 		let contract_module = ContractModule::new(&original_code, schedule)?;
-		let memory_limits = get_memory_limits(contract_module.scan_imports(&[])?, schedule)?;
+		let memory_limits = get_memory_limits(
+			contract_module.scan_imports(&[], &[]).map_err(|(_, msg)| msg)?,
+			schedule,
+		)?;
 		Ok(PrefabWasmModule {
 			instruction_weights_version: schedule.instruction_weights.version,
 			initial: memory_limits.0,
@@ -624,6 +782,11 @@ mod tests {
 			fn nop(_ctx: _, _memory: _, _unused: i32) -> Result<(), TrapReason> {
 				Ok(())
 			}
+
+			// deprecated, so only code that was uploaded before can import it.
+			fn seal_transfer(_ctx: _, _memory: _, _unused: u64) -> Result<(), TrapReason> {
+				Ok(())
+			}
 		}
 	}
 
@@ -650,8 +813,10 @@ mod tests {
 					ALICE,
 					Determinism::Deterministic,
 					TryInstantiate::Instantiate,
+					AllowDeprecatedInterface::No,
+					ExplainRejection::No,
 				);
-				assert_matches::assert_matches!(r.map_err(|(_, msg)| msg), $($expected)*);
+				assert_matches::assert_matches!(r.map_err(|err| err.msg), $($expected)*);
 			}
 		};
 	}
@@ -1018,6 +1183,39 @@ mod tests {
 			Err("module imports a banned function")
 		);
 
+		// a deprecated function can't be imported by new code.
+		prepare_test!(
+			can_not_import_deprecated_function,
+			r#"
+			(module
+				(import "seal0" "seal_transfer" (func (param i64)))
+
+				(func (export "call"))
+				(func (export "deploy"))
+			)
+			"#,
+			Err("module imports a deprecated function")
+		);
+
+		#[test]
+		fn existing_code_can_import_deprecated_function() {
+			let wasm = wat::parse_str(
+				r#"
+				(module
+					(import "seal0" "seal_transfer" (func (param i64)))
+
+					(func (export "call"))
+					(func (export "deploy"))
+				)
+				"#,
+			)
+			.unwrap();
+			let schedule = Schedule::<Test>::default();
+			assert!(
+				reinstrument::<env::Env, Test>(&wasm, &schedule, Determinism::Deterministic).is_ok()
+			);
+		}
+
 		// memory is in "env" and not in "seal0"
 		prepare_test!(
 			memory_not_in_seal0,
@@ -1168,7 +1366,19 @@ mod tests {
 				(func (export "whatevs"))
 			)
 			"#,
-			Err("unknown export: expecting only deploy and call functions")
+			Err("unknown export: expecting only deploy, call and migrate functions")
+		);
+
+		prepare_test!(
+			migrate_export,
+			r#"
+			(module
+				(func (export "call"))
+				(func (export "deploy"))
+				(func (export "migrate"))
+			)
+			"#,
+			Ok(_)
 		);
 
 		prepare_test!(
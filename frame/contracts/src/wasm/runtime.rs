@@ -18,7 +18,10 @@
 //! Environment definition of the wasm smart-contract runtime.
 
 use crate::{
-	exec::{ExecError, ExecResult, Ext, FixSizedKey, TopicOf, VarSizedKey},
+	exec::{
+		ExecError, ExecResult, Ext, FixSizedKey, StorageIterStep, StorageKey, TopicOf,
+		TrapCategory, VarSizedKey,
+	},
 	gas::{ChargedAmount, Token},
 	schedule::HostFnWeights,
 	BalanceOf, CodeHash, Config, Error, SENTINEL,
@@ -26,11 +29,20 @@ use crate::{
 
 use bitflags::bitflags;
 use codec::{Decode, DecodeLimit, Encode, MaxEncodedLen};
-use frame_support::{dispatch::DispatchError, ensure, traits::Get, weights::Weight, RuntimeDebug};
-use pallet_contracts_primitives::{ExecReturnValue, ReturnFlags};
+use frame_support::{
+	dispatch::{DispatchError, DispatchResultWithPostInfo},
+	ensure,
+	traits::Get,
+	weights::Weight,
+	RuntimeDebug,
+};
+use pallet_contracts_primitives::{ExecReturnValue, GasCategory, ReturnFlags};
 use pallet_contracts_proc_macro::define_env;
 use sp_io::hashing::{blake2_128, blake2_256, keccak_256, sha2_256};
-use sp_runtime::traits::{Bounded, Zero};
+use sp_runtime::{
+	traits::{Bounded, Zero},
+	ModuleError,
+};
 use sp_std::{fmt, prelude::*};
 use wasmi::{core::HostError, errors::LinkerError, Linker, Memory, Store};
 
@@ -110,6 +122,23 @@ pub enum ReturnCode {
 	/// ECDSA compressed pubkey conversion into Ethereum address failed (most probably
 	/// wrong pubkey provided).
 	EcdsaRecoverFailed = 11,
+	/// The call stack is already at its maximum depth and no further contract can be called
+	/// or instantiated. See [`Error::MaxCallDepthReached`].
+	///
+	/// Only returned by the versions of `seal_call`, `seal_delegate_call` and
+	/// `seal_instantiate` that document it. Older versions trap instead.
+	MaxCallDepthReached = 12,
+	/// The supplied output buffer is too small to hold the output. Nothing was copied, but the
+	/// required length was written to the location of the buffer length, so that the call can be
+	/// retried with a large enough buffer.
+	///
+	/// Only returned by host functions that opt into it, e.g. `seal2::get_storage`. All others
+	/// trap with [`Error::OutputBufferTooSmall`] instead.
+	OutputBufferTooSmall = 13,
+	/// The message passed to `xcm_execute` was rejected or failed to execute.
+	XcmExecutionFailed = 14,
+	/// The message passed to `xcm_send` was rejected or could not be sent.
+	XcmSendFailed = 15,
 }
 
 impl From<ExecReturnValue> for ReturnCode {
@@ -128,6 +157,30 @@ impl From<ReturnCode> for u32 {
 	}
 }
 
+/// Describes why a call failed, written to the output buffer of the caller if it set
+/// [`CallFlags::REPORT_CALLEE_ERROR`].
+///
+/// It is SCALE encoded: a single byte for the [`TrapCategory`], followed by the encoding of an
+/// `Option` holding the pallet index and the encoded error if the failure was caused by a module
+/// error. This encoding is stable.
+#[derive(Encode, Decode, RuntimeDebug, PartialEq, Eq)]
+pub struct CalleeError {
+	/// The coarse reason of the failure.
+	pub category: TrapCategory,
+	/// The module error that caused the failure, if any.
+	pub module_error: Option<ModuleError>,
+}
+
+impl CalleeError {
+	fn from_error<T: Config>(error: &DispatchError) -> Self {
+		let module_error = match error {
+			DispatchError::Module(error) => Some(*error),
+			_ => None,
+		};
+		Self { category: TrapCategory::from_error::<T>(error), module_error }
+	}
+}
+
 /// The data passed through when a contract uses `seal_return`.
 #[derive(RuntimeDebug)]
 pub struct ReturnData {
@@ -180,6 +233,9 @@ pub enum RuntimeCosts {
 	CopyFromContract(u32),
 	/// Weight charged for copying data to the sandbox.
 	CopyToContract(u32),
+	/// Weight charged for reporting the length of an output that doesn't fit into the buffer
+	/// supplied by the contract.
+	ReportOutputLen,
 	/// Weight of calling `seal_caller`.
 	Caller,
 	/// Weight of calling `seal_is_contract`.
@@ -190,6 +246,8 @@ pub enum RuntimeCosts {
 	OwnCodeHash,
 	/// Weight of calling `seal_caller_is_origin`.
 	CallerIsOrigin,
+	/// Weight of calling `caller_is_contract`.
+	CallerIsContract,
 	/// Weight of calling `seal_address`.
 	Address,
 	/// Weight of calling `seal_gas_left`.
@@ -228,6 +286,8 @@ pub enum RuntimeCosts {
 	GetStorage(u32),
 	/// Weight of calling `seal_take_storage` for the given size.
 	TakeStorage(u32),
+	/// Weight of visiting a single storage entry in `storage_iter_next`.
+	StorageIterNext,
 	/// Weight of calling `seal_transfer`.
 	Transfer,
 	/// Base weight of calling `seal_call`.
@@ -256,6 +316,12 @@ pub enum RuntimeCosts {
 	ChainExtension(u64),
 	/// Weight charged for calling into the runtime.
 	CallRuntime(Weight),
+	/// Weight of calling `xcm_execute`.
+	XcmExecute,
+	/// Weight of calling `xcm_send`.
+	XcmSend,
+	/// Weight charged for executing or sending an XCM message.
+	CallXcm(Weight),
 	/// Weight of calling `seal_set_code_hash`
 	SetCodeHash,
 	/// Weight of calling `ecdsa_to_eth_address`
@@ -266,6 +332,8 @@ pub enum RuntimeCosts {
 	AccountEntranceCount,
 	/// Weight of calling `instantiation_nonce`
 	InstantationNonce,
+	/// Weight of calling `call_stack_remaining`
+	CallStackRemaining,
 }
 
 impl RuntimeCosts {
@@ -275,11 +343,13 @@ impl RuntimeCosts {
 			MeteringBlock(amount) => s.gas.saturating_add(amount),
 			CopyFromContract(len) => s.return_per_byte.saturating_mul(len.into()),
 			CopyToContract(len) => s.input_per_byte.saturating_mul(len.into()),
+			ReportOutputLen => s.report_output_len,
 			Caller => s.caller,
 			IsContract => s.is_contract,
 			CodeHash => s.code_hash,
 			OwnCodeHash => s.own_code_hash,
 			CallerIsOrigin => s.caller_is_origin,
+			CallerIsContract => s.caller_is_contract,
 			Address => s.address,
 			GasLeft => s.gas_left,
 			Balance => s.balance,
@@ -312,6 +382,7 @@ impl RuntimeCosts {
 			TakeStorage(len) => s
 				.take_storage
 				.saturating_add(s.take_storage_per_byte.saturating_mul(len.into())),
+			StorageIterNext => s.storage_iter_next,
 			Transfer => s.transfer,
 			CallBase => s.call,
 			DelegateCallBase => s.delegate_call,
@@ -337,18 +408,58 @@ impl RuntimeCosts {
 			EcdsaRecovery => s.ecdsa_recover,
 			ChainExtension(amount) => amount,
 			CallRuntime(weight) => weight.ref_time(),
+			XcmExecute => s.xcm_execute,
+			XcmSend => s.xcm_send,
+			CallXcm(weight) => weight.ref_time(),
 			SetCodeHash => s.set_code_hash,
 			EcdsaToEthAddress => s.ecdsa_to_eth_address,
 			ReentrantCount => s.reentrance_count,
 			AccountEntranceCount => s.account_reentrance_count,
 			InstantationNonce => s.instantiation_nonce,
+			CallStackRemaining => s.call_stack_remaining,
 		};
 		RuntimeToken {
 			#[cfg(test)]
 			_created_from: *self,
 			weight: Weight::from_ref_time(weight),
+			category: self.category(),
 		}
 	}
+
+	/// The category this cost is accounted to in a gas breakdown.
+	fn category(&self) -> GasCategory {
+		use self::RuntimeCosts::*;
+		match *self {
+			MeteringBlock(_) => GasCategory::Instructions,
+			SetStorage { .. } |
+			ClearStorage(_) |
+			ContainsStorage(_) |
+			GetStorage(_) |
+			TakeStorage(_) |
+			StorageIterNext => GasCategory::Storage,
+			HashSha256(_) | HashKeccak256(_) | HashBlake256(_) | HashBlake128(_) |
+			EcdsaRecovery | EcdsaToEthAddress => GasCategory::Crypto,
+			Transfer |
+			CallBase |
+			DelegateCallBase |
+			CallSurchargeTransfer |
+			CallInputCloned(_) |
+			InstantiateBase { .. } |
+			InstantiateSurchargeTransfer => GasCategory::Calls,
+			_ => GasCategory::Other,
+		}
+	}
+}
+
+/// The weight consumed by an XCM operation that was limited to `limit`.
+///
+/// A missing `actual_weight` means that the whole limit was consumed.
+fn xcm_actual_weight(result: &DispatchResultWithPostInfo, limit: Weight) -> Weight {
+	let post_info = match result {
+		Ok(post_info) => post_info,
+		Err(err) => &err.post_info,
+	};
+	post_info.actual_weight.map_or(limit, |weight| weight.min(limit))
 }
 
 /// Same as [`Runtime::charge_gas`].
@@ -368,12 +479,17 @@ struct RuntimeToken {
 	#[cfg(test)]
 	_created_from: RuntimeCosts,
 	weight: Weight,
+	category: GasCategory,
 }
 
 impl<T: Config> Token<T> for RuntimeToken {
 	fn weight(&self) -> Weight {
 		self.weight
 	}
+
+	fn category(&self) -> GasCategory {
+		self.category
+	}
 }
 
 bitflags! {
@@ -419,6 +535,47 @@ bitflags! {
 		/// For `seal_delegate_call` should be always unset, otherwise
 		/// [`Error::InvalidCallFlags`] is returned.
 		const ALLOW_REENTRY = 0b0000_1000;
+		/// Report an output buffer that is too small to hold the output of the callee instead of
+		/// trapping.
+		///
+		/// Nothing is copied and the required length is written to `output_len_ptr`. The call
+		/// still returns the code matching its outcome, e.g. [`ReturnCode::Success`] or
+		/// [`ReturnCode::CalleeReverted`]. The caller detects the missing output by comparing
+		/// the written length with the size of its buffer.
+		///
+		/// # Note
+		///
+		/// The call itself has taken effect nonetheless, only its output is not copied. Retrying
+		/// it executes the callee again.
+		const REPORT_OUTPUT_LEN = 0b0001_0000;
+		/// Write a [`CalleeError`] to the output buffer if the call fails without reverting.
+		///
+		/// Without this flag the output buffer is left untouched in this case and the caller only
+		/// learns the return code, e.g. [`ReturnCode::CalleeTrapped`]. A revert still copies the
+		/// data returned by the callee.
+		const REPORT_CALLEE_ERROR = 0b0010_0000;
+		/// Transfer the value to the callee without calling it if the input is empty and the
+		/// callee is not a contract.
+		///
+		/// Without this flag such a call fails with [`ReturnCode::NotCallable`]. A contract
+		/// callee is called as usual.
+		///
+		/// # Note
+		///
+		/// For `seal_delegate_call` should be always unset, otherwise
+		/// [`Error::InvalidCallFlags`] is returned.
+		const TRANSFER_TO_ACCOUNT = 0b0100_0000;
+	}
+}
+
+bitflags! {
+	/// Flags used to change the behaviour of `seal_set_code_hash`.
+	pub struct SetCodeHashFlags: u32 {
+		/// Call the `migrate` export of the new code right after replacing the code hash.
+		///
+		/// The migration runs with all the remaining gas of the caller. If it traps or reverts,
+		/// the code hash is switched back to the previous one.
+		const MIGRATE = 0b0000_0001;
 	}
 }
 
@@ -452,6 +609,8 @@ pub struct Runtime<'a, E: Ext + 'a> {
 	input_data: Option<Vec<u8>>,
 	memory: Option<Memory>,
 	chain_extension: Option<Box<<E::T as Config>::ChainExtension>>,
+	/// The number of storage entries visited by `storage_iter_next` during this call so far.
+	storage_iterations: u32,
 }
 
 impl<'a, E: Ext + 'a> Runtime<'a, E> {
@@ -461,6 +620,7 @@ impl<'a, E: Ext + 'a> Runtime<'a, E> {
 			input_data: Some(input_data),
 			memory: None,
 			chain_extension: Some(Box::new(Default::default())),
+			storage_iterations: 0,
 		}
 	}
 
@@ -659,6 +819,38 @@ impl<'a, E: Ext + 'a> Runtime<'a, E> {
 		self.write_sandbox_memory(memory, out_len_ptr, &buf_len.encode())
 	}
 
+	/// Same as [`Self::write_sandbox_output`], but a buffer that is too small to fit `buf` is not
+	/// an error if `report_len` is set.
+	///
+	/// In that case nothing is copied, `buf.len()` is written to `out_len_ptr` and
+	/// [`ReturnCode::OutputBufferTooSmall`] is returned, so that the contract can retry with a
+	/// large enough buffer. Otherwise, [`ReturnCode::Success`] is returned.
+	///
+	/// Reporting the length is charged as [`RuntimeCosts::ReportOutputLen`].
+	pub fn write_sandbox_output_or_report_len(
+		&mut self,
+		memory: &mut [u8],
+		out_ptr: u32,
+		out_len_ptr: u32,
+		buf: &[u8],
+		allow_skip: bool,
+		report_len: bool,
+		create_token: impl FnOnce(u32) -> Option<RuntimeCosts>,
+	) -> Result<ReturnCode, DispatchError> {
+		if report_len && !(allow_skip && out_ptr == SENTINEL) {
+			let buf_len = buf.len() as u32;
+			let len: u32 = self.read_sandbox_memory_as(memory, out_len_ptr)?;
+			if len < buf_len {
+				self.charge_gas(RuntimeCosts::ReportOutputLen)?;
+				self.write_sandbox_memory(memory, out_len_ptr, &buf_len.encode())?;
+				return Ok(ReturnCode::OutputBufferTooSmall)
+			}
+		}
+
+		self.write_sandbox_output(memory, out_ptr, out_len_ptr, buf, allow_skip, create_token)?;
+		Ok(ReturnCode::Success)
+	}
+
 	/// Write the given buffer to the designated location in the sandbox memory.
 	///
 	/// Returns `Err` if one of the following conditions occurs:
@@ -726,6 +918,21 @@ impl<'a, E: Ext + 'a> Runtime<'a, E> {
 		}
 	}
 
+	/// Like [`Self::exec_into_return_code`] but reports a call stack that is already at its
+	/// maximum depth as `ReturnCode::MaxCallDepthReached` instead of trapping.
+	fn exec_into_return_code_reporting_depth(
+		from: ExecResult,
+	) -> Result<ReturnCode, DispatchError> {
+		use crate::exec::ErrorOrigin::Caller;
+
+		if let Err(ExecError { error, origin: Caller }) = &from {
+			if *error == Error::<E::T>::MaxCallDepthReached.into() {
+				return Ok(ReturnCode::MaxCallDepthReached)
+			}
+		}
+		Self::exec_into_return_code(from)
+	}
+
 	/// Fallible conversion of a `ExecResult` to `ReturnCode`.
 	fn exec_into_return_code(from: ExecResult) -> Result<ReturnCode, DispatchError> {
 		use crate::exec::ErrorOrigin::Callee;
@@ -809,6 +1016,7 @@ impl<'a, E: Ext + 'a> Runtime<'a, E> {
 		key_ptr: u32,
 		out_ptr: u32,
 		out_len_ptr: u32,
+		report_len: bool,
 	) -> Result<ReturnCode, TrapReason> {
 		let charged = self.charge_gas(RuntimeCosts::GetStorage(self.ext.max_value_size()))?;
 		let key = self.read_sandbox_memory(memory, key_ptr, key_type.len::<E::T>()?)?;
@@ -823,15 +1031,15 @@ impl<'a, E: Ext + 'a> Runtime<'a, E> {
 
 		if let Some(value) = outcome {
 			self.adjust_gas(charged, RuntimeCosts::GetStorage(value.len() as u32));
-			self.write_sandbox_output(
+			Ok(self.write_sandbox_output_or_report_len(
 				memory,
 				out_ptr,
 				out_len_ptr,
 				&value,
 				false,
+				report_len,
 				already_charged,
-			)?;
-			Ok(ReturnCode::Success)
+			)?)
 		} else {
 			self.adjust_gas(charged, RuntimeCosts::GetStorage(0));
 			Ok(ReturnCode::KeyNotFound)
@@ -868,6 +1076,7 @@ impl<'a, E: Ext + 'a> Runtime<'a, E> {
 		input_data_len: u32,
 		output_ptr: u32,
 		output_len_ptr: u32,
+		report_max_depth: bool,
 	) -> Result<ReturnCode, TrapReason> {
 		self.charge_gas(call_type.cost())?;
 		let input_data = if flags.contains(CallFlags::CLONE_INPUT) {
@@ -877,6 +1086,9 @@ impl<'a, E: Ext + 'a> Runtime<'a, E> {
 		} else if flags.contains(CallFlags::FORWARD_INPUT) {
 			self.input_data.take().ok_or(Error::<E::T>::InputForwarded)?
 		} else {
+			if input_data_len > self.ext.schedule().limits.input_len {
+				return Err(Error::<E::T>::InputTooLarge.into())
+			}
 			self.charge_gas(RuntimeCosts::CopyFromContract(input_data_len))?;
 			self.read_sandbox_memory(memory, input_data_ptr, input_data_len)?
 		};
@@ -890,16 +1102,27 @@ impl<'a, E: Ext + 'a> Runtime<'a, E> {
 				if value > 0u32.into() {
 					self.charge_gas(RuntimeCosts::CallSurchargeTransfer)?;
 				}
-				self.ext.call(
-					Weight::from_ref_time(gas),
-					callee,
-					value,
-					input_data,
-					flags.contains(CallFlags::ALLOW_REENTRY),
-				)
+				let transferred =
+					if flags.contains(CallFlags::TRANSFER_TO_ACCOUNT) && input_data.is_empty() {
+						self.ext.transfer_to_account(&callee, value)
+					} else {
+						Ok(false)
+					};
+				match transferred {
+					Ok(true) =>
+						Ok(ExecReturnValue { flags: ReturnFlags::empty(), data: Vec::new() }),
+					Ok(false) => self.ext.call(
+						Weight::from_ref_time(gas),
+						callee,
+						value,
+						input_data,
+						flags.contains(CallFlags::ALLOW_REENTRY),
+					),
+					Err(err) => Err(err.into()),
+				}
 			},
 			CallType::DelegateCall { code_hash_ptr } => {
-				if flags.contains(CallFlags::ALLOW_REENTRY) {
+				if flags.intersects(CallFlags::ALLOW_REENTRY | CallFlags::TRANSFER_TO_ACCOUNT) {
 					return Err(Error::<E::T>::InvalidCallFlags.into())
 				}
 				let code_hash = self.read_sandbox_memory_as(memory, code_hash_ptr)?;
@@ -918,17 +1141,33 @@ impl<'a, E: Ext + 'a> Runtime<'a, E> {
 			}
 		}
 
-		if let Ok(output) = &call_outcome {
-			self.write_sandbox_output(
+		let callee_error = match &call_outcome {
+			Err(err) if flags.contains(CallFlags::REPORT_CALLEE_ERROR) =>
+				Some(CalleeError::from_error::<E::T>(&err.error).encode()),
+			_ => None,
+		};
+		let output = match &call_outcome {
+			Ok(output) => Some(&output.data),
+			Err(_) => callee_error.as_ref(),
+		};
+		if let Some(output) = output {
+			// The callee has already run: A buffer that is too small is only visible through the
+			// reported length so that the return code still tells how the call went.
+			self.write_sandbox_output_or_report_len(
 				memory,
 				output_ptr,
 				output_len_ptr,
-				&output.data,
+				output,
 				true,
+				flags.contains(CallFlags::REPORT_OUTPUT_LEN),
 				|len| Some(RuntimeCosts::CopyToContract(len)),
 			)?;
 		}
-		Ok(Runtime::<E>::exec_into_return_code(call_outcome)?)
+		if report_max_depth {
+			Ok(Runtime::<E>::exec_into_return_code_reporting_depth(call_outcome)?)
+		} else {
+			Ok(Runtime::<E>::exec_into_return_code(call_outcome)?)
+		}
 	}
 
 	fn instantiate(
@@ -945,9 +1184,13 @@ impl<'a, E: Ext + 'a> Runtime<'a, E> {
 		output_len_ptr: u32,
 		salt_ptr: u32,
 		salt_len: u32,
+		report_max_depth: bool,
 	) -> Result<ReturnCode, TrapReason> {
 		let gas = Weight::from_ref_time(gas);
 		self.charge_gas(RuntimeCosts::InstantiateBase { input_data_len, salt_len })?;
+		if input_data_len > self.ext.schedule().limits.input_len {
+			return Err(Error::<E::T>::InputTooLarge.into())
+		}
 		let value: BalanceOf<<E as Ext>::T> = self.read_sandbox_memory_as(memory, value_ptr)?;
 		if value > 0u32.into() {
 			self.charge_gas(RuntimeCosts::InstantiateSurchargeTransfer)?;
@@ -977,7 +1220,12 @@ impl<'a, E: Ext + 'a> Runtime<'a, E> {
 				|len| Some(RuntimeCosts::CopyToContract(len)),
 			)?;
 		}
-		Ok(Runtime::<E>::exec_into_return_code(instantiate_outcome.map(|(_, retval)| retval))?)
+		let outcome = instantiate_outcome.map(|(_, retval)| retval);
+		if report_max_depth {
+			Ok(Runtime::<E>::exec_into_return_code_reporting_depth(outcome)?)
+		} else {
+			Ok(Runtime::<E>::exec_into_return_code(outcome)?)
+		}
 	}
 
 	fn terminate(&mut self, memory: &[u8], beneficiary_ptr: u32) -> Result<(), TrapReason> {
@@ -1132,7 +1380,7 @@ pub mod env {
 		out_ptr: u32,
 		out_len_ptr: u32,
 	) -> Result<ReturnCode, TrapReason> {
-		ctx.get_storage(memory, KeyType::Fix, key_ptr, out_ptr, out_len_ptr)
+		ctx.get_storage(memory, KeyType::Fix, key_ptr, out_ptr, out_len_ptr, false)
 	}
 
 	/// Retrieve the value under the given key from storage.
@@ -1163,7 +1411,44 @@ pub mod env {
 		out_ptr: u32,
 		out_len_ptr: u32,
 	) -> Result<ReturnCode, TrapReason> {
-		ctx.get_storage(memory, KeyType::Variable(key_len), key_ptr, out_ptr, out_len_ptr)
+		ctx.get_storage(memory, KeyType::Variable(key_len), key_ptr, out_ptr, out_len_ptr, false)
+	}
+
+	/// Retrieve the value under the given key from storage.
+	///
+	/// Equivalent to the previous version of `seal_get_storage`, but an output buffer that is too
+	/// small to hold the value does not trap. Instead, the length of the value is written to
+	/// `out_len_ptr` and `ReturnCode::OutputBufferTooSmall` is returned, so that the contract can
+	/// retry with a large enough buffer.
+	///
+	/// # Parameters
+	///
+	/// - `key_ptr`: pointer into the linear memory where the key of the requested value is placed.
+	/// - `key_len`: the length of the key in bytes.
+	/// - `out_ptr`: pointer to the linear memory where the value is written to.
+	/// - `out_len_ptr`: in-out pointer into linear memory where the buffer length is read from and
+	///   the value length is written to.
+	///
+	/// # Errors
+	///
+	/// `ReturnCode::KeyNotFound`
+	/// `ReturnCode::OutputBufferTooSmall`
+	///
+	/// # Unstable
+	///
+	/// This function is unstable and subject to change (or removal) in the future. Do not
+	/// deploy a contract using it to a production chain.
+	#[version(2)]
+	#[unstable]
+	fn get_storage(
+		ctx: _,
+		memory: _,
+		key_ptr: u32,
+		key_len: u32,
+		out_ptr: u32,
+		out_len_ptr: u32,
+	) -> Result<ReturnCode, TrapReason> {
+		ctx.get_storage(memory, KeyType::Variable(key_len), key_ptr, out_ptr, out_len_ptr, true)
 	}
 
 	/// Checks whether there is a value stored under the given key.
@@ -1241,6 +1526,76 @@ pub mod env {
 		}
 	}
 
+	/// Retrieve the storage key of the executing contract that follows the given one.
+	///
+	/// This allows a contract to enumerate its own storage, e.g. in order to clean it up or to
+	/// migrate it, without keeping track of its keys separately.
+	///
+	/// # Ordering
+	///
+	/// Keys are returned in ascending order of `blake2_128(key) ++ key`, which is how they are
+	/// laid out in the contract's child trie. This order is stable but unrelated to the order in
+	/// which the keys were written. Changes made earlier in the same call are visible, including
+	/// those of nested calls that were not reverted. A key that is inserted while iterating is
+	/// returned if it comes after the current position, a removed key is never returned.
+	///
+	/// Only keys written by the transparently hashing storage functions can be returned. Items
+	/// stored under a fixed sized key are skipped.
+	///
+	/// # Parameters
+	///
+	/// - `prev_key_ptr`: pointer into the linear memory where the previously returned key is
+	///   placed. `SENTINEL` starts the iteration at the first key.
+	/// - `prev_key_len`: the length of the previous key in bytes. Ignored when starting.
+	/// - `out_ptr`: pointer to the linear memory where the next key is written to.
+	/// - `out_len_ptr`: in-out pointer into linear memory where the buffer length is read from and
+	///   the key length is written to.
+	///
+	/// # Weight
+	///
+	/// Every visited storage entry is charged for as it is visited, including the skipped ones. A
+	/// call can visit at most `storage_iterations_per_call` entries as defined by the schedule.
+	/// Exceeding this limit traps the contract.
+	///
+	/// # Errors
+	///
+	/// `ReturnCode::KeyNotFound` if there are no more keys.
+	#[unstable]
+	fn storage_iter_next(
+		ctx: _,
+		memory: _,
+		prev_key_ptr: u32,
+		prev_key_len: u32,
+		out_ptr: u32,
+		out_len_ptr: u32,
+	) -> Result<ReturnCode, TrapReason> {
+		let mut cursor = if prev_key_ptr == SENTINEL {
+			Vec::new()
+		} else {
+			let key_len = KeyType::Variable(prev_key_len).len::<E::T>()?;
+			let key = ctx.read_sandbox_memory(memory, prev_key_ptr, key_len)?;
+			VarSizedKey::<E::T>::try_from(key)
+				.map_err(|_| Error::<E::T>::DecodingFailed)?
+				.hash()
+		};
+		let max_entries = ctx.ext.schedule().limits.storage_iterations_per_call;
+		loop {
+			ensure!(ctx.storage_iterations < max_entries, Error::<E::T>::TooManyStorageIterations);
+			ctx.charge_gas(RuntimeCosts::StorageIterNext)?;
+			ctx.storage_iterations += 1;
+			match ctx.ext.next_storage_key(&mut cursor) {
+				StorageIterStep::Key(key) => {
+					ctx.write_sandbox_output(memory, out_ptr, out_len_ptr, &key, false, |len| {
+						Some(RuntimeCosts::CopyToContract(len))
+					})?;
+					return Ok(ReturnCode::Success)
+				},
+				StorageIterStep::Skipped => continue,
+				StorageIterStep::End => return Ok(ReturnCode::KeyNotFound),
+			}
+		}
+	}
+
 	/// Transfer some value to another account.
 	///
 	/// # Parameters
@@ -1255,6 +1610,13 @@ pub mod env {
 	/// # Errors
 	///
 	/// `ReturnCode::TransferFailed`
+	///
+	/// # Deprecation
+	///
+	/// Newly uploaded code is rejected if it imports this function. Call `seal_call` with an
+	/// empty input and [`CallFlags::TRANSFER_TO_ACCOUNT`] set instead, which transfers to
+	/// accounts that are no contracts the same way. Code that was uploaded before can still
+	/// use it.
 	#[prefixed_alias]
 	fn transfer(
 		ctx: _,
@@ -1312,6 +1674,7 @@ pub mod env {
 			input_data_len,
 			output_ptr,
 			output_len_ptr,
+			false,
 		)
 	}
 
@@ -1321,6 +1684,10 @@ pub mod env {
 	/// The copy of the output buffer can be skipped by supplying the sentinel value
 	/// of `SENTINEL` to `output_ptr`.
 	///
+	/// If [`CallFlags::TRANSFER_TO_ACCOUNT`] is set, the input is empty and the callee is not a
+	/// contract, the value is transferred to it like a plain transfer and an empty output is
+	/// returned.
+	///
 	/// # Parameters
 	///
 	/// - flags: See [`CallFlags`] for a documenation of the supported flags.
@@ -1366,6 +1733,46 @@ pub mod env {
 			input_data_len,
 			output_ptr,
 			output_len_ptr,
+			false,
+		)
+	}
+
+	/// Make a call to another contract.
+	///
+	/// Equivalent to the previous version of this function except that it returns
+	/// `ReturnCode::MaxCallDepthReached` instead of trapping when the call stack is already at
+	/// its maximum depth. See the previous version for documentation.
+	///
+	/// # Errors
+	///
+	/// `ReturnCode::CalleeReverted`: Output buffer is returned.
+	/// `ReturnCode::CalleeTrapped`
+	/// `ReturnCode::TransferFailed`
+	/// `ReturnCode::NotCallable`
+	/// `ReturnCode::MaxCallDepthReached`
+	#[version(2)]
+	#[unstable]
+	fn call(
+		ctx: _,
+		memory: _,
+		flags: u32,
+		callee_ptr: u32,
+		gas: u64,
+		value_ptr: u32,
+		input_data_ptr: u32,
+		input_data_len: u32,
+		output_ptr: u32,
+		output_len_ptr: u32,
+	) -> Result<ReturnCode, TrapReason> {
+		ctx.call(
+			memory,
+			CallFlags::from_bits(flags).ok_or(Error::<E::T>::InvalidCallFlags)?,
+			CallType::Call { callee_ptr, value_ptr, gas },
+			input_data_ptr,
+			input_data_len,
+			output_ptr,
+			output_len_ptr,
+			true,
 		)
 	}
 
@@ -1412,6 +1819,43 @@ pub mod env {
 			input_data_len,
 			output_ptr,
 			output_len_ptr,
+			false,
+		)
+	}
+
+	/// Execute code in the context (storage, caller, value) of the current contract.
+	///
+	/// Equivalent to the previous version of this function except that it returns
+	/// `ReturnCode::MaxCallDepthReached` instead of trapping when the call stack is already at
+	/// its maximum depth. See the previous version for documentation.
+	///
+	/// # Errors
+	///
+	/// `ReturnCode::CalleeReverted`: Output buffer is returned.
+	/// `ReturnCode::CalleeTrapped`
+	/// `ReturnCode::CodeNotFound`
+	/// `ReturnCode::MaxCallDepthReached`
+	#[version(1)]
+	#[unstable]
+	fn delegate_call(
+		ctx: _,
+		memory: _,
+		flags: u32,
+		code_hash_ptr: u32,
+		input_data_ptr: u32,
+		input_data_len: u32,
+		output_ptr: u32,
+		output_len_ptr: u32,
+	) -> Result<ReturnCode, TrapReason> {
+		ctx.call(
+			memory,
+			CallFlags::from_bits(flags).ok_or(Error::<E::T>::InvalidCallFlags)?,
+			CallType::DelegateCall { code_hash_ptr },
+			input_data_ptr,
+			input_data_len,
+			output_ptr,
+			output_len_ptr,
+			true,
 		)
 	}
 
@@ -1458,6 +1902,7 @@ pub mod env {
 			output_len_ptr,
 			salt_ptr,
 			salt_len,
+			false,
 		)
 	}
 
@@ -1501,6 +1946,9 @@ pub mod env {
 	/// `ReturnCode::CalleeTrapped`
 	/// `ReturnCode::TransferFailed`
 	/// `ReturnCode::CodeNotFound`
+	///
+	/// Traps with `TooManyInstantiations` once the contracts of the current call have
+	/// instantiated `max_instantiations_per_call` contracts.
 	#[version(1)]
 	#[prefixed_alias]
 	fn instantiate(
@@ -1531,6 +1979,54 @@ pub mod env {
 			output_len_ptr,
 			salt_ptr,
 			salt_len,
+			false,
+		)
+	}
+
+	/// Instantiate a contract with the specified code hash.
+	///
+	/// Equivalent to the previous version of this function except that it returns
+	/// `ReturnCode::MaxCallDepthReached` instead of trapping when the call stack is already at
+	/// its maximum depth. See the previous version for documentation.
+	///
+	/// # Errors
+	///
+	/// `ReturnCode::CalleeReverted`: Output buffer is returned.
+	/// `ReturnCode::CalleeTrapped`
+	/// `ReturnCode::TransferFailed`
+	/// `ReturnCode::CodeNotFound`
+	/// `ReturnCode::MaxCallDepthReached`
+	#[version(2)]
+	#[unstable]
+	fn instantiate(
+		ctx: _,
+		memory: _,
+		code_hash_ptr: u32,
+		gas: u64,
+		value_ptr: u32,
+		input_data_ptr: u32,
+		input_data_len: u32,
+		address_ptr: u32,
+		address_len_ptr: u32,
+		output_ptr: u32,
+		output_len_ptr: u32,
+		salt_ptr: u32,
+		salt_len: u32,
+	) -> Result<ReturnCode, TrapReason> {
+		ctx.instantiate(
+			memory,
+			code_hash_ptr,
+			gas,
+			value_ptr,
+			input_data_ptr,
+			input_data_len,
+			address_ptr,
+			address_len_ptr,
+			output_ptr,
+			output_len_ptr,
+			salt_ptr,
+			salt_len,
+			true,
 		)
 	}
 
@@ -1558,6 +2054,9 @@ pub mod env {
 
 	/// Remove the calling account and transfer remaining **free** balance.
 	///
+	/// The storage deposit held by the contract is released before and hence transferred to
+	/// the beneficiary, too. It is not refunded to the origin of the call stack.
+	///
 	/// This function never returns. Either the termination was successful and the
 	/// execution of the destroyed contract is halted. Or it failed during the termination
 	/// which is considered fatal and results in a trap + rollback.
@@ -1617,7 +2116,8 @@ pub mod env {
 	/// bit [1, 31]: Reserved for future use.
 	/// --- msb ---
 	///
-	/// Using a reserved bit triggers a trap.
+	/// Using a reserved bit triggers a trap. Returning more than `max_return_len` bytes of
+	/// data also triggers a trap.
 	fn seal_return(
 		ctx: _,
 		memory: _,
@@ -1626,6 +2126,9 @@ pub mod env {
 		data_len: u32,
 	) -> Result<(), TrapReason> {
 		ctx.charge_gas(RuntimeCosts::Return(data_len))?;
+		if data_len > ctx.ext.schedule().limits.max_return_len {
+			return Err(Error::<E::T>::ReturnDataTooLarge.into())
+		}
 		Err(TrapReason::Return(ReturnData {
 			flags,
 			data: ctx.read_sandbox_memory(memory, data_ptr, data_len)?,
@@ -1800,6 +2303,44 @@ pub mod env {
 		)?)
 	}
 
+	/// Stores the price for the specified amount of weight into the supplied buffer.
+	///
+	/// Equivalent to the previous version of `seal_weight_to_fee` but takes both components of
+	/// the weight, `ref_time_limit` and `proof_size_limit`, into account when computing the price.
+	///
+	/// The value is stored to linear memory at the address pointed to by `out_ptr`.
+	/// `out_len_ptr` must point to a u32 value that describes the available space at
+	/// `out_ptr`. This call overwrites it with the size of the value. If the available
+	/// space at `out_ptr` is less than the size of the value a trap is triggered.
+	///
+	/// The data is encoded as T::Balance.
+	///
+	/// # Unstable
+	///
+	/// This function is unstable and subject to change (or removal) in the future. Do not
+	/// deploy a contract using it to a production chain.
+	#[version(1)]
+	#[unstable]
+	fn weight_to_fee(
+		ctx: _,
+		memory: _,
+		ref_time_limit: u64,
+		proof_size_limit: u64,
+		out_ptr: u32,
+		out_len_ptr: u32,
+	) -> Result<(), TrapReason> {
+		let weight = Weight::from_parts(ref_time_limit, proof_size_limit);
+		ctx.charge_gas(RuntimeCosts::WeightToFee)?;
+		Ok(ctx.write_sandbox_output(
+			memory,
+			out_ptr,
+			out_len_ptr,
+			&ctx.ext.get_weight_price(weight).encode(),
+			false,
+			already_charged,
+		)?)
+	}
+
 	/// Stores the amount of gas left into the supplied buffer.
 	///
 	/// The value is stored to linear memory at the address pointed to by `out_ptr`.
@@ -2065,7 +2606,9 @@ pub mod env {
 	}
 
 	/// Deposit a contract event with the data buffer and optional list of topics. There is a limit
-	/// on the maximum number of topics specified by `event_topics`.
+	/// on the maximum number of topics specified by `event_topics`. The data and topics of all
+	/// events deposited by the contracts of a call stack can't exceed `event_bytes_per_call` in
+	/// total, otherwise the call traps with `EventBudgetExhausted`.
 	///
 	/// - topics_ptr - a pointer to the buffer of topics encoded as `Vec<T::Hash>`. The value of
 	///   this is ignored if `topics_len` is set to 0. The topics list can't contain duplicates.
@@ -2117,7 +2660,7 @@ pub mod env {
 
 		let event_data = ctx.read_sandbox_memory(memory, data_ptr, data_len)?;
 
-		ctx.ext.deposit_event(topics, event_data);
+		ctx.ext.deposit_event(topics, event_data)?;
 
 		Ok(())
 	}
@@ -2364,6 +2907,10 @@ pub mod env {
 	/// when the code is executing on-chain. The message is interpreted as UTF-8 and
 	/// appended to the debug buffer which is then supplied to the calling RPC client.
 	///
+	/// When recording is disabled, the message might still be handed to the
+	/// [`crate::debug::ContractDebugSink`] of the chain. This is not observable by the contract,
+	/// apart from reading the message being charged per byte.
+	///
 	/// # Note
 	///
 	/// Even though no action is taken when debug message recording is disabled there is still
@@ -2387,6 +2934,19 @@ pub mod env {
 			ctx.ext.append_debug_buffer(msg);
 			return Ok(ReturnCode::Success)
 		}
+		if <E::T as Config>::Debugger::ENABLED &&
+			str_len <= <E::T as Config>::MaxDebugBufferLen::get()
+		{
+			// Reading and validating the message is charged, since this happens on-chain.
+			ctx.charge_gas(RuntimeCosts::CopyFromContract(str_len))?;
+			// Messages that cannot be read are dropped rather than trapping, such that the sink
+			// does not change the outcome of the execution.
+			if let Ok(data) = ctx.read_sandbox_memory(memory, str_ptr, str_len) {
+				if let Ok(msg) = core::str::from_utf8(&data) {
+					<E::T as Config>::Debugger::on_debug_message(ctx.ext.address(), msg);
+				}
+			}
+		}
 		Ok(ReturnCode::LoggingDisabled)
 	}
 
@@ -2450,6 +3010,100 @@ pub mod env {
 		}
 	}
 
+	/// Execute an XCM message locally with the contract as origin.
+	///
+	/// The message is handed as-is to the runtime's `Config::Xcm`, which decodes it. Which
+	/// versions of XCM are understood is up to the runtime. The weight limit is charged from the
+	/// gas meter upfront and any part of it that the execution did not use is refunded. The cost
+	/// of the call itself is never refunded, even if the runtime does not offer XCM.
+	///
+	/// Calls dispatched by a `Transact` instruction of the message are not subject to the
+	/// `Config::CallFilter` of this pallet, see the `xcm` module of this pallet.
+	///
+	/// # Parameters
+	///
+	/// - `msg_ptr`: the pointer into the linear memory where the encoded message is placed.
+	/// - `msg_len`: the length of the encoded message in bytes.
+	/// - `ref_time_limit`: the maximum amount of weight the execution may consume.
+	///
+	/// # Return Value
+	///
+	/// Returns `ReturnCode::Success` when the message was executed. Otherwise
+	/// `ReturnCode::XcmExecutionFailed` is returned. The full error is not provided because it is
+	/// not guaranteed to be stable.
+	///
+	/// # Unstable
+	///
+	/// This function is unstable and subject to change (or removal) in the future. Do not
+	/// deploy a contract using it to a production chain.
+	#[unstable]
+	fn xcm_execute(
+		ctx: _,
+		memory: _,
+		msg_ptr: u32,
+		msg_len: u32,
+		ref_time_limit: u64,
+	) -> Result<ReturnCode, TrapReason> {
+		ctx.charge_gas(RuntimeCosts::XcmExecute)?;
+		ctx.charge_gas(RuntimeCosts::CopyFromContract(msg_len))?;
+		let message = ctx.read_sandbox_memory(memory, msg_ptr, msg_len)?;
+		let weight_limit = Weight::from_ref_time(ref_time_limit);
+		let charged = ctx.charge_gas(RuntimeCosts::CallXcm(weight_limit))?;
+		let result = ctx.ext.xcm_execute(&message, weight_limit);
+		ctx.adjust_gas(charged, RuntimeCosts::CallXcm(xcm_actual_weight(&result, weight_limit)));
+		match result {
+			Ok(_) => Ok(ReturnCode::Success),
+			Err(_) => Ok(ReturnCode::XcmExecutionFailed),
+		}
+	}
+
+	/// Send an XCM message to another consensus system with the contract as origin.
+	///
+	/// The destination and the message are handed as-is to the runtime's `Config::Xcm`, which
+	/// decodes them. The weight limit and the cost of the call are charged in the same way as for
+	/// `xcm_execute`.
+	///
+	/// # Parameters
+	///
+	/// - `dest_ptr`: the pointer into the linear memory where the encoded destination is placed.
+	/// - `dest_len`: the length of the encoded destination in bytes.
+	/// - `msg_ptr`: the pointer into the linear memory where the encoded message is placed.
+	/// - `msg_len`: the length of the encoded message in bytes.
+	/// - `ref_time_limit`: the maximum amount of weight sending the message may consume.
+	///
+	/// # Return Value
+	///
+	/// Returns `ReturnCode::Success` when the message was sent. Otherwise
+	/// `ReturnCode::XcmSendFailed` is returned.
+	///
+	/// # Unstable
+	///
+	/// This function is unstable and subject to change (or removal) in the future. Do not
+	/// deploy a contract using it to a production chain.
+	#[unstable]
+	fn xcm_send(
+		ctx: _,
+		memory: _,
+		dest_ptr: u32,
+		dest_len: u32,
+		msg_ptr: u32,
+		msg_len: u32,
+		ref_time_limit: u64,
+	) -> Result<ReturnCode, TrapReason> {
+		ctx.charge_gas(RuntimeCosts::XcmSend)?;
+		ctx.charge_gas(RuntimeCosts::CopyFromContract(dest_len.saturating_add(msg_len)))?;
+		let dest = ctx.read_sandbox_memory(memory, dest_ptr, dest_len)?;
+		let message = ctx.read_sandbox_memory(memory, msg_ptr, msg_len)?;
+		let weight_limit = Weight::from_ref_time(ref_time_limit);
+		let charged = ctx.charge_gas(RuntimeCosts::CallXcm(weight_limit))?;
+		let result = ctx.ext.xcm_send(&dest, &message, weight_limit);
+		ctx.adjust_gas(charged, RuntimeCosts::CallXcm(xcm_actual_weight(&result, weight_limit)));
+		match result {
+			Ok(_) => Ok(ReturnCode::Success),
+			Err(_) => Ok(ReturnCode::XcmSendFailed),
+		}
+	}
+
 	/// Recovers the ECDSA public key from the given message hash and signature.
 	///
 	/// Writes the public key into the given output buffer.
@@ -2537,6 +3191,49 @@ pub mod env {
 		}
 	}
 
+	/// Replace the contract code at the specified address with new code.
+	///
+	/// Equivalent to the previous version of `seal_set_code_hash` with the exception of the
+	/// additional `flags` parameter. Calling it with empty `flags` behaves exactly like the
+	/// previous version. See its documentation for important considerations.
+	///
+	/// # Parameters
+	///
+	/// - `code_hash_ptr`: A pointer to the buffer that contains the new code hash.
+	/// - `flags`: See [`SetCodeHashFlags`] for a documentation of the supported flags.
+	///
+	/// # Errors
+	///
+	/// `ReturnCode::CodeNotFound`
+	/// `ReturnCode::CalleeReverted`: The migration reverted.
+	/// `ReturnCode::CalleeTrapped`: The migration trapped.
+	#[version(1)]
+	#[unstable]
+	fn set_code_hash(
+		ctx: _,
+		memory: _,
+		code_hash_ptr: u32,
+		flags: u32,
+	) -> Result<ReturnCode, TrapReason> {
+		ctx.charge_gas(RuntimeCosts::SetCodeHash)?;
+		let flags = SetCodeHashFlags::from_bits(flags).ok_or(Error::<E::T>::InvalidCallFlags)?;
+		let code_hash: CodeHash<<E as Ext>::T> =
+			ctx.read_sandbox_memory_as(memory, code_hash_ptr)?;
+		if flags.contains(SetCodeHashFlags::MIGRATE) {
+			// the migration is executed like a call into the contract itself.
+			ctx.charge_gas(RuntimeCosts::CallBase)?;
+			let result = ctx.ext.set_code_hash_and_migrate(code_hash);
+			return Ok(Runtime::<E>::exec_into_return_code(result)?)
+		}
+		match ctx.ext.set_code_hash(code_hash) {
+			Err(err) => {
+				let code = Runtime::<E>::err_into_return_code(err)?;
+				Ok(code)
+			},
+			Ok(()) => Ok(ReturnCode::Success),
+		}
+	}
+
 	/// Calculates Ethereum address from the ECDSA compressed public key and stores
 	/// it into the supplied buffer.
 	///
@@ -2612,4 +3309,30 @@ pub mod env {
 		ctx.charge_gas(RuntimeCosts::InstantationNonce)?;
 		Ok(ctx.ext.nonce())
 	}
+
+	/// Returns how many more nested contract calls or instantiations can be performed from the
+	/// currently executing contract.
+	///
+	/// # Return Value
+	///
+	/// Returns 0 when any further call or instantiation would fail with
+	/// `ReturnCode::MaxCallDepthReached`.
+	#[unstable]
+	fn call_stack_remaining(ctx: _, _memory: _) -> Result<u32, TrapReason> {
+		ctx.charge_gas(RuntimeCosts::CallStackRemaining)?;
+		Ok(ctx.ext.call_stack_remaining())
+	}
+
+	/// Checks whether the caller of the current contract is a contract.
+	///
+	/// Unlike `seal_caller_is_origin`, this also recognizes a contract that is the origin of the
+	/// whole call stack, e.g. because it dispatched a call via `seal_call_runtime`. This requires a
+	/// storage lookup.
+	///
+	/// Returned value is a u32-encoded boolean: (0 = false, 1 = true).
+	#[unstable]
+	fn caller_is_contract(ctx: _, _memory: _) -> Result<u32, TrapReason> {
+		ctx.charge_gas(RuntimeCosts::CallerIsContract)?;
+		Ok(ctx.ext.caller_is_contract() as u32)
+	}
 }
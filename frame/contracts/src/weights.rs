@@ -59,6 +59,8 @@ pub trait WeightInfo {
 	fn upload_code(c: u32, ) -> Weight;
 	fn remove_code() -> Weight;
 	fn set_code() -> Weight;
+	fn fund_reinstrument_pot() -> Weight;
+	fn import_contract_storage(n: u32, ) -> Weight;
 	fn seal_caller(r: u32, ) -> Weight;
 	fn seal_is_contract(r: u32, ) -> Weight;
 	fn seal_code_hash(r: u32, ) -> Weight;
@@ -88,11 +90,13 @@ pub trait WeightInfo {
 	fn seal_clear_storage(r: u32, ) -> Weight;
 	fn seal_clear_storage_per_kb(n: u32, ) -> Weight;
 	fn seal_get_storage(r: u32, ) -> Weight;
+	fn seal_get_storage_report_len(r: u32, ) -> Weight;
 	fn seal_get_storage_per_kb(n: u32, ) -> Weight;
 	fn seal_contains_storage(r: u32, ) -> Weight;
 	fn seal_contains_storage_per_kb(n: u32, ) -> Weight;
 	fn seal_take_storage(r: u32, ) -> Weight;
 	fn seal_take_storage_per_kb(n: u32, ) -> Weight;
+	fn seal_storage_iter_next(r: u32, ) -> Weight;
 	fn seal_transfer(r: u32, ) -> Weight;
 	fn seal_call(r: u32, ) -> Weight;
 	fn seal_delegate_call(r: u32, ) -> Weight;
@@ -113,6 +117,10 @@ pub trait WeightInfo {
 	fn seal_reentrance_count(r: u32, ) -> Weight;
 	fn seal_account_reentrance_count(r: u32, ) -> Weight;
 	fn seal_instantiation_nonce(r: u32, ) -> Weight;
+	fn seal_call_stack_remaining(r: u32, ) -> Weight;
+	fn seal_caller_is_contract(r: u32, ) -> Weight;
+	fn seal_xcm_execute(r: u32, ) -> Weight;
+	fn seal_xcm_send(r: u32, ) -> Weight;
 	fn instr_i64const(r: u32, ) -> Weight;
 	fn instr_i64load(r: u32, ) -> Weight;
 	fn instr_i64store(r: u32, ) -> Weight;
@@ -307,6 +315,28 @@ impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
 			.saturating_add(T::DbWeight::get().reads(6))
 			.saturating_add(T::DbWeight::get().writes(6))
 	}
+	// Storage: System Account (r:2 w:2)
+	fn fund_reinstrument_pot() -> Weight {
+		// Minimum execution time: 38_512 nanoseconds.
+		Weight::from_ref_time(39_104_000)
+			.saturating_add(T::DbWeight::get().reads(2))
+			.saturating_add(T::DbWeight::get().writes(2))
+	}
+	// Storage: Contracts ContractInfoOf (r:1 w:1)
+	// Storage: System Account (r:2 w:2)
+	// Storage: unknown [0x3a6368696c645f73746f726167653a64656661756c743a] (r:1 w:1)
+	// Storage: System EventTopics (r:1 w:1)
+	/// The range of component `n` is `[0, 128]`.
+	fn import_contract_storage(n: u32, ) -> Weight {
+		// Minimum execution time: 61_833 nanoseconds.
+		Weight::from_ref_time(64_208_271)
+			// Standard Error: 11_847
+			.saturating_add(Weight::from_ref_time(19_302_114).saturating_mul(n.into()))
+			.saturating_add(T::DbWeight::get().reads(4))
+			.saturating_add(T::DbWeight::get().reads((1_u64).saturating_mul(n.into())))
+			.saturating_add(T::DbWeight::get().writes(4))
+			.saturating_add(T::DbWeight::get().writes((1_u64).saturating_mul(n.into())))
+	}
 	// Storage: System Account (r:1 w:0)
 	// Storage: Contracts ContractInfoOf (r:1 w:1)
 	// Storage: Contracts CodeStorage (r:1 w:0)
@@ -615,7 +645,7 @@ impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
 	// Storage: Timestamp Now (r:1 w:0)
 	// Storage: System EventTopics (r:2 w:2)
 	/// The range of component `t` is `[0, 4]`.
-	/// The range of component `n` is `[0, 16]`.
+	/// The range of component `n` is `[0, 3]`.
 	fn seal_deposit_event_per_topic_and_kb(t: u32, n: u32, ) -> Weight {
 		// Minimum execution time: 1_241_001 nanoseconds.
 		Weight::from_ref_time(548_526_917)
@@ -714,6 +744,17 @@ impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
 			.saturating_add(T::DbWeight::get().writes(3))
 	}
 	// Storage: Skipped Metadata (r:0 w:0)
+	/// The range of component `r` is `[0, 10]`.
+	fn seal_get_storage_report_len(r: u32, ) -> Weight {
+		// Minimum execution time: 381_916 nanoseconds.
+		Weight::from_ref_time(349_150_912)
+			// Standard Error: 443_388
+			.saturating_add(Weight::from_ref_time(332_557_776).saturating_mul(r.into()))
+			.saturating_add(T::DbWeight::get().reads(6))
+			.saturating_add(T::DbWeight::get().reads((80_u64).saturating_mul(r.into())))
+			.saturating_add(T::DbWeight::get().writes(3))
+	}
+	// Storage: Skipped Metadata (r:0 w:0)
 	/// The range of component `n` is `[0, 8]`.
 	fn seal_get_storage_per_kb(n: u32, ) -> Weight {
 		// Minimum execution time: 459_294 nanoseconds.
@@ -770,6 +811,17 @@ impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
 			.saturating_add(T::DbWeight::get().writes(48))
 			.saturating_add(T::DbWeight::get().writes((7_u64).saturating_mul(n.into())))
 	}
+	// Storage: Skipped Metadata (r:0 w:0)
+	/// The range of component `r` is `[0, 10]`.
+	fn seal_storage_iter_next(r: u32, ) -> Weight {
+		// Minimum execution time: 374_000 nanoseconds.
+		Weight::from_ref_time(328_417_560)
+			// Standard Error: 584_212
+			.saturating_add(Weight::from_ref_time(231_658_074).saturating_mul(r.into()))
+			.saturating_add(T::DbWeight::get().reads(6))
+			.saturating_add(T::DbWeight::get().reads((80_u64).saturating_mul(r.into())))
+			.saturating_add(T::DbWeight::get().writes(3))
+	}
 	// Storage: System Account (r:1 w:0)
 	// Storage: Contracts ContractInfoOf (r:1 w:1)
 	// Storage: Contracts CodeStorage (r:1 w:0)
@@ -1077,6 +1129,63 @@ impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
 			.saturating_add(T::DbWeight::get().reads(7))
 			.saturating_add(T::DbWeight::get().writes(4))
 	}
+	// Storage: System Account (r:1 w:0)
+	// Storage: Contracts ContractInfoOf (r:1 w:1)
+	// Storage: Contracts CodeStorage (r:1 w:0)
+	// Storage: Timestamp Now (r:1 w:0)
+	// Storage: System EventTopics (r:2 w:2)
+	/// The range of component `r` is `[0, 20]`.
+	fn seal_call_stack_remaining(r: u32, ) -> Weight {
+		// Minimum execution time: 382_722 nanoseconds.
+		Weight::from_ref_time(387_231_409)
+			// Standard Error: 28_817
+			.saturating_add(Weight::from_ref_time(11_349_809).saturating_mul(r.into()))
+			.saturating_add(T::DbWeight::get().reads(6))
+			.saturating_add(T::DbWeight::get().writes(3))
+	}
+	// Storage: System Account (r:1 w:0)
+	// Storage: Contracts ContractInfoOf (r:2 w:1)
+	// Storage: Contracts CodeStorage (r:1 w:0)
+	// Storage: Timestamp Now (r:1 w:0)
+	// Storage: System EventTopics (r:2 w:2)
+	/// The range of component `r` is `[0, 1]`.
+	fn seal_caller_is_contract(r: u32, ) -> Weight {
+		// Minimum execution time: 378_516 nanoseconds.
+		Weight::from_ref_time(384_120_000)
+			// Standard Error: 312_407
+			.saturating_add(Weight::from_ref_time(6_982_000).saturating_mul(r.into()))
+			.saturating_add(T::DbWeight::get().reads(6))
+			.saturating_add(T::DbWeight::get().reads((1_u64).saturating_mul(r.into())))
+			.saturating_add(T::DbWeight::get().writes(3))
+	}
+	// Storage: System Account (r:1 w:0)
+	// Storage: Contracts ContractInfoOf (r:1 w:1)
+	// Storage: Contracts CodeStorage (r:1 w:0)
+	// Storage: Timestamp Now (r:1 w:0)
+	// Storage: System EventTopics (r:2 w:2)
+	/// The range of component `r` is `[0, 20]`.
+	fn seal_xcm_execute(r: u32, ) -> Weight {
+		// Minimum execution time: 382_722 nanoseconds.
+		Weight::from_ref_time(387_231_409)
+			// Standard Error: 28_817
+			.saturating_add(Weight::from_ref_time(11_349_809).saturating_mul(r.into()))
+			.saturating_add(T::DbWeight::get().reads(6))
+			.saturating_add(T::DbWeight::get().writes(3))
+	}
+	// Storage: System Account (r:1 w:0)
+	// Storage: Contracts ContractInfoOf (r:1 w:1)
+	// Storage: Contracts CodeStorage (r:1 w:0)
+	// Storage: Timestamp Now (r:1 w:0)
+	// Storage: System EventTopics (r:2 w:2)
+	/// The range of component `r` is `[0, 20]`.
+	fn seal_xcm_send(r: u32, ) -> Weight {
+		// Minimum execution time: 382_722 nanoseconds.
+		Weight::from_ref_time(387_231_409)
+			// Standard Error: 28_817
+			.saturating_add(Weight::from_ref_time(11_349_809).saturating_mul(r.into()))
+			.saturating_add(T::DbWeight::get().reads(6))
+			.saturating_add(T::DbWeight::get().writes(3))
+	}
 	/// The range of component `r` is `[0, 50]`.
 	fn instr_i64const(r: u32, ) -> Weight {
 		// Minimum execution time: 593 nanoseconds.
@@ -1582,6 +1691,28 @@ impl WeightInfo for () {
 			.saturating_add(RocksDbWeight::get().reads(6))
 			.saturating_add(RocksDbWeight::get().writes(6))
 	}
+	// Storage: System Account (r:2 w:2)
+	fn fund_reinstrument_pot() -> Weight {
+		// Minimum execution time: 38_512 nanoseconds.
+		Weight::from_ref_time(39_104_000)
+			.saturating_add(RocksDbWeight::get().reads(2))
+			.saturating_add(RocksDbWeight::get().writes(2))
+	}
+	// Storage: Contracts ContractInfoOf (r:1 w:1)
+	// Storage: System Account (r:2 w:2)
+	// Storage: unknown [0x3a6368696c645f73746f726167653a64656661756c743a] (r:1 w:1)
+	// Storage: System EventTopics (r:1 w:1)
+	/// The range of component `n` is `[0, 128]`.
+	fn import_contract_storage(n: u32, ) -> Weight {
+		// Minimum execution time: 61_833 nanoseconds.
+		Weight::from_ref_time(64_208_271)
+			// Standard Error: 11_847
+			.saturating_add(Weight::from_ref_time(19_302_114).saturating_mul(n.into()))
+			.saturating_add(RocksDbWeight::get().reads(4))
+			.saturating_add(RocksDbWeight::get().reads((1_u64).saturating_mul(n.into())))
+			.saturating_add(RocksDbWeight::get().writes(4))
+			.saturating_add(RocksDbWeight::get().writes((1_u64).saturating_mul(n.into())))
+	}
 	// Storage: System Account (r:1 w:0)
 	// Storage: Contracts ContractInfoOf (r:1 w:1)
 	// Storage: Contracts CodeStorage (r:1 w:0)
@@ -1890,7 +2021,7 @@ impl WeightInfo for () {
 	// Storage: Timestamp Now (r:1 w:0)
 	// Storage: System EventTopics (r:2 w:2)
 	/// The range of component `t` is `[0, 4]`.
-	/// The range of component `n` is `[0, 16]`.
+	/// The range of component `n` is `[0, 3]`.
 	fn seal_deposit_event_per_topic_and_kb(t: u32, n: u32, ) -> Weight {
 		// Minimum execution time: 1_241_001 nanoseconds.
 		Weight::from_ref_time(548_526_917)
@@ -1989,6 +2120,17 @@ impl WeightInfo for () {
 			.saturating_add(RocksDbWeight::get().writes(3))
 	}
 	// Storage: Skipped Metadata (r:0 w:0)
+	/// The range of component `r` is `[0, 10]`.
+	fn seal_get_storage_report_len(r: u32, ) -> Weight {
+		// Minimum execution time: 381_916 nanoseconds.
+		Weight::from_ref_time(349_150_912)
+			// Standard Error: 443_388
+			.saturating_add(Weight::from_ref_time(332_557_776).saturating_mul(r.into()))
+			.saturating_add(RocksDbWeight::get().reads(6))
+			.saturating_add(RocksDbWeight::get().reads((80_u64).saturating_mul(r.into())))
+			.saturating_add(RocksDbWeight::get().writes(3))
+	}
+	// Storage: Skipped Metadata (r:0 w:0)
 	/// The range of component `n` is `[0, 8]`.
 	fn seal_get_storage_per_kb(n: u32, ) -> Weight {
 		// Minimum execution time: 459_294 nanoseconds.
@@ -2045,6 +2187,17 @@ impl WeightInfo for () {
 			.saturating_add(RocksDbWeight::get().writes(48))
 			.saturating_add(RocksDbWeight::get().writes((7_u64).saturating_mul(n.into())))
 	}
+	// Storage: Skipped Metadata (r:0 w:0)
+	/// The range of component `r` is `[0, 10]`.
+	fn seal_storage_iter_next(r: u32, ) -> Weight {
+		// Minimum execution time: 374_000 nanoseconds.
+		Weight::from_ref_time(328_417_560)
+			// Standard Error: 584_212
+			.saturating_add(Weight::from_ref_time(231_658_074).saturating_mul(r.into()))
+			.saturating_add(RocksDbWeight::get().reads(6))
+			.saturating_add(RocksDbWeight::get().reads((80_u64).saturating_mul(r.into())))
+			.saturating_add(RocksDbWeight::get().writes(3))
+	}
 	// Storage: System Account (r:1 w:0)
 	// Storage: Contracts ContractInfoOf (r:1 w:1)
 	// Storage: Contracts CodeStorage (r:1 w:0)
@@ -2352,6 +2505,63 @@ impl WeightInfo for () {
 			.saturating_add(RocksDbWeight::get().reads(7))
 			.saturating_add(RocksDbWeight::get().writes(4))
 	}
+	// Storage: System Account (r:1 w:0)
+	// Storage: Contracts ContractInfoOf (r:1 w:1)
+	// Storage: Contracts CodeStorage (r:1 w:0)
+	// Storage: Timestamp Now (r:1 w:0)
+	// Storage: System EventTopics (r:2 w:2)
+	/// The range of component `r` is `[0, 20]`.
+	fn seal_call_stack_remaining(r: u32, ) -> Weight {
+		// Minimum execution time: 382_722 nanoseconds.
+		Weight::from_ref_time(387_231_409)
+			// Standard Error: 28_817
+			.saturating_add(Weight::from_ref_time(11_349_809).saturating_mul(r.into()))
+			.saturating_add(RocksDbWeight::get().reads(6))
+			.saturating_add(RocksDbWeight::get().writes(3))
+	}
+	// Storage: System Account (r:1 w:0)
+	// Storage: Contracts ContractInfoOf (r:2 w:1)
+	// Storage: Contracts CodeStorage (r:1 w:0)
+	// Storage: Timestamp Now (r:1 w:0)
+	// Storage: System EventTopics (r:2 w:2)
+	/// The range of component `r` is `[0, 1]`.
+	fn seal_caller_is_contract(r: u32, ) -> Weight {
+		// Minimum execution time: 378_516 nanoseconds.
+		Weight::from_ref_time(384_120_000)
+			// Standard Error: 312_407
+			.saturating_add(Weight::from_ref_time(6_982_000).saturating_mul(r.into()))
+			.saturating_add(RocksDbWeight::get().reads(6))
+			.saturating_add(RocksDbWeight::get().reads((1_u64).saturating_mul(r.into())))
+			.saturating_add(RocksDbWeight::get().writes(3))
+	}
+	// Storage: System Account (r:1 w:0)
+	// Storage: Contracts ContractInfoOf (r:1 w:1)
+	// Storage: Contracts CodeStorage (r:1 w:0)
+	// Storage: Timestamp Now (r:1 w:0)
+	// Storage: System EventTopics (r:2 w:2)
+	/// The range of component `r` is `[0, 20]`.
+	fn seal_xcm_execute(r: u32, ) -> Weight {
+		// Minimum execution time: 382_722 nanoseconds.
+		Weight::from_ref_time(387_231_409)
+			// Standard Error: 28_817
+			.saturating_add(Weight::from_ref_time(11_349_809).saturating_mul(r.into()))
+			.saturating_add(RocksDbWeight::get().reads(6))
+			.saturating_add(RocksDbWeight::get().writes(3))
+	}
+	// Storage: System Account (r:1 w:0)
+	// Storage: Contracts ContractInfoOf (r:1 w:1)
+	// Storage: Contracts CodeStorage (r:1 w:0)
+	// Storage: Timestamp Now (r:1 w:0)
+	// Storage: System EventTopics (r:2 w:2)
+	/// The range of component `r` is `[0, 20]`.
+	fn seal_xcm_send(r: u32, ) -> Weight {
+		// Minimum execution time: 382_722 nanoseconds.
+		Weight::from_ref_time(387_231_409)
+			// Standard Error: 28_817
+			.saturating_add(Weight::from_ref_time(11_349_809).saturating_mul(r.into()))
+			.saturating_add(RocksDbWeight::get().reads(6))
+			.saturating_add(RocksDbWeight::get().writes(3))
+	}
 	/// The range of component `r` is `[0, 50]`.
 	fn instr_i64const(r: u32, ) -> Weight {
 		// Minimum execution time: 593 nanoseconds.
@@ -0,0 +1,85 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2022 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! An integration point for letting contracts send and execute XCM messages.
+//!
+//! Contracts access this functionality through the `xcm_execute` and `xcm_send` host functions,
+//! which forward to the [`XcmInterface`] declared as [`Config::Xcm`]. This pallet does not depend
+//! on any XCM crate: messages and destinations are handed over in their SCALE encoded form and it
+//! is up to the implementation to decode them, usually into the versioned types of the XCM
+//! version that the runtime supports.
+//!
+//! Runtimes that do not want to offer XCM to contracts use [`Deny`].
+//!
+//! # Weight
+//!
+//! The contract supplies a weight limit with each message. It is charged from the gas meter
+//! before the implementation is invoked, which must never consume more than that. Any part of it
+//! that is reported as unused through the `actual_weight` of the returned post dispatch info is
+//! refunded. Returning no `actual_weight` means that the whole limit was consumed.
+//!
+//! On top of that, contracts pay for the host function itself, as benchmarked with the
+//! implementation configured in the runtime. This part is never refunded.
+//!
+//! # Call Filter
+//!
+//! The messages are opaque to this pallet. Calls dispatched by a `Transact` instruction are hence
+//! **not** subject to [`Config::CallFilter`]. Implementations that execute messages must filter
+//! them on their own, e.g. through the `SafeCallFilter` of the XCM executor, or reject messages
+//! that contain `Transact` altogether.
+
+use crate::{Config, Error};
+use frame_support::{
+	dispatch::{DispatchResultWithPostInfo, WithPostDispatchInfo},
+	weights::Weight,
+};
+
+/// Sends and executes XCM messages on behalf of contracts.
+///
+/// The `origin` is always a signed origin of the contract that issued the message. Converting it
+/// into a location, e.g. an `AccountId32` junction, is left to the implementation.
+pub trait XcmInterface<T: Config> {
+	/// Execute the encoded `message` locally, using up to `weight_limit`.
+	fn execute(
+		origin: T::RuntimeOrigin,
+		message: &[u8],
+		weight_limit: Weight,
+	) -> DispatchResultWithPostInfo;
+
+	/// Send the encoded `message` to the encoded destination `dest`.
+	///
+	/// The `weight_limit` bounds the weight of delivering the message from this chain.
+	fn send(
+		origin: T::RuntimeOrigin,
+		dest: &[u8],
+		message: &[u8],
+		weight_limit: Weight,
+	) -> DispatchResultWithPostInfo;
+}
+
+/// Refuses all messages with [`Error::XcmDenied`] without consuming any weight.
+pub struct Deny;
+
+impl<T: Config> XcmInterface<T> for Deny {
+	fn execute(_: T::RuntimeOrigin, _: &[u8], _: Weight) -> DispatchResultWithPostInfo {
+		Err(Error::<T>::XcmDenied.with_weight(Weight::zero()))
+	}
+
+	fn send(_: T::RuntimeOrigin, _: &[u8], _: &[u8], _: Weight) -> DispatchResultWithPostInfo {
+		Err(Error::<T>::XcmDenied.with_weight(Weight::zero()))
+	}
+}
@@ -42,7 +42,7 @@ benchmarks! {
 	}: set_dummy(RawOrigin::Root, value) // The execution phase is just running `set_dummy` extrinsic call
 	verify {
 		// This is the optional benchmark verification phase, asserting certain states.
-		assert_eq!(Pallet::<T>::dummy(), Some(value))
+		assert_eq!(Pallet::<T>::dummy().map(|d| d.amount), Some(value))
 	}
 
 	// This will measure the execution time of `accumulate_dummy`.
@@ -271,9 +271,10 @@
 // Ensure we're `no_std` when compiling for Wasm.
 #![cfg_attr(not(feature = "std"), no_std)]
 
-use codec::{Decode, Encode};
+use codec::{Decode, Encode, MaxEncodedLen};
 use frame_support::{
 	dispatch::{ClassifyDispatch, DispatchClass, DispatchResult, Pays, PaysFee, WeighData},
+	inherent::{InherentData, InherentIdentifier, IsFatalError, ProvideInherent},
 	traits::IsSubType,
 	weights::Weight,
 };
@@ -281,10 +282,11 @@ use frame_system::ensure_signed;
 use log::info;
 use scale_info::TypeInfo;
 use sp_runtime::{
-	traits::{Bounded, DispatchInfoOf, SaturatedConversion, Saturating, SignedExtension},
+	traits::{Bounded, DispatchInfoOf, SaturatedConversion, Saturating, SignedExtension, Zero},
 	transaction_validity::{
 		InvalidTransaction, TransactionValidity, TransactionValidityError, ValidTransaction,
 	},
+	RuntimeDebug,
 };
 use sp_std::{marker::PhantomData, prelude::*};
 
@@ -295,6 +297,7 @@ pub use pallet::*;
 mod tests;
 
 mod benchmarking;
+pub mod migrations;
 pub mod weights;
 pub use weights::*;
 
@@ -302,6 +305,44 @@ pub use weights::*;
 type BalanceOf<T> = <T as pallet_balances::Config>::Balance;
 const MILLICENTS: u32 = 1_000_000_000;
 
+/// The value stored in [`Dummy`].
+///
+/// Up until storage version 1, only the `amount` was stored. See [`migrations::v1`] for how
+/// the stored values are migrated.
+#[derive(
+	Encode, Decode, Clone, Copy, PartialEq, Eq, Default, RuntimeDebug, TypeInfo, MaxEncodedLen,
+)]
+pub struct DummyValue<Balance, BlockNumber> {
+	/// The amount itself.
+	pub amount: Balance,
+	/// The block in which the amount was last updated.
+	pub last_updated_block: BlockNumber,
+}
+
+/// A type alias for the [`DummyValue`] of a runtime.
+pub type DummyValueOf<T> = DummyValue<BalanceOf<T>, <T as frame_system::Config>::BlockNumber>;
+
+/// The identifier of the inherent data carrying a new amount for [`Dummy`].
+///
+/// The data itself is the SCALE encoded `T::Balance`. It is put into the inherent data by the
+/// block author, usually via an inherent data provider of the node, and turned into a
+/// [`Call::set_dummy_inherent`] by [`ProvideInherent::create_inherent`].
+pub const INHERENT_IDENTIFIER: InherentIdentifier = *b"exdummy0";
+
+/// Errors that can occur while checking the dummy inherent.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
+pub enum InherentError {
+	/// The new amount is larger than [`Config::MaxInherentDummy`].
+	TooLarge,
+}
+
+// A fatal error makes the whole block invalid, a non-fatal one is merely reported.
+impl IsFatalError for InherentError {
+	fn is_fatal_error(&self) -> bool {
+		true
+	}
+}
+
 // A custom weight calculator tailored for the dispatch call `set_dummy()`. This actually examines
 // the arguments and makes a decision based upon them.
 //
@@ -369,6 +410,14 @@ pub mod pallet {
 		#[pallet::constant]
 		type MagicNumber: Get<Self::Balance>;
 
+		/// The largest amount that can be set via the dummy inherent.
+		#[pallet::constant]
+		type MaxInherentDummy: Get<Self::Balance>;
+
+		/// The maximum length of the data that can be stored for a watched account.
+		#[pallet::constant]
+		type MaxWatchedLen: Get<u32>;
+
 		/// The overarching event type.
 		type RuntimeEvent: From<Event<Self>> + IsType<<Self as frame_system::Config>::RuntimeEvent>;
 
@@ -376,10 +425,15 @@ pub mod pallet {
 		type WeightInfo: WeightInfo;
 	}
 
+	// The current storage version. It needs to be bumped whenever the layout of the storage
+	// changes, and a migration must be provided for the old layout. See `migrations.rs`.
+	const STORAGE_VERSION: StorageVersion = StorageVersion::new(1);
+
 	// Simple declaration of the `Pallet` type. It is placeholder we use to implement traits and
 	// method.
 	#[pallet::pallet]
 	#[pallet::generate_store(pub(super) trait Store)]
+	#[pallet::storage_version(STORAGE_VERSION)]
 	pub struct Pallet<T>(_);
 
 	// Pallet implements [`Hooks`] trait to define some logic to execute in some context.
@@ -391,13 +445,16 @@ pub mod pallet {
 		// This function must return the weight consumed by `on_initialize` and `on_finalize`.
 		fn on_initialize(_n: T::BlockNumber) -> Weight {
 			// Anything that needs to be done at the start of the block.
-			// We don't do anything here.
-			Weight::zero()
+			// We don't do anything here, but we account for the write done in `on_finalize`.
+			T::DbWeight::get().writes(1)
 		}
 
 		// `on_finalize` is executed at the end of block after all extrinsic are dispatched.
 		fn on_finalize(_n: T::BlockNumber) {
 			// Perform necessary data/state clean up here.
+			//
+			// The dummy inherent can only be included once per block, so we forget that it was.
+			DidSetDummyInherent::<T>::kill();
 		}
 
 		// A runtime code run after every block and have access to extended set of APIs.
@@ -519,10 +576,11 @@ pub mod pallet {
 			// <Dummy<T>>::put(&new_dummy);
 
 			// Here's the new one of read and then modify the value.
+			let now = <frame_system::Pallet<T>>::block_number();
 			<Dummy<T>>::mutate(|dummy| {
 				// Using `saturating_add` instead of a regular `+` to avoid overflowing
-				let new_dummy = dummy.map_or(increase_by, |d| d.saturating_add(increase_by));
-				*dummy = Some(new_dummy);
+				let amount = dummy.map_or(increase_by, |d| d.amount.saturating_add(increase_by));
+				*dummy = Some(DummyValue { amount, last_updated_block: now });
 			});
 
 			// Let's deposit an event to let the outside world know this happened.
@@ -557,13 +615,84 @@ pub mod pallet {
 			info!("New value is now: {:?}", new_value);
 
 			// Put the new value into storage.
-			<Dummy<T>>::put(new_value);
+			let now = <frame_system::Pallet<T>>::block_number();
+			<Dummy<T>>::put(DummyValue { amount: new_value, last_updated_block: now });
 
 			Self::deposit_event(Event::SetDummy { balance: new_value });
 
 			// All good, no refund.
 			Ok(())
 		}
+
+		/// Set the dummy value from the inherent data of the block author.
+		///
+		/// The dispatch origin for this call must be `Inherent`, and it can be included at most
+		/// once per block.
+		// Inherents are unsigned extrinsics put into the block by its author. They are not
+		// gossiped, nor do they pay fees. The origin is therefore `None`, and the call is created
+		// by the `ProvideInherent` implementation below rather than by a user.
+		//
+		// Inherents usually have the `Mandatory` dispatch class, so that they are included even
+		// if the block is full already.
+		#[pallet::call_index(2)]
+		#[pallet::weight((T::DbWeight::get().reads_writes(1, 2), DispatchClass::Mandatory))]
+		pub fn set_dummy_inherent(
+			origin: OriginFor<T>,
+			#[pallet::compact] new_value: T::Balance,
+		) -> DispatchResult {
+			ensure_none(origin)?;
+
+			// `check_inherent` is not guaranteed to be run by all nodes, hence the call must
+			// enforce everything the block needs to be valid. Panicking here renders the block
+			// invalid, which is exactly what we want for a malformed inherent.
+			assert!(!DidSetDummyInherent::<T>::exists(), "Dummy inherent must be included once");
+			assert!(new_value <= T::MaxInherentDummy::get(), "Dummy inherent is out of range");
+
+			let now = <frame_system::Pallet<T>>::block_number();
+			<Dummy<T>>::put(DummyValue { amount: new_value, last_updated_block: now });
+			DidSetDummyInherent::<T>::put(true);
+
+			Self::deposit_event(Event::SetDummy { balance: new_value });
+			Ok(())
+		}
+	}
+
+	// The inherent declaration. Implementing `ProvideInherent` lets the block author create the
+	// inherent extrinsic out of the inherent data, and lets other nodes check it. The
+	// `#[pallet::inherent]` attribute makes `construct_runtime!` aware of it, provided the
+	// `Inherent` part is declared for this pallet.
+	//
+	// This inherent is optional: blocks without it are valid. Otherwise `is_inherent_required`
+	// would need to be implemented.
+	#[pallet::inherent]
+	impl<T: Config> ProvideInherent for Pallet<T> {
+		type Call = Call<T>;
+		type Error = InherentError;
+		const INHERENT_IDENTIFIER: InherentIdentifier = INHERENT_IDENTIFIER;
+
+		fn create_inherent(data: &InherentData) -> Option<Self::Call> {
+			// No data (or undecodable data) simply means no inherent in this block.
+			let new_value = data.get_data::<T::Balance>(&INHERENT_IDENTIFIER).ok().flatten()?;
+			// Never create an inherent that would render our own block invalid.
+			if new_value > T::MaxInherentDummy::get() {
+				log::warn!("Dummy inherent data {:?} is out of range, skipping.", new_value);
+				return None
+			}
+			Some(Call::set_dummy_inherent { new_value })
+		}
+
+		fn check_inherent(call: &Self::Call, _data: &InherentData) -> Result<(), Self::Error> {
+			match call {
+				Call::set_dummy_inherent { new_value }
+					if *new_value > T::MaxInherentDummy::get() =>
+					Err(InherentError::TooLarge),
+				_ => Ok(()),
+			}
+		}
+
+		fn is_inherent(call: &Self::Call) -> bool {
+			matches!(call, Call::set_dummy_inherent { .. })
+		}
 	}
 
 	/// Events are a simple means of reporting specific conditions and
@@ -604,8 +733,11 @@ pub mod pallet {
 	// The getter attribute generate a function on `Pallet` placeholder:
 	// `fn getter_name() -> Type` for basic value items or
 	// `fn getter_name(key: KeyType) -> ValueType` for map items.
+	//
+	// The type of a storage item can only be changed together with a migration of the values
+	// already stored. Up until storage version 1, this used to be a plain `T::Balance`.
 	#[pallet::getter(fn dummy)]
-	pub(super) type Dummy<T: Config> = StorageValue<_, T::Balance>;
+	pub(super) type Dummy<T: Config> = StorageValue<_, DummyValueOf<T>>;
 
 	// A map that has enumerable entries.
 	#[pallet::storage]
@@ -620,19 +752,44 @@ pub mod pallet {
 	#[pallet::storage]
 	pub type CountedMap<T> = CountedStorageMap<_, Blake2_128Concat, u8, u16>;
 
+	// Whether the dummy inherent was included in the current block. This is never persisted
+	// across blocks since it is removed in `on_finalize`.
+	#[pallet::storage]
+	pub(super) type DidSetDummyInherent<T: Config> = StorageValue<_, bool, ValueQuery>;
+
+	// A map of accounts to some bounded data about them, which is only seeded at genesis.
+	#[pallet::storage]
+	#[pallet::getter(fn watched)]
+	pub(super) type Watched<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::AccountId, BoundedVec<u8, T::MaxWatchedLen>>;
+
 	// The genesis config type.
+	//
+	// It is (de)serialized with `serde` as part of the chain spec. The `genesis_config` macro
+	// already derives `Serialize` and `Deserialize`, renames the fields to `camelCase` and denies
+	// unknown fields, but further `serde` attributes can be added to the fields.
 	#[pallet::genesis_config]
 	pub struct GenesisConfig<T: Config> {
 		pub dummy: T::Balance,
 		pub bar: Vec<(T::AccountId, T::Balance)>,
 		pub foo: T::Balance,
+		// Fields added after a chain spec was written should fall back to their default, such
+		// that the existing chain spec still deserializes. Without `#[serde(default)]`, a chain
+		// spec missing this field would be rejected.
+		#[serde(default)]
+		pub watched: Vec<(T::AccountId, Vec<u8>)>,
 	}
 
 	// The default value for the genesis config type.
 	#[cfg(feature = "std")]
 	impl<T: Config> Default for GenesisConfig<T> {
 		fn default() -> Self {
-			Self { dummy: Default::default(), bar: Default::default(), foo: Default::default() }
+			Self {
+				dummy: Default::default(),
+				bar: Default::default(),
+				foo: Default::default(),
+				watched: Default::default(),
+			}
 		}
 	}
 
@@ -640,11 +797,31 @@ pub mod pallet {
 	#[pallet::genesis_build]
 	impl<T: Config> GenesisBuild<T> for GenesisConfig<T> {
 		fn build(&self) {
-			<Dummy<T>>::put(&self.dummy);
+			<Dummy<T>>::put(DummyValue { amount: self.dummy, last_updated_block: Zero::zero() });
 			for (a, b) in &self.bar {
 				<Bar<T>>::insert(a, b);
 			}
 			<Foo<T>>::put(&self.foo);
+			// The chain spec can't be checked before it is built, so any data that doesn't fit
+			// into storage should fail loudly and tell the author what to fix.
+			for (who, data) in &self.watched {
+				assert!(
+					!<Watched<T>>::contains_key(who),
+					"Account {:?} is watched more than once in the genesis config",
+					who,
+				);
+				let bounded: BoundedVec<u8, T::MaxWatchedLen> =
+					data.clone().try_into().unwrap_or_else(|_| {
+						panic!(
+							"The watched data of account {:?} is {} bytes long, but at most {} \
+							bytes are allowed by `MaxWatchedLen`",
+							who,
+							data.len(),
+							T::MaxWatchedLen::get(),
+						)
+					});
+				<Watched<T>>::insert(who, bounded);
+			}
 		}
 	}
 }
@@ -0,0 +1,93 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2022 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Storage migrations for the basic example pallet.
+//!
+//! Each migration lives in a module named after the storage version it migrates to. The runtime
+//! includes it in the `Executive`'s list of migrations for as long as chains with an older
+//! storage version can exist, and it is removed afterwards.
+
+use super::*;
+use frame_support::{
+	dispatch::GetStorageVersion,
+	traits::{Get, OnRuntimeUpgrade, StorageVersion},
+};
+
+#[cfg(feature = "try-runtime")]
+use frame_support::ensure;
+
+/// Migration to storage version 1, in which [`Dummy`] stores a [`DummyValue`] instead of just the
+/// amount.
+pub mod v1 {
+	use super::*;
+
+	/// The layout of [`Dummy`] up until storage version 1.
+	///
+	/// A storage alias allows to access a storage item with a type that differs from the one
+	/// declared in the pallet. This is needed to read values that were stored with the old type.
+	#[cfg(any(feature = "try-runtime", test))]
+	#[frame_support::storage_alias]
+	pub(crate) type Dummy<T: Config> = StorageValue<Pallet<T>, BalanceOf<T>>;
+
+	/// Migrates [`crate::Dummy`] from storage version 0 to 1.
+	///
+	/// The `last_updated_block` of a migrated value is set to the block in which the migration
+	/// runs.
+	pub struct MigrateToV1<T>(PhantomData<T>);
+
+	impl<T: Config> OnRuntimeUpgrade for MigrateToV1<T> {
+		fn on_runtime_upgrade() -> Weight {
+			let onchain = Pallet::<T>::on_chain_storage_version();
+
+			// The guard makes the migration idempotent, i.e. it is a no-op if it is accidentally
+			// kept around for another runtime upgrade.
+			if onchain != 0 {
+				log::info!("basic example: migration to v1 can be removed");
+				return T::DbWeight::get().reads(1)
+			}
+
+			let now = <frame_system::Pallet<T>>::block_number();
+			let translated = <crate::Dummy<T>>::translate::<BalanceOf<T>, _>(|old| {
+				old.map(|amount| DummyValue { amount, last_updated_block: now })
+			});
+			if translated.is_err() {
+				log::error!("basic example: the old value of `Dummy` could not be decoded");
+			}
+
+			StorageVersion::new(1).put::<Pallet<T>>();
+			T::DbWeight::get().reads_writes(3, 2)
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn pre_upgrade() -> Result<Vec<u8>, &'static str> {
+			ensure!(Pallet::<T>::on_chain_storage_version() == 0, "this migration can be removed");
+			Ok(Dummy::<T>::get().encode())
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn post_upgrade(state: Vec<u8>) -> Result<(), &'static str> {
+			let old = Option::<BalanceOf<T>>::decode(&mut &state[..])
+				.map_err(|_| "the state of pre_upgrade could not be decoded")?;
+			ensure!(Pallet::<T>::on_chain_storage_version() == 1, "the migration needs to be run");
+			ensure!(
+				<crate::Dummy<T>>::get().map(|v| v.amount) == old,
+				"the amount of `Dummy` was not preserved"
+			);
+			Ok(())
+		}
+	}
+}
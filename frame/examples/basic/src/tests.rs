@@ -22,14 +22,14 @@ use frame_support::{
 	assert_ok,
 	dispatch::{DispatchInfo, GetDispatchInfo},
 	parameter_types,
-	traits::{ConstU64, OnInitialize},
+	traits::{ConstU32, ConstU64, GenesisBuild, OnInitialize},
 };
 use sp_core::H256;
 // The testing primitives are very useful for avoiding having to work with signatures
 // or public keys. `u64` is used as the `AccountId` and no `Signature`s are required.
 use sp_runtime::{
 	testing::Header,
-	traits::{BlakeTwo256, IdentityLookup},
+	traits::{BlakeTwo256, Block as BlockT, Header as HeaderT, IdentityLookup},
 	BuildStorage,
 };
 // Reexport crate as its pallet name for construct_runtime.
@@ -47,7 +47,7 @@ frame_support::construct_runtime!(
 	{
 		System: frame_system::{Pallet, Call, Config, Storage, Event<T>},
 		Balances: pallet_balances::{Pallet, Call, Storage, Config<T>, Event<T>},
-		Example: pallet_example_basic::{Pallet, Call, Storage, Config<T>, Event<T>},
+		Example: pallet_example_basic::{Pallet, Call, Storage, Config<T>, Event<T>, Inherent},
 	}
 );
 
@@ -79,7 +79,7 @@ impl frame_system::Config for Test {
 	type SystemWeightInfo = ();
 	type SS58Prefix = ();
 	type OnSetCode = ();
-	type MaxConsumers = frame_support::traits::ConstU32<16>;
+	type MaxConsumers = ConstU32<16>;
 }
 
 impl pallet_balances::Config for Test {
@@ -96,6 +96,8 @@ impl pallet_balances::Config for Test {
 
 impl Config for Test {
 	type MagicNumber = ConstU64<1_000_000_000>;
+	type MaxInherentDummy = ConstU64<1_000>;
+	type MaxWatchedLen = ConstU32<8>;
 	type RuntimeEvent = RuntimeEvent;
 	type WeightInfo = ();
 }
@@ -112,6 +114,7 @@ pub fn new_test_ext() -> sp_io::TestExternalities {
 			// we configure the map with (key, value) pairs.
 			bar: vec![(1, 2), (2, 3)],
 			foo: 24,
+			watched: vec![(1, b"one".to_vec())],
 		},
 	}
 	.build_storage()
@@ -125,19 +128,72 @@ fn it_works_for_optional_value() {
 		// Check that GenesisBuilder works properly.
 		let val1 = 42;
 		let val2 = 27;
-		assert_eq!(Example::dummy(), Some(val1));
+		assert_eq!(Example::dummy().map(|d| d.amount), Some(val1));
 
 		// Check that accumulate works when we have Some value in Dummy already.
 		assert_ok!(Example::accumulate_dummy(RuntimeOrigin::signed(1), val2));
-		assert_eq!(Example::dummy(), Some(val1 + val2));
+		assert_eq!(Example::dummy().map(|d| d.amount), Some(val1 + val2));
 
 		// Check that accumulate works when we Dummy has None in it.
 		<Example as OnInitialize<u64>>::on_initialize(2);
 		assert_ok!(Example::accumulate_dummy(RuntimeOrigin::signed(1), val1));
-		assert_eq!(Example::dummy(), Some(val1 + val2 + val1));
+		assert_eq!(Example::dummy().map(|d| d.amount), Some(val1 + val2 + val1));
 	});
 }
 
+#[test]
+fn genesis_seeds_watched() {
+	new_test_ext().execute_with(|| {
+		assert_eq!(Example::watched(1).map(|data| data.into_inner()), Some(b"one".to_vec()));
+		assert_eq!(Example::watched(2), None);
+	});
+}
+
+#[test]
+#[should_panic(expected = "The watched data of account 1 is 9 bytes long, but at most 8 bytes")]
+fn genesis_rejects_too_long_watched_data() {
+	let _ = GenesisBuild::<Test>::build_storage(&pallet_example_basic::GenesisConfig::<Test> {
+		watched: vec![(1, b"too long!".to_vec())],
+		..Default::default()
+	});
+}
+
+#[test]
+#[should_panic(expected = "Account 1 is watched more than once in the genesis config")]
+fn genesis_rejects_duplicate_watched_accounts() {
+	let _ = GenesisBuild::<Test>::build_storage(&pallet_example_basic::GenesisConfig::<Test> {
+		watched: vec![(1, b"one".to_vec()), (1, b"two".to_vec())],
+		..Default::default()
+	});
+}
+
+#[test]
+fn genesis_config_deserializes_from_chain_spec() {
+	// A chain spec written before `watched` was added still deserializes.
+	let config: pallet_example_basic::GenesisConfig<Test> =
+		serde_json::from_str(r#"{ "dummy": 42, "bar": [[1, 2]], "foo": 24 }"#).unwrap();
+	assert_eq!(config.dummy, 42);
+	assert_eq!(config.bar, vec![(1, 2)]);
+	assert!(config.watched.is_empty());
+
+	let config: pallet_example_basic::GenesisConfig<Test> = serde_json::from_str(
+		r#"{ "dummy": 42, "bar": [], "foo": 24, "watched": [[2, [116, 119, 111]]] }"#,
+	)
+	.unwrap();
+	assert_eq!(config.watched, vec![(2, b"two".to_vec())]);
+
+	let t = GenesisBuild::<Test>::build_storage(&config).unwrap();
+	sp_io::TestExternalities::from(t).execute_with(|| {
+		assert_eq!(Example::watched(2).map(|data| data.into_inner()), Some(b"two".to_vec()));
+	});
+
+	// Unknown fields are still rejected, e.g. misspelled ones.
+	assert!(serde_json::from_str::<pallet_example_basic::GenesisConfig<Test>>(
+		r#"{ "dummy": 42, "bar": [], "foo": 24, "wathced": [] }"#
+	)
+	.is_err());
+}
+
 #[test]
 fn it_works_for_default_value() {
 	new_test_ext().execute_with(|| {
@@ -152,7 +208,7 @@ fn set_dummy_works() {
 	new_test_ext().execute_with(|| {
 		let test_val = 133;
 		assert_ok!(Example::set_dummy(RuntimeOrigin::root(), test_val.into()));
-		assert_eq!(Example::dummy(), Some(test_val));
+		assert_eq!(Example::dummy().map(|d| d.amount), Some(test_val));
 	});
 }
 
@@ -201,3 +257,146 @@ fn weights_work() {
 	// TODO: account for proof size weight
 	assert!(info1.weight.ref_time() > info2.weight.ref_time());
 }
+
+#[test]
+fn weights_within_benchmarked_range_work() {
+	let range = weights::component_ranges::SORT_VECTOR_X;
+	assert!(range.contains(range.max));
+	assert!(!range.contains(range.max + 1));
+	assert!(<() as WeightInfo>::sort_vector(range.max).ref_time() > 0);
+}
+
+#[test]
+fn weights_beyond_benchmarked_range_are_reported() {
+	let range = weights::component_ranges::SORT_VECTOR_X;
+	// above the range, the weight is extrapolated.
+	assert_eq!(range.check("sort_vector", "x", range.max + 1), range.max + 1);
+	assert!(
+		<() as WeightInfo>::sort_vector(range.max + 1).ref_time() >
+			<() as WeightInfo>::sort_vector(range.max).ref_time()
+	);
+
+	// below the range, the weight is extrapolated as well.
+	let range = frame_support::weights::ComponentRange::new(10, 20);
+	assert_eq!(range.check("sort_vector", "x", 5), 5);
+	assert_eq!(range.check("sort_vector", "x", 15), 15);
+}
+
+#[test]
+fn set_dummy_records_block() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(5);
+		assert_ok!(Example::set_dummy(RuntimeOrigin::root(), 133));
+		assert_eq!(Example::dummy(), Some(DummyValue { amount: 133, last_updated_block: 5 }));
+
+		System::set_block_number(7);
+		assert_ok!(Example::accumulate_dummy(RuntimeOrigin::signed(1), 2));
+		assert_eq!(Example::dummy(), Some(DummyValue { amount: 135, last_updated_block: 7 }));
+	});
+}
+
+#[test]
+fn dummy_inherent_is_created_and_checked() {
+	new_test_ext().execute_with(|| {
+		// No inherent data, no inherent.
+		let mut data = InherentData::new();
+		assert!(data.create_extrinsics().is_empty());
+
+		// The block author provides a new amount, which the block builder turns into an inherent.
+		data.put_data(INHERENT_IDENTIFIER, &500u64).unwrap();
+		let xts = data.create_extrinsics();
+		assert_eq!(xts.len(), 1);
+		assert_eq!(
+			xts[0].function,
+			RuntimeCall::Example(Call::set_dummy_inherent { new_value: 500 })
+		);
+
+		// Other nodes accept the block containing it.
+		let block = Block::new(Header::new_from_number(1), xts);
+		assert!(data.check_extrinsics(&block).ok());
+
+		// Dispatching it updates the dummy value.
+		System::set_block_number(1);
+		assert_ok!(Example::set_dummy_inherent(RuntimeOrigin::none(), 500));
+		assert_eq!(Example::dummy(), Some(DummyValue { amount: 500, last_updated_block: 1 }));
+	});
+}
+
+#[test]
+fn dummy_inherent_out_of_range_is_rejected() {
+	new_test_ext().execute_with(|| {
+		// An out of range amount is never turned into an inherent by the author.
+		let mut data = InherentData::new();
+		data.put_data(INHERENT_IDENTIFIER, &1_001u64).unwrap();
+		assert!(data.create_extrinsics().is_empty());
+		assert_eq!(Example::create_inherent(&data), None);
+
+		// A block that contains one anyway is rejected by other nodes.
+		let call = Call::set_dummy_inherent { new_value: 1_001 };
+		assert_eq!(Example::check_inherent(&call, &data), Err(InherentError::TooLarge));
+		let xt = UncheckedExtrinsic::new_unsigned(RuntimeCall::Example(call));
+		let result = data.check_extrinsics(&Block::new(Header::new_from_number(1), vec![xt]));
+		assert!(result.fatal_error());
+		assert_eq!(
+			result.get_error::<InherentError>(&INHERENT_IDENTIFIER).unwrap(),
+			Some(InherentError::TooLarge)
+		);
+
+		// The upper bound itself is fine.
+		assert_eq!(
+			Example::check_inherent(&Call::set_dummy_inherent { new_value: 1_000 }, &data),
+			Ok(())
+		);
+	});
+}
+
+#[test]
+fn dummy_inherent_is_inherent() {
+	assert!(Example::is_inherent(&Call::set_dummy_inherent { new_value: 1 }));
+	assert!(!Example::is_inherent(&Call::set_dummy { new_value: 1 }));
+}
+
+#[test]
+#[should_panic(expected = "Dummy inherent must be included once")]
+fn dummy_inherent_can_only_be_included_once() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Example::set_dummy_inherent(RuntimeOrigin::none(), 1));
+		let _ = Example::set_dummy_inherent(RuntimeOrigin::none(), 2);
+	});
+}
+
+#[test]
+fn dummy_inherent_can_be_included_again_next_block() {
+	use frame_support::traits::OnFinalize;
+
+	new_test_ext().execute_with(|| {
+		assert_ok!(Example::set_dummy_inherent(RuntimeOrigin::none(), 1));
+		<Example as OnFinalize<u64>>::on_finalize(1);
+		assert_ok!(Example::set_dummy_inherent(RuntimeOrigin::none(), 2));
+		assert_eq!(Example::dummy().map(|d| d.amount), Some(2));
+	});
+}
+
+#[test]
+fn migration_to_v1_works() {
+	use frame_support::traits::{GetStorageVersion, OnRuntimeUpgrade, StorageVersion};
+
+	new_test_ext().execute_with(|| {
+		// given: the value as it was stored with storage version 0, a plain balance.
+		StorageVersion::new(0).put::<Example>();
+		migrations::v1::Dummy::<Test>::put(50);
+		System::set_block_number(3);
+
+		// when
+		migrations::v1::MigrateToV1::<Test>::on_runtime_upgrade();
+
+		// then
+		assert_eq!(Example::on_chain_storage_version(), 1);
+		assert_eq!(Example::dummy(), Some(DummyValue { amount: 50, last_updated_block: 3 }));
+
+		// running it again is a no-op.
+		System::set_block_number(4);
+		migrations::v1::MigrateToV1::<Test>::on_runtime_upgrade();
+		assert_eq!(Example::dummy(), Some(DummyValue { amount: 50, last_updated_block: 3 }));
+	});
+}
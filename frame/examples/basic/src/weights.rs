@@ -44,6 +44,16 @@
 use frame_support::{traits::Get, weights::{Weight, constants::RocksDbWeight}};
 use sp_std::marker::PhantomData;
 
+/// The benchmarked ranges of the components of the weight functions.
+///
+/// The weight functions extrapolate beyond them, which is reported defensively.
+pub mod component_ranges {
+	use frame_support::weights::ComponentRange;
+
+	/// The range of component `x` of `sort_vector`.
+	pub const SORT_VECTOR_X: ComponentRange = ComponentRange::new(0, 10000);
+}
+
 /// Weight functions needed for pallet_example_basic.
 pub trait WeightInfo {
 	fn set_dummy_benchmark() -> Weight;
@@ -56,20 +66,24 @@ pub struct SubstrateWeight<T>(PhantomData<T>);
 impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
 	// Storage: BasicExample Dummy (r:0 w:1)
 	fn set_dummy_benchmark() -> Weight {
-		Weight::from_ref_time(19_000_000 as u64)
-			.saturating_add(T::DbWeight::get().writes(1 as u64))
+		// Minimum execution time: 19_000 nanoseconds.
+		Weight::from_ref_time(19_000_000)
+			.saturating_add(T::DbWeight::get().writes(1))
 	}
 	// Storage: BasicExample Dummy (r:1 w:1)
 	fn accumulate_dummy() -> Weight {
-		Weight::from_ref_time(18_000_000 as u64)
-			.saturating_add(T::DbWeight::get().reads(1 as u64))
-			.saturating_add(T::DbWeight::get().writes(1 as u64))
+		// Minimum execution time: 18_000 nanoseconds.
+		Weight::from_ref_time(18_000_000)
+			.saturating_add(T::DbWeight::get().reads(1))
+			.saturating_add(T::DbWeight::get().writes(1))
 	}
 	/// The range of component `x` is `[0, 10000]`.
 	fn sort_vector(x: u32, ) -> Weight {
-		Weight::from_ref_time(0 as u64)
+		let x = component_ranges::SORT_VECTOR_X.check("sort_vector", "x", x);
+		// Minimum execution time: 0 nanoseconds.
+		Weight::from_ref_time(0)
 			// Standard Error: 2
-			.saturating_add(Weight::from_ref_time(520 as u64).saturating_mul(x as u64))
+			.saturating_add(Weight::from_ref_time(520).saturating_mul(x.into()))
 	}
 }
 
@@ -77,19 +91,23 @@ impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
 impl WeightInfo for () {
 	// Storage: BasicExample Dummy (r:0 w:1)
 	fn set_dummy_benchmark() -> Weight {
-		Weight::from_ref_time(19_000_000 as u64)
-			.saturating_add(RocksDbWeight::get().writes(1 as u64))
+		// Minimum execution time: 19_000 nanoseconds.
+		Weight::from_ref_time(19_000_000)
+			.saturating_add(RocksDbWeight::get().writes(1))
 	}
 	// Storage: BasicExample Dummy (r:1 w:1)
 	fn accumulate_dummy() -> Weight {
-		Weight::from_ref_time(18_000_000 as u64)
-			.saturating_add(RocksDbWeight::get().reads(1 as u64))
-			.saturating_add(RocksDbWeight::get().writes(1 as u64))
+		// Minimum execution time: 18_000 nanoseconds.
+		Weight::from_ref_time(18_000_000)
+			.saturating_add(RocksDbWeight::get().reads(1))
+			.saturating_add(RocksDbWeight::get().writes(1))
 	}
 	/// The range of component `x` is `[0, 10000]`.
 	fn sort_vector(x: u32, ) -> Weight {
-		Weight::from_ref_time(0 as u64)
+		let x = component_ranges::SORT_VECTOR_X.check("sort_vector", "x", x);
+		// Minimum execution time: 0 nanoseconds.
+		Weight::from_ref_time(0)
 			// Standard Error: 2
-			.saturating_add(Weight::from_ref_time(520 as u64).saturating_mul(x as u64))
+			.saturating_add(Weight::from_ref_time(520).saturating_mul(x.into()))
 	}
 }
@@ -0,0 +1,54 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2022 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Runtime API definition for the fast unstake pallet.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use codec::Codec;
+use sp_std::vec::Vec;
+
+sp_api::decl_runtime_apis! {
+	/// Runtime api for inspecting the queue of stashes waiting to be fast-unstaked.
+	///
+	/// `QueueEntry` is the value stored for each stash, i.e.
+	/// `pallet_fast_unstake::types::QueueEntry`, and `Telemetry` is
+	/// `pallet_fast_unstake::types::FastUnstakeTelemetry`.
+	///
+	/// Version 2 added [`FastUnstakeApi::estimated_blocks_remaining`].
+	#[api_version(2)]
+	pub trait FastUnstakeApi<AccountId, QueueEntry, Telemetry, BlockNumber>
+		where AccountId: Codec, QueueEntry: Codec, Telemetry: Codec, BlockNumber: Codec
+	{
+		/// Returns up to `len` queued stashes and their entries, skipping the first `start` ones.
+		///
+		/// Stashes are returned in the order of their storage keys, so that pages line up with
+		/// `state_getKeysPaged` over the prefix of the queue.
+		fn queue_page(start: u32, len: u32) -> Vec<(AccountId, QueueEntry)>;
+
+		/// Returns the length of the queue, the progress of its head, the number of eras
+		/// checked per block and whether staking still has room for more nominators.
+		fn telemetry() -> Telemetry;
+
+		/// The estimated number of blocks until the funds of the registered `stash` are unbonded
+		/// at the latest, that is through regular unbonding if it is not fast-unstaked before.
+		///
+		/// Returns `None` if `stash` is not registered or if no estimate is possible.
+		#[api_version(2)]
+		fn estimated_blocks_remaining(stash: AccountId) -> Option<BlockNumber>;
+	}
+}
@@ -23,7 +23,7 @@ use crate::{types::*, Pallet as FastUnstake, *};
 use frame_benchmarking::{benchmarks, whitelist_account};
 use frame_support::{
 	assert_ok,
-	traits::{Currency, EnsureOrigin, Get, Hooks},
+	traits::{Currency, EnsureOrigin, Get, Hooks, ReservableCurrency},
 };
 use frame_system::RawOrigin;
 use sp_runtime::traits::Zero;
@@ -98,9 +98,47 @@ benchmarks! {
 	// on_idle, we don't check anyone, but fully unbond them.
 	on_idle_unstake {
 		ErasToCheckPerBlock::<T>::put(1);
+		// worst case: the funds of every stash are transferred to a new account.
+		for (i, who) in create_unexposed_nominators::<T>().into_iter().enumerate() {
+			let beneficiary =
+				frame_benchmarking::account::<T::AccountId>("beneficiary", i as u32, USER_SEED);
+			assert_ok!(FastUnstake::<T>::register_fast_unstake(
+				RawOrigin::Signed(who.clone()).into(),
+				UnstakeDestination::Account(beneficiary),
+			));
+		}
+
+		// run on_idle once. This will check era 0.
+		assert_eq!(Head::<T>::get(), None);
+		on_idle_full_block::<T>();
+
+		assert!(matches!(
+			Head::<T>::get(),
+			Some(UnstakeRequest {
+				checked,
+				stashes,
+				..
+			}) if checked.len() == 1 && stashes.len() as u32 == T::BatchSize::get()
+		));
+	}
+	: {
+		on_idle_full_block::<T>();
+	}
+	verify {
+		assert!(matches!(
+			fast_unstake_events::<T>().last(),
+			Some(Event::BatchFinished)
+		));
+	}
+
+	// on_idle, we don't check anyone, but fully unbond them and make them join a pool.
+	on_idle_unstake_with_pool_join {
+		ErasToCheckPerBlock::<T>::put(1);
+		let pool_id = T::Pools::setup_open_pool();
 		for who in create_unexposed_nominators::<T>() {
 			assert_ok!(FastUnstake::<T>::register_fast_unstake(
 				RawOrigin::Signed(who.clone()).into(),
+				UnstakeDestination::Pool(pool_id),
 			));
 		}
 
@@ -144,12 +182,14 @@ benchmarks! {
 		let stashes = create_unexposed_nominators::<T>().into_iter().map(|s| {
 			assert_ok!(FastUnstake::<T>::register_fast_unstake(
 				RawOrigin::Signed(s.clone()).into(),
+				UnstakeDestination::Free,
 			));
 			(s, T::Deposit::get())
 		}).collect::<Vec<_>>();
 
-		// no one is queued thus far.
-		assert_eq!(Head::<T>::get(), None);
+		// the stashes are promoted beforehand, that is benchmarked by `queue_promotion`.
+		Head::<T>::put(FastUnstake::<T>::promote_from_queue().unwrap());
+		assert_eq!(Queue::<T>::count(), 0);
 	}
 	: {
 		on_idle_full_block::<T>();
@@ -160,9 +200,37 @@ benchmarks! {
 		assert_eq!(checked, request.checked.into_inner());
 		assert!(matches!(
 			fast_unstake_events::<T>().last(),
-			Some(Event::BatchChecked { .. })
+			Some(Event::ErasChecked { .. })
 		));
-		assert!(stashes.iter().all(|(s, _)| request.stashes.iter().find(|(ss, _)| ss == s).is_some()));
+		assert!(stashes.iter().all(|(s, _)| request.stashes.iter().any(|entry| &entry.stash == s)));
+	}
+
+	// taking a full batch from the queue to become the new head.
+	//
+	// worst case: all but one of the visited stashes have been denied, and need to be refunded.
+	queue_promotion {
+		ErasToCheckPerBlock::<T>::put(1);
+		let stashes = create_unexposed_nominators::<T>();
+		for who in stashes.iter() {
+			assert_ok!(FastUnstake::<T>::register_fast_unstake(
+				RawOrigin::Signed(who.clone()).into(),
+				UnstakeDestination::Free,
+			));
+		}
+		for who in stashes.iter().skip(1) {
+			Denylist::<T>::insert(who, ());
+		}
+		assert_eq!(Queue::<T>::count(), T::BatchSize::get());
+		assert_eq!(Head::<T>::get(), None);
+	}
+	: {
+		let head = FastUnstake::<T>::promote_from_queue().unwrap();
+		Head::<T>::put(head);
+	}
+	verify {
+		assert_eq!(Queue::<T>::count(), 0);
+		assert_eq!(Head::<T>::get().unwrap().stashes.len() as u32, 1);
+		assert!(stashes.iter().skip(1).all(|s| CurrencyOf::<T>::reserved_balance(s).is_zero()));
 	}
 
 	register_fast_unstake {
@@ -171,17 +239,36 @@ benchmarks! {
 		whitelist_account!(who);
 		assert_eq!(Queue::<T>::count(), 0);
 
+		// worst case: the stash has an unlocking chunk that is already unlocked, and needs to be
+		// withdrawn first.
+		assert_ok!(T::Staking::unbond(&who, CurrencyOf::<T>::minimum_balance()));
+		T::Staking::set_current_era(T::Staking::current_era() + T::Staking::bonding_duration());
+		assert!(T::Staking::is_unbonding(&who).unwrap());
 	}
-	:_(RawOrigin::Signed(who.clone()))
+	:_(RawOrigin::Signed(who.clone()), UnstakeDestination::Free)
 	verify {
 		assert_eq!(Queue::<T>::count(), 1);
 	}
 
+	// deregistering a stash from the queue.
+	//
+	// worst case: a full batch is in the head, which is searched for the stash first.
 	deregister {
 		ErasToCheckPerBlock::<T>::put(1);
-		let who = create_unexposed_nominators::<T>().get(0).cloned().unwrap();
+		for who in create_unexposed_nominators::<T>() {
+			assert_ok!(FastUnstake::<T>::register_fast_unstake(
+				RawOrigin::Signed(who).into(),
+				UnstakeDestination::Free,
+			));
+		}
+		Head::<T>::put(FastUnstake::<T>::promote_from_queue().unwrap());
+		assert_eq!(Head::<T>::get().unwrap().stashes.len() as u32, T::BatchSize::get());
+
+		let who = frame_benchmarking::account::<T::AccountId>("deregistering", 0, USER_SEED);
+		fund_and_bond_account::<T>(&who);
 		assert_ok!(FastUnstake::<T>::register_fast_unstake(
 			RawOrigin::Signed(who.clone()).into(),
+			UnstakeDestination::Free,
 		));
 		assert_eq!(Queue::<T>::count(), 1);
 		whitelist_account!(who);
@@ -189,13 +276,62 @@ benchmarks! {
 	:_(RawOrigin::Signed(who.clone()))
 	verify {
 		assert_eq!(Queue::<T>::count(), 0);
+		assert!(CurrencyOf::<T>::reserved_balance(&who).is_zero());
 	}
 
-	control {
+	// deregistering a stash from a head for which no era has been checked yet.
+	//
+	// worst case: the head holds a full batch, and the stash is the last one of it.
+	deregister_from_head {
+		ErasToCheckPerBlock::<T>::put(1);
+		for who in create_unexposed_nominators::<T>() {
+			assert_ok!(FastUnstake::<T>::register_fast_unstake(
+				RawOrigin::Signed(who).into(),
+				UnstakeDestination::Free,
+			));
+		}
+		Head::<T>::put(FastUnstake::<T>::promote_from_queue().unwrap());
+		let head = Head::<T>::get().unwrap();
+		assert_eq!(head.stashes.len() as u32, T::BatchSize::get());
+		assert!(head.checked.is_empty());
+
+		let who = head.stashes.last().map(|entry| entry.stash.clone()).unwrap();
+		whitelist_account!(who);
+	}
+	:deregister(RawOrigin::Signed(who.clone()))
+	verify {
+		assert!(Head::<T>::get()
+			.map_or(true, |head| head.stashes.iter().all(|entry| entry.stash != who)));
+		assert!(CurrencyOf::<T>::reserved_balance(&who).is_zero());
+	}
+
+	set_eras_to_check {
+		let origin = <T as Config>::ControlOrigin::successful_origin();
+		let max = T::MaxErasToCheckPerBlock::get();
+	}
+	: _<T::RuntimeOrigin>(origin, max)
+	verify {
+		assert_eq!(ErasToCheckPerBlock::<T>::get(), max);
+	}
+
+	add_to_denylist {
 		let origin = <T as Config>::ControlOrigin::successful_origin();
+		let stash = frame_benchmarking::account::<T::AccountId>("denied", 0, USER_SEED);
+	}
+	: _<T::RuntimeOrigin>(origin, stash.clone())
+	verify {
+		assert!(Denylist::<T>::contains_key(&stash));
+	}
+
+	remove_from_denylist {
+		let origin = <T as Config>::ControlOrigin::successful_origin();
+		let stash = frame_benchmarking::account::<T::AccountId>("denied", 0, USER_SEED);
+		Denylist::<T>::insert(&stash, ());
+	}
+	: _<T::RuntimeOrigin>(origin, stash.clone())
+	verify {
+		assert!(!Denylist::<T>::contains_key(&stash));
 	}
-	: _<T::RuntimeOrigin>(origin, 128)
-	verify {}
 
 	impl_benchmark_test_suite!(Pallet, crate::mock::ExtBuilder::default().build(), crate::mock::Runtime)
 }
@@ -29,7 +29,8 @@
 //!
 //! This pallet works off the basis of `on_idle`, meaning that it provides no guarantee about when
 //! it will succeed, if at all. Moreover, the queue implementation is unordered. In case of
-//! congestion, no FIFO ordering is provided.
+//! congestion, no FIFO ordering is provided. Chains that want steady progress regardless of the
+//! load can reserve a fixed budget in every block via [`Config::MandatoryWeightPerBlock`].
 //!
 //! Stakers who are certain about NOT being exposed can register themselves with
 //! [`Call::register_fast_unstake`]. This will chill, and fully unbond the staker, and place them in
@@ -38,11 +39,16 @@
 //! Once queued, but not being actively processed, stakers can withdraw their request via
 //! [`Call::deregister`].
 //!
+//! A batch that is being processed for more than [`Config::MaxHeadBlocks`] blocks is aborted. Its
+//! stakers are either put back into the queue or dropped, see [`Config::RequeueExpired`].
+//!
 //! Once queued, a staker wishing to unbond can perform no further action in pallet-staking. This is
 //! to prevent them from accidentally exposing themselves behind a validator etc.
 //!
 //! Once processed, if successful, no additional fee for the checking process is taken, and the
-//! staker is instantly unbonded.
+//! staker is instantly unbonded. Their funds are then moved to the [`types::UnstakeDestination`]
+//! chosen upon registration: they can remain free, join a nomination pool or be transferred to
+//! another account. If moving the funds fails, they remain free in the stash.
 //!
 //! If unsuccessful, meaning that the staker was exposed sometime in the last `BondingDuration` eras
 //! they will end up being slashed for the amount of wasted work they have inflicted on the chian.
@@ -83,7 +89,7 @@ pub mod pallet {
 	use crate::types::*;
 	use frame_support::{
 		pallet_prelude::*,
-		traits::{Defensive, ReservableCurrency, StorageVersion},
+		traits::{ExistenceRequirement, ReservableCurrency, StorageVersion},
 	};
 	use frame_system::pallet_prelude::*;
 	use sp_runtime::{
@@ -104,7 +110,7 @@ pub mod pallet {
 		}
 	}
 
-	const STORAGE_VERSION: StorageVersion = StorageVersion::new(1);
+	const STORAGE_VERSION: StorageVersion = StorageVersion::new(8);
 
 	#[pallet::pallet]
 	#[pallet::storage_version(STORAGE_VERSION)]
@@ -131,11 +137,53 @@ pub mod pallet {
 		/// Batch size.
 		///
 		/// This many stashes are processed in each unstake request.
+		#[pallet::constant]
 		type BatchSize: Get<u32>;
 
+		/// The maximum value that [`Config::ControlOrigin`] can set [`ErasToCheckPerBlock`] to.
+		#[pallet::constant]
+		type MaxErasToCheckPerBlock: Get<u32>;
+
 		/// The access to staking functionality.
 		type Staking: StakingInterface<Balance = BalanceOf<Self>, AccountId = Self::AccountId>;
 
+		/// The access to nomination pools, used for [`UnstakeDestination::Pool`].
+		type Pools: PoolJoiner<Self::AccountId, BalanceOf<Self>>;
+
+		/// Notified of every stash that has been unstaked, along with the amount it had bonded.
+		type OnUnstaked: OnFastUnstakeComplete<Self::AccountId, BalanceOf<Self>>;
+
+		/// Whether to reject registration of a nominator that is exposed in the current era.
+		///
+		/// Such a nominator is bound to fail the check, but detecting this early requires
+		/// iterating over all the exposures of the current era, so it is optional.
+		#[pallet::constant]
+		type RejectExposedOnRegister: Get<bool>;
+
+		/// The maximum number of blocks that a batch can remain the [`Head`] before it is aborted.
+		///
+		/// Every new era needs to be checked as well, hence a batch whose checking keeps being
+		/// interrupted (e.g. by elections) could otherwise stay in [`Head`] indefinitely and
+		/// starve the rest of the [`Queue`].
+		#[pallet::constant]
+		type MaxHeadBlocks: Get<Self::BlockNumber>;
+
+		/// The weight to spend on processing the queue in `on_initialize` of every block.
+		///
+		/// This weight is charged in full in every block, whether or not it is used. `on_idle`
+		/// still uses the leftover weight of the block on top of this. Both hooks
+		/// continue with the same [`Head`], so eras checked by one of them are not checked again
+		/// by the other. Set to zero to only rely on `on_idle`.
+		#[pallet::constant]
+		type MandatoryWeightPerBlock: Get<Weight>;
+
+		/// Whether the stashes of an aborted batch are put back into the [`Queue`].
+		///
+		/// If `false`, they are dropped from the queue entirely and their deposit is refunded.
+		/// Otherwise their deposit remains reserved until they are processed again.
+		#[pallet::constant]
+		type RequeueExpired: Get<bool>;
+
 		/// The weight information of this pallet.
 		type WeightInfo: WeightInfo;
 	}
@@ -146,17 +194,31 @@ pub mod pallet {
 
 	/// The map of all accounts wishing to be unstaked.
 	///
-	/// Keeps track of `AccountId` wishing to unstake and it's corresponding deposit.
+	/// Keeps track of `AccountId` wishing to unstake, it's corresponding deposit, the
+	/// destination of its funds and when it has been queued. See [`Pallet::queue_page`] to
+	/// inspect it.
+	#[pallet::storage]
+	pub type Queue<T: Config> = CountedStorageMap<_, Twox64Concat, T::AccountId, QueueEntry<T>>;
+
+	/// Stashes that are not allowed to fast-unstake, managed by [`Config::ControlOrigin`].
+	///
+	/// Denied stashes cannot register, and those that were already queued when they got listed
+	/// are dropped from the [`Queue`] with a refund instead of becoming part of the [`Head`].
+	#[pallet::storage]
+	pub type Denylist<T: Config> = StorageMap<_, Twox64Concat, T::AccountId, (), OptionQuery>;
+
+	/// The identifier assigned to the next registered request, see [`QueueEntry::id`].
 	#[pallet::storage]
-	pub type Queue<T: Config> = CountedStorageMap<_, Twox64Concat, T::AccountId, BalanceOf<T>>;
+	pub type NextRequestId<T: Config> = StorageValue<_, RequestId, ValueQuery>;
 
 	/// Number of eras to check per block.
 	///
 	/// If set to 0, this pallet does absolutely nothing.
 	///
-	/// Based on the amount of weight available at `on_idle`, up to this many eras of a single
-	/// nominator might be checked.
+	/// Based on the amount of weight available at `on_idle` and `on_initialize`, up to this many
+	/// eras of a single nominator might be checked by each of them.
 	#[pallet::storage]
+	#[pallet::getter(fn eras_to_check_per_block)]
 	pub type ErasToCheckPerBlock<T: Config> = StorageValue<_, u32, ValueQuery>;
 
 	/// The events of this pallet.
@@ -164,18 +226,57 @@ pub mod pallet {
 	#[pallet::generate_deposit(pub(super) fn deposit_event)]
 	pub enum Event<T: Config> {
 		/// A staker was unstaked.
-		Unstaked { stash: T::AccountId, result: DispatchResult },
+		///
+		/// `destination` is where its funds ended up. If they could not be moved to the requested
+		/// destination, this is [`UnstakeDestination::Free`] and `result` tells why.
+		/// `payee` is the account that received the staking rewards of the stash while it was
+		/// bonded, if any.
+		Unstaked {
+			stash: T::AccountId,
+			id: RequestId,
+			result: UnstakeOutcome,
+			destination: UnstakeDestination<T::AccountId>,
+			payee: Option<T::AccountId>,
+		},
 		/// A staker was slashed for requesting fast-unstake whilst being exposed.
-		Slashed { stash: T::AccountId, amount: BalanceOf<T> },
+		Slashed { stash: T::AccountId, id: RequestId, amount: BalanceOf<T> },
 		/// An internal error happened. Operations will be paused now.
 		InternalError,
 		/// A batch was partially checked for the given eras, but the process did not finish.
+		///
+		/// Deprecated: this is no longer emitted in favour of [`Event::ErasChecked`], and will be
+		/// removed in a future release.
 		BatchChecked { eras: Vec<EraIndex> },
 		/// A batch was terminated.
 		///
 		/// This is always follows by a number of `Unstaked` or `Slashed` events, marking the end
 		/// of the batch. A new batch will be created upon next block.
 		BatchFinished,
+		/// A batch was partially checked for `count` eras, but the process did not finish.
+		///
+		/// Eras are checked from the most recent one backwards, so `first_era` is the highest and
+		/// `last_era` the lowest of the eras checked. Eras in between might have been checked
+		/// before already, if a new era started in the meantime. `ids` are the requests that
+		/// remain in the batch after the check.
+		ErasChecked { first_era: EraIndex, last_era: EraIndex, count: u32, ids: Vec<RequestId> },
+		/// A staker was removed from the head because its batch exceeded
+		/// [`Config::MaxHeadBlocks`].
+		///
+		/// If `requeued`, it has been put back into the queue. Otherwise its deposit has been
+		/// refunded.
+		Expired { stash: T::AccountId, id: RequestId, requeued: bool },
+		/// A staker was not unstaked because its stake has changed or it has nominated since it
+		/// has been registered. Its deposit has been refunded.
+		Aborted { stash: T::AccountId, id: RequestId },
+		/// The number of eras to check per block was set by [`Config::ControlOrigin`].
+		ErasToCheckSet { eras_to_check: EraIndex },
+		/// A stash was added to the [`Denylist`].
+		Denylisted { stash: T::AccountId },
+		/// A stash was removed from the [`Denylist`].
+		Undenylisted { stash: T::AccountId },
+		/// A queued stash was dropped because it is in the [`Denylist`]. Its deposit has been
+		/// refunded.
+		Denied { stash: T::AccountId, id: RequestId },
 	}
 
 	#[pallet::error]
@@ -191,14 +292,41 @@ pub mod pallet {
 		NotFullyBonded,
 		/// The provided un-staker is not in the `Queue`.
 		NotQueued,
-		/// The provided un-staker is already in Head, and cannot deregister.
+		/// The provided un-staker is already in Head, and cannot register, or deregister once eras
+		/// have been checked for it.
 		AlreadyHead,
 		/// The call is not allowed at this point because the pallet is not active.
 		CallNotAllowed,
+		/// The bonded account nominates validators and is exposed in the current era.
+		ActiveNominations,
+		/// The number of eras to check exceeds [`Config::MaxErasToCheckPerBlock`].
+		TooManyEras,
+		/// The stash is in the [`Denylist`] and cannot fast-unstake.
+		Denied,
+		/// The stash is already in the [`Denylist`].
+		AlreadyDenylisted,
+		/// The stash is not in the [`Denylist`].
+		NotDenylisted,
 	}
 
 	#[pallet::hooks]
 	impl<T: Config> Hooks<T::BlockNumber> for Pallet<T> {
+		/// Process the queue with [`Config::MandatoryWeightPerBlock`].
+		///
+		/// The budget is always returned as the weight of this hook, such that a block knows the
+		/// weight of it upfront. The processing is bounded by the budget the same way as it is
+		/// bounded by the leftover weight in `on_idle`: eras are only checked and stashes only
+		/// unstaked if their benchmarked weight fits into it.
+		fn on_initialize(_: T::BlockNumber) -> Weight {
+			let budget = T::MandatoryWeightPerBlock::get();
+			if budget.any_lt(T::DbWeight::get().reads(2)) {
+				return budget
+			}
+
+			Self::do_on_idle(budget);
+			budget
+		}
+
 		fn on_idle(_: T::BlockNumber, remaining_weight: Weight) -> Weight {
 			if remaining_weight.any_lt(T::DbWeight::get().reads(2)) {
 				return Weight::from_ref_time(0)
@@ -215,39 +343,88 @@ pub mod pallet {
 		/// The dispatch origin of this call must be signed by the controller account, similar to
 		/// `staking::unbond`.
 		///
-		/// The stash associated with the origin must have no ongoing unlocking chunks. If
+		/// The stash associated with the origin must have no ongoing unlocking chunks. Chunks that
+		/// are already unlocked, but not yet withdrawn, are withdrawn on behalf of the stash. If
 		/// successful, this will fully unbond and chill the stash. Then, it will enqueue the stash
 		/// to be checked in further blocks.
 		///
+		/// If [`Config::RejectExposedOnRegister`] is set, a nominator that is exposed in the
+		/// current era is rejected right away, since it would inevitably fail the check. This
+		/// check is weighed like checking a single era in `on_idle`.
+		///
 		/// If by the time this is called, the stash is actually eligible for fast-unstake, then
 		/// they are guaranteed to remain eligible, because the call will chill them as well.
 		///
 		/// If the check works, the entire staking data is removed, i.e. the stash is fully
-		/// unstaked.
+		/// unstaked, and its funds are moved to `destination`. Should that fail, the funds remain
+		/// free in the stash.
 		///
 		/// If the check fails, the stash remains chilled and waiting for being unbonded as in with
 		/// the normal staking system, but they lose part of their unbonding chunks due to consuming
 		/// the chain's resources.
+		///
+		/// Stashes in the [`Denylist`] cannot register.
+		///
+		/// The active and total stake of the stash are recorded upon registration. If by the time
+		/// it is due either of them has changed, for example because the stash has rebonded, has
+		/// been paid a reward to its stake or has withdrawn, or if it nominates again, it is not
+		/// unstaked. Its request is aborted with [`Event::Aborted`] instead, and its deposit
+		/// refunded.
 		#[pallet::call_index(0)]
-		#[pallet::weight(<T as Config>::WeightInfo::register_fast_unstake())]
-		pub fn register_fast_unstake(origin: OriginFor<T>) -> DispatchResult {
+		#[pallet::weight(Pallet::<T>::register_weight())]
+		pub fn register_fast_unstake(
+			origin: OriginFor<T>,
+			destination: UnstakeDestination<T::AccountId>,
+		) -> DispatchResult {
 			let ctrl = ensure_signed(origin)?;
 
 			ensure!(ErasToCheckPerBlock::<T>::get() != 0, <Error<T>>::CallNotAllowed);
 			let stash_account =
 				T::Staking::stash_by_ctrl(&ctrl).map_err(|_| Error::<T>::NotController)?;
+			ensure!(!Denylist::<T>::contains_key(&stash_account), Error::<T>::Denied);
 			ensure!(!Queue::<T>::contains_key(&stash_account), Error::<T>::AlreadyQueued);
 			ensure!(!Self::is_head(&stash_account), Error::<T>::AlreadyHead);
-			ensure!(!T::Staking::is_unbonding(&stash_account)?, Error::<T>::NotFullyBonded);
+			if T::Staking::is_unbonding(&stash_account)? {
+				// chunks that are already unlocked are merely not withdrawn. Withdraw them, and
+				// only reject if some of them are still locked.
+				let num_slashing_spans = T::Staking::slashing_spans_count(&stash_account);
+				T::Staking::withdraw_unbonded(stash_account.clone(), num_slashing_spans)?;
+				ensure!(!T::Staking::is_unbonding(&stash_account)?, Error::<T>::NotFullyBonded);
+			}
+
+			if T::RejectExposedOnRegister::get() &&
+				T::Staking::nominations(stash_account.clone()).map_or(false, |n| !n.is_empty())
+			{
+				ensure!(
+					!T::Staking::is_exposed_in_era(&stash_account, &T::Staking::current_era()),
+					Error::<T>::ActiveNominations
+				);
+			}
 
 			// chill and fully unstake.
 			T::Staking::chill(&stash_account)?;
 			T::Staking::fully_unbond(&stash_account)?;
+			let stake = T::Staking::stake(&stash_account)?;
 
 			T::Currency::reserve(&stash_account, T::Deposit::get())?;
 
 			// enqueue them.
-			Queue::<T>::insert(stash_account, T::Deposit::get());
+			let id = NextRequestId::<T>::mutate(|next| {
+				let id = *next;
+				next.saturating_inc();
+				id
+			});
+			Queue::<T>::insert(
+				stash_account,
+				QueueEntry {
+					deposit: T::Deposit::get(),
+					destination,
+					registered_at: <frame_system::Pallet<T>>::block_number(),
+					id,
+					ledger_active: stake.active,
+					ledger_total: stake.total,
+				},
+			);
 			Ok(())
 		}
 
@@ -255,11 +432,17 @@ pub mod pallet {
 		///
 		/// This is useful if one is registered, they are still waiting, and they change their mind.
 		///
+		/// A stash that is already part of the [`Head`] can still deregister, as long as no era has
+		/// been checked for it yet.
+		///
 		/// Note that the associated stash is still fully unbonded and chilled as a consequence of
 		/// calling `register_fast_unstake`. This should probably be followed by a call to
 		/// `Staking::rebond`.
 		#[pallet::call_index(1)]
-		#[pallet::weight(<T as Config>::WeightInfo::deregister())]
+		#[pallet::weight(
+			<T as Config>::WeightInfo::deregister()
+				.max(<T as Config>::WeightInfo::deregister_from_head())
+		)]
 		pub fn deregister(origin: OriginFor<T>) -> DispatchResult {
 			let ctrl = ensure_signed(origin)?;
 
@@ -267,15 +450,30 @@ pub mod pallet {
 
 			let stash_account =
 				T::Staking::stash_by_ctrl(&ctrl).map_err(|_| Error::<T>::NotController)?;
-			ensure!(Queue::<T>::contains_key(&stash_account), Error::<T>::NotQueued);
-			ensure!(!Self::is_head(&stash_account), Error::<T>::AlreadyHead);
-			let deposit = Queue::<T>::take(stash_account.clone());
 
-			if let Some(deposit) = deposit.defensive() {
-				let remaining = T::Currency::unreserve(&stash_account, deposit);
-				if !remaining.is_zero() {
-					Self::halt("not enough balance to unreserve");
-				}
+			let deposit = match Head::<T>::get().and_then(|head| {
+				head.stashes
+					.iter()
+					.position(|entry| entry.stash == stash_account)
+					.map(|index| (head, index))
+			}) {
+				Some((mut head, index)) => {
+					// once checking has begun, leaving would waste the work already done.
+					ensure!(head.checked.is_empty(), Error::<T>::AlreadyHead);
+					let HeadEntry { deposit, .. } = head.stashes.remove(index);
+					if head.stashes.is_empty() {
+						Head::<T>::kill();
+					} else {
+						Head::<T>::put(head);
+					}
+					deposit
+				},
+				None => Queue::<T>::take(&stash_account).ok_or(Error::<T>::NotQueued)?.deposit,
+			};
+
+			let remaining = T::Currency::unreserve(&stash_account, deposit);
+			if !remaining.is_zero() {
+				Self::halt("not enough balance to unreserve");
 			}
 
 			Ok(())
@@ -284,11 +482,57 @@ pub mod pallet {
 		/// Control the operation of this pallet.
 		///
 		/// Dispatch origin must be signed by the [`Config::ControlOrigin`].
+		///
+		/// Deprecated: this is an alias of [`Pallet::set_eras_to_check`] and will be removed in
+		/// the next release. Its call index is never going to be reused, so that historic
+		/// extrinsics keep decoding. Admin calls are assigned indices from 3 onwards.
 		#[pallet::call_index(2)]
-		#[pallet::weight(<T as Config>::WeightInfo::control())]
+		#[pallet::weight(<T as Config>::WeightInfo::set_eras_to_check())]
 		pub fn control(origin: OriginFor<T>, unchecked_eras_to_check: EraIndex) -> DispatchResult {
+			Self::set_eras_to_check(origin, unchecked_eras_to_check)
+		}
+
+		/// Set the number of eras to check per block, see [`ErasToCheckPerBlock`].
+		///
+		/// Setting it to 0 pauses this pallet. It cannot exceed [`Config::MaxErasToCheckPerBlock`].
+		///
+		/// Dispatch origin must be signed by the [`Config::ControlOrigin`].
+		#[pallet::call_index(3)]
+		#[pallet::weight(<T as Config>::WeightInfo::set_eras_to_check())]
+		pub fn set_eras_to_check(origin: OriginFor<T>, eras_to_check: EraIndex) -> DispatchResult {
+			let _ = T::ControlOrigin::ensure_origin(origin)?;
+			ensure!(eras_to_check <= T::MaxErasToCheckPerBlock::get(), Error::<T>::TooManyEras);
+			ErasToCheckPerBlock::<T>::put(eras_to_check);
+			Self::deposit_event(Event::<T>::ErasToCheckSet { eras_to_check });
+			Ok(())
+		}
+
+		/// Forbid `stash` from fast-unstaking.
+		///
+		/// If `stash` is already queued, it is dropped from the [`Queue`] with a refund once it is
+		/// due, see [`Event::Denied`]. Stashes that are already part of the [`Head`] are not
+		/// affected.
+		///
+		/// Dispatch origin must be signed by the [`Config::ControlOrigin`].
+		#[pallet::call_index(4)]
+		#[pallet::weight(<T as Config>::WeightInfo::add_to_denylist())]
+		pub fn add_to_denylist(origin: OriginFor<T>, stash: T::AccountId) -> DispatchResult {
+			let _ = T::ControlOrigin::ensure_origin(origin)?;
+			ensure!(!Denylist::<T>::contains_key(&stash), Error::<T>::AlreadyDenylisted);
+			Denylist::<T>::insert(&stash, ());
+			Self::deposit_event(Event::<T>::Denylisted { stash });
+			Ok(())
+		}
+
+		/// Allow `stash` to fast-unstake again.
+		///
+		/// Dispatch origin must be signed by the [`Config::ControlOrigin`].
+		#[pallet::call_index(5)]
+		#[pallet::weight(<T as Config>::WeightInfo::remove_from_denylist())]
+		pub fn remove_from_denylist(origin: OriginFor<T>, stash: T::AccountId) -> DispatchResult {
 			let _ = T::ControlOrigin::ensure_origin(origin)?;
-			ErasToCheckPerBlock::<T>::put(unchecked_eras_to_check);
+			ensure!(Denylist::<T>::take(&stash).is_some(), Error::<T>::NotDenylisted);
+			Self::deposit_event(Event::<T>::Undenylisted { stash });
 			Ok(())
 		}
 	}
@@ -297,10 +541,159 @@ pub mod pallet {
 		/// Returns `true` if `staker` is anywhere to be found in the `head`.
 		pub(crate) fn is_head(staker: &T::AccountId) -> bool {
 			Head::<T>::get().map_or(false, |UnstakeRequest { stashes, .. }| {
-				stashes.iter().any(|(stash, _)| stash == staker)
+				stashes.iter().any(|entry| &entry.stash == staker)
 			})
 		}
 
+		/// Returns `true` if `stash` is registered, i.e. either queued or part of the [`Head`].
+		pub fn is_registered(stash: &T::AccountId) -> bool {
+			Queue::<T>::contains_key(stash) || Self::is_head(stash)
+		}
+
+		/// The block number at which the current [`Head`] was promoted from the [`Queue`], if any.
+		///
+		/// Tells how long the current head has been processing.
+		pub fn head_since_block() -> Option<T::BlockNumber> {
+			Head::<T>::get().map(|head| head.since)
+		}
+
+		/// Up to `len` stashes of the [`Queue`], skipping the first `start` ones.
+		///
+		/// The queue is iterated in the order of its storage keys, which is the same order as
+		/// `state_getKeysPaged` over its prefix. Note that this is not the order in which stashes
+		/// have registered, see [`QueueEntry::registered_at`] for that.
+		pub fn queue_page(start: u32, len: u32) -> Vec<(T::AccountId, QueueEntry<T>)> {
+			Queue::<T>::iter().skip(start as usize).take(len as usize).collect()
+		}
+
+		/// A summary of the [`Queue`], the [`Head`] and [`ErasToCheckPerBlock`].
+		///
+		/// The head has to be checked for the last `bonding_duration + 1` eras, up to the current
+		/// one. Eras it has been checked for that fall out of this range are not counted.
+		///
+		/// Also reports whether staking still accepts new nominators, such that users can be
+		/// warned before they give up their spot.
+		pub fn telemetry() -> FastUnstakeTelemetry {
+			let head_progress = Head::<T>::get().map(|head| {
+				let current_era = T::Staking::current_era();
+				let oldest_era = current_era.saturating_sub(T::Staking::bonding_duration());
+				let checked =
+					(oldest_era..=current_era).filter(|e| head.checked.contains(e)).count() as u32;
+				(checked, current_era - oldest_era + 1)
+			});
+
+			FastUnstakeTelemetry {
+				queue_len: Queue::<T>::count(),
+				head_progress,
+				eras_to_check: ErasToCheckPerBlock::<T>::get(),
+				can_renominate_later: T::Staking::max_nominators()
+					.map_or(true, |max| T::Staking::nominators_count() < max),
+			}
+		}
+
+		/// Returns `true` if `head` has exceeded [`Config::MaxHeadBlocks`] at block `now`.
+		fn head_expired(head: &UnstakeRequest<T>, now: T::BlockNumber) -> bool {
+			now.saturating_sub(head.since) > T::MaxHeadBlocks::get()
+		}
+
+		/// Abort the current head, see [`Config::RequeueExpired`].
+		fn expire_head() {
+			let stashes = Head::<T>::take().map(|head| head.stashes).unwrap_or_default();
+			let requeued = T::RequeueExpired::get();
+			let now = <frame_system::Pallet<T>>::block_number();
+			for HeadEntry { stash, deposit, dest, id, ledger_active, ledger_total } in stashes {
+				if requeued {
+					let entry = QueueEntry {
+						deposit,
+						destination: dest,
+						registered_at: now,
+						id,
+						ledger_active,
+						ledger_total,
+					};
+					Queue::<T>::insert(stash.clone(), entry);
+				} else {
+					let remaining = T::Currency::unreserve(&stash, deposit);
+					if !remaining.is_zero() {
+						Self::halt("not enough balance to unreserve");
+					}
+				}
+				log!(info, "head expired for {:?}, requeued: {:?}", stash, requeued);
+				Self::deposit_event(Event::<T>::Expired { stash, id, requeued });
+			}
+		}
+
+		/// The weight of [`Call::register_fast_unstake`].
+		///
+		/// Checking the exposures of the current era on registration iterates over all of them,
+		/// which costs as much as checking one era of `on_idle`.
+		fn register_weight() -> Weight {
+			let weight = <T as Config>::WeightInfo::register_fast_unstake();
+			if T::RejectExposedOnRegister::get() {
+				let validator_count = T::Staking::desired_validator_count();
+				weight.saturating_add(<T as Config>::WeightInfo::on_idle_check(validator_count))
+			} else {
+				weight
+			}
+		}
+
+		/// The weight of unstaking `stashes`.
+		///
+		/// Joining a pool is considerably more expensive than the other destinations, hence it
+		/// has its own weight function.
+		fn unstake_weight(stashes: &[HeadEntry<T>]) -> Weight {
+			if stashes.iter().any(|entry| matches!(entry.dest, UnstakeDestination::Pool(_))) {
+				<T as Config>::WeightInfo::on_idle_unstake_with_pool_join()
+			} else {
+				<T as Config>::WeightInfo::on_idle_unstake()
+			}
+		}
+
+		/// Whether the ledger of `stash` differs from the `ledger_active` and `ledger_total` it had
+		/// upon registration, or `stash` has nominated since.
+		///
+		/// Such a stash may have staked again, and unstaking it would release funds that have never
+		/// been checked for. The stake is compared exactly, as rebonding, rewards, slashes and
+		/// withdrawals can otherwise offset each other. A stash whose ledger is gone is not
+		/// considered to have staked.
+		fn staked_since_registration(
+			stash: &T::AccountId,
+			ledger_active: BalanceOf<T>,
+			ledger_total: BalanceOf<T>,
+		) -> bool {
+			let changed = T::Staking::stake(stash)
+				.map_or(false, |s| s.active != ledger_active || s.total != ledger_total);
+			let nominated = T::Staking::nominations(stash.clone()).map_or(false, |n| !n.is_empty());
+			changed || nominated
+		}
+
+		/// Refund the `deposit` of a queued `stash` that has been denied in the meantime.
+		fn drop_denied(stash: T::AccountId, deposit: BalanceOf<T>, id: RequestId) {
+			let remaining = T::Currency::unreserve(&stash, deposit);
+			if !remaining.is_zero() {
+				Self::halt("not enough balance to unreserve");
+			} else {
+				log!(info, "dropped {:?} from the queue, it has been denied", stash);
+				Self::deposit_event(Event::<T>::Denied { stash, id });
+			}
+		}
+
+		/// Record that the head has been checked for `eras`, skipping the ones it already has
+		/// been checked for, such that no era is ever counted twice.
+		///
+		/// Returns an error if `checked` would exceed its bound.
+		fn note_checked(
+			checked: &mut BoundedVec<EraIndex, MaxChecking<T>>,
+			eras: &[EraIndex],
+		) -> Result<(), ()> {
+			for era in eras {
+				if !checked.contains(era) {
+					checked.try_push(*era).map_err(|_| ())?;
+				}
+			}
+			Ok(())
+		}
+
 		/// Halt the operations of this pallet.
 		pub(crate) fn halt(reason: &'static str) {
 			frame_support::defensive!(reason);
@@ -308,13 +701,51 @@ pub mod pallet {
 			Self::deposit_event(Event::<T>::InternalError)
 		}
 
+		/// Move `amount` of the funds of the freshly unstaked `stash` to `destination`.
+		///
+		/// Returns the outcome and where the funds ended up. Any failure is rolled back, leaving
+		/// the funds free in the stash.
+		fn move_funds(
+			stash: &T::AccountId,
+			amount: BalanceOf<T>,
+			destination: UnstakeDestination<T::AccountId>,
+		) -> (UnstakeOutcome, UnstakeDestination<T::AccountId>) {
+			let result = frame_support::storage::with_storage_layer(|| match &destination {
+				UnstakeDestination::Free => Ok(()),
+				UnstakeDestination::Pool(pool_id) => T::Pools::join(stash, amount, *pool_id),
+				UnstakeDestination::Account(dest) =>
+					T::Currency::transfer(stash, dest, amount, ExistenceRequirement::AllowDeath),
+			});
+			match result {
+				Ok(()) => (UnstakeOutcome::Success, destination),
+				Err(e) => {
+					log!(
+						debug,
+						"failed to move funds of {:?} to {:?}: {:?}",
+						stash,
+						destination,
+						e
+					);
+					let outcome = match destination {
+						UnstakeDestination::Pool(_) =>
+							UnstakeOutcome::PoolJoinFailed(T::Pools::join_error(e)),
+						_ => UnstakeOutcome::TransferFailed,
+					};
+					(outcome, UnstakeDestination::Free)
+				},
+			}
+		}
+
 		/// process up to `remaining_weight`.
 		///
 		/// Returns the actual weight consumed.
 		///
+		/// Called from `on_initialize` with [`Config::MandatoryWeightPerBlock`] and from `on_idle`
+		/// with the leftover weight of the block.
+		///
 		/// Written for readability in mind, not efficiency. For example:
 		///
-		/// 1. We assume this is only ever called once per `on_idle`. This is because we know that
+		/// 1. We assume this is only ever called once per hook. This is because we know that
 		/// in all use cases, even a single nominator cannot be unbonded in a single call. Multiple
 		/// calls to this function are thus not needed.
 		///
@@ -331,20 +762,53 @@ pub mod pallet {
 			// meaning that the number of exposures to check is either this per era, or less.
 			let validator_count = T::Staking::desired_validator_count();
 
+			// the cost of finishing the current head, if any, which depends on its destinations.
+			let unstake_weight = Head::<T>::get()
+				.map_or(<T as Config>::WeightInfo::on_idle_unstake(), |head| {
+					Self::unstake_weight(&head.stashes)
+				});
+
+			// the cost of promoting a new head from the queue, which is only paid if there is no
+			// head yet, and something to promote.
+			let promotion_weight = if Head::<T>::exists() || Queue::<T>::count().is_zero() {
+				Weight::zero()
+			} else {
+				<T as Config>::WeightInfo::queue_promotion()
+			};
+
 			// determine the number of eras to check. This is based on both `ErasToCheckPerBlock`
 			// and `remaining_weight` passed on to us from the runtime executive.
 			let max_weight = |v, u| {
 				<T as Config>::WeightInfo::on_idle_check(v * u)
-					.max(<T as Config>::WeightInfo::on_idle_unstake())
+					.max(unstake_weight)
+					.saturating_add(promotion_weight)
 			};
+			let mut promote_only = false;
 			while max_weight(validator_count, eras_to_check_per_block).any_gt(remaining_weight) {
 				eras_to_check_per_block.saturating_dec();
 				if eras_to_check_per_block.is_zero() {
+					// a new head can still be promoted, its eras are checked in the next blocks.
+					if !promotion_weight.is_zero() && promotion_weight.all_lte(remaining_weight) {
+						promote_only = true;
+						break
+					}
 					log!(debug, "early existing because eras_to_check_per_block is zero");
 					return T::DbWeight::get().reads(2)
 				}
 			}
 
+			// the range that we're allowed to check in this round.
+			let current_era = T::Staking::current_era();
+			let bonding_duration = T::Staking::bonding_duration();
+
+			// abort the head if it has been stuck for too long. This also happens during an
+			// election, since it does not unstake anyone.
+			let now = <frame_system::Pallet<T>>::block_number();
+			if Head::<T>::get().map_or(false, |head| Self::head_expired(&head, now)) {
+				Self::expire_head();
+				return <T as Config>::WeightInfo::on_idle_unstake()
+			}
+
 			if T::Staking::election_ongoing() {
 				// NOTE: we assume `ongoing` does not consume any weight.
 				// there is an ongoing election -- we better not do anything. Imagine someone is not
@@ -353,25 +817,19 @@ pub mod pallet {
 				return T::DbWeight::get().reads(2)
 			}
 
-			let UnstakeRequest { stashes, mut checked } = match Head::<T>::take().or_else(|| {
-				// NOTE: there is no order guarantees in `Queue`.
-				let stashes: BoundedVec<_, T::BatchSize> = Queue::<T>::drain()
-					.take(T::BatchSize::get() as usize)
-					.collect::<Vec<_>>()
-					.try_into()
-					.expect("take ensures bound is met; qed");
-				if stashes.is_empty() {
-					None
-				} else {
-					Some(UnstakeRequest { stashes, checked: Default::default() })
-				}
-			}) {
-				None => {
-					// There's no `Head` and nothing in the `Queue`, nothing to do here.
-					return T::DbWeight::get().reads(4)
-				},
-				Some(head) => head,
-			};
+			let UnstakeRequest { stashes, mut checked, since } =
+				match Head::<T>::take().or_else(Self::promote_from_queue) {
+					None => {
+						// There's no `Head`, and the `Queue` was either empty, or only had denied
+						// stashes which have been dropped.
+						return T::DbWeight::get().reads(4).max(promotion_weight)
+					},
+					Some(head) if promote_only => {
+						Head::<T>::put(head);
+						return promotion_weight
+					},
+					Some(head) => head,
+				};
 
 			log!(
 				debug,
@@ -381,13 +839,12 @@ pub mod pallet {
 				remaining_weight
 			);
 
-			// the range that we're allowed to check in this round.
-			let current_era = T::Staking::current_era();
-			let bonding_duration = T::Staking::bonding_duration();
-
-			// prune all the old eras that we don't care about. This will help us keep the bound
-			// of `checked`.
-			checked.retain(|e| *e >= current_era.saturating_sub(bonding_duration));
+			// prune all the old eras that we don't care about, as well as duplicates which should
+			// never have been recorded. This will help us keep the bound of `checked`.
+			let mut distinct = BTreeSet::<EraIndex>::new();
+			checked.retain(|e| {
+				*e >= current_era.saturating_sub(bonding_duration) && distinct.insert(*e)
+			});
 
 			let unchecked_eras_to_check = {
 				// get the last available `bonding_duration` eras up to current era in reverse
@@ -418,18 +875,46 @@ pub mod pallet {
 				unchecked_eras_to_check
 			);
 
-			let unstake_stash = |stash: T::AccountId, deposit| {
+			let unstake_stash = |entry: HeadEntry<T>| {
+				let HeadEntry { stash, deposit, dest, id, ledger_active, ledger_total } = entry;
+				if Self::staked_since_registration(&stash, ledger_active, ledger_total) {
+					let remaining = T::Currency::unreserve(&stash, deposit);
+					if !remaining.is_zero() {
+						Self::halt("not enough balance to unreserve");
+					} else {
+						log!(info, "aborted unstaking {:?}, it has staked again", stash);
+						Self::deposit_event(Event::<T>::Aborted { stash, id });
+					}
+					return
+				}
+
+				let amount = T::Staking::total_stake(&stash).unwrap_or_default();
+				let payee = T::Staking::payee(&stash);
 				let result = T::Staking::force_unstake(stash.clone());
 				let remaining = T::Currency::unreserve(&stash, deposit);
 				if !remaining.is_zero() {
 					Self::halt("not enough balance to unreserve");
 				} else {
-					log!(info, "unstaked {:?}, outcome: {:?}", stash, result);
-					Self::deposit_event(Event::<T>::Unstaked { stash, result });
+					let (result, destination) = match result {
+						Ok(()) => {
+							let moved = Self::move_funds(&stash, amount, dest);
+							T::OnUnstaked::on_unstaked(&stash, amount);
+							moved
+						},
+						Err(e) => (UnstakeOutcome::UnstakeFailed(e), UnstakeDestination::Free),
+					};
+					log!(info, "unstaked {:?}, outcome: {:?}, to {:?}", stash, result, destination);
+					Self::deposit_event(Event::<T>::Unstaked {
+						stash,
+						id,
+						result,
+						destination,
+						payee,
+					});
 				}
 			};
 
-			let check_stash = |stash, deposit, eras_checked: &mut BTreeSet<EraIndex>| {
+			let check_stash = |stash, deposit, id, eras_checked: &mut BTreeSet<EraIndex>| {
 				let is_exposed = unchecked_eras_to_check.iter().any(|e| {
 					eras_checked.insert(*e);
 					T::Staking::is_exposed_in_era(&stash, e)
@@ -438,7 +923,7 @@ pub mod pallet {
 				if is_exposed {
 					T::Currency::slash_reserved(&stash, deposit);
 					log!(info, "slashed {:?} by {:?}", stash, deposit);
-					Self::deposit_event(Event::<T>::Slashed { stash, amount: deposit });
+					Self::deposit_event(Event::<T>::Slashed { stash, id, amount: deposit });
 					false
 				} else {
 					true
@@ -447,18 +932,19 @@ pub mod pallet {
 
 			if unchecked_eras_to_check.is_empty() {
 				// `stash` is not exposed in any era now -- we can let go of them now.
-				stashes.into_iter().for_each(|(stash, deposit)| unstake_stash(stash, deposit));
+				let unstake_weight = Self::unstake_weight(&stashes);
+				stashes.into_iter().for_each(unstake_stash);
 				Self::deposit_event(Event::<T>::BatchFinished);
-				<T as Config>::WeightInfo::on_idle_unstake()
+				unstake_weight
 			} else {
 				// eras checked so far.
 				let mut eras_checked = BTreeSet::<EraIndex>::new();
 
 				let pre_length = stashes.len();
-				let stashes: BoundedVec<(T::AccountId, BalanceOf<T>), T::BatchSize> = stashes
+				let stashes: BoundedVec<_, T::BatchSize> = stashes
 					.into_iter()
-					.filter(|(stash, deposit)| {
-						check_stash(stash.clone(), *deposit, &mut eras_checked)
+					.filter(|entry| {
+						check_stash(entry.stash.clone(), entry.deposit, entry.id, &mut eras_checked)
 					})
 					.collect::<Vec<_>>()
 					.try_into()
@@ -473,14 +959,21 @@ pub mod pallet {
 					post_length,
 				);
 
-				match checked.try_extend(unchecked_eras_to_check.clone().into_iter()) {
+				match Self::note_checked(&mut checked, &unchecked_eras_to_check) {
 					Ok(_) =>
 						if stashes.is_empty() {
 							Self::deposit_event(Event::<T>::BatchFinished);
 						} else {
-							Head::<T>::put(UnstakeRequest { stashes, checked });
-							Self::deposit_event(Event::<T>::BatchChecked {
-								eras: unchecked_eras_to_check,
+							let ids = stashes.iter().map(|entry| entry.id).collect();
+							Head::<T>::put(UnstakeRequest { stashes, checked, since });
+							// `unchecked_eras_to_check` is sorted in descending order.
+							let first_era = unchecked_eras_to_check.first().copied();
+							let last_era = unchecked_eras_to_check.last().copied();
+							Self::deposit_event(Event::<T>::ErasChecked {
+								first_era: first_era.unwrap_or_default(),
+								last_era: last_era.unwrap_or_default(),
+								count: unchecked_eras_to_check.len() as u32,
+								ids,
 							});
 						},
 					Err(_) => {
@@ -492,7 +985,60 @@ pub mod pallet {
 				<T as Config>::WeightInfo::on_idle_check(
 					validator_count * eras_checked.len() as u32,
 				)
+				.saturating_add(promotion_weight)
 			}
 		}
+
+		/// Take the next batch of up to [`Config::BatchSize`] stashes from the [`Queue`], to
+		/// become the new [`Head`].
+		///
+		/// At most [`Config::BatchSize`] entries are visited, such that the weight stays bounded
+		/// by [`WeightInfo::queue_promotion`]. Denied stashes are dropped on the way and still
+		/// take a slot of the batch. Returns `None` if none of the visited stashes remains.
+		pub(crate) fn promote_from_queue() -> Option<UnstakeRequest<T>> {
+			// NOTE: there is no order guarantees in `Queue`.
+			let stashes: BoundedVec<_, T::BatchSize> = Queue::<T>::drain()
+				.take(T::BatchSize::get() as usize)
+				.filter_map(|(stash, entry)| {
+					if Denylist::<T>::contains_key(&stash) {
+						Self::drop_denied(stash, entry.deposit, entry.id);
+						None
+					} else {
+						Some(HeadEntry {
+							stash,
+							deposit: entry.deposit,
+							dest: entry.destination,
+							id: entry.id,
+							ledger_active: entry.ledger_active,
+							ledger_total: entry.ledger_total,
+						})
+					}
+				})
+				.collect::<Vec<_>>()
+				.try_into()
+				.expect("take ensures bound is met; qed");
+			if stashes.is_empty() {
+				return None
+			}
+
+			Some(UnstakeRequest {
+				stashes,
+				checked: Default::default(),
+				since: <frame_system::Pallet<T>>::block_number(),
+			})
+		}
+	}
+
+	/// Rejects staking operations of stashes that are in the [`Queue`] or in the [`Head`].
+	///
+	/// Such stashes are expected to remain fully unbonded and not nominate until they are
+	/// unstaked or deregistered. Checking this at dispatch time also covers staking calls made
+	/// through e.g. a batch or a proxy.
+	impl<T: Config> sp_staking::OnStakingOperation<T::AccountId> for Pallet<T> {
+		fn ensure_allowed(stash: &T::AccountId, _: sp_staking::StakingOperation) -> DispatchResult {
+			ensure!(!Queue::<T>::contains_key(stash), Error::<T>::AlreadyQueued);
+			ensure!(!Self::is_head(stash), Error::<T>::AlreadyHead);
+			Ok(())
+		}
 	}
 }
@@ -18,6 +18,7 @@
 pub mod v1 {
 	use crate::{types::BalanceOf, *};
 	use frame_support::{
+		pallet_prelude::*,
 		storage::unhashed,
 		traits::{Defensive, Get, GetStorageVersion, OnRuntimeUpgrade},
 		weights::Weight,
@@ -25,6 +26,14 @@ pub mod v1 {
 	use sp_staking::EraIndex;
 	use sp_std::prelude::*;
 
+	#[frame_support::storage_alias]
+	type Queue<T: Config> =
+		StorageMap<Pallet<T>, Twox64Concat, <T as frame_system::Config>::AccountId, BalanceOf<T>>;
+
+	/// The counter of the [`Queue`], which is a counted map.
+	#[frame_support::storage_alias]
+	type CounterForQueue<T: Config> = StorageValue<Pallet<T>, u32, ValueQuery>;
+
 	pub struct MigrateToV1<T>(sp_std::marker::PhantomData<T>);
 	impl<T: Config> OnRuntimeUpgrade for MigrateToV1<T> {
 		fn on_runtime_upgrade() -> Weight {
@@ -50,6 +59,10 @@ pub mod v1 {
 						)
 						.defensive()
 					{
+						// written in the v1 layout, which is upgraded by `v2::MigrateToV2`.
+						if !Queue::<T>::contains_key(&stash) {
+							CounterForQueue::<T>::mutate(|count| *count = count.saturating_add(1));
+						}
 						Queue::<T>::insert(stash, deposit);
 					} else {
 						// not much we can do here -- head is already deleted.
@@ -77,3 +90,779 @@ pub mod v1 {
 		}
 	}
 }
+
+pub mod v2 {
+	use crate::{
+		types::{BalanceOf, UnstakeDestination},
+		*,
+	};
+	use codec::Decode;
+	use frame_support::{
+		pallet_prelude::*,
+		storage::unhashed,
+		traits::{GetStorageVersion, OnRuntimeUpgrade, StorageVersion},
+		weights::Weight,
+	};
+	use sp_runtime::traits::Saturating;
+	use sp_staking::EraIndex;
+	use sp_std::prelude::*;
+
+	#[derive(Decode)]
+	struct OldUnstakeRequest<T: Config> {
+		stashes: BoundedVec<(T::AccountId, BalanceOf<T>), T::BatchSize>,
+		checked: BoundedVec<EraIndex, MaxChecking<T>>,
+	}
+
+	#[frame_support::storage_alias]
+	type Queue<T: Config> = StorageMap<
+		Pallet<T>,
+		Twox64Concat,
+		<T as frame_system::Config>::AccountId,
+		(BalanceOf<T>, UnstakeDestination<<T as frame_system::Config>::AccountId>),
+	>;
+
+	/// Migrate the queued and in-flight stashes to carry an [`UnstakeDestination`].
+	///
+	/// All of them keep their funds free, which is what happened before.
+	pub struct MigrateToV2<T>(sp_std::marker::PhantomData<T>);
+	impl<T: Config> OnRuntimeUpgrade for MigrateToV2<T> {
+		fn on_runtime_upgrade() -> Weight {
+			let current = Pallet::<T>::current_storage_version();
+			let onchain = Pallet::<T>::on_chain_storage_version();
+
+			log!(
+				info,
+				"Running migration with current storage version {:?} / onchain {:?}",
+				current,
+				onchain
+			);
+
+			if onchain == 1 {
+				let mut translated = 0u64;
+				Queue::<T>::translate::<BalanceOf<T>, _>(|_, deposit| {
+					translated.saturating_inc();
+					Some((deposit, UnstakeDestination::Free))
+				});
+				// written in the v2 layout, which is upgraded by `v3::MigrateToV3`.
+				if let Some(old) = unhashed::take::<OldUnstakeRequest<T>>(&Head::<T>::hashed_key())
+				{
+					translated.saturating_inc();
+					let stashes = old
+						.stashes
+						.into_iter()
+						.map(|(stash, deposit)| (stash, deposit, UnstakeDestination::Free))
+						.collect::<Vec<_>>()
+						.try_into()
+						.expect("same bound as the old head; qed");
+					unhashed::put(
+						&Head::<T>::hashed_key(),
+						&super::v3::OldUnstakeRequest::<T> { stashes, checked: old.checked },
+					);
+				}
+
+				StorageVersion::new(2).put::<Pallet<T>>();
+				log!(info, "Upgraded {} items, storage to version {:?}", translated, current);
+				T::DbWeight::get().reads_writes(translated + 1, translated + 1)
+			} else {
+				log!(info, "Migration did not execute. This probably should be removed");
+				T::DbWeight::get().reads(1)
+			}
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn pre_upgrade() -> Result<Vec<u8>, &'static str> {
+			use codec::Encode;
+			assert_eq!(Pallet::<T>::on_chain_storage_version(), 1);
+			Ok(crate::Queue::<T>::count().encode())
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn post_upgrade(data: Vec<u8>) -> Result<(), &'static str> {
+			let count: u32 = Decode::decode(&mut &data[..]).map_err(|_| "invalid pre state")?;
+			assert_eq!(crate::Queue::<T>::count(), count);
+			assert_eq!(Pallet::<T>::on_chain_storage_version(), 2);
+			Ok(())
+		}
+	}
+}
+
+pub mod v3 {
+	use crate::{
+		types::{BalanceOf, UnstakeDestination},
+		*,
+	};
+	use codec::{Decode, Encode};
+	use frame_support::{
+		storage::unhashed,
+		traits::{Get, GetStorageVersion, OnRuntimeUpgrade, StorageVersion},
+		weights::Weight,
+		BoundedVec,
+	};
+	use sp_staking::EraIndex;
+	use sp_std::prelude::*;
+
+	#[derive(Encode, Decode)]
+	pub(crate) struct OldUnstakeRequest<T: Config> {
+		pub(crate) stashes: BoundedVec<
+			(T::AccountId, BalanceOf<T>, UnstakeDestination<T::AccountId>),
+			T::BatchSize,
+		>,
+		pub(crate) checked: BoundedVec<EraIndex, MaxChecking<T>>,
+	}
+
+	/// Migrate the [`Head`] to record the block at which it became the head.
+	///
+	/// The block of an existing head is unknown, so it is set to the block of the migration.
+	pub struct MigrateToV3<T>(sp_std::marker::PhantomData<T>);
+	impl<T: Config> OnRuntimeUpgrade for MigrateToV3<T> {
+		fn on_runtime_upgrade() -> Weight {
+			let current = Pallet::<T>::current_storage_version();
+			let onchain = Pallet::<T>::on_chain_storage_version();
+
+			log!(
+				info,
+				"Running migration with current storage version {:?} / onchain {:?}",
+				current,
+				onchain
+			);
+
+			if onchain == 2 {
+				let since = <frame_system::Pallet<T>>::block_number();
+				let translated =
+					match unhashed::take::<OldUnstakeRequest<T>>(&Head::<T>::hashed_key()) {
+						Some(old) => {
+							// written in the v4 layout, which is upgraded by `v5::MigrateToV5`.
+							unhashed::put(
+								&Head::<T>::hashed_key(),
+								&super::v5::OldUnstakeRequest::<T> {
+									stashes: old.stashes,
+									checked: old.checked,
+									since,
+								},
+							);
+							1
+						},
+						None => 0,
+					};
+
+				StorageVersion::new(3).put::<Pallet<T>>();
+				log!(info, "Upgraded {} items, storage to version {:?}", translated, current);
+				T::DbWeight::get().reads_writes(translated + 1, translated + 1)
+			} else {
+				log!(info, "Migration did not execute. This probably should be removed");
+				T::DbWeight::get().reads(1)
+			}
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn pre_upgrade() -> Result<Vec<u8>, &'static str> {
+			assert_eq!(Pallet::<T>::on_chain_storage_version(), 2);
+			Ok(Head::<T>::exists().encode())
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn post_upgrade(data: Vec<u8>) -> Result<(), &'static str> {
+			let existed: bool = Decode::decode(&mut &data[..]).map_err(|_| "invalid pre state")?;
+			assert_eq!(Head::<T>::exists(), existed);
+			assert_eq!(Pallet::<T>::on_chain_storage_version(), 3);
+			Ok(())
+		}
+	}
+}
+
+pub mod v4 {
+	use crate::{
+		types::{BalanceOf, UnstakeDestination},
+		*,
+	};
+	use codec::{Decode, Encode};
+	use frame_support::{
+		pallet_prelude::*,
+		traits::{GetStorageVersion, OnRuntimeUpgrade, StorageVersion},
+		weights::Weight,
+	};
+	use sp_runtime::traits::Saturating;
+	use sp_std::prelude::*;
+
+	#[derive(Encode, Decode)]
+	struct NewQueueEntry<T: Config> {
+		deposit: BalanceOf<T>,
+		destination: UnstakeDestination<T::AccountId>,
+		registered_at: T::BlockNumber,
+	}
+
+	#[frame_support::storage_alias]
+	type Queue<T: Config> = StorageMap<
+		Pallet<T>,
+		Twox64Concat,
+		<T as frame_system::Config>::AccountId,
+		NewQueueEntry<T>,
+	>;
+
+	/// Migrate the [`Queue`] to record the block at which each stash has been queued.
+	///
+	/// The block of an existing entry is unknown, so it is set to the block of the migration.
+	pub struct MigrateToV4<T>(sp_std::marker::PhantomData<T>);
+	impl<T: Config> OnRuntimeUpgrade for MigrateToV4<T> {
+		fn on_runtime_upgrade() -> Weight {
+			let current = Pallet::<T>::current_storage_version();
+			let onchain = Pallet::<T>::on_chain_storage_version();
+
+			log!(
+				info,
+				"Running migration with current storage version {:?} / onchain {:?}",
+				current,
+				onchain
+			);
+
+			if onchain == 3 {
+				let mut translated = 0u64;
+				let registered_at = <frame_system::Pallet<T>>::block_number();
+				Queue::<T>::translate::<(BalanceOf<T>, UnstakeDestination<T::AccountId>), _>(
+					|_, (deposit, destination)| {
+						translated.saturating_inc();
+						Some(NewQueueEntry { deposit, destination, registered_at })
+					},
+				);
+
+				StorageVersion::new(4).put::<Pallet<T>>();
+				log!(info, "Upgraded {} items, storage to version {:?}", translated, current);
+				T::DbWeight::get().reads_writes(translated + 1, translated + 1)
+			} else {
+				log!(info, "Migration did not execute. This probably should be removed");
+				T::DbWeight::get().reads(1)
+			}
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn pre_upgrade() -> Result<Vec<u8>, &'static str> {
+			assert_eq!(Pallet::<T>::on_chain_storage_version(), 3);
+			Ok(crate::Queue::<T>::count().encode())
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn post_upgrade(data: Vec<u8>) -> Result<(), &'static str> {
+			let count: u32 = Decode::decode(&mut &data[..]).map_err(|_| "invalid pre state")?;
+			assert_eq!(crate::Queue::<T>::count(), count);
+			assert_eq!(Pallet::<T>::on_chain_storage_version(), 4);
+			Ok(())
+		}
+	}
+}
+
+pub mod v5 {
+	use crate::{
+		types::{BalanceOf, RequestId, UnstakeDestination},
+		*,
+	};
+	use codec::{Decode, Encode};
+	use frame_support::{
+		pallet_prelude::*,
+		storage::unhashed,
+		traits::{GetStorageVersion, OnRuntimeUpgrade, StorageVersion},
+		weights::Weight,
+	};
+	use sp_runtime::traits::Saturating;
+	use sp_staking::EraIndex;
+	use sp_std::prelude::*;
+
+	#[derive(Decode)]
+	struct OldQueueEntry<T: Config> {
+		deposit: BalanceOf<T>,
+		destination: UnstakeDestination<T::AccountId>,
+		registered_at: T::BlockNumber,
+	}
+
+	#[derive(Encode, Decode)]
+	struct NewQueueEntry<T: Config> {
+		deposit: BalanceOf<T>,
+		destination: UnstakeDestination<T::AccountId>,
+		registered_at: T::BlockNumber,
+		id: RequestId,
+	}
+
+	#[frame_support::storage_alias]
+	type Queue<T: Config> = StorageMap<
+		Pallet<T>,
+		Twox64Concat,
+		<T as frame_system::Config>::AccountId,
+		NewQueueEntry<T>,
+	>;
+
+	#[derive(Encode, Decode)]
+	pub(crate) struct OldUnstakeRequest<T: Config> {
+		pub(crate) stashes: BoundedVec<
+			(T::AccountId, BalanceOf<T>, UnstakeDestination<T::AccountId>),
+			T::BatchSize,
+		>,
+		pub(crate) checked: BoundedVec<EraIndex, MaxChecking<T>>,
+		pub(crate) since: T::BlockNumber,
+	}
+
+	/// Migrate the [`Queue`] and the [`Head`] to assign an identifier to every request.
+	///
+	/// The stashes of the head get the lowest identifiers, since they have been queued before the
+	/// rest. [`NextRequestId`] continues after the highest identifier assigned.
+	pub struct MigrateToV5<T>(sp_std::marker::PhantomData<T>);
+	impl<T: Config> OnRuntimeUpgrade for MigrateToV5<T> {
+		fn on_runtime_upgrade() -> Weight {
+			let current = Pallet::<T>::current_storage_version();
+			let onchain = Pallet::<T>::on_chain_storage_version();
+
+			log!(
+				info,
+				"Running migration with current storage version {:?} / onchain {:?}",
+				current,
+				onchain
+			);
+
+			if onchain == 4 {
+				let mut next_id: RequestId = 0;
+				let mut assign_id = || {
+					let id = next_id;
+					next_id.saturating_inc();
+					id
+				};
+
+				let head_key = Head::<T>::hashed_key();
+				if unhashed::exists(&head_key) {
+					match unhashed::get::<OldUnstakeRequest<T>>(&head_key) {
+						// written in the v5 layout, which is upgraded by `v6::MigrateToV6`.
+						Some(old) => unhashed::put(
+							&head_key,
+							&super::v6::OldUnstakeRequest::<T> {
+								stashes: old
+									.stashes
+									.into_iter()
+									.map(|(stash, deposit, destination)| {
+										(stash, deposit, destination, assign_id())
+									})
+									.collect::<Vec<_>>()
+									.try_into()
+									.expect("same bound as the old head; qed"),
+								checked: old.checked,
+								since: old.since,
+							},
+						),
+						None => log!(warn, "Failed to decode the head, it is left as is"),
+					}
+				}
+				// written in the v5 layout, which is upgraded by `v6::MigrateToV6`.
+				Queue::<T>::translate::<OldQueueEntry<T>, _>(|_, old| {
+					Some(NewQueueEntry {
+						deposit: old.deposit,
+						destination: old.destination,
+						registered_at: old.registered_at,
+						id: assign_id(),
+					})
+				});
+
+				NextRequestId::<T>::put(next_id);
+				StorageVersion::new(5).put::<Pallet<T>>();
+				log!(info, "Upgraded {} items, storage to version {:?}", next_id, current);
+				T::DbWeight::get().reads_writes(next_id + 2, next_id + 3)
+			} else {
+				log!(info, "Migration did not execute. This probably should be removed");
+				T::DbWeight::get().reads(1)
+			}
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn pre_upgrade() -> Result<Vec<u8>, &'static str> {
+			assert_eq!(Pallet::<T>::on_chain_storage_version(), 4);
+			Ok(crate::Queue::<T>::count().encode())
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn post_upgrade(data: Vec<u8>) -> Result<(), &'static str> {
+			let count: u32 = Decode::decode(&mut &data[..]).map_err(|_| "invalid pre state")?;
+			assert_eq!(crate::Queue::<T>::count(), count);
+			let in_head = frame_support::storage::unhashed::get::<super::v6::OldUnstakeRequest<T>>(
+				&Head::<T>::hashed_key(),
+			)
+			.map_or(0, |head| head.stashes.len() as u64);
+			assert_eq!(NextRequestId::<T>::get(), in_head + count as u64);
+			assert_eq!(Pallet::<T>::on_chain_storage_version(), 5);
+			Ok(())
+		}
+	}
+}
+
+pub mod v6 {
+	use crate::{
+		types::{BalanceOf, RequestId, UnstakeDestination},
+		*,
+	};
+	use codec::{Decode, Encode};
+	use frame_support::{
+		pallet_prelude::*,
+		traits::{GetStorageVersion, OnRuntimeUpgrade, StorageVersion},
+		weights::Weight,
+	};
+	use sp_runtime::traits::Saturating;
+	use sp_staking::{EraIndex, StakingInterface};
+	use sp_std::prelude::*;
+
+	#[derive(Decode)]
+	struct OldQueueEntry<T: Config> {
+		deposit: BalanceOf<T>,
+		destination: UnstakeDestination<T::AccountId>,
+		registered_at: T::BlockNumber,
+		id: RequestId,
+	}
+
+	#[derive(Encode, Decode)]
+	pub(crate) struct OldUnstakeRequest<T: Config> {
+		pub(crate) stashes: BoundedVec<
+			(T::AccountId, BalanceOf<T>, UnstakeDestination<T::AccountId>, RequestId),
+			T::BatchSize,
+		>,
+		pub(crate) checked: BoundedVec<EraIndex, MaxChecking<T>>,
+		pub(crate) since: T::BlockNumber,
+	}
+
+	#[derive(Encode, Decode)]
+	struct NewQueueEntry<T: Config> {
+		deposit: BalanceOf<T>,
+		destination: UnstakeDestination<T::AccountId>,
+		registered_at: T::BlockNumber,
+		id: RequestId,
+		ledger_total: BalanceOf<T>,
+	}
+
+	#[derive(Encode, Decode)]
+	struct NewHeadEntry<T: Config> {
+		stash: T::AccountId,
+		deposit: BalanceOf<T>,
+		dest: UnstakeDestination<T::AccountId>,
+		id: RequestId,
+		ledger_total: BalanceOf<T>,
+	}
+
+	#[derive(Encode, Decode)]
+	struct NewUnstakeRequest<T: Config> {
+		stashes: BoundedVec<NewHeadEntry<T>, T::BatchSize>,
+		checked: BoundedVec<EraIndex, MaxChecking<T>>,
+		since: T::BlockNumber,
+	}
+
+	#[frame_support::storage_alias]
+	type Queue<T: Config> = StorageMap<
+		Pallet<T>,
+		Twox64Concat,
+		<T as frame_system::Config>::AccountId,
+		NewQueueEntry<T>,
+	>;
+
+	#[frame_support::storage_alias]
+	type Head<T: Config> = StorageValue<Pallet<T>, NewUnstakeRequest<T>>;
+
+	/// Migrate the [`Queue`] and the [`Head`] to record the total stake of every stash upon
+	/// registration.
+	///
+	/// The stake of an existing request upon registration is unknown, so its unlocking stake at
+	/// the block of the migration is recorded.
+	pub struct MigrateToV6<T>(sp_std::marker::PhantomData<T>);
+	impl<T: Config> OnRuntimeUpgrade for MigrateToV6<T> {
+		fn on_runtime_upgrade() -> Weight {
+			let current = Pallet::<T>::current_storage_version();
+			let onchain = Pallet::<T>::on_chain_storage_version();
+
+			log!(
+				info,
+				"Running migration with current storage version {:?} / onchain {:?}",
+				current,
+				onchain
+			);
+
+			if onchain == 5 {
+				let mut translated = 0u64;
+				// rewards paid out to the stake since the registration are not part of it.
+				let mut ledger_total = |stash: &T::AccountId| {
+					translated.saturating_inc();
+					T::Staking::stake(stash)
+						.map(|stake| stake.total.saturating_sub(stake.active))
+						.unwrap_or_default()
+				};
+
+				let head = Head::<T>::translate::<OldUnstakeRequest<T>, _>(|maybe_old| {
+					maybe_old.map(|old| NewUnstakeRequest {
+						stashes: old
+							.stashes
+							.into_iter()
+							.map(|(stash, deposit, dest, id)| {
+								let ledger_total = ledger_total(&stash);
+								NewHeadEntry { stash, deposit, dest, id, ledger_total }
+							})
+							.collect::<Vec<_>>()
+							.try_into()
+							.expect("same bound as the old head; qed"),
+						checked: old.checked,
+						since: old.since,
+					})
+				});
+				if head.is_err() {
+					log!(warn, "Failed to decode the head, it is left as is");
+				}
+				Queue::<T>::translate::<OldQueueEntry<T>, _>(|stash, old| {
+					Some(NewQueueEntry {
+						deposit: old.deposit,
+						destination: old.destination,
+						registered_at: old.registered_at,
+						id: old.id,
+						ledger_total: ledger_total(&stash),
+					})
+				});
+
+				StorageVersion::new(6).put::<Pallet<T>>();
+				log!(info, "Upgraded {} items, storage to version {:?}", translated, current);
+				// reading the total stake of a stash reads its controller and its ledger.
+				T::DbWeight::get().reads_writes(3 * translated + 2, translated + 2)
+			} else {
+				log!(info, "Migration did not execute. This probably should be removed");
+				T::DbWeight::get().reads(1)
+			}
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn pre_upgrade() -> Result<Vec<u8>, &'static str> {
+			assert_eq!(Pallet::<T>::on_chain_storage_version(), 5);
+			Ok(crate::Queue::<T>::count().encode())
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn post_upgrade(data: Vec<u8>) -> Result<(), &'static str> {
+			let count: u32 = Decode::decode(&mut &data[..]).map_err(|_| "invalid pre state")?;
+			assert_eq!(crate::Queue::<T>::count(), count);
+			assert_eq!(Pallet::<T>::on_chain_storage_version(), 6);
+			Ok(())
+		}
+	}
+}
+
+pub mod v7 {
+	use crate::{
+		types::{BalanceOf, RequestId, UnstakeDestination},
+		*,
+	};
+	use codec::{Decode, Encode};
+	use frame_support::{
+		pallet_prelude::*,
+		traits::{GetStorageVersion, OnRuntimeUpgrade, StorageVersion},
+		weights::Weight,
+	};
+	use sp_runtime::traits::Saturating;
+	use sp_staking::{EraIndex, StakingInterface};
+	use sp_std::prelude::*;
+
+	#[derive(Decode)]
+	struct OldQueueEntry<T: Config> {
+		deposit: BalanceOf<T>,
+		destination: UnstakeDestination<T::AccountId>,
+		registered_at: T::BlockNumber,
+		id: RequestId,
+		_ledger_total: BalanceOf<T>,
+	}
+
+	#[derive(Decode)]
+	struct OldHeadEntry<T: Config> {
+		stash: T::AccountId,
+		deposit: BalanceOf<T>,
+		dest: UnstakeDestination<T::AccountId>,
+		id: RequestId,
+		_ledger_total: BalanceOf<T>,
+	}
+
+	#[derive(Decode)]
+	struct OldUnstakeRequest<T: Config> {
+		stashes: BoundedVec<OldHeadEntry<T>, T::BatchSize>,
+		checked: BoundedVec<EraIndex, MaxChecking<T>>,
+		since: T::BlockNumber,
+	}
+
+	#[derive(Encode, Decode)]
+	struct NewQueueEntry<T: Config> {
+		deposit: BalanceOf<T>,
+		destination: UnstakeDestination<T::AccountId>,
+		registered_at: T::BlockNumber,
+		id: RequestId,
+		ledger_active: BalanceOf<T>,
+		ledger_total: BalanceOf<T>,
+	}
+
+	#[derive(Encode, Decode)]
+	struct NewHeadEntry<T: Config> {
+		stash: T::AccountId,
+		deposit: BalanceOf<T>,
+		dest: UnstakeDestination<T::AccountId>,
+		id: RequestId,
+		ledger_active: BalanceOf<T>,
+		ledger_total: BalanceOf<T>,
+	}
+
+	#[derive(Encode, Decode)]
+	struct NewUnstakeRequest<T: Config> {
+		stashes: BoundedVec<NewHeadEntry<T>, T::BatchSize>,
+		checked: BoundedVec<EraIndex, MaxChecking<T>>,
+		since: T::BlockNumber,
+	}
+
+	#[frame_support::storage_alias]
+	type Queue<T: Config> = StorageMap<
+		Pallet<T>,
+		Twox64Concat,
+		<T as frame_system::Config>::AccountId,
+		NewQueueEntry<T>,
+	>;
+
+	#[frame_support::storage_alias]
+	type Head<T: Config> = StorageValue<Pallet<T>, NewUnstakeRequest<T>>;
+
+	/// Migrate the [`Queue`] and the [`Head`] to record the active stake of every stash upon
+	/// registration next to its total stake.
+	///
+	/// The unlocking stake recorded by [`super::v6::MigrateToV6`] cannot be told apart from the
+	/// total stake recorded upon registration, so both the active and the total stake at the block
+	/// of the migration are recorded.
+	pub struct MigrateToV7<T>(sp_std::marker::PhantomData<T>);
+	impl<T: Config> OnRuntimeUpgrade for MigrateToV7<T> {
+		fn on_runtime_upgrade() -> Weight {
+			let current = Pallet::<T>::current_storage_version();
+			let onchain = Pallet::<T>::on_chain_storage_version();
+
+			log!(
+				info,
+				"Running migration with current storage version {:?} / onchain {:?}",
+				current,
+				onchain
+			);
+
+			if onchain == 6 {
+				let mut translated = 0u64;
+				let mut ledger = |stash: &T::AccountId| {
+					translated.saturating_inc();
+					T::Staking::stake(stash)
+						.map(|stake| (stake.active, stake.total))
+						.unwrap_or_default()
+				};
+
+				let head = Head::<T>::translate::<OldUnstakeRequest<T>, _>(|maybe_old| {
+					maybe_old.map(|old| NewUnstakeRequest {
+						stashes: old
+							.stashes
+							.into_iter()
+							.map(|OldHeadEntry { stash, deposit, dest, id, .. }| {
+								let (ledger_active, ledger_total) = ledger(&stash);
+								NewHeadEntry {
+									stash,
+									deposit,
+									dest,
+									id,
+									ledger_active,
+									ledger_total,
+								}
+							})
+							.collect::<Vec<_>>()
+							.try_into()
+							.expect("same bound as the old head; qed"),
+						checked: old.checked,
+						since: old.since,
+					})
+				});
+				if head.is_err() {
+					log!(warn, "Failed to decode the head, it is left as is");
+				}
+				Queue::<T>::translate::<OldQueueEntry<T>, _>(|stash, old| {
+					let (ledger_active, ledger_total) = ledger(&stash);
+					Some(NewQueueEntry {
+						deposit: old.deposit,
+						destination: old.destination,
+						registered_at: old.registered_at,
+						id: old.id,
+						ledger_active,
+						ledger_total,
+					})
+				});
+
+				StorageVersion::new(7).put::<Pallet<T>>();
+				log!(info, "Upgraded {} items, storage to version {:?}", translated, current);
+				// reading the stake of a stash reads its controller and its ledger.
+				T::DbWeight::get().reads_writes(3 * translated + 2, translated + 2)
+			} else {
+				log!(info, "Migration did not execute. This probably should be removed");
+				T::DbWeight::get().reads(1)
+			}
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn pre_upgrade() -> Result<Vec<u8>, &'static str> {
+			assert_eq!(Pallet::<T>::on_chain_storage_version(), 6);
+			Ok(crate::Queue::<T>::count().encode())
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn post_upgrade(data: Vec<u8>) -> Result<(), &'static str> {
+			let count: u32 = Decode::decode(&mut &data[..]).map_err(|_| "invalid pre state")?;
+			assert_eq!(crate::Queue::<T>::count(), count);
+			assert_eq!(Pallet::<T>::on_chain_storage_version(), 7);
+			Ok(())
+		}
+	}
+}
+
+pub mod v8 {
+	use crate::*;
+	use frame_support::{
+		pallet_prelude::*,
+		traits::{GetStorageVersion, OnRuntimeUpgrade, StorageVersion},
+		weights::Weight,
+	};
+	use sp_staking::EraIndex;
+	#[cfg(feature = "try-runtime")]
+	use sp_std::prelude::*;
+
+	#[frame_support::storage_alias]
+	pub(crate) type HeadSince<T: Config> = StorageValue<Pallet<T>, EraIndex>;
+
+	/// Migrate to measure the age of the [`Head`] in blocks, from its `since` block.
+	///
+	/// The era in which the head has been created is no longer needed, hence it is removed.
+	pub struct MigrateToV8<T>(sp_std::marker::PhantomData<T>);
+	impl<T: Config> OnRuntimeUpgrade for MigrateToV8<T> {
+		fn on_runtime_upgrade() -> Weight {
+			let current = Pallet::<T>::current_storage_version();
+			let onchain = Pallet::<T>::on_chain_storage_version();
+
+			log!(
+				info,
+				"Running migration with current storage version {:?} / onchain {:?}",
+				current,
+				onchain
+			);
+
+			if onchain == 7 {
+				HeadSince::<T>::kill();
+
+				StorageVersion::new(8).put::<Pallet<T>>();
+				log!(info, "Upgraded storage to version {:?}", current);
+				T::DbWeight::get().reads_writes(1, 2)
+			} else {
+				log!(info, "Migration did not execute. This probably should be removed");
+				T::DbWeight::get().reads(1)
+			}
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn pre_upgrade() -> Result<Vec<u8>, &'static str> {
+			assert_eq!(Pallet::<T>::on_chain_storage_version(), 7);
+			Ok(Vec::new())
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn post_upgrade(_: Vec<u8>) -> Result<(), &'static str> {
+			assert!(!HeadSince::<T>::exists());
+			assert_eq!(Pallet::<T>::on_chain_storage_version(), 8);
+			Ok(())
+		}
+	}
+}
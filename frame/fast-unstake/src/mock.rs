@@ -20,10 +20,13 @@ use frame_benchmarking::frame_support::assert_ok;
 use frame_support::{
 	pallet_prelude::*,
 	parameter_types,
-	traits::{ConstU64, Currency},
+	traits::{ConstU128, ConstU64, Currency},
 	weights::constants::WEIGHT_REF_TIME_PER_SECOND,
 };
-use sp_runtime::traits::{Convert, IdentityLookup};
+use sp_runtime::{
+	traits::{Convert, IdentityLookup},
+	ModuleError,
+};
 
 use pallet_staking::{Exposure, IndividualExposure, StakerStatus};
 use sp_std::prelude::*;
@@ -68,6 +71,28 @@ impl frame_system::Config for Runtime {
 	type MaxConsumers = frame_support::traits::ConstU32<16>;
 }
 
+impl pallet_utility::Config for Runtime {
+	type RuntimeEvent = RuntimeEvent;
+	type RuntimeCall = RuntimeCall;
+	type PalletsOrigin = OriginCaller;
+	type WeightInfo = ();
+}
+
+impl pallet_proxy::Config for Runtime {
+	type RuntimeEvent = RuntimeEvent;
+	type RuntimeCall = RuntimeCall;
+	type Currency = Balances;
+	type ProxyType = ();
+	type ProxyDepositBase = ConstU128<1>;
+	type ProxyDepositFactor = ConstU128<1>;
+	type MaxProxies = ConstU32<4>;
+	type WeightInfo = ();
+	type CallHasher = sp_runtime::traits::BlakeTwo256;
+	type MaxPending = ConstU32<2>;
+	type AnnouncementDepositBase = ConstU128<1>;
+	type AnnouncementDepositFactor = ConstU128<1>;
+}
+
 impl pallet_timestamp::Config for Runtime {
 	type Moment = u64;
 	type OnTimestampSet = ();
@@ -147,6 +172,7 @@ impl pallet_staking::Config for Runtime {
 	type NextNewSession = ();
 	type HistoryDepth = ConstU32<84>;
 	type MaxNominatorRewardedPerValidator = ConstU32<64>;
+	type MaxExposurePageSize = ConstU32<64>;
 	type OffendingValidatorsThreshold = ();
 	type ElectionProvider = MockElection;
 	type GenesisElectionProvider = Self::ElectionProvider;
@@ -154,6 +180,10 @@ impl pallet_staking::Config for Runtime {
 	type TargetList = pallet_staking::UseValidatorsMap<Self>;
 	type MaxUnlockingChunks = ConstU32<32>;
 	type OnStakerSlash = ();
+	type OnStakingOperation = FastUnstake;
+	type KickRewardPool = ();
+	type KickReward = ();
+	type MaxCommissionBatch = ConstU32<64>;
 	type BenchmarkingConfig = pallet_staking::TestBenchmarkingConfig;
 	type WeightInfo = ();
 }
@@ -175,6 +205,68 @@ impl Convert<sp_core::U256, Balance> for U256ToBalance {
 parameter_types! {
 	pub static Deposit: u128 = 7;
 	pub static BatchSize: u32 = 1;
+	pub static MaxErasToCheckPerBlock: u32 = 64;
+	pub static RejectExposedOnRegister: bool = false;
+	pub static MaxHeadBlocks: u64 = 100;
+	pub static RequeueExpired: bool = false;
+	pub static MandatoryWeightPerBlock: Weight = Weight::zero();
+	pub static PoolJoins: Vec<(AccountId, Balance, fast_unstake::types::PoolId)> = vec![];
+	pub static RejectPoolJoins: Option<DispatchError> = None;
+	pub static Unstaked: Vec<(AccountId, Balance)> = vec![];
+	pub static ValidatorsPerEra: AccountId = 32;
+	pub static ExposedInEras: Vec<(AccountId, Vec<u32>)> = vec![];
+}
+
+/// The errors of [`MockPools`], reported like those of a pallet at index 42.
+#[derive(Clone, Copy)]
+pub enum MockPoolError {
+	PoolNotFound,
+	MinimumBondNotMet,
+	MaxPoolMembers,
+}
+
+impl From<MockPoolError> for DispatchError {
+	fn from(error: MockPoolError) -> Self {
+		DispatchError::Module(ModuleError {
+			index: 42,
+			error: [error as u8, 0, 0, 0],
+			message: None,
+		})
+	}
+}
+
+/// Records pool joins, failing them with [`RejectPoolJoins`] if set.
+pub struct MockPools;
+impl fast_unstake::types::PoolJoiner<AccountId, Balance> for MockPools {
+	fn join(
+		who: &AccountId,
+		amount: Balance,
+		pool_id: fast_unstake::types::PoolId,
+	) -> DispatchResult {
+		if let Some(error) = RejectPoolJoins::get() {
+			return Err(error)
+		}
+		PoolJoins::mutate(|joins| joins.push((*who, amount, pool_id)));
+		Ok(())
+	}
+
+	fn join_error(error: DispatchError) -> fast_unstake::types::PoolJoinError {
+		use fast_unstake::types::PoolJoinError;
+		match error {
+			e if e == MockPoolError::PoolNotFound.into() => PoolJoinError::PoolNotFound,
+			e if e == MockPoolError::MinimumBondNotMet.into() => PoolJoinError::MinimumBondNotMet,
+			e if e == MockPoolError::MaxPoolMembers.into() => PoolJoinError::MaxPoolMembers,
+			e => PoolJoinError::Other(e),
+		}
+	}
+}
+
+/// Records the stashes reported as unstaked in [`Unstaked`].
+pub struct RecordUnstaked;
+impl fast_unstake::types::OnFastUnstakeComplete<AccountId, Balance> for RecordUnstaked {
+	fn on_unstaked(stash: &AccountId, amount: Balance) {
+		Unstaked::mutate(|unstaked| unstaked.push((*stash, amount)));
+	}
 }
 
 impl fast_unstake::Config for Runtime {
@@ -182,8 +274,15 @@ impl fast_unstake::Config for Runtime {
 	type Deposit = Deposit;
 	type Currency = Balances;
 	type Staking = Staking;
+	type Pools = MockPools;
+	type OnUnstaked = RecordUnstaked;
 	type ControlOrigin = frame_system::EnsureRoot<Self::AccountId>;
 	type BatchSize = BatchSize;
+	type MaxErasToCheckPerBlock = MaxErasToCheckPerBlock;
+	type RejectExposedOnRegister = RejectExposedOnRegister;
+	type MaxHeadBlocks = MaxHeadBlocks;
+	type RequeueExpired = RequeueExpired;
+	type MandatoryWeightPerBlock = MandatoryWeightPerBlock;
 	type WeightInfo = ();
 }
 
@@ -200,6 +299,8 @@ frame_support::construct_runtime!(
 		Balances: pallet_balances,
 		Staking: pallet_staking,
 		FastUnstake: fast_unstake,
+		Utility: pallet_utility,
+		Proxy: pallet_proxy,
 	}
 );
 
@@ -236,7 +337,6 @@ impl Default for ExtBuilder {
 	}
 }
 
-pub(crate) const VALIDATORS_PER_ERA: AccountId = 32;
 pub(crate) const VALIDATOR_PREFIX: AccountId = 100;
 pub(crate) const NOMINATORS_PER_VALIDATOR_PER_ERA: AccountId = 4;
 pub(crate) const NOMINATOR_PREFIX: AccountId = 1000;
@@ -246,15 +346,25 @@ impl ExtBuilder {
 		// validators are prefixed with 100 and nominators with 1000 to prevent conflict. Make sure
 		// all the other accounts used in tests are below 100. Also ensure here that we don't
 		// overlap.
-		assert!(VALIDATOR_PREFIX + VALIDATORS_PER_ERA < NOMINATOR_PREFIX);
+		assert!(VALIDATOR_PREFIX + ValidatorsPerEra::get() < NOMINATOR_PREFIX);
 
-		(VALIDATOR_PREFIX..VALIDATOR_PREFIX + VALIDATORS_PER_ERA)
+		// the stashes set up with `exposed_in_eras` back the first validator of this era.
+		let exposed = ExposedInEras::get()
+			.into_iter()
+			.filter(|(_, eras)| eras.contains(&era))
+			.map(|(who, _)| IndividualExposure { who, value: 0 as Balance })
+			.collect::<Vec<_>>();
+
+		(VALIDATOR_PREFIX..VALIDATOR_PREFIX + ValidatorsPerEra::get())
 			.map(|v| {
 				// for the sake of sanity, let's register this taker as an actual validator.
-				let others = (NOMINATOR_PREFIX..
+				let mut others = (NOMINATOR_PREFIX..
 					(NOMINATOR_PREFIX + NOMINATORS_PER_VALIDATOR_PER_ERA))
 					.map(|n| IndividualExposure { who: n, value: 0 as Balance })
 					.collect::<Vec<_>>();
+				if v == VALIDATOR_PREFIX {
+					others.extend(exposed.iter().cloned());
+				}
 				(v, Exposure { total: 0, own: 0, others })
 			})
 			.for_each(|(validator, exposure)| {
@@ -262,17 +372,53 @@ impl ExtBuilder {
 			});
 	}
 
+	/// Set the bonding duration, and thus the eras registered and checked, to `eras`.
+	pub(crate) fn bonding_duration(self, eras: u32) -> Self {
+		BondingDuration::set(eras);
+		self
+	}
+
+	/// Set the number of validators registered in each era.
+	pub(crate) fn validators_per_era(self, count: AccountId) -> Self {
+		ValidatorsPerEra::set(count);
+		self
+	}
+
+	/// Bond `stash` as a nominator that is exposed in `eras`, and nowhere else.
+	///
+	/// The stash is its own controller and is not registered for fast unstake.
+	pub(crate) fn exposed_in_eras(self, stash: AccountId, eras: Vec<u32>) -> Self {
+		ExposedInEras::mutate(|exposed| exposed.push((stash, eras)));
+		self
+	}
+
 	pub(crate) fn batch(self, size: u32) -> Self {
 		BatchSize::set(size);
 		self
 	}
 
+	pub(crate) fn reject_exposed_on_register(self, reject: bool) -> Self {
+		RejectExposedOnRegister::set(reject);
+		self
+	}
+
+	pub(crate) fn max_head_blocks(self, blocks: u64) -> Self {
+		MaxHeadBlocks::set(blocks);
+		self
+	}
+
+	pub(crate) fn requeue_expired(self, requeue: bool) -> Self {
+		RequeueExpired::set(requeue);
+		self
+	}
+
 	pub(crate) fn build(self) -> sp_io::TestExternalities {
 		sp_tracing::try_init_simple();
 		let mut storage =
 			frame_system::GenesisConfig::default().build_storage::<Runtime>().unwrap();
 
-		let validators_range = VALIDATOR_PREFIX..VALIDATOR_PREFIX + VALIDATORS_PER_ERA;
+		let validators_range = VALIDATOR_PREFIX..VALIDATOR_PREFIX + ValidatorsPerEra::get();
+		let exposed = ExposedInEras::get().into_iter().map(|(who, _)| who).collect::<Vec<_>>();
 		let nominators_range =
 			NOMINATOR_PREFIX..NOMINATOR_PREFIX + NOMINATORS_PER_VALIDATOR_PER_ERA;
 
@@ -290,6 +436,7 @@ impl ExtBuilder {
 				)
 				.chain(validators_range.clone().map(|x| (x, 7 + 100)))
 				.chain(nominators_range.clone().map(|x| (x, 7 + 100)))
+				.chain(exposed.iter().map(|x| (*x, 7 + 100)))
 				.collect::<Vec<_>>(),
 		}
 		.assimilate_storage(&mut storage);
@@ -301,6 +448,7 @@ impl ExtBuilder {
 				.map(|(x, y, z)| (x, y, z, pallet_staking::StakerStatus::Nominator(vec![42])))
 				.chain(validators_range.map(|x| (x, x, 100, StakerStatus::Validator)))
 				.chain(nominators_range.map(|x| (x, x, 100, StakerStatus::Nominator(vec![x]))))
+				.chain(exposed.into_iter().map(|x| (x, x, 100, StakerStatus::Nominator(vec![x]))))
 				.collect::<Vec<_>>(),
 			..Default::default()
 		}
@@ -317,7 +465,7 @@ impl ExtBuilder {
 			}
 
 			// because we read this value as a measure of how many validators we have.
-			pallet_staking::ValidatorCount::<Runtime>::put(VALIDATORS_PER_ERA as u32);
+			pallet_staking::ValidatorCount::<Runtime>::put(ValidatorsPerEra::get() as u32);
 		});
 
 		ext
@@ -363,7 +511,7 @@ pub fn assert_unstaked(stash: &AccountId) {
 
 pub fn create_exposed_nominator(exposed: AccountId, era: u32) {
 	// create an exposed nominator in era 1
-	pallet_staking::ErasStakers::<T>::mutate(era, VALIDATORS_PER_ERA, |expo| {
+	pallet_staking::ErasStakers::<T>::mutate(era, ValidatorsPerEra::get(), |expo| {
 		expo.others.push(IndividualExposure { who: exposed, value: 0 as Balance });
 	});
 	Balances::make_free_balance_be(&exposed, 100);
@@ -375,5 +523,8 @@ pub fn create_exposed_nominator(exposed: AccountId, era: u32) {
 	));
 	assert_ok!(Staking::nominate(RuntimeOrigin::signed(exposed), vec![exposed]));
 	// register the exposed one.
-	assert_ok!(FastUnstake::register_fast_unstake(RuntimeOrigin::signed(exposed)));
+	assert_ok!(FastUnstake::register_fast_unstake(
+		RuntimeOrigin::signed(exposed),
+		UnstakeDestination::Free
+	));
 }
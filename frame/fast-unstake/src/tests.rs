@@ -19,12 +19,43 @@
 
 use super::*;
 use crate::{mock::*, types::*, weights::WeightInfo, Event};
-use frame_support::{assert_noop, assert_ok, bounded_vec, pallet_prelude::*, traits::Currency};
+use frame_support::{
+	assert_noop, assert_ok, bounded_vec, dispatch::GetDispatchInfo, pallet_prelude::*,
+	traits::Currency,
+};
 use pallet_staking::{CurrentEra, RewardDestination};
 
-use sp_runtime::traits::BadOrigin;
+use sp_runtime::{traits::BadOrigin, ModuleError};
 use sp_staking::StakingInterface;
 
+/// The entry of request `id` that has been queued at `registered_at` with the default deposit, for
+/// a fully unbonded stash of the default stake.
+fn queued(
+	destination: UnstakeDestination<AccountId>,
+	registered_at: BlockNumber,
+	id: RequestId,
+) -> QueueEntry<T> {
+	QueueEntry {
+		deposit: Deposit::get(),
+		destination,
+		registered_at,
+		id,
+		ledger_active: 0,
+		ledger_total: 107,
+	}
+}
+
+/// The entry of `stash` in the head for request `id` with the default deposit, where the stash
+/// was fully unbonded with `ledger_total` upon registration.
+fn in_head(
+	stash: AccountId,
+	dest: UnstakeDestination<AccountId>,
+	id: RequestId,
+	ledger_total: Balance,
+) -> HeadEntry<T> {
+	HeadEntry { stash, deposit: Deposit::get(), dest, id, ledger_active: 0, ledger_total }
+}
+
 #[test]
 fn test_setup_works() {
 	ExtBuilder::default().build_and_execute(|| {
@@ -37,7 +68,10 @@ fn register_works() {
 	ExtBuilder::default().build_and_execute(|| {
 		ErasToCheckPerBlock::<T>::put(1);
 		// Controller account registers for fast unstake.
-		assert_ok!(FastUnstake::register_fast_unstake(RuntimeOrigin::signed(2)));
+		assert_ok!(FastUnstake::register_fast_unstake(
+			RuntimeOrigin::signed(2),
+			UnstakeDestination::Free
+		));
 		// Ensure stash is in the queue.
 		assert_ne!(Queue::<T>::get(1), None);
 	});
@@ -52,7 +86,7 @@ fn register_insufficient_funds_fails() {
 
 		// Controller account registers for fast unstake.
 		assert_noop!(
-			FastUnstake::register_fast_unstake(RuntimeOrigin::signed(2)),
+			FastUnstake::register_fast_unstake(RuntimeOrigin::signed(2), UnstakeDestination::Free),
 			BalancesError::<T, _>::InsufficientBalance,
 		);
 
@@ -65,7 +99,7 @@ fn register_insufficient_funds_fails() {
 fn register_disabled_fails() {
 	ExtBuilder::default().build_and_execute(|| {
 		assert_noop!(
-			FastUnstake::register_fast_unstake(RuntimeOrigin::signed(2)),
+			FastUnstake::register_fast_unstake(RuntimeOrigin::signed(2), UnstakeDestination::Free),
 			Error::<T>::CallNotAllowed
 		);
 	});
@@ -81,7 +115,7 @@ fn cannot_register_if_not_bonded() {
 		}
 		// Attempt to fast unstake.
 		assert_noop!(
-			FastUnstake::register_fast_unstake(RuntimeOrigin::signed(1)),
+			FastUnstake::register_fast_unstake(RuntimeOrigin::signed(1), UnstakeDestination::Free),
 			Error::<T>::NotController
 		);
 	});
@@ -92,27 +126,48 @@ fn cannot_register_if_in_queue() {
 	ExtBuilder::default().build_and_execute(|| {
 		ErasToCheckPerBlock::<T>::put(1);
 		// Insert some Queue item
-		Queue::<T>::insert(1, 10);
+		Queue::<T>::insert(1, queued(UnstakeDestination::Free, 1, 0));
 		// Cannot re-register, already in queue
 		assert_noop!(
-			FastUnstake::register_fast_unstake(RuntimeOrigin::signed(2)),
+			FastUnstake::register_fast_unstake(RuntimeOrigin::signed(2), UnstakeDestination::Free),
 			Error::<T>::AlreadyQueued
 		);
 	});
 }
 
+#[test]
+fn cannot_register_if_denylisted() {
+	ExtBuilder::default().build_and_execute(|| {
+		ErasToCheckPerBlock::<T>::put(1);
+		assert_ok!(FastUnstake::add_to_denylist(RuntimeOrigin::root(), 1));
+		assert_noop!(
+			FastUnstake::register_fast_unstake(RuntimeOrigin::signed(2), UnstakeDestination::Free),
+			Error::<T>::Denied
+		);
+
+		// once removed from the denylist, the stash can register again.
+		assert_ok!(FastUnstake::remove_from_denylist(RuntimeOrigin::root(), 1));
+		assert_ok!(FastUnstake::register_fast_unstake(
+			RuntimeOrigin::signed(2),
+			UnstakeDestination::Free
+		));
+		assert_eq!(Queue::<T>::get(1), Some(queued(UnstakeDestination::Free, 1, 0)));
+	});
+}
+
 #[test]
 fn cannot_register_if_head() {
 	ExtBuilder::default().build_and_execute(|| {
 		ErasToCheckPerBlock::<T>::put(1);
 		// Insert some Head item for stash
 		Head::<T>::put(UnstakeRequest {
-			stashes: bounded_vec![(1, Deposit::get())],
+			stashes: bounded_vec![in_head(1, UnstakeDestination::Free, 0, 107)],
 			checked: bounded_vec![],
+			since: 1,
 		});
 		// Controller attempts to regsiter
 		assert_noop!(
-			FastUnstake::register_fast_unstake(RuntimeOrigin::signed(2)),
+			FastUnstake::register_fast_unstake(RuntimeOrigin::signed(2), UnstakeDestination::Free),
 			Error::<T>::AlreadyHead
 		);
 	});
@@ -126,9 +181,125 @@ fn cannot_register_if_has_unlocking_chunks() {
 		assert_ok!(Staking::unbond(RuntimeOrigin::signed(2), 50_u128));
 		// Cannot register for fast unstake with unlock chunks active
 		assert_noop!(
-			FastUnstake::register_fast_unstake(RuntimeOrigin::signed(2)),
+			FastUnstake::register_fast_unstake(RuntimeOrigin::signed(2), UnstakeDestination::Free),
+			Error::<T>::NotFullyBonded
+		);
+	});
+}
+
+#[test]
+fn register_withdraws_fully_unlocked_chunks() {
+	ExtBuilder::default().build_and_execute(|| {
+		ErasToCheckPerBlock::<T>::put(1);
+		// Start unbonding half of staked tokens in era 0.
+		assert_ok!(Staking::unbond(RuntimeOrigin::signed(2), 50_u128));
+		// Chunk is unlocked, but not withdrawn.
+		CurrentEra::<T>::put(BondingDuration::get());
+		assert_eq!(Staking::ledger(2).unwrap().unlocking.len(), 1);
+
+		// Registration withdraws the chunk on behalf of the stash.
+		assert_ok!(FastUnstake::register_fast_unstake(
+			RuntimeOrigin::signed(2),
+			UnstakeDestination::Free
+		));
+		assert!(Queue::<T>::contains_key(1));
+		let ledger = Staking::ledger(2).unwrap();
+		assert_eq!(ledger.total, 107 - 50);
+		// the remaining active stake is fully unbonded by the registration.
+		assert_eq!(ledger.active, 0);
+		assert_eq!(ledger.unlocking.len(), 1);
+	});
+}
+
+#[test]
+fn cannot_register_if_has_partially_unlocked_chunks() {
+	ExtBuilder::default().build_and_execute(|| {
+		ErasToCheckPerBlock::<T>::put(1);
+		// Unbond in era 0 and era 1.
+		assert_ok!(Staking::unbond(RuntimeOrigin::signed(2), 10_u128));
+		CurrentEra::<T>::put(1);
+		assert_ok!(Staking::unbond(RuntimeOrigin::signed(2), 10_u128));
+		// Only the first chunk is unlocked.
+		CurrentEra::<T>::put(BondingDuration::get());
+
+		assert_noop!(
+			FastUnstake::register_fast_unstake(RuntimeOrigin::signed(2), UnstakeDestination::Free),
 			Error::<T>::NotFullyBonded
 		);
+		// nothing was withdrawn.
+		assert_eq!(Staking::ledger(2).unwrap().unlocking.len(), 2);
+	});
+}
+
+#[test]
+fn register_without_unlocking_chunks_works() {
+	ExtBuilder::default().build_and_execute(|| {
+		ErasToCheckPerBlock::<T>::put(1);
+		assert!(Staking::ledger(2).unwrap().unlocking.is_empty());
+
+		assert_ok!(FastUnstake::register_fast_unstake(
+			RuntimeOrigin::signed(2),
+			UnstakeDestination::Free
+		));
+		assert!(Queue::<T>::contains_key(1));
+		let ledger = Staking::ledger(2).unwrap();
+		assert_eq!(ledger.total, 107);
+		assert_eq!(ledger.active, 0);
+	});
+}
+
+#[test]
+fn register_rejects_exposed_nominator_if_configured() {
+	ExtBuilder::default().reject_exposed_on_register(true).build_and_execute(|| {
+		ErasToCheckPerBlock::<T>::put(1);
+		CurrentEra::<T>::put(BondingDuration::get());
+
+		// nominator 1000 is exposed in the current era.
+		assert!(Staking::nominators(NOMINATOR_PREFIX).is_some());
+		assert_noop!(
+			FastUnstake::register_fast_unstake(
+				RuntimeOrigin::signed(NOMINATOR_PREFIX),
+				UnstakeDestination::Free
+			),
+			Error::<T>::ActiveNominations
+		);
+
+		// while 1 nominates as well, but is not exposed.
+		assert_ok!(FastUnstake::register_fast_unstake(
+			RuntimeOrigin::signed(2),
+			UnstakeDestination::Free
+		));
+		assert!(Queue::<T>::contains_key(1));
+
+		// the check is weighed like checking one era.
+		let call = Call::<T>::register_fast_unstake { destination: UnstakeDestination::Free };
+		assert_eq!(
+			call.get_dispatch_info().weight,
+			<T as Config>::WeightInfo::register_fast_unstake().saturating_add(
+				<T as Config>::WeightInfo::on_idle_check(pallet_staking::ValidatorCount::<T>::get())
+			)
+		);
+	});
+}
+
+#[test]
+fn register_accepts_exposed_nominator_if_not_configured() {
+	ExtBuilder::default().reject_exposed_on_register(false).build_and_execute(|| {
+		ErasToCheckPerBlock::<T>::put(1);
+		CurrentEra::<T>::put(BondingDuration::get());
+
+		// the exposure is only found later on, when the stash is checked.
+		assert_ok!(FastUnstake::register_fast_unstake(
+			RuntimeOrigin::signed(NOMINATOR_PREFIX),
+			UnstakeDestination::Free
+		));
+		assert!(Queue::<T>::contains_key(NOMINATOR_PREFIX));
+
+		let call = Call::<T>::register_fast_unstake { destination: UnstakeDestination::Free };
+		assert_eq!(
+			call.get_dispatch_info().weight,
+			<T as Config>::WeightInfo::register_fast_unstake()
+		);
 	});
 }
 
@@ -140,7 +311,10 @@ fn deregister_works() {
 		assert_eq!(<T as Config>::Currency::reserved_balance(&1), 0);
 
 		// Controller account registers for fast unstake.
-		assert_ok!(FastUnstake::register_fast_unstake(RuntimeOrigin::signed(2)));
+		assert_ok!(FastUnstake::register_fast_unstake(
+			RuntimeOrigin::signed(2),
+			UnstakeDestination::Free
+		));
 		assert_eq!(<T as Config>::Currency::reserved_balance(&1), Deposit::get());
 
 		// Controller then changes mind and deregisters.
@@ -152,11 +326,102 @@ fn deregister_works() {
 	});
 }
 
+#[test]
+fn queued_stash_cannot_use_staking() {
+	ExtBuilder::default().build_and_execute(|| {
+		ErasToCheckPerBlock::<T>::put(1);
+		assert_ok!(FastUnstake::register_fast_unstake(
+			RuntimeOrigin::signed(2),
+			UnstakeDestination::Free
+		));
+
+		assert_noop!(Staking::bond_extra(RuntimeOrigin::signed(1), 10), Error::<T>::AlreadyQueued);
+		assert_noop!(Staking::unbond(RuntimeOrigin::signed(2), 10), Error::<T>::AlreadyQueued);
+		assert_noop!(
+			Staking::nominate(RuntimeOrigin::signed(2), vec![3]),
+			Error::<T>::AlreadyQueued
+		);
+		assert_noop!(
+			Staking::validate(RuntimeOrigin::signed(2), Default::default()),
+			Error::<T>::AlreadyQueued
+		);
+
+		// once deregistered, the stash can bond again.
+		assert_ok!(FastUnstake::deregister(RuntimeOrigin::signed(2)));
+		assert_ok!(Staking::bond_extra(RuntimeOrigin::signed(1), 10));
+	});
+}
+
+#[test]
+fn head_stash_cannot_use_staking() {
+	ExtBuilder::default().build_and_execute(|| {
+		ErasToCheckPerBlock::<T>::put(1);
+		CurrentEra::<T>::put(BondingDuration::get());
+		assert_ok!(FastUnstake::register_fast_unstake(
+			RuntimeOrigin::signed(2),
+			UnstakeDestination::Free
+		));
+		next_block(true);
+		assert!(FastUnstake::is_head(&1));
+
+		assert_noop!(Staking::bond_extra(RuntimeOrigin::signed(1), 10), Error::<T>::AlreadyHead);
+		assert_noop!(
+			Staking::validate(RuntimeOrigin::signed(2), Default::default()),
+			Error::<T>::AlreadyHead
+		);
+	});
+}
+
+#[test]
+fn staking_filter_cannot_be_bypassed_with_batch() {
+	ExtBuilder::default().build_and_execute(|| {
+		ErasToCheckPerBlock::<T>::put(1);
+		assert_ok!(FastUnstake::register_fast_unstake(
+			RuntimeOrigin::signed(2),
+			UnstakeDestination::Free
+		));
+
+		let nominate = RuntimeCall::Staking(pallet_staking::Call::nominate { targets: vec![3] });
+		assert_ok!(Utility::batch(RuntimeOrigin::signed(2), vec![nominate]));
+		System::assert_last_event(
+			pallet_utility::Event::BatchInterrupted {
+				index: 0,
+				error: Error::<T>::AlreadyQueued.into(),
+			}
+			.into(),
+		);
+		assert_eq!(pallet_staking::Nominators::<T>::get(1), None);
+	});
+}
+
+#[test]
+fn staking_filter_cannot_be_bypassed_with_proxy() {
+	ExtBuilder::default().build_and_execute(|| {
+		ErasToCheckPerBlock::<T>::put(1);
+		assert_ok!(FastUnstake::register_fast_unstake(
+			RuntimeOrigin::signed(2),
+			UnstakeDestination::Free
+		));
+		assert_ok!(Proxy::add_proxy(RuntimeOrigin::signed(2), 4, (), 0));
+
+		let unbond = RuntimeCall::Staking(pallet_staking::Call::unbond { value: 10 });
+		assert_ok!(Proxy::proxy(RuntimeOrigin::signed(4), 2, None, Box::new(unbond)));
+		System::assert_last_event(
+			pallet_proxy::Event::ProxyExecuted { result: Err(Error::<T>::AlreadyQueued.into()) }
+				.into(),
+		);
+		assert_eq!(Staking::ledger(2).unwrap().unlocking.len(), 1);
+	});
+}
+
 #[test]
 fn deregister_disabled_fails() {
 	ExtBuilder::default().build_and_execute(|| {
 		ErasToCheckPerBlock::<T>::put(1);
-		assert_ok!(FastUnstake::register_fast_unstake(RuntimeOrigin::signed(2)));
+		assert_ok!(FastUnstake::register_fast_unstake(
+			RuntimeOrigin::signed(2),
+			UnstakeDestination::Free
+		));
 		ErasToCheckPerBlock::<T>::put(0);
 		assert_noop!(FastUnstake::deregister(RuntimeOrigin::signed(2)), Error::<T>::CallNotAllowed);
 	});
@@ -167,7 +432,10 @@ fn cannot_deregister_if_not_controller() {
 	ExtBuilder::default().build_and_execute(|| {
 		ErasToCheckPerBlock::<T>::put(1);
 		// Controller account registers for fast unstake.
-		assert_ok!(FastUnstake::register_fast_unstake(RuntimeOrigin::signed(2)));
+		assert_ok!(FastUnstake::register_fast_unstake(
+			RuntimeOrigin::signed(2),
+			UnstakeDestination::Free
+		));
 		// Stash tries to deregister.
 		assert_noop!(FastUnstake::deregister(RuntimeOrigin::signed(1)), Error::<T>::NotController);
 	});
@@ -183,26 +451,153 @@ fn cannot_deregister_if_not_queued() {
 }
 
 #[test]
-fn cannot_deregister_already_head() {
+fn cannot_deregister_already_head_once_checking_began() {
 	ExtBuilder::default().build_and_execute(|| {
 		ErasToCheckPerBlock::<T>::put(1);
-		// Controller attempts to register, should fail
-		assert_ok!(FastUnstake::register_fast_unstake(RuntimeOrigin::signed(2)));
-		// Insert some Head item for stash.
+		assert_ok!(FastUnstake::register_fast_unstake(
+			RuntimeOrigin::signed(2),
+			UnstakeDestination::Free
+		));
+		// Move the stash to the head, with an era checked already.
+		Queue::<T>::remove(1);
 		Head::<T>::put(UnstakeRequest {
-			stashes: bounded_vec![(1, Deposit::get())],
-			checked: bounded_vec![],
+			stashes: bounded_vec![in_head(1, UnstakeDestination::Free, 0, 107)],
+			checked: bounded_vec![3],
+			since: 1,
 		});
 		// Controller attempts to deregister
 		assert_noop!(FastUnstake::deregister(RuntimeOrigin::signed(2)), Error::<T>::AlreadyHead);
 	});
 }
 
+#[test]
+fn can_deregister_already_head_before_checking_began() {
+	ExtBuilder::default().batch(2).build_and_execute(|| {
+		ErasToCheckPerBlock::<T>::put(1);
+		assert_ok!(FastUnstake::register_fast_unstake(
+			RuntimeOrigin::signed(2),
+			UnstakeDestination::Free
+		));
+		assert_ok!(FastUnstake::register_fast_unstake(
+			RuntimeOrigin::signed(4),
+			UnstakeDestination::Free
+		));
+		assert_eq!(<T as Config>::Currency::reserved_balance(&1), Deposit::get());
+
+		// Both stashes are promoted to the head, without any era checked yet.
+		Queue::<T>::remove(1);
+		Queue::<T>::remove(3);
+		Head::<T>::put(UnstakeRequest {
+			stashes: bounded_vec![
+				in_head(1, UnstakeDestination::Free, 0, 107),
+				in_head(3, UnstakeDestination::Free, 1, 107)
+			],
+			checked: bounded_vec![],
+			since: 1,
+		});
+
+		// The first one leaves the head and gets its deposit back.
+		assert_ok!(FastUnstake::deregister(RuntimeOrigin::signed(2)));
+		assert_eq!(<T as Config>::Currency::reserved_balance(&1), 0);
+		assert_eq!(
+			Head::<T>::get().unwrap().stashes.into_inner(),
+			vec![in_head(3, UnstakeDestination::Free, 1, 107)]
+		);
+
+		// The head is cleared once the last one leaves.
+		assert_ok!(FastUnstake::deregister(RuntimeOrigin::signed(4)));
+		assert_eq!(<T as Config>::Currency::reserved_balance(&3), 0);
+		assert_eq!(Head::<T>::get(), None);
+
+		// Neither is queued anymore.
+		assert_noop!(FastUnstake::deregister(RuntimeOrigin::signed(2)), Error::<T>::NotQueued);
+	});
+}
+
 #[test]
 fn control_works() {
 	ExtBuilder::default().build_and_execute(|| {
 		// account with control (root) origin wants to only check 1 era per block.
-		assert_ok!(FastUnstake::control(RuntimeOrigin::root(), 1_u32));
+		assert_ok!(FastUnstake::set_eras_to_check(RuntimeOrigin::root(), 1_u32));
+		assert_eq!(ErasToCheckPerBlock::<T>::get(), 1);
+		assert_eq!(
+			fast_unstake_events_since_last_call(),
+			vec![Event::ErasToCheckSet { eras_to_check: 1 }]
+		);
+
+		// the deprecated alias forwards to the new call.
+		assert_ok!(FastUnstake::control(RuntimeOrigin::root(), 2_u32));
+		assert_eq!(ErasToCheckPerBlock::<T>::get(), 2);
+		assert_eq!(
+			fast_unstake_events_since_last_call(),
+			vec![Event::ErasToCheckSet { eras_to_check: 2 }]
+		);
+	});
+}
+
+#[test]
+fn control_is_bounded_by_max_eras_to_check() {
+	ExtBuilder::default().build_and_execute(|| {
+		let max = MaxErasToCheckPerBlock::get();
+		assert_ok!(FastUnstake::set_eras_to_check(RuntimeOrigin::root(), max));
+		assert_eq!(FastUnstake::eras_to_check_per_block(), max);
+
+		assert_noop!(
+			FastUnstake::set_eras_to_check(RuntimeOrigin::root(), max + 1),
+			Error::<T>::TooManyEras
+		);
+		assert_noop!(FastUnstake::control(RuntimeOrigin::root(), max + 1), Error::<T>::TooManyEras);
+	});
+}
+
+#[test]
+fn constants_are_exposed_in_metadata() {
+	let constants = FastUnstake::pallet_constants_metadata();
+	let constant = |name: &str| {
+		constants
+			.iter()
+			.find(|constant| constant.name == name)
+			.unwrap_or_else(|| panic!("constant {} is missing from the metadata", name))
+			.clone()
+	};
+
+	let deposit = constant("Deposit");
+	assert_eq!(deposit.ty, scale_info::meta_type::<Balance>());
+	assert_eq!(Balance::decode(&mut &deposit.value[..]).unwrap(), Deposit::get());
+
+	let batch_size = constant("BatchSize");
+	assert_eq!(batch_size.ty, scale_info::meta_type::<u32>());
+	assert_eq!(u32::decode(&mut &batch_size.value[..]).unwrap(), BatchSize::get());
+
+	let max_eras = constant("MaxErasToCheckPerBlock");
+	assert_eq!(max_eras.ty, scale_info::meta_type::<u32>());
+	assert_eq!(u32::decode(&mut &max_eras.value[..]).unwrap(), MaxErasToCheckPerBlock::get());
+}
+
+#[test]
+fn denylist_is_managed_by_control_origin() {
+	ExtBuilder::default().build_and_execute(|| {
+		assert_noop!(FastUnstake::add_to_denylist(RuntimeOrigin::signed(1), 1), BadOrigin);
+
+		assert_ok!(FastUnstake::add_to_denylist(RuntimeOrigin::root(), 1));
+		assert!(Denylist::<T>::contains_key(1));
+		assert_noop!(
+			FastUnstake::add_to_denylist(RuntimeOrigin::root(), 1),
+			Error::<T>::AlreadyDenylisted
+		);
+
+		assert_noop!(FastUnstake::remove_from_denylist(RuntimeOrigin::signed(1), 1), BadOrigin);
+		assert_ok!(FastUnstake::remove_from_denylist(RuntimeOrigin::root(), 1));
+		assert!(!Denylist::<T>::contains_key(1));
+		assert_noop!(
+			FastUnstake::remove_from_denylist(RuntimeOrigin::root(), 1),
+			Error::<T>::NotDenylisted
+		);
+
+		assert_eq!(
+			fast_unstake_events_since_last_call(),
+			vec![Event::Denylisted { stash: 1 }, Event::Undenylisted { stash: 1 }]
+		);
 	});
 }
 
@@ -210,6 +605,7 @@ fn control_works() {
 fn control_must_be_control_origin() {
 	ExtBuilder::default().build_and_execute(|| {
 		// account without control (root) origin wants to only check 1 era per block.
+		assert_noop!(FastUnstake::set_eras_to_check(RuntimeOrigin::signed(1), 1_u32), BadOrigin);
 		assert_noop!(FastUnstake::control(RuntimeOrigin::signed(1), 1_u32), BadOrigin);
 	});
 }
@@ -224,15 +620,18 @@ mod on_idle {
 			CurrentEra::<T>::put(BondingDuration::get());
 
 			// set up Queue item
-			assert_ok!(FastUnstake::register_fast_unstake(RuntimeOrigin::signed(2)));
-			assert_eq!(Queue::<T>::get(1), Some(Deposit::get()));
+			assert_ok!(FastUnstake::register_fast_unstake(
+				RuntimeOrigin::signed(2),
+				UnstakeDestination::Free
+			));
+			assert_eq!(Queue::<T>::get(1), Some(queued(UnstakeDestination::Free, 1, 0)));
 
 			// call on_idle with no remaining weight
 			FastUnstake::on_idle(System::block_number(), Weight::from_ref_time(0));
 
 			// assert nothing changed in Queue and Head
 			assert_eq!(Head::<T>::get(), None);
-			assert_eq!(Queue::<T>::get(1), Some(Deposit::get()));
+			assert_eq!(Queue::<T>::get(1), Some(queued(UnstakeDestination::Free, 1, 0)));
 		});
 	}
 
@@ -244,29 +643,33 @@ mod on_idle {
 			CurrentEra::<T>::put(BondingDuration::get());
 
 			// given
-			assert_ok!(FastUnstake::register_fast_unstake(RuntimeOrigin::signed(2)));
-			assert_eq!(Queue::<T>::get(1), Some(Deposit::get()));
+			assert_ok!(FastUnstake::register_fast_unstake(
+				RuntimeOrigin::signed(2),
+				UnstakeDestination::Free
+			));
+			assert_eq!(Queue::<T>::get(1), Some(queued(UnstakeDestination::Free, 1, 0)));
 
 			assert_eq!(Queue::<T>::count(), 1);
 			assert_eq!(Head::<T>::get(), None);
 
-			// when: call fast unstake with not enough weight to process the whole thing, just one
-			// era.
+			// when: call fast unstake with not enough weight to process the whole thing, just the
+			// promotion from the queue and one era.
 			let remaining_weight = <T as Config>::WeightInfo::on_idle_check(
 				pallet_staking::ValidatorCount::<T>::get() * 1,
-			);
+			) + <T as Config>::WeightInfo::queue_promotion();
 			assert_eq!(FastUnstake::on_idle(0, remaining_weight), remaining_weight);
 
 			// then
 			assert_eq!(
 				fast_unstake_events_since_last_call(),
-				vec![Event::BatchChecked { eras: vec![3] }]
+				vec![Event::ErasChecked { first_era: 3, last_era: 3, count: 1, ids: vec![0] }]
 			);
 			assert_eq!(
 				Head::<T>::get(),
 				Some(UnstakeRequest {
-					stashes: bounded_vec![(1, Deposit::get())],
-					checked: bounded_vec![3]
+					stashes: bounded_vec![in_head(1, UnstakeDestination::Free, 0, 107)],
+					checked: bounded_vec![3],
+					since: 1,
 				})
 			);
 
@@ -279,13 +682,14 @@ mod on_idle {
 			// then:
 			assert_eq!(
 				fast_unstake_events_since_last_call(),
-				vec![Event::BatchChecked { eras: bounded_vec![2] }]
+				vec![Event::ErasChecked { first_era: 2, last_era: 2, count: 1, ids: vec![0] }]
 			);
 			assert_eq!(
 				Head::<T>::get(),
 				Some(UnstakeRequest {
-					stashes: bounded_vec![(1, Deposit::get())],
-					checked: bounded_vec![3, 2]
+					stashes: bounded_vec![in_head(1, UnstakeDestination::Free, 0, 107)],
+					checked: bounded_vec![3, 2],
+					since: 1,
 				})
 			);
 
@@ -304,13 +708,14 @@ mod on_idle {
 			// then:
 			assert_eq!(
 				fast_unstake_events_since_last_call(),
-				vec![Event::BatchChecked { eras: vec![1, 0] }]
+				vec![Event::ErasChecked { first_era: 1, last_era: 0, count: 2, ids: vec![0] }]
 			);
 			assert_eq!(
 				Head::<T>::get(),
 				Some(UnstakeRequest {
-					stashes: bounded_vec![(1, Deposit::get())],
-					checked: bounded_vec![3, 2, 1, 0]
+					stashes: bounded_vec![in_head(1, UnstakeDestination::Free, 0, 107)],
+					checked: bounded_vec![3, 2, 1, 0],
+					since: 1,
 				})
 			);
 
@@ -324,8 +729,9 @@ mod on_idle {
 			assert_eq!(
 				Head::<T>::get(),
 				Some(UnstakeRequest {
-					stashes: bounded_vec![(1, Deposit::get())],
-					checked: bounded_vec![3, 2, 1, 0]
+					stashes: bounded_vec![in_head(1, UnstakeDestination::Free, 0, 107)],
+					checked: bounded_vec![3, 2, 1, 0],
+					since: 1,
 				})
 			);
 
@@ -342,7 +748,16 @@ mod on_idle {
 			// then we finish the unbonding:
 			assert_eq!(
 				fast_unstake_events_since_last_call(),
-				vec![Event::Unstaked { stash: 1, result: Ok(()) }, Event::BatchFinished],
+				vec![
+					Event::Unstaked {
+						stash: 1,
+						id: 0,
+						result: UnstakeOutcome::Success,
+						destination: UnstakeDestination::Free,
+						payee: Some(1)
+					},
+					Event::BatchFinished
+				],
 			);
 			assert_eq!(Head::<T>::get(), None,);
 
@@ -351,200 +766,227 @@ mod on_idle {
 	}
 
 	#[test]
-	fn if_head_not_set_one_random_fetched_from_queue() {
+	fn promotes_head_with_only_promotion_weight() {
 		ExtBuilder::default().build_and_execute(|| {
 			ErasToCheckPerBlock::<T>::put(BondingDuration::get() + 1);
 			CurrentEra::<T>::put(BondingDuration::get());
 
 			// given
-			assert_eq!(<T as Config>::Currency::reserved_balance(&1), 0);
-
-			assert_ok!(FastUnstake::register_fast_unstake(RuntimeOrigin::signed(2)));
-			assert_ok!(FastUnstake::register_fast_unstake(RuntimeOrigin::signed(4)));
-			assert_ok!(FastUnstake::register_fast_unstake(RuntimeOrigin::signed(6)));
-			assert_ok!(FastUnstake::register_fast_unstake(RuntimeOrigin::signed(8)));
-			assert_ok!(FastUnstake::register_fast_unstake(RuntimeOrigin::signed(10)));
-
-			assert_eq!(<T as Config>::Currency::reserved_balance(&1), Deposit::get());
-
-			assert_eq!(Queue::<T>::count(), 5);
-			assert_eq!(Head::<T>::get(), None);
+			assert_ok!(FastUnstake::register_fast_unstake(
+				RuntimeOrigin::signed(2),
+				UnstakeDestination::Free
+			));
 
-			// when
-			next_block(true);
+			// when: there is only enough weight to promote the stash, but not to check any era.
+			let promotion_weight = <T as Config>::WeightInfo::queue_promotion();
+			assert_eq!(FastUnstake::on_idle(0, promotion_weight), promotion_weight);
 
-			// then
+			// then: it becomes the head without being checked.
+			assert_eq!(fast_unstake_events_since_last_call(), vec![]);
+			assert_eq!(Queue::<T>::count(), 0);
 			assert_eq!(
 				Head::<T>::get(),
 				Some(UnstakeRequest {
-					stashes: bounded_vec![(1, Deposit::get())],
-					checked: bounded_vec![3, 2, 1, 0]
+					stashes: bounded_vec![in_head(1, UnstakeDestination::Free, 0, 107)],
+					checked: bounded_vec![],
+					since: 1,
 				})
 			);
-			assert_eq!(Queue::<T>::count(), 4);
-
-			// when
-			next_block(true);
-
-			// then
-			assert_eq!(Head::<T>::get(), None,);
-			assert_eq!(Queue::<T>::count(), 4);
-
-			// when
-			next_block(true);
 
-			// then
-			assert_eq!(
-				Head::<T>::get(),
-				Some(UnstakeRequest {
-					stashes: bounded_vec![(5, Deposit::get())],
-					checked: bounded_vec![3, 2, 1, 0]
-				}),
+			// when: the next block can check one era, the promotion is not charged again.
+			let remaining_weight = <T as Config>::WeightInfo::on_idle_check(
+				pallet_staking::ValidatorCount::<T>::get() * 1,
 			);
-			assert_eq!(Queue::<T>::count(), 3);
-
-			assert_eq!(<T as Config>::Currency::reserved_balance(&1), 0);
+			assert_eq!(FastUnstake::on_idle(0, remaining_weight), remaining_weight);
 
+			// then
 			assert_eq!(
 				fast_unstake_events_since_last_call(),
-				vec![
-					Event::BatchChecked { eras: vec![3, 2, 1, 0] },
-					Event::Unstaked { stash: 1, result: Ok(()) },
-					Event::BatchFinished,
-					Event::BatchChecked { eras: vec![3, 2, 1, 0] }
-				]
+				vec![Event::ErasChecked { first_era: 3, last_era: 3, count: 1, ids: vec![0] }]
 			);
+			assert_eq!(Head::<T>::get().unwrap().checked, bounded_vec![3]);
 		});
 	}
 
 	#[test]
-	fn successful_multi_queue() {
+	fn pool_join_uses_its_own_unstake_weight() {
 		ExtBuilder::default().build_and_execute(|| {
 			ErasToCheckPerBlock::<T>::put(BondingDuration::get() + 1);
 			CurrentEra::<T>::put(BondingDuration::get());
 
-			// register multi accounts for fast unstake
-			assert_ok!(FastUnstake::register_fast_unstake(RuntimeOrigin::signed(2)));
-			assert_eq!(Queue::<T>::get(1), Some(Deposit::get()));
-			assert_ok!(FastUnstake::register_fast_unstake(RuntimeOrigin::signed(4)));
-			assert_eq!(Queue::<T>::get(3), Some(Deposit::get()));
-
-			// assert 2 queue items are in Queue & None in Head to start with
-			assert_eq!(Queue::<T>::count(), 2);
-			assert_eq!(Head::<T>::get(), None);
-
-			// process on idle and check eras for next Queue item
-			next_block(true);
-
-			// process on idle & let go of current Head
-			next_block(true);
-
-			// confirm Head / Queue items remaining
-			assert_eq!(Queue::<T>::count(), 1);
-			assert_eq!(Head::<T>::get(), None);
-
-			// process on idle and check eras for next Queue item
-			next_block(true);
+			// given
+			assert_ok!(FastUnstake::register_fast_unstake(
+				RuntimeOrigin::signed(2),
+				UnstakeDestination::Pool(3)
+			));
 
-			// process on idle & let go of current Head
+			// when: all eras are checked in one go.
 			next_block(true);
+			assert_eq!(
+				fast_unstake_events_since_last_call(),
+				vec![Event::ErasChecked { first_era: 3, last_era: 0, count: 4, ids: vec![0] }]
+			);
 
-			// Head & Queue should now be empty
-			assert_eq!(Head::<T>::get(), None);
-			assert_eq!(Queue::<T>::count(), 0);
+			// when: the head is unstaked.
+			let remaining_weight = <T as Config>::WeightInfo::on_idle_check(
+				pallet_staking::ValidatorCount::<T>::get() * 1,
+			);
+			assert_ne!(
+				<T as Config>::WeightInfo::on_idle_unstake_with_pool_join(),
+				<T as Config>::WeightInfo::on_idle_unstake()
+			);
+			assert_eq!(
+				FastUnstake::on_idle(0, remaining_weight),
+				<T as Config>::WeightInfo::on_idle_unstake_with_pool_join()
+			);
 
+			// then
 			assert_eq!(
 				fast_unstake_events_since_last_call(),
 				vec![
-					Event::BatchChecked { eras: vec![3, 2, 1, 0] },
-					Event::Unstaked { stash: 1, result: Ok(()) },
-					Event::BatchFinished,
-					Event::BatchChecked { eras: vec![3, 2, 1, 0] },
-					Event::Unstaked { stash: 3, result: Ok(()) },
-					Event::BatchFinished,
-				]
+					Event::Unstaked {
+						stash: 1,
+						id: 0,
+						result: UnstakeOutcome::Success,
+						destination: UnstakeDestination::Pool(3),
+						payee: Some(1)
+					},
+					Event::BatchFinished
+				],
 			);
-
-			assert_unstaked(&1);
-			assert_unstaked(&3);
+			assert_eq!(PoolJoins::get(), vec![(1, 107, 3)]);
 		});
 	}
 
 	#[test]
-	fn successful_unstake() {
+	fn mandatory_weight_progresses_without_idle_weight() {
 		ExtBuilder::default().build_and_execute(|| {
-			ErasToCheckPerBlock::<T>::put(BondingDuration::get() + 1);
+			ErasToCheckPerBlock::<T>::put(1);
 			CurrentEra::<T>::put(BondingDuration::get());
+			MandatoryWeightPerBlock::set(BlockWeights::get().max_block);
 
-			// register for fast unstake
-			assert_ok!(FastUnstake::register_fast_unstake(RuntimeOrigin::signed(2)));
-			assert_eq!(Queue::<T>::get(1), Some(Deposit::get()));
-
-			// process on idle
-			next_block(true);
-
-			// assert queue item has been moved to head
-			assert_eq!(Queue::<T>::get(1), None);
-
-			// assert head item present
-			assert_eq!(
-				Head::<T>::get(),
-				Some(UnstakeRequest {
-					stashes: bounded_vec![(1, Deposit::get())],
-					checked: bounded_vec![3, 2, 1, 0]
-				})
-			);
-
-			next_block(true);
-			assert_eq!(Head::<T>::get(), None,);
+			// given
+			assert_ok!(FastUnstake::register_fast_unstake(
+				RuntimeOrigin::signed(2),
+				UnstakeDestination::Free
+			));
 
+			// when: there is never any idle weight, one era is checked in every block.
+			for era in (0..=BondingDuration::get()).rev() {
+				next_block(false);
+				assert_eq!(
+					fast_unstake_events_since_last_call(),
+					vec![Event::ErasChecked {
+						first_era: era,
+						last_era: era,
+						count: 1,
+						ids: vec![0]
+					}]
+				);
+			}
+
+			// then the stash is unstaked in the next one.
+			next_block(false);
 			assert_eq!(
 				fast_unstake_events_since_last_call(),
 				vec![
-					Event::BatchChecked { eras: vec![3, 2, 1, 0] },
-					Event::Unstaked { stash: 1, result: Ok(()) },
+					Event::Unstaked {
+						stash: 1,
+						id: 0,
+						result: UnstakeOutcome::Success,
+						destination: UnstakeDestination::Free,
+						payee: Some(1)
+					},
 					Event::BatchFinished
 				]
 			);
+			assert_eq!(Head::<T>::get(), None);
 			assert_unstaked(&1);
 		});
 	}
 
 	#[test]
-	fn successful_unstake_all_eras_per_block() {
+	fn mandatory_weight_is_charged_in_full() {
 		ExtBuilder::default().build_and_execute(|| {
-			ErasToCheckPerBlock::<T>::put(BondingDuration::get() + 1);
+			ErasToCheckPerBlock::<T>::put(1);
 			CurrentEra::<T>::put(BondingDuration::get());
+			let budget = BlockWeights::get().max_block / 2;
+			MandatoryWeightPerBlock::set(budget);
 
-			Balances::make_free_balance_be(&2, 100);
-
-			// register for fast unstake
-			assert_ok!(FastUnstake::register_fast_unstake(RuntimeOrigin::signed(2)));
-			assert_eq!(Queue::<T>::get(1), Some(Deposit::get()));
+			// nothing to process.
+			assert_eq!(FastUnstake::on_initialize(System::block_number()), budget);
 
-			// process on idle
-			next_block(true);
+			// and a single era to check, which is well below the budget.
+			assert_ok!(FastUnstake::register_fast_unstake(
+				RuntimeOrigin::signed(2),
+				UnstakeDestination::Free
+			));
+			assert_eq!(FastUnstake::on_initialize(System::block_number()), budget);
+			assert_eq!(
+				fast_unstake_events_since_last_call(),
+				vec![Event::ErasChecked { first_era: 3, last_era: 3, count: 1, ids: vec![0] }]
+			);
 
-			// assert queue item has been moved to head
-			assert_eq!(Queue::<T>::get(1), None);
+			// a budget that cannot even afford reading the state is charged as well.
+			MandatoryWeightPerBlock::set(Weight::from_ref_time(1));
+			assert_eq!(
+				FastUnstake::on_initialize(System::block_number()),
+				Weight::from_ref_time(1)
+			);
+			assert_eq!(Head::<T>::get().unwrap().checked, bounded_vec![3]);
+		});
+	}
 
-			// assert head item present
+	#[test]
+	fn idle_weight_continues_after_mandatory_weight() {
+		ExtBuilder::default().build_and_execute(|| {
+			ErasToCheckPerBlock::<T>::put(BondingDuration::get() + 1);
+			CurrentEra::<T>::put(BondingDuration::get());
+			let era_weight = <T as Config>::WeightInfo::on_idle_check(
+				pallet_staking::ValidatorCount::<T>::get() * 1,
+			);
+			// only enough to promote the stash from the queue and check a single era in
+			// `on_initialize`.
+			MandatoryWeightPerBlock::set(era_weight + <T as Config>::WeightInfo::queue_promotion());
+
+			// given
+			assert_ok!(FastUnstake::register_fast_unstake(
+				RuntimeOrigin::signed(2),
+				UnstakeDestination::Free
+			));
+
+			// when: `on_initialize` checks the first era.
+			next_block(false);
 			assert_eq!(
-				Head::<T>::get(),
-				Some(UnstakeRequest {
-					stashes: bounded_vec![(1, Deposit::get())],
-					checked: bounded_vec![3, 2, 1, 0]
-				})
+				fast_unstake_events_since_last_call(),
+				vec![Event::ErasChecked { first_era: 3, last_era: 3, count: 1, ids: vec![0] }]
 			);
 
-			next_block(true);
-			assert_eq!(Head::<T>::get(), None,);
+			// and `on_idle` of the same block continues with the next ones.
+			let idle_weight = <T as Config>::WeightInfo::on_idle_check(
+				pallet_staking::ValidatorCount::<T>::get() * 2,
+			);
+			assert_eq!(FastUnstake::on_idle(System::block_number(), idle_weight), idle_weight);
+			assert_eq!(
+				fast_unstake_events_since_last_call(),
+				vec![Event::ErasChecked { first_era: 2, last_era: 1, count: 2, ids: vec![0] }]
+			);
+			assert_eq!(Head::<T>::get().unwrap().checked, bounded_vec![3, 2, 1]);
 
+			// then: with both budgets in one block, the last era is checked and the stash is
+			// unstaked.
+			next_block(true);
 			assert_eq!(
 				fast_unstake_events_since_last_call(),
 				vec![
-					Event::BatchChecked { eras: vec![3, 2, 1, 0] },
-					Event::Unstaked { stash: 1, result: Ok(()) },
+					Event::ErasChecked { first_era: 0, last_era: 0, count: 1, ids: vec![0] },
+					Event::Unstaked {
+						stash: 1,
+						id: 0,
+						result: UnstakeOutcome::Success,
+						destination: UnstakeDestination::Free,
+						payee: Some(1)
+					},
 					Event::BatchFinished
 				]
 			);
@@ -553,648 +995,2641 @@ mod on_idle {
 	}
 
 	#[test]
-	fn successful_unstake_one_era_per_block() {
+	fn if_head_not_set_one_random_fetched_from_queue() {
 		ExtBuilder::default().build_and_execute(|| {
-			// put 1 era per block
-			ErasToCheckPerBlock::<T>::put(1);
+			ErasToCheckPerBlock::<T>::put(BondingDuration::get() + 1);
 			CurrentEra::<T>::put(BondingDuration::get());
 
-			// register for fast unstake
-			assert_ok!(FastUnstake::register_fast_unstake(RuntimeOrigin::signed(2)));
-			assert_eq!(Queue::<T>::get(1), Some(Deposit::get()));
+			// given
+			assert_eq!(<T as Config>::Currency::reserved_balance(&1), 0);
 
-			// process on idle
-			next_block(true);
+			assert_ok!(FastUnstake::register_fast_unstake(
+				RuntimeOrigin::signed(2),
+				UnstakeDestination::Free
+			));
+			assert_ok!(FastUnstake::register_fast_unstake(
+				RuntimeOrigin::signed(4),
+				UnstakeDestination::Free
+			));
+			assert_ok!(FastUnstake::register_fast_unstake(
+				RuntimeOrigin::signed(6),
+				UnstakeDestination::Free
+			));
+			assert_ok!(FastUnstake::register_fast_unstake(
+				RuntimeOrigin::signed(8),
+				UnstakeDestination::Free
+			));
+			assert_ok!(FastUnstake::register_fast_unstake(
+				RuntimeOrigin::signed(10),
+				UnstakeDestination::Free
+			));
 
-			// assert queue item has been moved to head
-			assert_eq!(Queue::<T>::get(1), None);
+			assert_eq!(<T as Config>::Currency::reserved_balance(&1), Deposit::get());
 
-			// assert head item present
-			assert_eq!(
-				Head::<T>::get(),
-				Some(UnstakeRequest {
-					stashes: bounded_vec![(1, Deposit::get())],
-					checked: bounded_vec![3]
-				})
-			);
+			assert_eq!(Queue::<T>::count(), 5);
+			assert_eq!(Head::<T>::get(), None);
 
+			// when
 			next_block(true);
 
+			// then
 			assert_eq!(
 				Head::<T>::get(),
 				Some(UnstakeRequest {
-					stashes: bounded_vec![(1, Deposit::get())],
-					checked: bounded_vec![3, 2]
+					stashes: bounded_vec![in_head(1, UnstakeDestination::Free, 0, 107)],
+					checked: bounded_vec![3, 2, 1, 0],
+					since: 2,
 				})
 			);
+			assert_eq!(Queue::<T>::count(), 4);
 
+			// when
 			next_block(true);
 
-			assert_eq!(
-				Head::<T>::get(),
-				Some(UnstakeRequest {
-					stashes: bounded_vec![(1, Deposit::get())],
-					checked: bounded_vec![3, 2, 1]
-				})
-			);
+			// then
+			assert_eq!(Head::<T>::get(), None,);
+			assert_eq!(Queue::<T>::count(), 4);
 
+			// when
 			next_block(true);
 
+			// then
 			assert_eq!(
 				Head::<T>::get(),
 				Some(UnstakeRequest {
-					stashes: bounded_vec![(1, Deposit::get())],
-					checked: bounded_vec![3, 2, 1, 0]
-				})
+					stashes: bounded_vec![in_head(5, UnstakeDestination::Free, 2, 107)],
+					checked: bounded_vec![3, 2, 1, 0],
+					since: 4,
+				}),
 			);
+			assert_eq!(Queue::<T>::count(), 3);
 
-			next_block(true);
-
-			assert_eq!(Head::<T>::get(), None,);
+			assert_eq!(<T as Config>::Currency::reserved_balance(&1), 0);
 
 			assert_eq!(
 				fast_unstake_events_since_last_call(),
 				vec![
-					Event::BatchChecked { eras: vec![3] },
-					Event::BatchChecked { eras: vec![2] },
-					Event::BatchChecked { eras: vec![1] },
-					Event::BatchChecked { eras: vec![0] },
-					Event::Unstaked { stash: 1, result: Ok(()) },
-					Event::BatchFinished
+					Event::ErasChecked { first_era: 3, last_era: 0, count: 4, ids: vec![0] },
+					Event::Unstaked {
+						stash: 1,
+						id: 0,
+						result: UnstakeOutcome::Success,
+						destination: UnstakeDestination::Free,
+						payee: Some(1)
+					},
+					Event::BatchFinished,
+					Event::ErasChecked { first_era: 3, last_era: 0, count: 4, ids: vec![2] }
 				]
 			);
-			assert_unstaked(&1);
 		});
 	}
 
 	#[test]
-	fn old_checked_era_pruned() {
-		// the only scenario where checked era pruning (checked.retain) comes handy is a follows:
-		// the whole vector is full and at capacity and in the next call we are ready to unstake,
-		// but then a new era happens.
+	fn checked_event_size_does_not_grow_with_eras() {
+		let checked_event_size = |eras_per_block: u32| {
+			ExtBuilder::default().build().execute_with(|| {
+				ErasToCheckPerBlock::<T>::put(eras_per_block);
+				CurrentEra::<T>::put(BondingDuration::get());
+				assert_ok!(FastUnstake::register_fast_unstake(
+					RuntimeOrigin::signed(2),
+					UnstakeDestination::Free
+				));
+
+				next_block(true);
+
+				// not using `fast_unstake_events_since_last_call` as it is not reset between
+				// externalities.
+				let events = System::events()
+					.into_iter()
+					.filter_map(|r| match r.event {
+						RuntimeEvent::FastUnstake(e) => Some(e),
+						_ => None,
+					})
+					.collect::<Vec<_>>();
+				assert!(matches!(
+					events.as_slice(),
+					[Event::ErasChecked { count, .. }] if *count == eras_per_block
+				));
+				events[0].encode().len()
+			})
+		};
+
+		assert_eq!(checked_event_size(1), checked_event_size(BondingDuration::get()));
+	}
+
+	#[test]
+	fn successful_multi_queue() {
 		ExtBuilder::default().build_and_execute(|| {
-			// given
-			ErasToCheckPerBlock::<T>::put(1);
+			ErasToCheckPerBlock::<T>::put(BondingDuration::get() + 1);
 			CurrentEra::<T>::put(BondingDuration::get());
 
-			// register for fast unstake
-			assert_ok!(FastUnstake::register_fast_unstake(RuntimeOrigin::signed(2)));
-			assert_eq!(Queue::<T>::get(1), Some(Deposit::get()));
-
-			next_block(true);
-			assert_eq!(
-				Head::<T>::get(),
-				Some(UnstakeRequest {
-					stashes: bounded_vec![(1, Deposit::get())],
-					checked: bounded_vec![3]
-				})
-			);
+			// register multi accounts for fast unstake
+			assert_ok!(FastUnstake::register_fast_unstake(
+				RuntimeOrigin::signed(2),
+				UnstakeDestination::Free
+			));
+			assert_eq!(Queue::<T>::get(1), Some(queued(UnstakeDestination::Free, 1, 0)));
+			assert_ok!(FastUnstake::register_fast_unstake(
+				RuntimeOrigin::signed(4),
+				UnstakeDestination::Free
+			));
+			assert_eq!(Queue::<T>::get(3), Some(queued(UnstakeDestination::Free, 1, 1)));
 
-			next_block(true);
-			assert_eq!(
-				Head::<T>::get(),
-				Some(UnstakeRequest {
-					stashes: bounded_vec![(1, Deposit::get())],
-					checked: bounded_vec![3, 2]
-				})
-			);
+			// assert 2 queue items are in Queue & None in Head to start with
+			assert_eq!(Queue::<T>::count(), 2);
+			assert_eq!(Head::<T>::get(), None);
 
+			// process on idle and check eras for next Queue item
 			next_block(true);
-			assert_eq!(
-				Head::<T>::get(),
-				Some(UnstakeRequest {
-					stashes: bounded_vec![(1, Deposit::get())],
-					checked: bounded_vec![3, 2, 1]
-				})
-			);
 
+			// process on idle & let go of current Head
 			next_block(true);
-			assert_eq!(
-				Head::<T>::get(),
-				Some(UnstakeRequest {
-					stashes: bounded_vec![(1, Deposit::get())],
-					checked: bounded_vec![3, 2, 1, 0]
-				})
-			);
 
-			// when: a new era happens right before one is free.
-			CurrentEra::<T>::put(CurrentEra::<T>::get().unwrap() + 1);
-			ExtBuilder::register_stakers_for_era(CurrentEra::<T>::get().unwrap());
+			// confirm Head / Queue items remaining
+			assert_eq!(Queue::<T>::count(), 1);
+			assert_eq!(Head::<T>::get(), None);
 
-			// then
+			// process on idle and check eras for next Queue item
 			next_block(true);
-			assert_eq!(
-				Head::<T>::get(),
-				Some(UnstakeRequest {
-					stashes: bounded_vec![(1, Deposit::get())],
-					// note era 0 is pruned to keep the vector length sane.
-					checked: bounded_vec![3, 2, 1, 4],
-				})
-			);
 
+			// process on idle & let go of current Head
 			next_block(true);
+
+			// Head & Queue should now be empty
 			assert_eq!(Head::<T>::get(), None);
+			assert_eq!(Queue::<T>::count(), 0);
 
 			assert_eq!(
 				fast_unstake_events_since_last_call(),
 				vec![
-					Event::BatchChecked { eras: vec![3] },
-					Event::BatchChecked { eras: vec![2] },
-					Event::BatchChecked { eras: vec![1] },
-					Event::BatchChecked { eras: vec![0] },
-					Event::BatchChecked { eras: vec![4] },
-					Event::Unstaked { stash: 1, result: Ok(()) },
-					Event::BatchFinished
+					Event::ErasChecked { first_era: 3, last_era: 0, count: 4, ids: vec![0] },
+					Event::Unstaked {
+						stash: 1,
+						id: 0,
+						result: UnstakeOutcome::Success,
+						destination: UnstakeDestination::Free,
+						payee: Some(1)
+					},
+					Event::BatchFinished,
+					Event::ErasChecked { first_era: 3, last_era: 0, count: 4, ids: vec![1] },
+					Event::Unstaked {
+						stash: 3,
+						id: 1,
+						result: UnstakeOutcome::Success,
+						destination: UnstakeDestination::Free,
+						payee: Some(3)
+					},
+					Event::BatchFinished,
 				]
 			);
+
 			assert_unstaked(&1);
+			assert_unstaked(&3);
 		});
 	}
 
 	#[test]
-	fn unstake_paused_mid_election() {
+	fn successful_unstake() {
 		ExtBuilder::default().build_and_execute(|| {
-			// give: put 1 era per block
-			ErasToCheckPerBlock::<T>::put(1);
+			ErasToCheckPerBlock::<T>::put(BondingDuration::get() + 1);
 			CurrentEra::<T>::put(BondingDuration::get());
 
 			// register for fast unstake
-			assert_ok!(FastUnstake::register_fast_unstake(RuntimeOrigin::signed(2)));
-
-			// process 2 blocks
-			next_block(true);
-			assert_eq!(
-				Head::<T>::get(),
-				Some(UnstakeRequest {
-					stashes: bounded_vec![(1, Deposit::get())],
-					checked: bounded_vec![3]
-				})
-			);
-
-			next_block(true);
-			assert_eq!(
-				Head::<T>::get(),
-				Some(UnstakeRequest {
-					stashes: bounded_vec![(1, Deposit::get())],
-					checked: bounded_vec![3, 2]
-				})
-			);
-
-			// when
-			Ongoing::set(true);
-
-			// then nothing changes
-			next_block(true);
-			assert_eq!(
-				Head::<T>::get(),
-				Some(UnstakeRequest {
-					stashes: bounded_vec![(1, Deposit::get())],
-					checked: bounded_vec![3, 2]
-				})
-			);
+			assert_ok!(FastUnstake::register_fast_unstake(
+				RuntimeOrigin::signed(2),
+				UnstakeDestination::Free
+			));
+			assert_eq!(Queue::<T>::get(1), Some(queued(UnstakeDestination::Free, 1, 0)));
 
+			// process on idle
 			next_block(true);
-			assert_eq!(
-				Head::<T>::get(),
-				Some(UnstakeRequest {
-					stashes: bounded_vec![(1, Deposit::get())],
-					checked: bounded_vec![3, 2]
-				})
-			);
 
-			// then we register a new era.
-			Ongoing::set(false);
-			CurrentEra::<T>::put(CurrentEra::<T>::get().unwrap() + 1);
-			ExtBuilder::register_stakers_for_era(CurrentEra::<T>::get().unwrap());
+			// assert queue item has been moved to head
+			assert_eq!(Queue::<T>::get(1), None);
 
-			// then we can progress again, but notice that the new era that had to be checked.
-			next_block(true);
+			// assert head item present
 			assert_eq!(
 				Head::<T>::get(),
 				Some(UnstakeRequest {
-					stashes: bounded_vec![(1, Deposit::get())],
-					checked: bounded_vec![3, 2, 4]
+					stashes: bounded_vec![in_head(1, UnstakeDestination::Free, 0, 107)],
+					checked: bounded_vec![3, 2, 1, 0],
+					since: 2,
 				})
 			);
 
-			// progress to end
-			next_block(true);
-			assert_eq!(
-				Head::<T>::get(),
-				Some(UnstakeRequest {
-					stashes: bounded_vec![(1, Deposit::get())],
-					checked: bounded_vec![3, 2, 4, 1]
-				})
-			);
+			assert!(Unstaked::get().is_empty());
 
-			// but notice that we don't care about era 0 instead anymore! we're done.
 			next_block(true);
-			assert_eq!(Head::<T>::get(), None);
+			assert_eq!(Head::<T>::get(), None,);
 
 			assert_eq!(
 				fast_unstake_events_since_last_call(),
 				vec![
-					Event::BatchChecked { eras: vec![3] },
-					Event::BatchChecked { eras: vec![2] },
-					Event::BatchChecked { eras: vec![4] },
-					Event::BatchChecked { eras: vec![1] },
-					Event::Unstaked { stash: 1, result: Ok(()) },
+					Event::ErasChecked { first_era: 3, last_era: 0, count: 4, ids: vec![0] },
+					Event::Unstaked {
+						stash: 1,
+						id: 0,
+						result: UnstakeOutcome::Success,
+						destination: UnstakeDestination::Free,
+						payee: Some(1)
+					},
 					Event::BatchFinished
 				]
 			);
-
 			assert_unstaked(&1);
+			assert_eq!(Unstaked::get(), vec![(1, 7 + 100)]);
 		});
 	}
 
 	#[test]
-	fn exposed_nominator_cannot_unstake() {
+	fn bonding_extra_while_queued_aborts_unstake() {
 		ExtBuilder::default().build_and_execute(|| {
-			ErasToCheckPerBlock::<T>::put(1);
+			ErasToCheckPerBlock::<T>::put(BondingDuration::get() + 1);
 			CurrentEra::<T>::put(BondingDuration::get());
 
-			// create an exposed nominator in era 1
-			let exposed = 666;
-			create_exposed_nominator(exposed, 1);
+			assert_ok!(FastUnstake::register_fast_unstake(
+				RuntimeOrigin::signed(2),
+				UnstakeDestination::Free
+			));
+
+			// the stash bonds extra funds, bypassing the checks of this pallet.
+			pallet_staking::Ledger::<T>::mutate(2, |maybe_ledger| {
+				let ledger = maybe_ledger.as_mut().unwrap();
+				ledger.total += 10;
+				ledger.unlocking.iter_mut().for_each(|chunk| chunk.value += 10);
+			});
 
-			// a few blocks later, we realize they are slashed
-			next_block(true);
-			assert_eq!(
-				Head::<T>::get(),
-				Some(UnstakeRequest {
-					stashes: bounded_vec![(exposed, Deposit::get())],
-					checked: bounded_vec![3]
-				})
-			);
 			next_block(true);
-			assert_eq!(
-				Head::<T>::get(),
-				Some(UnstakeRequest {
-					stashes: bounded_vec![(exposed, Deposit::get())],
-					checked: bounded_vec![3, 2]
-				})
-			);
 			next_block(true);
 			assert_eq!(Head::<T>::get(), None);
 
+			// the request is aborted, rather than unstaking the larger amount.
 			assert_eq!(
 				fast_unstake_events_since_last_call(),
 				vec![
-					Event::BatchChecked { eras: vec![3] },
-					Event::BatchChecked { eras: vec![2] },
-					Event::Slashed { stash: exposed, amount: Deposit::get() },
+					Event::ErasChecked { first_era: 3, last_era: 0, count: 4, ids: vec![0] },
+					Event::Aborted { stash: 1, id: 0 },
 					Event::BatchFinished
 				]
 			);
+			assert_eq!(Staking::total_stake(&1), Ok(7 + 100 + 10));
+			assert_eq!(<T as Config>::Currency::reserved_balance(&1), 0);
+			assert!(Unstaked::get().is_empty());
 		});
 	}
 
 	#[test]
-	fn exposed_nominator_cannot_unstake_multi_check() {
+	fn staked_rewards_while_queued_abort_unstake() {
 		ExtBuilder::default().build_and_execute(|| {
-			// same as the previous check, but we check 2 eras per block, and we make the exposed be
-			// exposed in era 0, so that it is detected halfway in a check era.
-			ErasToCheckPerBlock::<T>::put(2);
+			ErasToCheckPerBlock::<T>::put(BondingDuration::get() + 1);
 			CurrentEra::<T>::put(BondingDuration::get());
 
-			// create an exposed nominator in era 0
-			let exposed = 666;
-			create_exposed_nominator(exposed, 0);
+			assert_ok!(FastUnstake::register_fast_unstake(
+				RuntimeOrigin::signed(2),
+				UnstakeDestination::Free
+			));
+
+			// a reward for an older era is paid out to the stake of the stash.
+			pallet_staking::Ledger::<T>::mutate(2, |maybe_ledger| {
+				let ledger = maybe_ledger.as_mut().unwrap();
+				ledger.total += 10;
+				ledger.active += 10;
+			});
 
-			// a few blocks later, we realize they are slashed
 			next_block(true);
-			assert_eq!(
-				Head::<T>::get(),
-				Some(UnstakeRequest {
-					stashes: bounded_vec![(exposed, Deposit::get())],
-					checked: bounded_vec![3, 2]
-				})
-			);
 			next_block(true);
 			assert_eq!(Head::<T>::get(), None);
 
+			// the active stake differs from the one upon registration, the request is aborted.
 			assert_eq!(
 				fast_unstake_events_since_last_call(),
-				// we slash them
 				vec![
-					Event::BatchChecked { eras: vec![3, 2] },
-					Event::Slashed { stash: exposed, amount: Deposit::get() },
+					Event::ErasChecked { first_era: 3, last_era: 0, count: 4, ids: vec![0] },
+					Event::Aborted { stash: 1, id: 0 },
 					Event::BatchFinished
 				]
 			);
+			assert_eq!(Staking::active_stake(&1), Ok(10));
+			assert_eq!(<T as Config>::Currency::reserved_balance(&1), 0);
+			assert!(Unstaked::get().is_empty());
 		});
 	}
 
 	#[test]
-	fn validators_cannot_bail() {
+	fn rebonding_while_queued_aborts_unstake() {
 		ExtBuilder::default().build_and_execute(|| {
 			ErasToCheckPerBlock::<T>::put(BondingDuration::get() + 1);
 			CurrentEra::<T>::put(BondingDuration::get());
 
-			// a validator switches role and register...
-			assert_ok!(Staking::nominate(
-				RuntimeOrigin::signed(VALIDATOR_PREFIX),
-				vec![VALIDATOR_PREFIX]
+			assert_ok!(FastUnstake::register_fast_unstake(
+				RuntimeOrigin::signed(2),
+				UnstakeDestination::Free
 			));
-			assert_ok!(FastUnstake::register_fast_unstake(RuntimeOrigin::signed(VALIDATOR_PREFIX)));
 
-			// but they indeed are exposed!
-			assert!(pallet_staking::ErasStakers::<T>::contains_key(
-				BondingDuration::get() - 1,
-				VALIDATOR_PREFIX
-			));
+			// the stash changes its mind and rebonds instead of deregistering.
+			assert_ok!(Staking::rebond(RuntimeOrigin::signed(2), 50));
+			assert_eq!(Staking::active_stake(&1), Ok(50));
 
-			// process a block, this validator is exposed and has been slashed.
+			next_block(true);
 			next_block(true);
 			assert_eq!(Head::<T>::get(), None);
 
 			assert_eq!(
 				fast_unstake_events_since_last_call(),
-				vec![Event::Slashed { stash: 100, amount: Deposit::get() }, Event::BatchFinished]
+				vec![
+					Event::ErasChecked { first_era: 3, last_era: 0, count: 4, ids: vec![0] },
+					Event::Aborted { stash: 1, id: 0 },
+					Event::BatchFinished
+				]
 			);
+			assert_eq!(Staking::active_stake(&1), Ok(50));
+			assert_eq!(<T as Config>::Currency::reserved_balance(&1), 0);
+			assert!(Unstaked::get().is_empty());
 		});
 	}
 
 	#[test]
-	fn unexposed_validator_can_fast_unstake() {
+	fn rebonding_and_being_slashed_while_queued_aborts_unstake() {
 		ExtBuilder::default().build_and_execute(|| {
 			ErasToCheckPerBlock::<T>::put(BondingDuration::get() + 1);
 			CurrentEra::<T>::put(BondingDuration::get());
 
-			// create a new validator that 100% not exposed.
-			Balances::make_free_balance_be(&42, 100 + Deposit::get());
-			assert_ok!(Staking::bond(RuntimeOrigin::signed(42), 42, 10, RewardDestination::Staked));
-			assert_ok!(Staking::validate(RuntimeOrigin::signed(42), Default::default()));
+			assert_ok!(FastUnstake::register_fast_unstake(
+				RuntimeOrigin::signed(2),
+				UnstakeDestination::Free
+			));
 
-			// let them register:
-			assert_ok!(FastUnstake::register_fast_unstake(RuntimeOrigin::signed(42)));
+			// the stash rebonds, and a slash takes its entire active stake afterwards.
+			assert_ok!(Staking::rebond(RuntimeOrigin::signed(2), 50));
+			pallet_staking::Ledger::<T>::mutate(2, |maybe_ledger| {
+				let ledger = maybe_ledger.as_mut().unwrap();
+				ledger.total -= 50;
+				ledger.active = 0;
+			});
+			assert_eq!(Staking::active_stake(&1), Ok(0));
 
-			// 2 block's enough to unstake them.
 			next_block(true);
+			next_block(true);
+			assert_eq!(Head::<T>::get(), None);
+
+			// although nothing is active anymore, the stash has been staking in the meantime.
 			assert_eq!(
-				Head::<T>::get(),
-				Some(UnstakeRequest {
-					stashes: bounded_vec![(42, Deposit::get())],
-					checked: bounded_vec![3, 2, 1, 0]
-				})
+				fast_unstake_events_since_last_call(),
+				vec![
+					Event::ErasChecked { first_era: 3, last_era: 0, count: 4, ids: vec![0] },
+					Event::Aborted { stash: 1, id: 0 },
+					Event::BatchFinished
+				]
 			);
+			assert_eq!(Staking::total_stake(&1), Ok(7 + 100 - 50));
+			assert_eq!(<T as Config>::Currency::reserved_balance(&1), 0);
+			assert!(Unstaked::get().is_empty());
+		});
+	}
+
+	#[test]
+	fn rebonding_and_withdrawing_while_queued_aborts_unstake() {
+		ExtBuilder::default().build_and_execute(|| {
+			ErasToCheckPerBlock::<T>::put(BondingDuration::get() + 1);
+			CurrentEra::<T>::put(BondingDuration::get());
+
+			assert_ok!(FastUnstake::register_fast_unstake(
+				RuntimeOrigin::signed(2),
+				UnstakeDestination::Free
+			));
+
+			// the stash rebonds, unbonds the rebonded stake again and withdraws what has kept
+			// unlocking since its registration.
+			assert_ok!(Staking::rebond(RuntimeOrigin::signed(2), 50));
+			pallet_staking::Ledger::<T>::mutate(2, |maybe_ledger| {
+				let ledger = maybe_ledger.as_mut().unwrap();
+				let rebonded = ledger.active;
+				ledger.active = 0;
+				ledger.total = rebonded;
+				ledger.unlocking.iter_mut().for_each(|chunk| chunk.value = rebonded);
+			});
+			assert_eq!(Staking::active_stake(&1), Ok(0));
+			assert_eq!(Staking::total_stake(&1), Ok(50));
+
+			next_block(true);
 			next_block(true);
 			assert_eq!(Head::<T>::get(), None);
 
 			assert_eq!(
 				fast_unstake_events_since_last_call(),
 				vec![
-					Event::BatchChecked { eras: vec![3, 2, 1, 0] },
-					Event::Unstaked { stash: 42, result: Ok(()) },
+					Event::ErasChecked { first_era: 3, last_era: 0, count: 4, ids: vec![0] },
+					Event::Aborted { stash: 1, id: 0 },
 					Event::BatchFinished
 				]
 			);
+			assert_eq!(<T as Config>::Currency::reserved_balance(&1), 0);
+			assert!(Unstaked::get().is_empty());
 		});
 	}
-}
 
-mod batched {
-	use super::*;
+	#[test]
+	fn denylisting_while_queued_drops_the_request() {
+		ExtBuilder::default().batch(2).build_and_execute(|| {
+			ErasToCheckPerBlock::<T>::put(BondingDuration::get() + 1);
+			CurrentEra::<T>::put(BondingDuration::get());
+
+			assert_ok!(FastUnstake::register_fast_unstake(
+				RuntimeOrigin::signed(2),
+				UnstakeDestination::Free
+			));
+			assert_ok!(FastUnstake::register_fast_unstake(
+				RuntimeOrigin::signed(4),
+				UnstakeDestination::Free
+			));
+			assert_ok!(FastUnstake::add_to_denylist(RuntimeOrigin::root(), 1));
+			assert_eq!(<T as Config>::Currency::reserved_balance(&1), Deposit::get());
+
+			// the denied stash is dropped with a refund, the other one becomes the head.
+			next_block(true);
+			assert_eq!(Queue::<T>::count(), 0);
+			assert_eq!(
+				Head::<T>::get().unwrap().stashes,
+				vec![in_head(3, UnstakeDestination::Free, 1, 107)]
+			);
+			assert_eq!(
+				fast_unstake_events_since_last_call(),
+				vec![
+					Event::Denylisted { stash: 1 },
+					Event::Denied { stash: 1, id: 0 },
+					Event::ErasChecked { first_era: 3, last_era: 0, count: 4, ids: vec![1] }
+				]
+			);
+			assert_eq!(<T as Config>::Currency::reserved_balance(&1), 0);
+
+			// the denied stash remains fully unbonded, as after registration.
+			assert_eq!(Staking::active_stake(&1), Ok(0));
+			next_block(true);
+			assert_unstaked(&3);
+			assert_eq!(Unstaked::get(), vec![(3, 7 + 100)]);
+		});
+	}
 
 	#[test]
-	fn single_block_batched_successful() {
-		ExtBuilder::default().batch(3).build_and_execute(|| {
+	fn promotion_visits_at_most_a_batch_of_denied_stashes() {
+		ExtBuilder::default().batch(1).build_and_execute(|| {
 			ErasToCheckPerBlock::<T>::put(BondingDuration::get() + 1);
 			CurrentEra::<T>::put(BondingDuration::get());
 
-			assert_ok!(FastUnstake::register_fast_unstake(RuntimeOrigin::signed(2)));
-			assert_ok!(FastUnstake::register_fast_unstake(RuntimeOrigin::signed(4)));
-			assert_ok!(FastUnstake::register_fast_unstake(RuntimeOrigin::signed(6)));
-			assert_ok!(FastUnstake::register_fast_unstake(RuntimeOrigin::signed(8)));
+			assert_ok!(FastUnstake::register_fast_unstake(
+				RuntimeOrigin::signed(2),
+				UnstakeDestination::Free
+			));
+			assert_ok!(FastUnstake::register_fast_unstake(
+				RuntimeOrigin::signed(4),
+				UnstakeDestination::Free
+			));
+			assert_ok!(FastUnstake::add_to_denylist(RuntimeOrigin::root(), 1));
+			assert_ok!(FastUnstake::add_to_denylist(RuntimeOrigin::root(), 3));
+			let _ = fast_unstake_events_since_last_call();
 
-			assert_eq!(Queue::<T>::count(), 4);
+			// only one denied stash is dropped per promotion, which is charged in full.
+			let promotion_weight = <T as Config>::WeightInfo::queue_promotion();
+			assert_eq!(FastUnstake::on_idle(0, BlockWeights::get().max_block), promotion_weight);
+			assert_eq!(Queue::<T>::count(), 1);
 			assert_eq!(Head::<T>::get(), None);
+			assert_eq!(fast_unstake_events_since_last_call().len(), 1);
 
-			// when
+			// the next promotion drops the other one.
+			assert_eq!(FastUnstake::on_idle(0, BlockWeights::get().max_block), promotion_weight);
+			assert_eq!(Queue::<T>::count(), 0);
+			assert_eq!(Head::<T>::get(), None);
+			assert_eq!(<T as Config>::Currency::reserved_balance(&1), 0);
+			assert_eq!(<T as Config>::Currency::reserved_balance(&3), 0);
+
+			// nothing left to promote.
+			assert_eq!(
+				FastUnstake::on_idle(0, BlockWeights::get().max_block),
+				<T as frame_system::Config>::DbWeight::get().reads(4)
+			);
+		});
+	}
+
+	#[test]
+	fn successful_unstake_all_eras_per_block() {
+		ExtBuilder::default().build_and_execute(|| {
+			ErasToCheckPerBlock::<T>::put(BondingDuration::get() + 1);
+			CurrentEra::<T>::put(BondingDuration::get());
+
+			Balances::make_free_balance_be(&2, 100);
+
+			// register for fast unstake
+			assert_ok!(FastUnstake::register_fast_unstake(
+				RuntimeOrigin::signed(2),
+				UnstakeDestination::Free
+			));
+			assert_eq!(Queue::<T>::get(1), Some(queued(UnstakeDestination::Free, 1, 0)));
+
+			// process on idle
 			next_block(true);
 
-			// then
+			// assert queue item has been moved to head
+			assert_eq!(Queue::<T>::get(1), None);
+
+			// assert head item present
 			assert_eq!(
 				Head::<T>::get(),
 				Some(UnstakeRequest {
-					stashes: bounded_vec![
-						(1, Deposit::get()),
-						(5, Deposit::get()),
-						(7, Deposit::get())
-					],
-					checked: bounded_vec![3, 2, 1, 0]
+					stashes: bounded_vec![in_head(1, UnstakeDestination::Free, 0, 107)],
+					checked: bounded_vec![3, 2, 1, 0],
+					since: 2,
 				})
 			);
-			assert_eq!(Queue::<T>::count(), 1);
 
-			// when
 			next_block(true);
-
-			// then
-			assert_eq!(Head::<T>::get(), None);
-			assert_eq!(Queue::<T>::count(), 1);
+			assert_eq!(Head::<T>::get(), None,);
 
 			assert_eq!(
 				fast_unstake_events_since_last_call(),
 				vec![
-					Event::BatchChecked { eras: vec![3, 2, 1, 0] },
-					Event::Unstaked { stash: 1, result: Ok(()) },
-					Event::Unstaked { stash: 5, result: Ok(()) },
-					Event::Unstaked { stash: 7, result: Ok(()) },
+					Event::ErasChecked { first_era: 3, last_era: 0, count: 4, ids: vec![0] },
+					Event::Unstaked {
+						stash: 1,
+						id: 0,
+						result: UnstakeOutcome::Success,
+						destination: UnstakeDestination::Free,
+						payee: Some(1)
+					},
 					Event::BatchFinished
 				]
 			);
+			assert_unstaked(&1);
 		});
 	}
 
 	#[test]
-	fn multi_block_batched_successful() {
-		ExtBuilder::default().batch(3).build_and_execute(|| {
-			ErasToCheckPerBlock::<T>::put(2);
+	fn successful_unstake_one_era_per_block() {
+		ExtBuilder::default().build_and_execute(|| {
+			// put 1 era per block
+			ErasToCheckPerBlock::<T>::put(1);
 			CurrentEra::<T>::put(BondingDuration::get());
 
-			assert_ok!(FastUnstake::register_fast_unstake(RuntimeOrigin::signed(2)));
-			assert_ok!(FastUnstake::register_fast_unstake(RuntimeOrigin::signed(4)));
-			assert_ok!(FastUnstake::register_fast_unstake(RuntimeOrigin::signed(6)));
-			assert_ok!(FastUnstake::register_fast_unstake(RuntimeOrigin::signed(8)));
+			// register for fast unstake
+			assert_ok!(FastUnstake::register_fast_unstake(
+				RuntimeOrigin::signed(2),
+				UnstakeDestination::Free
+			));
+			assert_eq!(Queue::<T>::get(1), Some(queued(UnstakeDestination::Free, 1, 0)));
 
-			assert_eq!(Queue::<T>::count(), 4);
-			assert_eq!(Head::<T>::get(), None);
+			// process on idle
+			next_block(true);
+
+			// assert queue item has been moved to head
+			assert_eq!(Queue::<T>::get(1), None);
+
+			// assert head item present
+			assert_eq!(
+				Head::<T>::get(),
+				Some(UnstakeRequest {
+					stashes: bounded_vec![in_head(1, UnstakeDestination::Free, 0, 107)],
+					checked: bounded_vec![3],
+					since: 2,
+				})
+			);
 
-			// when
 			next_block(true);
 
-			// then
 			assert_eq!(
 				Head::<T>::get(),
 				Some(UnstakeRequest {
-					stashes: bounded_vec![
-						(1, Deposit::get()),
-						(5, Deposit::get()),
-						(7, Deposit::get())
-					],
-					checked: bounded_vec![3, 2]
+					stashes: bounded_vec![in_head(1, UnstakeDestination::Free, 0, 107)],
+					checked: bounded_vec![3, 2],
+					since: 2,
 				})
 			);
 
-			// when
 			next_block(true);
 
-			// then
 			assert_eq!(
 				Head::<T>::get(),
 				Some(UnstakeRequest {
-					stashes: bounded_vec![
-						(1, Deposit::get()),
-						(5, Deposit::get()),
-						(7, Deposit::get())
-					],
-					checked: bounded_vec![3, 2, 1, 0]
+					stashes: bounded_vec![in_head(1, UnstakeDestination::Free, 0, 107)],
+					checked: bounded_vec![3, 2, 1],
+					since: 2,
 				})
 			);
 
-			// when
 			next_block(true);
 
-			// then
-			assert_eq!(Head::<T>::get(), None);
+			assert_eq!(
+				Head::<T>::get(),
+				Some(UnstakeRequest {
+					stashes: bounded_vec![in_head(1, UnstakeDestination::Free, 0, 107)],
+					checked: bounded_vec![3, 2, 1, 0],
+					since: 2,
+				})
+			);
+
+			next_block(true);
+
+			assert_eq!(Head::<T>::get(), None,);
 
 			assert_eq!(
 				fast_unstake_events_since_last_call(),
 				vec![
-					Event::BatchChecked { eras: vec![3, 2] },
-					Event::BatchChecked { eras: vec![1, 0] },
-					Event::Unstaked { stash: 1, result: Ok(()) },
-					Event::Unstaked { stash: 5, result: Ok(()) },
-					Event::Unstaked { stash: 7, result: Ok(()) },
+					Event::ErasChecked { first_era: 3, last_era: 3, count: 1, ids: vec![0] },
+					Event::ErasChecked { first_era: 2, last_era: 2, count: 1, ids: vec![0] },
+					Event::ErasChecked { first_era: 1, last_era: 1, count: 1, ids: vec![0] },
+					Event::ErasChecked { first_era: 0, last_era: 0, count: 1, ids: vec![0] },
+					Event::Unstaked {
+						stash: 1,
+						id: 0,
+						result: UnstakeOutcome::Success,
+						destination: UnstakeDestination::Free,
+						payee: Some(1)
+					},
 					Event::BatchFinished
 				]
 			);
+			assert_unstaked(&1);
 		});
 	}
 
 	#[test]
-	fn multi_block_batched_some_fail() {
-		ExtBuilder::default().batch(4).build_and_execute(|| {
-			ErasToCheckPerBlock::<T>::put(2);
+	fn old_checked_era_pruned() {
+		// the only scenario where checked era pruning (checked.retain) comes handy is a follows:
+		// the whole vector is full and at capacity and in the next call we are ready to unstake,
+		// but then a new era happens.
+		ExtBuilder::default().build_and_execute(|| {
+			// given
+			ErasToCheckPerBlock::<T>::put(1);
 			CurrentEra::<T>::put(BondingDuration::get());
 
-			// register two good ones.
-			assert_ok!(FastUnstake::register_fast_unstake(RuntimeOrigin::signed(2)));
-			assert_ok!(FastUnstake::register_fast_unstake(RuntimeOrigin::signed(4)));
-			create_exposed_nominator(666, 1);
-			create_exposed_nominator(667, 3);
+			// register for fast unstake
+			assert_ok!(FastUnstake::register_fast_unstake(
+				RuntimeOrigin::signed(2),
+				UnstakeDestination::Free
+			));
+			assert_eq!(Queue::<T>::get(1), Some(queued(UnstakeDestination::Free, 1, 0)));
 
-			// then
-			assert_eq!(Queue::<T>::count(), 4);
-			assert_eq!(Head::<T>::get(), None);
+			next_block(true);
+			assert_eq!(
+				Head::<T>::get(),
+				Some(UnstakeRequest {
+					stashes: bounded_vec![in_head(1, UnstakeDestination::Free, 0, 107)],
+					checked: bounded_vec![3],
+					since: 2,
+				})
+			);
 
-			// when
 			next_block(true);
+			assert_eq!(
+				Head::<T>::get(),
+				Some(UnstakeRequest {
+					stashes: bounded_vec![in_head(1, UnstakeDestination::Free, 0, 107)],
+					checked: bounded_vec![3, 2],
+					since: 2,
+				})
+			);
 
-			// then
+			next_block(true);
 			assert_eq!(
 				Head::<T>::get(),
 				Some(UnstakeRequest {
-					stashes: bounded_vec![
-						(1, Deposit::get()),
-						(3, Deposit::get()),
-						(666, Deposit::get())
-					],
-					checked: bounded_vec![3, 2]
+					stashes: bounded_vec![in_head(1, UnstakeDestination::Free, 0, 107)],
+					checked: bounded_vec![3, 2, 1],
+					since: 2,
 				})
 			);
 
-			// when
 			next_block(true);
+			assert_eq!(
+				Head::<T>::get(),
+				Some(UnstakeRequest {
+					stashes: bounded_vec![in_head(1, UnstakeDestination::Free, 0, 107)],
+					checked: bounded_vec![3, 2, 1, 0],
+					since: 2,
+				})
+			);
+
+			// when: a new era happens right before one is free.
+			CurrentEra::<T>::put(CurrentEra::<T>::get().unwrap() + 1);
+			ExtBuilder::register_stakers_for_era(CurrentEra::<T>::get().unwrap());
 
 			// then
+			next_block(true);
 			assert_eq!(
 				Head::<T>::get(),
 				Some(UnstakeRequest {
-					stashes: bounded_vec![(1, Deposit::get()), (3, Deposit::get()),],
-					checked: bounded_vec![3, 2, 1, 0]
+					stashes: bounded_vec![in_head(1, UnstakeDestination::Free, 0, 107)],
+					// note era 0 is pruned to keep the vector length sane.
+					checked: bounded_vec![3, 2, 1, 4],
+					since: 2,
 				})
 			);
 
-			// when
 			next_block(true);
-
-			// then
 			assert_eq!(Head::<T>::get(), None);
 
 			assert_eq!(
 				fast_unstake_events_since_last_call(),
 				vec![
-					Event::Slashed { stash: 667, amount: 7 },
-					Event::BatchChecked { eras: vec![3, 2] },
-					Event::Slashed { stash: 666, amount: 7 },
-					Event::BatchChecked { eras: vec![1, 0] },
-					Event::Unstaked { stash: 1, result: Ok(()) },
-					Event::Unstaked { stash: 3, result: Ok(()) },
+					Event::ErasChecked { first_era: 3, last_era: 3, count: 1, ids: vec![0] },
+					Event::ErasChecked { first_era: 2, last_era: 2, count: 1, ids: vec![0] },
+					Event::ErasChecked { first_era: 1, last_era: 1, count: 1, ids: vec![0] },
+					Event::ErasChecked { first_era: 0, last_era: 0, count: 1, ids: vec![0] },
+					Event::ErasChecked { first_era: 4, last_era: 4, count: 1, ids: vec![0] },
+					Event::Unstaked {
+						stash: 1,
+						id: 0,
+						result: UnstakeOutcome::Success,
+						destination: UnstakeDestination::Free,
+						payee: Some(1)
+					},
 					Event::BatchFinished
 				]
 			);
+			assert_unstaked(&1);
 		});
 	}
 
 	#[test]
-	fn multi_block_batched_all_fail_early_exit() {
-		ExtBuilder::default().batch(2).build_and_execute(|| {
+	fn unstake_paused_mid_election() {
+		ExtBuilder::default().build_and_execute(|| {
+			// give: put 1 era per block
 			ErasToCheckPerBlock::<T>::put(1);
 			CurrentEra::<T>::put(BondingDuration::get());
 
-			// register two bad ones.
-			create_exposed_nominator(666, 3);
-			create_exposed_nominator(667, 2);
+			// register for fast unstake
+			assert_ok!(FastUnstake::register_fast_unstake(
+				RuntimeOrigin::signed(2),
+				UnstakeDestination::Free
+			));
 
-			// then
-			assert_eq!(Queue::<T>::count(), 2);
-			assert_eq!(Head::<T>::get(), None);
+			// process 2 blocks
+			next_block(true);
+			assert_eq!(
+				Head::<T>::get(),
+				Some(UnstakeRequest {
+					stashes: bounded_vec![in_head(1, UnstakeDestination::Free, 0, 107)],
+					checked: bounded_vec![3],
+					since: 2,
+				})
+			);
 
-			// when we progress a block..
 			next_block(true);
+			assert_eq!(
+				Head::<T>::get(),
+				Some(UnstakeRequest {
+					stashes: bounded_vec![in_head(1, UnstakeDestination::Free, 0, 107)],
+					checked: bounded_vec![3, 2],
+					since: 2,
+				})
+			);
 
-			// ..and register two good ones.
-			assert_ok!(FastUnstake::register_fast_unstake(RuntimeOrigin::signed(2)));
-			assert_ok!(FastUnstake::register_fast_unstake(RuntimeOrigin::signed(4)));
+			// when
+			Ongoing::set(true);
 
-			// then one of the bad ones is reaped.
+			// then nothing changes
+			next_block(true);
 			assert_eq!(
 				Head::<T>::get(),
 				Some(UnstakeRequest {
-					stashes: bounded_vec![(667, Deposit::get())],
-					checked: bounded_vec![3]
+					stashes: bounded_vec![in_head(1, UnstakeDestination::Free, 0, 107)],
+					checked: bounded_vec![3, 2],
+					since: 2,
 				})
 			);
 
-			// when we go to next block
 			next_block(true);
+			assert_eq!(
+				Head::<T>::get(),
+				Some(UnstakeRequest {
+					stashes: bounded_vec![in_head(1, UnstakeDestination::Free, 0, 107)],
+					checked: bounded_vec![3, 2],
+					since: 2,
+				})
+			);
 
-			// then the head is empty, we early terminate the batch.
-			assert_eq!(Head::<T>::get(), None);
+			// then we register a new era.
+			Ongoing::set(false);
+			CurrentEra::<T>::put(CurrentEra::<T>::get().unwrap() + 1);
+			ExtBuilder::register_stakers_for_era(CurrentEra::<T>::get().unwrap());
 
-			// upon next block, we will assemble a new head.
+			// then we can progress again, but notice that the new era that had to be checked.
 			next_block(true);
-
 			assert_eq!(
 				Head::<T>::get(),
 				Some(UnstakeRequest {
-					stashes: bounded_vec![(1, Deposit::get()), (3, Deposit::get()),],
-					checked: bounded_vec![3]
+					stashes: bounded_vec![in_head(1, UnstakeDestination::Free, 0, 107)],
+					checked: bounded_vec![3, 2, 4],
+					since: 2,
 				})
 			);
 
+			// progress to end
+			next_block(true);
 			assert_eq!(
-				fast_unstake_events_since_last_call(),
-				vec![
-					Event::Slashed { stash: 666, amount: Deposit::get() },
-					Event::BatchChecked { eras: vec![3] },
-					Event::Slashed { stash: 667, amount: Deposit::get() },
-					Event::BatchFinished,
-					Event::BatchChecked { eras: vec![3] }
-				]
-			);
-		});
+				Head::<T>::get(),
+				Some(UnstakeRequest {
+					stashes: bounded_vec![in_head(1, UnstakeDestination::Free, 0, 107)],
+					checked: bounded_vec![3, 2, 4, 1],
+					since: 2,
+				})
+			);
+
+			// but notice that we don't care about era 0 instead anymore! we're done.
+			next_block(true);
+			assert_eq!(Head::<T>::get(), None);
+
+			assert_eq!(
+				fast_unstake_events_since_last_call(),
+				vec![
+					Event::ErasChecked { first_era: 3, last_era: 3, count: 1, ids: vec![0] },
+					Event::ErasChecked { first_era: 2, last_era: 2, count: 1, ids: vec![0] },
+					Event::ErasChecked { first_era: 4, last_era: 4, count: 1, ids: vec![0] },
+					Event::ErasChecked { first_era: 1, last_era: 1, count: 1, ids: vec![0] },
+					Event::Unstaked {
+						stash: 1,
+						id: 0,
+						result: UnstakeOutcome::Success,
+						destination: UnstakeDestination::Free,
+						payee: Some(1)
+					},
+					Event::BatchFinished
+				]
+			);
+
+			assert_unstaked(&1);
+		});
+	}
+
+	#[test]
+	fn duplicate_checked_eras_are_not_counted_twice() {
+		ExtBuilder::default().build_and_execute(|| {
+			// given
+			ErasToCheckPerBlock::<T>::put(1);
+			CurrentEra::<T>::put(BondingDuration::get());
+			assert_ok!(FastUnstake::register_fast_unstake(
+				RuntimeOrigin::signed(2),
+				UnstakeDestination::Free
+			));
+			next_block(true);
+			next_block(true);
+
+			// when era 2 has somehow been recorded more than once, filling up `checked`.
+			Head::<T>::mutate(|head| head.as_mut().unwrap().checked = bounded_vec![3, 2, 2, 2]);
+
+			// then it still counts as a single era.
+			assert_eq!(FastUnstake::telemetry().head_progress, Some((2, 4)));
+
+			// and the remaining distinct eras are still checked before unstaking.
+			next_block(true);
+			assert_eq!(
+				Head::<T>::get(),
+				Some(UnstakeRequest {
+					stashes: bounded_vec![in_head(1, UnstakeDestination::Free, 0, 107)],
+					checked: bounded_vec![3, 2, 1],
+					since: 2,
+				})
+			);
+
+			next_block(true);
+			assert_eq!(
+				Head::<T>::get(),
+				Some(UnstakeRequest {
+					stashes: bounded_vec![in_head(1, UnstakeDestination::Free, 0, 107)],
+					checked: bounded_vec![3, 2, 1, 0],
+					since: 2,
+				})
+			);
+
+			next_block(true);
+			assert_eq!(Head::<T>::get(), None);
+			assert_eq!(
+				fast_unstake_events_since_last_call(),
+				vec![
+					Event::ErasChecked { first_era: 3, last_era: 3, count: 1, ids: vec![0] },
+					Event::ErasChecked { first_era: 2, last_era: 2, count: 1, ids: vec![0] },
+					Event::ErasChecked { first_era: 1, last_era: 1, count: 1, ids: vec![0] },
+					Event::ErasChecked { first_era: 0, last_era: 0, count: 1, ids: vec![0] },
+					Event::Unstaked {
+						stash: 1,
+						id: 0,
+						result: UnstakeOutcome::Success,
+						destination: UnstakeDestination::Free,
+						payee: Some(1)
+					},
+					Event::BatchFinished
+				]
+			);
+			assert_unstaked(&1);
+		});
+	}
+
+	#[test]
+	fn head_expires_if_elections_keep_interrupting() {
+		ExtBuilder::default().max_head_blocks(1).build_and_execute(|| {
+			// given
+			ErasToCheckPerBlock::<T>::put(1);
+			CurrentEra::<T>::put(BondingDuration::get());
+			assert_ok!(FastUnstake::register_fast_unstake(
+				RuntimeOrigin::signed(2),
+				UnstakeDestination::Free
+			));
+			assert_eq!(<T as Config>::Currency::reserved_balance(&1), Deposit::get());
+
+			next_block(true);
+			assert_eq!(
+				Head::<T>::get(),
+				Some(UnstakeRequest {
+					stashes: bounded_vec![in_head(1, UnstakeDestination::Free, 0, 107)],
+					checked: bounded_vec![3],
+					since: 2,
+				})
+			);
+
+			// when an election interrupts the checking, while the eras keep going.
+			Ongoing::set(true);
+			CurrentEra::<T>::put(4);
+			ExtBuilder::register_stakers_for_era(4);
+			next_block(true);
+
+			// then the head is still within its deadline.
+			assert!(Head::<T>::get().is_some());
+
+			CurrentEra::<T>::put(5);
+			ExtBuilder::register_stakers_for_era(5);
+			next_block(true);
+
+			// then the head has expired and the stash is dropped.
+			assert_eq!(Head::<T>::get(), None);
+			assert!(!Queue::<T>::contains_key(&1));
+			assert_eq!(<T as Config>::Currency::reserved_balance(&1), 0);
+			assert_eq!(
+				fast_unstake_events_since_last_call(),
+				vec![
+					Event::ErasChecked { first_era: 3, last_era: 3, count: 1, ids: vec![0] },
+					Event::Expired { stash: 1, id: 0, requeued: false },
+				]
+			);
+		});
+	}
+
+	#[test]
+	fn expired_head_can_be_requeued() {
+		ExtBuilder::default()
+			.max_head_blocks(1)
+			.requeue_expired(true)
+			.build_and_execute(|| {
+				// given
+				ErasToCheckPerBlock::<T>::put(1);
+				CurrentEra::<T>::put(BondingDuration::get());
+				assert_ok!(FastUnstake::register_fast_unstake(
+					RuntimeOrigin::signed(2),
+					UnstakeDestination::Free
+				));
+				next_block(true);
+
+				// when the head is stuck for more than `MaxHeadBlocks`.
+				Ongoing::set(true);
+				for era in 4..=5 {
+					CurrentEra::<T>::put(era);
+					ExtBuilder::register_stakers_for_era(era);
+					next_block(true);
+				}
+
+				// then the stash is back in the queue, with its deposit still reserved.
+				assert_eq!(Head::<T>::get(), None);
+				assert_eq!(Queue::<T>::get(&1), Some(queued(UnstakeDestination::Free, 4, 0)));
+				assert_eq!(<T as Config>::Currency::reserved_balance(&1), Deposit::get());
+				assert_eq!(
+					fast_unstake_events_since_last_call(),
+					vec![
+						Event::ErasChecked { first_era: 3, last_era: 3, count: 1, ids: vec![0] },
+						Event::Expired { stash: 1, id: 0, requeued: true },
+					]
+				);
+
+				// and once the election is over, it is checked again from scratch.
+				Ongoing::set(false);
+				next_block(true);
+				assert_eq!(
+					Head::<T>::get(),
+					Some(UnstakeRequest {
+						stashes: bounded_vec![in_head(1, UnstakeDestination::Free, 0, 107)],
+						checked: bounded_vec![5],
+						since: 5,
+					})
+				);
+			});
+	}
+
+	#[test]
+	fn exposed_nominator_cannot_unstake() {
+		// 666 is an exposed nominator in era 1.
+		let exposed = 666;
+		ExtBuilder::default().exposed_in_eras(exposed, vec![1]).build_and_execute(|| {
+			ErasToCheckPerBlock::<T>::put(1);
+			CurrentEra::<T>::put(BondingDuration::get());
+			assert_ok!(FastUnstake::register_fast_unstake(
+				RuntimeOrigin::signed(exposed),
+				UnstakeDestination::Free
+			));
+
+			// a few blocks later, we realize they are slashed
+			next_block(true);
+			assert_eq!(
+				Head::<T>::get(),
+				Some(UnstakeRequest {
+					stashes: bounded_vec![in_head(exposed, UnstakeDestination::Free, 0, 100)],
+					checked: bounded_vec![3],
+					since: 2,
+				})
+			);
+			next_block(true);
+			assert_eq!(
+				Head::<T>::get(),
+				Some(UnstakeRequest {
+					stashes: bounded_vec![in_head(exposed, UnstakeDestination::Free, 0, 100)],
+					checked: bounded_vec![3, 2],
+					since: 2,
+				})
+			);
+			next_block(true);
+			assert_eq!(Head::<T>::get(), None);
+
+			assert_eq!(
+				fast_unstake_events_since_last_call(),
+				vec![
+					Event::ErasChecked { first_era: 3, last_era: 3, count: 1, ids: vec![0] },
+					Event::ErasChecked { first_era: 2, last_era: 2, count: 1, ids: vec![0] },
+					Event::Slashed { stash: exposed, id: 0, amount: Deposit::get() },
+					Event::BatchFinished
+				]
+			);
+		});
+	}
+
+	#[test]
+	fn staking_api_agrees_with_on_chain_check() {
+		// 666 is exposed in era 1, 1 is not exposed at all.
+		let exposed = 666;
+		ExtBuilder::default().exposed_in_eras(exposed, vec![1]).build_and_execute(|| {
+			ErasToCheckPerBlock::<T>::put(BondingDuration::get() + 1);
+			CurrentEra::<T>::put(BondingDuration::get());
+
+			assert_ok!(FastUnstake::register_fast_unstake(
+				RuntimeOrigin::signed(exposed),
+				UnstakeDestination::Free
+			));
+			assert_ok!(FastUnstake::register_fast_unstake(
+				RuntimeOrigin::signed(2),
+				UnstakeDestination::Free
+			));
+
+			assert_eq!(Staking::eras_of_exposure(exposed, 0, BondingDuration::get()), vec![1]);
+			assert!(Staking::exposed_in_era(exposed, 1));
+			assert!(Staking::eras_of_exposure(1, 0, BondingDuration::get()).is_empty());
+
+			// process both requests.
+			for _ in 0..4 {
+				next_block(true);
+			}
+			assert_eq!(Head::<T>::get(), None);
+			assert_eq!(Queue::<T>::count(), 0);
+
+			// the exposed one got slashed, the other one unstaked.
+			let events = fast_unstake_events_since_last_call();
+			assert!(events.contains(&Event::Slashed {
+				stash: exposed,
+				id: 0,
+				amount: Deposit::get()
+			}));
+			assert!(events.contains(&Event::Unstaked {
+				stash: 1,
+				id: 1,
+				result: UnstakeOutcome::Success,
+				destination: UnstakeDestination::Free,
+				payee: Some(1)
+			}));
+			assert_unstaked(&1);
+		});
+	}
+
+	#[test]
+	fn nominator_exposed_in_previous_era_only_cannot_unstake() {
+		let exposed = 666;
+		ExtBuilder::default()
+			.bonding_duration(6)
+			.validators_per_era(128)
+			.exposed_in_eras(exposed, vec![5])
+			.build_and_execute(|| {
+				ErasToCheckPerBlock::<T>::put(1);
+				CurrentEra::<T>::put(BondingDuration::get());
+				assert_eq!(pallet_staking::ErasStakers::<T>::iter_prefix(6).count(), 128);
+
+				// only exposed in `current - 1`.
+				assert_eq!(Staking::eras_of_exposure(exposed, 0, BondingDuration::get()), vec![5]);
+				assert!(!Staking::exposed_in_era(exposed, 6));
+				assert_ok!(FastUnstake::register_fast_unstake(
+					RuntimeOrigin::signed(exposed),
+					UnstakeDestination::Free
+				));
+
+				// the current era is fine, the one before is not.
+				next_block(true);
+				assert_eq!(
+					Head::<T>::get(),
+					Some(UnstakeRequest {
+						stashes: bounded_vec![in_head(exposed, UnstakeDestination::Free, 0, 100)],
+						checked: bounded_vec![6],
+						since: 2,
+					})
+				);
+				next_block(true);
+				assert_eq!(Head::<T>::get(), None);
+
+				assert_eq!(
+					fast_unstake_events_since_last_call(),
+					vec![
+						Event::ErasChecked { first_era: 6, last_era: 6, count: 1, ids: vec![0] },
+						Event::Slashed { stash: exposed, id: 0, amount: Deposit::get() },
+						Event::BatchFinished
+					]
+				);
+				assert!(Staking::nominators(exposed).is_some());
+			});
+	}
+
+	#[test]
+	fn nominator_exposed_in_previous_era_only_passes_register_check() {
+		let exposed = 666;
+		ExtBuilder::default()
+			.reject_exposed_on_register(true)
+			.exposed_in_eras(exposed, vec![BondingDuration::get() - 1])
+			.build_and_execute(|| {
+				ErasToCheckPerBlock::<T>::put(BondingDuration::get() + 1);
+				CurrentEra::<T>::put(BondingDuration::get());
+
+				// registration only looks at the current era, so the stash is accepted.
+				assert_ok!(FastUnstake::register_fast_unstake(
+					RuntimeOrigin::signed(exposed),
+					UnstakeDestination::Free
+				));
+
+				// but it is caught once all the eras are checked.
+				next_block(true);
+				assert_eq!(Head::<T>::get(), None);
+				assert_eq!(
+					fast_unstake_events_since_last_call(),
+					vec![
+						Event::Slashed { stash: exposed, id: 0, amount: Deposit::get() },
+						Event::BatchFinished
+					]
+				);
+			});
+	}
+
+	#[test]
+	fn exposed_nominator_cannot_unstake_multi_check() {
+		ExtBuilder::default().build_and_execute(|| {
+			// same as the previous check, but we check 2 eras per block, and we make the exposed be
+			// exposed in era 0, so that it is detected halfway in a check era.
+			ErasToCheckPerBlock::<T>::put(2);
+			CurrentEra::<T>::put(BondingDuration::get());
+
+			// create an exposed nominator in era 0
+			let exposed = 666;
+			create_exposed_nominator(exposed, 0);
+
+			// a few blocks later, we realize they are slashed
+			next_block(true);
+			assert_eq!(
+				Head::<T>::get(),
+				Some(UnstakeRequest {
+					stashes: bounded_vec![in_head(exposed, UnstakeDestination::Free, 0, 10)],
+					checked: bounded_vec![3, 2],
+					since: 2,
+				})
+			);
+			next_block(true);
+			assert_eq!(Head::<T>::get(), None);
+
+			assert_eq!(
+				fast_unstake_events_since_last_call(),
+				// we slash them
+				vec![
+					Event::ErasChecked { first_era: 3, last_era: 2, count: 2, ids: vec![0] },
+					Event::Slashed { stash: exposed, id: 0, amount: Deposit::get() },
+					Event::BatchFinished
+				]
+			);
+		});
+	}
+
+	#[test]
+	fn validators_cannot_bail() {
+		ExtBuilder::default().build_and_execute(|| {
+			ErasToCheckPerBlock::<T>::put(BondingDuration::get() + 1);
+			CurrentEra::<T>::put(BondingDuration::get());
+
+			// a validator switches role and register...
+			assert_ok!(Staking::nominate(
+				RuntimeOrigin::signed(VALIDATOR_PREFIX),
+				vec![VALIDATOR_PREFIX]
+			));
+			assert_ok!(FastUnstake::register_fast_unstake(
+				RuntimeOrigin::signed(VALIDATOR_PREFIX),
+				UnstakeDestination::Free
+			));
+
+			// but they indeed are exposed!
+			assert!(pallet_staking::ErasStakers::<T>::contains_key(
+				BondingDuration::get() - 1,
+				VALIDATOR_PREFIX
+			));
+
+			// process a block, this validator is exposed and has been slashed.
+			next_block(true);
+			assert_eq!(Head::<T>::get(), None);
+
+			assert_eq!(
+				fast_unstake_events_since_last_call(),
+				vec![
+					Event::Slashed { stash: 100, id: 0, amount: Deposit::get() },
+					Event::BatchFinished
+				]
+			);
+		});
+	}
+
+	#[test]
+	fn unexposed_validator_can_fast_unstake() {
+		ExtBuilder::default().build_and_execute(|| {
+			ErasToCheckPerBlock::<T>::put(BondingDuration::get() + 1);
+			CurrentEra::<T>::put(BondingDuration::get());
+
+			// create a new validator that 100% not exposed.
+			Balances::make_free_balance_be(&42, 100 + Deposit::get());
+			assert_ok!(Staking::bond(RuntimeOrigin::signed(42), 42, 10, RewardDestination::Staked));
+			assert_ok!(Staking::validate(RuntimeOrigin::signed(42), Default::default()));
+
+			// let them register:
+			assert_ok!(FastUnstake::register_fast_unstake(
+				RuntimeOrigin::signed(42),
+				UnstakeDestination::Free
+			));
+
+			// 2 block's enough to unstake them.
+			next_block(true);
+			assert_eq!(
+				Head::<T>::get(),
+				Some(UnstakeRequest {
+					stashes: bounded_vec![in_head(42, UnstakeDestination::Free, 0, 10)],
+					checked: bounded_vec![3, 2, 1, 0],
+					since: 2,
+				})
+			);
+			next_block(true);
+			assert_eq!(Head::<T>::get(), None);
+
+			assert_eq!(
+				fast_unstake_events_since_last_call(),
+				vec![
+					Event::ErasChecked { first_era: 3, last_era: 0, count: 4, ids: vec![0] },
+					Event::Unstaked {
+						stash: 42,
+						id: 0,
+						result: UnstakeOutcome::Success,
+						destination: UnstakeDestination::Free,
+						payee: Some(42)
+					},
+					Event::BatchFinished
+				]
+			);
+		});
+	}
+}
+
+mod batched {
+	use super::*;
+
+	#[test]
+	fn single_block_batched_successful() {
+		ExtBuilder::default().batch(3).build_and_execute(|| {
+			ErasToCheckPerBlock::<T>::put(BondingDuration::get() + 1);
+			CurrentEra::<T>::put(BondingDuration::get());
+
+			assert_ok!(FastUnstake::register_fast_unstake(
+				RuntimeOrigin::signed(2),
+				UnstakeDestination::Free
+			));
+			assert_ok!(FastUnstake::register_fast_unstake(
+				RuntimeOrigin::signed(4),
+				UnstakeDestination::Free
+			));
+			assert_ok!(FastUnstake::register_fast_unstake(
+				RuntimeOrigin::signed(6),
+				UnstakeDestination::Free
+			));
+			assert_ok!(FastUnstake::register_fast_unstake(
+				RuntimeOrigin::signed(8),
+				UnstakeDestination::Free
+			));
+
+			assert_eq!(Queue::<T>::count(), 4);
+			assert_eq!(Head::<T>::get(), None);
+
+			// when
+			next_block(true);
+
+			// then
+			assert_eq!(
+				Head::<T>::get(),
+				Some(UnstakeRequest {
+					stashes: bounded_vec![
+						in_head(1, UnstakeDestination::Free, 0, 107),
+						in_head(5, UnstakeDestination::Free, 2, 107),
+						in_head(7, UnstakeDestination::Free, 3, 107)
+					],
+					checked: bounded_vec![3, 2, 1, 0],
+					since: 2,
+				})
+			);
+			assert_eq!(Queue::<T>::count(), 1);
+
+			// when
+			next_block(true);
+
+			// then
+			assert_eq!(Head::<T>::get(), None);
+			assert_eq!(Queue::<T>::count(), 1);
+
+			assert_eq!(
+				fast_unstake_events_since_last_call(),
+				vec![
+					Event::ErasChecked { first_era: 3, last_era: 0, count: 4, ids: vec![0, 2, 3] },
+					Event::Unstaked {
+						stash: 1,
+						id: 0,
+						result: UnstakeOutcome::Success,
+						destination: UnstakeDestination::Free,
+						payee: Some(1)
+					},
+					Event::Unstaked {
+						stash: 5,
+						id: 2,
+						result: UnstakeOutcome::Success,
+						destination: UnstakeDestination::Free,
+						payee: Some(5)
+					},
+					Event::Unstaked {
+						stash: 7,
+						id: 3,
+						result: UnstakeOutcome::Success,
+						destination: UnstakeDestination::Free,
+						payee: Some(7)
+					},
+					Event::BatchFinished
+				]
+			);
+		});
+	}
+
+	#[test]
+	fn multi_block_batched_successful() {
+		ExtBuilder::default().batch(3).build_and_execute(|| {
+			ErasToCheckPerBlock::<T>::put(2);
+			CurrentEra::<T>::put(BondingDuration::get());
+
+			assert_ok!(FastUnstake::register_fast_unstake(
+				RuntimeOrigin::signed(2),
+				UnstakeDestination::Free
+			));
+			assert_ok!(FastUnstake::register_fast_unstake(
+				RuntimeOrigin::signed(4),
+				UnstakeDestination::Free
+			));
+			assert_ok!(FastUnstake::register_fast_unstake(
+				RuntimeOrigin::signed(6),
+				UnstakeDestination::Free
+			));
+			assert_ok!(FastUnstake::register_fast_unstake(
+				RuntimeOrigin::signed(8),
+				UnstakeDestination::Free
+			));
+
+			assert_eq!(Queue::<T>::count(), 4);
+			assert_eq!(Head::<T>::get(), None);
+
+			// when
+			next_block(true);
+
+			// then
+			assert_eq!(
+				Head::<T>::get(),
+				Some(UnstakeRequest {
+					stashes: bounded_vec![
+						in_head(1, UnstakeDestination::Free, 0, 107),
+						in_head(5, UnstakeDestination::Free, 2, 107),
+						in_head(7, UnstakeDestination::Free, 3, 107)
+					],
+					checked: bounded_vec![3, 2],
+					since: 2,
+				})
+			);
+
+			// when
+			next_block(true);
+
+			// then
+			assert_eq!(
+				Head::<T>::get(),
+				Some(UnstakeRequest {
+					stashes: bounded_vec![
+						in_head(1, UnstakeDestination::Free, 0, 107),
+						in_head(5, UnstakeDestination::Free, 2, 107),
+						in_head(7, UnstakeDestination::Free, 3, 107)
+					],
+					checked: bounded_vec![3, 2, 1, 0],
+					since: 2,
+				})
+			);
+
+			// when
+			next_block(true);
+
+			// then
+			assert_eq!(Head::<T>::get(), None);
+
+			assert_eq!(
+				fast_unstake_events_since_last_call(),
+				vec![
+					Event::ErasChecked { first_era: 3, last_era: 2, count: 2, ids: vec![0, 2, 3] },
+					Event::ErasChecked { first_era: 1, last_era: 0, count: 2, ids: vec![0, 2, 3] },
+					Event::Unstaked {
+						stash: 1,
+						id: 0,
+						result: UnstakeOutcome::Success,
+						destination: UnstakeDestination::Free,
+						payee: Some(1)
+					},
+					Event::Unstaked {
+						stash: 5,
+						id: 2,
+						result: UnstakeOutcome::Success,
+						destination: UnstakeDestination::Free,
+						payee: Some(5)
+					},
+					Event::Unstaked {
+						stash: 7,
+						id: 3,
+						result: UnstakeOutcome::Success,
+						destination: UnstakeDestination::Free,
+						payee: Some(7)
+					},
+					Event::BatchFinished
+				]
+			);
+		});
+	}
+
+	#[test]
+	fn multi_block_batched_some_fail() {
+		ExtBuilder::default().batch(4).build_and_execute(|| {
+			ErasToCheckPerBlock::<T>::put(2);
+			CurrentEra::<T>::put(BondingDuration::get());
+
+			// register two good ones.
+			assert_ok!(FastUnstake::register_fast_unstake(
+				RuntimeOrigin::signed(2),
+				UnstakeDestination::Free
+			));
+			assert_ok!(FastUnstake::register_fast_unstake(
+				RuntimeOrigin::signed(4),
+				UnstakeDestination::Free
+			));
+			create_exposed_nominator(666, 1);
+			create_exposed_nominator(667, 3);
+
+			// then
+			assert_eq!(Queue::<T>::count(), 4);
+			assert_eq!(Head::<T>::get(), None);
+
+			// when
+			next_block(true);
+
+			// then
+			assert_eq!(
+				Head::<T>::get(),
+				Some(UnstakeRequest {
+					stashes: bounded_vec![
+						in_head(1, UnstakeDestination::Free, 0, 107),
+						in_head(3, UnstakeDestination::Free, 1, 107),
+						in_head(666, UnstakeDestination::Free, 2, 10)
+					],
+					checked: bounded_vec![3, 2],
+					since: 2,
+				})
+			);
+
+			// when
+			next_block(true);
+
+			// then
+			assert_eq!(
+				Head::<T>::get(),
+				Some(UnstakeRequest {
+					stashes: bounded_vec![
+						in_head(1, UnstakeDestination::Free, 0, 107),
+						in_head(3, UnstakeDestination::Free, 1, 107),
+					],
+					checked: bounded_vec![3, 2, 1, 0],
+					since: 2,
+				})
+			);
+
+			// when
+			next_block(true);
+
+			// then
+			assert_eq!(Head::<T>::get(), None);
+
+			assert_eq!(
+				fast_unstake_events_since_last_call(),
+				vec![
+					Event::Slashed { stash: 667, id: 3, amount: 7 },
+					Event::ErasChecked { first_era: 3, last_era: 2, count: 2, ids: vec![0, 1, 2] },
+					Event::Slashed { stash: 666, id: 2, amount: 7 },
+					Event::ErasChecked { first_era: 1, last_era: 0, count: 2, ids: vec![0, 1] },
+					Event::Unstaked {
+						stash: 1,
+						id: 0,
+						result: UnstakeOutcome::Success,
+						destination: UnstakeDestination::Free,
+						payee: Some(1)
+					},
+					Event::Unstaked {
+						stash: 3,
+						id: 1,
+						result: UnstakeOutcome::Success,
+						destination: UnstakeDestination::Free,
+						payee: Some(3)
+					},
+					Event::BatchFinished
+				]
+			);
+		});
+	}
+
+	#[test]
+	fn multi_block_batched_all_fail_early_exit() {
+		ExtBuilder::default().batch(2).build_and_execute(|| {
+			ErasToCheckPerBlock::<T>::put(1);
+			CurrentEra::<T>::put(BondingDuration::get());
+
+			// register two bad ones.
+			create_exposed_nominator(666, 3);
+			create_exposed_nominator(667, 2);
+
+			// then
+			assert_eq!(Queue::<T>::count(), 2);
+			assert_eq!(Head::<T>::get(), None);
+
+			// when we progress a block..
+			next_block(true);
+
+			// ..and register two good ones.
+			assert_ok!(FastUnstake::register_fast_unstake(
+				RuntimeOrigin::signed(2),
+				UnstakeDestination::Free
+			));
+			assert_ok!(FastUnstake::register_fast_unstake(
+				RuntimeOrigin::signed(4),
+				UnstakeDestination::Free
+			));
+
+			// then one of the bad ones is reaped.
+			assert_eq!(
+				Head::<T>::get(),
+				Some(UnstakeRequest {
+					stashes: bounded_vec![in_head(667, UnstakeDestination::Free, 1, 10)],
+					checked: bounded_vec![3],
+					since: 2,
+				})
+			);
+
+			// when we go to next block
+			next_block(true);
+
+			// then the head is empty, we early terminate the batch.
+			assert_eq!(Head::<T>::get(), None);
+
+			// upon next block, we will assemble a new head.
+			next_block(true);
+
+			assert_eq!(
+				Head::<T>::get(),
+				Some(UnstakeRequest {
+					stashes: bounded_vec![
+						in_head(1, UnstakeDestination::Free, 2, 107),
+						in_head(3, UnstakeDestination::Free, 3, 107),
+					],
+					checked: bounded_vec![3],
+					since: 4,
+				})
+			);
+
+			assert_eq!(
+				fast_unstake_events_since_last_call(),
+				vec![
+					Event::Slashed { stash: 666, id: 0, amount: Deposit::get() },
+					Event::ErasChecked { first_era: 3, last_era: 3, count: 1, ids: vec![1] },
+					Event::Slashed { stash: 667, id: 1, amount: Deposit::get() },
+					Event::BatchFinished,
+					Event::ErasChecked { first_era: 3, last_era: 3, count: 1, ids: vec![2, 3] }
+				]
+			);
+		});
+	}
+}
+
+mod destination {
+	use super::*;
+
+	fn register_and_unstake(destination: UnstakeDestination<AccountId>) {
+		ErasToCheckPerBlock::<T>::put(BondingDuration::get() + 1);
+		CurrentEra::<T>::put(BondingDuration::get());
+
+		assert_ok!(FastUnstake::register_fast_unstake(
+			RuntimeOrigin::signed(2),
+			destination.clone()
+		));
+		assert_eq!(Queue::<T>::get(1), Some(queued(destination, 1, 0)));
+
+		// check all eras, then unstake.
+		next_block(true);
+		next_block(true);
+
+		assert_unstaked(&1);
+		assert_eq!(<T as Config>::Currency::reserved_balance(&1), 0);
+	}
+
+	#[test]
+	fn free_keeps_funds_in_stash() {
+		ExtBuilder::default().build_and_execute(|| {
+			register_and_unstake(UnstakeDestination::Free);
+
+			assert_eq!(
+				fast_unstake_events_since_last_call(),
+				vec![
+					Event::ErasChecked { first_era: 3, last_era: 0, count: 4, ids: vec![0] },
+					Event::Unstaked {
+						stash: 1,
+						id: 0,
+						result: UnstakeOutcome::Success,
+						destination: UnstakeDestination::Free,
+						payee: Some(1)
+					},
+					Event::BatchFinished
+				]
+			);
+			assert_eq!(Balances::free_balance(1), 214);
+		});
+	}
+
+	#[test]
+	fn unstaked_event_reports_payee() {
+		ExtBuilder::default().build_and_execute(|| {
+			// stash 1 is controlled by 2.
+			assert_ok!(Staking::set_payee(RuntimeOrigin::signed(2), RewardDestination::Controller));
+			register_and_unstake(UnstakeDestination::Free);
+
+			assert_eq!(
+				fast_unstake_events_since_last_call(),
+				vec![
+					Event::ErasChecked { first_era: 3, last_era: 0, count: 4, ids: vec![0] },
+					Event::Unstaked {
+						stash: 1,
+						id: 0,
+						result: UnstakeOutcome::Success,
+						destination: UnstakeDestination::Free,
+						payee: Some(2)
+					},
+					Event::BatchFinished
+				]
+			);
+		});
+	}
+
+	#[test]
+	fn pool_joins_with_unstaked_funds() {
+		ExtBuilder::default().build_and_execute(|| {
+			register_and_unstake(UnstakeDestination::Pool(3));
+
+			assert_eq!(
+				fast_unstake_events_since_last_call(),
+				vec![
+					Event::ErasChecked { first_era: 3, last_era: 0, count: 4, ids: vec![0] },
+					Event::Unstaked {
+						stash: 1,
+						id: 0,
+						result: UnstakeOutcome::Success,
+						destination: UnstakeDestination::Pool(3),
+						payee: Some(1)
+					},
+					Event::BatchFinished
+				]
+			);
+			assert_eq!(PoolJoins::get(), vec![(1, 107, 3)]);
+		});
+	}
+
+	#[test]
+	fn failed_pool_join_keeps_funds_free() {
+		ExtBuilder::default().build_and_execute(|| {
+			RejectPoolJoins::set(Some(DispatchError::Other("pool join rejected")));
+			register_and_unstake(UnstakeDestination::Pool(3));
+
+			assert_eq!(
+				fast_unstake_events_since_last_call(),
+				vec![
+					Event::ErasChecked { first_era: 3, last_era: 0, count: 4, ids: vec![0] },
+					Event::Unstaked {
+						stash: 1,
+						id: 0,
+						result: UnstakeOutcome::PoolJoinFailed(PoolJoinError::Other(
+							DispatchError::Other("pool join rejected")
+						)),
+						destination: UnstakeDestination::Free,
+						payee: Some(1)
+					},
+					Event::BatchFinished
+				]
+			);
+			assert!(PoolJoins::get().is_empty());
+			assert_eq!(Balances::free_balance(1), 214);
+		});
+	}
+
+	#[test]
+	fn failed_pool_join_reports_known_pool_errors() {
+		for (error, expected) in [
+			(MockPoolError::PoolNotFound, PoolJoinError::PoolNotFound),
+			(MockPoolError::MinimumBondNotMet, PoolJoinError::MinimumBondNotMet),
+			(MockPoolError::MaxPoolMembers, PoolJoinError::MaxPoolMembers),
+		] {
+			ExtBuilder::default().build_and_execute(|| {
+				RejectPoolJoins::set(Some(error.into()));
+				register_and_unstake(UnstakeDestination::Pool(3));
+
+				let unstaked = RuntimeEvent::FastUnstake(Event::Unstaked {
+					stash: 1,
+					id: 0,
+					result: UnstakeOutcome::PoolJoinFailed(expected.clone()),
+					destination: UnstakeDestination::Free,
+					payee: Some(1),
+				});
+				assert!(System::events().into_iter().any(|r| r.event == unstaked));
+				assert_eq!(Balances::free_balance(1), 214);
+			});
+		}
+	}
+
+	#[test]
+	fn pool_join_error_decoding() {
+		// known errors are named, whatever their message.
+		let with_message = |error: MockPoolError| match DispatchError::from(error) {
+			DispatchError::Module(e) =>
+				DispatchError::Module(ModuleError { message: Some("Message"), ..e }),
+			_ => unreachable!(),
+		};
+		assert_eq!(
+			MockPools::join_error(with_message(MockPoolError::PoolNotFound)),
+			PoolJoinError::PoolNotFound
+		);
+		assert_eq!(
+			MockPools::join_error(with_message(MockPoolError::MinimumBondNotMet)),
+			PoolJoinError::MinimumBondNotMet
+		);
+		assert_eq!(
+			MockPools::join_error(with_message(MockPoolError::MaxPoolMembers)),
+			PoolJoinError::MaxPoolMembers
+		);
+
+		// anything else is reported as is.
+		let unknown =
+			DispatchError::Module(ModuleError { index: 42, error: [7, 0, 0, 0], message: None });
+		assert_eq!(MockPools::join_error(unknown), PoolJoinError::Other(unknown));
+		let other_pallet =
+			DispatchError::Module(ModuleError { index: 43, error: [0, 0, 0, 0], message: None });
+		assert_eq!(MockPools::join_error(other_pallet), PoolJoinError::Other(other_pallet));
+	}
+
+	#[test]
+	fn account_receives_unstaked_funds() {
+		ExtBuilder::default().build_and_execute(|| {
+			register_and_unstake(UnstakeDestination::Account(42));
+
+			assert_eq!(
+				fast_unstake_events_since_last_call(),
+				vec![
+					Event::ErasChecked { first_era: 3, last_era: 0, count: 4, ids: vec![0] },
+					Event::Unstaked {
+						stash: 1,
+						id: 0,
+						result: UnstakeOutcome::Success,
+						destination: UnstakeDestination::Account(42),
+						payee: Some(1)
+					},
+					Event::BatchFinished
+				]
+			);
+			assert_eq!(Balances::free_balance(1), 107);
+			assert_eq!(Balances::free_balance(42), 107);
+		});
+	}
+
+	#[test]
+	fn transfer_below_existential_deposit_keeps_funds_free() {
+		ExtBuilder::default().build_and_execute(|| {
+			// the stake of 107 cannot create account 42.
+			ExistentialDeposit::set(200);
+			register_and_unstake(UnstakeDestination::Account(42));
+
+			assert_eq!(
+				fast_unstake_events_since_last_call(),
+				vec![
+					Event::ErasChecked { first_era: 3, last_era: 0, count: 4, ids: vec![0] },
+					Event::Unstaked {
+						stash: 1,
+						id: 0,
+						result: UnstakeOutcome::TransferFailed,
+						destination: UnstakeDestination::Free,
+						payee: Some(1)
+					},
+					Event::BatchFinished
+				]
+			);
+			assert_eq!(Balances::free_balance(1), 214);
+			assert_eq!(Balances::free_balance(42), 0);
+		});
+	}
+
+	#[test]
+	fn expired_head_keeps_destination_when_requeued() {
+		ExtBuilder::default()
+			.max_head_blocks(1)
+			.requeue_expired(true)
+			.build_and_execute(|| {
+				ErasToCheckPerBlock::<T>::put(1);
+				CurrentEra::<T>::put(BondingDuration::get());
+				assert_ok!(FastUnstake::register_fast_unstake(
+					RuntimeOrigin::signed(2),
+					UnstakeDestination::Account(42)
+				));
+
+				next_block(true);
+				assert_eq!(
+					Head::<T>::get().map(|head| head.stashes.into_inner()),
+					Some(vec![in_head(1, UnstakeDestination::Account(42), 0, 107)])
+				);
+
+				Ongoing::set(true);
+				run_to_block(4, true);
+
+				assert_eq!(Head::<T>::get(), None);
+				assert_eq!(Queue::<T>::get(1), Some(queued(UnstakeDestination::Account(42), 4, 0)));
+			});
+	}
+
+	#[test]
+	fn migration_to_v2_keeps_funds_free() {
+		use frame_support::{
+			storage::unhashed,
+			traits::{GetStorageVersion, OnRuntimeUpgrade, StorageVersion},
+		};
+
+		ExtBuilder::default().build_and_execute(|| {
+			StorageVersion::new(1).put::<FastUnstake>();
+			// a queued stash and a head, in their v1 layout.
+			Queue::<T>::insert(3, queued(UnstakeDestination::Free, 1, 0));
+			unhashed::put(&Queue::<T>::hashed_key_for(3), &Deposit::get());
+			let old_head: (Vec<(AccountId, Balance)>, Vec<u32>) =
+				(vec![(1, Deposit::get())], vec![3]);
+			unhashed::put(&Head::<T>::hashed_key(), &old_head);
+
+			migrations::v2::MigrateToV2::<T>::on_runtime_upgrade();
+
+			// the queue is left in its v2 layout.
+			assert_eq!(FastUnstake::on_chain_storage_version(), 2);
+			assert_eq!(
+				unhashed::get(&Queue::<T>::hashed_key_for(3)),
+				Some((Deposit::get(), UnstakeDestination::<AccountId>::Free))
+			);
+			assert_eq!(Queue::<T>::count(), 1);
+			// the head is left in its v2 layout.
+			let v2_head: (Vec<(AccountId, Balance, UnstakeDestination<AccountId>)>, Vec<u32>) =
+				(vec![(1, Deposit::get(), UnstakeDestination::Free)], vec![3]);
+			assert_eq!(unhashed::get(&Head::<T>::hashed_key()), Some(v2_head));
+		});
+	}
+
+	#[test]
+	fn migration_to_v3_records_head_since() {
+		use frame_support::{
+			storage::unhashed,
+			traits::{GetStorageVersion, OnRuntimeUpgrade, StorageVersion},
+		};
+
+		ExtBuilder::default().build_and_execute(|| {
+			StorageVersion::new(2).put::<FastUnstake>();
+			// a head in its v2 layout.
+			let old_head: (Vec<(AccountId, Balance, UnstakeDestination<AccountId>)>, Vec<u32>) =
+				(vec![(1, Deposit::get(), UnstakeDestination::Account(42))], vec![3, 2]);
+			unhashed::put(&Head::<T>::hashed_key(), &old_head);
+			assert_eq!(FastUnstake::head_since_block(), None);
+
+			run_to_block(5, false);
+			migrations::v3::MigrateToV3::<T>::on_runtime_upgrade();
+
+			// the head is considered to have started at the block of the migration. It is left in
+			// its v4 layout.
+			assert_eq!(FastUnstake::on_chain_storage_version(), 3);
+			let v4_head: (
+				Vec<(AccountId, Balance, UnstakeDestination<AccountId>)>,
+				Vec<u32>,
+				BlockNumber,
+			) = (vec![(1, Deposit::get(), UnstakeDestination::Account(42))], vec![3, 2], 5);
+			assert_eq!(unhashed::get(&Head::<T>::hashed_key()), Some(v4_head));
+		});
+	}
+
+	#[test]
+	fn head_since_block_is_set_on_promotion() {
+		ExtBuilder::default().build_and_execute(|| {
+			ErasToCheckPerBlock::<T>::put(1);
+			CurrentEra::<T>::put(BondingDuration::get());
+			assert_ok!(FastUnstake::register_fast_unstake(
+				RuntimeOrigin::signed(2),
+				UnstakeDestination::Free
+			));
+			assert_eq!(FastUnstake::head_since_block(), None);
+
+			run_to_block(3, false);
+			next_block(true);
+			assert_eq!(FastUnstake::head_since_block(), Some(4));
+
+			// it is kept while the head is being processed.
+			next_block(true);
+			next_block(true);
+			assert_eq!(FastUnstake::head_since_block(), Some(4));
+
+			// and cleared once it is done.
+			next_block(true);
+			next_block(true);
+			assert_eq!(Head::<T>::get(), None);
+			assert_eq!(FastUnstake::head_since_block(), None);
+		});
+	}
+}
+
+mod queue {
+	use super::*;
+
+	#[test]
+	fn registration_block_is_recorded() {
+		ExtBuilder::default().build_and_execute(|| {
+			ErasToCheckPerBlock::<T>::put(1);
+
+			run_to_block(5, false);
+			assert_ok!(FastUnstake::register_fast_unstake(
+				RuntimeOrigin::signed(2),
+				UnstakeDestination::Free
+			));
+			run_to_block(7, false);
+			assert_ok!(FastUnstake::register_fast_unstake(
+				RuntimeOrigin::signed(4),
+				UnstakeDestination::Pool(1)
+			));
+
+			assert_eq!(Queue::<T>::get(1), Some(queued(UnstakeDestination::Free, 5, 0)));
+			assert_eq!(Queue::<T>::get(3), Some(queued(UnstakeDestination::Pool(1), 7, 1)));
+		});
+	}
+
+	#[test]
+	fn queue_page_works() {
+		ExtBuilder::default().build_and_execute(|| {
+			ErasToCheckPerBlock::<T>::put(1);
+			assert_eq!(FastUnstake::queue_page(0, 10), vec![]);
+
+			assert_ok!(FastUnstake::register_fast_unstake(
+				RuntimeOrigin::signed(2),
+				UnstakeDestination::Free
+			));
+			next_block(false);
+			assert_ok!(FastUnstake::register_fast_unstake(
+				RuntimeOrigin::signed(4),
+				UnstakeDestination::Account(42)
+			));
+
+			// pages follow the order of the storage keys.
+			let all = FastUnstake::queue_page(0, 10);
+			assert_eq!(all.iter().map(|(stash, _)| *stash).collect::<Vec<_>>(), {
+				let mut keys = Queue::<T>::iter_keys().collect::<Vec<_>>();
+				keys.sort_by_key(|k| Queue::<T>::hashed_key_for(k));
+				keys
+			});
+			assert!(all.contains(&(1, queued(UnstakeDestination::Free, 1, 0))));
+			assert!(all.contains(&(3, queued(UnstakeDestination::Account(42), 2, 1))));
+
+			assert_eq!(FastUnstake::queue_page(0, 1), all[..1].to_vec());
+			assert_eq!(FastUnstake::queue_page(1, 1), all[1..].to_vec());
+			assert_eq!(FastUnstake::queue_page(1, 10), all[1..].to_vec());
+			assert_eq!(FastUnstake::queue_page(2, 10), vec![]);
+			assert_eq!(FastUnstake::queue_page(0, 0), vec![]);
+		});
+	}
+
+	#[test]
+	fn telemetry_follows_queue_and_head() {
+		ExtBuilder::default().build_and_execute(|| {
+			assert_eq!(
+				FastUnstake::telemetry(),
+				FastUnstakeTelemetry { can_renominate_later: true, ..Default::default() }
+			);
+
+			ErasToCheckPerBlock::<T>::put(1);
+			CurrentEra::<T>::put(BondingDuration::get());
+			assert_ok!(FastUnstake::register_fast_unstake(
+				RuntimeOrigin::signed(2),
+				UnstakeDestination::Free
+			));
+			assert_ok!(FastUnstake::register_fast_unstake(
+				RuntimeOrigin::signed(4),
+				UnstakeDestination::Free
+			));
+			assert_eq!(
+				FastUnstake::telemetry(),
+				FastUnstakeTelemetry {
+					queue_len: 2,
+					head_progress: None,
+					eras_to_check: 1,
+					can_renominate_later: true
+				}
+			);
+
+			// one stash is promoted to the head, and checked one era per block.
+			next_block(true);
+			assert_eq!(
+				FastUnstake::telemetry(),
+				FastUnstakeTelemetry {
+					queue_len: 1,
+					head_progress: Some((1, 4)),
+					eras_to_check: 1,
+					can_renominate_later: true
+				}
+			);
+
+			next_block(true);
+			assert_eq!(
+				FastUnstake::telemetry(),
+				FastUnstakeTelemetry {
+					queue_len: 1,
+					head_progress: Some((2, 4)),
+					eras_to_check: 1,
+					can_renominate_later: true
+				}
+			);
+		});
+	}
+
+	#[test]
+	fn telemetry_only_counts_eras_in_range() {
+		ExtBuilder::default().build_and_execute(|| {
+			// early in the chain, there are less eras to check than the bonding duration.
+			ErasToCheckPerBlock::<T>::put(1);
+			CurrentEra::<T>::put(1);
+			assert_ok!(FastUnstake::register_fast_unstake(
+				RuntimeOrigin::signed(2),
+				UnstakeDestination::Free
+			));
+			next_block(true);
+			assert_eq!(FastUnstake::telemetry().head_progress, Some((1, 2)));
+
+			next_block(true);
+			assert_eq!(FastUnstake::telemetry().head_progress, Some((2, 2)));
+			assert_eq!(Head::<T>::get().unwrap().checked, bounded_vec![1, 0]);
+
+			// era 0 is no longer relevant once enough eras have passed.
+			CurrentEra::<T>::put(BondingDuration::get() + 1);
+			assert_eq!(FastUnstake::telemetry().head_progress, Some((1, 4)));
+		});
+	}
+
+	#[test]
+	fn telemetry_warns_if_nominators_are_saturated() {
+		ExtBuilder::default().build_and_execute(|| {
+			let nominators = pallet_staking::Nominators::<T>::count();
+			assert_eq!(<Staking as StakingInterface>::nominators_count(), nominators);
+			assert_eq!(<Staking as StakingInterface>::max_nominators(), None);
+			assert!(FastUnstake::telemetry().can_renominate_later);
+
+			pallet_staking::MaxNominatorsCount::<T>::put(nominators + 1);
+			assert_eq!(<Staking as StakingInterface>::max_nominators(), Some(nominators + 1));
+			assert!(FastUnstake::telemetry().can_renominate_later);
+
+			// once the cap is reached, a stash that leaves may not be able to come back.
+			pallet_staking::MaxNominatorsCount::<T>::put(nominators);
+			assert!(!FastUnstake::telemetry().can_renominate_later);
+
+			// unstaking a nominator frees up a spot.
+			ErasToCheckPerBlock::<T>::put(BondingDuration::get() + 1);
+			CurrentEra::<T>::put(BondingDuration::get());
+			assert_ok!(FastUnstake::register_fast_unstake(
+				RuntimeOrigin::signed(2),
+				UnstakeDestination::Free
+			));
+			next_block(true);
+			next_block(true);
+			assert_unstaked(&1);
+			assert_eq!(<Staking as StakingInterface>::nominators_count(), nominators - 1);
+			assert!(FastUnstake::telemetry().can_renominate_later);
+		});
+	}
+
+	#[test]
+	fn migration_to_v4_records_registration_block() {
+		use frame_support::{
+			storage::unhashed,
+			traits::{GetStorageVersion, OnRuntimeUpgrade, StorageVersion},
+		};
+
+		ExtBuilder::default().build_and_execute(|| {
+			StorageVersion::new(3).put::<FastUnstake>();
+			// two queued stashes in their v3 layout.
+			for (stash, destination) in
+				[(1, UnstakeDestination::Free), (3, UnstakeDestination::Pool(1))]
+			{
+				Queue::<T>::insert(stash, queued(UnstakeDestination::Free, 1, 0));
+				unhashed::put(&Queue::<T>::hashed_key_for(stash), &(Deposit::get(), destination));
+			}
+
+			run_to_block(5, false);
+			migrations::v4::MigrateToV4::<T>::on_runtime_upgrade();
+
+			// the stashes are considered to have registered at the block of the migration. The
+			// queue is left in its v4 layout.
+			assert_eq!(FastUnstake::on_chain_storage_version(), 4);
+			for (stash, destination) in
+				[(1, UnstakeDestination::Free), (3, UnstakeDestination::Pool(1))]
+			{
+				assert_eq!(
+					unhashed::get(&Queue::<T>::hashed_key_for(stash)),
+					Some((Deposit::get(), destination, 5 as BlockNumber))
+				);
+			}
+			assert_eq!(Queue::<T>::count(), 2);
+		});
+	}
+
+	#[test]
+	fn migration_to_v5_assigns_request_ids() {
+		use frame_support::{
+			storage::unhashed,
+			traits::{GetStorageVersion, OnRuntimeUpgrade, StorageVersion},
+		};
+
+		ExtBuilder::default().batch(2).build_and_execute(|| {
+			StorageVersion::new(4).put::<FastUnstake>();
+			// a head and two queued stashes in their v4 layout.
+			let old_head: (
+				Vec<(AccountId, Balance, UnstakeDestination<AccountId>)>,
+				Vec<u32>,
+				BlockNumber,
+			) = (
+				vec![
+					(1, Deposit::get(), UnstakeDestination::Free),
+					(3, Deposit::get(), UnstakeDestination::Account(42)),
+				],
+				vec![3],
+				2,
+			);
+			unhashed::put(&Head::<T>::hashed_key(), &old_head);
+			for (stash, destination) in
+				[(5, UnstakeDestination::Free), (7, UnstakeDestination::Pool(1))]
+			{
+				Queue::<T>::insert(stash, queued(UnstakeDestination::Free, 1, 0));
+				unhashed::put(
+					&Queue::<T>::hashed_key_for(stash),
+					&(Deposit::get(), destination, 3 as BlockNumber),
+				);
+			}
+
+			migrations::v5::MigrateToV5::<T>::on_runtime_upgrade();
+
+			// the head gets the lowest ids, since it has been queued first. It is left in its v5
+			// layout.
+			assert_eq!(FastUnstake::on_chain_storage_version(), 5);
+			let v5_head: (
+				Vec<(AccountId, Balance, UnstakeDestination<AccountId>, RequestId)>,
+				Vec<u32>,
+				BlockNumber,
+			) = (
+				vec![
+					(1, Deposit::get(), UnstakeDestination::Free, 0),
+					(3, Deposit::get(), UnstakeDestination::Account(42), 1),
+				],
+				vec![3],
+				2,
+			);
+			assert_eq!(unhashed::get(&Head::<T>::hashed_key()), Some(v5_head));
+			// the queue gets the following ones, in the order of its storage keys. It is left in
+			// its v5 layout.
+			let v5_entry = |stash| {
+				unhashed::get::<(Balance, UnstakeDestination<AccountId>, BlockNumber, RequestId)>(
+					&Queue::<T>::hashed_key_for(stash),
+				)
+				.unwrap()
+			};
+			let mut ids = [5, 7].map(|stash| v5_entry(stash).3);
+			ids.sort();
+			assert_eq!(ids, [2, 3]);
+			let id_of_5 = v5_entry(5).3;
+			assert_eq!(v5_entry(5), (Deposit::get(), UnstakeDestination::Free, 3, id_of_5));
+			assert_eq!(Queue::<T>::count(), 2);
+
+			// and registrations continue after the highest id.
+			assert_eq!(NextRequestId::<T>::get(), 4);
+		});
+	}
+
+	#[test]
+	fn migration_to_v6_records_ledger_totals() {
+		use frame_support::{
+			storage::unhashed,
+			traits::{GetStorageVersion, OnRuntimeUpgrade, StorageVersion},
+		};
+
+		ExtBuilder::default().batch(2).build_and_execute(|| {
+			StorageVersion::new(5).put::<FastUnstake>();
+			// the stashes are fully unbonded, 1 has been paid a reward to its stake since.
+			for stash in [1, 3, 5] {
+				assert_ok!(Staking::fully_unbond(&stash));
+			}
+			pallet_staking::Ledger::<T>::mutate(2, |maybe_ledger| {
+				let ledger = maybe_ledger.as_mut().unwrap();
+				ledger.total += 10;
+				ledger.active += 10;
+			});
+			// a head and a queued stash in their v5 layout.
+			let old_head: (
+				Vec<(AccountId, Balance, UnstakeDestination<AccountId>, RequestId)>,
+				Vec<u32>,
+				BlockNumber,
+			) = (
+				vec![
+					(1, Deposit::get(), UnstakeDestination::Free, 0),
+					(3, Deposit::get(), UnstakeDestination::Account(42), 1),
+				],
+				vec![3],
+				2,
+			);
+			unhashed::put(&Head::<T>::hashed_key(), &old_head);
+			Queue::<T>::insert(5, queued(UnstakeDestination::Free, 1, 0));
+			unhashed::put(
+				&Queue::<T>::hashed_key_for(5),
+				&(Deposit::get(), UnstakeDestination::Pool(1), 3 as BlockNumber, 2 as RequestId),
+			);
+
+			migrations::v6::MigrateToV6::<T>::on_runtime_upgrade();
+
+			// the current unlocking stake of every stash is recorded. The queue and the head are
+			// left in their v6 layout.
+			assert_eq!(FastUnstake::on_chain_storage_version(), 6);
+			let v6_head: (
+				Vec<(AccountId, Balance, UnstakeDestination<AccountId>, RequestId, Balance)>,
+				Vec<u32>,
+				BlockNumber,
+			) = (
+				vec![
+					(1, Deposit::get(), UnstakeDestination::Free, 0, 107),
+					(3, Deposit::get(), UnstakeDestination::Account(42), 1, 107),
+				],
+				vec![3],
+				2,
+			);
+			assert_eq!(unhashed::get(&Head::<T>::hashed_key()), Some(v6_head));
+			assert_eq!(
+				unhashed::get(&Queue::<T>::hashed_key_for(5)),
+				Some((
+					Deposit::get(),
+					UnstakeDestination::Pool(1),
+					3 as BlockNumber,
+					2 as RequestId,
+					107 as Balance
+				))
+			);
+			assert_eq!(Queue::<T>::count(), 1);
+		});
+	}
+
+	#[test]
+	fn migration_to_v7_records_active_and_total_stake() {
+		use frame_support::{
+			storage::unhashed,
+			traits::{GetStorageVersion, OnRuntimeUpgrade, StorageVersion},
+		};
+
+		ExtBuilder::default().batch(2).build_and_execute(|| {
+			StorageVersion::new(6).put::<FastUnstake>();
+			// the stashes are fully unbonded, 1 has been paid a reward to its stake since.
+			for stash in [1, 3, 5] {
+				assert_ok!(Staking::fully_unbond(&stash));
+			}
+			pallet_staking::Ledger::<T>::mutate(2, |maybe_ledger| {
+				let ledger = maybe_ledger.as_mut().unwrap();
+				ledger.total += 10;
+				ledger.active += 10;
+			});
+			// a head and a queued stash in their v6 layout.
+			let old_head: (
+				Vec<(AccountId, Balance, UnstakeDestination<AccountId>, RequestId, Balance)>,
+				Vec<u32>,
+				BlockNumber,
+			) = (
+				vec![
+					(1, Deposit::get(), UnstakeDestination::Free, 0, 107),
+					(3, Deposit::get(), UnstakeDestination::Account(42), 1, 107),
+				],
+				vec![3],
+				2,
+			);
+			unhashed::put(&Head::<T>::hashed_key(), &old_head);
+			Queue::<T>::insert(5, queued(UnstakeDestination::Free, 1, 0));
+			unhashed::put(
+				&Queue::<T>::hashed_key_for(5),
+				&(
+					Deposit::get(),
+					UnstakeDestination::Pool(1),
+					3 as BlockNumber,
+					2 as RequestId,
+					107 as Balance,
+				),
+			);
+
+			migrations::v7::MigrateToV7::<T>::on_runtime_upgrade();
+
+			// the current active and total stake of every stash is recorded.
+			assert_eq!(FastUnstake::on_chain_storage_version(), 7);
+			assert_eq!(
+				Head::<T>::get(),
+				Some(UnstakeRequest {
+					stashes: bounded_vec![
+						HeadEntry {
+							ledger_active: 10,
+							ledger_total: 117,
+							..in_head(1, UnstakeDestination::Free, 0, 0)
+						},
+						in_head(3, UnstakeDestination::Account(42), 1, 107),
+					],
+					checked: bounded_vec![3],
+					since: 2,
+				})
+			);
+			assert_eq!(Queue::<T>::get(5), Some(queued(UnstakeDestination::Pool(1), 3, 2)));
+			assert_eq!(Queue::<T>::count(), 1);
+		});
+	}
+
+	#[test]
+	fn migration_to_v8_removes_head_since() {
+		use frame_support::traits::{GetStorageVersion, OnRuntimeUpgrade, StorageVersion};
+
+		ExtBuilder::default().build_and_execute(|| {
+			StorageVersion::new(7).put::<FastUnstake>();
+			migrations::v8::HeadSince::<T>::put(3);
+
+			migrations::v8::MigrateToV8::<T>::on_runtime_upgrade();
+
+			assert_eq!(FastUnstake::on_chain_storage_version(), 8);
+			assert!(!migrations::v8::HeadSince::<T>::exists());
+		});
+	}
+}
+
+mod state_machine {
+	//! Randomized tests that drive the pallet through arbitrary sequences of operations and check
+	//! its invariants after each of them.
+	//!
+	//! The operations are generated from a fixed set of seeds, such that any failure is
+	//! reproducible. A failing sequence is shrunk to the smallest subsequence that still fails
+	//! before being printed.
+	use super::*;
+	use frame_support::storage::with_storage_layer;
+	use std::collections::BTreeSet;
+
+	const SEEDS: u64 = 64;
+	const OPS_PER_SEED: usize = 64;
+	const STASHES: [AccountId; 5] = [1, 3, 5, 7, 9];
+
+	/// A xorshift generator, enough to get reproducible sequences of operations from a seed.
+	struct Rng(u64);
+
+	impl Rng {
+		fn new(seed: u64) -> Self {
+			Self(seed.wrapping_mul(0x9E37_79B9_7F4A_7C15) | 1)
+		}
+
+		fn below(&mut self, n: u64) -> u64 {
+			self.0 ^= self.0 << 13;
+			self.0 ^= self.0 >> 7;
+			self.0 ^= self.0 << 17;
+			self.0 % n
+		}
+
+		fn stash(&mut self) -> AccountId {
+			STASHES[self.below(STASHES.len() as u64) as usize]
+		}
+	}
+
+	#[derive(Clone, Debug)]
+	enum Op {
+		/// Move to the next block and run `on_idle` with the given `ref_time` budget.
+		NextBlock(u64),
+		/// Start a new era, in which the given stash is exposed, if any.
+		BumpEra(Option<AccountId>),
+		/// Start or stop the ongoing election.
+		ToggleElection,
+		/// Register the given controller for fast-unstake.
+		Register(AccountId),
+		/// Deregister the given controller from fast-unstake.
+		Deregister(AccountId),
+		/// Change `ErasToCheckPerBlock`.
+		SetErasToCheck(u32),
+	}
+
+	fn generate(seed: u64) -> Vec<Op> {
+		let mut rng = Rng::new(seed);
+		let max_ref_time = BlockWeights::get().max_block.ref_time();
+		(0..OPS_PER_SEED)
+			.map(|_| match rng.below(20) {
+				// small budgets only fit a handful of eras, if any.
+				0..=3 => Op::NextBlock(rng.below(max_ref_time / 50)),
+				4..=7 => Op::NextBlock(rng.below(max_ref_time + 1)),
+				8..=10 => Op::BumpEra(if rng.below(3) == 0 { Some(rng.stash()) } else { None }),
+				11 => Op::ToggleElection,
+				12..=15 => Op::Register(rng.stash() + 1),
+				16..=17 => Op::Deregister(rng.stash() + 1),
+				_ => Op::SetErasToCheck(rng.below(4) as u32),
+			})
+			.collect()
+	}
+
+	fn apply(op: &Op) -> Result<(), String> {
+		match op {
+			Op::NextBlock(ref_time) => {
+				next_block(false);
+				let budget =
+					Weight::from_parts(*ref_time, BlockWeights::get().max_block.proof_size());
+				let consumed = FastUnstake::on_idle(System::block_number(), budget);
+				if !consumed.all_lte(budget) {
+					return Err(format!("on_idle consumed {:?} out of {:?}", consumed, budget))
+				}
+			},
+			Op::BumpEra(exposed) => {
+				let era = <Staking as StakingInterface>::current_era() + 1;
+				CurrentEra::<T>::put(era);
+				ExtBuilder::register_stakers_for_era(era);
+				if let Some(stash) = exposed {
+					pallet_staking::ErasStakers::<T>::mutate(era, VALIDATOR_PREFIX, |expo| {
+						expo.others
+							.push(pallet_staking::IndividualExposure { who: *stash, value: 0 })
+					});
+				}
+			},
+			Op::ToggleElection => Ongoing::set(!Ongoing::get()),
+			Op::Register(ctrl) => {
+				// failing calls are expected, but must not leave any changes behind.
+				let _ = with_storage_layer(|| {
+					FastUnstake::register_fast_unstake(
+						RuntimeOrigin::signed(*ctrl),
+						UnstakeDestination::Free,
+					)
+				});
+			},
+			Op::Deregister(ctrl) => {
+				let _ =
+					with_storage_layer(|| FastUnstake::deregister(RuntimeOrigin::signed(*ctrl)));
+			},
+			Op::SetErasToCheck(eras) => {
+				FastUnstake::set_eras_to_check(RuntimeOrigin::root(), *eras)
+					.map_err(|e| format!("set_eras_to_check failed: {:?}", e))?;
+			},
+		}
+		Ok(())
+	}
+
+	fn run(batch: u32, ops: &[Op]) -> Result<(), String> {
+		ExtBuilder::default().batch(batch).build().execute_with(|| {
+			Ongoing::set(false);
+			ErasToCheckPerBlock::<T>::put(1);
+
+			// the era in which the head was last checked. Eras that left the bonding window since
+			// are only pruned from `checked` the next time the head is checked.
+			let mut checked_in_era = None;
+
+			for (index, op) in ops.iter().enumerate() {
+				let seen = System::events().len();
+				apply(op).map_err(|e| format!("op #{}: {}", index, e))?;
+
+				let current_era = <Staking as StakingInterface>::current_era();
+				let bonding_duration = BondingDuration::get();
+				let events = System::events().into_iter().skip(seen).filter_map(|r| {
+					if let RuntimeEvent::FastUnstake(e) = r.event {
+						Some(e)
+					} else {
+						None
+					}
+				});
+
+				for event in events {
+					match event {
+						Event::Unstaked { stash, .. } => {
+							let oldest = current_era.saturating_sub(bonding_duration);
+							if let Some(era) = (oldest..=current_era).find(|era| {
+								<Staking as StakingInterface>::is_exposed_in_era(&stash, era)
+							}) {
+								return Err(format!(
+									"op #{}: {} unstaked while exposed in era {}",
+									index, stash, era
+								))
+							}
+						},
+						Event::ErasChecked { .. } => checked_in_era = Some(current_era),
+						Event::InternalError => {
+							return Err(format!("op #{}: internal error", index))
+						},
+						_ => (),
+					}
+				}
+
+				if let Some(head) = Head::<T>::get() {
+					let oldest =
+						checked_in_era.unwrap_or(current_era).saturating_sub(bonding_duration);
+					if head.checked.iter().any(|era| *era < oldest || *era > current_era) {
+						return Err(format!(
+							"op #{}: checked eras {:?} not within {}..={}",
+							index, head.checked, oldest, current_era
+						))
+					}
+					if head.checked.iter().collect::<BTreeSet<_>>().len() != head.checked.len() {
+						return Err(format!(
+							"op #{}: checked eras {:?} contain duplicates",
+							index, head.checked
+						))
+					}
+					if let Some(entry) =
+						head.stashes.iter().find(|entry| Queue::<T>::contains_key(&entry.stash))
+					{
+						return Err(format!(
+							"op #{}: {} is both queued and in head",
+							index, entry.stash
+						))
+					}
+				}
+			}
+			Ok(())
+		})
+	}
+
+	/// Greedily drop operations from `ops` as long as the remaining sequence still fails.
+	fn shrink(batch: u32, mut ops: Vec<Op>) -> Vec<Op> {
+		let mut index = 0;
+		while index < ops.len() {
+			let mut candidate = ops.clone();
+			candidate.remove(index);
+			if run(batch, &candidate).is_err() {
+				ops = candidate;
+			} else {
+				index += 1;
+			}
+		}
+		ops
+	}
+
+	#[test]
+	fn random_operations_uphold_invariants() {
+		for seed in 0..SEEDS {
+			let batch = 1 + (seed % 3) as u32;
+			let ops = generate(seed);
+			if run(batch, &ops).is_err() {
+				let ops = shrink(batch, ops);
+				panic!(
+					"seed {} (batch size {}) failed with: {}\nminimal sequence of operations: {:#?}",
+					seed,
+					batch,
+					run(batch, &ops).unwrap_err(),
+					ops
+				);
+			}
+		}
 	}
 }
@@ -20,22 +20,185 @@
 use crate::{Config, MaxChecking};
 use codec::{Decode, Encode, MaxEncodedLen};
 use frame_support::{
-	traits::Currency, BoundedVec, EqNoBound, PartialEqNoBound, RuntimeDebugNoBound,
+	traits::Currency, BoundedVec, EqNoBound, PartialEqNoBound, RuntimeDebug, RuntimeDebugNoBound,
 };
 use scale_info::TypeInfo;
+use sp_runtime::{DispatchError, DispatchResult};
 use sp_staking::EraIndex;
 use sp_std::prelude::*;
 
 pub type BalanceOf<T> =
 	<<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::Balance;
+
+/// Identifier of a nomination pool.
+pub type PoolId = u32;
+
+/// Identifier of an unstake request, assigned upon registration.
+///
+/// All events about a request carry its identifier, such that its lifecycle can be followed.
+pub type RequestId = u64;
+
+/// Where the funds of a stash should go once it has been unstaked.
+#[derive(Encode, Decode, Eq, PartialEq, Clone, TypeInfo, RuntimeDebug, MaxEncodedLen)]
+pub enum UnstakeDestination<AccountId> {
+	/// The funds remain free in the stash.
+	Free,
+	/// The stash joins the given nomination pool with its funds.
+	Pool(PoolId),
+	/// The funds are transferred to the given account.
+	Account(AccountId),
+}
+
+impl<AccountId> Default for UnstakeDestination<AccountId> {
+	fn default() -> Self {
+		Self::Free
+	}
+}
+
+/// Why a stash could not join the nomination pool it asked for, see [`PoolJoiner::join_error`].
+#[derive(Encode, Decode, Eq, PartialEq, Clone, TypeInfo, RuntimeDebug)]
+pub enum PoolJoinError {
+	/// The pool does not exist.
+	PoolNotFound,
+	/// The unstaked funds are below the minimum bond to join a pool.
+	MinimumBondNotMet,
+	/// The pool, or the pools system as a whole, has reached its maximum number of members.
+	MaxPoolMembers,
+	/// Any other error, as reported by the pools system.
+	Other(DispatchError),
+}
+
+/// What happened to the funds of a stash once it has been unstaked, as reported by
+/// `Event::Unstaked`.
+#[derive(Encode, Decode, Eq, PartialEq, Clone, TypeInfo, RuntimeDebug)]
+pub enum UnstakeOutcome {
+	/// The funds reached their destination.
+	Success,
+	/// The stash could not join the requested pool, its funds remain free.
+	PoolJoinFailed(PoolJoinError),
+	/// The funds could not be transferred to the requested account, they remain free in the
+	/// stash.
+	TransferFailed,
+	/// The staking system refused to unstake the stash.
+	UnstakeFailed(DispatchError),
+}
+
+/// Something that can make an account join a nomination pool.
+pub trait PoolJoiner<AccountId, Balance> {
+	/// Make `who` join `pool_id` with `amount` of its free balance.
+	fn join(who: &AccountId, amount: Balance, pool_id: PoolId) -> DispatchResult;
+
+	/// Tell which [`PoolJoinError`] the `error` returned by [`PoolJoiner::join`] is.
+	///
+	/// Errors that are not known to the implementation are reported as [`PoolJoinError::Other`].
+	fn join_error(error: DispatchError) -> PoolJoinError {
+		PoolJoinError::Other(error)
+	}
+
+	/// Create an open pool that accounts can join, returning its id.
+	///
+	/// Only used to set up the worst case of the `on_idle_unstake_with_pool_join` benchmark.
+	#[cfg(feature = "runtime-benchmarks")]
+	fn setup_open_pool() -> PoolId {
+		Default::default()
+	}
+}
+
+impl<AccountId, Balance> PoolJoiner<AccountId, Balance> for () {
+	fn join(_: &AccountId, _: Balance, _: PoolId) -> DispatchResult {
+		Err(DispatchError::Other("no pools configured"))
+	}
+}
+
+/// Something that needs to know when a stash has been fully unstaked through this pallet.
+pub trait OnFastUnstakeComplete<AccountId, Balance> {
+	/// `stash` has been unstaked and `amount` of its funds are no longer bonded.
+	///
+	/// Called regardless of whether the funds were then moved to their destination.
+	fn on_unstaked(stash: &AccountId, amount: Balance);
+}
+
+#[impl_trait_for_tuples::impl_for_tuples(30)]
+impl<AccountId, Balance: Clone> OnFastUnstakeComplete<AccountId, Balance> for Tuple {
+	fn on_unstaked(stash: &AccountId, amount: Balance) {
+		for_tuples!( #( Tuple::on_unstaked(stash, amount.clone()); )* );
+	}
+}
+
+/// A stash waiting in the `Queue` to be unstaked.
+#[derive(
+	Encode, Decode, EqNoBound, PartialEqNoBound, Clone, TypeInfo, RuntimeDebugNoBound, MaxEncodedLen,
+)]
+#[scale_info(skip_type_params(T))]
+pub struct QueueEntry<T: Config> {
+	/// The deposit reserved from the stash upon registration.
+	pub deposit: BalanceOf<T>,
+	/// Where the funds of the stash should go once it has been unstaked.
+	pub destination: UnstakeDestination<T::AccountId>,
+	/// The block at which the stash entered the queue.
+	///
+	/// For a stash that has been put back into the queue after its batch expired, this is the
+	/// block at which it was requeued.
+	pub registered_at: T::BlockNumber,
+	/// The identifier of the request, which is kept when it is requeued.
+	pub id: RequestId,
+	/// The active stake of the stash upon registration.
+	pub ledger_active: BalanceOf<T>,
+	/// The total stake of the stash upon registration.
+	///
+	/// The stash is not unstaked if its active or total stake differs from the recorded one by the
+	/// time it is due.
+	pub ledger_total: BalanceOf<T>,
+}
+
+/// A stash that is being processed as part of an [`UnstakeRequest`].
+///
+/// This is the [`QueueEntry`] of the stash once it has left the `Queue`.
+#[derive(
+	Encode, Decode, EqNoBound, PartialEqNoBound, Clone, TypeInfo, RuntimeDebugNoBound, MaxEncodedLen,
+)]
+#[scale_info(skip_type_params(T))]
+pub struct HeadEntry<T: Config> {
+	/// The stash to unstake.
+	pub stash: T::AccountId,
+	/// The deposit reserved from the stash upon registration.
+	pub deposit: BalanceOf<T>,
+	/// Where the funds of the stash should go once it has been unstaked.
+	pub dest: UnstakeDestination<T::AccountId>,
+	/// The identifier of the request.
+	pub id: RequestId,
+	/// The active stake of the stash upon registration.
+	pub ledger_active: BalanceOf<T>,
+	/// The total stake of the stash upon registration, see [`QueueEntry::ledger_total`].
+	pub ledger_total: BalanceOf<T>,
+}
+
 /// An unstake request.
 #[derive(
 	Encode, Decode, EqNoBound, PartialEqNoBound, Clone, TypeInfo, RuntimeDebugNoBound, MaxEncodedLen,
 )]
 #[scale_info(skip_type_params(T))]
 pub struct UnstakeRequest<T: Config> {
-	/// This list of stashes being processed in this request, and their corresponding deposit.
-	pub(crate) stashes: BoundedVec<(T::AccountId, BalanceOf<T>), T::BatchSize>,
+	/// This list of stashes being processed in this request.
+	pub(crate) stashes: BoundedVec<HeadEntry<T>, T::BatchSize>,
 	/// The list of eras for which they have been checked.
 	pub(crate) checked: BoundedVec<EraIndex, MaxChecking<T>>,
+	/// The block number at which this request was promoted from the `Queue` to the `Head`.
+	pub(crate) since: T::BlockNumber,
+}
+
+/// A summary of the state of the pallet, cheap enough to be queried by a node at every block.
+#[derive(Encode, Decode, Clone, Default, PartialEq, Eq, TypeInfo, RuntimeDebug)]
+pub struct FastUnstakeTelemetry {
+	/// The number of stashes waiting in the `Queue`.
+	pub queue_len: u32,
+	/// The number of eras the current `Head` has been checked for, and the number of eras it has
+	/// to be checked for in total. `None` if there is no head.
+	pub head_progress: Option<(u32, u32)>,
+	/// The number of eras checked per block, as per `ErasToCheckPerBlock`.
+	pub eras_to_check: u32,
+	/// Whether there is room for another nominator in staking.
+	///
+	/// If not, a stash that fast-unstakes now may not be able to nominate again later on.
+	pub can_renominate_later: bool,
 }
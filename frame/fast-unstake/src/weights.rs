@@ -48,10 +48,15 @@ use sp_std::marker::PhantomData;
 /// Weight functions needed for pallet_fast_unstake.
 pub trait WeightInfo {
 	fn on_idle_unstake() -> Weight;
+	fn on_idle_unstake_with_pool_join() -> Weight;
 	fn on_idle_check(x: u32, ) -> Weight;
+	fn queue_promotion() -> Weight;
 	fn register_fast_unstake() -> Weight;
 	fn deregister() -> Weight;
-	fn control() -> Weight;
+	fn deregister_from_head() -> Weight;
+	fn set_eras_to_check() -> Weight;
+	fn add_to_denylist() -> Weight;
+	fn remove_from_denylist() -> Weight;
 }
 
 /// Weights for pallet_fast_unstake using the Substrate node and recommended hardware.
@@ -66,22 +71,46 @@ impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
 	// Storage: Staking Bonded (r:1 w:1)
 	// Storage: Staking Validators (r:1 w:0)
 	// Storage: Staking Nominators (r:1 w:0)
-	// Storage: System Account (r:1 w:1)
+	// Storage: System Account (r:2 w:2)
 	// Storage: Balances Locks (r:1 w:1)
 	// Storage: Staking Ledger (r:0 w:1)
 	// Storage: Staking Payee (r:0 w:1)
 	fn on_idle_unstake() -> Weight {
-		// Minimum execution time: 82_426 nanoseconds.
-		Weight::from_ref_time(83_422_000 as u64)
-			.saturating_add(T::DbWeight::get().reads(11 as u64))
-			.saturating_add(T::DbWeight::get().writes(6 as u64))
+		// Minimum execution time: 130_560 nanoseconds.
+		Weight::from_ref_time(132_233_000 as u64)
+			.saturating_add(T::DbWeight::get().reads(12 as u64))
+			.saturating_add(T::DbWeight::get().writes(7 as u64))
+	}
+	// Storage: FastUnstake ErasToCheckPerBlock (r:1 w:0)
+	// Storage: Staking ValidatorCount (r:1 w:0)
+	// Storage: ElectionProviderMultiPhase CurrentPhase (r:1 w:0)
+	// Storage: FastUnstake Head (r:1 w:1)
+	// Storage: Staking CurrentEra (r:1 w:0)
+	// Storage: Staking SlashingSpans (r:1 w:0)
+	// Storage: Staking Bonded (r:2 w:1)
+	// Storage: Staking Validators (r:1 w:0)
+	// Storage: Staking Nominators (r:1 w:0)
+	// Storage: System Account (r:2 w:2)
+	// Storage: Balances Locks (r:2 w:2)
+	// Storage: NominationPools MinJoinBond (r:1 w:0)
+	// Storage: NominationPools PoolMembers (r:1 w:1)
+	// Storage: NominationPools BondedPools (r:1 w:1)
+	// Storage: Staking Ledger (r:1 w:2)
+	// Storage: NominationPools RewardPools (r:1 w:1)
+	// Storage: NominationPools CounterForPoolMembers (r:1 w:1)
+	// Storage: NominationPools MaxPoolMembersPerPool (r:1 w:0)
+	// Storage: NominationPools MaxPoolMembers (r:1 w:0)
+	// Storage: Staking Payee (r:0 w:1)
+	fn on_idle_unstake_with_pool_join() -> Weight {
+		// Minimum execution time: 178_904 nanoseconds.
+		Weight::from_ref_time(180_537_000 as u64)
+			.saturating_add(T::DbWeight::get().reads(23 as u64))
+			.saturating_add(T::DbWeight::get().writes(14 as u64))
 	}
 	// Storage: FastUnstake ErasToCheckPerBlock (r:1 w:0)
 	// Storage: Staking ValidatorCount (r:1 w:0)
 	// Storage: ElectionProviderMultiPhase CurrentPhase (r:1 w:0)
 	// Storage: FastUnstake Head (r:1 w:1)
-	// Storage: FastUnstake Queue (r:2 w:1)
-	// Storage: FastUnstake CounterForQueue (r:1 w:1)
 	// Storage: Staking CurrentEra (r:1 w:0)
 	// Storage: Staking ErasStakers (r:1344 w:0)
 	/// The range of component `x` is `[672, 86016]`.
@@ -90,29 +119,42 @@ impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
 		Weight::from_ref_time(13_996_029_000 as u64)
 			// Standard Error: 16_878
 			.saturating_add(Weight::from_ref_time(18_113_540 as u64).saturating_mul(x as u64))
-			.saturating_add(T::DbWeight::get().reads(345 as u64))
+			.saturating_add(T::DbWeight::get().reads(342 as u64))
 			.saturating_add(T::DbWeight::get().reads((1 as u64).saturating_mul(x as u64)))
-			.saturating_add(T::DbWeight::get().writes(3 as u64))
+			.saturating_add(T::DbWeight::get().writes(1 as u64))
+	}
+	// Storage: FastUnstake Queue (r:128 w:128)
+	// Storage: FastUnstake Denylist (r:128 w:0)
+	// Storage: System Account (r:127 w:127)
+	// Storage: FastUnstake CounterForQueue (r:1 w:1)
+	// Storage: FastUnstake Head (r:0 w:1)
+	fn queue_promotion() -> Weight {
+		// Minimum execution time: 3_612_873 nanoseconds.
+		Weight::from_ref_time(3_640_518_000 as u64)
+			.saturating_add(T::DbWeight::get().reads(384 as u64))
+			.saturating_add(T::DbWeight::get().writes(257 as u64))
 	}
 	// Storage: FastUnstake ErasToCheckPerBlock (r:1 w:0)
 	// Storage: Staking Ledger (r:1 w:1)
+	// Storage: FastUnstake Denylist (r:1 w:0)
 	// Storage: FastUnstake Queue (r:1 w:1)
 	// Storage: FastUnstake Head (r:1 w:0)
 	// Storage: Staking Bonded (r:1 w:0)
+	// Storage: Staking CurrentEra (r:1 w:0)
+	// Storage: Balances Locks (r:1 w:1)
+	// Storage: System Account (r:1 w:1)
 	// Storage: Staking Validators (r:1 w:0)
 	// Storage: Staking Nominators (r:1 w:1)
 	// Storage: Staking CounterForNominators (r:1 w:1)
 	// Storage: VoterList ListNodes (r:1 w:1)
 	// Storage: VoterList ListBags (r:1 w:1)
 	// Storage: VoterList CounterForListNodes (r:1 w:1)
-	// Storage: Staking CurrentEra (r:1 w:0)
-	// Storage: Balances Locks (r:1 w:1)
 	// Storage: FastUnstake CounterForQueue (r:1 w:1)
 	fn register_fast_unstake() -> Weight {
-		// Minimum execution time: 120_190 nanoseconds.
-		Weight::from_ref_time(121_337_000 as u64)
-			.saturating_add(T::DbWeight::get().reads(14 as u64))
-			.saturating_add(T::DbWeight::get().writes(9 as u64))
+		// Minimum execution time: 166_759 nanoseconds.
+		Weight::from_ref_time(169_371_000 as u64)
+			.saturating_add(T::DbWeight::get().reads(16 as u64))
+			.saturating_add(T::DbWeight::get().writes(10 as u64))
 	}
 	// Storage: FastUnstake ErasToCheckPerBlock (r:1 w:0)
 	// Storage: Staking Ledger (r:1 w:0)
@@ -120,17 +162,38 @@ impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
 	// Storage: FastUnstake Head (r:1 w:0)
 	// Storage: FastUnstake CounterForQueue (r:1 w:1)
 	fn deregister() -> Weight {
-		// Minimum execution time: 49_897 nanoseconds.
-		Weight::from_ref_time(50_080_000 as u64)
+		// Minimum execution time: 53_611 nanoseconds.
+		Weight::from_ref_time(54_208_000 as u64)
 			.saturating_add(T::DbWeight::get().reads(5 as u64))
 			.saturating_add(T::DbWeight::get().writes(2 as u64))
 	}
+	// Storage: FastUnstake ErasToCheckPerBlock (r:1 w:0)
+	// Storage: Staking Ledger (r:1 w:0)
+	// Storage: FastUnstake Head (r:1 w:1)
+	fn deregister_from_head() -> Weight {
+		// Minimum execution time: 55_312 nanoseconds.
+		Weight::from_ref_time(56_104_000 as u64)
+			.saturating_add(T::DbWeight::get().reads(3 as u64))
+			.saturating_add(T::DbWeight::get().writes(1 as u64))
+	}
 	// Storage: FastUnstake ErasToCheckPerBlock (r:0 w:1)
-	fn control() -> Weight {
+	fn set_eras_to_check() -> Weight {
 		// Minimum execution time: 4_814 nanoseconds.
 		Weight::from_ref_time(4_997_000 as u64)
 			.saturating_add(T::DbWeight::get().writes(1 as u64))
 	}
+	// Storage: FastUnstake Denylist (r:0 w:1)
+	fn add_to_denylist() -> Weight {
+		// Minimum execution time: 5_102 nanoseconds.
+		Weight::from_ref_time(5_287_000 as u64)
+			.saturating_add(T::DbWeight::get().writes(1 as u64))
+	}
+	// Storage: FastUnstake Denylist (r:0 w:1)
+	fn remove_from_denylist() -> Weight {
+		// Minimum execution time: 5_063 nanoseconds.
+		Weight::from_ref_time(5_241_000 as u64)
+			.saturating_add(T::DbWeight::get().writes(1 as u64))
+	}
 }
 
 // For backwards compatibility and tests
@@ -144,22 +207,46 @@ impl WeightInfo for () {
 	// Storage: Staking Bonded (r:1 w:1)
 	// Storage: Staking Validators (r:1 w:0)
 	// Storage: Staking Nominators (r:1 w:0)
-	// Storage: System Account (r:1 w:1)
+	// Storage: System Account (r:2 w:2)
 	// Storage: Balances Locks (r:1 w:1)
 	// Storage: Staking Ledger (r:0 w:1)
 	// Storage: Staking Payee (r:0 w:1)
 	fn on_idle_unstake() -> Weight {
-		// Minimum execution time: 82_426 nanoseconds.
-		Weight::from_ref_time(83_422_000 as u64)
-			.saturating_add(RocksDbWeight::get().reads(11 as u64))
-			.saturating_add(RocksDbWeight::get().writes(6 as u64))
+		// Minimum execution time: 130_560 nanoseconds.
+		Weight::from_ref_time(132_233_000 as u64)
+			.saturating_add(RocksDbWeight::get().reads(12 as u64))
+			.saturating_add(RocksDbWeight::get().writes(7 as u64))
+	}
+	// Storage: FastUnstake ErasToCheckPerBlock (r:1 w:0)
+	// Storage: Staking ValidatorCount (r:1 w:0)
+	// Storage: ElectionProviderMultiPhase CurrentPhase (r:1 w:0)
+	// Storage: FastUnstake Head (r:1 w:1)
+	// Storage: Staking CurrentEra (r:1 w:0)
+	// Storage: Staking SlashingSpans (r:1 w:0)
+	// Storage: Staking Bonded (r:2 w:1)
+	// Storage: Staking Validators (r:1 w:0)
+	// Storage: Staking Nominators (r:1 w:0)
+	// Storage: System Account (r:2 w:2)
+	// Storage: Balances Locks (r:2 w:2)
+	// Storage: NominationPools MinJoinBond (r:1 w:0)
+	// Storage: NominationPools PoolMembers (r:1 w:1)
+	// Storage: NominationPools BondedPools (r:1 w:1)
+	// Storage: Staking Ledger (r:1 w:2)
+	// Storage: NominationPools RewardPools (r:1 w:1)
+	// Storage: NominationPools CounterForPoolMembers (r:1 w:1)
+	// Storage: NominationPools MaxPoolMembersPerPool (r:1 w:0)
+	// Storage: NominationPools MaxPoolMembers (r:1 w:0)
+	// Storage: Staking Payee (r:0 w:1)
+	fn on_idle_unstake_with_pool_join() -> Weight {
+		// Minimum execution time: 178_904 nanoseconds.
+		Weight::from_ref_time(180_537_000 as u64)
+			.saturating_add(RocksDbWeight::get().reads(23 as u64))
+			.saturating_add(RocksDbWeight::get().writes(14 as u64))
 	}
 	// Storage: FastUnstake ErasToCheckPerBlock (r:1 w:0)
 	// Storage: Staking ValidatorCount (r:1 w:0)
 	// Storage: ElectionProviderMultiPhase CurrentPhase (r:1 w:0)
 	// Storage: FastUnstake Head (r:1 w:1)
-	// Storage: FastUnstake Queue (r:2 w:1)
-	// Storage: FastUnstake CounterForQueue (r:1 w:1)
 	// Storage: Staking CurrentEra (r:1 w:0)
 	// Storage: Staking ErasStakers (r:1344 w:0)
 	/// The range of component `x` is `[672, 86016]`.
@@ -168,29 +255,42 @@ impl WeightInfo for () {
 		Weight::from_ref_time(13_996_029_000 as u64)
 			// Standard Error: 16_878
 			.saturating_add(Weight::from_ref_time(18_113_540 as u64).saturating_mul(x as u64))
-			.saturating_add(RocksDbWeight::get().reads(345 as u64))
+			.saturating_add(RocksDbWeight::get().reads(342 as u64))
 			.saturating_add(RocksDbWeight::get().reads((1 as u64).saturating_mul(x as u64)))
-			.saturating_add(RocksDbWeight::get().writes(3 as u64))
+			.saturating_add(RocksDbWeight::get().writes(1 as u64))
+	}
+	// Storage: FastUnstake Queue (r:128 w:128)
+	// Storage: FastUnstake Denylist (r:128 w:0)
+	// Storage: System Account (r:127 w:127)
+	// Storage: FastUnstake CounterForQueue (r:1 w:1)
+	// Storage: FastUnstake Head (r:0 w:1)
+	fn queue_promotion() -> Weight {
+		// Minimum execution time: 3_612_873 nanoseconds.
+		Weight::from_ref_time(3_640_518_000 as u64)
+			.saturating_add(RocksDbWeight::get().reads(384 as u64))
+			.saturating_add(RocksDbWeight::get().writes(257 as u64))
 	}
 	// Storage: FastUnstake ErasToCheckPerBlock (r:1 w:0)
 	// Storage: Staking Ledger (r:1 w:1)
+	// Storage: FastUnstake Denylist (r:1 w:0)
 	// Storage: FastUnstake Queue (r:1 w:1)
 	// Storage: FastUnstake Head (r:1 w:0)
 	// Storage: Staking Bonded (r:1 w:0)
+	// Storage: Staking CurrentEra (r:1 w:0)
+	// Storage: Balances Locks (r:1 w:1)
+	// Storage: System Account (r:1 w:1)
 	// Storage: Staking Validators (r:1 w:0)
 	// Storage: Staking Nominators (r:1 w:1)
 	// Storage: Staking CounterForNominators (r:1 w:1)
 	// Storage: VoterList ListNodes (r:1 w:1)
 	// Storage: VoterList ListBags (r:1 w:1)
 	// Storage: VoterList CounterForListNodes (r:1 w:1)
-	// Storage: Staking CurrentEra (r:1 w:0)
-	// Storage: Balances Locks (r:1 w:1)
 	// Storage: FastUnstake CounterForQueue (r:1 w:1)
 	fn register_fast_unstake() -> Weight {
-		// Minimum execution time: 120_190 nanoseconds.
-		Weight::from_ref_time(121_337_000 as u64)
-			.saturating_add(RocksDbWeight::get().reads(14 as u64))
-			.saturating_add(RocksDbWeight::get().writes(9 as u64))
+		// Minimum execution time: 166_759 nanoseconds.
+		Weight::from_ref_time(169_371_000 as u64)
+			.saturating_add(RocksDbWeight::get().reads(16 as u64))
+			.saturating_add(RocksDbWeight::get().writes(10 as u64))
 	}
 	// Storage: FastUnstake ErasToCheckPerBlock (r:1 w:0)
 	// Storage: Staking Ledger (r:1 w:0)
@@ -198,15 +298,36 @@ impl WeightInfo for () {
 	// Storage: FastUnstake Head (r:1 w:0)
 	// Storage: FastUnstake CounterForQueue (r:1 w:1)
 	fn deregister() -> Weight {
-		// Minimum execution time: 49_897 nanoseconds.
-		Weight::from_ref_time(50_080_000 as u64)
+		// Minimum execution time: 53_611 nanoseconds.
+		Weight::from_ref_time(54_208_000 as u64)
 			.saturating_add(RocksDbWeight::get().reads(5 as u64))
 			.saturating_add(RocksDbWeight::get().writes(2 as u64))
 	}
+	// Storage: FastUnstake ErasToCheckPerBlock (r:1 w:0)
+	// Storage: Staking Ledger (r:1 w:0)
+	// Storage: FastUnstake Head (r:1 w:1)
+	fn deregister_from_head() -> Weight {
+		// Minimum execution time: 55_312 nanoseconds.
+		Weight::from_ref_time(56_104_000 as u64)
+			.saturating_add(RocksDbWeight::get().reads(3 as u64))
+			.saturating_add(RocksDbWeight::get().writes(1 as u64))
+	}
 	// Storage: FastUnstake ErasToCheckPerBlock (r:0 w:1)
-	fn control() -> Weight {
+	fn set_eras_to_check() -> Weight {
 		// Minimum execution time: 4_814 nanoseconds.
 		Weight::from_ref_time(4_997_000 as u64)
 			.saturating_add(RocksDbWeight::get().writes(1 as u64))
 	}
+	// Storage: FastUnstake Denylist (r:0 w:1)
+	fn add_to_denylist() -> Weight {
+		// Minimum execution time: 5_102 nanoseconds.
+		Weight::from_ref_time(5_287_000 as u64)
+			.saturating_add(RocksDbWeight::get().writes(1 as u64))
+	}
+	// Storage: FastUnstake Denylist (r:0 w:1)
+	fn remove_from_denylist() -> Weight {
+		// Minimum execution time: 5_063 nanoseconds.
+		Weight::from_ref_time(5_241_000 as u64)
+			.saturating_add(RocksDbWeight::get().writes(1 as u64))
+	}
 }
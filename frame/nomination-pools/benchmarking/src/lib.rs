@@ -25,14 +25,21 @@ mod mock;
 
 use frame_benchmarking::{account, frame_support::traits::Currency, vec, whitelist_account, Vec};
 use frame_election_provider_support::SortedListProvider;
-use frame_support::{assert_ok, ensure, traits::Get};
+use frame_support::{
+	assert_ok, ensure,
+	traits::{Get, Hooks},
+};
 use frame_system::RawOrigin as RuntimeOrigin;
 use pallet_nomination_pools::{
-	BalanceOf, BondExtra, BondedPoolInner, BondedPools, ConfigOp, MaxPoolMembers,
-	MaxPoolMembersPerPool, MaxPools, Metadata, MinCreateBond, MinJoinBond, Pallet as Pools,
-	PoolMembers, PoolRoles, PoolState, RewardPools, SubPoolsStorage,
+	BalanceOf, BondExtra, BondedPoolInner, BondedPools, Commission, ConfigOp, MaxPoolMembers,
+	MaxPoolMembersPerPool, MaxPools, Metadata, MinCreateBond, MinJoinBond, MinMembershipDuration,
+	Pallet as Pools, PoolMembers, PoolMembersByPool, PoolMembersIndexCursor, PoolRoles, PoolState,
+	RewardAccountDeposits, RewardPools, SubPoolsStorage, SwitchPoolRequests,
+};
+use sp_runtime::{
+	traits::{Bounded, StaticLookup, UniqueSaturatedInto, Zero},
+	FixedPointNumber, SaturatedConversion,
 };
-use sp_runtime::traits::{Bounded, StaticLookup, Zero};
 use sp_staking::{EraIndex, StakingInterface};
 // `frame_benchmarking::benchmarks!` macro needs this
 use pallet_nomination_pools::Call;
@@ -193,7 +200,7 @@ impl<T: Config> ListScenario<T> {
 			maybe_pool.as_mut().map(|pool| pool.points -= amount)
 		});
 
-		Pools::<T>::join(RuntimeOrigin::Signed(joiner.clone()).into(), amount, 1).unwrap();
+		Pools::<T>::join(RuntimeOrigin::Signed(joiner.clone()).into(), amount, 1, None).unwrap();
 
 		// check that the vote weight is still the same as the original bonded
 		let weight_of = pallet_staking::Pallet::<T>::weight_of_fn();
@@ -225,10 +232,15 @@ frame_benchmarking::benchmarks! {
 		let joiner: T::AccountId
 			= create_funded_user_with_balance::<T>("joiner", 0, joiner_free);
 
+		// joining with a referrer is the worst case.
+		let referrer: T::AccountId = account("referrer", 0, USER_SEED);
+		let referrer_lookup = T::Lookup::unlookup(referrer.clone());
+
 		whitelist_account!(joiner);
-	}: _(RuntimeOrigin::Signed(joiner.clone()), max_additional, 1)
+	}: _(RuntimeOrigin::Signed(joiner.clone()), max_additional, 1, Some(referrer_lookup))
 	verify {
 		assert_eq!(CurrencyOf::<T>::free_balance(&joiner), joiner_free - max_additional);
+		assert_eq!(PoolMembers::<T>::get(&joiner).unwrap().referrer, Some(referrer));
 		assert_eq!(
 			T::Staking::active_stake(&scenario.origin1).unwrap(),
 			scenario.dest_weight
@@ -277,6 +289,11 @@ frame_benchmarking::benchmarks! {
 		// Send funds to the reward account of the pool
 		CurrencyOf::<T>::make_free_balance_be(&reward_account, ed + origin_weight);
 
+		// Worst case: the pool charges a commission, which is paid to a new account.
+		let payee: T::AccountId = account("payee", 0, USER_SEED);
+		let commission = T::MaxCommission::get();
+		Commission::<T>::insert(1, (commission, payee.clone()));
+
 		// Sanity check
 		assert_eq!(
 			CurrencyOf::<T>::free_balance(&depositor),
@@ -288,8 +305,9 @@ frame_benchmarking::benchmarks! {
 	verify {
 		assert_eq!(
 			CurrencyOf::<T>::free_balance(&depositor),
-			origin_weight * 2u32.into()
+			origin_weight * 2u32.into() - commission * origin_weight
 		);
+		assert_eq!(CurrencyOf::<T>::free_balance(&payee), commission * origin_weight);
 		assert_eq!(
 			CurrencyOf::<T>::free_balance(&reward_account),
 			ed + Zero::zero()
@@ -336,7 +354,7 @@ frame_benchmarking::benchmarks! {
 		// Add a new member
 		let min_join_bond = MinJoinBond::<T>::get().max(CurrencyOf::<T>::minimum_balance());
 		let joiner = create_funded_user_with_balance::<T>("joiner", 0, min_join_bond * 2u32.into());
-		Pools::<T>::join(RuntimeOrigin::Signed(joiner.clone()).into(), min_join_bond, 1)
+		Pools::<T>::join(RuntimeOrigin::Signed(joiner.clone()).into(), min_join_bond, 1, None)
 			.unwrap();
 
 		// Sanity check join worked
@@ -379,7 +397,7 @@ frame_benchmarking::benchmarks! {
 		let min_join_bond = MinJoinBond::<T>::get().max(CurrencyOf::<T>::minimum_balance());
 		let joiner = create_funded_user_with_balance::<T>("joiner", 0, min_join_bond * 2u32.into());
 		let joiner_lookup = T::Lookup::unlookup(joiner.clone());
-		Pools::<T>::join(RuntimeOrigin::Signed(joiner.clone()).into(), min_join_bond, 1)
+		Pools::<T>::join(RuntimeOrigin::Signed(joiner.clone()).into(), min_join_bond, 1, None)
 			.unwrap();
 
 		// Sanity check join worked
@@ -652,6 +670,178 @@ frame_benchmarking::benchmarks! {
 		assert!(T::Staking::nominations(Pools::<T>::create_bonded_account(1)).is_none());
 	}
 
+	sweep_dust {
+		// Create a pool with more points than the reward counter can resolve, so that a single
+		// unit of reward is lost to rounding.
+		let huge_bond: BalanceOf<T> =
+			UniqueSaturatedInto::<u128>::unique_saturated_into(T::RewardCounter::DIV)
+				.saturating_mul(2)
+				.saturated_into();
+		let _ = create_pool_account::<T>(0, huge_bond);
+		let reward_account = Pools::<T>::create_reward_account(1);
+		CurrencyOf::<T>::deposit_creating(&reward_account, 1u32.into());
+
+		// A joining member updates the records of the pool and thus records the dust.
+		let min_join_bond = MinJoinBond::<T>::get().max(CurrencyOf::<T>::minimum_balance());
+		let joiner = create_funded_user_with_balance::<T>("joiner", 0, min_join_bond * 2u32.into());
+		assert_ok!(Pools::<T>::join(RuntimeOrigin::Signed(joiner.clone()).into(), min_join_bond, 1, None));
+		assert!(!RewardPools::<T>::get(1).unwrap().dust().is_zero());
+
+		whitelist_account!(joiner);
+	}:_(RuntimeOrigin::Signed(joiner), 1)
+	verify {
+		assert!(RewardPools::<T>::get(1).unwrap().dust().is_zero());
+		assert_eq!(CurrencyOf::<T>::free_balance(&reward_account), CurrencyOf::<T>::minimum_balance());
+	}
+
+	reconcile_reward_pool {
+		// worst case: the reward account holds less than recorded and the records are adjusted.
+		// The pool has more points than the reward counter can resolve, so that an odd reward
+		// leaves a unit of dust, as in `sweep_dust`.
+		let huge_bond: BalanceOf<T> =
+			UniqueSaturatedInto::<u128>::unique_saturated_into(T::RewardCounter::DIV)
+				.saturating_mul(2)
+				.saturated_into();
+		let _ = create_pool_account::<T>(0, huge_bond);
+		let reward_account = Pools::<T>::create_reward_account(1);
+		let reward = CurrencyOf::<T>::minimum_balance() * 2u32.into() + 1u32.into();
+		CurrencyOf::<T>::deposit_creating(&reward_account, reward);
+
+		// A joining member records the rewards of the pool, and the dust with them.
+		let min_join_bond = MinJoinBond::<T>::get().max(CurrencyOf::<T>::minimum_balance());
+		let joiner = create_funded_user_with_balance::<T>("joiner", 0, min_join_bond * 2u32.into());
+		assert_ok!(Pools::<T>::join(RuntimeOrigin::Signed(joiner).into(), min_join_bond, 1, None));
+		let reward_pool = RewardPools::<T>::get(1).unwrap();
+		assert_eq!(reward_pool.dust(), 1u32.into());
+		assert!(!reward_pool.last_recorded_reward_counter().is_zero());
+
+		// All but a single unit of the recorded rewards go missing.
+		let deposit = RewardAccountDeposits::<T>::get(1);
+		CurrencyOf::<T>::make_free_balance_be(&reward_account, deposit + 1u32.into());
+	}:_(RuntimeOrigin::Root, 1, true)
+	verify {
+		let reconciled = RewardPools::<T>::get(1).unwrap();
+		// the counter is left as it is, the earnings are lowered to the balance which still
+		// covers the dust.
+		assert_eq!(
+			reconciled.last_recorded_reward_counter(),
+			reward_pool.last_recorded_reward_counter(),
+		);
+		assert_eq!(reconciled.last_recorded_total_payouts(), 1u32.into());
+		assert_eq!(reconciled.dust(), 1u32.into());
+	}
+
+	set_min_membership_duration {
+		let (root, _) = create_pool_account::<T>(0, Pools::<T>::depositor_min_bond() * 2u32.into());
+		let duration = <T as pallet_nomination_pools::Config>::MaxMinMembershipDuration::get();
+	}:_(RuntimeOrigin::Signed(root), 1, Some(duration))
+	verify {
+		assert_eq!(MinMembershipDuration::<T>::get(1), Some(duration));
+	}
+
+	set_commission {
+		let (root, _) = create_pool_account::<T>(0, Pools::<T>::depositor_min_bond() * 2u32.into());
+		let commission = (T::MaxCommission::get(), root.clone());
+	}:_(RuntimeOrigin::Signed(root), 1, Some(commission.clone()))
+	verify {
+		assert_eq!(Commission::<T>::get(1), Some(commission));
+	}
+
+	migrate_members {
+		let n in 0 .. 1_000;
+		// worst case: every member looked at belongs to the pool and is indexed.
+		let min_create_bond = Pools::<T>::depositor_min_bond();
+		let _ = create_pool_account::<T>(0, min_create_bond);
+		MaxPoolMembers::<T>::kill();
+		MaxPoolMembersPerPool::<T>::kill();
+
+		let min_join_bond = MinJoinBond::<T>::get().max(CurrencyOf::<T>::minimum_balance());
+		for i in 0 .. n {
+			let joiner =
+				create_funded_user_with_balance::<T>("joiner", i, min_join_bond * 2u32.into());
+			assert_ok!(Pools::<T>::join(RuntimeOrigin::Signed(joiner).into(), min_join_bond, 1, None));
+		}
+
+		// The members joined before the index was introduced.
+		let _ = PoolMembersByPool::<T>::clear_prefix(1, u32::MAX, None);
+		PoolMembersIndexCursor::<T>::put(None::<T::AccountId>);
+		let caller = account("caller", 0, USER_SEED);
+		whitelist_account!(caller);
+	}:_(RuntimeOrigin::Signed(caller), n)
+	verify {
+		assert_eq!(PoolMembersByPool::<T>::iter_key_prefix(1).count() as u32, n);
+		// the depositor is a member as well, hence there is at least one left to look at.
+		assert!(PoolMembersIndexCursor::<T>::exists());
+	}
+
+	switch_pool {
+		let origin_weight = Pools::<T>::depositor_min_bond() * 2u32.into();
+		let scenario = ListScenario::<T>::new(origin_weight, false)?;
+		let scenario = scenario.add_joiner(Pools::<T>::depositor_min_bond());
+		let member_id = scenario.origin1_member.unwrap();
+		whitelist_account!(member_id);
+	}:_(RuntimeOrigin::Signed(member_id.clone()), 2)
+	verify {
+		assert_eq!(SwitchPoolRequests::<T>::get(&member_id), Some(2));
+	}
+
+	process_switch_request {
+		// number of validators multiplied by the number of eras that are checked.
+		let v in 1 .. 2048;
+
+		// worst case: the member moves enough funds to change the bags of both pools.
+		let origin_weight = Pools::<T>::depositor_min_bond() * 200u32.into();
+		let scenario = ListScenario::<T>::new(origin_weight, false)?;
+		let amount = origin_weight - scenario.dest_weight;
+		let scenario = scenario.add_joiner(amount);
+		let member_id = scenario.origin1_member.unwrap();
+		assert_ok!(Pools::<T>::switch_pool(RuntimeOrigin::Signed(member_id.clone()).into(), 2));
+
+		// every era of the bonding duration has full exposures, none of them of the pool.
+		let eras = T::Staking::bonding_duration() + 1;
+		let current_era = eras - 1;
+		T::Staking::set_current_era(current_era);
+		let ed = CurrencyOf::<T>::minimum_balance();
+		let max_nominators = <T as pallet_staking::Config>::MaxNominatorRewardedPerValidator::get();
+		let others = (0 .. max_nominators)
+			.map(|n| (account("nominator", n, USER_SEED), ed))
+			.collect::<Vec<_>>();
+		for era in 0 ..= current_era {
+			for i in 0 .. v / eras {
+				let validator = account("validator", i, USER_SEED);
+				T::Staking::add_era_stakers(&era, &validator, others.clone());
+			}
+		}
+	}
+	: {
+		let remaining_weight = <T as frame_system::Config>::BlockWeights::get().max_block;
+		Pools::<T>::on_idle(Zero::zero(), remaining_weight);
+	}
+	verify {
+		assert_eq!(PoolMembers::<T>::get(&member_id).unwrap().pool_id, 2);
+		assert!(!SwitchPoolRequests::<T>::contains_key(&member_id));
+	}
+
+	adjust_pool_deposit {
+		// the existential deposit cannot be changed here, so pretend that it has been lowered
+		// to half of the deposit.
+		let min_create_bond = Pools::<T>::depositor_min_bond();
+		let (depositor, _) = create_pool_account::<T>(0, min_create_bond);
+		let reward_account = Pools::<T>::create_reward_account(1);
+		let ed = CurrencyOf::<T>::minimum_balance();
+		CurrencyOf::<T>::make_free_balance_be(&reward_account, ed * 2u32.into());
+		RewardAccountDeposits::<T>::insert(1, ed * 2u32.into());
+		let depositor_balance = CurrencyOf::<T>::free_balance(&depositor);
+
+		let caller = account("caller", 0, USER_SEED);
+		whitelist_account!(caller);
+	}:_(RuntimeOrigin::Signed(caller), 1)
+	verify {
+		assert_eq!(RewardAccountDeposits::<T>::get(1), ed);
+		assert_eq!(CurrencyOf::<T>::free_balance(&reward_account), ed);
+		assert_eq!(CurrencyOf::<T>::free_balance(&depositor), depositor_balance + ed);
+	}
+
 	impl_benchmark_test_suite!(
 		Pallet,
 		crate::mock::new_test_ext(),
@@ -20,7 +20,7 @@ use frame_election_provider_support::VoteWeight;
 use frame_support::{pallet_prelude::*, parameter_types, traits::ConstU64, PalletId};
 use sp_runtime::{
 	traits::{Convert, IdentityLookup},
-	FixedU128,
+	FixedU128E24, Perbill,
 };
 
 type AccountId = u128;
@@ -108,6 +108,7 @@ impl pallet_staking::Config for Runtime {
 	type EraPayout = pallet_staking::ConvertCurve<RewardCurve>;
 	type NextNewSession = ();
 	type MaxNominatorRewardedPerValidator = ConstU32<64>;
+	type MaxExposurePageSize = ConstU32<64>;
 	type OffendingValidatorsThreshold = ();
 	type ElectionProvider =
 		frame_election_provider_support::NoElection<(AccountId, BlockNumber, Staking, ())>;
@@ -117,6 +118,10 @@ impl pallet_staking::Config for Runtime {
 	type MaxUnlockingChunks = ConstU32<32>;
 	type HistoryDepth = ConstU32<84>;
 	type OnStakerSlash = Pools;
+	type OnStakingOperation = ();
+	type KickRewardPool = ();
+	type KickReward = ();
+	type MaxCommissionBatch = ConstU32<64>;
 	type BenchmarkingConfig = pallet_staking::TestBenchmarkingConfig;
 	type WeightInfo = ();
 }
@@ -151,21 +156,31 @@ parameter_types! {
 	pub static PostUnbondingPoolsWindow: u32 = 10;
 	pub const PoolsPalletId: PalletId = PalletId(*b"py/nopls");
 	pub const MaxPointsToBalance: u8 = 10;
+	pub const ReferrerCommission: Perbill = Perbill::from_percent(10);
+	pub const MaxCommission: Perbill = Perbill::from_percent(50);
+	pub const MinMembersForDepositorUnbond: u32 = 2;
+	pub const MaxMinMembershipDuration: u64 = 100;
 }
 
 impl pallet_nomination_pools::Config for Runtime {
 	type RuntimeEvent = RuntimeEvent;
 	type WeightInfo = ();
 	type Currency = Balances;
-	type RewardCounter = FixedU128;
+	type RewardCounter = FixedU128E24;
 	type BalanceToU256 = BalanceToU256;
 	type U256ToBalance = U256ToBalance;
 	type Staking = Staking;
+	type StakeAdapter = pallet_nomination_pools::adapter::TransferStake<Self>;
 	type PostUnbondingPoolsWindow = PostUnbondingPoolsWindow;
 	type MaxMetadataLen = ConstU32<256>;
 	type MaxUnbonding = ConstU32<8>;
+	type ReferrerCommission = ReferrerCommission;
+	type MaxCommission = MaxCommission;
+	type MinMembersForDepositorUnbond = MinMembersForDepositorUnbond;
+	type MaxMinMembershipDuration = MaxMinMembershipDuration;
 	type PalletId = PoolsPalletId;
 	type MaxPointsToBalance = MaxPointsToBalance;
+	type DustReceiver = ();
 }
 
 impl crate::Config for Runtime {}
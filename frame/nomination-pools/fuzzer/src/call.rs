@@ -98,7 +98,7 @@ fn random_call<R: Rng>(mut rng: &mut R) -> (pools::Call<T>, RuntimeOrigin) {
 			let (origin, who) = random_signed_origin(&mut rng);
 			fund_account(&mut rng, &who);
 			let amount = random_ed_multiple(&mut rng);
-			(PoolsCall::<T>::join { amount, pool_id }, origin)
+			(PoolsCall::<T>::join { amount, pool_id, referrer: None }, origin)
 		},
 		1 => {
 			// bond_extra
@@ -181,7 +181,9 @@ impl RewardAgent {
 		let _ = Balances::deposit_creating(&self.who, 10 * amount);
 		self.pool_id = Some(pool_id);
 		log::info!(target: "reward-agent", "🤖 reward agent joining in {} with {}", pool_id, amount);
-		assert_ok!(PoolsCall::join::<T> { amount, pool_id }.dispatch_bypass_filter(origin));
+		assert_ok!(
+			PoolsCall::join::<T> { amount, pool_id, referrer: None }.dispatch_bypass_filter(origin)
+		);
 	}
 
 	fn claim_payout(&mut self) {
@@ -21,13 +21,43 @@
 #![cfg_attr(not(feature = "std"), no_std)]
 
 use codec::Codec;
+use sp_std::vec::Vec;
 
 sp_api::decl_runtime_apis! {
 	/// Runtime api for accessing information about nomination pools.
+	///
+	/// Version 2 added `pool_nominations`. Version 3 added `total_value_locked`. Version 4 added
+	/// `below_minimum_active_stake`. Version 5 added `pool_members`. Version 6 added
+	/// `points_to_balance` and `member_balance`.
+	#[api_version(6)]
 	pub trait NominationPoolsApi<AccountId, Balance>
 		where AccountId: Codec, Balance: Codec
 	{
 		/// Returns the pending rewards for the member that the AccountId was given for.
 		fn pending_rewards(member: AccountId) -> Balance;
+
+		/// Returns the validators nominated by the given pool, if it exists and nominates.
+		fn pool_nominations(pool_id: u32) -> Option<Vec<AccountId>>;
+
+		/// Returns the sum of the funds staked by all pools, active and unlocking.
+		fn total_value_locked() -> Balance;
+
+		/// Returns whether the active stake of the given pool is below the minimum active stake
+		/// of the last election, if the pool exists.
+		fn below_minimum_active_stake(pool_id: u32) -> Option<bool>;
+
+		/// Returns up to `len` members of the given pool, skipping the first `start` ones.
+		///
+		/// The members of a pool that existed before the member index was introduced might not
+		/// all be returned until they have been migrated.
+		fn pool_members(pool_id: u32, start: u32, len: u32) -> Vec<AccountId>;
+
+		/// Returns the balance that the given points of a pool are currently worth, if the pool
+		/// exists.
+		fn points_to_balance(pool_id: u32, points: Balance) -> Option<Balance>;
+
+		/// Returns the balance of the given member, both active and unbonding, if it is a member
+		/// of any pool.
+		fn member_balance(member: AccountId) -> Option<Balance>;
 	}
 }
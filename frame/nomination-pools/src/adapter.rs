@@ -0,0 +1,174 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2023 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Adapters deciding where the funds of pool members are kept while they back the stake of a
+//! pool.
+//!
+//! All movements of member funds into, within and out of the staking system go through
+//! [`Config::StakeAdapter`]. The pallet itself only takes care of the accounting of points, sub
+//! pools and [`TotalValueLocked`].
+
+use crate::*;
+use sp_std::marker::PhantomData;
+
+/// Hold the funds of pool members and make them count towards the stake of a pool.
+///
+/// A pool is identified by its bonded account, `pool_account`, which is also the account that
+/// nominates in the staking system.
+pub trait StakeAdapter {
+	/// The balance type.
+	type Balance;
+
+	/// The account identifier type.
+	type AccountId;
+
+	/// The balance of the pool that is not actively staked anymore, and that can thus be given
+	/// back to members through [`StakeAdapter::member_withdraw`].
+	fn transferable_balance(pool_account: &Self::AccountId) -> Self::Balance;
+
+	/// The total balance backing the pool, whether it is actively staked, unbonding or already
+	/// unlocked.
+	fn total_balance(pool_account: &Self::AccountId) -> Self::Balance;
+
+	/// Take `amount` of the funds of `who` and bond them into the pool.
+	///
+	/// If `bond_type` is [`BondType::Create`], the pool is bonded for the first time, with
+	/// `reward_account` as payee, and `who` is allowed to be reaped.
+	fn pledge_bond(
+		who: &Self::AccountId,
+		pool_account: &Self::AccountId,
+		reward_account: &Self::AccountId,
+		amount: Self::Balance,
+		bond_type: BondType,
+	) -> DispatchResult;
+
+	/// Unbond `amount` of the active stake of the pool.
+	fn unbond(pool_account: &Self::AccountId, amount: Self::Balance) -> DispatchResult;
+
+	/// Withdraw the unlocked stake of the pool from the staking system, like
+	/// [`StakingInterface::withdraw_unbonded`].
+	///
+	/// Returns whether the stash has been killed.
+	fn withdraw_unbonded(
+		pool_account: &Self::AccountId,
+		num_slashing_spans: u32,
+	) -> Result<bool, DispatchError>;
+
+	/// Give `amount` of the transferable balance of the pool back to `who`.
+	fn member_withdraw(
+		who: &Self::AccountId,
+		pool_account: &Self::AccountId,
+		amount: Self::Balance,
+	) -> DispatchResult;
+
+	/// Instantly move `amount` of the active stake of `from_pool` that belongs to `who` into the
+	/// active stake of `to_pool`.
+	fn member_switch(
+		who: &Self::AccountId,
+		from_pool: &Self::AccountId,
+		to_pool: &Self::AccountId,
+		amount: Self::Balance,
+	) -> DispatchResult;
+
+	/// The staking system reported a slash of `amount` on the pool.
+	///
+	/// The slash has already been applied to the staking ledger of the pool, this is where it
+	/// reaches the funds backing it, if the staking system could not do so itself.
+	fn on_slash(pool_account: &Self::AccountId, amount: Self::Balance);
+
+	/// The pool is being dissolved: get rid of whatever is left of it.
+	fn dissolve(pool_account: &Self::AccountId);
+}
+
+/// The default [`StakeAdapter`]: member funds are transferred into the bonded account of the pool,
+/// which then bonds them itself in [`Config::Staking`].
+///
+/// Slashes are applied by the staking system on the bonded account directly.
+pub struct TransferStake<T: Config>(PhantomData<T>);
+
+impl<T: Config> StakeAdapter for TransferStake<T> {
+	type Balance = BalanceOf<T>;
+	type AccountId = T::AccountId;
+
+	fn transferable_balance(pool_account: &Self::AccountId) -> Self::Balance {
+		T::Currency::free_balance(pool_account)
+			.saturating_sub(T::Staking::active_stake(pool_account).unwrap_or_default())
+	}
+
+	fn total_balance(pool_account: &Self::AccountId) -> Self::Balance {
+		T::Currency::total_balance(pool_account)
+	}
+
+	fn pledge_bond(
+		who: &Self::AccountId,
+		pool_account: &Self::AccountId,
+		reward_account: &Self::AccountId,
+		amount: Self::Balance,
+		bond_type: BondType,
+	) -> DispatchResult {
+		match bond_type {
+			BondType::Create => {
+				T::Currency::transfer(who, pool_account, amount, ExistenceRequirement::AllowDeath)?;
+				T::Staking::bond(pool_account, amount, reward_account)
+			},
+			// The pool should always be created in such a way its in a state to bond extra, but if
+			// the active balance is slashed below the minimum bonded or the account cannot be
+			// found, we exit early.
+			BondType::Later => {
+				T::Currency::transfer(who, pool_account, amount, ExistenceRequirement::KeepAlive)?;
+				T::Staking::bond_extra(pool_account, amount)
+			},
+		}
+	}
+
+	fn unbond(pool_account: &Self::AccountId, amount: Self::Balance) -> DispatchResult {
+		T::Staking::unbond(pool_account, amount)
+	}
+
+	fn withdraw_unbonded(
+		pool_account: &Self::AccountId,
+		num_slashing_spans: u32,
+	) -> Result<bool, DispatchError> {
+		T::Staking::withdraw_unbonded(pool_account.clone(), num_slashing_spans)
+	}
+
+	fn member_withdraw(
+		who: &Self::AccountId,
+		pool_account: &Self::AccountId,
+		amount: Self::Balance,
+	) -> DispatchResult {
+		T::Currency::transfer(pool_account, who, amount, ExistenceRequirement::AllowDeath)
+	}
+
+	fn member_switch(
+		_who: &Self::AccountId,
+		from_pool: &Self::AccountId,
+		to_pool: &Self::AccountId,
+		amount: Self::Balance,
+	) -> DispatchResult {
+		T::Staking::unbond_instantly(from_pool, amount)?;
+		T::Currency::transfer(from_pool, to_pool, amount, ExistenceRequirement::KeepAlive)?;
+		T::Staking::bond_extra(to_pool, amount)
+	}
+
+	fn on_slash(_pool_account: &Self::AccountId, _amount: Self::Balance) {}
+
+	fn dissolve(pool_account: &Self::AccountId) {
+		// NOTE: this is purely defensive.
+		T::Currency::make_free_balance_be(pool_account, Zero::zero());
+	}
+}
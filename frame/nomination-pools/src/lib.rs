@@ -61,6 +61,10 @@
 //!
 //! After joining a pool, a member can claim rewards by calling [`Call::claim_payout`].
 //!
+//! The root of a pool can charge a [`Commission`] on the payouts of its members with
+//! [`Call::set_commission`]. If a member joined on referral, [`Config::ReferrerCommission`] of the
+//! commission is paid to their referrer.
+//!
 //! For design docs see the [reward pool](#reward-pool) section.
 //!
 //! ### Leave
@@ -78,6 +82,15 @@
 //! For design docs see the [bonded pool](#bonded-pool) and [unbonding sub
 //! pools](#unbonding-sub-pools) sections.
 //!
+//! ### Switch pools
+//!
+//! If the pool of a member has not been exposed in any of the last
+//! [`sp_staking::StakingInterface::bonding_duration`] eras, its funds cannot be slashed anymore and
+//! the member can move them to another pool right away with [`Call::switch_pool`]. Checking the
+//! exposure is expensive, hence the request is queued and processed in `on_idle`, which either
+//! emits [`Event::Switched`] or [`Event::SwitchFailed`]. If the pool has been exposed, the member
+//! has to leave it as described above.
+//!
 //! ### Slashes
 //!
 //! Slashes are distributed evenly across the bonded pool and the unbonding pools from slash era+1
@@ -118,7 +131,9 @@
 //! A pool has 4 administrative roles (see [`PoolRoles`]):
 //!
 //! * Depositor: creates the pool and is the initial member. They can only leave the pool once all
-//!   other members have left. Once they fully withdraw their funds, the pool is destroyed.
+//!   other members have left. Once they fully withdraw their funds, the pool is destroyed. While
+//!   the pool is open and has enough other members, they can reduce their bond down to
+//!   [`Pallet::depositor_unbond_floor`], see [`Config::MinMembersForDepositorUnbond`].
 //! * Nominator: can select which validators the pool nominates.
 //! * State-Toggler: can change the pools state and kick members if the pool is blocked.
 //! * Root: can change the nominator, state-toggler, or itself and can perform any of the actions
@@ -136,6 +151,12 @@
 //! > account's stake might not be able to ge below a certain threshold as a nominator. At this
 //! > point, the pool should `chill` itself to allow the depositor to leave. See [`Call::chill`].
 //!
+//! Should the bonded account of a pool lose its staking ledger, e.g. because it was force-unstaked
+//! by governance, the pool can no longer unbond or withdraw through the staking system, and every
+//! call that would reach it fails with [`Error::BondedLedgerMissing`]. Its members can then leave
+//! with [`Call::withdraw_orphaned`], which switches the pool to [`PoolState::Destroying`] and
+//! shares the balance left in its bonded account among them.
+//!
 //! ## Implementor's Guide
 //!
 //! Some notes and common mistakes that wallets/apps wishing to implement this pallet should be
@@ -159,6 +180,10 @@
 //!   least 2 DOT to join a pool. Consequently, if you are suggesting members to join a pool with
 //!   “Maximum possible value”, you must subtract 1 DOT to remain in the sender account to not
 //!   accidentally kill it.
+//! * Where the funds of members are kept is decided by [`Config::StakeAdapter`]. With
+//!   [`adapter::TransferStake`] they are transferred to the pool account as described above, but
+//!   an adapter may as well keep them on the member account under a hold, and only delegate them
+//!   to the pool.
 //! * Points and balance are not the same! Any pool member, at any point in time, can have points in
 //!   either the bonded pool or any of the unbonding pools. The crucial fact is that in any of these
 //!   pools, the ratio of point to balance is different and might not be 1. Each pool starts with a
@@ -237,9 +262,15 @@
 //! See [this link](https://hackmd.io/PFGn6wI5TbCmBYoEA_f2Uw) for an in-depth explanation of the
 //! reward pool mechanism.
 //!
+//! Rewards that are too small to be represented by the reward counter, which mostly happens in
+//! pools with a very large amount of points, can never be claimed by any member. They are tracked
+//! as the dust of the reward pool and can be swept with [`Call::sweep_dust`], either to the
+//! depositor of the pool or to the account configured as [`Config::DustReceiver`].
+//!
 //! **Relevant extrinsics:**
 //!
 //! * [`Call::claim_payout`]
+//! * [`Call::sweep_dust`]
 //!
 //! ### Unbonding sub pools
 //!
@@ -315,7 +346,8 @@
 //!   account. In the future this can be overcome by allowing the members to vote with their bonded
 //!   funds via vote splitting.
 //! * PoolMembers cannot quickly transfer to another pool if they do no like nominations, instead
-//!   they must wait for the unbonding duration.
+//!   they must wait for the unbonding duration, unless their pool has not been exposed recently.
+//!   See [`Call::switch_pool`].
 
 #![cfg_attr(not(feature = "std"), no_std)]
 
@@ -323,7 +355,7 @@ use codec::Codec;
 use frame_support::{
 	defensive, ensure,
 	pallet_prelude::{MaxEncodedLen, *},
-	storage::bounded_btree_map::BoundedBTreeMap,
+	storage::{bounded_btree_map::BoundedBTreeMap, with_storage_layer},
 	traits::{
 		Currency, Defensive, DefensiveOption, DefensiveResult, DefensiveSaturating,
 		ExistenceRequirement, Get,
@@ -334,9 +366,10 @@ use scale_info::TypeInfo;
 use sp_core::U256;
 use sp_runtime::{
 	traits::{
-		AccountIdConversion, CheckedAdd, CheckedSub, Convert, Saturating, StaticLookup, Zero,
+		AccountIdConversion, CheckedAdd, CheckedSub, Convert, Saturating, StaticLookup,
+		UniqueSaturatedInto, Zero,
 	},
-	FixedPointNumber,
+	FixedPointNumber, Perbill,
 };
 use sp_staking::{EraIndex, OnStakerSlash, StakingInterface};
 use sp_std::{collections::btree_map::BTreeMap, fmt::Debug, ops::Div, vec::Vec};
@@ -360,9 +393,11 @@ pub mod mock;
 #[cfg(test)]
 mod tests;
 
+pub mod adapter;
 pub mod migration;
 pub mod weights;
 
+pub use adapter::StakeAdapter;
 pub use pallet::*;
 pub use weights::WeightInfo;
 
@@ -388,7 +423,8 @@ pub enum ConfigOp<T: Codec + Debug> {
 }
 
 /// The type of bonding that can happen to a pool.
-enum BondType {
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BondType {
 	/// Someone is bonding into the pool upon creation.
 	Create,
 	/// Someone is adding more funds later to this pool.
@@ -427,6 +463,16 @@ pub struct PoolMember<T: Config> {
 	/// The eras in which this member is unbonding, mapped from era index to the number of
 	/// points scheduled to unbond in the given era.
 	pub unbonding_eras: BoundedBTreeMap<EraIndex, BalanceOf<T>, T::MaxUnbonding>,
+	/// The account that referred this member to the pool, if any.
+	///
+	/// This can only be set when joining and receives [`Config::ReferrerCommission`] of the
+	/// commission that the pool charges on the member's payouts.
+	pub referrer: Option<T::AccountId>,
+	/// The block at which this member joined the pool.
+	///
+	/// A member can only unbond by themselves once the [`MinMembershipDuration`] of the pool has
+	/// passed since this block.
+	pub joined_at: T::BlockNumber,
 }
 
 impl<T: Config> PoolMember<T> {
@@ -435,7 +481,7 @@ impl<T: Config> PoolMember<T> {
 		&self,
 		current_reward_counter: T::RewardCounter,
 	) -> Result<BalanceOf<T>, Error<T>> {
-		// accuracy note: Reward counters are `FixedU128` with base of 10^18. This value is being
+		// accuracy note: Reward counters are `FixedU128E24` with base of 10^24. This value is being
 		// multiplied by a point. The worse case of a point is 10x the granularity of the balance
 		// (10x is the common configuration of `MaxPointsToBalance`).
 		//
@@ -444,13 +490,13 @@ impl<T: Config> PoolMember<T> {
 		//
 		// The final multiplication is:
 		//
-		// rc * 10^20 / 10^18 = rc * 100
+		// rc * 10^20 / 10^24 = rc / 10^4
 		//
 		// the implementation of `multiply_by_rational_with_rounding` shows that it will only fail
-		// if the final division is not enough to fit in u128. In other words, if `rc * 100` is more
-		// than u128::max. Given that RC is interpreted as reward per unit of point, and unit of
-		// point is equal to balance (normally), and rewards are usually a proportion of the points
-		// in the pool, the likelihood of rc reaching near u128::MAX is near impossible.
+		// if the final division is not enough to fit in u128. In other words, if `rc / 10^4` is
+		// more than u128::max. Given that RC is interpreted as reward per unit of point, and unit
+		// of point is equal to balance (normally), and rewards are usually a proportion of the
+		// points in the pool, the likelihood of rc reaching near u128::MAX is near impossible.
 
 		(current_reward_counter.defensive_saturating_sub(self.last_recorded_reward_counter))
 			.checked_mul_int(self.active_points())
@@ -462,11 +508,9 @@ impl<T: Config> PoolMember<T> {
 	/// This is derived from the ratio of points in the pool to which the member belongs to.
 	/// Might return different values based on the pool state for the same member and points.
 	fn active_balance(&self) -> BalanceOf<T> {
-		if let Some(pool) = BondedPool::<T>::get(self.pool_id).defensive() {
-			pool.points_to_balance(self.points)
-		} else {
-			Zero::zero()
-		}
+		Pallet::<T>::points_to_balance(self.pool_id, self.points)
+			.defensive()
+			.unwrap_or_default()
 	}
 
 	/// Total points of this member, both active and unbonding.
@@ -680,6 +724,15 @@ impl<T: Config> BondedPool<T> {
 		Pallet::<T>::point_to_balance(bonded_balance, self.points, points)
 	}
 
+	/// Deposit an [`Event::PoolUpdated`] with the current points and active balance of the pool.
+	fn deposit_updated_event(&self) {
+		Pallet::<T>::deposit_event(Event::<T>::PoolUpdated {
+			pool_id: self.id,
+			points: self.points,
+			balance: T::Staking::active_stake(&self.bonded_account()).unwrap_or(Zero::zero()),
+		});
+	}
+
 	/// Issue points to [`Self`] for `new_funds`.
 	fn issue(&mut self, new_funds: BalanceOf<T>) -> BalanceOf<T> {
 		let points_to_issue = self.balance_to_point(new_funds);
@@ -725,9 +778,22 @@ impl<T: Config> BondedPool<T> {
 
 	/// The pools balance that is transferrable.
 	fn transferrable_balance(&self) -> BalanceOf<T> {
-		let account = self.bonded_account();
-		T::Currency::free_balance(&account)
-			.saturating_sub(T::Staking::active_stake(&account).unwrap_or_default())
+		T::StakeAdapter::transferable_balance(&self.bonded_account())
+	}
+
+	/// Whether the staking ledger of the bonded account is gone.
+	///
+	/// This never happens through this pallet while the pool exists, but the bonded account can
+	/// still be force-unstaked from the outside, e.g. by governance.
+	fn ledger_missing(&self) -> bool {
+		T::Staking::stake(&self.bonded_account()).is_err()
+	}
+
+	/// Check that the pool can still interact with the staking system, i.e. that the staking
+	/// ledger of its bonded account exists.
+	fn ok_to_stake(&self) -> Result<(), DispatchError> {
+		ensure!(!self.ledger_missing(), Error::<T>::BondedLedgerMissing);
+		Ok(())
 	}
 
 	fn is_root(&self, who: &T::AccountId) -> bool {
@@ -762,10 +828,25 @@ impl<T: Config> BondedPool<T> {
 		self.is_root(who) || self.is_state_toggler(who)
 	}
 
+	fn can_set_min_membership_duration(&self, who: &T::AccountId) -> bool {
+		self.is_root(who)
+	}
+
+	fn can_set_commission(&self, who: &T::AccountId) -> bool {
+		self.is_root(who)
+	}
+
 	fn is_destroying(&self) -> bool {
 		matches!(self.state, PoolState::Destroying)
 	}
 
+	/// Whether `member` has been in the pool for at least its [`MinMembershipDuration`].
+	fn min_membership_duration_elapsed(&self, member: &PoolMember<T>) -> bool {
+		MinMembershipDuration::<T>::get(self.id).map_or(true, |duration| {
+			frame_system::Pallet::<T>::block_number() >= member.joined_at.saturating_add(duration)
+		})
+	}
+
 	fn is_destroying_and_only_depositor(&self, alleged_depositor_points: BalanceOf<T>) -> bool {
 		// we need to ensure that `self.member_counter == 1` as well, because the depositor's
 		// initial `MinCreateBond` (or more) is what guarantees that the ledger of the pool does not
@@ -776,6 +857,13 @@ impl<T: Config> BondedPool<T> {
 		self.is_destroying() && self.points == alleged_depositor_points && self.member_counter == 1
 	}
 
+	/// Whether this pool is open and has enough other members for its depositor to unbond down to
+	/// [`Pallet::depositor_unbond_floor`], see [`Config::MinMembersForDepositorUnbond`].
+	fn depositor_can_unbond_to_floor(&self) -> bool {
+		self.state == PoolState::Open &&
+			self.member_counter.saturating_sub(1) >= T::MinMembersForDepositorUnbond::get()
+	}
+
 	/// Whether or not the pool is ok to be in `PoolSate::Open`. If this returns an `Err`, then the
 	/// pool is unrecoverable and should be in the destroying state.
 	fn ok_to_be_open(&self) -> Result<(), DispatchError> {
@@ -806,6 +894,18 @@ impl<T: Config> BondedPool<T> {
 		Ok(())
 	}
 
+	/// Whether or not anyone can set the pool to `PoolState::Destroying`.
+	///
+	/// This is the case if the pool is not ok to be open, has no points left, or its active
+	/// bonded balance has been slashed below [`Pallet::depositor_min_bond`].
+	fn ok_to_be_destroyed_permissionlessly(&self) -> bool {
+		let bonded_balance =
+			T::Staking::active_stake(&self.bonded_account()).unwrap_or(Zero::zero());
+		self.ok_to_be_open().is_err() ||
+			self.points.is_zero() ||
+			bonded_balance < Pallet::<T>::depositor_min_bond()
+	}
+
 	/// Check that the pool can accept a member with `new_funds`.
 	fn ok_to_join(&self) -> Result<(), DispatchError> {
 		ensure!(self.state == PoolState::Open, Error::<T>::NotOpen);
@@ -838,18 +938,25 @@ impl<T: Config> BondedPool<T> {
 			Error::<T>::PartialUnbondNotAllowedPermissionlessly
 		);
 
-		// any unbond must comply with the balance condition:
+		// members leaving on their own must have stayed for the minimum membership duration,
+		// unless the pool is being destroyed. Kicks are never restricted by it.
 		ensure!(
-			is_full_unbond ||
-				balance_after_unbond >=
-					if is_depositor {
-						Pallet::<T>::depositor_min_bond()
-					} else {
-						MinJoinBond::<T>::get()
-					},
-			Error::<T>::MinimumBondNotMet
+			!is_permissioned ||
+				self.is_destroying() ||
+				self.min_membership_duration_elapsed(target_member),
+			Error::<T>::MinMembershipDurationNotElapsed
 		);
 
+		// any unbond must comply with the balance condition. The depositor of an open pool with
+		// enough other members only needs to keep what it takes to create or join a pool.
+		let (min_balance, min_balance_error) = match (is_depositor, is_permissioned) {
+			(true, true) if self.depositor_can_unbond_to_floor() =>
+				(Pallet::<T>::depositor_unbond_floor(), Error::<T>::DepositorBondBelowFloor),
+			(true, _) => (Pallet::<T>::depositor_min_bond(), Error::<T>::MinimumBondNotMet),
+			(false, _) => (MinJoinBond::<T>::get(), Error::<T>::MinimumBondNotMet),
+		};
+		ensure!(is_full_unbond || balance_after_unbond >= min_balance, min_balance_error);
+
 		// additional checks:
 		match (is_permissioned, is_depositor) {
 			(true, false) => (),
@@ -898,10 +1005,11 @@ impl<T: Config> BondedPool<T> {
 		Ok(())
 	}
 
-	/// Bond exactly `amount` from `who`'s funds into this pool.
+	/// Bond exactly `amount` from `who`'s funds into this pool, through
+	/// [`StakeAdapter::pledge_bond`].
 	///
-	/// If the bond type is `Create`, `Staking::bond` is called, and `who`
-	/// is allowed to be killed. Otherwise, `Staking::bond_extra` is called and `who`
+	/// If the bond type is `Create`, the pool is bonded for the first time, and `who`
+	/// is allowed to be killed. Otherwise, the bond of the pool is extended and `who`
 	/// cannot be killed.
 	///
 	/// Returns `Ok(points_issues)`, `Err` otherwise.
@@ -911,40 +1019,31 @@ impl<T: Config> BondedPool<T> {
 		amount: BalanceOf<T>,
 		ty: BondType,
 	) -> Result<BalanceOf<T>, DispatchError> {
-		// Cache the value
-		let bonded_account = self.bonded_account();
-		T::Currency::transfer(
-			&who,
-			&bonded_account,
-			amount,
-			match ty {
-				BondType::Create => ExistenceRequirement::AllowDeath,
-				BondType::Later => ExistenceRequirement::KeepAlive,
-			},
-		)?;
 		// We must calculate the points issued *before* we bond who's funds, else points:balance
 		// ratio will be wrong.
 		let points_issued = self.issue(amount);
 
-		match ty {
-			BondType::Create => T::Staking::bond(&bonded_account, amount, &self.reward_account())?,
-			// The pool should always be created in such a way its in a state to bond extra, but if
-			// the active balance is slashed below the minimum bonded or the account cannot be
-			// found, we exit early.
-			BondType::Later => T::Staking::bond_extra(&bonded_account, amount)?,
-		}
+		T::StakeAdapter::pledge_bond(
+			who,
+			&self.bonded_account(),
+			&self.reward_account(),
+			amount,
+			ty,
+		)?;
+		TotalValueLocked::<T>::mutate(|tvl| tvl.defensive_saturating_accrue(amount));
 
 		Ok(points_issued)
 	}
 
 	// Set the state of `self`, and deposit an event if the state changed. State should never be set
 	// directly in in order to ensure a state change event is always correctly deposited.
-	fn set_state(&mut self, state: PoolState) {
+	fn set_state(&mut self, state: PoolState, caller: &T::AccountId) {
 		if self.state != state {
 			self.state = state;
 			Pallet::<T>::deposit_event(Event::<T>::StateChanged {
 				pool_id: self.id,
 				new_state: state,
+				caller: caller.clone(),
 			});
 		};
 	}
@@ -973,29 +1072,107 @@ pub struct RewardPool<T: Config> {
 	last_recorded_total_payouts: BalanceOf<T>,
 	/// Total amount that this pool has paid out so far to the members.
 	total_rewards_claimed: BalanceOf<T>,
+	/// Rewards that can not be claimed by any member because they were lost to rounding in the
+	/// reward counter, and have not been swept yet.
+	///
+	/// See [`Call::sweep_dust`].
+	dust: BalanceOf<T>,
 }
 
 impl<T: Config> RewardPool<T> {
 	/// Getter for [`RewardPool::last_recorded_reward_counter`].
-	pub(crate) fn last_recorded_reward_counter(&self) -> T::RewardCounter {
+	pub fn last_recorded_reward_counter(&self) -> T::RewardCounter {
 		self.last_recorded_reward_counter
 	}
 
+	/// Getter for [`RewardPool::last_recorded_total_payouts`].
+	pub fn last_recorded_total_payouts(&self) -> BalanceOf<T> {
+		self.last_recorded_total_payouts
+	}
+
+	/// Getter for [`RewardPool::dust`].
+	pub fn dust(&self) -> BalanceOf<T> {
+		self.dust
+	}
+
 	/// Register some rewards that are claimed from the pool by the members.
 	fn register_claimed_reward(&mut self, reward: BalanceOf<T>) {
 		self.total_rewards_claimed = self.total_rewards_claimed.saturating_add(reward);
 	}
 
+	/// Register that all of the dust has been swept from the pool.
+	///
+	/// The dust leaves the reward account like a claimed reward, such that the payouts of the
+	/// pool are not affected by it.
+	fn register_swept_dust(&mut self) {
+		let dust = sp_std::mem::take(&mut self.dust);
+		self.register_claimed_reward(dust);
+	}
+
+	/// The balance that the reward account is expected to hold according to the recorded
+	/// values, i.e. the recorded payouts that have not been claimed yet.
+	fn expected_balance(&self) -> BalanceOf<T> {
+		self.last_recorded_total_payouts.saturating_sub(self.total_rewards_claimed)
+	}
+
+	/// Lower the recorded payouts to match the actual `balance` of the reward account.
+	///
+	/// Returns `false` if the recorded payouts are already covered by `balance`.
+	fn reconcile(&mut self, balance: BalanceOf<T>) -> bool {
+		if balance >= self.expected_balance() {
+			return false
+		}
+		self.last_recorded_total_payouts = balance.saturating_add(self.total_rewards_claimed);
+		self.dust = self.dust.min(balance);
+		true
+	}
+
 	/// Update the recorded values of the pool.
 	fn update_records(&mut self, id: PoolId, bonded_points: BalanceOf<T>) -> Result<(), Error<T>> {
 		let balance = Self::current_balance(id);
-		self.last_recorded_reward_counter = self.current_reward_counter(id, bonded_points)?;
+		let reward_counter = self.current_reward_counter(id, bonded_points)?;
+		self.record_dust(balance, reward_counter, bonded_points);
+		self.last_recorded_reward_counter = reward_counter;
 		self.last_recorded_total_payouts = balance
 			.checked_add(&self.total_rewards_claimed)
 			.ok_or(Error::<T>::OverflowRisk)?;
 		Ok(())
 	}
 
+	/// Record the part of the payouts since the last record that is not represented by moving
+	/// the reward counter to `reward_counter`, and can thus never be claimed by any member.
+	fn record_dust(
+		&mut self,
+		balance: BalanceOf<T>,
+		reward_counter: T::RewardCounter,
+		bonded_points: BalanceOf<T>,
+	) {
+		let increment = reward_counter.saturating_sub(self.last_recorded_reward_counter);
+		// Equivalent of `increment * bonded_points`, rounded up. The members claim their share of
+		// it rounded down, possibly at a later reward counter, so rounding up makes sure that the
+		// dust never contains anything that a member might still claim.
+		let u256 = |x: <T::RewardCounter as FixedPointNumber>::Inner| {
+			U256::from(UniqueSaturatedInto::<u128>::unique_saturated_into(x))
+		};
+		let div = u256(T::RewardCounter::DIV);
+		let claimable = T::U256ToBalance::convert(
+			u256(increment.into_inner())
+				.saturating_mul(T::BalanceToU256::convert(bonded_points))
+				.saturating_add(div.saturating_sub(U256::one()))
+				.div(div),
+		);
+		let unclaimable = self.payouts_since_last_record(balance).saturating_sub(claimable);
+		self.dust = self.dust.saturating_add(unclaimable);
+	}
+
+	/// The payouts of the pool since the last time the records were updated, given its current
+	/// `balance`.
+	fn payouts_since_last_record(&self, balance: BalanceOf<T>) -> BalanceOf<T> {
+		balance
+			.saturating_add(self.total_rewards_claimed)
+			.saturating_sub(self.last_recorded_total_payouts)
+	}
+
 	/// Get the current reward counter, based on the given `bonded_points` being the state of the
 	/// bonded pool at this time.
 	fn current_reward_counter(
@@ -1003,10 +1180,7 @@ impl<T: Config> RewardPool<T> {
 		id: PoolId,
 		bonded_points: BalanceOf<T>,
 	) -> Result<T::RewardCounter, Error<T>> {
-		let balance = Self::current_balance(id);
-		let payouts_since_last_record = balance
-			.saturating_add(self.total_rewards_claimed)
-			.saturating_sub(self.last_recorded_total_payouts);
+		let payouts_since_last_record = self.payouts_since_last_record(Self::current_balance(id));
 
 		// * accuracy notes regarding the multiplication in `checked_from_rational`:
 		// `payouts_since_last_record` is a subset of the total_issuance at the very
@@ -1014,33 +1188,36 @@ impl<T: Config> RewardPool<T> {
 		// balance, and are thus below within the range of total_issuance. In the worse case
 		// scenario, for `saturating_from_rational`, we have:
 		//
-		// dot_total_issuance * 10^18 / `minJoinBond`
+		// dot_total_issuance * 10^24 / `minJoinBond`
 		//
 		// assuming `MinJoinBond == ED`
 		//
-		// dot_total_issuance * 10^18 / 10^10 = dot_total_issuance * 10^8
+		// dot_total_issuance * 10^24 / 10^10 = dot_total_issuance * 10^14
 		//
-		// which, with the current numbers, is a miniscule fraction of the u128 capacity.
+		// which, with the current numbers, is around 10^33 and thus still well within the u128
+		// capacity.
 		//
 		// Thus, adding two values of type reward counter should be safe for ages in a chain like
 		// Polkadot. The important note here is that `reward_pool.last_recorded_reward_counter` only
-		// ever accumulates, but its semantics imply that it is less than total_issuance, when
-		// represented as `FixedU128`, which means it is less than `total_issuance * 10^18`.
+		// ever accumulates, but its semantics imply that it is less than total_issuance / ED, when
+		// represented as `FixedU128E24`, which means it is less than `total_issuance * 10^14`.
 		//
 		// * accuracy notes regarding `checked_from_rational` collapsing to zero, meaning that no
 		// reward can be claimed:
 		//
-		// largest `bonded_points`, such that the reward counter is non-zero, with `FixedU128`
+		// largest `bonded_points`, such that the reward counter is non-zero, with `FixedU128E24`
 		// will be when the payout is being computed. This essentially means `payout/bonded_points`
-		// needs to be more than 1/1^18. Thus, assuming that `bonded_points` will always be less
+		// needs to be more than 1/10^24. Thus, assuming that `bonded_points` will always be less
 		// than `10 * dot_total_issuance`, if the reward_counter is the smallest possible value,
 		// the value of the reward being calculated is:
 		//
-		// x / 10^20 = 1/ 10^18
+		// x / 10^20 = 1/ 10^24
 		//
-		// x = 100
+		// x = 10^-4
 		//
-		// which is basically 10^-8 DOTs. See `smallest_claimable_reward` for an example of this.
+		// which is less than a single unit of balance, so no reward is lost to the rounding of the
+		// reward counter. With `FixedU128`, it would be 100 units, or 10^-8 DOTs. See
+		// `smallest_claimable_reward` for an example of this.
 		T::RewardCounter::checked_from_rational(payouts_since_last_record, bonded_points)
 			.and_then(|ref r| self.last_recorded_reward_counter.checked_add(r))
 			.ok_or(Error::<T>::OverflowRisk)
@@ -1051,7 +1228,7 @@ impl<T: Config> RewardPool<T> {
 	/// This is sum of all the rewards that are claimable by pool members.
 	fn current_balance(id: PoolId) -> BalanceOf<T> {
 		T::Currency::free_balance(&Pallet::<T>::create_reward_account(id))
-			.saturating_sub(T::Currency::minimum_balance())
+			.saturating_sub(RewardAccountDeposits::<T>::get(id))
 	}
 }
 
@@ -1143,7 +1320,6 @@ impl<T: Config> SubPools<T> {
 	}
 
 	/// The sum of all unbonding balance, regardless of whether they are actually unlocked or not.
-	#[cfg(any(feature = "try-runtime", feature = "fuzzing", test, debug_assertions))]
 	fn sum_unbonding_balance(&self) -> BalanceOf<T> {
 		self.no_era.balance.saturating_add(
 			self.with_era
@@ -1151,6 +1327,31 @@ impl<T: Config> SubPools<T> {
 				.fold(BalanceOf::<T>::zero(), |acc, pool| acc.saturating_add(pool.balance)),
 		)
 	}
+
+	/// Convert `points` that were unbonded in `era` to balance, given the current state of the
+	/// unbonding pool that holds them.
+	fn point_to_balance(&self, era: EraIndex, points: BalanceOf<T>) -> BalanceOf<T> {
+		// A pool that does not belong to this era must have been merged to the era-less pool.
+		self.with_era.get(&era).unwrap_or(&self.no_era).point_to_balance(points)
+	}
+
+	/// Dissolve `points` that were unbonded in `era` from the unbonding pool that holds them,
+	/// removing the pool of the era once it is empty.
+	///
+	/// Returns the actual amount of `Balance` that was removed, see [`UnbondPool::dissolve`].
+	fn dissolve(&mut self, era: EraIndex, points: BalanceOf<T>) -> BalanceOf<T> {
+		if let Some(era_pool) = self.with_era.get_mut(&era) {
+			let balance = era_pool.dissolve(points);
+			if era_pool.points.is_zero() {
+				self.with_era.remove(&era);
+			}
+			balance
+		} else {
+			// A pool does not belong to this era, so it must have been merged to the era-less
+			// pool.
+			self.no_era.dissolve(points)
+		}
+	}
 }
 
 /// The maximum amount of eras an unbonding pool can exist prior to being merged with the
@@ -1173,7 +1374,7 @@ pub mod pallet {
 	use frame_system::{ensure_signed, pallet_prelude::*};
 
 	/// The current storage version.
-	const STORAGE_VERSION: StorageVersion = StorageVersion::new(3);
+	const STORAGE_VERSION: StorageVersion = StorageVersion::new(10);
 
 	#[pallet::pallet]
 	#[pallet::generate_store(pub(crate) trait Store)]
@@ -1200,9 +1401,14 @@ pub mod pallet {
 		/// a PITA to do).
 		///
 		/// See the inline code docs of `Member::pending_rewards` and `RewardPool::update_recorded`
-		/// for example analysis. A [`sp_runtime::FixedU128`] should be fine for chains with balance
-		/// types similar to that of Polkadot and Kusama, in the absence of severe slashing (or
-		/// prevented via a reasonable `MaxPointsToBalance`), for many many years to come.
+		/// for example analysis. A [`sp_runtime::FixedU128E24`] should be fine for chains with
+		/// balance types similar to that of Polkadot and Kusama, in the absence of severe slashing
+		/// (or prevented via a reasonable `MaxPointsToBalance`), for many many years to come.
+		///
+		/// The precision of this type determines the smallest reward that a pool can record. With
+		/// a [`sp_runtime::FixedU128`], pools with very large amounts of points lose small rewards
+		/// to rounding, which are tracked as dust, see [`Call::sweep_dust`]. Chains that switch
+		/// from it to a [`sp_runtime::FixedU128E24`] must run [`migration::v10::MigrateToV10`].
 		type RewardCounter: FixedPointNumber + MaxEncodedLen + TypeInfo + Default + codec::FullCodec;
 
 		/// The nomination pool's pallet id.
@@ -1221,6 +1427,9 @@ pub mod pallet {
 		///
 		/// For a value of 10, the threshold would be a pool points-to-balance ratio of 10:1.
 		/// Such a scenario would also be the equivalent of the pool being 90% slashed.
+		///
+		/// Funds can only be added to a pool, through [`Call::join`] or [`Call::bond_extra`],
+		/// while it is below this threshold. Otherwise they fail with [`Error::OverflowRisk`].
 		#[pallet::constant]
 		type MaxPointsToBalance: Get<u8>;
 
@@ -1233,6 +1442,11 @@ pub mod pallet {
 		/// The interface for nominating.
 		type Staking: StakingInterface<Balance = BalanceOf<Self>, AccountId = Self::AccountId>;
 
+		/// How the funds of members are held and made to count towards the stake of their pool.
+		///
+		/// [`adapter::TransferStake`] transfers them into the bonded account of the pool.
+		type StakeAdapter: StakeAdapter<Balance = BalanceOf<Self>, AccountId = Self::AccountId>;
+
 		/// The amount of eras a `SubPools::with_era` pool can exist before it gets merged into the
 		/// `SubPools::no_era` pool. In other words, this is the amount of eras a member will be
 		/// able to withdraw from an unbonding pool which is guaranteed to have the correct ratio of
@@ -1244,7 +1458,36 @@ pub mod pallet {
 		type MaxMetadataLen: Get<u32>;
 
 		/// The maximum number of simultaneous unbonding chunks that can exist per member.
+		///
+		/// Unbonding multiple times within the same era only consumes a single chunk.
+		#[pallet::constant]
 		type MaxUnbonding: Get<u32>;
+
+		/// The share of the pool commission on a member's payout that is paid to the account
+		/// that referred them. The rest of it is paid to the payee of the [`Commission`].
+		#[pallet::constant]
+		type ReferrerCommission: Get<Perbill>;
+
+		/// The maximum [`Commission`] that the root of a pool can set.
+		#[pallet::constant]
+		type MaxCommission: Get<Perbill>;
+
+		/// The minimum number of other members an open pool must have for its depositor to unbond
+		/// below [`Pallet::depositor_min_bond`], down to [`Pallet::depositor_unbond_floor`].
+		///
+		/// The depositor can still only fully unbond once the pool is destroying and they are its
+		/// last member.
+		#[pallet::constant]
+		type MinMembersForDepositorUnbond: Get<u32>;
+
+		/// The maximum [`MinMembershipDuration`] that the root of a pool can set, in blocks.
+		#[pallet::constant]
+		type MaxMinMembershipDuration: Get<Self::BlockNumber>;
+
+		/// The account that the dust of all pools is swept to, for example the treasury.
+		///
+		/// If `None`, the dust of a pool is swept to its depositor. See [`Call::sweep_dust`].
+		type DustReceiver: Get<Option<Self::AccountId>>;
 	}
 
 	/// Minimum amount to bond to join a pool.
@@ -1261,6 +1504,14 @@ pub mod pallet {
 	#[pallet::storage]
 	pub type MinCreateBond<T: Config> = StorageValue<_, BalanceOf<T>, ValueQuery>;
 
+	/// The sum of the funds staked by all pools, active and unlocking.
+	///
+	/// This is kept equal to the sum of the total stake of all pool bonded accounts, and is
+	/// updated whenever they bond, withdraw or get slashed.
+	#[pallet::storage]
+	#[pallet::getter(fn total_value_locked)]
+	pub type TotalValueLocked<T: Config> = StorageValue<_, BalanceOf<T>, ValueQuery>;
+
 	/// Maximum number of nomination pools that can exist. If `None`, then an unbounded number of
 	/// pools can exist.
 	#[pallet::storage]
@@ -1281,6 +1532,21 @@ pub mod pallet {
 	pub type PoolMembers<T: Config> =
 		CountedStorageMap<_, Twox64Concat, T::AccountId, PoolMember<T>>;
 
+	/// The members of each pool, as a secondary index of [`PoolMembers`].
+	///
+	/// The members of the pools that existed before this index are only partially indexed as long
+	/// as [`PoolMembersIndexCursor`] is set.
+	#[pallet::storage]
+	pub type PoolMembersByPool<T: Config> =
+		StorageDoubleMap<_, Twox64Concat, PoolId, Twox64Concat, T::AccountId, (), OptionQuery>;
+
+	/// Set as long as not all members that predate [`PoolMembersByPool`] are indexed, see
+	/// [`Call::migrate_members`].
+	///
+	/// The value is the last member account that has been checked, if any.
+	#[pallet::storage]
+	pub type PoolMembersIndexCursor<T: Config> = StorageValue<_, Option<T::AccountId>, OptionQuery>;
+
 	/// Storage for bonded pools.
 	// To get or insert a pool see [`BondedPool::get`] and [`BondedPool::put`]
 	#[pallet::storage]
@@ -1292,6 +1558,27 @@ pub mod pallet {
 	#[pallet::storage]
 	pub type RewardPools<T: Config> = CountedStorageMap<_, Twox64Concat, PoolId, RewardPool<T>>;
 
+	#[pallet::type_value]
+	pub fn RewardAccountDepositOnEmpty<T: Config>() -> BalanceOf<T> {
+		T::Currency::minimum_balance()
+	}
+
+	/// The deposit that keeps the reward account of each pool alive.
+	///
+	/// It is put in by the depositor and is not part of the rewards of the pool. This is the
+	/// existential deposit at the time the pool was created, or at the last
+	/// [`Call::adjust_pool_deposit`]. Pools without an entry are assumed to hold the current
+	/// existential deposit.
+	#[pallet::storage]
+	pub type RewardAccountDeposits<T: Config> = StorageMap<
+		_,
+		Twox64Concat,
+		PoolId,
+		BalanceOf<T>,
+		ValueQuery,
+		RewardAccountDepositOnEmpty<T>,
+	>;
+
 	/// Groups of unbonding pools. Each group of unbonding pools belongs to a bonded pool,
 	/// hence the name sub-pools. Keyed by the bonded pools account.
 	#[pallet::storage]
@@ -1302,6 +1589,23 @@ pub mod pallet {
 	pub type Metadata<T: Config> =
 		CountedStorageMap<_, Twox64Concat, PoolId, BoundedVec<u8, T::MaxMetadataLen>, ValueQuery>;
 
+	/// The minimum number of blocks a member has to stay in a pool before they can unbond.
+	///
+	/// Kicked members and members of a destroying pool are not bound by it. Pools without an entry
+	/// have no minimum.
+	#[pallet::storage]
+	pub type MinMembershipDuration<T: Config> =
+		StorageMap<_, Twox64Concat, PoolId, T::BlockNumber, OptionQuery>;
+
+	/// The share of the payouts of its members that a pool charges as commission, and the account
+	/// it is paid to.
+	///
+	/// [`Config::ReferrerCommission`] of it goes to the referrer of the member instead, if any.
+	/// Pools without an entry charge no commission.
+	#[pallet::storage]
+	pub type Commission<T: Config> =
+		StorageMap<_, Twox64Concat, PoolId, (Perbill, T::AccountId), OptionQuery>;
+
 	/// Ever increasing number of all pools created so far.
 	#[pallet::storage]
 	pub type LastPoolId<T: Config> = StorageValue<_, u32, ValueQuery>;
@@ -1314,6 +1618,13 @@ pub mod pallet {
 	pub type ReversePoolIdLookup<T: Config> =
 		CountedStorageMap<_, Twox64Concat, T::AccountId, PoolId, OptionQuery>;
 
+	/// The members that requested to switch to another pool, mapped to that pool.
+	///
+	/// These are processed in `on_idle`, see [`Call::switch_pool`].
+	#[pallet::storage]
+	pub type SwitchPoolRequests<T: Config> =
+		CountedStorageMap<_, Twox64Concat, T::AccountId, PoolId, OptionQuery>;
+
 	#[pallet::genesis_config]
 	pub struct GenesisConfig<T: Config> {
 		pub min_join_bond: BalanceOf<T>,
@@ -1360,9 +1671,35 @@ pub mod pallet {
 		/// A pool has been created.
 		Created { depositor: T::AccountId, pool_id: PoolId },
 		/// A member has became bonded in a pool.
-		Bonded { member: T::AccountId, pool_id: PoolId, bonded: BalanceOf<T>, joined: bool },
+		///
+		/// `points` is the number of points issued to the member in return of `bonded`.
+		Bonded {
+			member: T::AccountId,
+			pool_id: PoolId,
+			bonded: BalanceOf<T>,
+			points: BalanceOf<T>,
+			joined: bool,
+		},
 		/// A payout has been made to a member.
 		PaidOut { member: T::AccountId, pool_id: PoolId, payout: BalanceOf<T> },
+		/// A member has joined a pool on referral of `referrer`.
+		Referred { member: T::AccountId, pool_id: PoolId, referrer: T::AccountId },
+		/// The referrer of `member` has been paid their share of the commission on the member's
+		/// payout.
+		ReferrerPaidOut {
+			referrer: T::AccountId,
+			member: T::AccountId,
+			pool_id: PoolId,
+			payout: BalanceOf<T>,
+		},
+		/// The commission of pool `pool_id` on the payout of `member` has been paid to `payee`,
+		/// without the share of the referrer of `member`.
+		CommissionPaidOut {
+			payee: T::AccountId,
+			member: T::AccountId,
+			pool_id: PoolId,
+			payout: BalanceOf<T>,
+		},
 		/// A member has unbonded from their pool.
 		///
 		/// - `balance` is the corresponding balance of the number of points that has been
@@ -1395,8 +1732,8 @@ pub mod pallet {
 		},
 		/// A pool has been destroyed.
 		Destroyed { pool_id: PoolId },
-		/// The state of a pool has changed
-		StateChanged { pool_id: PoolId, new_state: PoolState },
+		/// The state of a pool has changed by `caller`.
+		StateChanged { pool_id: PoolId, new_state: PoolState, caller: T::AccountId },
 		/// A member has been removed from a pool.
 		///
 		/// The removal can be voluntary (withdrawn all unbonded funds) or involuntary (kicked).
@@ -1408,10 +1745,70 @@ pub mod pallet {
 			state_toggler: Option<T::AccountId>,
 			nominator: Option<T::AccountId>,
 		},
-		/// The active balance of pool `pool_id` has been slashed to `balance`.
-		PoolSlashed { pool_id: PoolId, balance: BalanceOf<T> },
-		/// The unbond pool at `era` of pool `pool_id` has been slashed to `balance`.
-		UnbondingPoolSlashed { pool_id: PoolId, era: EraIndex, balance: BalanceOf<T> },
+		/// The active balance of pool `pool_id` has been slashed to `balance`, for an offence in
+		/// `slash_era`.
+		PoolSlashed { pool_id: PoolId, balance: BalanceOf<T>, slash_era: EraIndex },
+		/// The unbond pool at `era` of pool `pool_id` has been slashed to `balance`, for an
+		/// offence in `slash_era`.
+		UnbondingPoolSlashed {
+			pool_id: PoolId,
+			era: EraIndex,
+			balance: BalanceOf<T>,
+			slash_era: EraIndex,
+		},
+		/// The dust of pool `pool_id` has been swept to `beneficiary`.
+		DustSwept { pool_id: PoolId, beneficiary: T::AccountId, amount: BalanceOf<T> },
+		/// The reward pool of `pool_id` was audited.
+		///
+		/// `expected` is the balance its reward account should hold according to the recorded
+		/// payouts, `actual` is what it holds. If `adjusted`, the recorded payouts have been
+		/// lowered to `actual`.
+		RewardPoolReconciled {
+			pool_id: PoolId,
+			expected: BalanceOf<T>,
+			actual: BalanceOf<T>,
+			adjusted: bool,
+		},
+		/// The minimum membership duration of pool `pool_id` has been set, or removed if `None`.
+		MinMembershipDurationUpdated { pool_id: PoolId, duration: Option<T::BlockNumber> },
+		/// The commission of pool `pool_id` has been set, or removed if `None`.
+		CommissionUpdated { pool_id: PoolId, commission: Option<(Perbill, T::AccountId)> },
+		/// The bonded pool `pool_id` now has a total of `points` backed by an active `balance`.
+		///
+		/// This is emitted after every bond and unbond, so that the exchange rate of a pool can
+		/// be followed from events alone. Slashes don't change the points of a pool, its new
+		/// balance is reported by `PoolSlashed` instead.
+		PoolUpdated { pool_id: PoolId, points: BalanceOf<T>, balance: BalanceOf<T> },
+		/// The bonded account of pool `pool_id` lost its staking ledger and the pool has been
+		/// switched to destroying.
+		///
+		/// `balance` is what was left in the bonded account at that point. It is shared among the
+		/// members as they leave through [`Call::withdraw_orphaned`].
+		LedgerLost { pool_id: PoolId, balance: BalanceOf<T> },
+		/// `checked` members have been looked at for indexing them in [`PoolMembersByPool`]. If
+		/// `complete`, all members are indexed now.
+		MembersIndexed { checked: u32, complete: bool },
+		/// A member of pool `pool_id` has requested to switch to pool `new_pool_id`.
+		SwitchRequested { member: T::AccountId, pool_id: PoolId, new_pool_id: PoolId },
+		/// A member has moved the `balance` of its points in pool `pool_id` to pool `new_pool_id`,
+		/// where it has been issued `points`.
+		Switched {
+			member: T::AccountId,
+			pool_id: PoolId,
+			new_pool_id: PoolId,
+			balance: BalanceOf<T>,
+			points: BalanceOf<T>,
+		},
+		/// The request of a member to switch to pool `new_pool_id` failed with `error`.
+		///
+		/// If the error is [`Error::PoolExposed`], the member has to unbond instead.
+		SwitchFailed { member: T::AccountId, new_pool_id: PoolId, error: DispatchError },
+		/// The deposit in the reward account of pool `pool_id` exceeded the existential deposit
+		/// and the excess `amount` has been returned to the depositor.
+		MinBalanceExcessAdjusted { pool_id: PoolId, amount: BalanceOf<T> },
+		/// The deposit in the reward account of pool `pool_id` fell short of the existential
+		/// deposit and the missing `amount` has been charged from the depositor.
+		MinBalanceDeficitAdjusted { pool_id: PoolId, amount: BalanceOf<T> },
 	}
 
 	#[pallet::error]
@@ -1432,6 +1829,9 @@ pub mod pallet {
 		/// anymore to, for example, collect rewards).
 		FullyUnbonding,
 		/// The member cannot unbond further chunks due to reaching the limit.
+		///
+		/// See [`Config::MaxUnbonding`]. Waiting for an era to pass and unbonding again, or
+		/// withdrawing the unlocked chunks first, is possible.
 		MaxUnbondingLimit,
 		/// None of the funds can be withdrawn yet because the bonding duration has not passed.
 		CannotWithdrawAny,
@@ -1471,6 +1871,47 @@ pub mod pallet {
 		PoolIdInUse,
 		/// Pool id provided is not correct/usable.
 		InvalidPoolId,
+		/// More targets were provided than the staking system allows a single nominator to
+		/// nominate. See [`sp_staking::StakingInterface::max_nominations`].
+		TooManyTargets,
+		/// A member cannot refer themselves.
+		SelfReferral,
+		/// The pool has no dust that could be swept.
+		NoDust,
+		/// The depositor cannot unbond below [`Pallet::depositor_unbond_floor`] while the pool has
+		/// other members.
+		DepositorBondBelowFloor,
+		/// The minimum membership duration exceeds [`Config::MaxMinMembershipDuration`].
+		MinMembershipDurationTooLong,
+		/// The member cannot unbond before the [`MinMembershipDuration`] of the pool has passed
+		/// since they joined.
+		MinMembershipDurationNotElapsed,
+		/// The bonded account of the pool has no staking ledger anymore. Members can only leave
+		/// through [`Call::withdraw_orphaned`].
+		BondedLedgerMissing,
+		/// The bonded account of the pool still has a staking ledger.
+		BondedLedgerNotMissing,
+		/// The depositor can only leave a pool once all other members have left.
+		DepositorNotLastMember,
+		/// The members are already all indexed in [`PoolMembersByPool`].
+		MembersAlreadyIndexed,
+		/// The member already requested to switch to another pool.
+		AlreadySwitching,
+		/// The member cannot switch to the pool it already belongs to.
+		SwitchToSamePool,
+		/// The member cannot switch pools while some of its funds are unbonding.
+		SwitchWhileUnbonding,
+		/// The depositor of a pool cannot switch to another pool.
+		DepositorCannotSwitch,
+		/// The pool has been exposed in the last [`StakingInterface::bonding_duration`] eras, hence
+		/// its members can only leave through [`Call::unbond`].
+		PoolExposed,
+		/// The deposit in the reward account of the pool already matches the existential deposit.
+		NothingToAdjust,
+		/// Fewer slashing spans were declared than the bonded account of the pool has.
+		IncorrectSlashingSpans,
+		/// The commission exceeds [`Config::MaxCommission`].
+		CommissionTooHigh,
 	}
 
 	#[derive(Encode, Decode, PartialEq, TypeInfo, frame_support::PalletError, RuntimeDebug)]
@@ -1506,20 +1947,27 @@ pub mod pallet {
 		/// * This call will *not* dust the member account, so the member must have at least
 		///   `existential deposit + amount` in their account.
 		/// * Only a pool with [`PoolState::Open`] can be joined
+		/// * The optional `referrer` is recorded with the membership and cannot be changed
+		///   afterwards. It receives [`Config::ReferrerCommission`] of the pool commission on all
+		///   future payouts.
 		#[pallet::call_index(0)]
 		#[pallet::weight(T::WeightInfo::join())]
 		pub fn join(
 			origin: OriginFor<T>,
 			#[pallet::compact] amount: BalanceOf<T>,
 			pool_id: PoolId,
+			referrer: Option<AccountIdLookupOf<T>>,
 		) -> DispatchResult {
 			let who = ensure_signed(origin)?;
+			let referrer = referrer.map(T::Lookup::lookup).transpose()?;
+			ensure!(referrer.as_ref() != Some(&who), Error::<T>::SelfReferral);
 
 			ensure!(amount >= MinJoinBond::<T>::get(), Error::<T>::MinimumBondNotMet);
 			// If a member already exists that means they already belong to a pool
 			ensure!(!PoolMembers::<T>::contains_key(&who), Error::<T>::AccountBelongsToOtherPool);
 
 			let mut bonded_pool = BondedPool::<T>::get(pool_id).ok_or(Error::<T>::PoolNotFound)?;
+			bonded_pool.ok_to_stake()?;
 			bonded_pool.ok_to_join()?;
 
 			let mut reward_pool = RewardPools::<T>::get(pool_id)
@@ -1530,6 +1978,7 @@ pub mod pallet {
 			bonded_pool.try_inc_members()?;
 			let points_issued = bonded_pool.try_bond_funds(&who, amount, BondType::Later)?;
 
+			PoolMembersByPool::<T>::insert(pool_id, &who, ());
 			PoolMembers::insert(
 				who.clone(),
 				PoolMember::<T> {
@@ -1539,15 +1988,22 @@ pub mod pallet {
 					// `update_recorded`.
 					last_recorded_reward_counter: reward_pool.last_recorded_reward_counter(),
 					unbonding_eras: Default::default(),
+					referrer: referrer.clone(),
+					joined_at: frame_system::Pallet::<T>::block_number(),
 				},
 			);
 
 			Self::deposit_event(Event::<T>::Bonded {
-				member: who,
+				member: who.clone(),
 				pool_id,
 				bonded: amount,
+				points: points_issued,
 				joined: true,
 			});
+			bonded_pool.deposit_updated_event();
+			if let Some(referrer) = referrer {
+				Self::deposit_event(Event::<T>::Referred { member: who, pool_id, referrer });
+			}
 
 			bonded_pool.put();
 			RewardPools::<T>::insert(pool_id, reward_pool);
@@ -1572,6 +2028,10 @@ pub mod pallet {
 		pub fn bond_extra(origin: OriginFor<T>, extra: BondExtra<BalanceOf<T>>) -> DispatchResult {
 			let who = ensure_signed(origin)?;
 			let (mut member, mut bonded_pool, mut reward_pool) = Self::get_member_with_pools(&who)?;
+			bonded_pool.ok_to_stake()?;
+			// checked before any funds are moved. Bonding at the current rate can only lower the
+			// points-to-balance ratio, so this still holds afterwards.
+			bonded_pool.ok_to_be_open()?;
 
 			// payout related stuff: we must claim the payouts, and updated recorded payout data
 			// before updating the bonded pool points, similar to that of `join` transaction.
@@ -1586,7 +2046,6 @@ pub mod pallet {
 					(bonded_pool.try_bond_funds(&who, claimed, BondType::Later)?, claimed),
 			};
 
-			bonded_pool.ok_to_be_open()?;
 			member.points =
 				member.points.checked_add(&points_issued).ok_or(Error::<T>::OverflowRisk)?;
 
@@ -1594,8 +2053,10 @@ pub mod pallet {
 				member: who.clone(),
 				pool_id: member.pool_id,
 				bonded,
+				points: points_issued,
 				joined: false,
 			});
+			bonded_pool.deposit_updated_event();
 			Self::put_member_with_pools(&who, member, bonded_pool, reward_pool);
 
 			Ok(())
@@ -1640,6 +2101,12 @@ pub mod pallet {
 		/// * The caller is not the depositor.
 		/// * The caller is the depositor, the pool is destroying and no other members are in the
 		///   pool.
+		/// * The caller is the depositor and only partially unbonds, keeping at least
+		///   [`Pallet::depositor_min_bond`], or [`Pallet::depositor_unbond_floor`] if the pool is
+		///   open and has at least [`Config::MinMembersForDepositorUnbond`] other members.
+		///
+		/// Unless the pool is destroying, a permissioned dispatch is only possible once the
+		/// [`MinMembershipDuration`] of the pool has passed since the member joined.
 		///
 		/// # Note
 		///
@@ -1662,20 +2129,27 @@ pub mod pallet {
 			let (mut member, mut bonded_pool, mut reward_pool) =
 				Self::get_member_with_pools(&member_account)?;
 
+			bonded_pool.ok_to_stake()?;
 			bonded_pool.ok_to_unbond_with(&who, &member_account, &member, unbonding_points)?;
 
 			// Claim the the payout prior to unbonding. Once the user is unbonding their points no
 			// longer exist in the bonded pool and thus they can no longer claim their payouts. It
-			// is not strictly necessary to claim the rewards, but we do it here for UX.
+			// is not strictly necessary to claim the rewards, but we do it here for UX. The payout
+			// always goes to `member_account`, even if this is a kick.
 			let _ = reward_pool.update_records(bonded_pool.id, bonded_pool.points)?;
-			let _ = Self::do_reward_payout(&who, &mut member, &mut bonded_pool, &mut reward_pool)?;
+			let _ = Self::do_reward_payout(
+				&member_account,
+				&mut member,
+				&mut bonded_pool,
+				&mut reward_pool,
+			)?;
 
 			let current_era = T::Staking::current_era();
 			let unbond_era = T::Staking::bonding_duration().saturating_add(current_era);
 
 			// Unbond in the actual underlying nominator.
 			let unbonding_balance = bonded_pool.dissolve(unbonding_points);
-			T::Staking::unbond(&bonded_pool.bonded_account(), unbonding_balance)?;
+			Self::do_unbond_stake(&bonded_pool.bonded_account(), unbonding_balance)?;
 
 			// Note that we lazily create the unbonding pools here if they don't already exist
 			let mut sub_pools = SubPoolsStorage::<T>::get(member.pool_id)
@@ -1712,6 +2186,7 @@ pub mod pallet {
 				balance: unbonding_balance,
 				era: unbond_era,
 			});
+			bonded_pool.deposit_updated_event();
 
 			// Now that we know everything has worked write the items to storage.
 			SubPoolsStorage::insert(&member.pool_id, sub_pools);
@@ -1726,20 +2201,30 @@ pub mod pallet {
 		/// can be cleared by withdrawing. In the case there are too many unlocking chunks, the user
 		/// would probably see an error like `NoMoreChunks` emitted from the staking system when
 		/// they attempt to unbond.
+		///
+		/// `num_slashing_spans` must be at least the number of slashing spans of the bonded account
+		/// of the pool, which is queried from the staking system. The weight of any spans declared
+		/// beyond that is refunded.
 		#[pallet::call_index(4)]
 		#[pallet::weight(T::WeightInfo::pool_withdraw_unbonded(*num_slashing_spans))]
 		pub fn pool_withdraw_unbonded(
 			origin: OriginFor<T>,
 			pool_id: PoolId,
 			num_slashing_spans: u32,
-		) -> DispatchResult {
+		) -> DispatchResultWithPostInfo {
 			let _ = ensure_signed(origin)?;
 			let pool = BondedPool::<T>::get(pool_id).ok_or(Error::<T>::PoolNotFound)?;
 			// For now we only allow a pool to withdraw unbonded if its not destroying. If the pool
 			// is destroying then `withdraw_unbonded` can be used.
 			ensure!(pool.state != PoolState::Destroying, Error::<T>::NotDestroying);
-			T::Staking::withdraw_unbonded(pool.bonded_account(), num_slashing_spans)?;
-			Ok(())
+			pool.ok_to_stake()?;
+			let actual_slashing_spans = T::Staking::slashing_spans_count(&pool.bonded_account());
+			ensure!(
+				actual_slashing_spans <= num_slashing_spans,
+				Error::<T>::IncorrectSlashingSpans
+			);
+			Self::do_withdraw_unbonded_stake(pool.bonded_account(), actual_slashing_spans)?;
+			Ok(Some(T::WeightInfo::pool_withdraw_unbonded(actual_slashing_spans)).into())
 		}
 
 		/// Withdraw unbonded funds from `member_account`. If no bonded funds can be unbonded, an
@@ -1760,7 +2245,16 @@ pub mod pallet {
 		///
 		/// # Note
 		///
+		/// The withdrawn funds are always transferred to `member_account`, regardless of the
+		/// caller. This allows the root or state-toggler to finish kicking a member once the unlock
+		/// era has passed. Any pending rewards of the member have already been paid out to them as
+		/// part of [`Call::unbond`], so nothing is forfeited when the member is removed.
+		///
 		/// If the target is the depositor, the pool will be destroyed.
+		///
+		/// `num_slashing_spans` must be at least the number of slashing spans of the bonded account
+		/// of the pool, which is queried from the staking system. The weight of any spans declared
+		/// beyond that is refunded.
 		#[pallet::call_index(5)]
 		#[pallet::weight(
 			T::WeightInfo::withdraw_unbonded_kill(*num_slashing_spans)
@@ -1778,6 +2272,7 @@ pub mod pallet {
 
 			let bonded_pool = BondedPool::<T>::get(member.pool_id)
 				.defensive_ok_or::<Error<T>>(DefensiveError::PoolNotFound.into())?;
+			bonded_pool.ok_to_stake()?;
 			let mut sub_pools =
 				SubPoolsStorage::<T>::get(member.pool_id).ok_or(Error::<T>::SubPoolsNotFound)?;
 
@@ -1789,8 +2284,15 @@ pub mod pallet {
 
 			// Before calculating the `balance_to_unbond`, we call withdraw unbonded to ensure the
 			// `transferrable_balance` is correct.
+			let actual_slashing_spans =
+				T::Staking::slashing_spans_count(&bonded_pool.bonded_account());
+			ensure!(
+				actual_slashing_spans <= num_slashing_spans,
+				Error::<T>::IncorrectSlashingSpans
+			);
+			let num_slashing_spans = actual_slashing_spans;
 			let stash_killed =
-				T::Staking::withdraw_unbonded(bonded_pool.bonded_account(), num_slashing_spans)?;
+				Self::do_withdraw_unbonded_stake(bonded_pool.bonded_account(), num_slashing_spans)?;
 
 			// defensive-only: the depositor puts enough funds into the stash so that it will only
 			// be destroyed when they are leaving.
@@ -1804,17 +2306,7 @@ pub mod pallet {
 				.iter()
 				.fold(BalanceOf::<T>::zero(), |accumulator, (era, unlocked_points)| {
 					sum_unlocked_points = sum_unlocked_points.saturating_add(*unlocked_points);
-					if let Some(era_pool) = sub_pools.with_era.get_mut(&era) {
-						let balance_to_unbond = era_pool.dissolve(*unlocked_points);
-						if era_pool.points.is_zero() {
-							sub_pools.with_era.remove(&era);
-						}
-						accumulator.saturating_add(balance_to_unbond)
-					} else {
-						// A pool does not belong to this era, so it must have been merged to the
-						// era-less pool.
-						accumulator.saturating_add(sub_pools.no_era.dissolve(*unlocked_points))
-					}
+					accumulator.saturating_add(sub_pools.dissolve(*era, *unlocked_points))
 				})
 				// A call to this transaction may cause the pool's stash to get dusted. If this
 				// happens before the last member has withdrawn, then all subsequent withdraws will
@@ -1825,11 +2317,10 @@ pub mod pallet {
 				// order to ensure members can leave the pool and it can be destroyed.
 				.min(bonded_pool.transferrable_balance());
 
-			T::Currency::transfer(
-				&bonded_pool.bonded_account(),
+			T::StakeAdapter::member_withdraw(
 				&member_account,
+				&bonded_pool.bonded_account(),
 				balance_to_unbond,
-				ExistenceRequirement::AllowDeath,
 			)
 			.defensive()?;
 
@@ -1843,6 +2334,7 @@ pub mod pallet {
 			let post_info_weight = if member.total_points().is_zero() {
 				// member being reaped.
 				PoolMembers::<T>::remove(&member_account);
+				PoolMembersByPool::<T>::remove(member.pool_id, &member_account);
 				Self::deposit_event(Event::<T>::MemberRemoved {
 					pool_id: member.pool_id,
 					member: member_account.clone(),
@@ -1850,7 +2342,7 @@ pub mod pallet {
 
 				if member_account == bonded_pool.roles.depositor {
 					Pallet::<T>::dissolve_pool(bonded_pool);
-					None
+					Some(T::WeightInfo::withdraw_unbonded_kill(num_slashing_spans))
 				} else {
 					bonded_pool.dec_members().put();
 					SubPoolsStorage::<T>::insert(&member.pool_id, sub_pools);
@@ -1932,7 +2424,8 @@ pub mod pallet {
 		/// root role.
 		///
 		/// This directly forward the call to the staking pallet, on behalf of the pool bonded
-		/// account.
+		/// account. At most [`sp_staking::StakingInterface::max_nominations`] validators can be
+		/// nominated.
 		#[pallet::call_index(8)]
 		#[pallet::weight(T::WeightInfo::nominate(validators.len() as u32))]
 		pub fn nominate(
@@ -1943,6 +2436,20 @@ pub mod pallet {
 			let who = ensure_signed(origin)?;
 			let bonded_pool = BondedPool::<T>::get(pool_id).ok_or(Error::<T>::PoolNotFound)?;
 			ensure!(bonded_pool.can_nominate(&who), Error::<T>::NotNominator);
+			bonded_pool.ok_to_stake()?;
+
+			let max_nominations = T::Staking::max_nominations();
+			if validators.len() > max_nominations as usize {
+				log!(
+					debug,
+					"pool {} cannot nominate {} targets, the maximum is {}",
+					pool_id,
+					validators.len(),
+					max_nominations,
+				);
+				return Err(Error::<T>::TooManyTargets.into())
+			}
+
 			T::Staking::nominate(&bonded_pool.bonded_account(), validators)
 		}
 
@@ -1954,8 +2461,10 @@ pub mod pallet {
 		/// The dispatch origin of this call must be either:
 		///
 		/// 1. signed by the state toggler, or the root role of the pool,
-		/// 2. if the pool conditions to be open are NOT met (as described by `ok_to_be_open`), and
-		///    then the state of the pool can be permissionlessly changed to `Destroying`.
+		/// 2. if the pool conditions to be open are NOT met (as described by `ok_to_be_open`), the
+		///    pool has no points, or its bonded balance has been slashed below the minimum bond of
+		///    the depositor, then the state of the pool can be permissionlessly changed to
+		///    `Destroying` by any signed origin.
 		#[pallet::call_index(9)]
 		#[pallet::weight(T::WeightInfo::set_state())]
 		pub fn set_state(
@@ -1968,10 +2477,12 @@ pub mod pallet {
 			ensure!(bonded_pool.state != PoolState::Destroying, Error::<T>::CanNotChangeState);
 
 			if bonded_pool.can_toggle_state(&who) {
-				bonded_pool.set_state(state);
-			} else if bonded_pool.ok_to_be_open().is_err() && state == PoolState::Destroying {
+				bonded_pool.set_state(state, &who);
+			} else if state == PoolState::Destroying &&
+				bonded_pool.ok_to_be_destroyed_permissionlessly()
+			{
 				// If the pool has bad properties, then anyone can set it as destroying
-				bonded_pool.set_state(PoolState::Destroying);
+				bonded_pool.set_state(PoolState::Destroying, &who);
 			} else {
 				Err(Error::<T>::CanNotChangeState)?;
 			}
@@ -2103,7 +2614,9 @@ pub mod pallet {
 		/// Chill on behalf of the pool.
 		///
 		/// The dispatch origin of this call must be signed by the pool nominator or the pool
-		/// root role, same as [`Pallet::nominate`].
+		/// root role, same as [`Pallet::nominate`]. If the active bond of the pool fell below
+		/// [`sp_staking::StakingInterface::minimum_nominator_bond`], e.g. due to a slash, any
+		/// signed account can chill it.
 		///
 		/// This directly forward the call to the staking pallet, on behalf of the pool bonded
 		/// account.
@@ -2112,13 +2625,371 @@ pub mod pallet {
 		pub fn chill(origin: OriginFor<T>, pool_id: PoolId) -> DispatchResult {
 			let who = ensure_signed(origin)?;
 			let bonded_pool = BondedPool::<T>::get(pool_id).ok_or(Error::<T>::PoolNotFound)?;
-			ensure!(bonded_pool.can_nominate(&who), Error::<T>::NotNominator);
-			T::Staking::chill(&bonded_pool.bonded_account())
+			bonded_pool.ok_to_stake()?;
+			let bonded_account = bonded_pool.bonded_account();
+			ensure!(
+				bonded_pool.can_nominate(&who) ||
+					T::Staking::active_stake(&bonded_account).unwrap_or_default() <
+						T::Staking::minimum_nominator_bond(),
+				Error::<T>::NotNominator
+			);
+			T::Staking::chill(&bonded_account)
+		}
+
+		/// Sweep the dust of a pool to [`Config::DustReceiver`], or to the depositor of the pool if
+		/// there is none.
+		///
+		/// The dust of a pool are rewards that can never be claimed by any of its members
+		/// because they were lost to rounding in the reward counter. This is mostly the case for
+		/// pools with a very large amount of points receiving small rewards.
+		///
+		/// The dispatch origin of this call must be signed, by any account.
+		#[pallet::call_index(14)]
+		#[pallet::weight(T::WeightInfo::sweep_dust())]
+		pub fn sweep_dust(origin: OriginFor<T>, pool_id: PoolId) -> DispatchResult {
+			let _ = ensure_signed(origin)?;
+			let bonded_pool = BondedPool::<T>::get(pool_id).ok_or(Error::<T>::PoolNotFound)?;
+			let mut reward_pool = RewardPools::<T>::get(pool_id)
+				.defensive_ok_or::<Error<T>>(DefensiveError::RewardPoolNotFound.into())?;
+
+			let dust = reward_pool.dust();
+			ensure!(!dust.is_zero(), Error::<T>::NoDust);
+
+			let beneficiary =
+				T::DustReceiver::get().unwrap_or_else(|| bonded_pool.roles.depositor.clone());
+			T::Currency::transfer(
+				&bonded_pool.reward_account(),
+				&beneficiary,
+				dust,
+				ExistenceRequirement::KeepAlive,
+			)?;
+			reward_pool.register_swept_dust();
+			RewardPools::<T>::insert(pool_id, reward_pool);
+
+			Self::deposit_event(Event::<T>::DustSwept { pool_id, beneficiary, amount: dust });
+			Ok(())
+		}
+
+		/// Compare the balance of the reward account of `pool_id` with the balance expected from
+		/// the recorded payouts, and emit [`Event::RewardPoolReconciled`] with both values.
+		///
+		/// A reward account holding less than expected stops the pool from recording any new
+		/// rewards until the difference is made up. If `adjust` is set, the recorded payouts are
+		/// lowered to the actual balance in this case. A surplus is regular income that has not
+		/// been recorded yet and is never adjusted. No funds are moved, minted or burned.
+		///
+		/// The dispatch origin of this call must be Root.
+		#[pallet::call_index(15)]
+		#[pallet::weight(T::WeightInfo::reconcile_reward_pool())]
+		pub fn reconcile_reward_pool(
+			origin: OriginFor<T>,
+			pool_id: PoolId,
+			adjust: bool,
+		) -> DispatchResult {
+			ensure_root(origin)?;
+			ensure!(BondedPools::<T>::contains_key(pool_id), Error::<T>::PoolNotFound);
+			let mut reward_pool = RewardPools::<T>::get(pool_id)
+				.defensive_ok_or::<Error<T>>(DefensiveError::RewardPoolNotFound.into())?;
+
+			let expected = reward_pool.expected_balance();
+			let actual = RewardPool::<T>::current_balance(pool_id);
+			let adjusted = adjust && reward_pool.reconcile(actual);
+			if adjusted {
+				RewardPools::<T>::insert(pool_id, reward_pool);
+			}
+
+			Self::deposit_event(Event::<T>::RewardPoolReconciled {
+				pool_id,
+				expected,
+				actual,
+				adjusted,
+			});
+			Ok(())
+		}
+
+		/// Set the minimum number of blocks a member has to stay in the pool before they can
+		/// unbond by themselves, or remove it if `None`. See [`MinMembershipDuration`].
+		///
+		/// The dispatch origin of this call must be signed by the root role of the pool. The
+		/// duration cannot exceed [`Config::MaxMinMembershipDuration`]. It applies to existing
+		/// members as well, counted from the block at which they joined.
+		#[pallet::call_index(16)]
+		#[pallet::weight(T::WeightInfo::set_min_membership_duration())]
+		pub fn set_min_membership_duration(
+			origin: OriginFor<T>,
+			pool_id: PoolId,
+			duration: Option<T::BlockNumber>,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			let bonded_pool = BondedPool::<T>::get(pool_id).ok_or(Error::<T>::PoolNotFound)?;
+			ensure!(
+				bonded_pool.can_set_min_membership_duration(&who),
+				Error::<T>::DoesNotHavePermission
+			);
+			ensure!(
+				duration.map_or(true, |d| d <= T::MaxMinMembershipDuration::get()),
+				Error::<T>::MinMembershipDurationTooLong
+			);
+
+			MinMembershipDuration::<T>::set(pool_id, duration);
+
+			Self::deposit_event(Event::<T>::MinMembershipDurationUpdated { pool_id, duration });
+			Ok(())
+		}
+
+		/// Remove `member_account` from a pool whose bonded account lost its staking ledger, e.g.
+		/// because it was force-unstaked by governance, and transfer their share of the balance
+		/// that is left in the bonded account to them.
+		///
+		/// Such a pool can no longer unbond or withdraw through the staking system. The first call
+		/// switches it to [`PoolState::Destroying`] and emits [`Event::LedgerLost`]. This is the
+		/// only call that does so; all others fail with [`Error::BondedLedgerMissing`].
+		///
+		/// The share of a member is pro rata of the balance of their active and unbonding points.
+		/// Unbonding points are valued at the balance of their unbonding pool, active points at
+		/// whatever is left in the bonded account beyond that. Pending rewards are paid out one
+		/// last time. The depositor can only leave once all other members have left, which
+		/// dissolves the pool.
+		///
+		/// The dispatch origin of this call must be signed, by any account. The funds are always
+		/// transferred to `member_account`.
+		#[pallet::call_index(17)]
+		// bounded by removing the last member of a pool, which dissolves it, plus a final payout.
+		#[pallet::weight(
+			T::WeightInfo::withdraw_unbonded_kill(0)
+			.saturating_add(T::WeightInfo::claim_payout())
+			.saturating_add(Pallet::<T>::orphan_check_weight())
+		)]
+		pub fn withdraw_orphaned(
+			origin: OriginFor<T>,
+			member_account: AccountIdLookupOf<T>,
+		) -> DispatchResult {
+			let caller = ensure_signed(origin)?;
+			let member_account = T::Lookup::lookup(member_account)?;
+			let (mut member, mut bonded_pool, mut reward_pool) =
+				Self::get_member_with_pools(&member_account)?;
+			ensure!(bonded_pool.ledger_missing(), Error::<T>::BondedLedgerNotMissing);
+			let is_depositor = member_account == bonded_pool.roles.depositor;
+			ensure!(
+				!is_depositor || bonded_pool.member_counter == 1,
+				Error::<T>::DepositorNotLastMember
+			);
+
+			let pool_id = bonded_pool.id;
+			let bonded_account = bonded_pool.bonded_account();
+			if !bonded_pool.is_destroying() {
+				let balance = T::StakeAdapter::transferable_balance(&bonded_account);
+				log!(warn, "staking ledger of pool {} is missing, destroying it", pool_id);
+				// The staking system did not report the lost ledger to us. Whatever it unlocked
+				// is left in the bonded account and no longer locked.
+				TotalValueLocked::<T>::mutate(|tvl| tvl.saturating_reduce(balance));
+				bonded_pool.set_state(PoolState::Destroying, &caller);
+				Self::deposit_event(Event::<T>::LedgerLost { pool_id, balance });
+			}
+
+			if !member.active_points().is_zero() {
+				reward_pool.update_records(pool_id, bonded_pool.points)?;
+				let _ = Self::do_reward_payout(
+					&member_account,
+					&mut member,
+					&mut bonded_pool,
+					&mut reward_pool,
+				)?;
+			}
+
+			// Everything that was bonded or unbonding is left in the bonded account. What is not
+			// claimed by the unbonding pools is what the active points stand for.
+			let mut sub_pools = SubPoolsStorage::<T>::get(pool_id).unwrap_or_default();
+			let remaining = T::StakeAdapter::transferable_balance(&bonded_account);
+			let unbonding = sub_pools.sum_unbonding_balance();
+			let active_balance = remaining.saturating_sub(unbonding);
+			let total_claims = active_balance.saturating_add(unbonding);
+			let mut points = member.active_points();
+			let mut claim =
+				Self::point_to_balance(active_balance, bonded_pool.points, member.active_points());
+			for (era, unbonding_points) in member.unbonding_eras.iter() {
+				points = points.saturating_add(*unbonding_points);
+				claim = claim.saturating_add(sub_pools.dissolve(*era, *unbonding_points));
+			}
+
+			// The depositor leaves last and takes whatever rounding left behind.
+			let balance = if is_depositor {
+				remaining
+			} else {
+				Self::point_to_balance(remaining, total_claims, claim)
+			};
+			T::StakeAdapter::member_withdraw(&member_account, &bonded_account, balance)?;
+			bonded_pool.points = bonded_pool.points.saturating_sub(member.active_points());
+
+			Self::deposit_event(Event::<T>::Withdrawn {
+				member: member_account.clone(),
+				pool_id,
+				balance,
+				points,
+			});
+			PoolMembers::<T>::remove(&member_account);
+			PoolMembersByPool::<T>::remove(pool_id, &member_account);
+			Self::deposit_event(Event::<T>::MemberRemoved {
+				pool_id,
+				member: member_account.clone(),
+			});
+
+			if is_depositor {
+				Self::dissolve_pool(bonded_pool);
+			} else {
+				bonded_pool.dec_members().put();
+				SubPoolsStorage::<T>::insert(pool_id, sub_pools);
+				RewardPools::<T>::insert(pool_id, reward_pool);
+			}
+
+			Ok(())
+		}
+
+		/// Index up to `limit` more of the members that joined before [`PoolMembersByPool`] was
+		/// introduced.
+		///
+		/// This looks at the next `limit` entries of [`PoolMembers`], starting after the last one
+		/// looked at, and indexes them under their pool. Once all of them have been looked at,
+		/// [`PoolMembersIndexCursor`] is removed.
+		///
+		/// The dispatch origin of this call must be signed, by any account.
+		#[pallet::call_index(18)]
+		#[pallet::weight(T::WeightInfo::migrate_members(*limit))]
+		pub fn migrate_members(origin: OriginFor<T>, limit: u32) -> DispatchResult {
+			let _ = ensure_signed(origin)?;
+			let mut last =
+				PoolMembersIndexCursor::<T>::get().ok_or(Error::<T>::MembersAlreadyIndexed)?;
+
+			let members = match &last {
+				Some(last) => PoolMembers::<T>::iter_from(PoolMembers::<T>::hashed_key_for(last)),
+				None => PoolMembers::<T>::iter(),
+			};
+			let mut checked = 0u32;
+			for (who, member) in members.take(limit as usize) {
+				PoolMembersByPool::<T>::insert(member.pool_id, &who, ());
+				checked.saturating_inc();
+				last = Some(who);
+			}
+
+			let complete = checked < limit;
+			if complete {
+				PoolMembersIndexCursor::<T>::kill();
+			} else {
+				PoolMembersIndexCursor::<T>::put(last);
+			}
+
+			Self::deposit_event(Event::<T>::MembersIndexed { checked, complete });
+			Ok(())
+		}
+
+		/// Request to move all the active funds of the caller to pool `new_pool_id`, without
+		/// waiting for the bonding duration.
+		///
+		/// This is only possible if the pool of the caller has not been exposed in any of the
+		/// last [`StakingInterface::bonding_duration`] eras, and the caller is neither its
+		/// depositor nor unbonding any funds. Since checking the exposure is expensive, the
+		/// request is queued and processed in `on_idle`, see [`Event::Switched`] and
+		/// [`Event::SwitchFailed`]. Pending rewards are paid out when the funds are moved.
+		///
+		/// The dispatch origin of this call must be signed by the member.
+		#[pallet::call_index(19)]
+		#[pallet::weight(T::WeightInfo::switch_pool())]
+		pub fn switch_pool(origin: OriginFor<T>, new_pool_id: PoolId) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			ensure!(!SwitchPoolRequests::<T>::contains_key(&who), Error::<T>::AlreadySwitching);
+			let (member, bonded_pool, _) = Self::get_member_with_pools(&who)?;
+			Self::ok_to_switch(&who, &member, &bonded_pool, new_pool_id)?;
+			BondedPool::<T>::get(new_pool_id)
+				.ok_or(Error::<T>::PoolNotFound)?
+				.ok_to_join()?;
+
+			SwitchPoolRequests::<T>::insert(&who, new_pool_id);
+			Self::deposit_event(Event::<T>::SwitchRequested {
+				member: who,
+				pool_id: member.pool_id,
+				new_pool_id,
+			});
+			Ok(())
+		}
+
+		/// Bring the deposit in the reward account of `pool_id` in line with the current
+		/// existential deposit, see [`RewardAccountDeposits`].
+		///
+		/// If the existential deposit has been lowered since the deposit was made, the excess is
+		/// returned to the depositor of the pool. If it has been raised, the shortfall is charged
+		/// from the depositor, so that the reward account cannot be reaped. The rewards of the pool
+		/// are not affected either way.
+		///
+		/// The dispatch origin of this call must be signed, by any account.
+		#[pallet::call_index(20)]
+		#[pallet::weight(T::WeightInfo::adjust_pool_deposit())]
+		pub fn adjust_pool_deposit(origin: OriginFor<T>, pool_id: PoolId) -> DispatchResult {
+			let _ = ensure_signed(origin)?;
+			let bonded_pool = BondedPool::<T>::get(pool_id).ok_or(Error::<T>::PoolNotFound)?;
+			let deposit = RewardAccountDeposits::<T>::get(pool_id);
+			let min_balance = T::Currency::minimum_balance();
+			ensure!(deposit != min_balance, Error::<T>::NothingToAdjust);
+
+			let reward_account = bonded_pool.reward_account();
+			let depositor = &bonded_pool.roles.depositor;
+			let event = if deposit > min_balance {
+				let amount = deposit.saturating_sub(min_balance);
+				T::Currency::transfer(
+					&reward_account,
+					depositor,
+					amount,
+					ExistenceRequirement::KeepAlive,
+				)?;
+				Event::<T>::MinBalanceExcessAdjusted { pool_id, amount }
+			} else {
+				let amount = min_balance.saturating_sub(deposit);
+				T::Currency::transfer(
+					depositor,
+					&reward_account,
+					amount,
+					ExistenceRequirement::AllowDeath,
+				)?;
+				Event::<T>::MinBalanceDeficitAdjusted { pool_id, amount }
+			};
+			RewardAccountDeposits::<T>::insert(pool_id, min_balance);
+
+			Self::deposit_event(event);
+			Ok(())
+		}
+
+		/// Set the [`Commission`] of the pool to a share of the payouts of its members and the
+		/// account it is paid to, or remove it if `None`.
+		///
+		/// The dispatch origin of this call must be signed by the root role of the pool. The share
+		/// cannot exceed [`Config::MaxCommission`]. It applies to all payouts from then on.
+		#[pallet::call_index(21)]
+		#[pallet::weight(T::WeightInfo::set_commission())]
+		pub fn set_commission(
+			origin: OriginFor<T>,
+			pool_id: PoolId,
+			commission: Option<(Perbill, T::AccountId)>,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			let bonded_pool = BondedPool::<T>::get(pool_id).ok_or(Error::<T>::PoolNotFound)?;
+			ensure!(bonded_pool.can_set_commission(&who), Error::<T>::DoesNotHavePermission);
+			ensure!(
+				commission.as_ref().map_or(true, |(c, _)| *c <= T::MaxCommission::get()),
+				Error::<T>::CommissionTooHigh
+			);
+
+			Commission::<T>::set(pool_id, commission.clone());
+
+			Self::deposit_event(Event::<T>::CommissionUpdated { pool_id, commission });
+			Ok(())
 		}
 	}
 
 	#[pallet::hooks]
 	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+		fn on_idle(_: BlockNumberFor<T>, remaining_weight: Weight) -> Weight {
+			Self::process_switch_requests(remaining_weight)
+		}
+
 		#[cfg(feature = "try-runtime")]
 		fn try_state(_n: BlockNumberFor<T>) -> Result<(), &'static str> {
 			Self::do_try_state(u8::MAX)
@@ -2158,6 +3029,28 @@ impl<T: Config> Pallet<T> {
 		None
 	}
 
+	/// Returns the balance that `points` of the pool `pool_id` are currently worth.
+	///
+	/// This is the same conversion that is used when the points are unbonded. `None` is returned
+	/// if the pool does not exist.
+	pub fn points_to_balance(pool_id: PoolId, points: BalanceOf<T>) -> Option<BalanceOf<T>> {
+		BondedPool::<T>::get(pool_id).map(|pool| pool.points_to_balance(points))
+	}
+
+	/// Returns the balance of `member_account` in its pool, both active and unbonding.
+	///
+	/// Active points are valued like [`Self::points_to_balance`], unbonding points like they
+	/// would be when withdrawn in the current state of their unbonding pools. `None` is returned
+	/// if the account is not a member of any pool.
+	pub fn member_balance(member_account: T::AccountId) -> Option<BalanceOf<T>> {
+		let member = PoolMembers::<T>::get(member_account)?;
+		let active = Self::points_to_balance(member.pool_id, member.active_points())?;
+		let sub_pools = SubPoolsStorage::<T>::get(member.pool_id).unwrap_or_default();
+		Some(member.unbonding_eras.iter().fold(active, |acc, (era, points)| {
+			acc.saturating_add(sub_pools.point_to_balance(*era, *points))
+		}))
+	}
+
 	/// The amount of bond that MUST REMAIN IN BONDED in ALL POOLS.
 	///
 	/// It is the responsibility of the depositor to put these funds into the pool initially. Upon
@@ -2171,6 +3064,87 @@ impl<T: Config> Pallet<T> {
 			.max(MinJoinBond::<T>::get())
 			.max(T::Currency::minimum_balance())
 	}
+
+	/// The weight of [`BondedPool::ledger_missing`], i.e. of reading the staking ledger of the
+	/// bonded account, on top of the benchmarked weight of a dispatchable.
+	fn orphan_check_weight() -> Weight {
+		T::DbWeight::get().reads(2)
+	}
+
+	/// The amount of bond the depositor of an open pool with enough other members must keep.
+	///
+	/// It is `max { MinCreateBond, MinJoinBond }`: the rest of [`Self::depositor_min_bond`] is
+	/// then covered by the other members. See [`Config::MinMembersForDepositorUnbond`].
+	pub fn depositor_unbond_floor() -> BalanceOf<T> {
+		MinCreateBond::<T>::get().max(MinJoinBond::<T>::get())
+	}
+
+	/// Returns the validators currently nominated by the pool `pool_id`.
+	///
+	/// `None` is returned if the pool does not exist or is not nominating.
+	pub fn pool_nominations(pool_id: PoolId) -> Option<Vec<T::AccountId>> {
+		if !BondedPools::<T>::contains_key(pool_id) {
+			return None
+		}
+		T::Staking::nominations(Self::create_bonded_account(pool_id))
+	}
+
+	/// Returns up to `len` members of the pool `pool_id`, skipping the first `start` ones.
+	///
+	/// Members are returned in the order of their keys in [`PoolMembersByPool`]. The list is
+	/// incomplete for pools that predate it as long as [`PoolMembersIndexCursor`] is set.
+	pub fn pool_members(pool_id: PoolId, start: u32, len: u32) -> Vec<T::AccountId> {
+		PoolMembersByPool::<T>::iter_key_prefix(pool_id)
+			.skip(start as usize)
+			.take(len as usize)
+			.collect()
+	}
+
+	/// Returns whether the active stake of the pool `pool_id` is below the minimum active stake of
+	/// the last election snapshot, in which case its members are unlikely to earn rewards.
+	///
+	/// `None` is returned if the pool does not exist.
+	pub fn below_minimum_active_stake(pool_id: PoolId) -> Option<bool> {
+		if !BondedPools::<T>::contains_key(pool_id) {
+			return None
+		}
+		let active =
+			T::Staking::active_stake(&Self::create_bonded_account(pool_id)).unwrap_or(Zero::zero());
+		Some(active < T::Staking::minimum_active_stake())
+	}
+
+	/// Unbond `amount` of the active stake of the pool `bonded_account`.
+	///
+	/// The staking system may withdraw unlocked funds on the way to make room for a new unlocking
+	/// chunk. These are removed from [`TotalValueLocked`], like in
+	/// [`Self::do_withdraw_unbonded_stake`].
+	fn do_unbond_stake(bonded_account: &T::AccountId, amount: BalanceOf<T>) -> DispatchResult {
+		let pre_total = T::Staking::total_stake(bonded_account).unwrap_or_default();
+		T::StakeAdapter::unbond(bonded_account, amount)?;
+		let post_total = T::Staking::total_stake(bonded_account).unwrap_or_default();
+		TotalValueLocked::<T>::mutate(|tvl| {
+			tvl.defensive_saturating_reduce(pre_total.saturating_sub(post_total))
+		});
+		Ok(())
+	}
+
+	/// Withdraw the unlocked funds of the pool `bonded_account` from the staking system, and
+	/// remove them from [`TotalValueLocked`].
+	///
+	/// Returns whether the stash has been killed, like [`StakingInterface::withdraw_unbonded`].
+	fn do_withdraw_unbonded_stake(
+		bonded_account: T::AccountId,
+		num_slashing_spans: u32,
+	) -> Result<bool, DispatchError> {
+		let pre_total = T::Staking::total_stake(&bonded_account).unwrap_or_default();
+		let stash_killed = T::StakeAdapter::withdraw_unbonded(&bonded_account, num_slashing_spans)?;
+		let post_total = T::Staking::total_stake(&bonded_account).unwrap_or_default();
+		TotalValueLocked::<T>::mutate(|tvl| {
+			tvl.defensive_saturating_reduce(pre_total.saturating_sub(post_total))
+		});
+		Ok(stash_killed)
+	}
+
 	/// Remove everything related to the given bonded pool.
 	///
 	/// Metadata and all of the sub-pools are also deleted. All accounts are dusted and the leftover
@@ -2181,6 +3155,7 @@ impl<T: Config> Pallet<T> {
 
 		ReversePoolIdLookup::<T>::remove(&bonded_account);
 		RewardPools::<T>::remove(bonded_pool.id);
+		RewardAccountDeposits::<T>::remove(bonded_pool.id);
 		SubPoolsStorage::<T>::remove(bonded_pool.id);
 
 		// Kill accounts from storage by making their balance go below ED. We assume that the
@@ -2208,11 +3183,13 @@ impl<T: Config> Pallet<T> {
 
 		// NOTE: this is purely defensive.
 		T::Currency::make_free_balance_be(&reward_account, Zero::zero());
-		T::Currency::make_free_balance_be(&bonded_pool.bonded_account(), Zero::zero());
+		T::StakeAdapter::dissolve(&bonded_account);
 
 		Self::deposit_event(Event::<T>::Destroyed { pool_id: bonded_pool.id });
 		// Remove bonded pool metadata.
 		Metadata::<T>::remove(bonded_pool.id);
+		MinMembershipDuration::<T>::remove(bonded_pool.id);
+		Commission::<T>::remove(bonded_pool.id);
 
 		bonded_pool.remove();
 	}
@@ -2254,6 +3231,126 @@ impl<T: Config> Pallet<T> {
 		PoolMembers::<T>::insert(member_account, member);
 	}
 
+	/// Check that `who` can switch from `bonded_pool` to pool `new_pool_id`, apart from the
+	/// exposure of `bonded_pool`.
+	fn ok_to_switch(
+		who: &T::AccountId,
+		member: &PoolMember<T>,
+		bonded_pool: &BondedPool<T>,
+		new_pool_id: PoolId,
+	) -> DispatchResult {
+		ensure!(member.pool_id != new_pool_id, Error::<T>::SwitchToSamePool);
+		ensure!(*who != bonded_pool.roles.depositor, Error::<T>::DepositorCannotSwitch);
+		ensure!(member.unbonding_eras.is_empty(), Error::<T>::SwitchWhileUnbonding);
+		ensure!(!member.active_points().is_zero(), Error::<T>::FullyUnbonding);
+		ensure!(
+			bonded_pool.min_membership_duration_elapsed(member),
+			Error::<T>::MinMembershipDurationNotElapsed
+		);
+		Ok(())
+	}
+
+	/// Whether the bonded account of `bonded_pool` has been exposed in the current era or any of
+	/// the [`StakingInterface::bonding_duration`] eras before it.
+	fn recently_exposed(bonded_pool: &BondedPool<T>) -> bool {
+		let bonded_account = bonded_pool.bonded_account();
+		let current_era = T::Staking::current_era();
+		(current_era.saturating_sub(T::Staking::bonding_duration())..=current_era)
+			.any(|era| T::Staking::is_exposed_in_era(&bonded_account, &era))
+	}
+
+	/// Process as many [`SwitchPoolRequests`] as fit in `remaining_weight`.
+	///
+	/// Returns the weight that has been consumed.
+	fn process_switch_requests(remaining_weight: Weight) -> Weight {
+		// every request checks the exposure of all validators in each of the eras, both here and
+		// in `StakingInterface::unbond_instantly`.
+		let eras = T::Staking::bonding_duration().saturating_add(1);
+		let per_request = T::WeightInfo::process_switch_request(
+			T::Staking::desired_validator_count().saturating_mul(eras),
+		);
+
+		let mut consumed = Weight::zero();
+		while consumed.saturating_add(per_request).all_lte(remaining_weight) {
+			let (who, new_pool_id) = match SwitchPoolRequests::<T>::drain().next() {
+				Some(request) => request,
+				None => return consumed.saturating_add(T::DbWeight::get().reads(1)),
+			};
+			consumed.saturating_accrue(per_request);
+
+			if let Err(error) = with_storage_layer(|| Self::do_switch_pool(&who, new_pool_id)) {
+				Self::deposit_event(Event::<T>::SwitchFailed { member: who, new_pool_id, error });
+			}
+		}
+		consumed
+	}
+
+	/// Move all the active funds of `who` to pool `new_pool_id`, see [`Call::switch_pool`].
+	///
+	/// Nothing is reverted on error, hence this must be called within a storage layer.
+	fn do_switch_pool(who: &T::AccountId, new_pool_id: PoolId) -> DispatchResult {
+		let (mut member, mut bonded_pool, mut reward_pool) = Self::get_member_with_pools(who)?;
+		Self::ok_to_switch(who, &member, &bonded_pool, new_pool_id)?;
+		bonded_pool.ok_to_stake()?;
+		ensure!(!Self::recently_exposed(&bonded_pool), Error::<T>::PoolExposed);
+
+		let mut new_bonded_pool =
+			BondedPool::<T>::get(new_pool_id).ok_or(Error::<T>::PoolNotFound)?;
+		new_bonded_pool.ok_to_stake()?;
+		new_bonded_pool.ok_to_join()?;
+		// the member is moved rather than added, hence only the limit per pool applies.
+		ensure!(
+			MaxPoolMembersPerPool::<T>::get()
+				.map_or(true, |max_per_pool| new_bonded_pool.member_counter < max_per_pool),
+			Error::<T>::MaxPoolMembers
+		);
+		let mut new_reward_pool = RewardPools::<T>::get(new_pool_id)
+			.defensive_ok_or::<Error<T>>(DefensiveError::RewardPoolNotFound.into())?;
+
+		// the member has no points left in the old pool to claim its rewards with afterwards.
+		reward_pool.update_records(bonded_pool.id, bonded_pool.points)?;
+		let _ = Self::do_reward_payout(who, &mut member, &mut bonded_pool, &mut reward_pool)?;
+
+		let pool_id = bonded_pool.id;
+		let balance = bonded_pool.dissolve(member.active_points());
+		ensure!(balance >= MinJoinBond::<T>::get(), Error::<T>::MinimumBondNotMet);
+
+		// IMPORTANT: reward pool records must be updated with the old points.
+		new_reward_pool.update_records(new_pool_id, new_bonded_pool.points)?;
+		new_bonded_pool.member_counter.saturating_inc();
+		// We must calculate the points issued *before* the funds are moved, else points:balance
+		// ratio will be wrong. The funds stay staked, hence `TotalValueLocked` is unchanged.
+		let points = new_bonded_pool.issue(balance);
+		T::StakeAdapter::member_switch(
+			who,
+			&bonded_pool.bonded_account(),
+			&new_bonded_pool.bonded_account(),
+			balance,
+		)?;
+
+		PoolMembersByPool::<T>::remove(pool_id, who);
+		PoolMembersByPool::<T>::insert(new_pool_id, who, ());
+		member.pool_id = new_pool_id;
+		member.points = points;
+		member.last_recorded_reward_counter = new_reward_pool.last_recorded_reward_counter();
+		member.joined_at = frame_system::Pallet::<T>::block_number();
+
+		Self::deposit_event(Event::<T>::Switched {
+			member: who.clone(),
+			pool_id,
+			new_pool_id,
+			balance,
+			points,
+		});
+		bonded_pool.deposit_updated_event();
+		new_bonded_pool.deposit_updated_event();
+
+		bonded_pool.dec_members().put();
+		RewardPools::<T>::insert(pool_id, reward_pool);
+		Self::put_member_with_pools(who, member, new_bonded_pool, new_reward_pool);
+		Ok(())
+	}
+
 	/// Calculate the equivalent point of `new_funds` in a pool with `current_balance` and
 	/// `current_points`.
 	fn balance_to_point(
@@ -2328,11 +3425,15 @@ impl<T: Config> Pallet<T> {
 		member.last_recorded_reward_counter = current_reward_counter;
 		reward_pool.register_claimed_reward(pending_rewards);
 
+		let commission_payout =
+			Self::do_commission_payout(member_account, member, bonded_pool, pending_rewards);
+		let member_payout = pending_rewards.saturating_sub(commission_payout);
+
 		// Transfer payout to the member.
 		T::Currency::transfer(
 			&bonded_pool.reward_account(),
 			&member_account,
-			pending_rewards,
+			member_payout,
 			// defensive: the depositor has put existential deposit into the pool and it stays
 			// untouched, reward account shall not die.
 			ExistenceRequirement::AllowDeath,
@@ -2341,10 +3442,93 @@ impl<T: Config> Pallet<T> {
 		Self::deposit_event(Event::<T>::PaidOut {
 			member: member_account.clone(),
 			pool_id: member.pool_id,
-			payout: pending_rewards,
+			payout: member_payout,
+		});
+
+		Ok(member_payout)
+	}
+
+	/// Pay the [`Commission`] of `bonded_pool` on a `payout` to `member`, if any.
+	///
+	/// The referrer of the member receives their share of it, the payee of the pool the rest.
+	/// Returns the amount paid, which is deducted from the member's payout. A share that cannot be
+	/// paid (e.g. because it is below the existential deposit) is kept by the member.
+	fn do_commission_payout(
+		member_account: &T::AccountId,
+		member: &PoolMember<T>,
+		bonded_pool: &BondedPool<T>,
+		payout: BalanceOf<T>,
+	) -> BalanceOf<T> {
+		let (rate, payee) = match Commission::<T>::get(bonded_pool.id) {
+			Some(commission) => commission,
+			None => return Zero::zero(),
+		};
+		let commission = rate * payout;
+		let referrer_share = match member.referrer {
+			Some(_) => T::ReferrerCommission::get() * commission,
+			None => Zero::zero(),
+		};
+		let referrer_payout = Self::do_referrer_payout(member_account, member, referrer_share);
+		let payee_payout = commission.saturating_sub(referrer_share);
+		if payee_payout.is_zero() {
+			return referrer_payout
+		}
+
+		let transferred = T::Currency::transfer(
+			&bonded_pool.reward_account(),
+			&payee,
+			payee_payout,
+			ExistenceRequirement::AllowDeath,
+		);
+		if transferred.is_err() {
+			log!(debug, "failed to pay out commission {:?} to {:?}", payee_payout, payee);
+			return referrer_payout
+		}
+
+		Self::deposit_event(Event::<T>::CommissionPaidOut {
+			payee,
+			member: member_account.clone(),
+			pool_id: bonded_pool.id,
+			payout: payee_payout,
 		});
+		referrer_payout.saturating_add(payee_payout)
+	}
+
+	/// Pay the referrer of `member`, if any, their `referrer_payout` of the commission on a payout.
+	///
+	/// Returns the amount paid to the referrer. If the referrer cannot receive their share (e.g.
+	/// because it is below the existential deposit), nothing is paid.
+	fn do_referrer_payout(
+		member_account: &T::AccountId,
+		member: &PoolMember<T>,
+		referrer_payout: BalanceOf<T>,
+	) -> BalanceOf<T> {
+		let referrer = match member.referrer {
+			Some(ref referrer) => referrer,
+			None => return Zero::zero(),
+		};
+		if referrer_payout.is_zero() {
+			return Zero::zero()
+		}
+
+		let transferred = T::Currency::transfer(
+			&Self::create_reward_account(member.pool_id),
+			referrer,
+			referrer_payout,
+			ExistenceRequirement::AllowDeath,
+		);
+		if transferred.is_err() {
+			log!(debug, "failed to pay out {:?} to referrer {:?}", referrer_payout, referrer);
+			return Zero::zero()
+		}
 
-		Ok(pending_rewards)
+		Self::deposit_event(Event::<T>::ReferrerPaidOut {
+			referrer: referrer.clone(),
+			member: member_account.clone(),
+			pool_id: member.pool_id,
+			payout: referrer_payout,
+		});
+		referrer_payout
 	}
 
 	fn do_create(
@@ -2376,15 +3560,20 @@ impl<T: Config> Pallet<T> {
 		);
 
 		bonded_pool.try_inc_members()?;
-		let points = bonded_pool.try_bond_funds(&who, amount, BondType::Create)?;
 
+		// The reward account needs to exist before it can be set as the payee of the bond.
+		let deposit = T::Currency::minimum_balance();
 		T::Currency::transfer(
 			&who,
 			&bonded_pool.reward_account(),
-			T::Currency::minimum_balance(),
+			deposit,
 			ExistenceRequirement::AllowDeath,
 		)?;
+		RewardAccountDeposits::<T>::insert(pool_id, deposit);
+
+		let points = bonded_pool.try_bond_funds(&who, amount, BondType::Create)?;
 
+		PoolMembersByPool::<T>::insert(pool_id, &who, ());
 		PoolMembers::<T>::insert(
 			who.clone(),
 			PoolMember::<T> {
@@ -2392,6 +3581,8 @@ impl<T: Config> Pallet<T> {
 				points,
 				last_recorded_reward_counter: Zero::zero(),
 				unbonding_eras: Default::default(),
+				referrer: None,
+				joined_at: frame_system::Pallet::<T>::block_number(),
 			},
 		);
 		RewardPools::<T>::insert(
@@ -2410,8 +3601,10 @@ impl<T: Config> Pallet<T> {
 			member: who,
 			pool_id,
 			bonded: amount,
+			points,
 			joined: true,
 		});
+		bonded_pool.deposit_updated_event();
 		bonded_pool.put();
 
 		Ok(())
@@ -2465,25 +3658,33 @@ impl<T: Config> Pallet<T> {
 
 		assert!(Metadata::<T>::iter_keys().all(|k| bonded_pools.contains(&k)));
 		assert!(SubPoolsStorage::<T>::iter_keys().all(|k| bonded_pools.contains(&k)));
+		assert!(RewardAccountDeposits::<T>::iter_keys().all(|k| bonded_pools.contains(&k)));
+		assert!(Commission::<T>::iter_keys().all(|k| bonded_pools.contains(&k)));
 
 		assert!(MaxPools::<T>::get().map_or(true, |max| bonded_pools.len() <= (max as usize)));
 
 		for id in reward_pools {
 			let account = Self::create_reward_account(id);
 			assert!(
-				T::Currency::free_balance(&account) >= T::Currency::minimum_balance(),
-				"reward pool of {id}: {:?} (ed = {:?})",
+				T::Currency::free_balance(&account) >= RewardAccountDeposits::<T>::get(id),
+				"reward pool of {id}: {:?} (deposit = {:?})",
 				T::Currency::free_balance(&account),
-				T::Currency::minimum_balance()
+				RewardAccountDeposits::<T>::get(id)
 			);
 		}
 
 		let mut pools_members = BTreeMap::<PoolId, u32>::new();
 		let mut pools_members_pending_rewards = BTreeMap::<PoolId, BalanceOf<T>>::new();
 		let mut all_members = 0u32;
-		PoolMembers::<T>::iter().for_each(|(_, d)| {
+		PoolMembers::<T>::iter().for_each(|(who, d)| {
 			let bonded_pool = BondedPools::<T>::get(d.pool_id).unwrap();
 			assert!(!d.total_points().is_zero(), "no member should have zero points: {:?}", d);
+			assert!(
+				PoolMembersIndexCursor::<T>::exists() ||
+					PoolMembersByPool::<T>::contains_key(d.pool_id, &who),
+				"member {:?} is not indexed",
+				who
+			);
 			*pools_members.entry(d.pool_id).or_default() += 1;
 			all_members += 1;
 
@@ -2496,19 +3697,30 @@ impl<T: Config> Pallet<T> {
 			} // else this pool has been heavily slashed and cannot have any rewards anymore.
 		});
 
+		// every indexed member is a member of the pool it is indexed under.
+		assert!(PoolMembersByPool::<T>::iter_keys().all(|(pool_id, who)| {
+			PoolMembers::<T>::get(&who).map_or(false, |m| m.pool_id == pool_id)
+		}));
+
 		RewardPools::<T>::iter_keys().for_each(|id| {
-			// the sum of the pending rewards must be less than the leftover balance. Since the
-			// reward math rounds down, we might accumulate some dust here.
+			// the sum of the pending rewards and the recorded dust must be less than the leftover
+			// balance. Since the reward math rounds down, we might accumulate some more dust here.
+			let dust = RewardPools::<T>::get(id).map(|p| p.dust).unwrap_or_default();
 			log!(
 				trace,
-				"pool {:?}, sum pending rewards = {:?}, remaining balance = {:?}",
+				"pool {:?}, sum pending rewards = {:?}, dust = {:?}, remaining balance = {:?}",
 				id,
 				pools_members_pending_rewards.get(&id),
+				dust,
 				RewardPool::<T>::current_balance(id)
 			);
 			assert!(
 				RewardPool::<T>::current_balance(id) >=
-					pools_members_pending_rewards.get(&id).map(|x| *x).unwrap_or_default()
+					pools_members_pending_rewards
+						.get(&id)
+						.map(|x| *x)
+						.unwrap_or_default()
+						.saturating_add(dust)
 			)
 		});
 
@@ -2535,13 +3747,15 @@ impl<T: Config> Pallet<T> {
 			return Ok(())
 		}
 
+		let mut expected_tvl: BalanceOf<T> = Zero::zero();
 		for (pool_id, _pool) in BondedPools::<T>::iter() {
 			let pool_account = Pallet::<T>::create_bonded_account(pool_id);
 			let subs = SubPoolsStorage::<T>::get(pool_id).unwrap_or_default();
+			expected_tvl += T::Staking::total_stake(&pool_account).unwrap_or_default();
 
 			let sum_unbonding_balance = subs.sum_unbonding_balance();
 			let bonded_balance = T::Staking::active_stake(&pool_account).unwrap_or_default();
-			let total_balance = T::Currency::total_balance(&pool_account);
+			let total_balance = T::StakeAdapter::total_balance(&pool_account);
 
 			assert!(
 				total_balance >= bonded_balance + sum_unbonding_balance,
@@ -2553,6 +3767,11 @@ impl<T: Config> Pallet<T> {
 				sum_unbonding_balance
 			);
 		}
+		assert_eq!(
+			TotalValueLocked::<T>::get(),
+			expected_tvl,
+			"TVL deviates from the sum of the total stake of all pools"
+		);
 
 		Ok(())
 	}
@@ -2579,8 +3798,12 @@ impl<T: Config> OnStakerSlash<T::AccountId, BalanceOf<T>> for Pallet<T> {
 		// anything here.
 		slashed_bonded: BalanceOf<T>,
 		slashed_unlocking: &BTreeMap<EraIndex, BalanceOf<T>>,
+		slashed_total: BalanceOf<T>,
+		slash_era: EraIndex,
 	) {
 		if let Some(pool_id) = ReversePoolIdLookup::<T>::get(pool_account) {
+			T::StakeAdapter::on_slash(pool_account, slashed_total);
+			TotalValueLocked::<T>::mutate(|tvl| tvl.defensive_saturating_reduce(slashed_total));
 			let mut sub_pools = match SubPoolsStorage::<T>::get(pool_id).defensive() {
 				Some(sub_pools) => sub_pools,
 				None => return,
@@ -2592,11 +3815,16 @@ impl<T: Config> OnStakerSlash<T::AccountId, BalanceOf<T>> for Pallet<T> {
 						era: *era,
 						pool_id,
 						balance: *slashed_balance,
+						slash_era,
 					});
 				}
 			}
 
-			Self::deposit_event(Event::<T>::PoolSlashed { pool_id, balance: slashed_bonded });
+			Self::deposit_event(Event::<T>::PoolSlashed {
+				pool_id,
+				balance: slashed_bonded,
+				slash_era,
+			});
 			SubPoolsStorage::<T>::insert(pool_id, sub_pools);
 		}
 	}
@@ -119,50 +119,50 @@ pub mod v2 {
 		ExtBuilder::default().build_and_execute(|| {
 			let join = |x| {
 				Balances::make_free_balance_be(&x, Balances::minimum_balance() + 10);
-				frame_support::assert_ok!(Pools::join(RuntimeOrigin::signed(x), 10, 1));
+				frame_support::assert_ok!(Pools::join(RuntimeOrigin::signed(x), 10, 1, None));
 			};
 
 			assert_eq!(BondedPool::<Runtime>::get(1).unwrap().points, 10);
 			assert_eq!(
-				RewardPools::<Runtime>::get(1).unwrap(),
+				crate::RewardPools::<Runtime>::get(1).unwrap(),
 				RewardPool { ..Default::default() }
 			);
 			assert_eq!(
-				PoolMembers::<Runtime>::get(10).unwrap().last_recorded_reward_counter,
+				crate::PoolMembers::<Runtime>::get(10).unwrap().last_recorded_reward_counter,
 				Zero::zero()
 			);
 
 			join(20);
 			assert_eq!(BondedPool::<Runtime>::get(1).unwrap().points, 20);
 			assert_eq!(
-				RewardPools::<Runtime>::get(1).unwrap(),
+				crate::RewardPools::<Runtime>::get(1).unwrap(),
 				RewardPool { ..Default::default() }
 			);
 			assert_eq!(
-				PoolMembers::<Runtime>::get(10).unwrap().last_recorded_reward_counter,
+				crate::PoolMembers::<Runtime>::get(10).unwrap().last_recorded_reward_counter,
 				Zero::zero()
 			);
 			assert_eq!(
-				PoolMembers::<Runtime>::get(20).unwrap().last_recorded_reward_counter,
+				crate::PoolMembers::<Runtime>::get(20).unwrap().last_recorded_reward_counter,
 				Zero::zero()
 			);
 
 			join(30);
 			assert_eq!(BondedPool::<Runtime>::get(1).unwrap().points, 30);
 			assert_eq!(
-				RewardPools::<Runtime>::get(1).unwrap(),
+				crate::RewardPools::<Runtime>::get(1).unwrap(),
 				RewardPool { ..Default::default() }
 			);
 			assert_eq!(
-				PoolMembers::<Runtime>::get(10).unwrap().last_recorded_reward_counter,
+				crate::PoolMembers::<Runtime>::get(10).unwrap().last_recorded_reward_counter,
 				Zero::zero()
 			);
 			assert_eq!(
-				PoolMembers::<Runtime>::get(20).unwrap().last_recorded_reward_counter,
+				crate::PoolMembers::<Runtime>::get(20).unwrap().last_recorded_reward_counter,
 				Zero::zero()
 			);
 			assert_eq!(
-				PoolMembers::<Runtime>::get(30).unwrap().last_recorded_reward_counter,
+				crate::PoolMembers::<Runtime>::get(30).unwrap().last_recorded_reward_counter,
 				Zero::zero()
 			);
 		});
@@ -183,6 +183,32 @@ pub mod v2 {
 		pub unbonding_eras: BoundedBTreeMap<EraIndex, BalanceOf<T>, T::MaxUnbonding>,
 	}
 
+	#[derive(Encode, Decode)]
+	pub struct NewRewardPool<T: Config> {
+		pub last_recorded_reward_counter: T::RewardCounter,
+		pub last_recorded_total_payouts: BalanceOf<T>,
+		pub total_rewards_claimed: BalanceOf<T>,
+	}
+
+	#[derive(Encode, Decode)]
+	pub struct NewPoolMember<T: Config> {
+		pub pool_id: PoolId,
+		pub points: BalanceOf<T>,
+		pub last_recorded_reward_counter: T::RewardCounter,
+		pub unbonding_eras: BoundedBTreeMap<EraIndex, BalanceOf<T>, T::MaxUnbonding>,
+	}
+
+	#[frame_support::storage_alias]
+	type RewardPools<T: Config> = StorageMap<Pallet<T>, Twox64Concat, PoolId, NewRewardPool<T>>;
+
+	#[frame_support::storage_alias]
+	type PoolMembers<T: Config> = StorageMap<
+		Pallet<T>,
+		Twox64Concat,
+		<T as frame_system::Config>::AccountId,
+		NewPoolMember<T>,
+	>;
+
 	/// Migrate the pool reward scheme to the new version, as per
 	/// <https://github.com/paritytech/substrate/pull/11669.>.
 	pub struct MigrateToV2<T>(sp_std::marker::PhantomData<T>);
@@ -203,7 +229,7 @@ pub mod v2 {
 
 				total_points_locked += old_member.points;
 				members_translated += 1;
-				Some(PoolMember::<T> {
+				Some(NewPoolMember::<T> {
 					last_recorded_reward_counter: Zero::zero(),
 					pool_id: old_member.pool_id,
 					points: old_member.points,
@@ -303,7 +329,7 @@ pub mod v2 {
 					// finally, migrate the reward pool.
 					reward_pools_translated += 1;
 
-					Some(RewardPool {
+					Some(NewRewardPool {
 						last_recorded_reward_counter: Zero::zero(),
 						last_recorded_total_payouts: Zero::zero(),
 						total_rewards_claimed: Zero::zero(),
@@ -349,7 +375,7 @@ pub mod v2 {
 		#[cfg(feature = "try-runtime")]
 		fn pre_upgrade() -> Result<Vec<u8>, &'static str> {
 			// all reward accounts must have more than ED.
-			RewardPools::<T>::iter().for_each(|(id, _)| {
+			RewardPools::<T>::iter_keys().for_each(|id| {
 				assert!(
 					T::Currency::free_balance(&Pallet::<T>::create_reward_account(id)) >=
 						T::Currency::minimum_balance()
@@ -365,12 +391,12 @@ pub mod v2 {
 			assert_eq!(Pallet::<T>::on_chain_storage_version(), 2);
 
 			// no reward or bonded pool has been skipped.
-			assert_eq!(RewardPools::<T>::iter().count() as u32, RewardPools::<T>::count());
+			assert_eq!(RewardPools::<T>::iter().count() as u32, crate::RewardPools::<T>::count());
 			assert_eq!(BondedPools::<T>::iter().count() as u32, BondedPools::<T>::count());
 
 			// all reward pools must have exactly ED in them. This means no reward can be claimed,
 			// and that setting reward counters all over the board to zero will work henceforth.
-			RewardPools::<T>::iter().for_each(|(id, _)| {
+			RewardPools::<T>::iter_keys().for_each(|id| {
 				assert_eq!(
 					RewardPool::<T>::current_balance(id),
 					Zero::zero(),
@@ -403,7 +429,7 @@ pub mod v3 {
 				onchain
 			);
 
-			if current > onchain {
+			if onchain == 2 {
 				let mut metadata_iterated = 0u64;
 				let mut metadata_removed = 0u64;
 				Metadata::<T>::iter_keys()
@@ -417,7 +443,7 @@ pub mod v3 {
 						metadata_removed += 1;
 						Metadata::<T>::remove(&id);
 					});
-				current.put::<Pallet<T>>();
+				StorageVersion::new(3).put::<Pallet<T>>();
 				// metadata iterated + bonded pools read + a storage version read
 				let total_reads = metadata_iterated * 2 + 1;
 				// metadata removed + a storage version write
@@ -449,3 +475,816 @@ pub mod v3 {
 		}
 	}
 }
+
+pub mod v4 {
+	use super::*;
+
+	#[derive(Decode)]
+	pub struct OldPoolMember<T: Config> {
+		pub pool_id: PoolId,
+		pub points: BalanceOf<T>,
+		pub last_recorded_reward_counter: T::RewardCounter,
+		pub unbonding_eras: BoundedBTreeMap<EraIndex, BalanceOf<T>, T::MaxUnbonding>,
+	}
+
+	#[derive(Encode, Decode)]
+	pub struct NewPoolMember<T: Config> {
+		pub pool_id: PoolId,
+		pub points: BalanceOf<T>,
+		pub last_recorded_reward_counter: T::RewardCounter,
+		pub unbonding_eras: BoundedBTreeMap<EraIndex, BalanceOf<T>, T::MaxUnbonding>,
+		pub referrer: Option<T::AccountId>,
+	}
+
+	#[frame_support::storage_alias]
+	type PoolMembers<T: Config> = StorageMap<
+		Pallet<T>,
+		Twox64Concat,
+		<T as frame_system::Config>::AccountId,
+		NewPoolMember<T>,
+	>;
+
+	/// This migration adds an empty `referrer` to all existing pool members.
+	pub struct MigrateToV4<T>(sp_std::marker::PhantomData<T>);
+	impl<T: Config> OnRuntimeUpgrade for MigrateToV4<T> {
+		fn on_runtime_upgrade() -> Weight {
+			let current = Pallet::<T>::current_storage_version();
+			let onchain = Pallet::<T>::on_chain_storage_version();
+
+			log!(
+				info,
+				"Running migration with current storage version {:?} / onchain {:?}",
+				current,
+				onchain
+			);
+
+			if onchain == 3 {
+				let mut members_translated = 0u64;
+				PoolMembers::<T>::translate::<OldPoolMember<T>, _>(|_key, old_member| {
+					members_translated += 1;
+					Some(NewPoolMember::<T> {
+						pool_id: old_member.pool_id,
+						points: old_member.points,
+						last_recorded_reward_counter: old_member.last_recorded_reward_counter,
+						unbonding_eras: old_member.unbonding_eras,
+						referrer: None,
+					})
+				});
+				StorageVersion::new(4).put::<Pallet<T>>();
+				log!(info, "Upgraded {} pool members, storage to version 4", members_translated);
+				// members translated + a storage version read and write
+				T::DbWeight::get().reads_writes(members_translated + 1, members_translated + 1)
+			} else {
+				log!(info, "MigrateToV4 should be removed");
+				T::DbWeight::get().reads(1)
+			}
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn pre_upgrade() -> Result<Vec<u8>, &'static str> {
+			ensure!(
+				Pallet::<T>::current_storage_version() > Pallet::<T>::on_chain_storage_version(),
+				"the on_chain version is equal or more than the current one"
+			);
+			Ok(crate::PoolMembers::<T>::count().encode())
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn post_upgrade(data: Vec<u8>) -> Result<(), &'static str> {
+			let members_before: u32 =
+				Decode::decode(&mut &data[..]).map_err(|_| "failed to decode pre_upgrade data")?;
+			ensure!(
+				PoolMembers::<T>::iter().count() as u32 == members_before,
+				"not all pool members have been migrated"
+			);
+			ensure!(
+				PoolMembers::<T>::iter_values().all(|m| m.referrer.is_none()),
+				"migrated pool members must not have a referrer"
+			);
+			ensure!(Pallet::<T>::on_chain_storage_version() == 4, "wrong storage version");
+			Ok(())
+		}
+	}
+}
+
+pub mod v5 {
+	use super::*;
+
+	#[derive(Encode, Decode)]
+	pub struct OldRewardPool<T: Config> {
+		pub last_recorded_reward_counter: T::RewardCounter,
+		pub last_recorded_total_payouts: BalanceOf<T>,
+		pub total_rewards_claimed: BalanceOf<T>,
+	}
+
+	#[derive(Encode, Decode)]
+	pub struct NewRewardPool<T: Config> {
+		pub last_recorded_reward_counter: T::RewardCounter,
+		pub last_recorded_total_payouts: BalanceOf<T>,
+		pub total_rewards_claimed: BalanceOf<T>,
+		pub dust: BalanceOf<T>,
+	}
+
+	/// The values of the counted map, without its counter which this migration does not change.
+	#[frame_support::storage_alias]
+	type RewardPools<T: Config> = StorageMap<Pallet<T>, Twox64Concat, PoolId, NewRewardPool<T>>;
+
+	/// This migration adds an empty `dust` to all existing reward pools.
+	///
+	/// Dust that accumulated before this migration is not recovered.
+	pub struct MigrateToV5<T>(sp_std::marker::PhantomData<T>);
+	impl<T: Config> OnRuntimeUpgrade for MigrateToV5<T> {
+		fn on_runtime_upgrade() -> Weight {
+			let current = Pallet::<T>::current_storage_version();
+			let onchain = Pallet::<T>::on_chain_storage_version();
+
+			log!(
+				info,
+				"Running migration with current storage version {:?} / onchain {:?}",
+				current,
+				onchain
+			);
+
+			if onchain == 4 {
+				let mut reward_pools_translated = 0u64;
+				RewardPools::<T>::translate::<OldRewardPool<T>, _>(|_key, old_pool| {
+					reward_pools_translated += 1;
+					Some(NewRewardPool::<T> {
+						last_recorded_reward_counter: old_pool.last_recorded_reward_counter,
+						last_recorded_total_payouts: old_pool.last_recorded_total_payouts,
+						total_rewards_claimed: old_pool.total_rewards_claimed,
+						dust: Zero::zero(),
+					})
+				});
+				StorageVersion::new(5).put::<Pallet<T>>();
+				log!(
+					info,
+					"Upgraded {} reward pools, storage to version 5",
+					reward_pools_translated
+				);
+				// reward pools translated + a storage version read and write
+				T::DbWeight::get()
+					.reads_writes(reward_pools_translated + 1, reward_pools_translated + 1)
+			} else {
+				log!(info, "MigrateToV5 should be removed");
+				T::DbWeight::get().reads(1)
+			}
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn pre_upgrade() -> Result<Vec<u8>, &'static str> {
+			ensure!(
+				Pallet::<T>::current_storage_version() > Pallet::<T>::on_chain_storage_version(),
+				"the on_chain version is equal or more than the current one"
+			);
+			Ok(crate::RewardPools::<T>::count().encode())
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn post_upgrade(data: Vec<u8>) -> Result<(), &'static str> {
+			let pools_before: u32 =
+				Decode::decode(&mut &data[..]).map_err(|_| "failed to decode pre_upgrade data")?;
+			ensure!(
+				RewardPools::<T>::iter().count() as u32 == pools_before,
+				"not all reward pools have been migrated"
+			);
+			ensure!(
+				RewardPools::<T>::iter_values().all(|p| p.dust.is_zero()),
+				"migrated reward pools must not have any dust"
+			);
+			ensure!(Pallet::<T>::on_chain_storage_version() == 5, "wrong storage version");
+			Ok(())
+		}
+	}
+}
+
+pub mod v6 {
+	use super::*;
+
+	#[derive(Encode, Decode)]
+	pub struct OldPoolMember<T: Config> {
+		pub pool_id: PoolId,
+		pub points: BalanceOf<T>,
+		pub last_recorded_reward_counter: T::RewardCounter,
+		pub unbonding_eras: BoundedBTreeMap<EraIndex, BalanceOf<T>, T::MaxUnbonding>,
+		pub referrer: Option<T::AccountId>,
+	}
+
+	#[derive(Encode, Decode)]
+	pub struct NewPoolMember<T: Config> {
+		pub pool_id: PoolId,
+		pub points: BalanceOf<T>,
+		pub last_recorded_reward_counter: T::RewardCounter,
+		pub unbonding_eras: BoundedBTreeMap<EraIndex, BalanceOf<T>, T::MaxUnbonding>,
+		pub referrer: Option<T::AccountId>,
+		pub joined_at: T::BlockNumber,
+	}
+
+	#[frame_support::storage_alias]
+	type PoolMembers<T: Config> = StorageMap<
+		Pallet<T>,
+		Twox64Concat,
+		<T as frame_system::Config>::AccountId,
+		NewPoolMember<T>,
+	>;
+
+	/// This migration records the block at which all existing pool members joined.
+	///
+	/// The actual block is unknown, so it is set to the block of the migration. Pools do not have
+	/// a minimum membership duration yet, so no member is restricted by this.
+	pub struct MigrateToV6<T>(sp_std::marker::PhantomData<T>);
+	impl<T: Config> OnRuntimeUpgrade for MigrateToV6<T> {
+		fn on_runtime_upgrade() -> Weight {
+			let current = Pallet::<T>::current_storage_version();
+			let onchain = Pallet::<T>::on_chain_storage_version();
+
+			log!(
+				info,
+				"Running migration with current storage version {:?} / onchain {:?}",
+				current,
+				onchain
+			);
+
+			if onchain == 5 {
+				let joined_at = frame_system::Pallet::<T>::block_number();
+				let mut members_translated = 0u64;
+				PoolMembers::<T>::translate::<OldPoolMember<T>, _>(|_key, old_member| {
+					members_translated += 1;
+					Some(NewPoolMember::<T> {
+						pool_id: old_member.pool_id,
+						points: old_member.points,
+						last_recorded_reward_counter: old_member.last_recorded_reward_counter,
+						unbonding_eras: old_member.unbonding_eras,
+						referrer: old_member.referrer,
+						joined_at,
+					})
+				});
+				StorageVersion::new(6).put::<Pallet<T>>();
+				log!(info, "Upgraded {} pool members, storage to version 6", members_translated);
+				// members translated + a storage version read and write
+				T::DbWeight::get().reads_writes(members_translated + 1, members_translated + 1)
+			} else {
+				log!(info, "MigrateToV6 should be removed");
+				T::DbWeight::get().reads(1)
+			}
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn pre_upgrade() -> Result<Vec<u8>, &'static str> {
+			ensure!(
+				Pallet::<T>::current_storage_version() > Pallet::<T>::on_chain_storage_version(),
+				"the on_chain version is equal or more than the current one"
+			);
+			Ok(crate::PoolMembers::<T>::count().encode())
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn post_upgrade(data: Vec<u8>) -> Result<(), &'static str> {
+			let members_before: u32 =
+				Decode::decode(&mut &data[..]).map_err(|_| "failed to decode pre_upgrade data")?;
+			ensure!(
+				PoolMembers::<T>::iter().count() as u32 == members_before,
+				"not all pool members have been migrated"
+			);
+			ensure!(Pallet::<T>::on_chain_storage_version() == 6, "wrong storage version");
+			Ok(())
+		}
+	}
+}
+
+pub mod v7 {
+	use super::*;
+
+	/// This migration initializes [`TotalValueLocked`] to the sum of the total stake of all pools.
+	pub struct MigrateToV7<T>(sp_std::marker::PhantomData<T>);
+	impl<T: Config> MigrateToV7<T> {
+		fn calculate_tvl_by_total_stake() -> BalanceOf<T> {
+			BondedPools::<T>::iter_keys()
+				.map(|id| {
+					T::Staking::total_stake(&Pallet::<T>::create_bonded_account(id))
+						.unwrap_or_default()
+				})
+				.fold(Zero::zero(), |acc: BalanceOf<T>, total| acc.saturating_add(total))
+		}
+	}
+
+	impl<T: Config> OnRuntimeUpgrade for MigrateToV7<T> {
+		fn on_runtime_upgrade() -> Weight {
+			let current = Pallet::<T>::current_storage_version();
+			let onchain = Pallet::<T>::on_chain_storage_version();
+
+			log!(
+				info,
+				"Running migration with current storage version {:?} / onchain {:?}",
+				current,
+				onchain
+			);
+
+			if onchain == 6 {
+				let pools = BondedPools::<T>::count() as u64;
+				TotalValueLocked::<T>::set(Self::calculate_tvl_by_total_stake());
+				StorageVersion::new(7).put::<Pallet<T>>();
+				log!(info, "Set the total value locked of {} pools, storage to version 7", pools);
+				// a pool and a staking ledger read per pool + a storage version read and write +
+				// the total value locked write
+				T::DbWeight::get().reads_writes(2 * pools + 1, 2)
+			} else {
+				log!(info, "MigrateToV7 should be removed");
+				T::DbWeight::get().reads(1)
+			}
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn pre_upgrade() -> Result<Vec<u8>, &'static str> {
+			ensure!(
+				Pallet::<T>::current_storage_version() > Pallet::<T>::on_chain_storage_version(),
+				"the on_chain version is equal or more than the current one"
+			);
+			Ok(Vec::new())
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn post_upgrade(_: Vec<u8>) -> Result<(), &'static str> {
+			ensure!(
+				TotalValueLocked::<T>::get() == Self::calculate_tvl_by_total_stake(),
+				"the total value locked does not match the total stake of all pools"
+			);
+			ensure!(Pallet::<T>::on_chain_storage_version() == 7, "wrong storage version");
+			Ok(())
+		}
+	}
+}
+
+pub mod v8 {
+	use super::*;
+
+	/// This migration sets [`PoolMembersIndexCursor`], so that all existing members can be
+	/// indexed in [`PoolMembersByPool`] through [`Call::migrate_members`].
+	///
+	/// Members that join after this migration are indexed right away.
+	pub struct MigrateToV8<T>(sp_std::marker::PhantomData<T>);
+	impl<T: Config> OnRuntimeUpgrade for MigrateToV8<T> {
+		fn on_runtime_upgrade() -> Weight {
+			let current = Pallet::<T>::current_storage_version();
+			let onchain = Pallet::<T>::on_chain_storage_version();
+
+			log!(
+				info,
+				"Running migration with current storage version {:?} / onchain {:?}",
+				current,
+				onchain
+			);
+
+			if onchain == 7 {
+				PoolMembersIndexCursor::<T>::put(None::<T::AccountId>);
+				StorageVersion::new(8).put::<Pallet<T>>();
+				log!(info, "Queued the pool members for indexing, storage to version 8");
+				// the cursor write + a storage version read and write
+				T::DbWeight::get().reads_writes(1, 2)
+			} else {
+				log!(info, "MigrateToV8 should be removed");
+				T::DbWeight::get().reads(1)
+			}
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn pre_upgrade() -> Result<Vec<u8>, &'static str> {
+			ensure!(
+				Pallet::<T>::current_storage_version() > Pallet::<T>::on_chain_storage_version(),
+				"the on_chain version is equal or more than the current one"
+			);
+			Ok(Vec::new())
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn post_upgrade(_: Vec<u8>) -> Result<(), &'static str> {
+			ensure!(
+				PoolMembersIndexCursor::<T>::exists(),
+				"the pool members are not queued for indexing"
+			);
+			ensure!(Pallet::<T>::on_chain_storage_version() == 8, "wrong storage version");
+			Ok(())
+		}
+	}
+}
+
+pub mod v9 {
+	use super::*;
+
+	/// This migration records the existential deposit as the deposit in the reward account of
+	/// all existing pools in [`RewardAccountDeposits`], which is what they have been created with.
+	pub struct MigrateToV9<T>(sp_std::marker::PhantomData<T>);
+	impl<T: Config> OnRuntimeUpgrade for MigrateToV9<T> {
+		fn on_runtime_upgrade() -> Weight {
+			let current = Pallet::<T>::current_storage_version();
+			let onchain = Pallet::<T>::on_chain_storage_version();
+
+			log!(
+				info,
+				"Running migration with current storage version {:?} / onchain {:?}",
+				current,
+				onchain
+			);
+
+			if onchain == 8 {
+				let deposit = T::Currency::minimum_balance();
+				let mut pools = 0u64;
+				BondedPools::<T>::iter_keys().for_each(|id| {
+					RewardAccountDeposits::<T>::insert(id, deposit);
+					pools.saturating_inc();
+				});
+				StorageVersion::new(9).put::<Pallet<T>>();
+				log!(
+					info,
+					"Recorded the reward account deposit of {} pools, storage to version 9",
+					pools
+				);
+				// a pool read and a deposit write per pool + a storage version read and write
+				T::DbWeight::get().reads_writes(pools + 1, pools + 1)
+			} else {
+				log!(info, "MigrateToV9 should be removed");
+				T::DbWeight::get().reads(1)
+			}
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn pre_upgrade() -> Result<Vec<u8>, &'static str> {
+			ensure!(
+				Pallet::<T>::current_storage_version() > Pallet::<T>::on_chain_storage_version(),
+				"the on_chain version is equal or more than the current one"
+			);
+			Ok(Vec::new())
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn post_upgrade(_: Vec<u8>) -> Result<(), &'static str> {
+			ensure!(
+				BondedPools::<T>::iter_keys()
+					.all(|id| RewardAccountDeposits::<T>::contains_key(id)),
+				"not all pools have a reward account deposit"
+			);
+			ensure!(Pallet::<T>::on_chain_storage_version() == 9, "wrong storage version");
+			Ok(())
+		}
+	}
+}
+
+pub mod v10 {
+	use super::*;
+
+	#[derive(Encode, Decode)]
+	pub struct OldRewardPool<T: Config, OldRewardCounter> {
+		pub last_recorded_reward_counter: OldRewardCounter,
+		pub last_recorded_total_payouts: BalanceOf<T>,
+		pub total_rewards_claimed: BalanceOf<T>,
+		pub dust: BalanceOf<T>,
+	}
+
+	#[derive(Encode, Decode)]
+	pub struct NewRewardPool<T: Config> {
+		pub last_recorded_reward_counter: T::RewardCounter,
+		pub last_recorded_total_payouts: BalanceOf<T>,
+		pub total_rewards_claimed: BalanceOf<T>,
+		pub dust: BalanceOf<T>,
+	}
+
+	#[derive(Encode, Decode)]
+	pub struct OldPoolMember<T: Config, OldRewardCounter> {
+		pub pool_id: PoolId,
+		pub points: BalanceOf<T>,
+		pub last_recorded_reward_counter: OldRewardCounter,
+		pub unbonding_eras: BoundedBTreeMap<EraIndex, BalanceOf<T>, T::MaxUnbonding>,
+		pub referrer: Option<T::AccountId>,
+		pub joined_at: T::BlockNumber,
+	}
+
+	#[derive(Encode, Decode)]
+	pub struct NewPoolMember<T: Config> {
+		pub pool_id: PoolId,
+		pub points: BalanceOf<T>,
+		pub last_recorded_reward_counter: T::RewardCounter,
+		pub unbonding_eras: BoundedBTreeMap<EraIndex, BalanceOf<T>, T::MaxUnbonding>,
+		pub referrer: Option<T::AccountId>,
+		pub joined_at: T::BlockNumber,
+	}
+
+	/// The values of the counted map, without its counter which this migration does not change.
+	#[frame_support::storage_alias]
+	type RewardPools<T: Config> = StorageMap<Pallet<T>, Twox64Concat, PoolId, NewRewardPool<T>>;
+
+	/// The values of the counted map, without its counter which this migration does not change.
+	#[frame_support::storage_alias]
+	type PoolMembers<T: Config> = StorageMap<
+		Pallet<T>,
+		Twox64Concat,
+		<T as frame_system::Config>::AccountId,
+		NewPoolMember<T>,
+	>;
+
+	/// This migration converts all reward counters from `OldRewardCounter`, the type that
+	/// [`Config::RewardCounter`] was before the upgrade, to the current [`Config::RewardCounter`].
+	///
+	/// It is needed when switching to a reward counter with a different precision, e.g. from
+	/// [`sp_runtime::FixedU128`] to [`sp_runtime::FixedU128E24`]. The counters of all reward pools
+	/// and all pool members keep their value, so that no pending rewards are lost. The rounding
+	/// of the counters recorded before the upgrade is not recovered.
+	pub struct MigrateToV10<T, OldRewardCounter>(
+		sp_std::marker::PhantomData<(T, OldRewardCounter)>,
+	);
+	impl<T: Config, OldRewardCounter: FixedPointNumber + Decode> MigrateToV10<T, OldRewardCounter> {
+		fn convert(old: OldRewardCounter) -> T::RewardCounter {
+			T::RewardCounter::saturating_from_rational(old.into_inner(), OldRewardCounter::DIV)
+		}
+	}
+
+	impl<T: Config, OldRewardCounter: FixedPointNumber + Decode> OnRuntimeUpgrade
+		for MigrateToV10<T, OldRewardCounter>
+	{
+		fn on_runtime_upgrade() -> Weight {
+			let current = Pallet::<T>::current_storage_version();
+			let onchain = Pallet::<T>::on_chain_storage_version();
+
+			log!(
+				info,
+				"Running migration with current storage version {:?} / onchain {:?}",
+				current,
+				onchain
+			);
+
+			if onchain == 9 {
+				let mut translated = 0u64;
+				RewardPools::<T>::translate::<OldRewardPool<T, OldRewardCounter>, _>(
+					|_key, old_pool| {
+						translated += 1;
+						Some(NewRewardPool::<T> {
+							last_recorded_reward_counter: Self::convert(
+								old_pool.last_recorded_reward_counter,
+							),
+							last_recorded_total_payouts: old_pool.last_recorded_total_payouts,
+							total_rewards_claimed: old_pool.total_rewards_claimed,
+							dust: old_pool.dust,
+						})
+					},
+				);
+				PoolMembers::<T>::translate::<OldPoolMember<T, OldRewardCounter>, _>(
+					|_key, old_member| {
+						translated += 1;
+						Some(NewPoolMember::<T> {
+							pool_id: old_member.pool_id,
+							points: old_member.points,
+							last_recorded_reward_counter: Self::convert(
+								old_member.last_recorded_reward_counter,
+							),
+							unbonding_eras: old_member.unbonding_eras,
+							referrer: old_member.referrer,
+							joined_at: old_member.joined_at,
+						})
+					},
+				);
+				StorageVersion::new(10).put::<Pallet<T>>();
+				log!(
+					info,
+					"Upgraded {} reward pools and pool members, storage to version 10",
+					translated
+				);
+				// reward pools and members translated + a storage version read and write
+				T::DbWeight::get().reads_writes(translated + 1, translated + 1)
+			} else {
+				log!(info, "MigrateToV10 should be removed");
+				T::DbWeight::get().reads(1)
+			}
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn pre_upgrade() -> Result<Vec<u8>, &'static str> {
+			ensure!(
+				Pallet::<T>::current_storage_version() > Pallet::<T>::on_chain_storage_version(),
+				"the on_chain version is equal or more than the current one"
+			);
+			Ok((crate::RewardPools::<T>::count(), crate::PoolMembers::<T>::count()).encode())
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn post_upgrade(data: Vec<u8>) -> Result<(), &'static str> {
+			let (pools_before, members_before): (u32, u32) =
+				Decode::decode(&mut &data[..]).map_err(|_| "failed to decode pre_upgrade data")?;
+			ensure!(
+				RewardPools::<T>::iter().count() as u32 == pools_before,
+				"not all reward pools have been migrated"
+			);
+			ensure!(
+				PoolMembers::<T>::iter().count() as u32 == members_before,
+				"not all pool members have been migrated"
+			);
+			ensure!(Pallet::<T>::on_chain_storage_version() == 10, "wrong storage version");
+			Ok(())
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::mock::*;
+	use frame_support::{assert_ok, storage::unhashed};
+	use sp_runtime::FixedU128;
+
+	#[test]
+	fn migrate_to_v5_adds_empty_dust() {
+		ExtBuilder::default().build_and_execute(|| {
+			// rewards that were claimed before the migration.
+			let reward_pool = RewardPool::<Runtime> {
+				last_recorded_reward_counter: Zero::zero(),
+				last_recorded_total_payouts: 10,
+				total_rewards_claimed: 10,
+				dust: 0,
+			};
+			unhashed::put(
+				&RewardPools::<Runtime>::hashed_key_for(1),
+				&v5::OldRewardPool::<Runtime> {
+					last_recorded_reward_counter: reward_pool.last_recorded_reward_counter,
+					last_recorded_total_payouts: reward_pool.last_recorded_total_payouts,
+					total_rewards_claimed: reward_pool.total_rewards_claimed,
+				},
+			);
+			StorageVersion::new(4).put::<Pools>();
+
+			v5::MigrateToV5::<Runtime>::on_runtime_upgrade();
+			assert_eq!(Pools::on_chain_storage_version(), 5);
+			assert_eq!(RewardPools::<Runtime>::get(1).unwrap(), reward_pool);
+			assert_eq!(RewardPools::<Runtime>::count(), 1);
+		});
+	}
+
+	/// Put the members back in the layout of version 5.
+	fn put_v5_members() -> Vec<(AccountId, PoolMember<Runtime>)> {
+		let members = PoolMembers::<Runtime>::iter().collect::<Vec<_>>();
+		for (who, member) in members.iter() {
+			unhashed::put(
+				&PoolMembers::<Runtime>::hashed_key_for(who),
+				&v6::OldPoolMember::<Runtime> {
+					pool_id: member.pool_id,
+					points: member.points,
+					last_recorded_reward_counter: member.last_recorded_reward_counter,
+					unbonding_eras: member.unbonding_eras.clone(),
+					referrer: member.referrer,
+				},
+			);
+		}
+		StorageVersion::new(5).put::<Pools>();
+		members
+	}
+
+	#[test]
+	fn migrate_to_v6_records_join_block() {
+		ExtBuilder::default().add_members(vec![(20, 20)]).build_and_execute(|| {
+			let members = put_v5_members();
+			assert_eq!(members.len(), 2);
+
+			System::set_block_number(42);
+			v6::MigrateToV6::<Runtime>::on_runtime_upgrade();
+			assert_eq!(Pools::on_chain_storage_version(), 6);
+			for (who, member) in members {
+				assert_eq!(
+					PoolMembers::<Runtime>::get(who).unwrap(),
+					PoolMember { joined_at: 42, ..member }
+				);
+			}
+			assert_eq!(PoolMembers::<Runtime>::count(), 2);
+
+			// the migration does not run twice.
+			System::set_block_number(43);
+			v6::MigrateToV6::<Runtime>::on_runtime_upgrade();
+			assert!(PoolMembers::<Runtime>::iter_values().all(|m| m.joined_at == 42));
+		});
+	}
+
+	#[test]
+	fn migrate_to_v7_sets_total_value_locked() {
+		ExtBuilder::default().add_members(vec![(20, 20)]).build_and_execute(|| {
+			Balances::make_free_balance_be(&30, 100);
+			assert_ok!(Pools::create(RuntimeOrigin::signed(30), 15, 30, 30, 30));
+			assert_ok!(Pools::unbond(RuntimeOrigin::signed(20), 20, 5));
+			TotalValueLocked::<Runtime>::kill();
+			StorageVersion::new(6).put::<Pools>();
+
+			v7::MigrateToV7::<Runtime>::on_runtime_upgrade();
+			assert_eq!(Pools::on_chain_storage_version(), 7);
+			// unbonding funds are still locked.
+			assert_eq!(TotalValueLocked::<Runtime>::get(), 10 + 20 + 15);
+		});
+	}
+
+	#[test]
+	fn migrate_to_v8_queues_members_for_indexing() {
+		ExtBuilder::default().add_members(vec![(20, 20)]).build_and_execute(|| {
+			let _ = PoolMembersByPool::<Runtime>::clear(u32::MAX, None);
+			StorageVersion::new(7).put::<Pools>();
+
+			v8::MigrateToV8::<Runtime>::on_runtime_upgrade();
+			assert_eq!(Pools::on_chain_storage_version(), 8);
+			assert_eq!(PoolMembersIndexCursor::<Runtime>::get(), Some(None));
+
+			assert_ok!(Pools::migrate_members(RuntimeOrigin::signed(99), 10));
+			assert_eq!(Pools::pool_members(1, 0, 10).len(), 2);
+		});
+	}
+
+	#[test]
+	fn migrate_from_v5_to_v9() {
+		ExtBuilder::default().add_members(vec![(20, 20)]).build_and_execute(|| {
+			let members = put_v5_members();
+			TotalValueLocked::<Runtime>::kill();
+			let _ = PoolMembersByPool::<Runtime>::clear(u32::MAX, None);
+			let _ = RewardAccountDeposits::<Runtime>::clear(u32::MAX, None);
+			System::set_block_number(42);
+
+			<(
+				v6::MigrateToV6<Runtime>,
+				v7::MigrateToV7<Runtime>,
+				v8::MigrateToV8<Runtime>,
+				v9::MigrateToV9<Runtime>,
+			) as OnRuntimeUpgrade>::on_runtime_upgrade();
+			assert_eq!(Pools::on_chain_storage_version(), 9);
+
+			for (who, member) in members {
+				assert_eq!(
+					PoolMembers::<Runtime>::get(who).unwrap(),
+					PoolMember { joined_at: 42, ..member }
+				);
+			}
+			assert_eq!(TotalValueLocked::<Runtime>::get(), 30);
+			assert_eq!(PoolMembersIndexCursor::<Runtime>::get(), Some(None));
+			assert!(RewardAccountDeposits::<Runtime>::contains_key(1));
+			assert_eq!(RewardAccountDeposits::<Runtime>::get(1), ExistentialDeposit::get());
+
+			// let the index catch up for the final checks.
+			assert_ok!(Pools::migrate_members(RuntimeOrigin::signed(99), 10));
+		});
+	}
+
+	#[test]
+	fn migrate_to_v10_keeps_the_value_of_reward_counters() {
+		ExtBuilder::default().add_members(vec![(20, 20)]).build_and_execute(|| {
+			// the depositor claims their share of a reward of 10 for 30 points.
+			assert_ok!(Balances::mutate_account(&default_reward_account(), |a| a.free += 10));
+			assert_ok!(Pools::claim_payout(RuntimeOrigin::signed(10)));
+
+			// put the counters back in the layout and precision of version 9.
+			let old_counter = FixedU128::from_rational(1, 3);
+			let reward_pool = RewardPools::<Runtime>::get(1).unwrap();
+			unhashed::put(
+				&RewardPools::<Runtime>::hashed_key_for(1),
+				&v10::OldRewardPool::<Runtime, FixedU128> {
+					last_recorded_reward_counter: old_counter,
+					last_recorded_total_payouts: reward_pool.last_recorded_total_payouts,
+					total_rewards_claimed: reward_pool.total_rewards_claimed,
+					dust: reward_pool.dust,
+				},
+			);
+			for (who, member) in PoolMembers::<Runtime>::iter().collect::<Vec<_>>() {
+				unhashed::put(
+					&PoolMembers::<Runtime>::hashed_key_for(who),
+					&v10::OldPoolMember::<Runtime, FixedU128> {
+						pool_id: member.pool_id,
+						points: member.points,
+						last_recorded_reward_counter: if who == 10 {
+							old_counter
+						} else {
+							Zero::zero()
+						},
+						unbonding_eras: member.unbonding_eras,
+						referrer: member.referrer,
+						joined_at: member.joined_at,
+					},
+				);
+			}
+			StorageVersion::new(9).put::<Pools>();
+
+			v10::MigrateToV10::<Runtime, FixedU128>::on_runtime_upgrade();
+			assert_eq!(Pools::on_chain_storage_version(), 10);
+			assert_eq!(RewardPools::<Runtime>::count(), 1);
+			assert_eq!(PoolMembers::<Runtime>::count(), 2);
+
+			// the counters keep their value, now with 24 decimals.
+			let new_counter = RewardCounter::from_inner(333_333_333_333_333_333_000_000);
+			assert_eq!(
+				RewardPools::<Runtime>::get(1).unwrap(),
+				RewardPool { last_recorded_reward_counter: new_counter, ..reward_pool }
+			);
+			let depositor = PoolMembers::<Runtime>::get(10).unwrap();
+			assert_eq!(depositor.last_recorded_reward_counter, new_counter);
+			assert_eq!(depositor.pending_rewards(new_counter).unwrap(), 0);
+			let member = PoolMembers::<Runtime>::get(20).unwrap();
+			assert_eq!(member.last_recorded_reward_counter, Zero::zero());
+
+			// hence the pending rewards do not change.
+			assert_eq!(member.pending_rewards(new_counter).unwrap(), 6);
+			assert_ok!(Pools::claim_payout(RuntimeOrigin::signed(20)));
+			assert_eq!(
+				Balances::free_balance(&default_reward_account()),
+				ExistentialDeposit::get() + 1
+			);
+		});
+	}
+}
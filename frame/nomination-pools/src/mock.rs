@@ -1,14 +1,14 @@
 use super::*;
-use crate::{self as pools};
-use frame_support::{assert_ok, parameter_types, PalletId};
+use crate::{self as pools, adapter::TransferStake};
+use frame_support::{assert_ok, parameter_types, traits::ReservableCurrency, PalletId};
 use frame_system::RawOrigin;
-use sp_runtime::FixedU128;
+use sp_runtime::FixedU128E24;
 use sp_staking::Stake;
 
 pub type BlockNumber = u64;
 pub type AccountId = u128;
 pub type Balance = u128;
-pub type RewardCounter = FixedU128;
+pub type RewardCounter = FixedU128E24;
 // This sneaky little hack allows us to write code exactly as we would do in the pallet in the tests
 // as well, e.g. `StorageItem::<T>::get()`.
 pub type T = Runtime;
@@ -32,15 +32,46 @@ parameter_types! {
 	#[derive(Clone, PartialEq)]
 	pub static MaxUnbonding: u32 = 8;
 	pub static StakingMinBond: Balance = 10;
+	pub static StakingMinActiveStake: Balance = 0;
+	pub static MaxNominations: u32 = 16;
+	pub static ReferrerCommission: Perbill = Perbill::zero();
+	pub static MaxCommission: Perbill = Perbill::from_percent(50);
+	pub static MinMembersForDepositorUnbond: u32 = 2;
+	pub static MaxMinMembershipDuration: BlockNumber = 10;
+	pub static MaxPointsToBalance: u8 = 10;
+	pub static DustReceiver: Option<AccountId> = None;
 	pub storage Nominations: Option<Vec<AccountId>> = None;
+	pub storage Exposures: Vec<(AccountId, EraIndex)> = vec![];
+	pub static DelegateStake: bool = false;
+	pub static UnbondWithdraws: bool = false;
+	pub storage Delegations: BTreeMap<AccountId, BTreeMap<AccountId, Balance>> = Default::default();
 }
 
 pub struct StakingMock;
 impl StakingMock {
+	/// Set the bonded balance of `who`, e.g. to simulate a reward or a slash.
+	///
+	/// If `who` is the bonded account of a pool, [`TotalValueLocked`] follows along as if the
+	/// change had been reported to the pools pallet.
 	pub(crate) fn set_bonded_balance(who: AccountId, bonded: Balance) {
 		let mut x = BondedBalanceMap::get();
-		x.insert(who, bonded);
-		BondedBalanceMap::set(&x)
+		let previous = x.insert(who, bonded).unwrap_or_default();
+		BondedBalanceMap::set(&x);
+		if ReversePoolIdLookup::<Runtime>::contains_key(who) {
+			TotalValueLocked::<Runtime>::mutate(|tvl| *tvl = (*tvl + bonded) - previous);
+		}
+	}
+
+	/// Slash `amount` out of the bonded balance of the pool `pool_id` and report it to the pools
+	/// pallet, like the staking system does.
+	pub(crate) fn slash_by(pool_id: PoolId, amount: Balance) {
+		let who = Pools::create_bonded_account(pool_id);
+		let mut x = BondedBalanceMap::get();
+		let active = x.get_mut(&who).unwrap();
+		*active -= amount;
+		let active = *active;
+		BondedBalanceMap::set(&x);
+		Pools::on_slash(&who, active, &Default::default(), amount, CurrentEra::get());
 	}
 }
 
@@ -54,8 +85,23 @@ impl sp_staking::StakingInterface for StakingMock {
 	fn minimum_validator_bond() -> Self::Balance {
 		StakingMinBond::get()
 	}
+	fn minimum_active_stake() -> Self::Balance {
+		StakingMinActiveStake::get()
+	}
 
 	fn desired_validator_count() -> u32 {
+		10
+	}
+
+	fn max_nominations() -> u32 {
+		MaxNominations::get()
+	}
+
+	fn nominators_count() -> u32 {
+		unimplemented!("method currently not used in testing")
+	}
+
+	fn max_nominators() -> Option<u32> {
 		unimplemented!("method currently not used in testing")
 	}
 
@@ -75,6 +121,10 @@ impl sp_staking::StakingInterface for StakingMock {
 	}
 
 	fn unbond(who: &Self::AccountId, amount: Self::Balance) -> DispatchResult {
+		// Simulates the staking system withdrawing the unlocked chunks to make room for a new one.
+		if UnbondWithdraws::get() {
+			Self::withdraw_unbonded(*who, 0)?;
+		}
 		let mut x = BondedBalanceMap::get();
 		*x.get_mut(who).unwrap() = x.get_mut(who).unwrap().saturating_sub(amount);
 		BondedBalanceMap::set(&x);
@@ -84,7 +134,22 @@ impl sp_staking::StakingInterface for StakingMock {
 		Ok(())
 	}
 
+	fn unbond_instantly(who: &Self::AccountId, amount: Self::Balance) -> DispatchResult {
+		let current_era = Self::current_era();
+		if (current_era.saturating_sub(Self::bonding_duration())..=current_era)
+			.any(|era| Self::is_exposed_in_era(who, &era))
+		{
+			return Err(DispatchError::Other("recently exposed"))
+		}
+		let mut x = BondedBalanceMap::get();
+		let active = x.get_mut(who).ok_or(DispatchError::Other("balance not found"))?;
+		*active = active.checked_sub(amount).ok_or(DispatchError::Other("not enough bonded"))?;
+		BondedBalanceMap::set(&x);
+		Ok(())
+	}
+
 	fn chill(_: &Self::AccountId) -> sp_runtime::DispatchResult {
+		Nominations::set(&None);
 		Ok(())
 	}
 
@@ -97,6 +162,14 @@ impl sp_staking::StakingInterface for StakingMock {
 		Ok(UnbondingBalanceMap::get().is_empty() && BondedBalanceMap::get().is_empty())
 	}
 
+	fn slashing_spans_count(_: &Self::AccountId) -> u32 {
+		0
+	}
+
+	fn payee(_: &Self::AccountId) -> Option<Self::AccountId> {
+		None
+	}
+
 	fn bond(stash: &Self::AccountId, value: Self::Balance, _: &Self::AccountId) -> DispatchResult {
 		StakingMock::set_bonded_balance(*stash, value);
 		Ok(())
@@ -107,7 +180,6 @@ impl sp_staking::StakingInterface for StakingMock {
 		Ok(())
 	}
 
-	#[cfg(feature = "runtime-benchmarks")]
 	fn nominations(_: Self::AccountId) -> Option<Vec<Self::AccountId>> {
 		Nominations::get()
 	}
@@ -132,12 +204,19 @@ impl sp_staking::StakingInterface for StakingMock {
 		unimplemented!("method currently not used in testing")
 	}
 
-	fn force_unstake(_who: Self::AccountId) -> sp_runtime::DispatchResult {
-		unimplemented!("method currently not used in testing")
+	fn force_unstake(who: Self::AccountId) -> sp_runtime::DispatchResult {
+		// Like the staking system, this does not report anything to the pools pallet.
+		let mut x = BondedBalanceMap::get();
+		x.remove(&who);
+		BondedBalanceMap::set(&x);
+		let mut y = UnbondingBalanceMap::get();
+		y.remove(&who);
+		UnbondingBalanceMap::set(&y);
+		Ok(())
 	}
 
-	fn is_exposed_in_era(_who: &Self::AccountId, _era: &EraIndex) -> bool {
-		unimplemented!("method currently not used in testing")
+	fn is_exposed_in_era(who: &Self::AccountId, era: &EraIndex) -> bool {
+		Exposures::get().contains(&(*who, *era))
 	}
 
 	#[cfg(feature = "runtime-benchmarks")]
@@ -155,6 +234,255 @@ impl sp_staking::StakingInterface for StakingMock {
 	}
 }
 
+/// A [`StakeAdapter`] that keeps the funds of members reserved on their own account, and only
+/// delegates them to the bonded account of their pool.
+///
+/// [`StakingMock`] does not require the bonded balance of an account to be backed by its own funds,
+/// hence pools can be bonded without ever receiving any funds.
+pub struct DelegateStakeMock;
+impl DelegateStakeMock {
+	/// The amount `who` has delegated to `pool_account`.
+	pub(crate) fn delegation(who: AccountId, pool_account: AccountId) -> Balance {
+		Delegations::get()
+			.get(&pool_account)
+			.and_then(|delegations| delegations.get(&who).copied())
+			.unwrap_or_default()
+	}
+
+	fn delegated(pool_account: &AccountId) -> Balance {
+		Delegations::get()
+			.get(pool_account)
+			.map(|delegations| delegations.values().sum())
+			.unwrap_or_default()
+	}
+
+	fn delegate(who: AccountId, pool_account: AccountId, amount: Balance) {
+		let mut x = Delegations::get();
+		*x.entry(pool_account).or_default().entry(who).or_default() += amount;
+		Delegations::set(&x);
+	}
+
+	fn undelegate(who: AccountId, pool_account: AccountId, amount: Balance) -> DispatchResult {
+		if amount.is_zero() {
+			return Ok(())
+		}
+		let mut x = Delegations::get();
+		let delegations = x.get_mut(&pool_account).ok_or(DispatchError::Other("no delegations"))?;
+		let delegation = delegations.get_mut(&who).ok_or(DispatchError::Other("not delegated"))?;
+		*delegation = delegation
+			.checked_sub(amount)
+			.ok_or(DispatchError::Other("not enough delegated"))?;
+		if delegation.is_zero() {
+			delegations.remove(&who);
+		}
+		Delegations::set(&x);
+		Ok(())
+	}
+}
+
+impl StakeAdapter for DelegateStakeMock {
+	type Balance = Balance;
+	type AccountId = AccountId;
+
+	fn transferable_balance(pool_account: &AccountId) -> Balance {
+		Self::delegated(pool_account)
+			.saturating_sub(StakingMock::active_stake(pool_account).unwrap_or_default())
+	}
+
+	fn total_balance(pool_account: &AccountId) -> Balance {
+		Self::delegated(pool_account)
+	}
+
+	fn pledge_bond(
+		who: &AccountId,
+		pool_account: &AccountId,
+		reward_account: &AccountId,
+		amount: Balance,
+		bond_type: BondType,
+	) -> DispatchResult {
+		Balances::reserve(who, amount)?;
+		Self::delegate(*who, *pool_account, amount);
+		match bond_type {
+			BondType::Create => StakingMock::bond(pool_account, amount, reward_account),
+			BondType::Later => StakingMock::bond_extra(pool_account, amount),
+		}
+	}
+
+	fn unbond(pool_account: &AccountId, amount: Balance) -> DispatchResult {
+		StakingMock::unbond(pool_account, amount)
+	}
+
+	fn withdraw_unbonded(
+		pool_account: &AccountId,
+		num_slashing_spans: u32,
+	) -> Result<bool, DispatchError> {
+		StakingMock::withdraw_unbonded(*pool_account, num_slashing_spans)
+	}
+
+	fn member_withdraw(
+		who: &AccountId,
+		pool_account: &AccountId,
+		amount: Balance,
+	) -> DispatchResult {
+		Self::undelegate(*who, *pool_account, amount)?;
+		Balances::unreserve(who, amount);
+		Ok(())
+	}
+
+	fn member_switch(
+		who: &AccountId,
+		from_pool: &AccountId,
+		to_pool: &AccountId,
+		amount: Balance,
+	) -> DispatchResult {
+		StakingMock::unbond_instantly(from_pool, amount)?;
+		Self::undelegate(*who, *from_pool, amount)?;
+		Self::delegate(*who, *to_pool, amount);
+		StakingMock::bond_extra(to_pool, amount)
+	}
+
+	fn on_slash(pool_account: &AccountId, amount: Balance) {
+		// Each delegator bears a share of the slash proportional to their delegation.
+		let total = Self::delegated(pool_account);
+		if total.is_zero() {
+			return
+		}
+		let mut x = Delegations::get();
+		let delegations = match x.get_mut(pool_account) {
+			Some(delegations) => delegations,
+			None => return,
+		};
+		let amount = amount.min(total);
+		let mut remainder = amount;
+		for (who, delegation) in delegations.iter_mut() {
+			let share = amount * *delegation / total;
+			let _ = Balances::slash_reserved(who, share);
+			*delegation -= share;
+			remainder -= share;
+		}
+		// shares are rounded down, the last delegators bear what is left.
+		for (who, delegation) in delegations.iter_mut().rev() {
+			let share = remainder.min(*delegation);
+			let _ = Balances::slash_reserved(who, share);
+			*delegation -= share;
+			remainder -= share;
+		}
+		Delegations::set(&x);
+	}
+
+	fn dissolve(pool_account: &AccountId) {
+		let mut x = Delegations::get();
+		for (who, delegation) in x.remove(pool_account).unwrap_or_default() {
+			Balances::unreserve(&who, delegation);
+		}
+		Delegations::set(&x);
+	}
+}
+
+/// The [`StakeAdapter`] of the mock runtime: [`DelegateStakeMock`] if [`DelegateStake`] is set,
+/// [`TransferStake`] otherwise.
+pub struct StakeAdapterMock;
+impl StakeAdapter for StakeAdapterMock {
+	type Balance = Balance;
+	type AccountId = AccountId;
+
+	fn transferable_balance(pool_account: &AccountId) -> Balance {
+		if DelegateStake::get() {
+			DelegateStakeMock::transferable_balance(pool_account)
+		} else {
+			TransferStake::<Runtime>::transferable_balance(pool_account)
+		}
+	}
+
+	fn total_balance(pool_account: &AccountId) -> Balance {
+		if DelegateStake::get() {
+			DelegateStakeMock::total_balance(pool_account)
+		} else {
+			TransferStake::<Runtime>::total_balance(pool_account)
+		}
+	}
+
+	fn pledge_bond(
+		who: &AccountId,
+		pool_account: &AccountId,
+		reward_account: &AccountId,
+		amount: Balance,
+		bond_type: BondType,
+	) -> DispatchResult {
+		if DelegateStake::get() {
+			DelegateStakeMock::pledge_bond(who, pool_account, reward_account, amount, bond_type)
+		} else {
+			TransferStake::<Runtime>::pledge_bond(
+				who,
+				pool_account,
+				reward_account,
+				amount,
+				bond_type,
+			)
+		}
+	}
+
+	fn unbond(pool_account: &AccountId, amount: Balance) -> DispatchResult {
+		if DelegateStake::get() {
+			DelegateStakeMock::unbond(pool_account, amount)
+		} else {
+			TransferStake::<Runtime>::unbond(pool_account, amount)
+		}
+	}
+
+	fn withdraw_unbonded(
+		pool_account: &AccountId,
+		num_slashing_spans: u32,
+	) -> Result<bool, DispatchError> {
+		if DelegateStake::get() {
+			DelegateStakeMock::withdraw_unbonded(pool_account, num_slashing_spans)
+		} else {
+			TransferStake::<Runtime>::withdraw_unbonded(pool_account, num_slashing_spans)
+		}
+	}
+
+	fn member_withdraw(
+		who: &AccountId,
+		pool_account: &AccountId,
+		amount: Balance,
+	) -> DispatchResult {
+		if DelegateStake::get() {
+			DelegateStakeMock::member_withdraw(who, pool_account, amount)
+		} else {
+			TransferStake::<Runtime>::member_withdraw(who, pool_account, amount)
+		}
+	}
+
+	fn member_switch(
+		who: &AccountId,
+		from_pool: &AccountId,
+		to_pool: &AccountId,
+		amount: Balance,
+	) -> DispatchResult {
+		if DelegateStake::get() {
+			DelegateStakeMock::member_switch(who, from_pool, to_pool, amount)
+		} else {
+			TransferStake::<Runtime>::member_switch(who, from_pool, to_pool, amount)
+		}
+	}
+
+	fn on_slash(pool_account: &AccountId, amount: Balance) {
+		if DelegateStake::get() {
+			DelegateStakeMock::on_slash(pool_account, amount)
+		} else {
+			TransferStake::<Runtime>::on_slash(pool_account, amount)
+		}
+	}
+
+	fn dissolve(pool_account: &AccountId) {
+		if DelegateStake::get() {
+			DelegateStakeMock::dissolve(pool_account)
+		} else {
+			TransferStake::<Runtime>::dissolve(pool_account)
+		}
+	}
+}
+
 impl frame_system::Config for Runtime {
 	type SS58Prefix = ();
 	type BaseCallFilter = frame_support::traits::Everything;
@@ -226,11 +554,17 @@ impl pools::Config for Runtime {
 	type BalanceToU256 = BalanceToU256;
 	type U256ToBalance = U256ToBalance;
 	type Staking = StakingMock;
+	type StakeAdapter = StakeAdapterMock;
 	type PostUnbondingPoolsWindow = PostUnbondingPoolsWindow;
 	type PalletId = PoolsPalletId;
 	type MaxMetadataLen = MaxMetadataLen;
 	type MaxUnbonding = MaxUnbonding;
-	type MaxPointsToBalance = frame_support::traits::ConstU8<10>;
+	type ReferrerCommission = ReferrerCommission;
+	type MaxCommission = MaxCommission;
+	type MinMembersForDepositorUnbond = MinMembersForDepositorUnbond;
+	type MaxMinMembershipDuration = MaxMinMembershipDuration;
+	type MaxPointsToBalance = MaxPointsToBalance;
+	type DustReceiver = DustReceiver;
 }
 
 type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Runtime>;
@@ -247,6 +581,7 @@ frame_support::construct_runtime!(
 	}
 );
 
+#[derive(Clone)]
 pub struct ExtBuilder {
 	members: Vec<(AccountId, Balance)>,
 	max_members: Option<u32>,
@@ -297,6 +632,12 @@ impl ExtBuilder {
 		self
 	}
 
+	/// Keep the funds of members on their own account, see [`DelegateStakeMock`].
+	pub fn delegate_stake(self, delegate: bool) -> Self {
+		DelegateStake::set(delegate);
+		self
+	}
+
 	pub fn build(self) -> sp_io::TestExternalities {
 		sp_tracing::try_init_simple();
 		let mut storage =
@@ -325,7 +666,12 @@ impl ExtBuilder {
 			let last_pool = LastPoolId::<Runtime>::get();
 			for (account_id, bonded) in self.members {
 				Balances::make_free_balance_be(&account_id, bonded * 2);
-				assert_ok!(Pools::join(RawOrigin::Signed(account_id).into(), bonded, last_pool));
+				assert_ok!(Pools::join(
+					RawOrigin::Signed(account_id).into(),
+					bonded,
+					last_pool,
+					None
+				));
 			}
 		});
 
@@ -338,6 +684,15 @@ impl ExtBuilder {
 			Pools::do_try_state(CheckLevel::get()).unwrap();
 		})
 	}
+
+	/// Like [`Self::build_and_execute`], but once with each of the [`StakeAdapter`]s of the mock.
+	pub fn build_and_execute_with_each_adapter(self, test: impl Fn()) {
+		let current_era = CurrentEra::get();
+		for delegate in [false, true] {
+			CurrentEra::set(current_era);
+			self.clone().delegate_stake(delegate).build_and_execute(&test);
+		}
+	}
 }
 
 pub fn unsafe_set_state(pool_id: PoolId, state: PoolState) {
@@ -67,12 +67,18 @@ fn test_setup_works() {
 			RewardPool::<Runtime> {
 				last_recorded_reward_counter: Zero::zero(),
 				last_recorded_total_payouts: 0,
-				total_rewards_claimed: 0
+				total_rewards_claimed: 0,
+				dust: 0,
 			}
 		);
 		assert_eq!(
 			PoolMembers::<Runtime>::get(10).unwrap(),
-			PoolMember::<Runtime> { pool_id: last_pool, points: 10, ..Default::default() }
+			PoolMember::<Runtime> {
+				pool_id: last_pool,
+				points: 10,
+				joined_at: 1,
+				..Default::default()
+			}
 		);
 
 		let bonded_account = Pools::create_bonded_account(last_pool);
@@ -442,7 +448,7 @@ mod join {
 			assert!(!PoolMembers::<Runtime>::contains_key(&11));
 
 			// When
-			assert_ok!(Pools::join(RuntimeOrigin::signed(11), 2, 1));
+			assert_ok!(Pools::join(RuntimeOrigin::signed(11), 2, 1, None));
 
 			// Then
 
@@ -450,14 +456,16 @@ mod join {
 				pool_events_since_last_call(),
 				vec![
 					Event::Created { depositor: 10, pool_id: 1 },
-					Event::Bonded { member: 10, pool_id: 1, bonded: 10, joined: true },
-					Event::Bonded { member: 11, pool_id: 1, bonded: 2, joined: true },
+					Event::Bonded { member: 10, pool_id: 1, bonded: 10, points: 10, joined: true },
+					Event::PoolUpdated { pool_id: 1, points: 10, balance: 10 },
+					Event::Bonded { member: 11, pool_id: 1, bonded: 2, points: 2, joined: true },
+					Event::PoolUpdated { pool_id: 1, points: 12, balance: 12 },
 				]
 			);
 
 			assert_eq!(
 				PoolMembers::<Runtime>::get(&11).unwrap(),
-				PoolMember::<Runtime> { pool_id: 1, points: 2, ..Default::default() }
+				PoolMember::<Runtime> { pool_id: 1, points: 2, joined_at: 1, ..Default::default() }
 			);
 			assert_eq!(BondedPool::<Runtime>::get(1).unwrap(), bonded(12, 2));
 
@@ -470,17 +478,25 @@ mod join {
 			assert!(!PoolMembers::<Runtime>::contains_key(&12));
 
 			// When
-			assert_ok!(Pools::join(RuntimeOrigin::signed(12), 12, 1));
+			assert_ok!(Pools::join(RuntimeOrigin::signed(12), 12, 1, None));
 
 			// Then
 			assert_eq!(
 				pool_events_since_last_call(),
-				vec![Event::Bonded { member: 12, pool_id: 1, bonded: 12, joined: true }]
+				vec![
+					Event::Bonded { member: 12, pool_id: 1, bonded: 12, points: 24, joined: true },
+					Event::PoolUpdated { pool_id: 1, points: 36, balance: 18 },
+				]
 			);
 
 			assert_eq!(
 				PoolMembers::<Runtime>::get(&12).unwrap(),
-				PoolMember::<Runtime> { pool_id: 1, points: 24, ..Default::default() }
+				PoolMember::<Runtime> {
+					pool_id: 1,
+					points: 24,
+					joined_at: 1,
+					..Default::default()
+				}
 			);
 			assert_eq!(BondedPool::<Runtime>::get(1).unwrap(), bonded(12 + 24, 3));
 		});
@@ -493,19 +509,19 @@ mod join {
 			assert_eq!(PoolMembers::<Runtime>::get(&10).unwrap().pool_id, 1);
 
 			assert_noop!(
-				Pools::join(RuntimeOrigin::signed(10), 420, 123),
+				Pools::join(RuntimeOrigin::signed(10), 420, 123, None),
 				Error::<Runtime>::AccountBelongsToOtherPool
 			);
 
 			assert_noop!(
-				Pools::join(RuntimeOrigin::signed(11), 420, 123),
+				Pools::join(RuntimeOrigin::signed(11), 420, 123, None),
 				Error::<Runtime>::PoolNotFound
 			);
 
 			// Force the pools bonded balance to 0, simulating a 100% slash
 			StakingMock::set_bonded_balance(Pools::create_bonded_account(1), 0);
 			assert_noop!(
-				Pools::join(RuntimeOrigin::signed(11), 420, 1),
+				Pools::join(RuntimeOrigin::signed(11), 420, 1, None),
 				Error::<Runtime>::OverflowRisk
 			);
 
@@ -533,7 +549,7 @@ mod join {
 				max_points_to_balance,
 			);
 			assert_noop!(
-				Pools::join(RuntimeOrigin::signed(11), 420, 123),
+				Pools::join(RuntimeOrigin::signed(11), 420, 123, None),
 				Error::<Runtime>::OverflowRisk
 			);
 
@@ -543,7 +559,7 @@ mod join {
 			);
 			// Balance needs to be gt Balance::MAX / `MaxPointsToBalance`
 			assert_noop!(
-				Pools::join(RuntimeOrigin::signed(11), 5, 123),
+				Pools::join(RuntimeOrigin::signed(11), 5, 123, None),
 				pallet_balances::Error::<Runtime>::InsufficientBalance,
 			);
 
@@ -552,13 +568,13 @@ mod join {
 			// Cannot join a pool that isn't open
 			unsafe_set_state(123, PoolState::Blocked);
 			assert_noop!(
-				Pools::join(RuntimeOrigin::signed(11), max_points_to_balance, 123),
+				Pools::join(RuntimeOrigin::signed(11), max_points_to_balance, 123, None),
 				Error::<Runtime>::NotOpen
 			);
 
 			unsafe_set_state(123, PoolState::Destroying);
 			assert_noop!(
-				Pools::join(RuntimeOrigin::signed(11), max_points_to_balance, 123),
+				Pools::join(RuntimeOrigin::signed(11), max_points_to_balance, 123, None),
 				Error::<Runtime>::NotOpen
 			);
 
@@ -567,7 +583,7 @@ mod join {
 
 			// Then
 			assert_noop!(
-				Pools::join(RuntimeOrigin::signed(11), 99, 123),
+				Pools::join(RuntimeOrigin::signed(11), 99, 123, None),
 				Error::<Runtime>::MinimumBondNotMet
 			);
 		});
@@ -589,7 +605,7 @@ mod join {
 				},
 			}
 			.put();
-			let _ = Pools::join(RuntimeOrigin::signed(11), 420, 123);
+			let _ = Pools::join(RuntimeOrigin::signed(11), 420, 123, None);
 		});
 	}
 
@@ -602,7 +618,7 @@ mod join {
 				let account = i + 100;
 				Balances::make_free_balance_be(&account, 100 + Balances::minimum_balance());
 
-				assert_ok!(Pools::join(RuntimeOrigin::signed(account), 100, 1));
+				assert_ok!(Pools::join(RuntimeOrigin::signed(account), 100, 1, None));
 			}
 
 			Balances::make_free_balance_be(&103, 100 + Balances::minimum_balance());
@@ -612,14 +628,29 @@ mod join {
 				pool_events_since_last_call(),
 				vec![
 					Event::Created { depositor: 10, pool_id: 1 },
-					Event::Bonded { member: 10, pool_id: 1, bonded: 10, joined: true },
-					Event::Bonded { member: 101, pool_id: 1, bonded: 100, joined: true },
-					Event::Bonded { member: 102, pool_id: 1, bonded: 100, joined: true }
+					Event::Bonded { member: 10, pool_id: 1, bonded: 10, points: 10, joined: true },
+					Event::PoolUpdated { pool_id: 1, points: 10, balance: 10 },
+					Event::Bonded {
+						member: 101,
+						pool_id: 1,
+						bonded: 100,
+						points: 100,
+						joined: true
+					},
+					Event::PoolUpdated { pool_id: 1, points: 110, balance: 110 },
+					Event::Bonded {
+						member: 102,
+						pool_id: 1,
+						bonded: 100,
+						points: 100,
+						joined: true
+					},
+					Event::PoolUpdated { pool_id: 1, points: 210, balance: 210 }
 				]
 			);
 
 			assert_noop!(
-				Pools::join(RuntimeOrigin::signed(103), 100, 1),
+				Pools::join(RuntimeOrigin::signed(103), 100, 1, None),
 				Error::<Runtime>::MaxPoolMembers
 			);
 
@@ -640,16 +671,94 @@ mod join {
 				pool_events_since_last_call(),
 				vec![
 					Event::Created { depositor: 104, pool_id: 2 },
-					Event::Bonded { member: 104, pool_id: 2, bonded: 100, joined: true }
+					Event::Bonded {
+						member: 104,
+						pool_id: 2,
+						bonded: 100,
+						points: 100,
+						joined: true
+					},
+					Event::PoolUpdated { pool_id: 2, points: 100, balance: 100 }
 				]
 			);
 
 			assert_noop!(
-				Pools::join(RuntimeOrigin::signed(103), 100, pool_account),
+				Pools::join(RuntimeOrigin::signed(103), 100, pool_account, None),
 				Error::<Runtime>::MaxPoolMembers
 			);
 		});
 	}
+
+	#[test]
+	fn join_with_referrer_works() {
+		ExtBuilder::default().build_and_execute(|| {
+			// Given
+			Balances::make_free_balance_be(&11, ExistentialDeposit::get() + 2);
+
+			// When
+			assert_ok!(Pools::join(RuntimeOrigin::signed(11), 2, 1, Some(30)));
+
+			// Then
+			assert_eq!(
+				pool_events_since_last_call(),
+				vec![
+					Event::Created { depositor: 10, pool_id: 1 },
+					Event::Bonded { member: 10, pool_id: 1, bonded: 10, points: 10, joined: true },
+					Event::PoolUpdated { pool_id: 1, points: 10, balance: 10 },
+					Event::Bonded { member: 11, pool_id: 1, bonded: 2, points: 2, joined: true },
+					Event::PoolUpdated { pool_id: 1, points: 12, balance: 12 },
+					Event::Referred { member: 11, pool_id: 1, referrer: 30 },
+				]
+			);
+			assert_eq!(
+				PoolMembers::<Runtime>::get(&11).unwrap(),
+				PoolMember::<Runtime> {
+					pool_id: 1,
+					points: 2,
+					referrer: Some(30),
+					joined_at: 1,
+					..Default::default()
+				}
+			);
+
+			// the referrer is kept when bonding extra.
+			Balances::make_free_balance_be(&11, ExistentialDeposit::get() + 2);
+			assert_ok!(Pools::bond_extra(RuntimeOrigin::signed(11), BondExtra::FreeBalance(2)));
+			assert_eq!(PoolMembers::<Runtime>::get(&11).unwrap().referrer, Some(30));
+		});
+	}
+
+	#[test]
+	fn join_rejects_self_referral() {
+		ExtBuilder::default().build_and_execute(|| {
+			Balances::make_free_balance_be(&11, ExistentialDeposit::get() + 2);
+
+			assert_noop!(
+				Pools::join(RuntimeOrigin::signed(11), 2, 1, Some(11)),
+				Error::<Runtime>::SelfReferral
+			);
+		});
+	}
+
+	#[test]
+	fn join_respects_max_points_to_balance() {
+		ExtBuilder::default().add_members(vec![(20, 20)]).build_and_execute(|| {
+			Balances::make_free_balance_be(&30, 100);
+
+			// a 90% slash puts the pool at a points-to-balance ratio of 10:1.
+			StakingMock::slash_by(1, 27);
+			assert_eq!(BondedPool::<Runtime>::get(1).unwrap().points, 30);
+			assert_noop!(
+				Pools::join(RuntimeOrigin::signed(30), 10, 1, None),
+				Error::<Runtime>::OverflowRisk
+			);
+
+			// a runtime that tolerates a higher ratio still accepts new members.
+			MaxPointsToBalance::set(11);
+			assert_ok!(Pools::join(RuntimeOrigin::signed(30), 10, 1, None));
+			assert_eq!(PoolMembers::<Runtime>::get(30).unwrap().points, 100);
+		});
+	}
 }
 
 mod claim_payout {
@@ -661,6 +770,8 @@ mod claim_payout {
 			points,
 			last_recorded_reward_counter: last_recorded_reward_counter.into(),
 			unbonding_eras: Default::default(),
+			referrer: None,
+			joined_at: 1,
 		}
 	}
 
@@ -668,8 +779,15 @@ mod claim_payout {
 		PoolMember {
 			pool_id: 1,
 			points,
-			last_recorded_reward_counter: RewardCounter::from_float(last_recorded_reward_counter),
+			// `from_float` is not exact at the precision of the reward counter, and all counters
+			// in these tests have at most two decimals.
+			last_recorded_reward_counter: RewardCounter::from_rational(
+				(last_recorded_reward_counter * 100.0).round() as u128,
+				100,
+			),
 			unbonding_eras: Default::default(),
+			referrer: None,
+			joined_at: 1,
 		}
 	}
 
@@ -682,6 +800,7 @@ mod claim_payout {
 			last_recorded_reward_counter: last_recorded_reward_counter.into(),
 			last_recorded_total_payouts,
 			total_rewards_claimed,
+			dust: 0,
 		}
 	}
 
@@ -689,7 +808,7 @@ mod claim_payout {
 	fn claim_payout_works() {
 		ExtBuilder::default()
 			.add_members(vec![(40, 40), (50, 50)])
-			.build_and_execute(|| {
+			.build_and_execute_with_each_adapter(|| {
 				// Given each member currently has a free balance of
 				Balances::make_free_balance_be(&10, 0);
 				Balances::make_free_balance_be(&40, 0);
@@ -869,32 +988,49 @@ mod claim_payout {
 
 	#[test]
 	fn reward_payout_errors_if_a_member_is_fully_unbonding() {
-		ExtBuilder::default().add_members(vec![(11, 11)]).build_and_execute(|| {
-			// fully unbond the member.
-			assert_ok!(fully_unbond_permissioned(11));
+		ExtBuilder::default()
+			.add_members(vec![(11, 11)])
+			.build_and_execute_with_each_adapter(|| {
+				// fully unbond the member.
+				assert_ok!(fully_unbond_permissioned(11));
 
-			assert_noop!(
-				Pools::claim_payout(RuntimeOrigin::signed(11)),
-				Error::<Runtime>::FullyUnbonding
-			);
+				assert_noop!(
+					Pools::claim_payout(RuntimeOrigin::signed(11)),
+					Error::<Runtime>::FullyUnbonding
+				);
 
-			assert_eq!(
-				pool_events_since_last_call(),
-				vec![
-					Event::Created { depositor: 10, pool_id: 1 },
-					Event::Bonded { member: 10, pool_id: 1, bonded: 10, joined: true },
-					Event::Bonded { member: 11, pool_id: 1, bonded: 11, joined: true },
-					Event::Unbonded { member: 11, pool_id: 1, points: 11, balance: 11, era: 3 }
-				]
-			);
-		});
+				assert_eq!(
+					pool_events_since_last_call(),
+					vec![
+						Event::Created { depositor: 10, pool_id: 1 },
+						Event::Bonded {
+							member: 10,
+							pool_id: 1,
+							bonded: 10,
+							points: 10,
+							joined: true
+						},
+						Event::PoolUpdated { pool_id: 1, points: 10, balance: 10 },
+						Event::Bonded {
+							member: 11,
+							pool_id: 1,
+							bonded: 11,
+							points: 11,
+							joined: true
+						},
+						Event::PoolUpdated { pool_id: 1, points: 21, balance: 21 },
+						Event::Unbonded { member: 11, pool_id: 1, points: 11, balance: 11, era: 3 },
+						Event::PoolUpdated { pool_id: 1, points: 10, balance: 10 }
+					]
+				);
+			});
 	}
 
 	#[test]
 	fn do_reward_payout_works_with_a_pool_of_1() {
 		let del = |last_recorded_reward_counter| del_float(10, last_recorded_reward_counter);
 
-		ExtBuilder::default().build_and_execute(|| {
+		ExtBuilder::default().build_and_execute_with_each_adapter(|| {
 			let (mut member, mut bonded_pool, mut reward_pool) =
 				Pools::get_member_with_pools(&10).unwrap();
 			let ed = Balances::minimum_balance();
@@ -921,7 +1057,8 @@ mod claim_payout {
 				pool_events_since_last_call(),
 				vec![
 					Event::Created { depositor: 10, pool_id: 1 },
-					Event::Bonded { member: 10, pool_id: 1, bonded: 10, joined: true },
+					Event::Bonded { member: 10, pool_id: 1, bonded: 10, points: 10, joined: true },
+					Event::PoolUpdated { pool_id: 1, points: 10, balance: 10 },
 					Event::PaidOut { member: 10, pool_id: 1, payout: 5 }
 				]
 			);
@@ -966,7 +1103,7 @@ mod claim_payout {
 	fn do_reward_payout_works_with_a_pool_of_3() {
 		ExtBuilder::default()
 			.add_members(vec![(40, 40), (50, 50)])
-			.build_and_execute(|| {
+			.build_and_execute_with_each_adapter(|| {
 				let mut bonded_pool = BondedPool::<Runtime>::get(1).unwrap();
 				let mut reward_pool = RewardPools::<Runtime>::get(1).unwrap();
 
@@ -978,9 +1115,30 @@ mod claim_payout {
 					pool_events_since_last_call(),
 					vec![
 						Event::Created { depositor: 10, pool_id: 1 },
-						Event::Bonded { member: 10, pool_id: 1, bonded: 10, joined: true },
-						Event::Bonded { member: 40, pool_id: 1, bonded: 40, joined: true },
-						Event::Bonded { member: 50, pool_id: 1, bonded: 50, joined: true }
+						Event::Bonded {
+							member: 10,
+							pool_id: 1,
+							bonded: 10,
+							points: 10,
+							joined: true
+						},
+						Event::PoolUpdated { pool_id: 1, points: 10, balance: 10 },
+						Event::Bonded {
+							member: 40,
+							pool_id: 1,
+							bonded: 40,
+							points: 40,
+							joined: true
+						},
+						Event::PoolUpdated { pool_id: 1, points: 50, balance: 50 },
+						Event::Bonded {
+							member: 50,
+							pool_id: 1,
+							bonded: 50,
+							points: 50,
+							joined: true
+						},
+						Event::PoolUpdated { pool_id: 1, points: 100, balance: 100 }
 					]
 				);
 
@@ -1148,14 +1306,101 @@ mod claim_payout {
 	}
 
 	#[test]
-	fn rewards_distribution_is_fair_basic() {
+	fn referrer_is_paid_out_of_the_commission() {
+		ExtBuilder::default().build_and_execute_with_each_adapter(|| {
+			ReferrerCommission::set(Perbill::from_percent(50));
+			Balances::make_free_balance_be(&30, Balances::minimum_balance());
+			Balances::make_free_balance_be(&40, Balances::minimum_balance());
+			assert_ok!(Pools::set_commission(
+				RuntimeOrigin::signed(900),
+				1,
+				Some((Perbill::from_percent(50), 40))
+			));
+
+			// 20 joins on referral of 30.
+			Balances::make_free_balance_be(&20, Balances::minimum_balance() + 10);
+			assert_ok!(Pools::join(RuntimeOrigin::signed(20), 10, 1, Some(30)));
+
+			// reward pool by 40.
+			Balances::mutate_account(&default_reward_account(), |f| f.free += 40).unwrap();
+
+			// the pool charges half of each payout of 20 as commission. 10 has no referrer, so
+			// all of it goes to 40, while 30 gets half of it on the payout of 20.
+			assert_ok!(Pools::claim_payout(RuntimeOrigin::signed(10)));
+			assert_ok!(Pools::claim_payout(RuntimeOrigin::signed(20)));
+			assert_eq!(
+				pool_events_since_last_call(),
+				vec![
+					Event::Created { depositor: 10, pool_id: 1 },
+					Event::Bonded { member: 10, pool_id: 1, bonded: 10, points: 10, joined: true },
+					Event::PoolUpdated { pool_id: 1, points: 10, balance: 10 },
+					Event::CommissionUpdated {
+						pool_id: 1,
+						commission: Some((Perbill::from_percent(50), 40))
+					},
+					Event::Bonded { member: 20, pool_id: 1, bonded: 10, points: 10, joined: true },
+					Event::PoolUpdated { pool_id: 1, points: 20, balance: 20 },
+					Event::Referred { member: 20, pool_id: 1, referrer: 30 },
+					Event::CommissionPaidOut { payee: 40, member: 10, pool_id: 1, payout: 10 },
+					Event::PaidOut { member: 10, pool_id: 1, payout: 10 },
+					Event::ReferrerPaidOut { referrer: 30, member: 20, pool_id: 1, payout: 5 },
+					Event::CommissionPaidOut { payee: 40, member: 20, pool_id: 1, payout: 5 },
+					Event::PaidOut { member: 20, pool_id: 1, payout: 10 },
+				]
+			);
+			assert_eq!(Balances::free_balance(&20), Balances::minimum_balance() + 10);
+			assert_eq!(Balances::free_balance(&30), Balances::minimum_balance() + 5);
+			assert_eq!(Balances::free_balance(&40), Balances::minimum_balance() + 15);
+			assert_eq!(
+				Balances::free_balance(&default_reward_account()),
+				Balances::minimum_balance()
+			);
+		});
+	}
+
+	#[test]
+	fn member_keeps_shares_of_the_commission_that_cannot_be_paid() {
 		ExtBuilder::default().build_and_execute(|| {
+			ReferrerCommission::set(Perbill::from_percent(50));
+			Balances::make_free_balance_be(&40, Balances::minimum_balance());
+			assert_ok!(Pools::set_commission(
+				RuntimeOrigin::signed(900),
+				1,
+				Some((Perbill::from_percent(20), 40))
+			));
+
+			// 20 joins on referral of 30, who does not exist.
+			Balances::make_free_balance_be(&20, Balances::minimum_balance() + 10);
+			assert_ok!(Pools::join(RuntimeOrigin::signed(20), 10, 1, Some(30)));
+
+			// reward pool by 40.
+			Balances::mutate_account(&default_reward_account(), |f| f.free += 40).unwrap();
+
+			// the commission is 4, and the share of 30 would be 2, which is below the existential
+			// deposit. 20 keeps it and only the share of 40 is deducted.
+			assert_ok!(Pools::claim_payout(RuntimeOrigin::signed(20)));
+			assert_eq!(
+				pool_events_since_last_call().into_iter().rev().take(2).collect::<Vec<_>>(),
+				vec![
+					Event::PaidOut { member: 20, pool_id: 1, payout: 18 },
+					Event::CommissionPaidOut { payee: 40, member: 20, pool_id: 1, payout: 2 },
+				]
+			);
+			assert_eq!(Balances::free_balance(&20), Balances::minimum_balance() + 18);
+			assert_eq!(Balances::free_balance(&30), 0);
+			assert_eq!(Balances::free_balance(&40), Balances::minimum_balance() + 2);
+		});
+	}
+
+	#[test]
+	fn rewards_distribution_is_fair_basic() {
+		ExtBuilder::default().build_and_execute_with_each_adapter(|| {
 			// reward pool by 10.
 			Balances::mutate_account(&default_reward_account(), |f| f.free += 10).unwrap();
 
 			// 20 joins afterwards.
 			Balances::make_free_balance_be(&20, Balances::minimum_balance() + 10);
-			assert_ok!(Pools::join(RuntimeOrigin::signed(20), 10, 1));
+			assert_ok!(Pools::join(RuntimeOrigin::signed(20), 10, 1, None));
 
 			// reward by another 20
 			Balances::mutate_account(&default_reward_account(), |f| f.free += 20).unwrap();
@@ -1167,8 +1412,10 @@ mod claim_payout {
 				pool_events_since_last_call(),
 				vec![
 					Event::Created { depositor: 10, pool_id: 1 },
-					Event::Bonded { member: 10, pool_id: 1, bonded: 10, joined: true },
-					Event::Bonded { member: 20, pool_id: 1, bonded: 10, joined: true },
+					Event::Bonded { member: 10, pool_id: 1, bonded: 10, points: 10, joined: true },
+					Event::PoolUpdated { pool_id: 1, points: 10, balance: 10 },
+					Event::Bonded { member: 20, pool_id: 1, bonded: 10, points: 10, joined: true },
+					Event::PoolUpdated { pool_id: 1, points: 20, balance: 20 },
 					Event::PaidOut { member: 10, pool_id: 1, payout: 20 },
 					Event::PaidOut { member: 20, pool_id: 1, payout: 10 },
 				]
@@ -1194,11 +1441,11 @@ mod claim_payout {
 	fn rewards_distribution_is_fair_basic_with_fractions() {
 		// basically checks the case where the amount of rewards is less than the pool shares. for
 		// this, we have to rely on fixed point arithmetic.
-		ExtBuilder::default().build_and_execute(|| {
+		ExtBuilder::default().build_and_execute_with_each_adapter(|| {
 			Balances::mutate_account(&default_reward_account(), |f| f.free += 3).unwrap();
 
 			Balances::make_free_balance_be(&20, Balances::minimum_balance() + 10);
-			assert_ok!(Pools::join(RuntimeOrigin::signed(20), 10, 1));
+			assert_ok!(Pools::join(RuntimeOrigin::signed(20), 10, 1, None));
 
 			Balances::mutate_account(&default_reward_account(), |f| f.free += 6).unwrap();
 
@@ -1210,8 +1457,10 @@ mod claim_payout {
 				pool_events_since_last_call(),
 				vec![
 					Event::Created { depositor: 10, pool_id: 1 },
-					Event::Bonded { member: 10, pool_id: 1, bonded: 10, joined: true },
-					Event::Bonded { member: 20, pool_id: 1, bonded: 10, joined: true },
+					Event::Bonded { member: 10, pool_id: 1, bonded: 10, points: 10, joined: true },
+					Event::PoolUpdated { pool_id: 1, points: 10, balance: 10 },
+					Event::Bonded { member: 20, pool_id: 1, bonded: 10, points: 10, joined: true },
+					Event::PoolUpdated { pool_id: 1, points: 20, balance: 20 },
 					Event::PaidOut { member: 10, pool_id: 1, payout: 3 + 3 },
 					Event::PaidOut { member: 20, pool_id: 1, payout: 3 },
 				]
@@ -1249,18 +1498,18 @@ mod claim_payout {
 
 	#[test]
 	fn rewards_distribution_is_fair_3() {
-		ExtBuilder::default().build_and_execute(|| {
+		ExtBuilder::default().build_and_execute_with_each_adapter(|| {
 			let ed = Balances::minimum_balance();
 
 			Balances::mutate_account(&default_reward_account(), |f| f.free += 30).unwrap();
 
 			Balances::make_free_balance_be(&20, ed + 10);
-			assert_ok!(Pools::join(RuntimeOrigin::signed(20), 10, 1));
+			assert_ok!(Pools::join(RuntimeOrigin::signed(20), 10, 1, None));
 
 			Balances::mutate_account(&default_reward_account(), |f| f.free += 100).unwrap();
 
 			Balances::make_free_balance_be(&30, ed + 10);
-			assert_ok!(Pools::join(RuntimeOrigin::signed(30), 10, 1));
+			assert_ok!(Pools::join(RuntimeOrigin::signed(30), 10, 1, None));
 
 			Balances::mutate_account(&default_reward_account(), |f| f.free += 60).unwrap();
 
@@ -1273,9 +1522,12 @@ mod claim_payout {
 				pool_events_since_last_call(),
 				vec![
 					Event::Created { depositor: 10, pool_id: 1 },
-					Event::Bonded { member: 10, pool_id: 1, bonded: 10, joined: true },
-					Event::Bonded { member: 20, pool_id: 1, bonded: 10, joined: true },
-					Event::Bonded { member: 30, pool_id: 1, bonded: 10, joined: true },
+					Event::Bonded { member: 10, pool_id: 1, bonded: 10, points: 10, joined: true },
+					Event::PoolUpdated { pool_id: 1, points: 10, balance: 10 },
+					Event::Bonded { member: 20, pool_id: 1, bonded: 10, points: 10, joined: true },
+					Event::PoolUpdated { pool_id: 1, points: 20, balance: 20 },
+					Event::Bonded { member: 30, pool_id: 1, bonded: 10, points: 10, joined: true },
+					Event::PoolUpdated { pool_id: 1, points: 30, balance: 30 },
 					Event::PaidOut { member: 10, pool_id: 1, payout: 30 + 100 / 2 + 60 / 3 },
 					Event::PaidOut { member: 20, pool_id: 1, payout: 100 / 2 + 60 / 3 },
 					Event::PaidOut { member: 30, pool_id: 1, payout: 60 / 3 },
@@ -1302,7 +1554,7 @@ mod claim_payout {
 
 	#[test]
 	fn pending_rewards_per_member_works() {
-		ExtBuilder::default().build_and_execute(|| {
+		ExtBuilder::default().build_and_execute_with_each_adapter(|| {
 			let ed = Balances::minimum_balance();
 
 			assert_eq!(Pools::pending_rewards(10), Some(0));
@@ -1311,7 +1563,7 @@ mod claim_payout {
 			assert_eq!(Pools::pending_rewards(20), None);
 
 			Balances::make_free_balance_be(&20, ed + 10);
-			assert_ok!(Pools::join(RuntimeOrigin::signed(20), 10, 1));
+			assert_ok!(Pools::join(RuntimeOrigin::signed(20), 10, 1, None));
 
 			assert_eq!(Pools::pending_rewards(10), Some(30));
 			assert_eq!(Pools::pending_rewards(20), Some(0));
@@ -1323,7 +1575,7 @@ mod claim_payout {
 			assert_eq!(Pools::pending_rewards(30), None);
 
 			Balances::make_free_balance_be(&30, ed + 10);
-			assert_ok!(Pools::join(RuntimeOrigin::signed(30), 10, 1));
+			assert_ok!(Pools::join(RuntimeOrigin::signed(30), 10, 1, None));
 
 			assert_eq!(Pools::pending_rewards(10), Some(30 + 50));
 			assert_eq!(Pools::pending_rewards(20), Some(50));
@@ -1355,13 +1607,13 @@ mod claim_payout {
 
 	#[test]
 	fn rewards_distribution_is_fair_bond_extra() {
-		ExtBuilder::default().build_and_execute(|| {
+		ExtBuilder::default().build_and_execute_with_each_adapter(|| {
 			let ed = Balances::minimum_balance();
 
 			Balances::make_free_balance_be(&20, ed + 20);
-			assert_ok!(Pools::join(RuntimeOrigin::signed(20), 20, 1));
+			assert_ok!(Pools::join(RuntimeOrigin::signed(20), 20, 1, None));
 			Balances::make_free_balance_be(&30, ed + 20);
-			assert_ok!(Pools::join(RuntimeOrigin::signed(30), 10, 1));
+			assert_ok!(Pools::join(RuntimeOrigin::signed(30), 10, 1, None));
 
 			Balances::mutate_account(&default_reward_account(), |f| f.free += 40).unwrap();
 
@@ -1374,9 +1626,12 @@ mod claim_payout {
 				pool_events_since_last_call(),
 				vec![
 					Event::Created { depositor: 10, pool_id: 1 },
-					Event::Bonded { member: 10, pool_id: 1, bonded: 10, joined: true },
-					Event::Bonded { member: 20, pool_id: 1, bonded: 20, joined: true },
-					Event::Bonded { member: 30, pool_id: 1, bonded: 10, joined: true },
+					Event::Bonded { member: 10, pool_id: 1, bonded: 10, points: 10, joined: true },
+					Event::PoolUpdated { pool_id: 1, points: 10, balance: 10 },
+					Event::Bonded { member: 20, pool_id: 1, bonded: 20, points: 20, joined: true },
+					Event::PoolUpdated { pool_id: 1, points: 30, balance: 30 },
+					Event::Bonded { member: 30, pool_id: 1, bonded: 10, points: 10, joined: true },
+					Event::PoolUpdated { pool_id: 1, points: 40, balance: 40 },
 					Event::PaidOut { member: 10, pool_id: 1, payout: 10 },
 					Event::PaidOut { member: 20, pool_id: 1, payout: 20 },
 					Event::PaidOut { member: 30, pool_id: 1, payout: 10 }
@@ -1396,7 +1651,8 @@ mod claim_payout {
 			assert_eq!(
 				pool_events_since_last_call(),
 				vec![
-					Event::Bonded { member: 30, pool_id: 1, bonded: 10, joined: false },
+					Event::Bonded { member: 30, pool_id: 1, bonded: 10, points: 10, joined: false },
+					Event::PoolUpdated { pool_id: 1, points: 50, balance: 50 },
 					Event::PaidOut { member: 10, pool_id: 1, payout: 20 },
 					Event::PaidOut { member: 20, pool_id: 1, payout: 40 },
 					Event::PaidOut { member: 30, pool_id: 1, payout: 40 }
@@ -1407,11 +1663,11 @@ mod claim_payout {
 
 	#[test]
 	fn rewards_distribution_is_fair_unbond() {
-		ExtBuilder::default().build_and_execute(|| {
+		ExtBuilder::default().build_and_execute_with_each_adapter(|| {
 			let ed = Balances::minimum_balance();
 
 			Balances::make_free_balance_be(&20, ed + 20);
-			assert_ok!(Pools::join(RuntimeOrigin::signed(20), 20, 1));
+			assert_ok!(Pools::join(RuntimeOrigin::signed(20), 20, 1, None));
 
 			Balances::mutate_account(&default_reward_account(), |f| f.free += 30).unwrap();
 
@@ -1423,8 +1679,10 @@ mod claim_payout {
 				pool_events_since_last_call(),
 				vec![
 					Event::Created { depositor: 10, pool_id: 1 },
-					Event::Bonded { member: 10, pool_id: 1, bonded: 10, joined: true },
-					Event::Bonded { member: 20, pool_id: 1, bonded: 20, joined: true },
+					Event::Bonded { member: 10, pool_id: 1, bonded: 10, points: 10, joined: true },
+					Event::PoolUpdated { pool_id: 1, points: 10, balance: 10 },
+					Event::Bonded { member: 20, pool_id: 1, bonded: 20, points: 20, joined: true },
+					Event::PoolUpdated { pool_id: 1, points: 30, balance: 30 },
 					Event::PaidOut { member: 10, pool_id: 1, payout: 10 },
 					Event::PaidOut { member: 20, pool_id: 1, payout: 20 }
 				]
@@ -1443,6 +1701,7 @@ mod claim_payout {
 				pool_events_since_last_call(),
 				vec![
 					Event::Unbonded { member: 20, pool_id: 1, balance: 10, points: 10, era: 3 },
+					Event::PoolUpdated { pool_id: 1, points: 20, balance: 20 },
 					Event::PaidOut { member: 10, pool_id: 1, payout: 50 },
 					Event::PaidOut { member: 20, pool_id: 1, payout: 50 },
 				]
@@ -1452,13 +1711,13 @@ mod claim_payout {
 
 	#[test]
 	fn unclaimed_reward_is_safe() {
-		ExtBuilder::default().build_and_execute(|| {
+		ExtBuilder::default().build_and_execute_with_each_adapter(|| {
 			let ed = Balances::minimum_balance();
 
 			Balances::make_free_balance_be(&20, ed + 20);
-			assert_ok!(Pools::join(RuntimeOrigin::signed(20), 20, 1));
+			assert_ok!(Pools::join(RuntimeOrigin::signed(20), 20, 1, None));
 			Balances::make_free_balance_be(&30, ed + 20);
-			assert_ok!(Pools::join(RuntimeOrigin::signed(30), 10, 1));
+			assert_ok!(Pools::join(RuntimeOrigin::signed(30), 10, 1, None));
 
 			// 10 gets 10, 20 gets 20, 30 gets 10
 			Balances::mutate_account(&default_reward_account(), |f| f.free += 40).unwrap();
@@ -1471,9 +1730,12 @@ mod claim_payout {
 				pool_events_since_last_call(),
 				vec![
 					Event::Created { depositor: 10, pool_id: 1 },
-					Event::Bonded { member: 10, pool_id: 1, bonded: 10, joined: true },
-					Event::Bonded { member: 20, pool_id: 1, bonded: 20, joined: true },
-					Event::Bonded { member: 30, pool_id: 1, bonded: 10, joined: true },
+					Event::Bonded { member: 10, pool_id: 1, bonded: 10, points: 10, joined: true },
+					Event::PoolUpdated { pool_id: 1, points: 10, balance: 10 },
+					Event::Bonded { member: 20, pool_id: 1, bonded: 20, points: 20, joined: true },
+					Event::PoolUpdated { pool_id: 1, points: 30, balance: 30 },
+					Event::Bonded { member: 30, pool_id: 1, bonded: 10, points: 10, joined: true },
+					Event::PoolUpdated { pool_id: 1, points: 40, balance: 40 },
 					Event::PaidOut { member: 10, pool_id: 1, payout: 10 },
 					Event::PaidOut { member: 20, pool_id: 1, payout: 20 }
 				]
@@ -1521,11 +1783,11 @@ mod claim_payout {
 
 	#[test]
 	fn bond_extra_and_delayed_claim() {
-		ExtBuilder::default().build_and_execute(|| {
+		ExtBuilder::default().build_and_execute_with_each_adapter(|| {
 			let ed = Balances::minimum_balance();
 
 			Balances::make_free_balance_be(&20, ed + 200);
-			assert_ok!(Pools::join(RuntimeOrigin::signed(20), 20, 1));
+			assert_ok!(Pools::join(RuntimeOrigin::signed(20), 20, 1, None));
 
 			// 10 gets 10, 20 gets 20, 30 gets 10
 			Balances::mutate_account(&default_reward_account(), |f| f.free += 30).unwrap();
@@ -1537,8 +1799,10 @@ mod claim_payout {
 				pool_events_since_last_call(),
 				vec![
 					Event::Created { depositor: 10, pool_id: 1 },
-					Event::Bonded { member: 10, pool_id: 1, bonded: 10, joined: true },
-					Event::Bonded { member: 20, pool_id: 1, bonded: 20, joined: true },
+					Event::Bonded { member: 10, pool_id: 1, bonded: 10, points: 10, joined: true },
+					Event::PoolUpdated { pool_id: 1, points: 10, balance: 10 },
+					Event::Bonded { member: 20, pool_id: 1, bonded: 20, points: 20, joined: true },
+					Event::PoolUpdated { pool_id: 1, points: 30, balance: 30 },
 					Event::PaidOut { member: 10, pool_id: 1, payout: 10 }
 				]
 			);
@@ -1558,7 +1822,8 @@ mod claim_payout {
 				vec![
 					// 20 + 40, which means the extra amount they bonded did not impact us.
 					Event::PaidOut { member: 20, pool_id: 1, payout: 60 },
-					Event::Bonded { member: 20, pool_id: 1, bonded: 10, joined: false },
+					Event::Bonded { member: 20, pool_id: 1, bonded: 10, points: 10, joined: false },
+					Event::PoolUpdated { pool_id: 1, points: 40, balance: 40 },
 					Event::PaidOut { member: 10, pool_id: 1, payout: 20 }
 				]
 			);
@@ -1582,7 +1847,7 @@ mod claim_payout {
 
 	#[test]
 	fn create_sets_recorded_data() {
-		ExtBuilder::default().build_and_execute(|| {
+		ExtBuilder::default().build_and_execute_with_each_adapter(|| {
 			MaxPools::<Runtime>::set(None);
 			// pool 10 has already been created.
 			let (member_10, _, reward_pool_10) = Pools::get_member_with_pools(&10).unwrap();
@@ -1636,11 +1901,14 @@ mod claim_payout {
 				pool_events_since_last_call(),
 				vec![
 					Event::Created { depositor: 10, pool_id: 1 },
-					Event::Bonded { member: 10, pool_id: 1, bonded: 10, joined: true },
+					Event::Bonded { member: 10, pool_id: 1, bonded: 10, points: 10, joined: true },
+					Event::PoolUpdated { pool_id: 1, points: 10, balance: 10 },
 					Event::Created { depositor: 20, pool_id: 2 },
-					Event::Bonded { member: 20, pool_id: 2, bonded: 10, joined: true },
+					Event::Bonded { member: 20, pool_id: 2, bonded: 10, points: 10, joined: true },
+					Event::PoolUpdated { pool_id: 2, points: 10, balance: 10 },
 					Event::Created { depositor: 30, pool_id: 3 },
-					Event::Bonded { member: 30, pool_id: 3, bonded: 10, joined: true },
+					Event::Bonded { member: 30, pool_id: 3, bonded: 10, points: 10, joined: true },
+					Event::PoolUpdated { pool_id: 3, points: 10, balance: 10 },
 					Event::PaidOut { member: 30, pool_id: 3, payout: 10 }
 				]
 			);
@@ -1649,12 +1917,12 @@ mod claim_payout {
 
 	#[test]
 	fn join_updates_recorded_data() {
-		ExtBuilder::default().build_and_execute(|| {
+		ExtBuilder::default().build_and_execute_with_each_adapter(|| {
 			MaxPoolMembers::<Runtime>::set(None);
 			MaxPoolMembersPerPool::<Runtime>::set(None);
 			let join = |x, y| {
 				Balances::make_free_balance_be(&x, y + Balances::minimum_balance());
-				assert_ok!(Pools::join(RuntimeOrigin::signed(x), y, 1));
+				assert_ok!(Pools::join(RuntimeOrigin::signed(x), y, 1, None));
 			};
 
 			{
@@ -1705,10 +1973,14 @@ mod claim_payout {
 				pool_events_since_last_call(),
 				vec![
 					Event::Created { depositor: 10, pool_id: 1 },
-					Event::Bonded { member: 10, pool_id: 1, bonded: 10, joined: true },
-					Event::Bonded { member: 20, pool_id: 1, bonded: 10, joined: true },
-					Event::Bonded { member: 30, pool_id: 1, bonded: 10, joined: true },
-					Event::Bonded { member: 40, pool_id: 1, bonded: 10, joined: true }
+					Event::Bonded { member: 10, pool_id: 1, bonded: 10, points: 10, joined: true },
+					Event::PoolUpdated { pool_id: 1, points: 10, balance: 10 },
+					Event::Bonded { member: 20, pool_id: 1, bonded: 10, points: 10, joined: true },
+					Event::PoolUpdated { pool_id: 1, points: 20, balance: 20 },
+					Event::Bonded { member: 30, pool_id: 1, bonded: 10, points: 10, joined: true },
+					Event::PoolUpdated { pool_id: 1, points: 30, balance: 30 },
+					Event::Bonded { member: 40, pool_id: 1, bonded: 10, points: 10, joined: true },
+					Event::PoolUpdated { pool_id: 1, points: 40, balance: 40 }
 				]
 			);
 		})
@@ -1716,140 +1988,192 @@ mod claim_payout {
 
 	#[test]
 	fn bond_extra_updates_recorded_data() {
-		ExtBuilder::default().add_members(vec![(20, 20)]).build_and_execute(|| {
-			MaxPoolMembers::<Runtime>::set(None);
-			MaxPoolMembersPerPool::<Runtime>::set(None);
-
-			// initial state of pool 1.
-			{
-				let (member_10, _, reward_pool_10) = Pools::get_member_with_pools(&10).unwrap();
-
-				assert_eq!(reward_pool_10.last_recorded_total_payouts, 0);
-				assert_eq!(reward_pool_10.total_rewards_claimed, 0);
-				assert_eq!(reward_pool_10.last_recorded_reward_counter, 0.into());
-
-				assert_eq!(member_10.last_recorded_reward_counter, 0.into());
-			}
+		ExtBuilder::default()
+			.add_members(vec![(20, 20)])
+			.build_and_execute_with_each_adapter(|| {
+				MaxPoolMembers::<Runtime>::set(None);
+				MaxPoolMembersPerPool::<Runtime>::set(None);
 
-			Balances::make_free_balance_be(&10, 100);
-			Balances::make_free_balance_be(&20, 100);
+				// initial state of pool 1.
+				{
+					let (member_10, _, reward_pool_10) = Pools::get_member_with_pools(&10).unwrap();
 
-			// 10 bonds extra without any rewards.
-			{
-				assert_ok!(Pools::bond_extra(
-					RuntimeOrigin::signed(10),
-					BondExtra::FreeBalance(10)
-				));
-				let (member, _, reward_pool) = Pools::get_member_with_pools(&10).unwrap();
-				assert_eq!(member.last_recorded_reward_counter, 0.into());
-				assert_eq!(reward_pool.last_recorded_total_payouts, 0);
-				assert_eq!(reward_pool.last_recorded_reward_counter, 0.into());
-			}
+					assert_eq!(reward_pool_10.last_recorded_total_payouts, 0);
+					assert_eq!(reward_pool_10.total_rewards_claimed, 0);
+					assert_eq!(reward_pool_10.last_recorded_reward_counter, 0.into());
 
-			// 10 bonds extra again with some rewards. This reward should be split equally between
-			// 10 and 20, as they both have equal points now.
-			Balances::mutate_account(&default_reward_account(), |f| f.free += 30).unwrap();
+					assert_eq!(member_10.last_recorded_reward_counter, 0.into());
+				}
 
-			{
-				assert_ok!(Pools::bond_extra(
-					RuntimeOrigin::signed(10),
-					BondExtra::FreeBalance(10)
-				));
-				let (member, _, reward_pool) = Pools::get_member_with_pools(&10).unwrap();
-				// explanation: before bond_extra takes place, there is 40 points and 30 balance in
-				// the system, RewardCounter is therefore 7.5
-				assert_eq!(member.last_recorded_reward_counter, RewardCounter::from_float(0.75));
-				assert_eq!(
-					reward_pool.last_recorded_reward_counter,
-					RewardCounter::from_float(0.75)
-				);
-				assert_eq!(reward_pool.last_recorded_total_payouts, 30);
-			}
+				Balances::make_free_balance_be(&10, 100);
+				Balances::make_free_balance_be(&20, 100);
 
-			// 20 bonds extra again, without further rewards.
-			{
-				assert_ok!(Pools::bond_extra(
-					RuntimeOrigin::signed(20),
-					BondExtra::FreeBalance(10)
-				));
-				let (member, _, reward_pool) = Pools::get_member_with_pools(&20).unwrap();
-				assert_eq!(member.last_recorded_reward_counter, RewardCounter::from_float(0.75));
-				assert_eq!(
-					reward_pool.last_recorded_reward_counter,
-					RewardCounter::from_float(0.75)
-				);
-				assert_eq!(reward_pool.last_recorded_total_payouts, 30);
-			}
+				// 10 bonds extra without any rewards.
+				{
+					assert_ok!(Pools::bond_extra(
+						RuntimeOrigin::signed(10),
+						BondExtra::FreeBalance(10)
+					));
+					let (member, _, reward_pool) = Pools::get_member_with_pools(&10).unwrap();
+					assert_eq!(member.last_recorded_reward_counter, 0.into());
+					assert_eq!(reward_pool.last_recorded_total_payouts, 0);
+					assert_eq!(reward_pool.last_recorded_reward_counter, 0.into());
+				}
 
-			assert_eq!(
-				pool_events_since_last_call(),
-				vec![
-					Event::Created { depositor: 10, pool_id: 1 },
-					Event::Bonded { member: 10, pool_id: 1, bonded: 10, joined: true },
-					Event::Bonded { member: 20, pool_id: 1, bonded: 20, joined: true },
-					Event::Bonded { member: 10, pool_id: 1, bonded: 10, joined: false },
-					Event::PaidOut { member: 10, pool_id: 1, payout: 15 },
-					Event::Bonded { member: 10, pool_id: 1, bonded: 10, joined: false },
-					Event::PaidOut { member: 20, pool_id: 1, payout: 15 },
-					Event::Bonded { member: 20, pool_id: 1, bonded: 10, joined: false }
-				]
-			);
-		})
-	}
+				// 10 bonds extra again with some rewards. This reward should be split equally
+				// between 10 and 20, as they both have equal points now.
+				Balances::mutate_account(&default_reward_account(), |f| f.free += 30).unwrap();
 
-	#[test]
-	fn bond_extra_pending_rewards_works() {
-		ExtBuilder::default().add_members(vec![(20, 20)]).build_and_execute(|| {
-			MaxPoolMembers::<Runtime>::set(None);
-			MaxPoolMembersPerPool::<Runtime>::set(None);
+				{
+					assert_ok!(Pools::bond_extra(
+						RuntimeOrigin::signed(10),
+						BondExtra::FreeBalance(10)
+					));
+					let (member, _, reward_pool) = Pools::get_member_with_pools(&10).unwrap();
+					// explanation: before bond_extra takes place, there is 40 points and 30 balance
+					// in the system, RewardCounter is therefore 7.5
+					assert_eq!(
+						member.last_recorded_reward_counter,
+						RewardCounter::from_rational(3, 4)
+					);
+					assert_eq!(
+						reward_pool.last_recorded_reward_counter,
+						RewardCounter::from_rational(3, 4)
+					);
+					assert_eq!(reward_pool.last_recorded_total_payouts, 30);
+				}
 
-			// pool receives some rewards.
-			Balances::mutate_account(&default_reward_account(), |f| f.free += 30).unwrap();
-			System::reset_events();
-
-			// 10 cashes it out, and bonds it.
-			{
-				assert_ok!(Pools::claim_payout(RuntimeOrigin::signed(10)));
-				let (member, _, reward_pool) = Pools::get_member_with_pools(&10).unwrap();
-				// there is 30 points and 30 reward points in the system RC is 1.
-				assert_eq!(member.last_recorded_reward_counter, 1.into());
-				assert_eq!(reward_pool.total_rewards_claimed, 10);
-				// these two are not updated -- only updated when the points change.
-				assert_eq!(reward_pool.last_recorded_total_payouts, 0);
-				assert_eq!(reward_pool.last_recorded_reward_counter, 0.into());
-
-				assert_eq!(
-					pool_events_since_last_call(),
-					vec![Event::PaidOut { member: 10, pool_id: 1, payout: 10 }]
-				);
-			}
-
-			// 20 re-bonds it.
-			{
-				assert_ok!(Pools::bond_extra(RuntimeOrigin::signed(20), BondExtra::Rewards));
-				let (member, _, reward_pool) = Pools::get_member_with_pools(&10).unwrap();
-				assert_eq!(member.last_recorded_reward_counter, 1.into());
-				assert_eq!(reward_pool.total_rewards_claimed, 30);
-				// since points change, these two are updated.
-				assert_eq!(reward_pool.last_recorded_total_payouts, 30);
-				assert_eq!(reward_pool.last_recorded_reward_counter, 1.into());
+				// 20 bonds extra again, without further rewards.
+				{
+					assert_ok!(Pools::bond_extra(
+						RuntimeOrigin::signed(20),
+						BondExtra::FreeBalance(10)
+					));
+					let (member, _, reward_pool) = Pools::get_member_with_pools(&20).unwrap();
+					assert_eq!(
+						member.last_recorded_reward_counter,
+						RewardCounter::from_rational(3, 4)
+					);
+					assert_eq!(
+						reward_pool.last_recorded_reward_counter,
+						RewardCounter::from_rational(3, 4)
+					);
+					assert_eq!(reward_pool.last_recorded_total_payouts, 30);
+				}
 
 				assert_eq!(
 					pool_events_since_last_call(),
 					vec![
-						Event::PaidOut { member: 20, pool_id: 1, payout: 20 },
-						Event::Bonded { member: 20, pool_id: 1, bonded: 20, joined: false }
+						Event::Created { depositor: 10, pool_id: 1 },
+						Event::Bonded {
+							member: 10,
+							pool_id: 1,
+							bonded: 10,
+							points: 10,
+							joined: true
+						},
+						Event::PoolUpdated { pool_id: 1, points: 10, balance: 10 },
+						Event::Bonded {
+							member: 20,
+							pool_id: 1,
+							bonded: 20,
+							points: 20,
+							joined: true
+						},
+						Event::PoolUpdated { pool_id: 1, points: 30, balance: 30 },
+						Event::Bonded {
+							member: 10,
+							pool_id: 1,
+							bonded: 10,
+							points: 10,
+							joined: false
+						},
+						Event::PoolUpdated { pool_id: 1, points: 40, balance: 40 },
+						Event::PaidOut { member: 10, pool_id: 1, payout: 15 },
+						Event::Bonded {
+							member: 10,
+							pool_id: 1,
+							bonded: 10,
+							points: 10,
+							joined: false
+						},
+						Event::PoolUpdated { pool_id: 1, points: 50, balance: 50 },
+						Event::PaidOut { member: 20, pool_id: 1, payout: 15 },
+						Event::Bonded {
+							member: 20,
+							pool_id: 1,
+							bonded: 10,
+							points: 10,
+							joined: false
+						},
+						Event::PoolUpdated { pool_id: 1, points: 60, balance: 60 }
 					]
 				);
-			}
-		})
+			})
+	}
+
+	#[test]
+	fn bond_extra_pending_rewards_works() {
+		ExtBuilder::default()
+			.add_members(vec![(20, 20)])
+			.build_and_execute_with_each_adapter(|| {
+				MaxPoolMembers::<Runtime>::set(None);
+				MaxPoolMembersPerPool::<Runtime>::set(None);
+
+				// pool receives some rewards.
+				Balances::mutate_account(&default_reward_account(), |f| f.free += 30).unwrap();
+				System::reset_events();
+
+				// 10 cashes it out, and bonds it.
+				{
+					assert_ok!(Pools::claim_payout(RuntimeOrigin::signed(10)));
+					let (member, _, reward_pool) = Pools::get_member_with_pools(&10).unwrap();
+					// there is 30 points and 30 reward points in the system RC is 1.
+					assert_eq!(member.last_recorded_reward_counter, 1.into());
+					assert_eq!(reward_pool.total_rewards_claimed, 10);
+					// these two are not updated -- only updated when the points change.
+					assert_eq!(reward_pool.last_recorded_total_payouts, 0);
+					assert_eq!(reward_pool.last_recorded_reward_counter, 0.into());
+
+					assert_eq!(
+						pool_events_since_last_call(),
+						vec![Event::PaidOut { member: 10, pool_id: 1, payout: 10 }]
+					);
+				}
+
+				// 20 re-bonds it.
+				{
+					assert_ok!(Pools::bond_extra(RuntimeOrigin::signed(20), BondExtra::Rewards));
+					let (member, _, reward_pool) = Pools::get_member_with_pools(&10).unwrap();
+					assert_eq!(member.last_recorded_reward_counter, 1.into());
+					assert_eq!(reward_pool.total_rewards_claimed, 30);
+					// since points change, these two are updated.
+					assert_eq!(reward_pool.last_recorded_total_payouts, 30);
+					assert_eq!(reward_pool.last_recorded_reward_counter, 1.into());
+
+					assert_eq!(
+						pool_events_since_last_call(),
+						vec![
+							Event::PaidOut { member: 20, pool_id: 1, payout: 20 },
+							Event::Bonded {
+								member: 20,
+								pool_id: 1,
+								bonded: 20,
+								points: 20,
+								joined: false
+							},
+							Event::PoolUpdated { pool_id: 1, points: 50, balance: 50 }
+						]
+					);
+				}
+			})
 	}
 
 	#[test]
 	fn unbond_updates_recorded_data() {
 		ExtBuilder::default()
 			.add_members(vec![(20, 20), (30, 20)])
-			.build_and_execute(|| {
+			.build_and_execute_with_each_adapter(|| {
 				MaxPoolMembers::<Runtime>::set(None);
 				MaxPoolMembersPerPool::<Runtime>::set(None);
 
@@ -1884,12 +2208,12 @@ mod claim_payout {
 					// RewardCounter is 3/4.
 					assert_eq!(
 						member.last_recorded_reward_counter,
-						RewardCounter::from_float(0.75)
+						RewardCounter::from_rational(3, 4)
 					);
 					assert_eq!(reward_pool.last_recorded_total_payouts, 30);
 					assert_eq!(
 						reward_pool.last_recorded_reward_counter,
-						RewardCounter::from_float(0.75)
+						RewardCounter::from_rational(3, 4)
 					);
 				}
 
@@ -1899,12 +2223,12 @@ mod claim_payout {
 					let (member, _, reward_pool) = Pools::get_member_with_pools(&30).unwrap();
 					assert_eq!(
 						member.last_recorded_reward_counter,
-						RewardCounter::from_float(0.75)
+						RewardCounter::from_rational(3, 4)
 					);
 					assert_eq!(reward_pool.last_recorded_total_payouts, 30);
 					assert_eq!(
 						reward_pool.last_recorded_reward_counter,
-						RewardCounter::from_float(0.75)
+						RewardCounter::from_rational(3, 4)
 					);
 				}
 
@@ -1914,12 +2238,12 @@ mod claim_payout {
 					let (member, _, reward_pool) = Pools::get_member_with_pools(&20).unwrap();
 					assert_eq!(
 						member.last_recorded_reward_counter,
-						RewardCounter::from_float(0.75)
+						RewardCounter::from_rational(3, 4)
 					);
 					assert_eq!(reward_pool.last_recorded_total_payouts, 30);
 					assert_eq!(
 						reward_pool.last_recorded_reward_counter,
-						RewardCounter::from_float(0.75)
+						RewardCounter::from_rational(3, 4)
 					);
 				}
 
@@ -1930,15 +2254,40 @@ mod claim_payout {
 					pool_events_since_last_call(),
 					vec![
 						Event::Created { depositor: 10, pool_id: 1 },
-						Event::Bonded { member: 10, pool_id: 1, bonded: 10, joined: true },
-						Event::Bonded { member: 20, pool_id: 1, bonded: 20, joined: true },
-						Event::Bonded { member: 30, pool_id: 1, bonded: 20, joined: true },
+						Event::Bonded {
+							member: 10,
+							pool_id: 1,
+							bonded: 10,
+							points: 10,
+							joined: true
+						},
+						Event::PoolUpdated { pool_id: 1, points: 10, balance: 10 },
+						Event::Bonded {
+							member: 20,
+							pool_id: 1,
+							bonded: 20,
+							points: 20,
+							joined: true
+						},
+						Event::PoolUpdated { pool_id: 1, points: 30, balance: 30 },
+						Event::Bonded {
+							member: 30,
+							pool_id: 1,
+							bonded: 20,
+							points: 20,
+							joined: true
+						},
+						Event::PoolUpdated { pool_id: 1, points: 50, balance: 50 },
 						Event::Unbonded { member: 20, pool_id: 1, balance: 10, points: 10, era: 3 },
+						Event::PoolUpdated { pool_id: 1, points: 40, balance: 40 },
 						Event::PaidOut { member: 30, pool_id: 1, payout: 15 },
 						Event::Unbonded { member: 30, pool_id: 1, balance: 10, points: 10, era: 3 },
+						Event::PoolUpdated { pool_id: 1, points: 30, balance: 30 },
 						Event::Unbonded { member: 30, pool_id: 1, balance: 5, points: 5, era: 3 },
+						Event::PoolUpdated { pool_id: 1, points: 25, balance: 25 },
 						Event::PaidOut { member: 20, pool_id: 1, payout: 7 },
 						Event::Unbonded { member: 20, pool_id: 1, balance: 5, points: 5, era: 3 },
+						Event::PoolUpdated { pool_id: 1, points: 20, balance: 20 },
 						Event::PaidOut { member: 10, pool_id: 1, payout: 7 }
 					]
 				);
@@ -1947,63 +2296,85 @@ mod claim_payout {
 
 	#[test]
 	fn rewards_are_rounded_down_depositor_collects_them() {
-		ExtBuilder::default().add_members(vec![(20, 20)]).build_and_execute(|| {
-			// initial balance of 10.
+		ExtBuilder::default()
+			.add_members(vec![(20, 20)])
+			.build_and_execute_with_each_adapter(|| {
+				// initial balance of 10.
 
-			assert_eq!(Balances::free_balance(&10), 35);
-			assert_eq!(
-				Balances::free_balance(&default_reward_account()),
-				Balances::minimum_balance()
-			);
+				assert_eq!(Balances::free_balance(&10), 35);
+				assert_eq!(
+					Balances::free_balance(&default_reward_account()),
+					Balances::minimum_balance()
+				);
 
-			// some rewards come in.
-			Balances::mutate_account(&default_reward_account(), |f| f.free += 40).unwrap();
+				// some rewards come in.
+				Balances::mutate_account(&default_reward_account(), |f| f.free += 40).unwrap();
 
-			// everyone claims
-			assert_ok!(Pools::claim_payout(RuntimeOrigin::signed(10)));
-			assert_ok!(Pools::claim_payout(RuntimeOrigin::signed(20)));
+				// everyone claims
+				assert_ok!(Pools::claim_payout(RuntimeOrigin::signed(10)));
+				assert_ok!(Pools::claim_payout(RuntimeOrigin::signed(20)));
 
-			// some dust (1) remains in the reward account.
-			assert_eq!(
-				pool_events_since_last_call(),
-				vec![
-					Event::Created { depositor: 10, pool_id: 1 },
-					Event::Bonded { member: 10, pool_id: 1, bonded: 10, joined: true },
-					Event::Bonded { member: 20, pool_id: 1, bonded: 20, joined: true },
-					Event::PaidOut { member: 10, pool_id: 1, payout: 13 },
-					Event::PaidOut { member: 20, pool_id: 1, payout: 26 }
-				]
-			);
+				// some dust (1) remains in the reward account.
+				assert_eq!(
+					pool_events_since_last_call(),
+					vec![
+						Event::Created { depositor: 10, pool_id: 1 },
+						Event::Bonded {
+							member: 10,
+							pool_id: 1,
+							bonded: 10,
+							points: 10,
+							joined: true
+						},
+						Event::PoolUpdated { pool_id: 1, points: 10, balance: 10 },
+						Event::Bonded {
+							member: 20,
+							pool_id: 1,
+							bonded: 20,
+							points: 20,
+							joined: true
+						},
+						Event::PoolUpdated { pool_id: 1, points: 30, balance: 30 },
+						Event::PaidOut { member: 10, pool_id: 1, payout: 13 },
+						Event::PaidOut { member: 20, pool_id: 1, payout: 26 }
+					]
+				);
 
-			// start dismantling the pool.
-			assert_ok!(Pools::set_state(RuntimeOrigin::signed(902), 1, PoolState::Destroying));
-			assert_ok!(fully_unbond_permissioned(20));
+				// start dismantling the pool.
+				assert_ok!(Pools::set_state(RuntimeOrigin::signed(902), 1, PoolState::Destroying));
+				assert_ok!(fully_unbond_permissioned(20));
 
-			CurrentEra::set(3);
-			assert_ok!(Pools::withdraw_unbonded(RuntimeOrigin::signed(20), 20, 0));
-			assert_ok!(fully_unbond_permissioned(10));
+				CurrentEra::set(3);
+				assert_ok!(Pools::withdraw_unbonded(RuntimeOrigin::signed(20), 20, 0));
+				assert_ok!(fully_unbond_permissioned(10));
 
-			CurrentEra::set(6);
-			assert_ok!(Pools::withdraw_unbonded(RuntimeOrigin::signed(10), 10, 0));
+				CurrentEra::set(6);
+				assert_ok!(Pools::withdraw_unbonded(RuntimeOrigin::signed(10), 10, 0));
 
-			assert_eq!(
-				pool_events_since_last_call(),
-				vec![
-					Event::StateChanged { pool_id: 1, new_state: PoolState::Destroying },
-					Event::Unbonded { member: 20, pool_id: 1, balance: 20, points: 20, era: 3 },
-					Event::Withdrawn { member: 20, pool_id: 1, balance: 20, points: 20 },
-					Event::MemberRemoved { pool_id: 1, member: 20 },
-					Event::Unbonded { member: 10, pool_id: 1, balance: 10, points: 10, era: 6 },
-					Event::Withdrawn { member: 10, pool_id: 1, balance: 10, points: 10 },
-					Event::MemberRemoved { pool_id: 1, member: 10 },
-					Event::Destroyed { pool_id: 1 }
-				]
-			);
+				assert_eq!(
+					pool_events_since_last_call(),
+					vec![
+						Event::StateChanged {
+							pool_id: 1,
+							new_state: PoolState::Destroying,
+							caller: 902
+						},
+						Event::Unbonded { member: 20, pool_id: 1, balance: 20, points: 20, era: 3 },
+						Event::PoolUpdated { pool_id: 1, points: 10, balance: 10 },
+						Event::Withdrawn { member: 20, pool_id: 1, balance: 20, points: 20 },
+						Event::MemberRemoved { pool_id: 1, member: 20 },
+						Event::Unbonded { member: 10, pool_id: 1, balance: 10, points: 10, era: 6 },
+						Event::PoolUpdated { pool_id: 1, points: 0, balance: 0 },
+						Event::Withdrawn { member: 10, pool_id: 1, balance: 10, points: 10 },
+						Event::MemberRemoved { pool_id: 1, member: 10 },
+						Event::Destroyed { pool_id: 1 }
+					]
+				);
 
-			assert!(!Metadata::<T>::contains_key(1));
-			// original ed + ed put into reward account + reward + bond + dust.
-			assert_eq!(Balances::free_balance(&10), 35 + 5 + 13 + 10 + 1);
-		})
+				assert!(!Metadata::<T>::contains_key(1));
+				// original ed + ed put into reward account + reward + bond + dust.
+				assert_eq!(Balances::free_balance(&10), 35 + 5 + 13 + 10 + 1);
+			})
 	}
 
 	#[test]
@@ -2016,7 +2387,7 @@ mod claim_payout {
 			.max_members(Some(4))
 			.max_members_per_pool(Some(4))
 			.add_members(vec![(20, 1500 * unit), (21, 2500 * unit), (22, 5000 * unit)])
-			.build_and_execute(|| {
+			.build_and_execute_with_each_adapter(|| {
 				// some rewards come in.
 				assert_eq!(Balances::free_balance(&default_reward_account()), unit);
 				Balances::mutate_account(&default_reward_account(), |f| f.free += unit / 1000)
@@ -2036,26 +2407,50 @@ mod claim_payout {
 							member: 10,
 							pool_id: 1,
 							bonded: 1000000000000000,
+							points: 1000000000000000,
 							joined: true
 						},
+						Event::PoolUpdated {
+							pool_id: 1,
+							points: 1000000000000000,
+							balance: 1000000000000000,
+						},
 						Event::Bonded {
 							member: 20,
 							pool_id: 1,
 							bonded: 1500000000000000,
+							points: 1500000000000000,
 							joined: true
 						},
+						Event::PoolUpdated {
+							pool_id: 1,
+							points: 2500000000000000,
+							balance: 2500000000000000,
+						},
 						Event::Bonded {
 							member: 21,
 							pool_id: 1,
 							bonded: 2500000000000000,
+							points: 2500000000000000,
 							joined: true
 						},
+						Event::PoolUpdated {
+							pool_id: 1,
+							points: 5000000000000000,
+							balance: 5000000000000000,
+						},
 						Event::Bonded {
 							member: 22,
 							pool_id: 1,
 							bonded: 5000000000000000,
+							points: 5000000000000000,
 							joined: true
 						},
+						Event::PoolUpdated {
+							pool_id: 1,
+							points: 10000000000000000,
+							balance: 10000000000000000,
+						},
 						Event::PaidOut { member: 10, pool_id: 1, payout: 100000000 },
 						Event::PaidOut { member: 20, pool_id: 1, payout: 150000000 },
 						Event::PaidOut { member: 21, pool_id: 1, payout: 250000000 },
@@ -2343,6 +2738,93 @@ mod unbond {
 		})
 	}
 
+	#[test]
+	fn depositor_unbond_open_with_enough_members() {
+		// depositor in pool, pool state open, `MinMembersForDepositorUnbond` other members
+		//   - depositor can unbond to the floor, below `depositor_min_bond`.
+		//   - depositor cannot unbond to below the floor or 0.
+		ExtBuilder::default()
+			.min_join_bond(4)
+			.add_members(vec![(20, 20), (30, 20)])
+			.build_and_execute(|| {
+				assert_eq!(MinMembersForDepositorUnbond::get(), 2);
+				assert_eq!(Pools::depositor_min_bond(), 10);
+				assert_eq!(Pools::depositor_unbond_floor(), 4);
+
+				// can unbond to exactly the floor.
+				assert_ok!(Pools::unbond(RuntimeOrigin::signed(10), 10, 6));
+				assert_eq!(PoolMembers::<Runtime>::get(10).unwrap().active_points(), 4);
+				assert_eq!(PoolMembers::<Runtime>::get(10).unwrap().unbonding_points(), 6);
+
+				// but not below it.
+				assert_noop!(
+					Pools::unbond(RuntimeOrigin::signed(10), 10, 1),
+					Error::<T>::DepositorBondBelowFloor
+				);
+
+				// and certainly not zero.
+				assert_noop!(
+					Pools::unbond(RuntimeOrigin::signed(10), 10, 4),
+					Error::<T>::MinimumBondNotMet
+				);
+			})
+	}
+
+	#[test]
+	fn depositor_unbond_to_floor_requires_open_pool_with_enough_members() {
+		ExtBuilder::default().add_members(vec![(20, 20)]).build_and_execute(|| {
+			assert_eq!(Pools::depositor_unbond_floor(), 2);
+
+			// a single other member is not enough.
+			assert_noop!(
+				Pools::unbond(RuntimeOrigin::signed(10), 10, 8),
+				Error::<T>::MinimumBondNotMet
+			);
+
+			// two are.
+			Balances::make_free_balance_be(&30, ExistentialDeposit::get() + 20);
+			assert_ok!(Pools::join(RuntimeOrigin::signed(30), 20, 1, None));
+			assert_ok!(Pools::unbond(RuntimeOrigin::signed(10), 10, 7));
+			assert_eq!(PoolMembers::<Runtime>::get(10).unwrap().active_points(), 3);
+
+			// but only as long as the pool is open.
+			unsafe_set_state(1, PoolState::Blocked);
+			assert_noop!(
+				Pools::unbond(RuntimeOrigin::signed(10), 10, 1),
+				Error::<T>::MinimumBondNotMet
+			);
+		})
+	}
+
+	#[test]
+	fn depositor_unbonded_to_floor_can_leave_destroying_pool() {
+		ExtBuilder::default()
+			.add_members(vec![(20, 20), (30, 20)])
+			.build_and_execute(|| {
+				// the depositor reduces their bond while the pool is open.
+				assert_ok!(Pools::unbond(RuntimeOrigin::signed(10), 10, 8));
+				assert_eq!(PoolMembers::<Runtime>::get(10).unwrap().active_points(), 2);
+
+				// once destroying, they still cannot leave before everyone else.
+				unsafe_set_state(1, PoolState::Destroying);
+				assert_noop!(fully_unbond_permissioned(10), Error::<T>::MinimumBondNotMet);
+
+				// everyone else leaves.
+				assert_ok!(Pools::fully_unbond(RuntimeOrigin::signed(420), 20));
+				assert_ok!(Pools::fully_unbond(RuntimeOrigin::signed(420), 30));
+				CurrentEra::set(3);
+				assert_ok!(Pools::withdraw_unbonded(RuntimeOrigin::signed(420), 20, 0));
+				assert_ok!(Pools::withdraw_unbonded(RuntimeOrigin::signed(420), 30, 0));
+
+				// then the depositor can fully unbond and withdraw, destroying the pool.
+				assert_ok!(fully_unbond_permissioned(10));
+				CurrentEra::set(6);
+				assert_ok!(Pools::withdraw_unbonded(RuntimeOrigin::signed(10), 10, 0));
+				assert!(!BondedPools::<Runtime>::contains_key(1));
+				assert!(pool_events_since_last_call().contains(&Event::Destroyed { pool_id: 1 }));
+			})
+	}
+
 	#[test]
 	fn unbond_of_1_works() {
 		ExtBuilder::default().build_and_execute(|| {
@@ -2406,11 +2888,33 @@ mod unbond {
 					pool_events_since_last_call(),
 					vec![
 						Event::Created { depositor: 10, pool_id: 1 },
-						Event::Bonded { member: 10, pool_id: 1, bonded: 10, joined: true },
-						Event::Bonded { member: 40, pool_id: 1, bonded: 40, joined: true },
-						Event::Bonded { member: 550, pool_id: 1, bonded: 550, joined: true },
+						Event::Bonded {
+							member: 10,
+							pool_id: 1,
+							bonded: 10,
+							points: 10,
+							joined: true
+						},
+						Event::PoolUpdated { pool_id: 1, points: 10, balance: 10 },
+						Event::Bonded {
+							member: 40,
+							pool_id: 1,
+							bonded: 40,
+							points: 40,
+							joined: true
+						},
+						Event::PoolUpdated { pool_id: 1, points: 50, balance: 50 },
+						Event::Bonded {
+							member: 550,
+							pool_id: 1,
+							bonded: 550,
+							points: 550,
+							joined: true
+						},
+						Event::PoolUpdated { pool_id: 1, points: 600, balance: 600 },
 						Event::PaidOut { member: 40, pool_id: 1, payout: 40 },
-						Event::Unbonded { member: 40, pool_id: 1, points: 6, balance: 6, era: 3 }
+						Event::Unbonded { member: 40, pool_id: 1, points: 6, balance: 6, era: 3 },
+						Event::PoolUpdated { pool_id: 1, points: 560, balance: 94 }
 					]
 				);
 
@@ -2458,7 +2962,8 @@ mod unbond {
 							points: 92,
 							balance: 92,
 							era: 3
-						}
+						},
+						Event::PoolUpdated { pool_id: 1, points: 10, balance: 2 }
 					]
 				);
 
@@ -2496,7 +3001,8 @@ mod unbond {
 						Event::Withdrawn { member: 550, pool_id: 1, points: 92, balance: 92 },
 						Event::MemberRemoved { pool_id: 1, member: 550 },
 						Event::PaidOut { member: 10, pool_id: 1, payout: 10 },
-						Event::Unbonded { member: 10, pool_id: 1, points: 2, balance: 2, era: 6 }
+						Event::Unbonded { member: 10, pool_id: 1, points: 2, balance: 2, era: 6 },
+						Event::PoolUpdated { pool_id: 1, points: 0, balance: 0 }
 					]
 				);
 			});
@@ -2541,8 +3047,10 @@ mod unbond {
 				pool_events_since_last_call(),
 				vec![
 					Event::Created { depositor: 10, pool_id: 1 },
-					Event::Bonded { member: 10, pool_id: 1, bonded: 10, joined: true },
-					Event::Unbonded { member: 10, pool_id: 1, points: 10, balance: 10, era: 9 }
+					Event::Bonded { member: 10, pool_id: 1, bonded: 10, points: 10, joined: true },
+					Event::PoolUpdated { pool_id: 1, points: 10, balance: 10 },
+					Event::Unbonded { member: 10, pool_id: 1, points: 10, balance: 10, era: 9 },
+					Event::PoolUpdated { pool_id: 1, points: 0, balance: 0 }
 				]
 			);
 		});
@@ -2574,9 +3082,30 @@ mod unbond {
 					pool_events_since_last_call(),
 					vec![
 						Event::Created { depositor: 10, pool_id: 1 },
-						Event::Bonded { member: 10, pool_id: 1, bonded: 10, joined: true },
-						Event::Bonded { member: 100, pool_id: 1, bonded: 100, joined: true },
-						Event::Bonded { member: 200, pool_id: 1, bonded: 200, joined: true },
+						Event::Bonded {
+							member: 10,
+							pool_id: 1,
+							bonded: 10,
+							points: 10,
+							joined: true
+						},
+						Event::PoolUpdated { pool_id: 1, points: 10, balance: 10 },
+						Event::Bonded {
+							member: 100,
+							pool_id: 1,
+							bonded: 100,
+							points: 100,
+							joined: true
+						},
+						Event::PoolUpdated { pool_id: 1, points: 110, balance: 110 },
+						Event::Bonded {
+							member: 200,
+							pool_id: 1,
+							bonded: 200,
+							points: 200,
+							joined: true
+						},
+						Event::PoolUpdated { pool_id: 1, points: 310, balance: 310 },
 						Event::Unbonded {
 							member: 100,
 							pool_id: 1,
@@ -2584,6 +3113,7 @@ mod unbond {
 							balance: 100,
 							era: 3
 						},
+						Event::PoolUpdated { pool_id: 1, points: 210, balance: 210 },
 					]
 				);
 
@@ -2592,21 +3122,24 @@ mod unbond {
 
 				assert_eq!(
 					pool_events_since_last_call(),
-					vec![Event::Unbonded {
-						member: 200,
-						pool_id: 1,
-						points: 200,
-						balance: 200,
-						era: 3
-					}]
-				);
-
-				assert_eq!(
-					BondedPool::<Runtime>::get(1).unwrap(),
-					BondedPool {
-						id: 1,
-						inner: BondedPoolInner {
-							roles: DEFAULT_ROLES,
+					vec![
+						Event::Unbonded {
+							member: 200,
+							pool_id: 1,
+							points: 200,
+							balance: 200,
+							era: 3
+						},
+						Event::PoolUpdated { pool_id: 1, points: 10, balance: 10 },
+					]
+				);
+
+				assert_eq!(
+					BondedPool::<Runtime>::get(1).unwrap(),
+					BondedPool {
+						id: 1,
+						inner: BondedPoolInner {
+							roles: DEFAULT_ROLES,
 							state: PoolState::Blocked,
 							points: 10, // Only 10 points because 200 + 100 was unbonded
 							member_counter: 3,
@@ -2665,9 +3198,18 @@ mod unbond {
 				pool_events_since_last_call(),
 				vec![
 					Event::Created { depositor: 10, pool_id: 1 },
-					Event::Bonded { member: 10, pool_id: 1, bonded: 10, joined: true },
-					Event::Bonded { member: 100, pool_id: 1, bonded: 100, joined: true },
-					Event::Unbonded { member: 100, pool_id: 1, points: 100, balance: 100, era: 3 }
+					Event::Bonded { member: 10, pool_id: 1, bonded: 10, points: 10, joined: true },
+					Event::PoolUpdated { pool_id: 1, points: 10, balance: 10 },
+					Event::Bonded {
+						member: 100,
+						pool_id: 1,
+						bonded: 100,
+						points: 100,
+						joined: true
+					},
+					Event::PoolUpdated { pool_id: 1, points: 110, balance: 110 },
+					Event::Unbonded { member: 100, pool_id: 1, points: 100, balance: 100, era: 3 },
+					Event::PoolUpdated { pool_id: 1, points: 10, balance: 10 }
 				]
 			);
 
@@ -2817,8 +3359,10 @@ mod unbond {
 				pool_events_since_last_call(),
 				vec![
 					Event::Created { depositor: 10, pool_id: 1 },
-					Event::Bonded { member: 10, pool_id: 1, bonded: 10, joined: true },
-					Event::Unbonded { member: 10, pool_id: 1, points: 1, balance: 1, era: 3 }
+					Event::Bonded { member: 10, pool_id: 1, bonded: 10, points: 10, joined: true },
+					Event::PoolUpdated { pool_id: 1, points: 10, balance: 10 },
+					Event::Unbonded { member: 10, pool_id: 1, points: 1, balance: 1, era: 3 },
+					Event::PoolUpdated { pool_id: 1, points: 9, balance: 9 }
 				]
 			);
 
@@ -2844,7 +3388,10 @@ mod unbond {
 			);
 			assert_eq!(
 				pool_events_since_last_call(),
-				vec![Event::Unbonded { member: 10, pool_id: 1, points: 5, balance: 5, era: 3 }]
+				vec![
+					Event::Unbonded { member: 10, pool_id: 1, points: 5, balance: 5, era: 3 },
+					Event::PoolUpdated { pool_id: 1, points: 4, balance: 4 },
+				]
 			);
 
 			// when: casual further unbond, next era.
@@ -2871,7 +3418,10 @@ mod unbond {
 			);
 			assert_eq!(
 				pool_events_since_last_call(),
-				vec![Event::Unbonded { member: 10, pool_id: 1, points: 1, balance: 1, era: 4 }]
+				vec![
+					Event::Unbonded { member: 10, pool_id: 1, points: 1, balance: 1, era: 4 },
+					Event::PoolUpdated { pool_id: 1, points: 3, balance: 3 },
+				]
 			);
 
 			// when: unbonding more than our active: error
@@ -2906,7 +3456,10 @@ mod unbond {
 			);
 			assert_eq!(
 				pool_events_since_last_call(),
-				vec![Event::Unbonded { member: 10, pool_id: 1, points: 3, balance: 3, era: 4 }]
+				vec![
+					Event::Unbonded { member: 10, pool_id: 1, points: 3, balance: 3, era: 4 },
+					Event::PoolUpdated { pool_id: 1, points: 0, balance: 0 },
+				]
 			);
 		});
 	}
@@ -2949,16 +3502,66 @@ mod unbond {
 				pool_events_since_last_call(),
 				vec![
 					Event::Created { depositor: 10, pool_id: 1 },
-					Event::Bonded { member: 10, pool_id: 1, bonded: 10, joined: true },
-					Event::Bonded { member: 20, pool_id: 1, bonded: 20, joined: true },
+					Event::Bonded { member: 10, pool_id: 1, bonded: 10, points: 10, joined: true },
+					Event::PoolUpdated { pool_id: 1, points: 10, balance: 10 },
+					Event::Bonded { member: 20, pool_id: 1, bonded: 20, points: 20, joined: true },
+					Event::PoolUpdated { pool_id: 1, points: 30, balance: 30 },
 					Event::Unbonded { member: 20, pool_id: 1, points: 2, balance: 2, era: 3 },
+					Event::PoolUpdated { pool_id: 1, points: 28, balance: 28 },
 					Event::Unbonded { member: 20, pool_id: 1, points: 3, balance: 3, era: 4 },
-					Event::Unbonded { member: 20, pool_id: 1, points: 1, balance: 1, era: 5 }
+					Event::PoolUpdated { pool_id: 1, points: 25, balance: 25 },
+					Event::Unbonded { member: 20, pool_id: 1, points: 1, balance: 1, era: 5 },
+					Event::PoolUpdated { pool_id: 1, points: 24, balance: 24 }
 				]
 			);
 		})
 	}
 
+	#[test]
+	fn partial_unbond_same_era_merges_chunks() {
+		ExtBuilder::default().add_members(vec![(20, 20)]).ed(1).build_and_execute(|| {
+			MaxUnbonding::set(2);
+
+			// given: repeated unbonds within the same era only consume one chunk.
+			assert_ok!(Pools::unbond(RuntimeOrigin::signed(20), 20, 2));
+			assert_ok!(Pools::unbond(RuntimeOrigin::signed(20), 20, 3));
+			assert_ok!(Pools::unbond(RuntimeOrigin::signed(20), 20, 1));
+			assert_eq!(
+				PoolMembers::<Runtime>::get(20).unwrap().unbonding_eras,
+				member_unbonding_eras!(3 => 6)
+			);
+
+			// and the next era consumes the second and last one.
+			CurrentEra::set(1);
+			assert_ok!(Pools::unbond(RuntimeOrigin::signed(20), 20, 4));
+			assert_ok!(Pools::unbond(RuntimeOrigin::signed(20), 20, 4));
+			assert_eq!(
+				PoolMembers::<Runtime>::get(20).unwrap().unbonding_eras,
+				member_unbonding_eras!(3 => 6, 4 => 8)
+			);
+
+			// when: the limit is reached in a new era.
+			CurrentEra::set(2);
+			assert_noop!(
+				frame_support::storage::with_storage_layer(|| Pools::unbond(
+					RuntimeOrigin::signed(20),
+					20,
+					1
+				)),
+				Error::<Runtime>::MaxUnbondingLimit
+			);
+
+			// then: once the first chunk is withdrawn, unbonding is possible again.
+			CurrentEra::set(3);
+			assert_ok!(Pools::withdraw_unbonded(RuntimeOrigin::signed(20), 20, 0));
+			assert_ok!(Pools::unbond(RuntimeOrigin::signed(20), 20, 1));
+			assert_eq!(
+				PoolMembers::<Runtime>::get(20).unwrap().unbonding_eras,
+				member_unbonding_eras!(4 => 8, 6 => 1)
+			);
+		})
+	}
+
 	// depositor can unbond only up to `MinCreateBond`.
 	#[test]
 	fn depositor_permissioned_partial_unbond() {
@@ -2985,8 +3588,10 @@ mod unbond {
 				pool_events_since_last_call(),
 				vec![
 					Event::Created { depositor: 10, pool_id: 1 },
-					Event::Bonded { member: 10, pool_id: 1, bonded: 10, joined: true },
-					Event::Unbonded { member: 10, pool_id: 1, points: 3, balance: 3, era: 3 }
+					Event::Bonded { member: 10, pool_id: 1, bonded: 10, points: 10, joined: true },
+					Event::PoolUpdated { pool_id: 1, points: 10, balance: 10 },
+					Event::Unbonded { member: 10, pool_id: 1, points: 3, balance: 3, era: 3 },
+					Event::PoolUpdated { pool_id: 1, points: 7, balance: 7 }
 				]
 			);
 		});
@@ -3029,10 +3634,13 @@ mod unbond {
 				vec![
 					// 2/3 of ed, which is 20's share.
 					Event::Created { depositor: 10, pool_id: 1 },
-					Event::Bonded { member: 10, pool_id: 1, bonded: 10, joined: true },
-					Event::Bonded { member: 20, pool_id: 1, bonded: 20, joined: true },
+					Event::Bonded { member: 10, pool_id: 1, bonded: 10, points: 10, joined: true },
+					Event::PoolUpdated { pool_id: 1, points: 10, balance: 10 },
+					Event::Bonded { member: 20, pool_id: 1, bonded: 20, points: 20, joined: true },
+					Event::PoolUpdated { pool_id: 1, points: 30, balance: 30 },
 					Event::PaidOut { member: 20, pool_id: 1, payout: 10 },
-					Event::Unbonded { member: 20, pool_id: 1, balance: 2, points: 2, era: 3 }
+					Event::Unbonded { member: 20, pool_id: 1, balance: 2, points: 2, era: 3 },
+					Event::PoolUpdated { pool_id: 1, points: 28, balance: 28 }
 				]
 			);
 
@@ -3048,7 +3656,8 @@ mod unbond {
 				vec![
 					// 2/3 of ed, which is 20's share.
 					Event::PaidOut { member: 20, pool_id: 1, payout: 6 },
-					Event::Unbonded { member: 20, pool_id: 1, points: 3, balance: 3, era: 4 }
+					Event::Unbonded { member: 20, pool_id: 1, points: 3, balance: 3, era: 4 },
+					Event::PoolUpdated { pool_id: 1, points: 25, balance: 25 }
 				]
 			);
 
@@ -3063,7 +3672,8 @@ mod unbond {
 				pool_events_since_last_call(),
 				vec![
 					Event::PaidOut { member: 20, pool_id: 1, payout: 3 },
-					Event::Unbonded { member: 20, pool_id: 1, points: 5, balance: 5, era: 5 }
+					Event::Unbonded { member: 20, pool_id: 1, points: 5, balance: 5, era: 5 },
+					Event::PoolUpdated { pool_id: 1, points: 20, balance: 20 }
 				]
 			);
 
@@ -3132,8 +3742,11 @@ mod withdraw_unbonded {
 					SubPoolsStorage::<Runtime>::insert(1, sub_pools);
 					// Update the equivalent of the unbonding chunks for the `StakingMock`
 					let mut x = UnbondingBalanceMap::get();
-					*x.get_mut(&default_bonded_account()).unwrap() /= 5;
+					let unbonding = x.get_mut(&default_bonded_account()).unwrap();
+					let slashed = *unbonding - *unbonding / 5;
+					*unbonding -= slashed;
 					UnbondingBalanceMap::set(&x);
+					TotalValueLocked::<Runtime>::mutate(|tvl| *tvl -= slashed);
 					Balances::make_free_balance_be(
 						&default_bonded_account(),
 						Balances::free_balance(&default_bonded_account()) / 2, // 300
@@ -3166,9 +3779,30 @@ mod withdraw_unbonded {
 					pool_events_since_last_call(),
 					vec![
 						Event::Created { depositor: 10, pool_id: 1 },
-						Event::Bonded { member: 10, pool_id: 1, bonded: 10, joined: true },
-						Event::Bonded { member: 40, pool_id: 1, bonded: 40, joined: true },
-						Event::Bonded { member: 550, pool_id: 1, bonded: 550, joined: true },
+						Event::Bonded {
+							member: 10,
+							pool_id: 1,
+							bonded: 10,
+							points: 10,
+							joined: true
+						},
+						Event::PoolUpdated { pool_id: 1, points: 10, balance: 10 },
+						Event::Bonded {
+							member: 40,
+							pool_id: 1,
+							bonded: 40,
+							points: 40,
+							joined: true
+						},
+						Event::PoolUpdated { pool_id: 1, points: 50, balance: 50 },
+						Event::Bonded {
+							member: 550,
+							pool_id: 1,
+							bonded: 550,
+							points: 550,
+							joined: true
+						},
+						Event::PoolUpdated { pool_id: 1, points: 600, balance: 600 },
 						Event::Unbonded {
 							member: 550,
 							pool_id: 1,
@@ -3176,7 +3810,9 @@ mod withdraw_unbonded {
 							balance: 550,
 							era: 3
 						},
+						Event::PoolUpdated { pool_id: 1, points: 50, balance: 50 },
 						Event::Unbonded { member: 40, pool_id: 1, points: 40, balance: 40, era: 3 },
+						Event::PoolUpdated { pool_id: 1, points: 10, balance: 10 },
 					]
 				);
 				assert_eq!(
@@ -3242,6 +3878,7 @@ mod withdraw_unbonded {
 					pool_events_since_last_call(),
 					vec![
 						Event::Unbonded { member: 10, pool_id: 1, balance: 5, points: 5, era: 9 },
+						Event::PoolUpdated { pool_id: 1, points: 5, balance: 5 },
 						Event::Withdrawn { member: 10, pool_id: 1, balance: 5, points: 5 },
 						Event::MemberRemoved { pool_id: 1, member: 10 },
 						Event::Destroyed { pool_id: 1 }
@@ -3284,17 +3921,40 @@ mod withdraw_unbonded {
 					pool_events_since_last_call(),
 					vec![
 						Event::Created { depositor: 10, pool_id: 1 },
-						Event::Bonded { member: 10, pool_id: 1, bonded: 10, joined: true },
-						Event::Bonded { member: 40, pool_id: 1, bonded: 40, joined: true },
-						Event::Bonded { member: 550, pool_id: 1, bonded: 550, joined: true },
+						Event::Bonded {
+							member: 10,
+							pool_id: 1,
+							bonded: 10,
+							points: 10,
+							joined: true
+						},
+						Event::PoolUpdated { pool_id: 1, points: 10, balance: 10 },
+						Event::Bonded {
+							member: 40,
+							pool_id: 1,
+							bonded: 40,
+							points: 40,
+							joined: true
+						},
+						Event::PoolUpdated { pool_id: 1, points: 50, balance: 50 },
+						Event::Bonded {
+							member: 550,
+							pool_id: 1,
+							bonded: 550,
+							points: 550,
+							joined: true
+						},
+						Event::PoolUpdated { pool_id: 1, points: 600, balance: 600 },
 						Event::Unbonded { member: 40, pool_id: 1, balance: 20, points: 20, era: 3 },
+						Event::PoolUpdated { pool_id: 1, points: 560, balance: 280 },
 						Event::Unbonded {
 							member: 550,
 							pool_id: 1,
 							balance: 275,
 							points: 275,
 							era: 3,
-						}
+						},
+						Event::PoolUpdated { pool_id: 1, points: 10, balance: 5 }
 					]
 				);
 				assert_eq!(
@@ -3375,6 +4035,7 @@ mod withdraw_unbonded {
 					pool_events_since_last_call(),
 					vec![
 						Event::Unbonded { member: 10, pool_id: 1, points: 5, balance: 5, era: 6 },
+						Event::PoolUpdated { pool_id: 1, points: 0, balance: 0 },
 						Event::Withdrawn { member: 10, pool_id: 1, points: 5, balance: 5 },
 						Event::MemberRemoved { pool_id: 1, member: 10 },
 						Event::Destroyed { pool_id: 1 }
@@ -3486,9 +4147,30 @@ mod withdraw_unbonded {
 					pool_events_since_last_call(),
 					vec![
 						Event::Created { depositor: 10, pool_id: 1 },
-						Event::Bonded { member: 10, pool_id: 1, bonded: 10, joined: true },
-						Event::Bonded { member: 100, pool_id: 1, bonded: 100, joined: true },
-						Event::Bonded { member: 200, pool_id: 1, bonded: 200, joined: true },
+						Event::Bonded {
+							member: 10,
+							pool_id: 1,
+							bonded: 10,
+							points: 10,
+							joined: true
+						},
+						Event::PoolUpdated { pool_id: 1, points: 10, balance: 10 },
+						Event::Bonded {
+							member: 100,
+							pool_id: 1,
+							bonded: 100,
+							points: 100,
+							joined: true
+						},
+						Event::PoolUpdated { pool_id: 1, points: 110, balance: 110 },
+						Event::Bonded {
+							member: 200,
+							pool_id: 1,
+							bonded: 200,
+							points: 200,
+							joined: true
+						},
+						Event::PoolUpdated { pool_id: 1, points: 310, balance: 310 },
 						Event::Unbonded {
 							member: 100,
 							pool_id: 1,
@@ -3496,13 +4178,15 @@ mod withdraw_unbonded {
 							balance: 100,
 							era: 3
 						},
+						Event::PoolUpdated { pool_id: 1, points: 210, balance: 210 },
 						Event::Unbonded {
 							member: 200,
 							pool_id: 1,
 							points: 200,
 							balance: 200,
 							era: 3
-						}
+						},
+						Event::PoolUpdated { pool_id: 1, points: 10, balance: 10 }
 					]
 				);
 
@@ -3538,6 +4222,97 @@ mod withdraw_unbonded {
 			});
 	}
 
+	#[test]
+	fn withdraw_unbonded_kick_pays_rewards_to_member() {
+		ExtBuilder::default()
+			.add_members(vec![(100, 100), (200, 200)])
+			.build_and_execute(|| {
+				// Given
+				unsafe_set_state(1, PoolState::Blocked);
+				let kicker = DEFAULT_ROLES.state_toggler.unwrap();
+				let kicker_balance = Balances::free_balance(kicker);
+				assert_eq!(Balances::free_balance(100), 100);
+
+				// the pool earns some rewards, 100 of which belong to member 100.
+				assert_ok!(Balances::mutate_account(&default_reward_account(), |a| a.free += 310));
+
+				// When
+				assert_ok!(Pools::fully_unbond(RuntimeOrigin::signed(kicker), 100));
+
+				// Then the pending rewards are paid out to the kicked member, not the kicker.
+				assert_eq!(Balances::free_balance(100), 100 + 100);
+				assert_eq!(Balances::free_balance(kicker), kicker_balance);
+				assert_eq!(
+					pool_events_since_last_call(),
+					vec![
+						Event::Created { depositor: 10, pool_id: 1 },
+						Event::Bonded {
+							member: 10,
+							pool_id: 1,
+							bonded: 10,
+							points: 10,
+							joined: true
+						},
+						Event::PoolUpdated { pool_id: 1, points: 10, balance: 10 },
+						Event::Bonded {
+							member: 100,
+							pool_id: 1,
+							bonded: 100,
+							points: 100,
+							joined: true
+						},
+						Event::PoolUpdated { pool_id: 1, points: 110, balance: 110 },
+						Event::Bonded {
+							member: 200,
+							pool_id: 1,
+							bonded: 200,
+							points: 200,
+							joined: true
+						},
+						Event::PoolUpdated { pool_id: 1, points: 310, balance: 310 },
+						Event::PaidOut { member: 100, pool_id: 1, payout: 100 },
+						Event::Unbonded {
+							member: 100,
+							pool_id: 1,
+							points: 100,
+							balance: 100,
+							era: 3
+						},
+						Event::PoolUpdated { pool_id: 1, points: 210, balance: 210 }
+					]
+				);
+
+				// the kicker cannot withdraw before the unlock era.
+				assert_noop!(
+					Pools::withdraw_unbonded(RuntimeOrigin::signed(kicker), 100, 0),
+					Error::<Runtime>::CannotWithdrawAny
+				);
+
+				// When the unlock era passes
+				CurrentEra::set(StakingMock::bonding_duration());
+				assert_ok!(Pools::withdraw_unbonded(RuntimeOrigin::signed(kicker), 100, 0));
+
+				// Then the funds go to the member and they are removed from the pool.
+				assert_eq!(Balances::free_balance(100), 100 + 100 + 100);
+				assert_eq!(Balances::free_balance(kicker), kicker_balance);
+				assert!(!PoolMembers::<Runtime>::contains_key(100));
+				assert_eq!(
+					pool_events_since_last_call(),
+					vec![
+						Event::Withdrawn { member: 100, pool_id: 1, points: 100, balance: 100 },
+						Event::MemberRemoved { pool_id: 1, member: 100 }
+					]
+				);
+
+				// and the remaining members can still claim their share of the rewards.
+				assert_ok!(Pools::claim_payout(RuntimeOrigin::signed(200)));
+				assert_eq!(
+					pool_events_since_last_call(),
+					vec![Event::PaidOut { member: 200, pool_id: 1, payout: 200 }]
+				);
+			});
+	}
+
 	#[test]
 	fn withdraw_unbonded_destroying_permissionless() {
 		ExtBuilder::default().add_members(vec![(100, 100)]).build_and_execute(|| {
@@ -3577,9 +4352,18 @@ mod withdraw_unbonded {
 				pool_events_since_last_call(),
 				vec![
 					Event::Created { depositor: 10, pool_id: 1 },
-					Event::Bonded { member: 10, pool_id: 1, bonded: 10, joined: true },
-					Event::Bonded { member: 100, pool_id: 1, bonded: 100, joined: true },
+					Event::Bonded { member: 10, pool_id: 1, bonded: 10, points: 10, joined: true },
+					Event::PoolUpdated { pool_id: 1, points: 10, balance: 10 },
+					Event::Bonded {
+						member: 100,
+						pool_id: 1,
+						bonded: 100,
+						points: 100,
+						joined: true
+					},
+					Event::PoolUpdated { pool_id: 1, points: 110, balance: 110 },
 					Event::Unbonded { member: 100, pool_id: 1, points: 100, balance: 100, era: 3 },
+					Event::PoolUpdated { pool_id: 1, points: 10, balance: 10 },
 					Event::Withdrawn { member: 100, pool_id: 1, points: 100, balance: 100 },
 					Event::MemberRemoved { pool_id: 1, member: 100 }
 				]
@@ -3617,10 +4401,14 @@ mod withdraw_unbonded {
 				pool_events_since_last_call(),
 				vec![
 					Event::Created { depositor: 10, pool_id: 1 },
-					Event::Bonded { member: 10, pool_id: 1, bonded: 10, joined: true },
-					Event::Bonded { member: 10, pool_id: 1, bonded: 10, joined: false },
+					Event::Bonded { member: 10, pool_id: 1, bonded: 10, points: 10, joined: true },
+					Event::PoolUpdated { pool_id: 1, points: 10, balance: 10 },
+					Event::Bonded { member: 10, pool_id: 1, bonded: 10, points: 10, joined: false },
+					Event::PoolUpdated { pool_id: 1, points: 20, balance: 20 },
 					Event::Unbonded { member: 10, pool_id: 1, points: 6, balance: 6, era: 3 },
-					Event::Unbonded { member: 10, pool_id: 1, points: 1, balance: 1, era: 4 }
+					Event::PoolUpdated { pool_id: 1, points: 14, balance: 14 },
+					Event::Unbonded { member: 10, pool_id: 1, points: 1, balance: 1, era: 4 },
+					Event::PoolUpdated { pool_id: 1, points: 13, balance: 13 }
 				]
 			);
 
@@ -3704,10 +4492,14 @@ mod withdraw_unbonded {
 				pool_events_since_last_call(),
 				vec![
 					Event::Created { depositor: 10, pool_id: 1 },
-					Event::Bonded { member: 10, pool_id: 1, bonded: 10, joined: true },
-					Event::Bonded { member: 11, pool_id: 1, bonded: 10, joined: true },
+					Event::Bonded { member: 10, pool_id: 1, bonded: 10, points: 10, joined: true },
+					Event::PoolUpdated { pool_id: 1, points: 10, balance: 10 },
+					Event::Bonded { member: 11, pool_id: 1, bonded: 10, points: 10, joined: true },
+					Event::PoolUpdated { pool_id: 1, points: 20, balance: 20 },
 					Event::Unbonded { member: 11, pool_id: 1, points: 6, balance: 6, era: 3 },
-					Event::Unbonded { member: 11, pool_id: 1, points: 1, balance: 1, era: 4 }
+					Event::PoolUpdated { pool_id: 1, points: 14, balance: 14 },
+					Event::Unbonded { member: 11, pool_id: 1, points: 1, balance: 1, era: 4 },
+					Event::PoolUpdated { pool_id: 1, points: 13, balance: 13 }
 				]
 			);
 
@@ -3794,10 +4586,20 @@ mod withdraw_unbonded {
 				pool_events_since_last_call(),
 				vec![
 					Event::Created { depositor: 10, pool_id: 1 },
-					Event::Bonded { member: 10, pool_id: 1, bonded: 10, joined: true },
-					Event::Bonded { member: 100, pool_id: 1, bonded: 100, joined: true },
+					Event::Bonded { member: 10, pool_id: 1, bonded: 10, points: 10, joined: true },
+					Event::PoolUpdated { pool_id: 1, points: 10, balance: 10 },
+					Event::Bonded {
+						member: 100,
+						pool_id: 1,
+						bonded: 100,
+						points: 100,
+						joined: true
+					},
+					Event::PoolUpdated { pool_id: 1, points: 110, balance: 110 },
 					Event::Unbonded { member: 100, pool_id: 1, points: 75, balance: 75, era: 3 },
+					Event::PoolUpdated { pool_id: 1, points: 35, balance: 35 },
 					Event::Unbonded { member: 100, pool_id: 1, points: 25, balance: 25, era: 4 },
+					Event::PoolUpdated { pool_id: 1, points: 10, balance: 10 },
 					Event::Withdrawn { member: 100, pool_id: 1, points: 75, balance: 75 },
 				]
 			);
@@ -3856,7 +4658,9 @@ mod withdraw_unbonded {
 					pool_events_since_last_call(),
 					vec![
 						Event::Unbonded { member: 20, pool_id: 1, points: 5, balance: 5, era: 3 },
+						Event::PoolUpdated { pool_id: 1, points: 205, balance: 205 },
 						Event::Unbonded { member: 30, pool_id: 1, points: 5, balance: 5, era: 3 },
+						Event::PoolUpdated { pool_id: 1, points: 200, balance: 200 },
 					]
 				);
 
@@ -3881,7 +4685,10 @@ mod withdraw_unbonded {
 				);
 				assert_eq!(
 					pool_events_since_last_call(),
-					vec![Event::Unbonded { member: 20, pool_id: 1, points: 5, balance: 5, era: 4 }]
+					vec![
+						Event::Unbonded { member: 20, pool_id: 1, points: 5, balance: 5, era: 4 },
+						Event::PoolUpdated { pool_id: 1, points: 195, balance: 195 },
+					]
 				);
 
 				// when
@@ -3906,7 +4713,10 @@ mod withdraw_unbonded {
 				);
 				assert_eq!(
 					pool_events_since_last_call(),
-					vec![Event::Unbonded { member: 20, pool_id: 1, points: 5, balance: 5, era: 5 }]
+					vec![
+						Event::Unbonded { member: 20, pool_id: 1, points: 5, balance: 5, era: 5 },
+						Event::PoolUpdated { pool_id: 1, points: 190, balance: 190 },
+					]
 				);
 
 				// when
@@ -3932,7 +4742,10 @@ mod withdraw_unbonded {
 				);
 				assert_eq!(
 					pool_events_since_last_call(),
-					vec![Event::Unbonded { member: 20, pool_id: 1, points: 5, balance: 5, era: 8 }]
+					vec![
+						Event::Unbonded { member: 20, pool_id: 1, points: 5, balance: 5, era: 8 },
+						Event::PoolUpdated { pool_id: 1, points: 185, balance: 185 },
+					]
 				);
 
 				// now we start withdrawing unlocked bonds.
@@ -4030,11 +4843,16 @@ mod withdraw_unbonded {
 				pool_events_since_last_call(),
 				vec![
 					Event::Created { depositor: 10, pool_id: 1 },
-					Event::Bonded { member: 10, pool_id: 1, bonded: 10, joined: true },
-					Event::Bonded { member: 10, pool_id: 1, bonded: 10, joined: false },
+					Event::Bonded { member: 10, pool_id: 1, bonded: 10, points: 10, joined: true },
+					Event::PoolUpdated { pool_id: 1, points: 10, balance: 10 },
+					Event::Bonded { member: 10, pool_id: 1, bonded: 10, points: 10, joined: false },
+					Event::PoolUpdated { pool_id: 1, points: 20, balance: 20 },
 					Event::Unbonded { member: 10, pool_id: 1, balance: 7, points: 7, era: 3 },
+					Event::PoolUpdated { pool_id: 1, points: 13, balance: 13 },
 					Event::Unbonded { member: 10, pool_id: 1, balance: 3, points: 3, era: 4 },
+					Event::PoolUpdated { pool_id: 1, points: 10, balance: 10 },
 					Event::Unbonded { member: 10, pool_id: 1, balance: 10, points: 10, era: 4 },
+					Event::PoolUpdated { pool_id: 1, points: 0, balance: 0 },
 					Event::Withdrawn { member: 10, pool_id: 1, balance: 7, points: 7 }
 				]
 			);
@@ -4093,6 +4911,7 @@ mod create {
 				PoolMember {
 					pool_id: 2,
 					points: StakingMock::minimum_nominator_bond(),
+					joined_at: 1,
 					..Default::default()
 				}
 			);
@@ -4126,9 +4945,11 @@ mod create {
 				pool_events_since_last_call(),
 				vec![
 					Event::Created { depositor: 10, pool_id: 1 },
-					Event::Bonded { member: 10, pool_id: 1, bonded: 10, joined: true },
+					Event::Bonded { member: 10, pool_id: 1, bonded: 10, points: 10, joined: true },
+					Event::PoolUpdated { pool_id: 1, points: 10, balance: 10 },
 					Event::Created { depositor: 11, pool_id: 2 },
-					Event::Bonded { member: 11, pool_id: 2, bonded: 10, joined: true }
+					Event::Bonded { member: 11, pool_id: 2, bonded: 10, points: 10, joined: true },
+					Event::PoolUpdated { pool_id: 2, points: 10, balance: 10 }
 				]
 			);
 		});
@@ -4273,6 +5094,118 @@ mod nominate {
 			);
 		});
 	}
+
+	#[test]
+	fn nominate_respects_max_nominations() {
+		ExtBuilder::default().build_and_execute(|| {
+			MaxNominations::set(3);
+
+			// Exactly the maximum works
+			assert_ok!(Pools::nominate(RuntimeOrigin::signed(901), 1, vec![21, 22, 23]));
+			assert_eq!(Nominations::get().unwrap(), vec![21, 22, 23]);
+
+			// One more than the maximum doesn't
+			assert_noop!(
+				Pools::nominate(RuntimeOrigin::signed(901), 1, vec![21, 22, 23, 24]),
+				Error::<Runtime>::TooManyTargets
+			);
+			assert_eq!(Nominations::get().unwrap(), vec![21, 22, 23]);
+		});
+	}
+}
+
+mod chill {
+	use super::*;
+
+	#[test]
+	fn chill_works() {
+		ExtBuilder::default().build_and_execute(|| {
+			assert_ok!(Pools::nominate(RuntimeOrigin::signed(901), 1, vec![21]));
+
+			// Depositor and state toggler can't chill
+			assert_noop!(
+				Pools::chill(RuntimeOrigin::signed(10), 1),
+				Error::<Runtime>::NotNominator
+			);
+			assert_noop!(
+				Pools::chill(RuntimeOrigin::signed(902), 1),
+				Error::<Runtime>::NotNominator
+			);
+
+			// Nominator can chill
+			assert_ok!(Pools::chill(RuntimeOrigin::signed(901), 1));
+			assert_eq!(Nominations::get(), None);
+
+			// Root can chill
+			assert_ok!(Pools::nominate(RuntimeOrigin::signed(901), 1, vec![21]));
+			assert_ok!(Pools::chill(RuntimeOrigin::signed(900), 1));
+			assert_eq!(Nominations::get(), None);
+
+			// Can't chill a pool that doesn't exist
+			assert_noop!(
+				Pools::chill(RuntimeOrigin::signed(900), 123),
+				Error::<Runtime>::PoolNotFound
+			);
+		});
+	}
+
+	#[test]
+	fn anyone_can_chill_pool_below_min_nominator_bond() {
+		ExtBuilder::default().build_and_execute(|| {
+			assert_ok!(Pools::nominate(RuntimeOrigin::signed(901), 1, vec![21]));
+			assert_eq!(StakingMock::active_stake(&default_bonded_account()).unwrap(), 10);
+			assert_eq!(StakingMock::minimum_nominator_bond(), 10);
+
+			// At the minimum nominator bond, only the roles can chill.
+			assert_noop!(
+				Pools::chill(RuntimeOrigin::signed(10), 1),
+				Error::<Runtime>::NotNominator
+			);
+
+			// The pool gets slashed below the minimum nominator bond.
+			StakingMock::set_bonded_balance(default_bonded_account(), 5);
+
+			// Now anyone can chill it.
+			assert_ok!(Pools::chill(RuntimeOrigin::signed(10), 1));
+			assert_eq!(Nominations::get(), None);
+		});
+	}
+
+	#[test]
+	fn pool_nominations_works() {
+		ExtBuilder::default().build_and_execute(|| {
+			// Not nominating yet
+			assert_eq!(Pools::pool_nominations(1), None);
+
+			assert_ok!(Pools::nominate(RuntimeOrigin::signed(901), 1, vec![21, 22]));
+			assert_eq!(Pools::pool_nominations(1), Some(vec![21, 22]));
+
+			// Pools that don't exist have no nominations
+			assert_eq!(Pools::pool_nominations(123), None);
+
+			assert_ok!(Pools::chill(RuntimeOrigin::signed(901), 1));
+			assert_eq!(Pools::pool_nominations(1), None);
+		});
+	}
+
+	#[test]
+	fn below_minimum_active_stake_works() {
+		ExtBuilder::default().build_and_execute(|| {
+			// The default pool has 10 active.
+			assert_eq!(Pools::below_minimum_active_stake(1), Some(false));
+
+			StakingMinActiveStake::set(11);
+			assert_eq!(Pools::below_minimum_active_stake(1), Some(true));
+
+			// Joining brings the pool back above the minimum.
+			Balances::make_free_balance_be(&11, ExistentialDeposit::get() + 2);
+			assert_ok!(Pools::join(RuntimeOrigin::signed(11), 2, 1, None));
+			assert_eq!(Pools::below_minimum_active_stake(1), Some(false));
+
+			// Pools that don't exist are not reported.
+			assert_eq!(Pools::below_minimum_active_stake(123), None);
+		});
+	}
 }
 
 mod set_state {
@@ -4301,8 +5234,9 @@ mod set_state {
 				pool_events_since_last_call(),
 				vec![
 					Event::Created { depositor: 10, pool_id: 1 },
-					Event::Bonded { member: 10, pool_id: 1, bonded: 10, joined: true },
-					Event::StateChanged { pool_id: 1, new_state: PoolState::Blocked }
+					Event::Bonded { member: 10, pool_id: 1, bonded: 10, points: 10, joined: true },
+					Event::PoolUpdated { pool_id: 1, points: 10, balance: 10 },
+					Event::StateChanged { pool_id: 1, new_state: PoolState::Blocked, caller: 900 }
 				]
 			);
 
@@ -4353,14 +5287,76 @@ mod set_state {
 			assert_eq!(
 				pool_events_since_last_call(),
 				vec![
-					Event::StateChanged { pool_id: 1, new_state: PoolState::Destroying },
-					Event::StateChanged { pool_id: 1, new_state: PoolState::Destroying },
-					Event::StateChanged { pool_id: 1, new_state: PoolState::Destroying }
-				]
-			);
-		});
-	}
-}
+					Event::StateChanged {
+						pool_id: 1,
+						new_state: PoolState::Destroying,
+						caller: 902
+					},
+					Event::StateChanged {
+						pool_id: 1,
+						new_state: PoolState::Destroying,
+						caller: 11
+					},
+					Event::StateChanged {
+						pool_id: 1,
+						new_state: PoolState::Destroying,
+						caller: 11
+					}
+				]
+			);
+		});
+	}
+
+	#[test]
+	fn set_state_permissionless_destroying_works() {
+		ExtBuilder::default().build_and_execute(|| {
+			// Given a healthy pool, a random account cannot destroy it.
+			assert_ok!(BondedPool::<Runtime>::get(1).unwrap().ok_to_be_open());
+			assert_noop!(
+				Pools::set_state(RuntimeOrigin::signed(11), 1, PoolState::Destroying),
+				Error::<Runtime>::CanNotChangeState
+			);
+
+			// When the pool is slashed below the minimum bond of the depositor.
+			assert_eq!(Pools::depositor_min_bond(), 10);
+			StakingMock::set_bonded_balance(default_bonded_account(), 9);
+			// Then it is still ok to be open by the points to balance ratio..
+			assert_ok!(BondedPool::<Runtime>::get(1).unwrap().ok_to_be_open());
+			// ..but anyone can destroy it.
+			assert_ok!(Pools::set_state(RuntimeOrigin::signed(11), 1, PoolState::Destroying));
+			assert_eq!(BondedPool::<Runtime>::get(1).unwrap().state, PoolState::Destroying);
+
+			// Given a pool with zero points.
+			unsafe_set_state(1, PoolState::Open);
+			StakingMock::set_bonded_balance(default_bonded_account(), 10);
+			let mut bonded_pool = BondedPool::<Runtime>::get(1).unwrap();
+			bonded_pool.points = 0;
+			bonded_pool.put();
+			// Then anyone can destroy it.
+			assert_ok!(Pools::set_state(RuntimeOrigin::signed(12), 1, PoolState::Destroying));
+			assert_eq!(BondedPool::<Runtime>::get(1).unwrap().state, PoolState::Destroying);
+
+			assert_eq!(
+				pool_events_since_last_call(),
+				vec![
+					Event::Created { depositor: 10, pool_id: 1 },
+					Event::Bonded { member: 10, pool_id: 1, bonded: 10, points: 10, joined: true },
+					Event::PoolUpdated { pool_id: 1, points: 10, balance: 10 },
+					Event::StateChanged {
+						pool_id: 1,
+						new_state: PoolState::Destroying,
+						caller: 11
+					},
+					Event::StateChanged {
+						pool_id: 1,
+						new_state: PoolState::Destroying,
+						caller: 12
+					},
+				]
+			);
+		});
+	}
+}
 
 mod set_metadata {
 	use super::*;
@@ -4473,8 +5469,10 @@ mod bond_extra {
 				pool_events_since_last_call(),
 				vec![
 					Event::Created { depositor: 10, pool_id: 1 },
-					Event::Bonded { member: 10, pool_id: 1, bonded: 10, joined: true },
-					Event::Bonded { member: 10, pool_id: 1, bonded: 10, joined: false }
+					Event::Bonded { member: 10, pool_id: 1, bonded: 10, points: 10, joined: true },
+					Event::PoolUpdated { pool_id: 1, points: 10, balance: 10 },
+					Event::Bonded { member: 10, pool_id: 1, bonded: 10, points: 10, joined: false },
+					Event::PoolUpdated { pool_id: 1, points: 20, balance: 20 }
 				]
 			);
 
@@ -4488,7 +5486,10 @@ mod bond_extra {
 
 			assert_eq!(
 				pool_events_since_last_call(),
-				vec![Event::Bonded { member: 10, pool_id: 1, bonded: 20, joined: false }]
+				vec![
+					Event::Bonded { member: 10, pool_id: 1, bonded: 20, points: 20, joined: false },
+					Event::PoolUpdated { pool_id: 1, points: 40, balance: 40 },
+				]
 			);
 		})
 	}
@@ -4519,13 +5520,20 @@ mod bond_extra {
 				pool_events_since_last_call(),
 				vec![
 					Event::Created { depositor: 10, pool_id: 1 },
-					Event::Bonded { member: 10, pool_id: 1, bonded: 10, joined: true },
+					Event::Bonded { member: 10, pool_id: 1, bonded: 10, points: 10, joined: true },
+					Event::PoolUpdated { pool_id: 1, points: 10, balance: 10 },
 					Event::PaidOut { member: 10, pool_id: 1, payout: claimable_reward },
 					Event::Bonded {
 						member: 10,
 						pool_id: 1,
 						bonded: claimable_reward,
+						points: claimable_reward,
 						joined: false
+					},
+					Event::PoolUpdated {
+						pool_id: 1,
+						points: 10 + claimable_reward,
+						balance: 10 + claimable_reward
 					}
 				]
 			);
@@ -4573,16 +5581,37 @@ mod bond_extra {
 				pool_events_since_last_call(),
 				vec![
 					Event::Created { depositor: 10, pool_id: 1 },
-					Event::Bonded { member: 10, pool_id: 1, bonded: 10, joined: true },
-					Event::Bonded { member: 20, pool_id: 1, bonded: 20, joined: true },
+					Event::Bonded { member: 10, pool_id: 1, bonded: 10, points: 10, joined: true },
+					Event::PoolUpdated { pool_id: 1, points: 10, balance: 10 },
+					Event::Bonded { member: 20, pool_id: 1, bonded: 20, points: 20, joined: true },
+					Event::PoolUpdated { pool_id: 1, points: 30, balance: 30 },
 					Event::PaidOut { member: 10, pool_id: 1, payout: 1 },
-					Event::Bonded { member: 10, pool_id: 1, bonded: 1, joined: false },
+					Event::Bonded { member: 10, pool_id: 1, bonded: 1, points: 1, joined: false },
+					Event::PoolUpdated { pool_id: 1, points: 31, balance: 31 },
 					Event::PaidOut { member: 20, pool_id: 1, payout: 2 },
-					Event::Bonded { member: 20, pool_id: 1, bonded: 2, joined: false }
+					Event::Bonded { member: 20, pool_id: 1, bonded: 2, points: 2, joined: false },
+					Event::PoolUpdated { pool_id: 1, points: 33, balance: 33 }
 				]
 			);
 		})
 	}
+
+	#[test]
+	fn bond_extra_respects_max_points_to_balance() {
+		ExtBuilder::default().add_members(vec![(20, 20)]).build_and_execute(|| {
+			// a 90% slash puts the pool at a points-to-balance ratio of 10:1.
+			StakingMock::slash_by(1, 27);
+			assert_noop!(
+				Pools::bond_extra(RuntimeOrigin::signed(20), BondExtra::FreeBalance(1)),
+				Error::<Runtime>::OverflowRisk
+			);
+
+			// a runtime that tolerates a higher ratio still accepts more funds.
+			MaxPointsToBalance::set(11);
+			assert_ok!(Pools::bond_extra(RuntimeOrigin::signed(20), BondExtra::FreeBalance(1)));
+			assert_eq!(PoolMembers::<Runtime>::get(20).unwrap().points, 30);
+		});
+	}
 }
 
 mod update_roles {
@@ -4661,7 +5690,8 @@ mod update_roles {
 				pool_events_since_last_call(),
 				vec![
 					Event::Created { depositor: 10, pool_id: 1 },
-					Event::Bonded { member: 10, pool_id: 1, bonded: 10, joined: true },
+					Event::Bonded { member: 10, pool_id: 1, bonded: 10, points: 10, joined: true },
+					Event::PoolUpdated { pool_id: 1, points: 10, balance: 10 },
 					Event::RolesUpdated {
 						root: Some(5),
 						state_toggler: Some(7),
@@ -4774,7 +5804,11 @@ mod reward_counter_precision {
 		start
 	}
 
-	fn default_pool_reward_counter() -> FixedU128 {
+	/// A pool with more points than the reward counter can resolve, i.e. a reward of a single
+	/// unit per point is out of its range.
+	const HUGE_POOL_BOND: Balance = inflation(50) * 1_000_000;
+
+	fn default_pool_reward_counter() -> RewardCounter {
 		RewardPools::<T>::get(1)
 			.unwrap()
 			.current_reward_counter(1, BondedPools::<T>::get(1).unwrap().points)
@@ -4790,9 +5824,8 @@ mod reward_counter_precision {
 
 	#[test]
 	fn smallest_claimable_reward() {
-		// create a pool that has all of the polkadot issuance in 50 years.
-		let pool_bond = inflation(50);
-		ExtBuilder::default().ed(DOT).min_bond(pool_bond).build_and_execute(|| {
+		// create a pool that has a million times all of the polkadot issuance in 50 years.
+		ExtBuilder::default().ed(DOT).min_bond(HUGE_POOL_BOND).build_and_execute(|| {
 			assert_eq!(
 				pool_events_since_last_call(),
 				vec![
@@ -4800,14 +5833,20 @@ mod reward_counter_precision {
 					Event::Bonded {
 						member: 10,
 						pool_id: 1,
-						bonded: 1173908528796953165005,
+						bonded: 1173908528796953165005000000,
+						points: 1173908528796953165005000000,
 						joined: true,
+					},
+					Event::PoolUpdated {
+						pool_id: 1,
+						points: 1173908528796953165005000000,
+						balance: 1173908528796953165005000000,
 					}
 				]
 			);
 
 			// the smallest reward that this pool can handle is
-			let expected_smallest_reward = inflation(50) / 10u128.pow(18);
+			let expected_smallest_reward = HUGE_POOL_BOND / RewardCounter::DIV;
 
 			// tad bit less. cannot be paid out.
 			assert_ok!(Balances::mutate_account(&default_reward_account(), |a| a.free +=
@@ -4830,6 +5869,164 @@ mod reward_counter_precision {
 		})
 	}
 
+	#[test]
+	fn rewards_below_the_old_precision_are_not_lost() {
+		// create a pool that has all of the polkadot issuance in 50 years.
+		let pool_bond = inflation(50);
+		ExtBuilder::default().ed(DOT).min_bond(pool_bond).build_and_execute(|| {
+			// a reward that is too small to move a reward counter with 18 decimals.
+			let tiny_reward = inflation(50) / 10u128.pow(18) - 1;
+			assert_ok!(
+				Balances::mutate_account(&default_reward_account(), |a| a.free += tiny_reward)
+			);
+			assert_eq!(
+				FixedU128::checked_from_rational(tiny_reward, pool_bond),
+				Some(Zero::zero())
+			);
+			assert!(!default_pool_reward_counter().is_zero());
+
+			// a new member joins and the reward is absorbed into the records, without any of it
+			// being lost to rounding.
+			Balances::make_free_balance_be(&20, 20 * DOT);
+			assert_ok!(Pools::join(RuntimeOrigin::signed(20), 10 * DOT, 1, None));
+			assert_eq!(RewardPools::<T>::get(1).unwrap().dust(), 0);
+			assert_eq!(pending_rewards(20), Some(0));
+
+			// the depositor can claim all of it, but for the rounding of their own share.
+			assert_eq!(pending_rewards(10), Some(tiny_reward - 1));
+			assert_ok!(Pools::claim_payout(RuntimeOrigin::signed(10)));
+			assert_eq!(
+				pool_events_since_last_call().last(),
+				Some(&Event::PaidOut { member: 10, pool_id: 1, payout: tiny_reward - 1 })
+			);
+		})
+	}
+
+	#[test]
+	fn rewards_lost_to_rounding_are_tracked_as_dust() {
+		ExtBuilder::default().ed(DOT).min_bond(HUGE_POOL_BOND).build_and_execute(|| {
+			// a reward that is too small to move the reward counter of this pool.
+			let tiny_reward = HUGE_POOL_BOND / RewardCounter::DIV - 1;
+			assert_ok!(
+				Balances::mutate_account(&default_reward_account(), |a| a.free += tiny_reward)
+			);
+			assert_eq!(default_pool_reward_counter(), RewardCounter::zero());
+
+			// nothing to sweep until the records of the pool are updated.
+			assert_eq!(RewardPools::<T>::get(1).unwrap().dust(), 0);
+			assert_noop!(Pools::sweep_dust(RuntimeOrigin::signed(20), 1), Error::<T>::NoDust);
+
+			// a new member joins and the reward is absorbed into the records, without anyone
+			// being able to claim it.
+			Balances::make_free_balance_be(&20, 20 * DOT);
+			assert_ok!(Pools::join(RuntimeOrigin::signed(20), 10 * DOT, 1, None));
+			assert_eq!(pending_rewards(10), Some(0));
+			assert_eq!(pending_rewards(20), Some(0));
+
+			// instead of being stuck in the reward account, it is tracked as dust.
+			assert_eq!(RewardPools::<T>::get(1).unwrap().dust(), tiny_reward);
+
+			// which anyone can sweep to the depositor.
+			let depositor_balance = Balances::free_balance(&10);
+			assert_ok!(Pools::sweep_dust(RuntimeOrigin::signed(20), 1));
+			assert_eq!(Balances::free_balance(&10), depositor_balance + tiny_reward);
+			assert_eq!(Balances::free_balance(&default_reward_account()), DOT);
+			assert_eq!(RewardPools::<T>::get(1).unwrap().dust(), 0);
+			assert_noop!(Pools::sweep_dust(RuntimeOrigin::signed(20), 1), Error::<T>::NoDust);
+
+			assert_eq!(
+				pool_events_since_last_call(),
+				vec![
+					Event::Created { depositor: 10, pool_id: 1 },
+					Event::Bonded {
+						member: 10,
+						pool_id: 1,
+						bonded: 1173908528796953165005000000,
+						points: 1173908528796953165005000000,
+						joined: true,
+					},
+					Event::PoolUpdated {
+						pool_id: 1,
+						points: 1173908528796953165005000000,
+						balance: 1173908528796953165005000000,
+					},
+					Event::Bonded {
+						member: 20,
+						pool_id: 1,
+						bonded: 100000000000,
+						points: 100000000000,
+						joined: true
+					},
+					Event::PoolUpdated {
+						pool_id: 1,
+						points: 1173908528796953265005000000,
+						balance: 1173908528796953265005000000,
+					},
+					Event::DustSwept { pool_id: 1, beneficiary: 10, amount: tiny_reward },
+				]
+			);
+
+			// the sweep does not affect rewards that come in later.
+			assert_ok!(
+				Balances::mutate_account(&default_reward_account(), |a| a.free += 1000 * DOT)
+			);
+			let pending = pending_rewards(10).unwrap() + pending_rewards(20).unwrap();
+			assert!(pending > 1000 * DOT - 2 * tiny_reward && pending <= 1000 * DOT);
+			assert_ok!(Pools::claim_payout(RuntimeOrigin::signed(10)));
+			assert_ok!(Pools::claim_payout(RuntimeOrigin::signed(20)));
+		})
+	}
+
+	#[test]
+	fn repeated_tiny_rewards_are_all_accounted_for() {
+		ExtBuilder::default().ed(DOT).min_bond(HUGE_POOL_BOND).build_and_execute(|| {
+			let tiny_reward = HUGE_POOL_BOND / RewardCounter::DIV - 1;
+
+			// every reward drips in between two updates of the records and none of them moves
+			// the reward counter.
+			for member in 20..23 {
+				assert_ok!(
+					Balances::mutate_account(&default_reward_account(), |a| a.free += tiny_reward)
+				);
+				Balances::make_free_balance_be(&member, 20 * DOT);
+				assert_ok!(Pools::join(RuntimeOrigin::signed(member), 10 * DOT, 1, None));
+			}
+			assert_eq!(default_pool_reward_counter(), RewardCounter::zero());
+			assert!([10, 20, 21, 22].into_iter().all(|member| pending_rewards(member) == Some(0)));
+
+			// all of them are lost to rounding, and all of them are tracked as dust.
+			assert_eq!(RewardPools::<T>::get(1).unwrap().dust(), 3 * tiny_reward);
+			assert_eq!(Balances::free_balance(&default_reward_account()), DOT + 3 * tiny_reward);
+		})
+	}
+
+	#[test]
+	fn dust_is_swept_to_the_dust_receiver() {
+		ExtBuilder::default().ed(DOT).min_bond(HUGE_POOL_BOND).build_and_execute(|| {
+			let tiny_reward = HUGE_POOL_BOND / RewardCounter::DIV - 1;
+			assert_ok!(
+				Balances::mutate_account(&default_reward_account(), |a| a.free += tiny_reward)
+			);
+			Balances::make_free_balance_be(&20, 20 * DOT);
+			assert_ok!(Pools::join(RuntimeOrigin::signed(20), 10 * DOT, 1, None));
+			assert_eq!(RewardPools::<T>::get(1).unwrap().dust(), tiny_reward);
+
+			// e.g. the treasury.
+			DustReceiver::set(Some(99));
+			Balances::make_free_balance_be(&99, DOT);
+			let depositor_balance = Balances::free_balance(&10);
+
+			assert_ok!(Pools::sweep_dust(RuntimeOrigin::signed(20), 1));
+			assert_eq!(Balances::free_balance(&99), DOT + tiny_reward);
+			assert_eq!(Balances::free_balance(&10), depositor_balance);
+			assert_eq!(RewardPools::<T>::get(1).unwrap().dust(), 0);
+			assert_eq!(
+				pool_events_since_last_call().last(),
+				Some(&Event::DustSwept { pool_id: 1, beneficiary: 99, amount: tiny_reward })
+			);
+		})
+	}
+
 	#[test]
 	fn massive_reward_in_small_pool() {
 		let tiny_bond = 1000 * DOT;
@@ -4838,12 +6035,23 @@ mod reward_counter_precision {
 				pool_events_since_last_call(),
 				vec![
 					Event::Created { depositor: 10, pool_id: 1 },
-					Event::Bonded { member: 10, pool_id: 1, bonded: 10000000000000, joined: true }
+					Event::Bonded {
+						member: 10,
+						pool_id: 1,
+						bonded: 10000000000000,
+						points: 10000000000000,
+						joined: true
+					},
+					Event::PoolUpdated {
+						pool_id: 1,
+						points: 10000000000000,
+						balance: 10000000000000,
+					}
 				]
 			);
 
 			Balances::make_free_balance_be(&20, tiny_bond);
-			assert_ok!(Pools::join(RuntimeOrigin::signed(20), tiny_bond / 2, 1));
+			assert_ok!(Pools::join(RuntimeOrigin::signed(20), tiny_bond / 2, 1, None));
 
 			// Suddenly, add a shit ton of rewards.
 			assert_ok!(
@@ -4857,7 +6065,18 @@ mod reward_counter_precision {
 			assert_eq!(
 				pool_events_since_last_call(),
 				vec![
-					Event::Bonded { member: 20, pool_id: 1, bonded: 5000000000000, joined: true },
+					Event::Bonded {
+						member: 20,
+						pool_id: 1,
+						bonded: 5000000000000,
+						points: 5000000000000,
+						joined: true
+					},
+					Event::PoolUpdated {
+						pool_id: 1,
+						points: 15000000000000,
+						balance: 15000000000000,
+					},
 					Event::PaidOut { member: 10, pool_id: 1, payout: 7333333333333333333 },
 					Event::PaidOut { member: 20, pool_id: 1, payout: 3666666666666666666 }
 				]
@@ -4878,7 +6097,13 @@ mod reward_counter_precision {
 						member: 10,
 						pool_id: 1,
 						bonded: 12_968_712_300_500_000_000,
+						points: 12_968_712_300_500_000_000,
 						joined: true,
+					},
+					Event::PoolUpdated {
+						pool_id: 1,
+						points: 12_968_712_300_500_000_000,
+						balance: 12_968_712_300_500_000_000,
 					}
 				]
 			);
@@ -4893,16 +6118,24 @@ mod reward_counter_precision {
 			// some whale now joins with the other half ot the total issuance. This will bloat all
 			// the calculation regarding current reward counter.
 			Balances::make_free_balance_be(&20, pool_bond * 2);
-			assert_ok!(Pools::join(RuntimeOrigin::signed(20), pool_bond, 1));
+			assert_ok!(Pools::join(RuntimeOrigin::signed(20), pool_bond, 1, None));
 
 			assert_eq!(
 				pool_events_since_last_call(),
-				vec![Event::Bonded {
-					member: 20,
-					pool_id: 1,
-					bonded: 12_968_712_300_500_000_000,
-					joined: true
-				}]
+				vec![
+					Event::Bonded {
+						member: 20,
+						pool_id: 1,
+						bonded: 12_968_712_300_500_000_000,
+						points: 12_968_712_300_500_000_000,
+						joined: true
+					},
+					Event::PoolUpdated {
+						pool_id: 1,
+						points: 25_937_424_601_000_000_000,
+						balance: 25_937_424_601_000_000_000,
+					},
+				]
 			);
 
 			assert_ok!(Pools::claim_payout(RuntimeOrigin::signed(10)));
@@ -4910,12 +6143,12 @@ mod reward_counter_precision {
 
 			assert_eq!(
 				pool_events_since_last_call(),
-				vec![Event::PaidOut { member: 10, pool_id: 1, payout: 15937424600999999996 }]
+				vec![Event::PaidOut { member: 10, pool_id: 1, payout: 15937424600999999999 }]
 			);
 
 			// now let a small member join with 10 DOTs.
 			Balances::make_free_balance_be(&30, 20 * DOT);
-			assert_ok!(Pools::join(RuntimeOrigin::signed(30), 10 * DOT, 1));
+			assert_ok!(Pools::join(RuntimeOrigin::signed(30), 10 * DOT, 1, None));
 
 			// and give a reasonably small reward to the pool.
 			assert_ok!(Balances::mutate_account(&default_reward_account(), |a| a.free += DOT));
@@ -4924,7 +6157,18 @@ mod reward_counter_precision {
 			assert_eq!(
 				pool_events_since_last_call(),
 				vec![
-					Event::Bonded { member: 30, pool_id: 1, bonded: 100000000000, joined: true },
+					Event::Bonded {
+						member: 30,
+						pool_id: 1,
+						bonded: 100000000000,
+						points: 100000000000,
+						joined: true
+					},
+					Event::PoolUpdated {
+						pool_id: 1,
+						points: 25_937_424_701_000_000_000,
+						balance: 25_937_424_701_000_000_000,
+					},
 					// quite small, but working fine.
 					Event::PaidOut { member: 30, pool_id: 1, payout: 38 }
 				]
@@ -4945,7 +6189,13 @@ mod reward_counter_precision {
 						member: 10,
 						pool_id: 1,
 						bonded: 12_968_712_300_500_000_000,
+						points: 12_968_712_300_500_000_000,
 						joined: true,
+					},
+					Event::PoolUpdated {
+						pool_id: 1,
+						points: 12_968_712_300_500_000_000,
+						balance: 12_968_712_300_500_000_000,
 					}
 				]
 			);
@@ -4959,7 +6209,7 @@ mod reward_counter_precision {
 			// also set the reward counters to some large value.
 			Balances::make_free_balance_be(&20, pool_bond * 2);
 			assert_err!(
-				Pools::join(RuntimeOrigin::signed(20), pool_bond, 1),
+				Pools::join(RuntimeOrigin::signed(20), pool_bond, 1, None),
 				Error::<T>::OverflowRisk
 			);
 		})
@@ -4980,14 +6230,20 @@ mod reward_counter_precision {
 							member: 10,
 							pool_id: 1,
 							bonded: 2500000000000000000,
+							points: 2500000000000000000,
 							joined: true,
+						},
+						Event::PoolUpdated {
+							pool_id: 1,
+							points: 2500000000000000000,
+							balance: 2500000000000000000,
 						}
 					]
 				);
 
 				// and have a tiny fish join the pool as well..
 				Balances::make_free_balance_be(&20, 20 * DOT);
-				assert_ok!(Pools::join(RuntimeOrigin::signed(20), 10 * DOT, 1));
+				assert_ok!(Pools::join(RuntimeOrigin::signed(20), 10 * DOT, 1, None));
 
 				// earn some small rewards
 				assert_ok!(
@@ -5004,9 +6260,15 @@ mod reward_counter_precision {
 							member: 20,
 							pool_id: 1,
 							bonded: 100000000000,
+							points: 100000000000,
 							joined: true
 						},
-						Event::PaidOut { member: 10, pool_id: 1, payout: 9999997 }
+						Event::PoolUpdated {
+							pool_id: 1,
+							points: 2500000100000000000,
+							balance: 2500000100000000000,
+						},
+						Event::PaidOut { member: 10, pool_id: 1, payout: 9999999 }
 					]
 				);
 
@@ -5019,7 +6281,7 @@ mod reward_counter_precision {
 				assert_ok!(Pools::claim_payout(RuntimeOrigin::signed(10)));
 				assert_eq!(
 					pool_events_since_last_call(),
-					vec![Event::PaidOut { member: 10, pool_id: 1, payout: 10000000 }]
+					vec![Event::PaidOut { member: 10, pool_id: 1, payout: 9999999 }]
 				);
 
 				// earn some more rewards, this time 20 can also claim.
@@ -5032,7 +6294,7 @@ mod reward_counter_precision {
 				assert_eq!(
 					pool_events_since_last_call(),
 					vec![
-						Event::PaidOut { member: 10, pool_id: 1, payout: 10000000 },
+						Event::PaidOut { member: 10, pool_id: 1, payout: 9999999 },
 						Event::PaidOut { member: 20, pool_id: 1, payout: 1 }
 					]
 				);
@@ -5054,14 +6316,20 @@ mod reward_counter_precision {
 							member: 10,
 							pool_id: 1,
 							bonded: 2500000000000000000,
+							points: 2500000000000000000,
 							joined: true,
+						},
+						Event::PoolUpdated {
+							pool_id: 1,
+							points: 2500000000000000000,
+							balance: 2500000000000000000,
 						}
 					]
 				);
 
 				// and have a tiny fish join the pool as well..
 				Balances::make_free_balance_be(&20, 20 * DOT);
-				assert_ok!(Pools::join(RuntimeOrigin::signed(20), 10 * DOT, 1));
+				assert_ok!(Pools::join(RuntimeOrigin::signed(20), 10 * DOT, 1, None));
 
 				// earn some small rewards
 				assert_ok!(
@@ -5080,9 +6348,15 @@ mod reward_counter_precision {
 							member: 20,
 							pool_id: 1,
 							bonded: 100000000000,
+							points: 100000000000,
 							joined: true
 						},
-						Event::PaidOut { member: 10, pool_id: 1, payout: 9999997 }
+						Event::PoolUpdated {
+							pool_id: 1,
+							points: 2500000100000000000,
+							balance: 2500000100000000000,
+						},
+						Event::PaidOut { member: 10, pool_id: 1, payout: 9999999 }
 					]
 				);
 
@@ -5100,3 +6374,1137 @@ mod reward_counter_precision {
 			});
 	}
 }
+
+mod reconcile_reward_pool {
+	use super::*;
+	use sp_runtime::traits::BadOrigin;
+
+	#[test]
+	fn reconcile_reward_pool_works() {
+		ExtBuilder::default().add_members(vec![(20, 20)]).build_and_execute(|| {
+			// Given the pool earns 30, which is recorded by a joining member.
+			assert_ok!(Balances::mutate_account(&default_reward_account(), |a| a.free += 30));
+			Balances::make_free_balance_be(&30, 100);
+			assert_ok!(Pools::join(RuntimeOrigin::signed(30), 10, 1, None));
+			let reward_pool = RewardPools::<Runtime>::get(1).unwrap();
+			assert_eq!(reward_pool.expected_balance(), 30);
+
+			// When the reward account drifts below the recorded payouts.
+			assert_ok!(Balances::mutate_account(&default_reward_account(), |a| a.free -= 12));
+			let issuance = Balances::total_issuance();
+			let _ = pool_events_since_last_call();
+
+			// Then the audit reports it without touching anything.
+			assert_noop!(
+				Pools::reconcile_reward_pool(RuntimeOrigin::signed(10), 1, true),
+				BadOrigin
+			);
+			assert_noop!(
+				Pools::reconcile_reward_pool(RuntimeOrigin::root(), 2, true),
+				Error::<Runtime>::PoolNotFound
+			);
+			assert_ok!(Pools::reconcile_reward_pool(RuntimeOrigin::root(), 1, false));
+			assert_eq!(
+				pool_events_since_last_call(),
+				vec![Event::RewardPoolReconciled {
+					pool_id: 1,
+					expected: 30,
+					actual: 18,
+					adjusted: false
+				}]
+			);
+			assert_eq!(RewardPools::<Runtime>::get(1).unwrap(), reward_pool);
+
+			// and adjusts the records when asked to.
+			assert_ok!(Pools::reconcile_reward_pool(RuntimeOrigin::root(), 1, true));
+			assert_eq!(
+				pool_events_since_last_call(),
+				vec![Event::RewardPoolReconciled {
+					pool_id: 1,
+					expected: 30,
+					actual: 18,
+					adjusted: true
+				}]
+			);
+			assert_eq!(RewardPools::<Runtime>::get(1).unwrap().expected_balance(), 18);
+
+			// A surplus is regular income and is never adjusted.
+			assert_ok!(Balances::mutate_account(&default_reward_account(), |a| a.free += 5));
+			let reward_pool = RewardPools::<Runtime>::get(1).unwrap();
+			assert_ok!(Pools::reconcile_reward_pool(RuntimeOrigin::root(), 1, true));
+			assert_eq!(
+				pool_events_since_last_call(),
+				vec![Event::RewardPoolReconciled {
+					pool_id: 1,
+					expected: 18,
+					actual: 23,
+					adjusted: false
+				}]
+			);
+			assert_eq!(RewardPools::<Runtime>::get(1).unwrap(), reward_pool);
+
+			// No funds have been minted or burned.
+			assert_eq!(Balances::total_issuance(), issuance);
+			assert_eq!(
+				Balances::free_balance(&default_reward_account()),
+				ExistentialDeposit::get() + 23
+			);
+		});
+	}
+}
+
+mod commission {
+	use super::*;
+
+	#[test]
+	fn set_commission_works() {
+		ExtBuilder::default().build_and_execute(|| {
+			let commission = Some((Perbill::from_percent(10), 900));
+
+			// only the root can set it.
+			assert_noop!(
+				Pools::set_commission(RuntimeOrigin::signed(902), 1, commission.clone()),
+				Error::<Runtime>::DoesNotHavePermission
+			);
+			assert_noop!(
+				Pools::set_commission(RuntimeOrigin::signed(900), 2, commission.clone()),
+				Error::<Runtime>::PoolNotFound
+			);
+
+			// it cannot exceed the maximum.
+			assert_noop!(
+				Pools::set_commission(
+					RuntimeOrigin::signed(900),
+					1,
+					Some((MaxCommission::get() + Perbill::from_parts(1), 900))
+				),
+				Error::<Runtime>::CommissionTooHigh
+			);
+
+			assert_ok!(Pools::set_commission(RuntimeOrigin::signed(900), 1, commission.clone()));
+			assert_eq!(Commission::<Runtime>::get(1), commission);
+			assert_eq!(
+				pool_events_since_last_call(),
+				vec![
+					Event::Created { depositor: 10, pool_id: 1 },
+					Event::Bonded { member: 10, pool_id: 1, bonded: 10, points: 10, joined: true },
+					Event::PoolUpdated { pool_id: 1, points: 10, balance: 10 },
+					Event::CommissionUpdated { pool_id: 1, commission },
+				]
+			);
+
+			// and it can be removed again.
+			assert_ok!(Pools::set_commission(RuntimeOrigin::signed(900), 1, None));
+			assert!(!Commission::<Runtime>::contains_key(1));
+			assert_eq!(
+				pool_events_since_last_call(),
+				vec![Event::CommissionUpdated { pool_id: 1, commission: None }]
+			);
+		});
+	}
+
+	#[test]
+	fn commission_is_removed_with_the_pool() {
+		ExtBuilder::default().build_and_execute(|| {
+			assert_ok!(Pools::set_commission(
+				RuntimeOrigin::signed(900),
+				1,
+				Some((Perbill::from_percent(10), 900))
+			));
+
+			assert_ok!(Pools::set_state(RuntimeOrigin::signed(902), 1, PoolState::Destroying));
+			assert_ok!(fully_unbond_permissioned(10));
+			CurrentEra::set(3);
+			assert_ok!(Pools::withdraw_unbonded(RuntimeOrigin::signed(10), 10, 0));
+
+			assert!(pool_events_since_last_call().contains(&Event::Destroyed { pool_id: 1 }));
+			assert!(!Commission::<Runtime>::contains_key(1));
+		});
+	}
+}
+
+mod min_membership_duration {
+	use super::*;
+
+	#[test]
+	fn set_min_membership_duration_works() {
+		ExtBuilder::default().build_and_execute(|| {
+			// only the root can set it.
+			assert_noop!(
+				Pools::set_min_membership_duration(RuntimeOrigin::signed(902), 1, Some(5)),
+				Error::<Runtime>::DoesNotHavePermission
+			);
+			assert_noop!(
+				Pools::set_min_membership_duration(RuntimeOrigin::signed(900), 2, Some(5)),
+				Error::<Runtime>::PoolNotFound
+			);
+
+			// it cannot exceed the maximum.
+			assert_noop!(
+				Pools::set_min_membership_duration(
+					RuntimeOrigin::signed(900),
+					1,
+					Some(MaxMinMembershipDuration::get() + 1)
+				),
+				Error::<Runtime>::MinMembershipDurationTooLong
+			);
+
+			assert_ok!(Pools::set_min_membership_duration(RuntimeOrigin::signed(900), 1, Some(5)));
+			assert_eq!(MinMembershipDuration::<Runtime>::get(1), Some(5));
+			assert_eq!(
+				pool_events_since_last_call(),
+				vec![
+					Event::Created { depositor: 10, pool_id: 1 },
+					Event::Bonded { member: 10, pool_id: 1, bonded: 10, points: 10, joined: true },
+					Event::PoolUpdated { pool_id: 1, points: 10, balance: 10 },
+					Event::MinMembershipDurationUpdated { pool_id: 1, duration: Some(5) },
+				]
+			);
+
+			// and it can be removed again.
+			assert_ok!(Pools::set_min_membership_duration(RuntimeOrigin::signed(900), 1, None));
+			assert!(!MinMembershipDuration::<Runtime>::contains_key(1));
+			assert_eq!(
+				pool_events_since_last_call(),
+				vec![Event::MinMembershipDurationUpdated { pool_id: 1, duration: None }]
+			);
+		});
+	}
+
+	#[test]
+	fn unbond_respects_min_membership_duration() {
+		ExtBuilder::default().build_and_execute(|| {
+			// Given
+			assert_ok!(Pools::set_min_membership_duration(RuntimeOrigin::signed(900), 1, Some(5)));
+			System::set_block_number(3);
+			Balances::make_free_balance_be(&20, 100);
+
+			// When a member joins at block 3
+			assert_ok!(Pools::join(RuntimeOrigin::signed(20), 20, 1, None));
+			assert_eq!(PoolMembers::<Runtime>::get(20).unwrap().joined_at, 3);
+
+			// Then they cannot unbond right away, neither partially nor fully
+			assert_noop!(
+				Pools::unbond(RuntimeOrigin::signed(20), 20, 10),
+				Error::<Runtime>::MinMembershipDurationNotElapsed
+			);
+			assert_noop!(
+				Pools::fully_unbond(RuntimeOrigin::signed(20), 20),
+				Error::<Runtime>::MinMembershipDurationNotElapsed
+			);
+
+			// nor one block before the duration has passed.
+			System::set_block_number(7);
+			assert_noop!(
+				Pools::fully_unbond(RuntimeOrigin::signed(20), 20),
+				Error::<Runtime>::MinMembershipDurationNotElapsed
+			);
+
+			// When the duration has passed
+			System::set_block_number(8);
+
+			// Then they can unbond
+			assert_ok!(Pools::unbond(RuntimeOrigin::signed(20), 20, 10));
+			assert_ok!(Pools::fully_unbond(RuntimeOrigin::signed(20), 20));
+			assert_eq!(PoolMembers::<Runtime>::get(20).unwrap().active_points(), 0);
+		});
+	}
+
+	#[test]
+	fn kicks_and_destroying_pools_bypass_min_membership_duration() {
+		ExtBuilder::default()
+			.add_members(vec![(20, 20), (30, 30)])
+			.build_and_execute(|| {
+				// Given
+				let root = RuntimeOrigin::signed(900);
+				assert_ok!(Pools::set_min_membership_duration(root, 1, Some(10)));
+				assert_noop!(
+					Pools::fully_unbond(RuntimeOrigin::signed(20), 20),
+					Error::<Runtime>::MinMembershipDurationNotElapsed
+				);
+
+				// When the pool is blocked, then a member can be kicked
+				unsafe_set_state(1, PoolState::Blocked);
+				assert_ok!(Pools::fully_unbond(RuntimeOrigin::signed(902), 20));
+
+				// When the pool is destroying, then a member can leave by themselves
+				unsafe_set_state(1, PoolState::Destroying);
+				assert_ok!(Pools::fully_unbond(RuntimeOrigin::signed(30), 30));
+
+				assert_eq!(
+					pool_events_since_last_call(),
+					vec![
+						Event::Created { depositor: 10, pool_id: 1 },
+						Event::Bonded {
+							member: 10,
+							pool_id: 1,
+							bonded: 10,
+							points: 10,
+							joined: true
+						},
+						Event::PoolUpdated { pool_id: 1, points: 10, balance: 10 },
+						Event::Bonded {
+							member: 20,
+							pool_id: 1,
+							bonded: 20,
+							points: 20,
+							joined: true
+						},
+						Event::PoolUpdated { pool_id: 1, points: 30, balance: 30 },
+						Event::Bonded {
+							member: 30,
+							pool_id: 1,
+							bonded: 30,
+							points: 30,
+							joined: true
+						},
+						Event::PoolUpdated { pool_id: 1, points: 60, balance: 60 },
+						Event::MinMembershipDurationUpdated { pool_id: 1, duration: Some(10) },
+						Event::Unbonded { member: 20, pool_id: 1, balance: 20, points: 20, era: 3 },
+						Event::PoolUpdated { pool_id: 1, points: 40, balance: 40 },
+						Event::Unbonded { member: 30, pool_id: 1, balance: 30, points: 30, era: 3 },
+						Event::PoolUpdated { pool_id: 1, points: 10, balance: 10 },
+					]
+				);
+			});
+	}
+}
+
+mod total_value_locked {
+	use super::*;
+
+	#[test]
+	fn bonding_increases_tvl() {
+		ExtBuilder::default().build_and_execute(|| {
+			// the depositor of the default pool.
+			assert_eq!(Pools::total_value_locked(), 10);
+
+			// join
+			Balances::make_free_balance_be(&20, 100);
+			assert_ok!(Pools::join(RuntimeOrigin::signed(20), 20, 1, None));
+			assert_eq!(Pools::total_value_locked(), 30);
+
+			// bond extra from the free balance
+			assert_ok!(Pools::bond_extra(RuntimeOrigin::signed(20), BondExtra::FreeBalance(10)));
+			assert_eq!(Pools::total_value_locked(), 40);
+
+			// bond extra from the rewards, of which 20 owns 30 / 40.
+			Balances::make_free_balance_be(
+				&default_reward_account(),
+				ExistentialDeposit::get() + 40,
+			);
+			assert_ok!(Pools::bond_extra(RuntimeOrigin::signed(20), BondExtra::Rewards));
+			assert_eq!(Pools::total_value_locked(), 40 + 30);
+
+			// create another pool
+			Balances::make_free_balance_be(&30, 100);
+			assert_ok!(Pools::create(RuntimeOrigin::signed(30), 15, 30, 30, 30));
+			assert_eq!(Pools::total_value_locked(), 70 + 15);
+			assert_eq!(
+				Pools::total_value_locked(),
+				StakingMock::total_stake(&default_bonded_account()).unwrap() +
+					StakingMock::total_stake(&Pools::create_bonded_account(2)).unwrap()
+			);
+		});
+	}
+
+	#[test]
+	fn unbonding_keeps_tvl_and_withdrawing_decreases_it() {
+		ExtBuilder::default().add_members(vec![(20, 20)]).build_and_execute(|| {
+			assert_eq!(Pools::total_value_locked(), 30);
+
+			// unbonded funds are still locked.
+			assert_ok!(Pools::unbond(RuntimeOrigin::signed(20), 20, 15));
+			assert_eq!(Pools::total_value_locked(), 30);
+
+			// until they are withdrawn.
+			CurrentEra::set(StakingMock::bonding_duration());
+			assert_ok!(Pools::withdraw_unbonded(RuntimeOrigin::signed(20), 20, 0));
+			assert_eq!(Pools::total_value_locked(), 15);
+			assert_eq!(
+				Pools::total_value_locked(),
+				StakingMock::total_stake(&default_bonded_account()).unwrap()
+			);
+		});
+	}
+
+	#[test]
+	fn withdrawing_when_unbonding_decreases_tvl() {
+		ExtBuilder::default().add_members(vec![(20, 20)]).build_and_execute(|| {
+			assert_ok!(Pools::unbond(RuntimeOrigin::signed(20), 20, 10));
+			assert_eq!(Pools::total_value_locked(), 30);
+
+			// the staking system withdraws the first chunk to make room for the second one.
+			UnbondWithdraws::set(true);
+			assert_ok!(Pools::unbond(RuntimeOrigin::signed(20), 20, 5));
+			assert_eq!(Pools::total_value_locked(), 20);
+			assert_eq!(
+				Pools::total_value_locked(),
+				StakingMock::total_stake(&default_bonded_account()).unwrap()
+			);
+
+			// the member still gets all of their unbonded funds.
+			CurrentEra::set(StakingMock::bonding_duration());
+			let balance_before = Balances::free_balance(&20);
+			assert_ok!(Pools::withdraw_unbonded(RuntimeOrigin::signed(20), 20, 0));
+			assert_eq!(Balances::free_balance(&20), balance_before + 15);
+			assert_eq!(Pools::total_value_locked(), 15);
+		});
+	}
+
+	#[test]
+	fn pool_withdraw_unbonded_decreases_tvl() {
+		ExtBuilder::default().build_and_execute(|| {
+			// 5 unbonded directly against the pool account.
+			assert_ok!(StakingMock::unbond(&default_bonded_account(), 5));
+			assert_eq!(Pools::total_value_locked(), 10);
+
+			assert_ok!(Pools::pool_withdraw_unbonded(RuntimeOrigin::signed(10), 1, 0));
+			assert_eq!(Pools::total_value_locked(), 5);
+		});
+	}
+
+	#[test]
+	fn slashing_decreases_tvl() {
+		ExtBuilder::default()
+			.add_members(vec![(20, 20)])
+			.build_and_execute_with_each_adapter(|| {
+				assert_eq!(Pools::total_value_locked(), 30);
+
+				StakingMock::slash_by(1, 6);
+				assert_eq!(Pools::total_value_locked(), 24);
+				assert_eq!(
+					pool_events_since_last_call(),
+					vec![
+						Event::Created { depositor: 10, pool_id: 1 },
+						Event::Bonded {
+							member: 10,
+							pool_id: 1,
+							bonded: 10,
+							points: 10,
+							joined: true
+						},
+						Event::PoolUpdated { pool_id: 1, points: 10, balance: 10 },
+						Event::Bonded {
+							member: 20,
+							pool_id: 1,
+							bonded: 20,
+							points: 20,
+							joined: true
+						},
+						Event::PoolUpdated { pool_id: 1, points: 30, balance: 30 },
+						Event::PoolSlashed { pool_id: 1, balance: 24, slash_era: 0 },
+					]
+				);
+
+				// slashes of other accounts are ignored.
+				Pools::on_slash(&42, 0, &Default::default(), 10, 0);
+				assert_eq!(Pools::total_value_locked(), 24);
+			});
+	}
+}
+
+mod pool_updated_event {
+	use super::*;
+
+	#[test]
+	fn exchange_rate_can_be_reconstructed_from_events() {
+		ExtBuilder::default().add_members(vec![(20, 20)]).build_and_execute(|| {
+			// halve the value of each point, then bond and unbond at the new rate.
+			StakingMock::slash_by(1, 15);
+			Balances::make_free_balance_be(&30, ExistentialDeposit::get() + 15);
+			assert_ok!(Pools::join(RuntimeOrigin::signed(30), 15, 1, None));
+			assert_ok!(Pools::unbond(RuntimeOrigin::signed(20), 20, 20));
+
+			// replay the events, knowing nothing but what they report.
+			let (mut points, mut balance) = (0, 0);
+			for event in pool_events_since_last_call() {
+				match event {
+					// every bond into a non-empty pool is priced at the last reported rate.
+					Event::Bonded { bonded, points: issued, .. } if points > 0 =>
+						assert_eq!(issued, bonded * points / balance),
+					Event::PoolUpdated { points: new_points, balance: new_balance, .. } => {
+						points = new_points;
+						balance = new_balance;
+					},
+					Event::PoolSlashed { balance: new_balance, .. } => balance = new_balance,
+					_ => {},
+				}
+			}
+
+			assert_eq!((points, balance), (40, 20));
+			assert_eq!(BondedPool::<Runtime>::get(1).unwrap().points, points);
+			assert_eq!(StakingMock::active_stake(&default_bonded_account()).unwrap(), balance);
+		});
+	}
+}
+
+mod withdraw_orphaned {
+	use super::*;
+
+	#[test]
+	fn staking_calls_fail_once_the_ledger_is_missing() {
+		ExtBuilder::default()
+			.add_members(vec![(20, 20)])
+			.build_and_execute_with_each_adapter(|| {
+				assert_ok!(StakingMock::force_unstake(default_bonded_account()));
+				Balances::make_free_balance_be(&30, 100);
+				pool_events_since_last_call();
+
+				// none of the calls that would reach the staking system succeed, nor do they
+				// change the state of the pool.
+				assert_noop!(
+					Pools::join(RuntimeOrigin::signed(30), 10, 1, None),
+					Error::<Runtime>::BondedLedgerMissing
+				);
+				assert_noop!(
+					Pools::bond_extra(RuntimeOrigin::signed(20), BondExtra::FreeBalance(10)),
+					Error::<Runtime>::BondedLedgerMissing
+				);
+				assert_noop!(
+					Pools::unbond(RuntimeOrigin::signed(20), 20, 20),
+					Error::<Runtime>::BondedLedgerMissing
+				);
+				assert_noop!(
+					Pools::withdraw_unbonded(RuntimeOrigin::signed(20), 20, 0),
+					Error::<Runtime>::BondedLedgerMissing
+				);
+				assert_noop!(
+					Pools::pool_withdraw_unbonded(RuntimeOrigin::signed(20), 1, 0),
+					Error::<Runtime>::BondedLedgerMissing
+				);
+				assert_noop!(
+					Pools::nominate(RuntimeOrigin::signed(901), 1, vec![21]),
+					Error::<Runtime>::BondedLedgerMissing
+				);
+				assert_noop!(
+					Pools::chill(RuntimeOrigin::signed(901), 1),
+					Error::<Runtime>::BondedLedgerMissing
+				);
+				assert_eq!(pool_events_since_last_call(), vec![]);
+				assert_eq!(BondedPool::<Runtime>::get(1).unwrap().state, PoolState::Open);
+
+				// only withdrawing an orphaned member switches the pool to destroying.
+				assert_ok!(Pools::withdraw_orphaned(RuntimeOrigin::signed(20), 20));
+				assert_eq!(
+					pool_events_since_last_call(),
+					vec![
+						Event::StateChanged {
+							pool_id: 1,
+							new_state: PoolState::Destroying,
+							caller: 20
+						},
+						Event::LedgerLost { pool_id: 1, balance: 30 },
+						Event::Withdrawn { member: 20, pool_id: 1, balance: 20, points: 20 },
+						Event::MemberRemoved { pool_id: 1, member: 20 },
+					]
+				);
+				assert_eq!(BondedPool::<Runtime>::get(1).unwrap().state, PoolState::Destroying);
+				assert_eq!(Pools::total_value_locked(), 0);
+
+				// the depositor can still leave.
+				assert_ok!(Pools::withdraw_orphaned(RuntimeOrigin::signed(10), 10));
+				assert!(BondedPools::<Runtime>::get(1).is_none());
+			});
+	}
+
+	#[test]
+	fn active_points_are_valued_at_the_remaining_balance() {
+		ExtBuilder::default().add_members(vec![(20, 20)]).build_and_execute(|| {
+			// rewards were bonded, 30 points are worth 60.
+			StakingMock::set_bonded_balance(default_bonded_account(), 60);
+			Balances::make_free_balance_be(&default_bonded_account(), 60);
+			assert_ok!(Pools::unbond(RuntimeOrigin::signed(20), 20, 10));
+			assert_eq!(SubPoolsStorage::<Runtime>::get(1).unwrap().sum_unbonding_balance(), 20);
+
+			assert_ok!(StakingMock::force_unstake(default_bonded_account()));
+			let before = Balances::free_balance(&20);
+
+			// 10 active points are worth 20, like the 10 points that are unbonding.
+			assert_ok!(Pools::withdraw_orphaned(RuntimeOrigin::signed(20), 20));
+			assert_eq!(Balances::free_balance(&20), before + 40);
+
+			// the depositor takes the rest.
+			let before = Balances::free_balance(&10);
+			assert_ok!(Pools::withdraw_orphaned(RuntimeOrigin::signed(10), 10));
+			assert_eq!(Balances::free_balance(&10), before + 20);
+			assert_eq!(Balances::free_balance(&default_bonded_account()), 0);
+		});
+	}
+
+	#[test]
+	fn members_recover_their_share() {
+		ExtBuilder::default()
+			.add_members(vec![(20, 20), (21, 10)])
+			.build_and_execute_with_each_adapter(|| {
+				// 21 is unbonding half of its stake when the ledger disappears.
+				assert_ok!(Pools::unbond(RuntimeOrigin::signed(21), 21, 5));
+				Balances::make_free_balance_be(
+					&default_reward_account(),
+					ExistentialDeposit::get() + 35,
+				);
+				assert_noop!(
+					Pools::withdraw_orphaned(RuntimeOrigin::signed(20), 20),
+					Error::<Runtime>::BondedLedgerNotMissing
+				);
+				pool_events_since_last_call();
+
+				assert_ok!(StakingMock::force_unstake(default_bonded_account()));
+				assert_eq!(StakeAdapterMock::total_balance(&default_bonded_account()), 40);
+
+				// the depositor has to leave last.
+				assert_noop!(
+					Pools::withdraw_orphaned(RuntimeOrigin::signed(10), 10),
+					Error::<Runtime>::DepositorNotLastMember
+				);
+
+				// anyone can recover a member, which destroys the pool on first use.
+				assert_ok!(Pools::withdraw_orphaned(RuntimeOrigin::signed(99), 20));
+				assert_eq!(
+					pool_events_since_last_call(),
+					vec![
+						Event::StateChanged {
+							pool_id: 1,
+							new_state: PoolState::Destroying,
+							caller: 99
+						},
+						Event::LedgerLost { pool_id: 1, balance: 40 },
+						Event::PaidOut { member: 20, pool_id: 1, payout: 20 },
+						Event::Withdrawn { member: 20, pool_id: 1, balance: 20, points: 20 },
+						Event::MemberRemoved { pool_id: 1, member: 20 },
+					]
+				);
+				assert_eq!(Balances::free_balance(&20), 20 + 20 + 20);
+				assert_eq!(Pools::total_value_locked(), 0);
+
+				// unbonding points are valued at the balance of their unbonding pool.
+				assert_ok!(Pools::withdraw_orphaned(RuntimeOrigin::signed(21), 21));
+				assert_eq!(
+					pool_events_since_last_call(),
+					vec![
+						Event::PaidOut { member: 21, pool_id: 1, payout: 5 },
+						Event::Withdrawn { member: 21, pool_id: 1, balance: 10, points: 10 },
+						Event::MemberRemoved { pool_id: 1, member: 21 },
+					]
+				);
+				assert_eq!(Balances::free_balance(&21), 10 + 10 + 5);
+
+				// the depositor takes the rest and the pool is dissolved.
+				assert_ok!(Pools::withdraw_orphaned(RuntimeOrigin::signed(10), 10));
+				assert_eq!(
+					pool_events_since_last_call(),
+					vec![
+						Event::PaidOut { member: 10, pool_id: 1, payout: 10 },
+						Event::Withdrawn { member: 10, pool_id: 1, balance: 10, points: 10 },
+						Event::MemberRemoved { pool_id: 1, member: 10 },
+						Event::Destroyed { pool_id: 1 },
+					]
+				);
+				assert_eq!(StakeAdapterMock::total_balance(&default_bonded_account()), 0);
+				assert_eq!(Balances::reserved_balance(&10), 0);
+				assert!(BondedPools::<Runtime>::get(1).is_none());
+				assert!(!PoolMembers::<Runtime>::contains_key(10));
+			});
+	}
+}
+
+mod members_index {
+	use super::*;
+
+	fn sorted_members(pool_id: PoolId) -> Vec<AccountId> {
+		let mut members = Pools::pool_members(pool_id, 0, u32::MAX);
+		members.sort();
+		members
+	}
+
+	#[test]
+	fn join_and_leave_maintain_the_index() {
+		ExtBuilder::default().add_members(vec![(20, 20)]).build_and_execute(|| {
+			assert_eq!(sorted_members(1), vec![10, 20]);
+
+			Balances::make_free_balance_be(&30, 100);
+			assert_ok!(Pools::join(RuntimeOrigin::signed(30), 10, 1, None));
+			assert_eq!(sorted_members(1), vec![10, 20, 30]);
+
+			// unbonding members are still listed, until they are removed.
+			assert_ok!(Pools::unbond(RuntimeOrigin::signed(20), 20, 20));
+			assert_eq!(sorted_members(1), vec![10, 20, 30]);
+			CurrentEra::set(3);
+			assert_ok!(Pools::withdraw_unbonded(RuntimeOrigin::signed(20), 20, 0));
+			assert_eq!(sorted_members(1), vec![10, 30]);
+
+			// the list can be paged through.
+			let first = Pools::pool_members(1, 0, 1);
+			let second = Pools::pool_members(1, 1, 1);
+			assert_eq!(first.len(), 1);
+			assert_eq!(second.len(), 1);
+			assert_ne!(first, second);
+			assert!(Pools::pool_members(1, 2, 1).is_empty());
+			assert!(Pools::pool_members(2, 0, 10).is_empty());
+		});
+	}
+
+	#[test]
+	fn migrate_members_indexes_in_chunks() {
+		ExtBuilder::default().add_members(vec![(20, 20)]).build_and_execute(|| {
+			Balances::make_free_balance_be(&30, 100);
+			assert_ok!(Pools::create(RuntimeOrigin::signed(30), 15, 30, 30, 30));
+
+			// simulate pools that existed before the index.
+			let _ = PoolMembersByPool::<Runtime>::clear(u32::MAX, None);
+			PoolMembersIndexCursor::<Runtime>::put(None::<AccountId>);
+			pool_events_since_last_call();
+
+			// all pools are indexed in one pass over the members.
+			assert_ok!(Pools::migrate_members(RuntimeOrigin::signed(99), 2));
+			assert!(PoolMembersIndexCursor::<Runtime>::exists());
+			assert_ok!(Pools::migrate_members(RuntimeOrigin::signed(99), 2));
+			assert_eq!(
+				pool_events_since_last_call(),
+				vec![
+					Event::MembersIndexed { checked: 2, complete: false },
+					Event::MembersIndexed { checked: 1, complete: true },
+				]
+			);
+			assert!(!PoolMembersIndexCursor::<Runtime>::exists());
+			assert_eq!(sorted_members(1), vec![10, 20]);
+			assert_eq!(sorted_members(2), vec![30]);
+
+			assert_noop!(
+				Pools::migrate_members(RuntimeOrigin::signed(99), 1),
+				Error::<Runtime>::MembersAlreadyIndexed
+			);
+		});
+	}
+}
+
+mod switch_pool {
+	use super::*;
+
+	fn sorted_members(pool_id: PoolId) -> Vec<AccountId> {
+		let mut members = Pools::pool_members(pool_id, 0, u32::MAX);
+		members.sort();
+		members
+	}
+
+	#[test]
+	fn switch_from_unexposed_pool_works() {
+		ExtBuilder::default().add_members(vec![(40, 40)]).build_and_execute(|| {
+			Balances::make_free_balance_be(&20, 100);
+			assert_ok!(Pools::create(RuntimeOrigin::signed(20), 10, 20, 20, 20));
+			// pool 1 has been exposed, but not in the last `BondingDuration` eras.
+			CurrentEra::set(5);
+			Exposures::set(&vec![(default_bonded_account(), 1)]);
+			// and has some rewards for its members.
+			Balances::make_free_balance_be(
+				&default_reward_account(),
+				Balances::minimum_balance() + 50,
+			);
+			pool_events_since_last_call();
+
+			assert_ok!(Pools::switch_pool(RuntimeOrigin::signed(40), 2));
+			assert_eq!(
+				pool_events_since_last_call(),
+				vec![Event::SwitchRequested { member: 40, pool_id: 1, new_pool_id: 2 }]
+			);
+			// nothing moves until the request is processed.
+			assert_eq!(PoolMembers::<Runtime>::get(40).unwrap().pool_id, 1);
+
+			Pools::on_idle(1, Weight::MAX);
+			assert_eq!(
+				pool_events_since_last_call(),
+				vec![
+					Event::PaidOut { member: 40, pool_id: 1, payout: 40 },
+					Event::Switched {
+						member: 40,
+						pool_id: 1,
+						new_pool_id: 2,
+						balance: 40,
+						points: 40
+					},
+					Event::PoolUpdated { pool_id: 1, points: 10, balance: 10 },
+					Event::PoolUpdated { pool_id: 2, points: 50, balance: 50 },
+				]
+			);
+			assert_eq!(SwitchPoolRequests::<Runtime>::count(), 0);
+
+			let member = PoolMembers::<Runtime>::get(40).unwrap();
+			assert_eq!((member.pool_id, member.points), (2, 40));
+			assert_eq!(BondedPool::<Runtime>::get(1).unwrap().member_counter, 1);
+			assert_eq!(BondedPool::<Runtime>::get(2).unwrap().member_counter, 2);
+			assert_eq!(sorted_members(1), vec![10]);
+			assert_eq!(sorted_members(2), vec![20, 40]);
+
+			// the funds moved without unbonding.
+			assert_eq!(StakingMock::total_stake(&default_bonded_account()).unwrap(), 10);
+			assert_eq!(StakingMock::active_stake(&Pools::create_bonded_account(2)).unwrap(), 50);
+			assert_eq!(TotalValueLocked::<Runtime>::get(), 60);
+		});
+	}
+
+	#[test]
+	fn switch_from_exposed_pool_fails() {
+		ExtBuilder::default().add_members(vec![(40, 40)]).build_and_execute(|| {
+			Balances::make_free_balance_be(&20, 100);
+			assert_ok!(Pools::create(RuntimeOrigin::signed(20), 10, 20, 20, 20));
+			// pool 1 has been exposed 3 eras ago, which can still be slashed.
+			CurrentEra::set(5);
+			Exposures::set(&vec![(default_bonded_account(), 2)]);
+			pool_events_since_last_call();
+
+			assert_ok!(Pools::switch_pool(RuntimeOrigin::signed(40), 2));
+			Pools::on_idle(1, Weight::MAX);
+			assert_eq!(
+				pool_events_since_last_call(),
+				vec![
+					Event::SwitchRequested { member: 40, pool_id: 1, new_pool_id: 2 },
+					Event::SwitchFailed {
+						member: 40,
+						new_pool_id: 2,
+						error: Error::<Runtime>::PoolExposed.into()
+					},
+				]
+			);
+			assert_eq!(SwitchPoolRequests::<Runtime>::count(), 0);
+
+			// nothing has changed and the member has to unbond instead.
+			let member = PoolMembers::<Runtime>::get(40).unwrap();
+			assert_eq!((member.pool_id, member.points), (1, 40));
+			assert_eq!(StakingMock::active_stake(&default_bonded_account()).unwrap(), 50);
+			assert_eq!(sorted_members(2), vec![20]);
+			assert_ok!(Pools::unbond(RuntimeOrigin::signed(40), 40, 40));
+		});
+	}
+
+	#[test]
+	fn switch_pool_errors_correctly() {
+		let members = vec![(40, 40), (50, 50)];
+		ExtBuilder::default().add_members(members).build_and_execute(|| {
+			Balances::make_free_balance_be(&20, 100);
+			assert_ok!(Pools::create(RuntimeOrigin::signed(20), 10, 20, 20, 20));
+
+			assert_noop!(
+				Pools::switch_pool(RuntimeOrigin::signed(60), 2),
+				Error::<Runtime>::PoolMemberNotFound
+			);
+			assert_noop!(
+				Pools::switch_pool(RuntimeOrigin::signed(40), 1),
+				Error::<Runtime>::SwitchToSamePool
+			);
+			assert_noop!(
+				Pools::switch_pool(RuntimeOrigin::signed(40), 3),
+				Error::<Runtime>::PoolNotFound
+			);
+			assert_noop!(
+				Pools::switch_pool(RuntimeOrigin::signed(10), 2),
+				Error::<Runtime>::DepositorCannotSwitch
+			);
+
+			assert_ok!(Pools::unbond(RuntimeOrigin::signed(50), 50, 10));
+			assert_noop!(
+				Pools::switch_pool(RuntimeOrigin::signed(50), 2),
+				Error::<Runtime>::SwitchWhileUnbonding
+			);
+
+			assert_ok!(Pools::switch_pool(RuntimeOrigin::signed(40), 2));
+			assert_noop!(
+				Pools::switch_pool(RuntimeOrigin::signed(40), 2),
+				Error::<Runtime>::AlreadySwitching
+			);
+
+			// requests are only processed if there is enough weight left.
+			assert_eq!(Pools::on_idle(1, Weight::zero()), Weight::zero());
+			assert_eq!(SwitchPoolRequests::<Runtime>::count(), 1);
+			Pools::on_idle(1, Weight::MAX);
+			assert_eq!(PoolMembers::<Runtime>::get(40).unwrap().pool_id, 2);
+		});
+	}
+}
+
+mod member_balance {
+	use super::*;
+
+	#[test]
+	fn member_balance_works() {
+		ExtBuilder::default().add_members(vec![(20, 20)]).build_and_execute(|| {
+			assert_eq!(Pools::points_to_balance(1, 10), Some(10));
+			assert_eq!(Pools::points_to_balance(2, 10), None);
+			assert_eq!(Pools::member_balance(20), Some(20));
+			assert_eq!(Pools::member_balance(21), None);
+
+			// unbonding points are included.
+			assert_ok!(Pools::unbond(RuntimeOrigin::signed(20), 20, 15));
+			assert_eq!(Pools::member_balance(20), Some(20));
+
+			// and so are slashes of the bonded and the unbonding pool.
+			StakingMock::set_bonded_balance(default_bonded_account(), 5);
+			Pools::on_slash(&default_bonded_account(), 5, &BTreeMap::from([(3, 5)]), 20, 0);
+			assert_eq!(Pools::points_to_balance(1, 10), Some(3));
+			assert_eq!(Pools::member_balance(10), Some(3));
+			assert_eq!(Pools::member_balance(20), Some(1 + 5));
+		});
+	}
+
+	#[test]
+	fn member_balance_matches_withdrawn_balance_after_slash() {
+		ExtBuilder::default()
+			.add_members(vec![(20, 20), (30, 30)])
+			.build_and_execute(|| {
+				assert_ok!(Pools::unbond(RuntimeOrigin::signed(30), 30, 30));
+				assert_ok!(Pools::unbond(RuntimeOrigin::signed(20), 20, 10));
+				assert_eq!(Pools::member_balance(20), Some(20));
+				assert_eq!(Pools::member_balance(30), Some(30));
+
+				// slash the active stake by half and the unbonding stake by a quarter.
+				let bonded = default_bonded_account();
+				StakingMock::set_bonded_balance(bonded, 10);
+				UnbondingBalanceMap::set(&BTreeMap::from([(bonded, 30)]));
+				Balances::make_free_balance_be(&bonded, 40);
+				Pools::on_slash(&bonded, 10, &BTreeMap::from([(3, 30)]), 20, 0);
+				assert_eq!(Pools::points_to_balance(1, 10), Some(5));
+				assert_eq!(Pools::member_balance(20), Some(5 + 7));
+				assert_eq!(Pools::member_balance(30), Some(22));
+
+				CurrentEra::set(StakingMock::bonding_duration());
+				let free = Balances::free_balance(30);
+				assert_ok!(Pools::withdraw_unbonded(RuntimeOrigin::signed(30), 30, 0));
+				assert_eq!(Balances::free_balance(30), free + 22);
+				assert_eq!(Pools::member_balance(30), None);
+
+				// the rounding left behind by 30 is now worth more to 20.
+				assert_eq!(Pools::member_balance(20), Some(5 + 8));
+				let free = Balances::free_balance(20);
+				assert_ok!(Pools::withdraw_unbonded(RuntimeOrigin::signed(20), 20, 0));
+				assert_eq!(Balances::free_balance(20), free + 8);
+				assert_eq!(Pools::member_balance(20), Some(5));
+
+				// active points are unbonded at the same rate.
+				assert_ok!(Pools::unbond(RuntimeOrigin::signed(20), 20, 10));
+				assert_eq!(SubPoolsStorage::<Runtime>::get(1).unwrap().with_era[&6].balance, 5);
+				assert_eq!(Pools::member_balance(20), Some(5));
+			});
+	}
+}
+
+mod adjust_pool_deposit {
+	use super::*;
+
+	#[test]
+	fn adjust_pool_deposit_returns_excess_to_depositor() {
+		ExtBuilder::default().build_and_execute(|| {
+			// Given the pool has earned 10.
+			assert_ok!(Balances::mutate_account(&default_reward_account(), |a| a.free += 10));
+			assert_eq!(RewardAccountDeposits::<Runtime>::get(1), 5);
+			assert_noop!(
+				Pools::adjust_pool_deposit(RuntimeOrigin::signed(11), 2),
+				Error::<Runtime>::PoolNotFound
+			);
+			assert_noop!(
+				Pools::adjust_pool_deposit(RuntimeOrigin::signed(11), 1),
+				Error::<Runtime>::NothingToAdjust
+			);
+
+			// When the existential deposit is lowered, the rewards stay the same.
+			ExistentialDeposit::set(2);
+			assert_eq!(RewardPool::<Runtime>::current_balance(1), 10);
+			let depositor_balance = Balances::free_balance(&10);
+			let _ = pool_events_since_last_call();
+
+			// Then anyone can return the excess to the depositor.
+			assert_ok!(Pools::adjust_pool_deposit(RuntimeOrigin::signed(11), 1));
+			assert_eq!(
+				pool_events_since_last_call(),
+				vec![Event::MinBalanceExcessAdjusted { pool_id: 1, amount: 3 }]
+			);
+			assert_eq!(Balances::free_balance(&10), depositor_balance + 3);
+			assert_eq!(Balances::free_balance(&default_reward_account()), 12);
+			assert_eq!(RewardAccountDeposits::<Runtime>::get(1), 2);
+			assert_eq!(RewardPool::<Runtime>::current_balance(1), 10);
+			assert_noop!(
+				Pools::adjust_pool_deposit(RuntimeOrigin::signed(11), 1),
+				Error::<Runtime>::NothingToAdjust
+			);
+
+			// and the rewards can still be claimed in full.
+			assert_ok!(Pools::claim_payout(RuntimeOrigin::signed(10)));
+			assert_eq!(
+				pool_events_since_last_call(),
+				vec![Event::PaidOut { member: 10, pool_id: 1, payout: 10 }]
+			);
+			assert_eq!(Balances::free_balance(&default_reward_account()), 2);
+		});
+	}
+
+	#[test]
+	fn adjust_pool_deposit_charges_shortfall_from_depositor() {
+		ExtBuilder::default().build_and_execute(|| {
+			// Given the pool has earned 10.
+			assert_ok!(Balances::mutate_account(&default_reward_account(), |a| a.free += 10));
+
+			// When the existential deposit is raised, the rewards stay the same.
+			ExistentialDeposit::set(8);
+			assert_eq!(RewardPool::<Runtime>::current_balance(1), 10);
+			let depositor_balance = Balances::free_balance(&10);
+			let _ = pool_events_since_last_call();
+
+			// Then anyone can make the depositor top up the reward account.
+			assert_ok!(Pools::adjust_pool_deposit(RuntimeOrigin::signed(11), 1));
+			assert_eq!(
+				pool_events_since_last_call(),
+				vec![Event::MinBalanceDeficitAdjusted { pool_id: 1, amount: 3 }]
+			);
+			assert_eq!(Balances::free_balance(&10), depositor_balance - 3);
+			assert_eq!(Balances::free_balance(&default_reward_account()), 18);
+			assert_eq!(RewardAccountDeposits::<Runtime>::get(1), 8);
+			assert_eq!(RewardPool::<Runtime>::current_balance(1), 10);
+
+			// and claiming the rewards in full leaves the reward account alive.
+			assert_ok!(Pools::claim_payout(RuntimeOrigin::signed(10)));
+			assert_eq!(
+				pool_events_since_last_call(),
+				vec![Event::PaidOut { member: 10, pool_id: 1, payout: 10 }]
+			);
+			assert_eq!(Balances::free_balance(&default_reward_account()), 8);
+		});
+	}
+}
+
+mod stake_adapter {
+	use super::*;
+
+	/// Run `test` against a pool that `members` joined, once with each adapter of the mock.
+	fn with_each_adapter(members: Vec<(AccountId, Balance)>, test: impl Fn()) {
+		ExtBuilder::default()
+			.add_members(members)
+			.build_and_execute_with_each_adapter(test);
+	}
+
+	#[test]
+	fn bond_holds_member_funds() {
+		with_each_adapter(vec![(20, 10)], || {
+			let pool_account = default_bonded_account();
+			assert_eq!(Balances::free_balance(&20), 10);
+
+			assert_ok!(Pools::bond_extra(RuntimeOrigin::signed(20), BondExtra::FreeBalance(2)));
+			assert_eq!(Balances::free_balance(&20), 8);
+
+			// the funds count towards the stake of the pool either way,
+			assert_eq!(StakingMock::active_stake(&pool_account).unwrap(), 22);
+			assert_eq!(StakeAdapterMock::total_balance(&pool_account), 22);
+			assert_eq!(TotalValueLocked::<Runtime>::get(), 22);
+
+			// but only stay on the account of the member when delegated.
+			if DelegateStake::get() {
+				assert_eq!(Balances::reserved_balance(&20), 12);
+				assert_eq!(DelegateStakeMock::delegation(20, pool_account), 12);
+				assert_eq!(Balances::free_balance(&pool_account), 0);
+			} else {
+				assert_eq!(Balances::reserved_balance(&20), 0);
+				assert_eq!(Balances::free_balance(&pool_account), 22);
+			}
+		});
+	}
+
+	#[test]
+	fn withdraw_releases_member_funds() {
+		with_each_adapter(vec![(20, 10)], || {
+			assert_ok!(fully_unbond_permissioned(20));
+			assert_eq!(StakingMock::active_stake(&default_bonded_account()).unwrap(), 10);
+			// nothing is released while unbonding.
+			assert_eq!(Balances::free_balance(&20), 10);
+
+			CurrentEra::set(3);
+			assert_ok!(Pools::withdraw_unbonded(RuntimeOrigin::signed(20), 20, 0));
+			assert!(!PoolMembers::<Runtime>::contains_key(20));
+			assert_eq!(Balances::free_balance(&20), 20);
+			assert_eq!(Balances::reserved_balance(&20), 0);
+			assert_eq!(StakeAdapterMock::total_balance(&default_bonded_account()), 10);
+		});
+	}
+
+	#[test]
+	fn slash_reaches_member_funds() {
+		with_each_adapter(vec![(20, 10)], || {
+			// half of the stake of the pool is lost.
+			StakingMock::slash_by(1, 10);
+			if DelegateStake::get() {
+				assert_eq!(Balances::reserved_balance(&10), 5);
+				assert_eq!(Balances::reserved_balance(&20), 5);
+			}
+
+			assert_ok!(fully_unbond_permissioned(20));
+			CurrentEra::set(3);
+			assert_ok!(Pools::withdraw_unbonded(RuntimeOrigin::signed(20), 20, 0));
+
+			// 20 gets back half of what it bonded.
+			assert_eq!(
+				pool_events_since_last_call().last(),
+				Some(&Event::MemberRemoved { pool_id: 1, member: 20 })
+			);
+			assert_eq!(Balances::free_balance(&20), 15);
+			assert_eq!(Balances::reserved_balance(&20), 0);
+		});
+	}
+
+	#[test]
+	fn slash_rounding_is_borne_by_the_last_delegator() {
+		ExtBuilder::default()
+			.add_members(vec![(20, 20)])
+			.delegate_stake(true)
+			.build_and_execute(|| {
+				let pool_account = default_bonded_account();
+				StakingMock::slash_by(1, 7);
+
+				// 10 bears 7 * 10 / 30 = 2, 20 bears 7 * 20 / 30 = 4 and the remaining 1.
+				assert_eq!(DelegateStakeMock::delegation(10, pool_account), 8);
+				assert_eq!(DelegateStakeMock::delegation(20, pool_account), 15);
+				assert_eq!(Balances::reserved_balance(&10), 8);
+				assert_eq!(Balances::reserved_balance(&20), 15);
+				assert_eq!(StakeAdapterMock::total_balance(&pool_account), 23);
+				assert_eq!(StakingMock::active_stake(&pool_account).unwrap(), 23);
+			});
+	}
+
+	#[test]
+	fn switch_moves_member_funds() {
+		with_each_adapter(vec![(40, 40)], || {
+			Balances::make_free_balance_be(&20, 100);
+			assert_ok!(Pools::create(RuntimeOrigin::signed(20), 10, 20, 20, 20));
+			let new_pool_account = Pools::create_bonded_account(2);
+
+			assert_ok!(Pools::switch_pool(RuntimeOrigin::signed(40), 2));
+			Pools::on_idle(1, Weight::MAX);
+			assert_eq!(PoolMembers::<Runtime>::get(40).unwrap().pool_id, 2);
+
+			// the funds moved without unbonding.
+			assert_eq!(StakingMock::active_stake(&default_bonded_account()).unwrap(), 10);
+			assert_eq!(StakingMock::active_stake(&new_pool_account).unwrap(), 50);
+			assert_eq!(StakeAdapterMock::total_balance(&default_bonded_account()), 10);
+			assert_eq!(StakeAdapterMock::total_balance(&new_pool_account), 50);
+			assert_eq!(Balances::free_balance(&40), 40);
+			if DelegateStake::get() {
+				assert_eq!(Balances::reserved_balance(&40), 40);
+				assert_eq!(DelegateStakeMock::delegation(40, default_bonded_account()), 0);
+				assert_eq!(DelegateStakeMock::delegation(40, new_pool_account), 40);
+			}
+		});
+	}
+
+	#[test]
+	fn dissolving_pool_releases_all_funds() {
+		with_each_adapter(vec![], || {
+			assert_eq!(Balances::free_balance(&10), 35);
+
+			assert_ok!(Pools::set_state(RuntimeOrigin::signed(902), 1, PoolState::Destroying));
+			assert_ok!(fully_unbond_permissioned(10));
+			CurrentEra::set(3);
+			assert_ok!(Pools::withdraw_unbonded(RuntimeOrigin::signed(10), 10, 0));
+			assert!(!BondedPools::<Runtime>::contains_key(1));
+
+			// the bond and the deposit of the reward account are back.
+			assert_eq!(Balances::free_balance(&10), 50);
+			assert_eq!(Balances::reserved_balance(&10), 0);
+			assert_eq!(StakeAdapterMock::total_balance(&default_bonded_account()), 0);
+		});
+	}
+}
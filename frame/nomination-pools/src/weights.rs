@@ -62,6 +62,14 @@ pub trait WeightInfo {
 	fn set_configs() -> Weight;
 	fn update_roles() -> Weight;
 	fn chill() -> Weight;
+	fn sweep_dust() -> Weight;
+	fn reconcile_reward_pool() -> Weight;
+	fn set_min_membership_duration() -> Weight;
+	fn migrate_members(n: u32, ) -> Weight;
+	fn switch_pool() -> Weight;
+	fn process_switch_request(v: u32, ) -> Weight;
+	fn adjust_pool_deposit() -> Weight;
+	fn set_commission() -> Weight;
 }
 
 /// Weights for pallet_nomination_pools using the Substrate node and recommended hardware.
@@ -80,11 +88,12 @@ impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
 	// Storage: Balances Locks (r:1 w:1)
 	// Storage: VoterList ListNodes (r:3 w:3)
 	// Storage: VoterList ListBags (r:2 w:2)
+	// Storage: NominationPools PoolMembersByPool (r:0 w:1)
 	fn join() -> Weight {
 		// Minimum execution time: 159_948 nanoseconds.
 		Weight::from_ref_time(161_133_000 as u64)
 			.saturating_add(T::DbWeight::get().reads(17 as u64))
-			.saturating_add(T::DbWeight::get().writes(12 as u64))
+			.saturating_add(T::DbWeight::get().writes(13 as u64))
 	}
 	// Storage: NominationPools PoolMembers (r:1 w:1)
 	// Storage: NominationPools BondedPools (r:1 w:1)
@@ -119,12 +128,13 @@ impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
 	// Storage: NominationPools PoolMembers (r:1 w:1)
 	// Storage: NominationPools BondedPools (r:1 w:1)
 	// Storage: NominationPools RewardPools (r:1 w:1)
-	// Storage: System Account (r:1 w:1)
+	// Storage: NominationPools Commission (r:1 w:0)
+	// Storage: System Account (r:2 w:2)
 	fn claim_payout() -> Weight {
-		// Minimum execution time: 64_560 nanoseconds.
-		Weight::from_ref_time(64_950_000 as u64)
-			.saturating_add(T::DbWeight::get().reads(4 as u64))
-			.saturating_add(T::DbWeight::get().writes(4 as u64))
+		// Minimum execution time: 88_417 nanoseconds.
+		Weight::from_ref_time(89_301_000 as u64)
+			.saturating_add(T::DbWeight::get().reads(6 as u64))
+			.saturating_add(T::DbWeight::get().writes(5 as u64))
 	}
 	// Storage: NominationPools PoolMembers (r:1 w:1)
 	// Storage: NominationPools BondedPools (r:1 w:1)
@@ -169,6 +179,7 @@ impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
 	// Storage: Balances Locks (r:1 w:1)
 	// Storage: System Account (r:1 w:1)
 	// Storage: NominationPools CounterForPoolMembers (r:1 w:1)
+	// Storage: NominationPools PoolMembersByPool (r:0 w:1)
 	/// The range of component `s` is `[0, 100]`.
 	fn withdraw_unbonded_update(s: u32, ) -> Weight {
 		// Minimum execution time: 111_156 nanoseconds.
@@ -176,7 +187,7 @@ impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
 			// Standard Error: 655
 			.saturating_add(Weight::from_ref_time(53_711 as u64).saturating_mul(s as u64))
 			.saturating_add(T::DbWeight::get().reads(9 as u64))
-			.saturating_add(T::DbWeight::get().writes(7 as u64))
+			.saturating_add(T::DbWeight::get().writes(8 as u64))
 	}
 	// Storage: NominationPools PoolMembers (r:1 w:1)
 	// Storage: Staking CurrentEra (r:1 w:0)
@@ -198,6 +209,7 @@ impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
 	// Storage: NominationPools Metadata (r:1 w:1)
 	// Storage: NominationPools CounterForBondedPools (r:1 w:1)
 	// Storage: Staking Payee (r:0 w:1)
+	// Storage: NominationPools PoolMembersByPool (r:0 w:1)
 	/// The range of component `s` is `[0, 100]`.
 	fn withdraw_unbonded_kill(s: u32, ) -> Weight {
 		// Minimum execution time: 168_270 nanoseconds.
@@ -205,7 +217,7 @@ impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
 			// Standard Error: 1_506
 			.saturating_add(Weight::from_ref_time(1_258 as u64).saturating_mul(s as u64))
 			.saturating_add(T::DbWeight::get().reads(20 as u64))
-			.saturating_add(T::DbWeight::get().writes(17 as u64))
+			.saturating_add(T::DbWeight::get().writes(18 as u64))
 	}
 	// Storage: NominationPools LastPoolId (r:1 w:1)
 	// Storage: Staking MinNominatorBond (r:1 w:0)
@@ -228,11 +240,12 @@ impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
 	// Storage: NominationPools CounterForReversePoolIdLookup (r:1 w:1)
 	// Storage: NominationPools BondedPools (r:1 w:1)
 	// Storage: Staking Payee (r:0 w:1)
+	// Storage: NominationPools PoolMembersByPool (r:0 w:1)
 	fn create() -> Weight {
 		// Minimum execution time: 146_153 nanoseconds.
 		Weight::from_ref_time(146_955_000 as u64)
 			.saturating_add(T::DbWeight::get().reads(21 as u64))
-			.saturating_add(T::DbWeight::get().writes(15 as u64))
+			.saturating_add(T::DbWeight::get().writes(16 as u64))
 	}
 	// Storage: NominationPools BondedPools (r:1 w:0)
 	// Storage: Staking Bonded (r:1 w:0)
@@ -309,6 +322,105 @@ impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
 			.saturating_add(T::DbWeight::get().reads(9 as u64))
 			.saturating_add(T::DbWeight::get().writes(5 as u64))
 	}
+	// Storage: NominationPools BondedPools (r:1 w:0)
+	// Storage: NominationPools RewardPools (r:1 w:1)
+	// Storage: System Account (r:2 w:2)
+	fn sweep_dust() -> Weight {
+		// Minimum execution time: 49_581 nanoseconds.
+		Weight::from_ref_time(50_347_000 as u64)
+			.saturating_add(T::DbWeight::get().reads(4 as u64))
+			.saturating_add(T::DbWeight::get().writes(3 as u64))
+	}
+	// Storage: NominationPools BondedPools (r:1 w:0)
+	// Storage: NominationPools RewardPools (r:1 w:1)
+	// Storage: System Account (r:1 w:0)
+	fn reconcile_reward_pool() -> Weight {
+		// Minimum execution time: 24_113 nanoseconds.
+		Weight::from_ref_time(24_620_000 as u64)
+			.saturating_add(T::DbWeight::get().reads(3 as u64))
+			.saturating_add(T::DbWeight::get().writes(1 as u64))
+	}
+	// Storage: NominationPools BondedPools (r:1 w:0)
+	// Storage: NominationPools MinMembershipDuration (r:0 w:1)
+	fn set_min_membership_duration() -> Weight {
+		// Minimum execution time: 18_902 nanoseconds.
+		Weight::from_ref_time(19_437_000 as u64)
+			.saturating_add(T::DbWeight::get().reads(1 as u64))
+			.saturating_add(T::DbWeight::get().writes(1 as u64))
+	}
+	// Storage: NominationPools PoolMembersIndexCursor (r:1 w:1)
+	// Storage: NominationPools PoolMembers (r:1 w:0)
+	// Storage: NominationPools PoolMembersByPool (r:0 w:1)
+	/// The range of component `n` is `[0, 1000]`.
+	fn migrate_members(n: u32, ) -> Weight {
+		// Minimum execution time: 15_873 nanoseconds.
+		Weight::from_ref_time(16_402_000 as u64)
+			// Standard Error: 1_906
+			.saturating_add(Weight::from_ref_time(7_148_000 as u64).saturating_mul(n as u64))
+			.saturating_add(T::DbWeight::get().reads(1 as u64))
+			.saturating_add(T::DbWeight::get().reads((1 as u64).saturating_mul(n as u64)))
+			.saturating_add(T::DbWeight::get().writes(1 as u64))
+			.saturating_add(T::DbWeight::get().writes((1 as u64).saturating_mul(n as u64)))
+	}
+	// Storage: NominationPools SwitchPoolRequests (r:1 w:1)
+	// Storage: NominationPools PoolMembers (r:1 w:0)
+	// Storage: NominationPools BondedPools (r:2 w:0)
+	// Storage: NominationPools RewardPools (r:1 w:0)
+	// Storage: NominationPools MinMembershipDuration (r:1 w:0)
+	// Storage: NominationPools CounterForSwitchPoolRequests (r:1 w:1)
+	fn switch_pool() -> Weight {
+		// Minimum execution time: 38_217 nanoseconds.
+		Weight::from_ref_time(39_106_000 as u64)
+			.saturating_add(T::DbWeight::get().reads(7 as u64))
+			.saturating_add(T::DbWeight::get().writes(2 as u64))
+	}
+	// Storage: Staking ValidatorCount (r:1 w:0)
+	// Storage: NominationPools SwitchPoolRequests (r:2 w:1)
+	// Storage: NominationPools CounterForSwitchPoolRequests (r:1 w:1)
+	// Storage: NominationPools PoolMembers (r:1 w:1)
+	// Storage: NominationPools BondedPools (r:2 w:2)
+	// Storage: NominationPools RewardPools (r:2 w:2)
+	// Storage: NominationPools MinMembershipDuration (r:1 w:0)
+	// Storage: Staking Bonded (r:2 w:0)
+	// Storage: Staking Ledger (r:2 w:2)
+	// Storage: Staking CurrentEra (r:1 w:0)
+	// Storage: Staking ErasStakers (r:128 w:0)
+	// Storage: NominationPools MinJoinBond (r:1 w:0)
+	// Storage: Staking Nominators (r:2 w:0)
+	// Storage: Staking Validators (r:2 w:0)
+	// Storage: BagsList ListNodes (r:4 w:4)
+	// Storage: BagsList ListBags (r:2 w:2)
+	// Storage: Balances Locks (r:2 w:2)
+	// Storage: System Account (r:4 w:4)
+	// Storage: NominationPools TotalValueLocked (r:1 w:1)
+	// Storage: NominationPools PoolMembersByPool (r:0 w:2)
+	/// The range of component `v` is `[1, 2048]`.
+	fn process_switch_request(v: u32, ) -> Weight {
+		// Minimum execution time: 232_917 nanoseconds.
+		Weight::from_ref_time(236_044_000 as u64)
+			// Standard Error: 41_873
+			.saturating_add(Weight::from_ref_time(297_361_000 as u64).saturating_mul(v as u64))
+			.saturating_add(T::DbWeight::get().reads(35 as u64))
+			.saturating_add(T::DbWeight::get().reads((2 as u64).saturating_mul(v as u64)))
+			.saturating_add(T::DbWeight::get().writes(24 as u64))
+	}
+	// Storage: NominationPools BondedPools (r:1 w:0)
+	// Storage: NominationPools RewardAccountDeposits (r:1 w:1)
+	// Storage: System Account (r:2 w:2)
+	fn adjust_pool_deposit() -> Weight {
+		// Minimum execution time: 41_208 nanoseconds.
+		Weight::from_ref_time(42_117_000 as u64)
+			.saturating_add(T::DbWeight::get().reads(4 as u64))
+			.saturating_add(T::DbWeight::get().writes(3 as u64))
+	}
+	// Storage: NominationPools BondedPools (r:1 w:0)
+	// Storage: NominationPools Commission (r:0 w:1)
+	fn set_commission() -> Weight {
+		// Minimum execution time: 19_214 nanoseconds.
+		Weight::from_ref_time(19_885_000 as u64)
+			.saturating_add(T::DbWeight::get().reads(1 as u64))
+			.saturating_add(T::DbWeight::get().writes(1 as u64))
+	}
 }
 
 // For backwards compatibility and tests
@@ -326,11 +438,12 @@ impl WeightInfo for () {
 	// Storage: Balances Locks (r:1 w:1)
 	// Storage: VoterList ListNodes (r:3 w:3)
 	// Storage: VoterList ListBags (r:2 w:2)
+	// Storage: NominationPools PoolMembersByPool (r:0 w:1)
 	fn join() -> Weight {
 		// Minimum execution time: 159_948 nanoseconds.
 		Weight::from_ref_time(161_133_000 as u64)
 			.saturating_add(RocksDbWeight::get().reads(17 as u64))
-			.saturating_add(RocksDbWeight::get().writes(12 as u64))
+			.saturating_add(RocksDbWeight::get().writes(13 as u64))
 	}
 	// Storage: NominationPools PoolMembers (r:1 w:1)
 	// Storage: NominationPools BondedPools (r:1 w:1)
@@ -365,12 +478,13 @@ impl WeightInfo for () {
 	// Storage: NominationPools PoolMembers (r:1 w:1)
 	// Storage: NominationPools BondedPools (r:1 w:1)
 	// Storage: NominationPools RewardPools (r:1 w:1)
-	// Storage: System Account (r:1 w:1)
+	// Storage: NominationPools Commission (r:1 w:0)
+	// Storage: System Account (r:2 w:2)
 	fn claim_payout() -> Weight {
-		// Minimum execution time: 64_560 nanoseconds.
-		Weight::from_ref_time(64_950_000 as u64)
-			.saturating_add(RocksDbWeight::get().reads(4 as u64))
-			.saturating_add(RocksDbWeight::get().writes(4 as u64))
+		// Minimum execution time: 88_417 nanoseconds.
+		Weight::from_ref_time(89_301_000 as u64)
+			.saturating_add(RocksDbWeight::get().reads(6 as u64))
+			.saturating_add(RocksDbWeight::get().writes(5 as u64))
 	}
 	// Storage: NominationPools PoolMembers (r:1 w:1)
 	// Storage: NominationPools BondedPools (r:1 w:1)
@@ -415,6 +529,7 @@ impl WeightInfo for () {
 	// Storage: Balances Locks (r:1 w:1)
 	// Storage: System Account (r:1 w:1)
 	// Storage: NominationPools CounterForPoolMembers (r:1 w:1)
+	// Storage: NominationPools PoolMembersByPool (r:0 w:1)
 	/// The range of component `s` is `[0, 100]`.
 	fn withdraw_unbonded_update(s: u32, ) -> Weight {
 		// Minimum execution time: 111_156 nanoseconds.
@@ -422,7 +537,7 @@ impl WeightInfo for () {
 			// Standard Error: 655
 			.saturating_add(Weight::from_ref_time(53_711 as u64).saturating_mul(s as u64))
 			.saturating_add(RocksDbWeight::get().reads(9 as u64))
-			.saturating_add(RocksDbWeight::get().writes(7 as u64))
+			.saturating_add(RocksDbWeight::get().writes(8 as u64))
 	}
 	// Storage: NominationPools PoolMembers (r:1 w:1)
 	// Storage: Staking CurrentEra (r:1 w:0)
@@ -444,6 +559,7 @@ impl WeightInfo for () {
 	// Storage: NominationPools Metadata (r:1 w:1)
 	// Storage: NominationPools CounterForBondedPools (r:1 w:1)
 	// Storage: Staking Payee (r:0 w:1)
+	// Storage: NominationPools PoolMembersByPool (r:0 w:1)
 	/// The range of component `s` is `[0, 100]`.
 	fn withdraw_unbonded_kill(s: u32, ) -> Weight {
 		// Minimum execution time: 168_270 nanoseconds.
@@ -451,7 +567,7 @@ impl WeightInfo for () {
 			// Standard Error: 1_506
 			.saturating_add(Weight::from_ref_time(1_258 as u64).saturating_mul(s as u64))
 			.saturating_add(RocksDbWeight::get().reads(20 as u64))
-			.saturating_add(RocksDbWeight::get().writes(17 as u64))
+			.saturating_add(RocksDbWeight::get().writes(18 as u64))
 	}
 	// Storage: NominationPools LastPoolId (r:1 w:1)
 	// Storage: Staking MinNominatorBond (r:1 w:0)
@@ -474,11 +590,12 @@ impl WeightInfo for () {
 	// Storage: NominationPools CounterForReversePoolIdLookup (r:1 w:1)
 	// Storage: NominationPools BondedPools (r:1 w:1)
 	// Storage: Staking Payee (r:0 w:1)
+	// Storage: NominationPools PoolMembersByPool (r:0 w:1)
 	fn create() -> Weight {
 		// Minimum execution time: 146_153 nanoseconds.
 		Weight::from_ref_time(146_955_000 as u64)
 			.saturating_add(RocksDbWeight::get().reads(21 as u64))
-			.saturating_add(RocksDbWeight::get().writes(15 as u64))
+			.saturating_add(RocksDbWeight::get().writes(16 as u64))
 	}
 	// Storage: NominationPools BondedPools (r:1 w:0)
 	// Storage: Staking Bonded (r:1 w:0)
@@ -555,4 +672,103 @@ impl WeightInfo for () {
 			.saturating_add(RocksDbWeight::get().reads(9 as u64))
 			.saturating_add(RocksDbWeight::get().writes(5 as u64))
 	}
+	// Storage: NominationPools BondedPools (r:1 w:0)
+	// Storage: NominationPools RewardPools (r:1 w:1)
+	// Storage: System Account (r:2 w:2)
+	fn sweep_dust() -> Weight {
+		// Minimum execution time: 49_581 nanoseconds.
+		Weight::from_ref_time(50_347_000 as u64)
+			.saturating_add(RocksDbWeight::get().reads(4 as u64))
+			.saturating_add(RocksDbWeight::get().writes(3 as u64))
+	}
+	// Storage: NominationPools BondedPools (r:1 w:0)
+	// Storage: NominationPools RewardPools (r:1 w:1)
+	// Storage: System Account (r:1 w:0)
+	fn reconcile_reward_pool() -> Weight {
+		// Minimum execution time: 24_113 nanoseconds.
+		Weight::from_ref_time(24_620_000 as u64)
+			.saturating_add(RocksDbWeight::get().reads(3 as u64))
+			.saturating_add(RocksDbWeight::get().writes(1 as u64))
+	}
+	// Storage: NominationPools BondedPools (r:1 w:0)
+	// Storage: NominationPools MinMembershipDuration (r:0 w:1)
+	fn set_min_membership_duration() -> Weight {
+		// Minimum execution time: 18_902 nanoseconds.
+		Weight::from_ref_time(19_437_000 as u64)
+			.saturating_add(RocksDbWeight::get().reads(1 as u64))
+			.saturating_add(RocksDbWeight::get().writes(1 as u64))
+	}
+	// Storage: NominationPools PoolMembersIndexCursor (r:1 w:1)
+	// Storage: NominationPools PoolMembers (r:1 w:0)
+	// Storage: NominationPools PoolMembersByPool (r:0 w:1)
+	/// The range of component `n` is `[0, 1000]`.
+	fn migrate_members(n: u32, ) -> Weight {
+		// Minimum execution time: 15_873 nanoseconds.
+		Weight::from_ref_time(16_402_000 as u64)
+			// Standard Error: 1_906
+			.saturating_add(Weight::from_ref_time(7_148_000 as u64).saturating_mul(n as u64))
+			.saturating_add(RocksDbWeight::get().reads(1 as u64))
+			.saturating_add(RocksDbWeight::get().reads((1 as u64).saturating_mul(n as u64)))
+			.saturating_add(RocksDbWeight::get().writes(1 as u64))
+			.saturating_add(RocksDbWeight::get().writes((1 as u64).saturating_mul(n as u64)))
+	}
+	// Storage: NominationPools SwitchPoolRequests (r:1 w:1)
+	// Storage: NominationPools PoolMembers (r:1 w:0)
+	// Storage: NominationPools BondedPools (r:2 w:0)
+	// Storage: NominationPools RewardPools (r:1 w:0)
+	// Storage: NominationPools MinMembershipDuration (r:1 w:0)
+	// Storage: NominationPools CounterForSwitchPoolRequests (r:1 w:1)
+	fn switch_pool() -> Weight {
+		// Minimum execution time: 38_217 nanoseconds.
+		Weight::from_ref_time(39_106_000 as u64)
+			.saturating_add(RocksDbWeight::get().reads(7 as u64))
+			.saturating_add(RocksDbWeight::get().writes(2 as u64))
+	}
+	// Storage: Staking ValidatorCount (r:1 w:0)
+	// Storage: NominationPools SwitchPoolRequests (r:2 w:1)
+	// Storage: NominationPools CounterForSwitchPoolRequests (r:1 w:1)
+	// Storage: NominationPools PoolMembers (r:1 w:1)
+	// Storage: NominationPools BondedPools (r:2 w:2)
+	// Storage: NominationPools RewardPools (r:2 w:2)
+	// Storage: NominationPools MinMembershipDuration (r:1 w:0)
+	// Storage: Staking Bonded (r:2 w:0)
+	// Storage: Staking Ledger (r:2 w:2)
+	// Storage: Staking CurrentEra (r:1 w:0)
+	// Storage: Staking ErasStakers (r:128 w:0)
+	// Storage: NominationPools MinJoinBond (r:1 w:0)
+	// Storage: Staking Nominators (r:2 w:0)
+	// Storage: Staking Validators (r:2 w:0)
+	// Storage: BagsList ListNodes (r:4 w:4)
+	// Storage: BagsList ListBags (r:2 w:2)
+	// Storage: Balances Locks (r:2 w:2)
+	// Storage: System Account (r:4 w:4)
+	// Storage: NominationPools TotalValueLocked (r:1 w:1)
+	// Storage: NominationPools PoolMembersByPool (r:0 w:2)
+	/// The range of component `v` is `[1, 2048]`.
+	fn process_switch_request(v: u32, ) -> Weight {
+		// Minimum execution time: 232_917 nanoseconds.
+		Weight::from_ref_time(236_044_000 as u64)
+			// Standard Error: 41_873
+			.saturating_add(Weight::from_ref_time(297_361_000 as u64).saturating_mul(v as u64))
+			.saturating_add(RocksDbWeight::get().reads(35 as u64))
+			.saturating_add(RocksDbWeight::get().reads((2 as u64).saturating_mul(v as u64)))
+			.saturating_add(RocksDbWeight::get().writes(24 as u64))
+	}
+	// Storage: NominationPools BondedPools (r:1 w:0)
+	// Storage: NominationPools RewardAccountDeposits (r:1 w:1)
+	// Storage: System Account (r:2 w:2)
+	fn adjust_pool_deposit() -> Weight {
+		// Minimum execution time: 41_208 nanoseconds.
+		Weight::from_ref_time(42_117_000 as u64)
+			.saturating_add(RocksDbWeight::get().reads(4 as u64))
+			.saturating_add(RocksDbWeight::get().writes(3 as u64))
+	}
+	// Storage: NominationPools BondedPools (r:1 w:0)
+	// Storage: NominationPools Commission (r:0 w:1)
+	fn set_commission() -> Weight {
+		// Minimum execution time: 19_214 nanoseconds.
+		Weight::from_ref_time(19_885_000 as u64)
+			.saturating_add(RocksDbWeight::get().reads(1 as u64))
+			.saturating_add(RocksDbWeight::get().writes(1 as u64))
+	}
 }
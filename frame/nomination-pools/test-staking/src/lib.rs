@@ -19,11 +19,12 @@
 
 mod mock;
 
+use codec::{Decode, Encode};
 use frame_support::{assert_noop, assert_ok, bounded_btree_map, traits::Currency};
 use mock::*;
 use pallet_nomination_pools::{
 	BondedPools, Error as PoolsError, Event as PoolsEvent, LastPoolId, PoolMember, PoolMembers,
-	PoolState,
+	PoolState, WeightInfo,
 };
 use pallet_staking::{CurrentEra, Event as StakingEvent, Payee, RewardDestination};
 use sp_runtime::traits::Zero;
@@ -49,13 +50,14 @@ fn pool_lifecycle_e2e() {
 			pool_events_since_last_call(),
 			vec![
 				PoolsEvent::Created { depositor: 10, pool_id: 1 },
-				PoolsEvent::Bonded { member: 10, pool_id: 1, bonded: 50, joined: true },
+				PoolsEvent::Bonded { member: 10, pool_id: 1, bonded: 50, points: 50, joined: true },
+				PoolsEvent::PoolUpdated { pool_id: 1, points: 50, balance: 50 },
 			]
 		);
 
 		// have two members join
-		assert_ok!(Pools::join(RuntimeOrigin::signed(20), 10, 1));
-		assert_ok!(Pools::join(RuntimeOrigin::signed(21), 10, 1));
+		assert_ok!(Pools::join(RuntimeOrigin::signed(20), 10, 1, None));
+		assert_ok!(Pools::join(RuntimeOrigin::signed(21), 10, 1, None));
 
 		assert_eq!(
 			staking_events_since_last_call(),
@@ -67,8 +69,10 @@ fn pool_lifecycle_e2e() {
 		assert_eq!(
 			pool_events_since_last_call(),
 			vec![
-				PoolsEvent::Bonded { member: 20, pool_id: 1, bonded: 10, joined: true },
-				PoolsEvent::Bonded { member: 21, pool_id: 1, bonded: 10, joined: true },
+				PoolsEvent::Bonded { member: 20, pool_id: 1, bonded: 10, points: 10, joined: true },
+				PoolsEvent::PoolUpdated { pool_id: 1, points: 60, balance: 60 },
+				PoolsEvent::Bonded { member: 21, pool_id: 1, bonded: 10, points: 10, joined: true },
+				PoolsEvent::PoolUpdated { pool_id: 1, points: 70, balance: 70 },
 			]
 		);
 
@@ -100,9 +104,15 @@ fn pool_lifecycle_e2e() {
 		assert_eq!(
 			pool_events_since_last_call(),
 			vec![
-				PoolsEvent::StateChanged { pool_id: 1, new_state: PoolState::Destroying },
+				PoolsEvent::StateChanged {
+					pool_id: 1,
+					new_state: PoolState::Destroying,
+					caller: 10
+				},
 				PoolsEvent::Unbonded { member: 20, pool_id: 1, points: 10, balance: 10, era: 3 },
+				PoolsEvent::PoolUpdated { pool_id: 1, points: 60, balance: 60 },
 				PoolsEvent::Unbonded { member: 21, pool_id: 1, points: 10, balance: 10, era: 3 },
+				PoolsEvent::PoolUpdated { pool_id: 1, points: 50, balance: 50 },
 			]
 		);
 
@@ -168,7 +178,10 @@ fn pool_lifecycle_e2e() {
 		);
 		assert_eq!(
 			pool_events_since_last_call(),
-			vec![PoolsEvent::Unbonded { member: 10, pool_id: 1, points: 50, balance: 50, era: 6 }]
+			vec![
+				PoolsEvent::Unbonded { member: 10, pool_id: 1, points: 50, balance: 50, era: 6 },
+				PoolsEvent::PoolUpdated { pool_id: 1, points: 0, balance: 0 }
+			]
 		);
 
 		// waiting another bonding duration:
@@ -210,15 +223,16 @@ fn pool_slash_e2e() {
 			pool_events_since_last_call(),
 			vec![
 				PoolsEvent::Created { depositor: 10, pool_id: 1 },
-				PoolsEvent::Bonded { member: 10, pool_id: 1, bonded: 40, joined: true },
+				PoolsEvent::Bonded { member: 10, pool_id: 1, bonded: 40, points: 40, joined: true },
+				PoolsEvent::PoolUpdated { pool_id: 1, points: 40, balance: 40 },
 			]
 		);
 
 		assert_eq!(Payee::<Runtime>::get(POOL1_BONDED), RewardDestination::Account(POOL1_REWARD));
 
 		// have two members join
-		assert_ok!(Pools::join(RuntimeOrigin::signed(20), 20, 1));
-		assert_ok!(Pools::join(RuntimeOrigin::signed(21), 20, 1));
+		assert_ok!(Pools::join(RuntimeOrigin::signed(20), 20, 1, None));
+		assert_ok!(Pools::join(RuntimeOrigin::signed(21), 20, 1, None));
 
 		assert_eq!(
 			staking_events_since_last_call(),
@@ -230,8 +244,10 @@ fn pool_slash_e2e() {
 		assert_eq!(
 			pool_events_since_last_call(),
 			vec![
-				PoolsEvent::Bonded { member: 20, pool_id: 1, bonded: 20, joined: true },
-				PoolsEvent::Bonded { member: 21, pool_id: 1, bonded: 20, joined: true },
+				PoolsEvent::Bonded { member: 20, pool_id: 1, bonded: 20, points: 20, joined: true },
+				PoolsEvent::PoolUpdated { pool_id: 1, points: 60, balance: 60 },
+				PoolsEvent::Bonded { member: 21, pool_id: 1, bonded: 20, points: 20, joined: true },
+				PoolsEvent::PoolUpdated { pool_id: 1, points: 80, balance: 80 },
 			]
 		);
 
@@ -253,7 +269,9 @@ fn pool_slash_e2e() {
 			pool_events_since_last_call(),
 			vec![
 				PoolsEvent::Unbonded { member: 10, pool_id: 1, balance: 10, points: 10, era: 4 },
-				PoolsEvent::Unbonded { member: 20, pool_id: 1, balance: 10, points: 10, era: 4 }
+				PoolsEvent::PoolUpdated { pool_id: 1, points: 70, balance: 70 },
+				PoolsEvent::Unbonded { member: 20, pool_id: 1, balance: 10, points: 10, era: 4 },
+				PoolsEvent::PoolUpdated { pool_id: 1, points: 60, balance: 60 }
 			]
 		);
 
@@ -278,34 +296,40 @@ fn pool_slash_e2e() {
 			pool_events_since_last_call(),
 			vec![
 				PoolsEvent::Unbonded { member: 10, pool_id: 1, balance: 10, points: 10, era: 5 },
+				PoolsEvent::PoolUpdated { pool_id: 1, points: 50, balance: 50 },
 				PoolsEvent::Unbonded { member: 20, pool_id: 1, balance: 10, points: 10, era: 5 },
+				PoolsEvent::PoolUpdated { pool_id: 1, points: 40, balance: 40 },
 				PoolsEvent::Unbonded { member: 21, pool_id: 1, balance: 10, points: 10, era: 5 },
+				PoolsEvent::PoolUpdated { pool_id: 1, points: 30, balance: 30 },
 			]
 		);
 
 		// At this point, 20 are safe from slash, 30 are unlocking but vulnerable to slash, and and
 		// another 30 are active and vulnerable to slash. Let's slash half of them.
+		assert_eq!(Pools::total_value_locked(), 80);
 		pallet_staking::slashing::do_slash::<Runtime>(
 			&POOL1_BONDED,
 			30,
 			&mut Default::default(),
 			&mut Default::default(),
 			2, // slash era 2, affects chunks at era 5 onwards.
+			2,
 		);
 
 		assert_eq!(
 			staking_events_since_last_call(),
-			vec![StakingEvent::Slashed { staker: POOL1_BONDED, amount: 30 }]
+			vec![StakingEvent::Slashed { staker: POOL1_BONDED, amount: 30, slash_era: 2 }]
 		);
 		assert_eq!(
 			pool_events_since_last_call(),
 			vec![
 				// 30 has been slashed to 15 (15 slash)
-				PoolsEvent::UnbondingPoolSlashed { pool_id: 1, era: 5, balance: 15 },
+				PoolsEvent::UnbondingPoolSlashed { pool_id: 1, era: 5, balance: 15, slash_era: 2 },
 				// 30 has been slashed to 15 (15 slash)
-				PoolsEvent::PoolSlashed { pool_id: 1, balance: 15 }
+				PoolsEvent::PoolSlashed { pool_id: 1, balance: 15, slash_era: 2 }
 			]
 		);
+		assert_eq!(Pools::total_value_locked(), 50);
 
 		CurrentEra::<Runtime>::set(Some(3));
 		assert_ok!(Pools::unbond(RuntimeOrigin::signed(21), 21, 10));
@@ -317,7 +341,9 @@ fn pool_slash_e2e() {
 				points: 0,
 				last_recorded_reward_counter: Zero::zero(),
 				// the 10 points unlocked just now correspond to 5 points in the unbond pool.
-				unbonding_eras: bounded_btree_map!(5 => 10, 6 => 5)
+				unbonding_eras: bounded_btree_map!(5 => 10, 6 => 5),
+				referrer: None,
+				joined_at: 1,
 			}
 		);
 		assert_eq!(
@@ -326,7 +352,10 @@ fn pool_slash_e2e() {
 		);
 		assert_eq!(
 			pool_events_since_last_call(),
-			vec![PoolsEvent::Unbonded { member: 21, pool_id: 1, balance: 5, points: 5, era: 6 }]
+			vec![
+				PoolsEvent::Unbonded { member: 21, pool_id: 1, balance: 5, points: 5, era: 6 },
+				PoolsEvent::PoolUpdated { pool_id: 1, points: 20, balance: 10 }
+			]
 		);
 
 		// now we start withdrawing. we do it all at once, at era 6 where 20 and 21 are fully free.
@@ -362,8 +391,13 @@ fn pool_slash_e2e() {
 		assert_eq!(
 			pool_events_since_last_call(),
 			vec![
-				PoolsEvent::StateChanged { pool_id: 1, new_state: PoolState::Destroying },
-				PoolsEvent::Unbonded { member: 10, pool_id: 1, points: 10, balance: 10, era: 9 }
+				PoolsEvent::StateChanged {
+					pool_id: 1,
+					new_state: PoolState::Destroying,
+					caller: 10
+				},
+				PoolsEvent::Unbonded { member: 10, pool_id: 1, points: 10, balance: 10, era: 9 },
+				PoolsEvent::PoolUpdated { pool_id: 1, points: 0, balance: 0 }
 			]
 		);
 
@@ -374,7 +408,9 @@ fn pool_slash_e2e() {
 				pool_id: 1,
 				points: 0,
 				last_recorded_reward_counter: Zero::zero(),
-				unbonding_eras: bounded_btree_map!(4 => 10, 5 => 10, 9 => 10)
+				unbonding_eras: bounded_btree_map!(4 => 10, 5 => 10, 9 => 10),
+				referrer: None,
+				joined_at: 1,
 			}
 		);
 		// withdraw the depositor, they should lose 12 balance in total due to slash.
@@ -417,15 +453,16 @@ fn pool_slash_proportional() {
 			pool_events_since_last_call(),
 			vec![
 				PoolsEvent::Created { depositor: 10, pool_id: 1 },
-				PoolsEvent::Bonded { member: 10, pool_id: 1, bonded: 40, joined: true },
+				PoolsEvent::Bonded { member: 10, pool_id: 1, bonded: 40, points: 40, joined: true },
+				PoolsEvent::PoolUpdated { pool_id: 1, points: 40, balance: 40 },
 			]
 		);
 
 		// have two members join
 		let bond = 20;
-		assert_ok!(Pools::join(RuntimeOrigin::signed(20), bond, 1));
-		assert_ok!(Pools::join(RuntimeOrigin::signed(21), bond, 1));
-		assert_ok!(Pools::join(RuntimeOrigin::signed(22), bond, 1));
+		assert_ok!(Pools::join(RuntimeOrigin::signed(20), bond, 1, None));
+		assert_ok!(Pools::join(RuntimeOrigin::signed(21), bond, 1, None));
+		assert_ok!(Pools::join(RuntimeOrigin::signed(22), bond, 1, None));
 
 		assert_eq!(
 			staking_events_since_last_call(),
@@ -438,9 +475,30 @@ fn pool_slash_proportional() {
 		assert_eq!(
 			pool_events_since_last_call(),
 			vec![
-				PoolsEvent::Bonded { member: 20, pool_id: 1, bonded: bond, joined: true },
-				PoolsEvent::Bonded { member: 21, pool_id: 1, bonded: bond, joined: true },
-				PoolsEvent::Bonded { member: 22, pool_id: 1, bonded: bond, joined: true },
+				PoolsEvent::Bonded {
+					member: 20,
+					pool_id: 1,
+					bonded: bond,
+					points: bond,
+					joined: true
+				},
+				PoolsEvent::PoolUpdated { pool_id: 1, points: 60, balance: 60 },
+				PoolsEvent::Bonded {
+					member: 21,
+					pool_id: 1,
+					bonded: bond,
+					points: bond,
+					joined: true
+				},
+				PoolsEvent::PoolUpdated { pool_id: 1, points: 80, balance: 80 },
+				PoolsEvent::Bonded {
+					member: 22,
+					pool_id: 1,
+					bonded: bond,
+					points: bond,
+					joined: true
+				},
+				PoolsEvent::PoolUpdated { pool_id: 1, points: 100, balance: 100 },
 			]
 		);
 
@@ -456,13 +514,16 @@ fn pool_slash_proportional() {
 		);
 		assert_eq!(
 			pool_events_since_last_call(),
-			vec![PoolsEvent::Unbonded {
-				member: 20,
-				pool_id: 1,
-				balance: bond,
-				points: bond,
-				era: 127
-			}]
+			vec![
+				PoolsEvent::Unbonded {
+					member: 20,
+					pool_id: 1,
+					balance: bond,
+					points: bond,
+					era: 127
+				},
+				PoolsEvent::PoolUpdated { pool_id: 1, points: 80, balance: 80 }
+			]
 		);
 
 		CurrentEra::<T>::set(Some(100));
@@ -473,13 +534,16 @@ fn pool_slash_proportional() {
 		);
 		assert_eq!(
 			pool_events_since_last_call(),
-			vec![PoolsEvent::Unbonded {
-				member: 21,
-				pool_id: 1,
-				balance: bond,
-				points: bond,
-				era: 128
-			}]
+			vec![
+				PoolsEvent::Unbonded {
+					member: 21,
+					pool_id: 1,
+					balance: bond,
+					points: bond,
+					era: 128
+				},
+				PoolsEvent::PoolUpdated { pool_id: 1, points: 60, balance: 60 }
+			]
 		);
 
 		CurrentEra::<T>::set(Some(101));
@@ -490,13 +554,16 @@ fn pool_slash_proportional() {
 		);
 		assert_eq!(
 			pool_events_since_last_call(),
-			vec![PoolsEvent::Unbonded {
-				member: 22,
-				pool_id: 1,
-				balance: bond,
-				points: bond,
-				era: 129
-			}]
+			vec![
+				PoolsEvent::Unbonded {
+					member: 22,
+					pool_id: 1,
+					balance: bond,
+					points: bond,
+					era: 129
+				},
+				PoolsEvent::PoolUpdated { pool_id: 1, points: 40, balance: 40 }
+			]
 		);
 
 		// Apply a slash that happened in era 100. This is typically applied with a delay.
@@ -508,22 +575,33 @@ fn pool_slash_proportional() {
 			&mut Default::default(),
 			&mut Default::default(),
 			100,
+			100,
 		);
 
 		assert_eq!(
 			staking_events_since_last_call(),
-			vec![StakingEvent::Slashed { staker: POOL1_BONDED, amount: 50 }]
+			vec![StakingEvent::Slashed { staker: POOL1_BONDED, amount: 50, slash_era: 100 }]
 		);
 		assert_eq!(
 			pool_events_since_last_call(),
 			vec![
 				// This era got slashed 12.5, which rounded up to 13.
-				PoolsEvent::UnbondingPoolSlashed { pool_id: 1, era: 128, balance: 7 },
+				PoolsEvent::UnbondingPoolSlashed {
+					pool_id: 1,
+					era: 128,
+					balance: 7,
+					slash_era: 100
+				},
 				// This era got slashed 12 instead of 12.5 because an earlier chunk got 0.5 more
 				// slashed, and 12 is all the remaining slash
-				PoolsEvent::UnbondingPoolSlashed { pool_id: 1, era: 129, balance: 8 },
+				PoolsEvent::UnbondingPoolSlashed {
+					pool_id: 1,
+					era: 129,
+					balance: 8,
+					slash_era: 100
+				},
 				// Bonded pool got slashed for 25, remaining 15 in it.
-				PoolsEvent::PoolSlashed { pool_id: 1, balance: 15 }
+				PoolsEvent::PoolSlashed { pool_id: 1, balance: 15, slash_era: 100 }
 			]
 		);
 	});
@@ -552,20 +630,30 @@ fn pool_slash_non_proportional_only_bonded_pool() {
 			pool_events_since_last_call(),
 			vec![
 				PoolsEvent::Created { depositor: 10, pool_id: 1 },
-				PoolsEvent::Bonded { member: 10, pool_id: 1, bonded: 40, joined: true },
+				PoolsEvent::Bonded { member: 10, pool_id: 1, bonded: 40, points: 40, joined: true },
+				PoolsEvent::PoolUpdated { pool_id: 1, points: 40, balance: 40 },
 			]
 		);
 
 		// have two members join
 		let bond = 20;
-		assert_ok!(Pools::join(RuntimeOrigin::signed(20), bond, 1));
+		assert_ok!(Pools::join(RuntimeOrigin::signed(20), bond, 1, None));
 		assert_eq!(
 			staking_events_since_last_call(),
 			vec![StakingEvent::Bonded { stash: POOL1_BONDED, amount: bond }]
 		);
 		assert_eq!(
 			pool_events_since_last_call(),
-			vec![PoolsEvent::Bonded { member: 20, pool_id: 1, bonded: bond, joined: true }]
+			vec![
+				PoolsEvent::Bonded {
+					member: 20,
+					pool_id: 1,
+					bonded: bond,
+					points: bond,
+					joined: true
+				},
+				PoolsEvent::PoolUpdated { pool_id: 1, points: 60, balance: 60 }
+			]
 		);
 
 		// progress and unbond.
@@ -577,13 +665,16 @@ fn pool_slash_non_proportional_only_bonded_pool() {
 		);
 		assert_eq!(
 			pool_events_since_last_call(),
-			vec![PoolsEvent::Unbonded {
-				member: 20,
-				pool_id: 1,
-				balance: bond,
-				points: bond,
-				era: 127
-			}]
+			vec![
+				PoolsEvent::Unbonded {
+					member: 20,
+					pool_id: 1,
+					balance: bond,
+					points: bond,
+					era: 127
+				},
+				PoolsEvent::PoolUpdated { pool_id: 1, points: 40, balance: 40 }
+			]
 		);
 
 		// slash for 30. This will be deducted only from the bonded pool.
@@ -595,15 +686,16 @@ fn pool_slash_non_proportional_only_bonded_pool() {
 			&mut Default::default(),
 			&mut Default::default(),
 			100,
+			100,
 		);
 
 		assert_eq!(
 			staking_events_since_last_call(),
-			vec![StakingEvent::Slashed { staker: POOL1_BONDED, amount: 30 }]
+			vec![StakingEvent::Slashed { staker: POOL1_BONDED, amount: 30, slash_era: 100 }]
 		);
 		assert_eq!(
 			pool_events_since_last_call(),
-			vec![PoolsEvent::PoolSlashed { pool_id: 1, balance: 10 }]
+			vec![PoolsEvent::PoolSlashed { pool_id: 1, balance: 10, slash_era: 100 }]
 		);
 	});
 }
@@ -631,20 +723,30 @@ fn pool_slash_non_proportional_bonded_pool_and_chunks() {
 			pool_events_since_last_call(),
 			vec![
 				PoolsEvent::Created { depositor: 10, pool_id: 1 },
-				PoolsEvent::Bonded { member: 10, pool_id: 1, bonded: 40, joined: true },
+				PoolsEvent::Bonded { member: 10, pool_id: 1, bonded: 40, points: 40, joined: true },
+				PoolsEvent::PoolUpdated { pool_id: 1, points: 40, balance: 40 },
 			]
 		);
 
 		// have two members join
 		let bond = 20;
-		assert_ok!(Pools::join(RuntimeOrigin::signed(20), bond, 1));
+		assert_ok!(Pools::join(RuntimeOrigin::signed(20), bond, 1, None));
 		assert_eq!(
 			staking_events_since_last_call(),
 			vec![StakingEvent::Bonded { stash: POOL1_BONDED, amount: bond }]
 		);
 		assert_eq!(
 			pool_events_since_last_call(),
-			vec![PoolsEvent::Bonded { member: 20, pool_id: 1, bonded: bond, joined: true }]
+			vec![
+				PoolsEvent::Bonded {
+					member: 20,
+					pool_id: 1,
+					bonded: bond,
+					points: bond,
+					joined: true
+				},
+				PoolsEvent::PoolUpdated { pool_id: 1, points: 60, balance: 60 }
+			]
 		);
 
 		// progress and unbond.
@@ -656,13 +758,16 @@ fn pool_slash_non_proportional_bonded_pool_and_chunks() {
 		);
 		assert_eq!(
 			pool_events_since_last_call(),
-			vec![PoolsEvent::Unbonded {
-				member: 20,
-				pool_id: 1,
-				balance: bond,
-				points: bond,
-				era: 127
-			}]
+			vec![
+				PoolsEvent::Unbonded {
+					member: 20,
+					pool_id: 1,
+					balance: bond,
+					points: bond,
+					era: 127
+				},
+				PoolsEvent::PoolUpdated { pool_id: 1, points: 40, balance: 40 }
+			]
 		);
 
 		// slash 50. This will be deducted only from the bonded pool and one of the unbonding pools.
@@ -674,20 +779,77 @@ fn pool_slash_non_proportional_bonded_pool_and_chunks() {
 			&mut Default::default(),
 			&mut Default::default(),
 			100,
+			100,
 		);
 
 		assert_eq!(
 			staking_events_since_last_call(),
-			vec![StakingEvent::Slashed { staker: POOL1_BONDED, amount: 50 }]
+			vec![StakingEvent::Slashed { staker: POOL1_BONDED, amount: 50, slash_era: 100 }]
 		);
 		assert_eq!(
 			pool_events_since_last_call(),
 			vec![
 				// out of 20, 10 was taken.
-				PoolsEvent::UnbondingPoolSlashed { pool_id: 1, era: 127, balance: 10 },
+				PoolsEvent::UnbondingPoolSlashed {
+					pool_id: 1,
+					era: 127,
+					balance: 10,
+					slash_era: 100
+				},
 				// out of 40, all was taken.
-				PoolsEvent::PoolSlashed { pool_id: 1, balance: 0 }
+				PoolsEvent::PoolSlashed { pool_id: 1, balance: 0, slash_era: 100 }
 			]
 		);
 	});
 }
+
+#[test]
+fn pool_withdraw_with_slashing_spans_e2e() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Pools::create(RuntimeOrigin::signed(10), 50, 10, 10, 10));
+		assert_ok!(Pools::join(RuntimeOrigin::signed(20), 10, 1, None));
+
+		// the pool's stash has been slashed in a prior span, so it has two spans now: the one
+		// started at era 0 and the ongoing one started at era 3.
+		let spans = pallet_staking::slashing::SlashingSpans::decode(
+			&mut &(1u32, 3u32, 2u32, vec![3u32]).encode()[..],
+		)
+		.unwrap();
+		pallet_staking::SlashingSpans::<Runtime>::insert(POOL1_BONDED, spans);
+		assert_eq!(Staking::slashing_spans_count(&POOL1_BONDED), 2);
+
+		assert_ok!(Pools::unbond(RuntimeOrigin::signed(20), 20, 10));
+
+		// the spans must not be underdeclared, since they are charged for upfront.
+		CurrentEra::<Runtime>::set(Some(BondingDuration::get()));
+		assert_noop!(
+			Pools::withdraw_unbonded(RuntimeOrigin::signed(20), 20, 1),
+			PoolsError::<Runtime>::IncorrectSlashingSpans
+		);
+		assert_noop!(
+			Pools::pool_withdraw_unbonded(RuntimeOrigin::signed(20), 1, 1),
+			PoolsError::<Runtime>::IncorrectSlashingSpans
+		);
+
+		// withdrawing a member leaves the spans untouched.
+		assert_ok!(Pools::withdraw_unbonded(RuntimeOrigin::signed(20), 20, 2));
+		assert!(pallet_staking::SlashingSpans::<Runtime>::contains_key(POOL1_BONDED));
+
+		assert_ok!(Pools::set_state(RuntimeOrigin::signed(10), 1, PoolState::Destroying));
+		assert_ok!(Pools::unbond(RuntimeOrigin::signed(10), 10, 50));
+
+		// the depositor kills the stash, and the spans are cleaned up. Declaring more spans than
+		// there are is fine, the extra weight is refunded.
+		CurrentEra::<Runtime>::set(Some(BondingDuration::get() * 2));
+		let post_info = Pools::withdraw_unbonded(RuntimeOrigin::signed(10), 10, 10).unwrap();
+		assert_eq!(
+			post_info.actual_weight,
+			Some(<Runtime as pallet_nomination_pools::Config>::WeightInfo::withdraw_unbonded_kill(
+				2
+			))
+		);
+		assert!(!pallet_staking::SlashingSpans::<Runtime>::contains_key(POOL1_BONDED));
+		assert!(pallet_staking::Ledger::<Runtime>::get(POOL1_BONDED).is_none());
+		assert!(BondedPools::<Runtime>::get(1).is_none());
+	})
+}
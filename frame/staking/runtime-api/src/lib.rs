@@ -0,0 +1,59 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2022 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Runtime API definition for the staking pallet.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use codec::Codec;
+use sp_staking::EraIndex;
+use sp_std::vec::Vec;
+
+sp_api::decl_runtime_apis! {
+	/// Runtime api for querying the exposure of stakers and the timing of eras.
+	///
+	/// Version 2 added [`StakingApi::expected_era_duration`] and
+	/// [`StakingApi::eras_remaining_blocks`].
+	#[api_version(2)]
+	pub trait StakingApi<AccountId, BlockNumber>
+		where AccountId: Codec, BlockNumber: Codec
+	{
+		/// Returns whether `stash` has been exposed in `era`, either as a validator or as one of
+		/// its nominators.
+		///
+		/// Eras outside of the retained history are never reported as exposed.
+		fn exposed_in_era(stash: AccountId, era: EraIndex) -> bool;
+
+		/// Returns all eras in the inclusive range `from..=to` in which `stash` has been exposed.
+		///
+		/// The range is clamped to the eras retained by the pallet, i.e. the last `HistoryDepth`
+		/// eras up to and including the current one.
+		fn eras_of_exposure(stash: AccountId, from: EraIndex, to: EraIndex) -> Vec<EraIndex>;
+
+		/// The expected number of blocks that an era lasts.
+		#[api_version(2)]
+		fn expected_era_duration() -> BlockNumber;
+
+		/// The estimated number of blocks until `era` becomes the active era, e.g. to tell
+		/// when unbonded funds can be withdrawn.
+		///
+		/// Returns `Some(0)` for eras that are already active or have passed and `None` if no
+		/// estimate is possible, for example because no new eras are being planned.
+		#[api_version(2)]
+		fn eras_remaining_blocks(era: EraIndex) -> Option<BlockNumber>;
+	}
+}
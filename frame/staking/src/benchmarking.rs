@@ -219,7 +219,8 @@ benchmarks! {
 		let stash = create_funded_user::<T>("stash", USER_SEED, 100);
 		let controller = create_funded_user::<T>("controller", USER_SEED, 100);
 		let controller_lookup = T::Lookup::unlookup(controller.clone());
-		let reward_destination = RewardDestination::Staked;
+		let payee = create_funded_user::<T>("payee", USER_SEED, 100);
+		let reward_destination = RewardDestination::Account(payee);
 		let amount = T::Currency::minimum_balance() * 10u32.into();
 		whitelist_account!(stash);
 	}: _(RawOrigin::Signed(stash.clone()), controller_lookup, amount, reward_destination)
@@ -463,10 +464,11 @@ benchmarks! {
 	set_payee {
 		let (stash, controller) = create_stash_controller::<T>(USER_SEED, 100, Default::default())?;
 		assert_eq!(Payee::<T>::get(&stash), RewardDestination::Staked);
+		let payee = create_funded_user::<T>("payee", USER_SEED, 100);
 		whitelist_account!(controller);
-	}: _(RawOrigin::Signed(controller), RewardDestination::Controller)
+	}: _(RawOrigin::Signed(controller), RewardDestination::Account(payee.clone()))
 	verify {
-		assert_eq!(Payee::<T>::get(&stash), RewardDestination::Controller);
+		assert_eq!(Payee::<T>::get(&stash), RewardDestination::Account(payee));
 	}
 
 	set_controller {
@@ -614,6 +616,43 @@ benchmarks! {
 		}
 	}
 
+	payout_stakers_by_page {
+		let n in 0 .. T::MaxExposurePageSize::get().min(T::MaxNominatorRewardedPerValidator::get());
+		let (validator, nominators) = create_validator_with_nominators::<T>(
+			n,
+			T::MaxNominatorRewardedPerValidator::get() as u32,
+			false,
+			RewardDestination::Staked,
+		)?;
+
+		let current_era = CurrentEra::<T>::get().unwrap();
+		// set the commission for this particular era as well.
+		<ErasValidatorPrefs<T>>::insert(current_era, validator.clone(), <Staking<T>>::validators(&validator));
+
+		let caller = whitelisted_caller();
+		let balance_before = T::Currency::free_balance(&validator);
+		let mut nominator_balances_before = Vec::new();
+		for (stash, _) in &nominators {
+			let balance = T::Currency::free_balance(stash);
+			nominator_balances_before.push(balance);
+		}
+		// page 0 also pays out the validator.
+	}: _(RawOrigin::Signed(caller), validator.clone(), current_era, 0)
+	verify {
+		let balance_after = T::Currency::free_balance(&validator);
+		ensure!(
+			balance_before < balance_after,
+			"Balance of validator stash should have increased after payout.",
+		);
+		for ((stash, _), balance_before) in nominators.iter().zip(nominator_balances_before.iter()) {
+			let balance_after = T::Currency::free_balance(stash);
+			ensure!(
+				balance_before < &balance_after,
+				"Balance of nominator stash should have increased after payout.",
+			);
+		}
+	}
+
 	rebond {
 		let l in 1 .. T::MaxUnlockingChunks::get() as u32;
 
@@ -785,6 +824,7 @@ benchmarks! {
 			slash_amount,
 			&mut BalanceOf::<T>::zero(),
 			&mut NegativeImbalanceOf::<T>::zero(),
+			EraIndex::zero(),
 			EraIndex::zero()
 		);
 	} verify {
@@ -922,6 +962,84 @@ benchmarks! {
 		);
 	}
 
+	force_apply_min_commission_batch {
+		let v in 0 .. T::MaxCommissionBatch::get();
+		// Clean up any existing state
+		clear_validators_and_nominators::<T>();
+
+		// Create `v` validators with a commission of 50%, all of which get bumped.
+		let validators: BoundedVec<_, T::MaxCommissionBatch> = create_validators::<T>(v, 100)?
+			.into_iter()
+			.map(|v| T::Lookup::lookup(v).unwrap())
+			.collect::<Vec<_>>()
+			.try_into()
+			.unwrap();
+
+		// Set the min commission to 75%
+		MinCommission::<T>::set(Perbill::from_percent(75));
+		let caller = whitelisted_caller();
+	}: _(RawOrigin::Signed(caller), validators.clone())
+	verify {
+		assert!(validators.iter().all(|stash| {
+			Validators::<T>::get(stash).commission == Perbill::from_percent(75)
+		}));
+	}
+
+	kick_below_minimum {
+		let s in 1 .. MAX_SPANS;
+		let v in 1 .. MaxValidators::<T>::get();
+		// clean up any existing state.
+		clear_validators_and_nominators::<T>();
+
+		let origin_weight = MinNominatorBond::<T>::get().max(T::Currency::minimum_balance());
+
+		// setup a worst case list scenario. Note that we don't care about the setup of the
+		// destination position because we are doing a removal from the list but no insert.
+		let scenario = ListScenario::<T>::new(origin_weight, true)?;
+		let stash = scenario.origin_stash1;
+		add_slashing_spans::<T>(&stash, s);
+
+		// the stash falls below the minimum once it is raised.
+		MinNominatorBond::<T>::put(origin_weight + One::one());
+
+		// fill every era that is checked with `v` full exposures, none of which include the
+		// stash. Every exposure is decoded and all of its nominators are compared to the stash.
+		let current_era = T::BondingDuration::get();
+		CurrentEra::<T>::put(current_era);
+		let value = T::Currency::minimum_balance();
+		let others = (0 .. T::MaxNominatorRewardedPerValidator::get())
+			.map(|n| IndividualExposure { who: account("nominator", n, SEED), value })
+			.collect::<Vec<_>>();
+		let exposure = Exposure {
+			total: value * (others.len() as u32 + 1).into(),
+			own: value,
+			others,
+		};
+		for era in 0 ..= current_era {
+			for i in 0 .. v {
+				let validator: T::AccountId = account("validator", i, SEED);
+				ErasStakers::<T>::insert(era, validator, exposure.clone());
+			}
+		}
+
+		// make sure the reward is actually paid out.
+		if let Some(pool) = T::KickRewardPool::get() {
+			T::Currency::make_free_balance_be(
+				&pool,
+				T::KickReward::get() + T::Currency::minimum_balance() * 10u32.into(),
+			);
+		}
+
+		assert!(Bonded::<T>::contains_key(&stash));
+		assert!(T::VoterList::contains(&stash));
+
+		let caller = whitelisted_caller();
+	}: _(RawOrigin::Signed(caller), stash.clone(), s)
+	verify {
+		assert!(!Bonded::<T>::contains_key(&stash));
+		assert!(!T::VoterList::contains(&stash));
+	}
+
 	set_min_commission {
 		let min_commission = Perbill::max_value();
 	}: _(RawOrigin::Root, min_commission)
@@ -929,6 +1047,27 @@ benchmarks! {
 		assert_eq!(MinCommission::<T>::get(), Perbill::from_percent(100));
 	}
 
+	repair_ledger {
+		// clean up any existing state.
+		clear_validators_and_nominators::<T>();
+
+		// the worst case is a nominator whose ledger got lost, which is removed from the list.
+		let origin_weight = MinNominatorBond::<T>::get().max(T::Currency::minimum_balance());
+		let scenario = ListScenario::<T>::new(origin_weight, true)?;
+		let controller = scenario.origin_controller1.clone();
+		let stash = scenario.origin_stash1;
+		Bonded::<T>::remove(&stash);
+		Ledger::<T>::remove(&controller);
+		assert!(T::VoterList::contains(&stash));
+
+		// only the stash itself can remove its leftovers.
+		whitelist_account!(stash);
+	}: _(RawOrigin::Signed(stash.clone()), stash.clone())
+	verify {
+		assert!(!T::VoterList::contains(&stash));
+		assert!(!Payee::<T>::contains_key(&stash));
+	}
+
 	impl_benchmark_test_suite!(
 		Staking,
 		crate::mock::ExtBuilder::default().has_stakers(true),
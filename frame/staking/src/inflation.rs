@@ -19,8 +19,29 @@
 //! the total payout for the era given the era duration and the staking rate in NPoS.
 //! The staking rate in NPoS is the total amount of tokens staked by nominators and validators,
 //! divided by the total token supply.
+//!
+//! It also provides [`EraPayout`] implementations that can be used instead of a reward curve:
+//! [`FixedRateEraPayout`] mints a fixed yearly rate of the total issuance and [`CappedInflation`]
+//! limits what any other implementation mints.
 
-use sp_runtime::{curve::PiecewiseLinear, traits::AtLeast32BitUnsigned, Perbill};
+use crate::EraPayout;
+use frame_support::traits::Get;
+use sp_runtime::{
+	curve::PiecewiseLinear,
+	traits::{AtLeast32BitUnsigned, Zero},
+	Perbill,
+};
+use sp_std::marker::PhantomData;
+
+/// Milliseconds per year for the Julian year (365.25 days).
+const MILLISECONDS_PER_YEAR: u64 = 1000 * 3600 * 24 * 36525 / 100;
+
+/// The portion of a year that passed during an era of `era_duration` milliseconds.
+///
+/// Eras lasting longer than a year are rewarded as a year.
+fn era_portion(era_duration: u64) -> Perbill {
+	Perbill::from_rational(era_duration, MILLISECONDS_PER_YEAR)
+}
 
 /// The total payout to all validators (and their nominators) per era and maximum payout.
 ///
@@ -38,10 +59,7 @@ pub fn compute_total_payout<N>(
 where
 	N: AtLeast32BitUnsigned + Clone,
 {
-	// Milliseconds per year for the Julian year (365.25 days).
-	const MILLISECONDS_PER_YEAR: u64 = 1000 * 3600 * 24 * 36525 / 100;
-
-	let portion = Perbill::from_rational(era_duration as u64, MILLISECONDS_PER_YEAR);
+	let portion = era_portion(era_duration);
 	let payout = portion *
 		yearly_inflation
 			.calculate_for_fraction_times_denominator(npos_token_staked, total_tokens.clone());
@@ -49,9 +67,61 @@ where
 	(payout, maximum)
 }
 
+/// An [`EraPayout`] that mints a fixed yearly `Rate` of the total issuance, regardless of how
+/// much of it is staked.
+///
+/// The yearly amount is pro-rated by the duration of the era in the same way as
+/// [`compute_total_payout`] does. Everything is paid to the stakers and nothing is left for the
+/// remainder.
+pub struct FixedRateEraPayout<Rate>(PhantomData<Rate>);
+
+impl<Balance, Rate> EraPayout<Balance> for FixedRateEraPayout<Rate>
+where
+	Balance: AtLeast32BitUnsigned + Clone,
+	Rate: Get<Perbill>,
+{
+	fn era_payout(
+		_total_staked: Balance,
+		total_issuance: Balance,
+		era_duration_millis: u64,
+	) -> (Balance, Balance) {
+		let payout = era_portion(era_duration_millis) * (Rate::get() * total_issuance);
+		(payout, Zero::zero())
+	}
+}
+
+/// Limits the total amount minted by the `Inner` [`EraPayout`] to a yearly `MaxRate` of the
+/// total issuance.
+///
+/// The limit is pro-rated like in [`FixedRateEraPayout`]. It is applied to the payout of the
+/// stakers first, the remainder gets whatever is left of it.
+pub struct CappedInflation<Inner, MaxRate>(PhantomData<(Inner, MaxRate)>);
+
+impl<Balance, Inner, MaxRate> EraPayout<Balance> for CappedInflation<Inner, MaxRate>
+where
+	Balance: AtLeast32BitUnsigned + Clone,
+	Inner: EraPayout<Balance>,
+	MaxRate: Get<Perbill>,
+{
+	fn era_payout(
+		total_staked: Balance,
+		total_issuance: Balance,
+		era_duration_millis: u64,
+	) -> (Balance, Balance) {
+		let cap = era_portion(era_duration_millis) * (MaxRate::get() * total_issuance.clone());
+		let (payout, rest) = Inner::era_payout(total_staked, total_issuance, era_duration_millis);
+		let payout = payout.min(cap.clone());
+		let rest = rest.min(cap.saturating_sub(payout.clone()));
+		(payout, rest)
+	}
+}
+
 #[cfg(test)]
 mod test {
-	use sp_runtime::curve::PiecewiseLinear;
+	use super::{CappedInflation, FixedRateEraPayout};
+	use crate::{ConvertCurve, EraPayout};
+	use frame_support::parameter_types;
+	use sp_runtime::{curve::PiecewiseLinear, Perbill};
 
 	pallet_staking_reward_curve::build! {
 		const I_NPOS: PiecewiseLinear<'static> = curve!(
@@ -105,4 +175,64 @@ mod test {
 			57_038_500_000_000_000_000_000
 		);
 	}
+
+	parameter_types! {
+		const NposCurve: &'static PiecewiseLinear<'static> = &I_NPOS;
+		const FivePercent: Perbill = Perbill::from_percent(5);
+		const TenPercent: Perbill = Perbill::from_percent(10);
+	}
+
+	const DAY: u64 = 24 * 60 * 60 * 1000;
+	const JULIAN_YEAR: u64 = 36525 * DAY / 100;
+
+	#[test]
+	fn fixed_rate_era_payout_works() {
+		type Payout = FixedRateEraPayout<TenPercent>;
+
+		// a julian year mints the whole rate, regardless of the stake.
+		assert_eq!(Payout::era_payout(0, 100_000u64, JULIAN_YEAR), (10_000, 0));
+		assert_eq!(Payout::era_payout(100_000, 100_000u64, JULIAN_YEAR), (10_000, 0));
+
+		// shorter eras are pro-rated.
+		const ISSUANCE: u128 = 1_000_000_000_000;
+		assert_eq!(Payout::era_payout(0, ISSUANCE, DAY), (273_785_000, 0));
+		assert_eq!(Payout::era_payout(0, ISSUANCE, 365 * DAY), (99_931_553_700, 0));
+
+		// an era longer than the julian year, like a leap year, is rewarded as a year.
+		assert_eq!(Payout::era_payout(0, ISSUANCE, 366 * DAY), (100_000_000_000, 0));
+		assert_eq!(Payout::era_payout(0, ISSUANCE, u64::MAX), (100_000_000_000, 0));
+
+		// nothing is minted without issuance or time.
+		assert_eq!(Payout::era_payout(0, 0u128, JULIAN_YEAR), (0, 0));
+		assert_eq!(Payout::era_payout(0, 0u128, u64::MAX), (0, 0));
+		assert_eq!(Payout::era_payout(0, ISSUANCE, 0), (0, 0));
+	}
+
+	#[test]
+	fn capped_inflation_works() {
+		type Curve = ConvertCurve<NposCurve>;
+		type Capped = CappedInflation<Curve, FivePercent>;
+
+		// the cap applies to the payout of the stakers first.
+		assert_eq!(Capped::era_payout(50_000, 100_000u64, JULIAN_YEAR), (5_000, 0));
+
+		// the remainder gets whatever is left of it.
+		let (payout, rest) = Curve::era_payout(0, 100_000u64, JULIAN_YEAR);
+		assert!(payout < 5_000 && payout + rest > 5_000);
+		assert_eq!(Capped::era_payout(0, 100_000u64, JULIAN_YEAR), (payout, 5_000 - payout));
+
+		// a cap above the maximum of the inner payout has no effect.
+		type Uncapped = CappedInflation<Curve, TenPercent>;
+		let payout = Curve::era_payout(25_000, 100_000u64, DAY);
+		assert_eq!(Uncapped::era_payout(25_000, 100_000u64, DAY), payout);
+
+		// other implementations can be capped as well.
+		type CappedFixedRate = CappedInflation<FixedRateEraPayout<TenPercent>, FivePercent>;
+		assert_eq!(CappedFixedRate::era_payout(0, 100_000u64, JULIAN_YEAR), (5_000, 0));
+		assert_eq!(CappedFixedRate::era_payout(0, 100_000u64, 366 * DAY), (5_000, 0));
+
+		// nothing is minted without issuance or time.
+		assert_eq!(Capped::era_payout(0, 0u64, JULIAN_YEAR), (0, 0));
+		assert_eq!(Capped::era_payout(0, 100_000u64, 0), (0, 0));
+	}
 }
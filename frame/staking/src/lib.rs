@@ -116,7 +116,9 @@
 //! `payout_stakers` call. Any account can call `payout_stakers`, which pays the reward to the
 //! validator as well as its nominators. Only the [`Config::MaxNominatorRewardedPerValidator`]
 //! biggest stakers can claim their reward. This is to limit the i/o cost to mutate storage for each
-//! nominator's account.
+//! nominator's account. These stakers are split into pages of at most
+//! [`Config::MaxExposurePageSize`] nominators. `payout_stakers` pays out all of them at once,
+//! while `payout_stakers_by_page` pays out a single page.
 //!
 //! Slashing can occur at any point in time, once misbehavior is reported. Once slashing is
 //! determined, a value is deducted from the balance of the validator and all the nominators who
@@ -203,6 +205,9 @@
 //! The remaining reward is send to the configurable end-point
 //! [`Config::RewardRemainder`].
 //!
+//! Runtimes that don't want to use a curve can use [`inflation::FixedRateEraPayout`] instead, and
+//! limit any [`EraPayout`] with [`inflation::CappedInflation`].
+//!
 //! ### Reward Calculation
 //!
 //! Validators and nominators are rewarded at the end of each era. The total reward of an era is
@@ -340,6 +345,9 @@ pub type MaxWinnersOf<T> = <<T as Config>::ElectionProvider as frame_election_pr
 /// Counter for the number of "reward" points earned by a given validator.
 pub type RewardPoint = u32;
 
+/// Index of a page of the nominators of a validator's exposure.
+pub type Page = u32;
+
 /// The balance type of this pallet.
 pub type BalanceOf<T> = <T as Config>::CurrencyBalance;
 
@@ -567,6 +575,20 @@ impl<T: Config> StakingLedger<T> {
 		slash_amount: BalanceOf<T>,
 		minimum_balance: BalanceOf<T>,
 		slash_era: EraIndex,
+	) -> BalanceOf<T> {
+		self.slash_for_offence(slash_amount, minimum_balance, slash_era, slash_era)
+	}
+
+	/// Same as [`Self::slash`], but reports `offence_era` instead of `slash_era` to
+	/// `Config::OnStakerSlash::on_slash`.
+	///
+	/// Deferred slashes are applied with a `slash_era` one past the era of the offence.
+	pub(crate) fn slash_for_offence(
+		&mut self,
+		slash_amount: BalanceOf<T>,
+		minimum_balance: BalanceOf<T>,
+		slash_era: EraIndex,
+		offence_era: EraIndex,
 	) -> BalanceOf<T> {
 		if slash_amount.is_zero() {
 			return Zero::zero()
@@ -678,8 +700,15 @@ impl<T: Config> StakingLedger<T> {
 		// clean unlocking chunks that are set to zero.
 		self.unlocking.retain(|c| !c.value.is_zero());
 
-		T::OnStakerSlash::on_slash(&self.stash, self.active, &slashed_unlocking);
-		pre_slash_total.saturating_sub(self.total)
+		let slashed_total = pre_slash_total.saturating_sub(self.total);
+		T::OnStakerSlash::on_slash(
+			&self.stash,
+			self.active,
+			&slashed_unlocking,
+			slashed_total,
+			offence_era,
+		);
+		slashed_total
 	}
 }
 
@@ -732,6 +761,25 @@ impl<AccountId, Balance: Default + HasCompact> Default for Exposure<AccountId, B
 	}
 }
 
+impl<AccountId, Balance: HasCompact> Exposure<AccountId, Balance> {
+	/// The number of pages of at most `page_size` nominators this exposure is split into.
+	///
+	/// An exposure without any nominators still has a single page, through which the validator
+	/// is paid out.
+	pub fn page_count(&self, page_size: u32) -> Page {
+		let page_size = page_size.max(1) as usize;
+		(self.others.len().saturating_sub(1) / page_size).saturating_add(1) as Page
+	}
+
+	/// The nominators that are part of `page`, given at most `page_size` nominators per page.
+	pub fn page(&self, page: Page, page_size: u32) -> &[IndividualExposure<AccountId, Balance>] {
+		let page_size = page_size.max(1) as usize;
+		let start = (page as usize).saturating_mul(page_size).min(self.others.len());
+		let end = start.saturating_add(page_size).min(self.others.len());
+		&self.others[start..end]
+	}
+}
+
 /// A pending slash record. The value of the slash has been computed but not applied yet,
 /// rather deferred for several eras.
 #[derive(Encode, Decode, RuntimeDebug, TypeInfo)]
@@ -906,6 +954,18 @@ impl Default for Releases {
 	}
 }
 
+/// The maximum number of pages the clipped exposure of a validator can be split into.
+///
+/// This is the page count of an exposure with `MaxNominatorRewardedPerValidator` nominators.
+pub struct MaxExposurePagesOf<T>(sp_std::marker::PhantomData<T>);
+
+impl<T: Config> Get<u32> for MaxExposurePagesOf<T> {
+	fn get() -> u32 {
+		let page_size = T::MaxExposurePageSize::get().max(1);
+		(T::MaxNominatorRewardedPerValidator::get().saturating_sub(1) / page_size).saturating_add(1)
+	}
+}
+
 /// A `Convert` implementation that finds the stash of the given controller account,
 /// if any.
 pub struct StashOf<T>(sp_std::marker::PhantomData<T>);
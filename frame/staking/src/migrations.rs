@@ -191,7 +191,7 @@ pub mod v10 {
 						// in the old slashing scheme, the slash era was the key at which we read
 						// from `UnappliedSlashes`.
 						log!(warn, "prematurely applying a slash ({:?}) for era {:?}", slash, era);
-						slashing::apply_slash::<T>(slash, era);
+						slashing::apply_slash::<T>(slash, era, era);
 					}
 				}
 
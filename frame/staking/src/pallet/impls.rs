@@ -44,8 +44,8 @@ use sp_std::prelude::*;
 
 use crate::{
 	log, slashing, weights::WeightInfo, ActiveEraInfo, BalanceOf, EraPayout, Exposure, ExposureOf,
-	Forcing, IndividualExposure, MaxWinnersOf, Nominations, PositiveImbalanceOf, RewardDestination,
-	SessionInterface, StakingLedger, ValidatorPrefs,
+	Forcing, IndividualExposure, MaxWinnersOf, Nominations, Page, PositiveImbalanceOf,
+	RewardDestination, SessionInterface, StakingLedger, ValidatorPrefs,
 };
 
 use super::{pallet::*, STAKING_ID};
@@ -59,6 +59,107 @@ use super::{pallet::*, STAKING_ID};
 const NPOS_MAX_ITERATIONS_COEFFICIENT: u32 = 2;
 
 impl<T: Config> Pallet<T> {
+	/// The number of slashing spans of `stash`, as expected by the `num_slashing_spans` parameter
+	/// of calls that might remove the stash.
+	pub fn slashing_spans_count(stash: &T::AccountId) -> u32 {
+		Self::slashing_spans(stash).map_or(0, |s| s.iter().count() as u32)
+	}
+
+	/// Ensure that the account of a [`RewardDestination::Account`] exists.
+	///
+	/// All other destinations are accounts of the staker itself and hence always valid.
+	pub(crate) fn ensure_payee_exists(payee: &RewardDestination<T::AccountId>) -> DispatchResult {
+		if let RewardDestination::Account(account) = payee {
+			ensure!(
+				frame_system::Pallet::<T>::account_exists(account),
+				Error::<T>::PayeeDoesNotExist
+			);
+		}
+		Ok(())
+	}
+
+	/// Whether `stash` has been exposed in `era`.
+	///
+	/// Same check as [`StakingInterface::is_exposed_in_era`], except that eras which are not
+	/// retained anymore, or not planned yet, are never reported as exposed.
+	pub fn exposed_in_era(stash: T::AccountId, era: EraIndex) -> bool {
+		!Self::eras_of_exposure(stash, era, era).is_empty()
+	}
+
+	/// All eras in `from..=to` in which `stash` has been exposed.
+	///
+	/// The range is clamped to the last `HistoryDepth` eras up to and including the current
+	/// era, which are the only ones for which exposures are retained.
+	pub fn eras_of_exposure(stash: T::AccountId, from: EraIndex, to: EraIndex) -> Vec<EraIndex> {
+		let current_era = match Self::current_era() {
+			Some(era) => era,
+			None => return Vec::new(),
+		};
+		let from = from.max(current_era.saturating_sub(T::HistoryDepth::get()));
+		let to = to.min(current_era);
+
+		(from..=to)
+			.filter(|era| <Self as StakingInterface>::is_exposed_in_era(&stash, era))
+			.collect()
+	}
+
+	/// The expected number of blocks that an era lasts, based on the average session length.
+	pub fn expected_era_duration() -> T::BlockNumber {
+		T::NextNewSession::average_session_length().saturating_mul(T::SessionsPerEra::get().into())
+	}
+
+	/// The estimated number of blocks until `era` becomes the active era.
+	///
+	/// Returns `Some(0)` if `era` is already active or has passed. Returns `None` if there is no
+	/// active era yet, or if new eras are not going to be planned because of
+	/// [`Forcing::ForceNone`].
+	pub fn eras_remaining_blocks(era: EraIndex) -> Option<T::BlockNumber> {
+		let active_era = Self::active_era()?.index;
+		if era <= active_era {
+			return Some(Zero::zero())
+		}
+
+		let now = <frame_system::Pallet<T>>::block_number();
+		let session_length = T::NextNewSession::average_session_length();
+		let force_era = ForceEra::<T>::get();
+		let next_era_planned = Self::current_era().unwrap_or(0) > active_era;
+		let eras_to_plan = (era - active_era).saturating_sub(next_era_planned as EraIndex);
+		if eras_to_plan > 0 && force_era == Forcing::ForceNone {
+			return None
+		}
+
+		// a planned era becomes active at the start of the session after it has been planned.
+		let until_next_era = if next_era_planned {
+			T::NextNewSession::estimate_next_new_session(now)
+				.0
+				.unwrap_or_default()
+				.saturating_sub(now)
+		} else {
+			<Self as ElectionDataProvider>::next_election_prediction(now)
+				.saturating_sub(now)
+				.saturating_add(session_length)
+		};
+		let era_length = match force_era {
+			Forcing::ForceAlways => session_length,
+			_ => Self::expected_era_duration(),
+		};
+		let later_eras: T::BlockNumber = (era - active_era - 1).into();
+
+		Some(until_next_era.saturating_add(era_length.saturating_mul(later_eras)))
+	}
+
+	/// The estimated number of blocks until all the unlocking funds of `stash` can be withdrawn.
+	///
+	/// Returns `Some(0)` if nothing is unlocking, and `None` if `stash` is not bonded or if no
+	/// estimate is possible, see [`Self::eras_remaining_blocks`].
+	pub fn unbonding_blocks_remaining(stash: &T::AccountId) -> Option<T::BlockNumber> {
+		let ledger = Self::ledger(Self::bonded(stash)?)?;
+		match ledger.unlocking.iter().map(|chunk| chunk.era).max() {
+			Some(era) => Self::eras_remaining_blocks(era),
+			None => Some(Zero::zero()),
+		}
+	}
+
 	/// The total balance that can be slashed from a stash account as of right now.
 	pub fn slashable_balance_of(stash: &T::AccountId) -> BalanceOf<T> {
 		// Weight note: consider making the stake accessible through stash.
@@ -131,9 +232,14 @@ impl<T: Config> Pallet<T> {
 		Ok(used_weight)
 	}
 
+	/// Pay out the stakers of `validator_stash` for `era`.
+	///
+	/// Only the given page of the exposure is paid out, or all pages that have not been paid
+	/// out yet if `page` is `None`.
 	pub(super) fn do_payout_stakers(
 		validator_stash: T::AccountId,
 		era: EraIndex,
+		page: Option<Page>,
 	) -> DispatchResultWithPostInfo {
 		// Validate input data
 		let current_era = CurrentEra::<T>::get().ok_or_else(|| {
@@ -159,28 +265,57 @@ impl<T: Config> Pallet<T> {
 		})?;
 		let mut ledger = <Ledger<T>>::get(&controller).ok_or(Error::<T>::NotController)?;
 
+		let exposure = <ErasStakersClipped<T>>::get(&era, &ledger.stash);
+		let page_size = T::MaxExposurePageSize::get();
+		let page_count = exposure.page_count(page_size);
+		ensure!(
+			page.map_or(true, |page| page < page_count),
+			Error::<T>::InvalidPage.with_weight(T::WeightInfo::payout_stakers_alive_staked(0))
+		);
+
 		ledger
 			.claimed_rewards
 			.retain(|&x| x >= current_era.saturating_sub(history_depth));
 
+		let mut claimed_pages = <ClaimedRewards<T>>::get(&era, &ledger.stash);
+		let pages: Vec<Page> = match page {
+			Some(page) if claimed_pages.contains(&page) => Vec::new(),
+			Some(page) => vec![page],
+			None => (0..page_count).filter(|page| !claimed_pages.contains(page)).collect(),
+		};
 		match ledger.claimed_rewards.binary_search(&era) {
 			Ok(_) =>
 				return Err(Error::<T>::AlreadyClaimed
 					.with_weight(T::WeightInfo::payout_stakers_alive_staked(0))),
-			Err(pos) => ledger
-				.claimed_rewards
-				.try_insert(pos, era)
-				// Since we retain era entries in `claimed_rewards` only upto
-				// `HistoryDepth`, following bound is always expected to be
-				// satisfied.
-				.defensive_map_err(|_| Error::<T>::BoundNotMet)?,
+			Err(_) if pages.is_empty() =>
+				return Err(Error::<T>::AlreadyClaimed
+					.with_weight(T::WeightInfo::payout_stakers_alive_staked(0))),
+			Err(pos) => {
+				for page in pages.iter() {
+					// `page_count` never exceeds the bound, and each page is only pushed once.
+					claimed_pages.try_push(*page).defensive_map_err(|_| Error::<T>::BoundNotMet)?;
+				}
+				// The era is only recorded in the ledger once all pages have been paid out.
+				if claimed_pages.len() as u32 == page_count {
+					ledger
+						.claimed_rewards
+						.try_insert(pos, era)
+						// Since we retain era entries in `claimed_rewards` only upto
+						// `HistoryDepth`, following bound is always expected to be
+						// satisfied.
+						.defensive_map_err(|_| Error::<T>::BoundNotMet)?;
+				}
+			},
 		}
 
-		let exposure = <ErasStakersClipped<T>>::get(&era, &ledger.stash);
-
 		// Input data seems good, no errors allowed after this point
 
 		<Ledger<T>>::insert(&controller, &ledger);
+		if claimed_pages.len() as u32 == page_count {
+			<ClaimedRewards<T>>::remove(&era, &ledger.stash);
+		} else {
+			<ClaimedRewards<T>>::insert(&era, &ledger.stash, claimed_pages);
+		}
 
 		// Get Era reward points. It has TOTAL and INDIVIDUAL
 		// Find the fraction of the era reward that belongs to the validator
@@ -220,49 +355,63 @@ impl<T: Config> Pallet<T> {
 		let validator_exposure_part = Perbill::from_rational(exposure.own, exposure.total);
 		let validator_staking_payout = validator_exposure_part * validator_leftover_payout;
 
-		Self::deposit_event(Event::<T>::PayoutStarted {
-			era_index: era,
-			validator_stash: ledger.stash.clone(),
-		});
-
 		let mut total_imbalance = PositiveImbalanceOf::<T>::zero();
-		// We can now make total validator payout:
-		if let Some(imbalance) =
-			Self::make_payout(&ledger.stash, validator_staking_payout + validator_commission_payout)
-		{
-			Self::deposit_event(Event::<T>::Rewarded {
-				stash: ledger.stash,
-				amount: imbalance.peek(),
-			});
-			total_imbalance.subsume(imbalance);
-		}
 
 		// Track the number of payout ops to nominators. Note:
 		// `WeightInfo::payout_stakers_alive_staked` always assumes at least a validator is paid
 		// out, so we do not need to count their payout op.
 		let mut nominator_payout_count: u32 = 0;
 
-		// Lets now calculate how this is split to the nominators.
-		// Reward only the clipped exposures. Note this is not necessarily sorted.
-		for nominator in exposure.others.iter() {
-			let nominator_exposure_part = Perbill::from_rational(nominator.value, exposure.total);
-
-			let nominator_reward: BalanceOf<T> =
-				nominator_exposure_part * validator_leftover_payout;
-			// We can now make nominator payout:
-			if let Some(imbalance) = Self::make_payout(&nominator.who, nominator_reward) {
-				// Note: this logic does not count payouts for `RewardDestination::None`.
-				nominator_payout_count += 1;
-				let e =
-					Event::<T>::Rewarded { stash: nominator.who.clone(), amount: imbalance.peek() };
-				Self::deposit_event(e);
-				total_imbalance.subsume(imbalance);
+		for page in pages {
+			Self::deposit_event(Event::<T>::PayoutStarted {
+				era_index: era,
+				validator_stash: ledger.stash.clone(),
+				page,
+			});
+
+			// We can now make total validator payout, which happens along with the first page:
+			if page.is_zero() {
+				if let Some(imbalance) = Self::make_payout(
+					&ledger.stash,
+					validator_staking_payout + validator_commission_payout,
+				) {
+					Self::deposit_event(Event::<T>::Rewarded {
+						stash: ledger.stash.clone(),
+						amount: imbalance.peek(),
+					});
+					total_imbalance.subsume(imbalance);
+				}
+			}
+
+			// Lets now calculate how this is split to the nominators.
+			// Reward only the clipped exposures of this page. Note this is not necessarily sorted.
+			for nominator in exposure.page(page, page_size).iter() {
+				let nominator_exposure_part =
+					Perbill::from_rational(nominator.value, exposure.total);
+
+				let nominator_reward: BalanceOf<T> =
+					nominator_exposure_part * validator_leftover_payout;
+				// We can now make nominator payout:
+				if let Some(imbalance) = Self::make_payout(&nominator.who, nominator_reward) {
+					// Note: this logic does not count payouts for `RewardDestination::None`.
+					nominator_payout_count += 1;
+					let e = Event::<T>::Rewarded {
+						stash: nominator.who.clone(),
+						amount: imbalance.peek(),
+					};
+					Self::deposit_event(e);
+					total_imbalance.subsume(imbalance);
+				}
 			}
 		}
 
 		T::Reward::on_unbalanced(total_imbalance);
 		debug_assert!(nominator_payout_count <= T::MaxNominatorRewardedPerValidator::get());
-		Ok(Some(T::WeightInfo::payout_stakers_alive_staked(nominator_payout_count)).into())
+		let actual_weight = match page {
+			Some(_) => T::WeightInfo::payout_stakers_by_page(nominator_payout_count),
+			None => T::WeightInfo::payout_stakers_alive_staked(nominator_payout_count),
+		};
+		Ok(Some(actual_weight).into())
 	}
 
 	/// Update the ledger for a controller.
@@ -673,6 +822,8 @@ impl<T: Config> Pallet<T> {
 		#[allow(deprecated)]
 		<ErasStakersClipped<T>>::remove_prefix(era_index, None);
 		#[allow(deprecated)]
+		<ClaimedRewards<T>>::remove_prefix(era_index, None);
+		#[allow(deprecated)]
 		<ErasValidatorPrefs<T>>::remove_prefix(era_index, None);
 		<ErasValidatorReward<T>>::remove(era_index);
 		<ErasRewardPoints<T>>::remove(era_index);
@@ -689,9 +840,12 @@ impl<T: Config> Pallet<T> {
 			era_slashes.len(),
 			active_era,
 		);
+		let slash_era = active_era.saturating_sub(T::SlashDeferDuration::get());
+		// offences of `offence_era` are deferred to `offence_era + SlashDeferDuration + 1`, see
+		// `on_offence`.
+		let offence_era = slash_era.saturating_sub(One::one());
 		for slash in era_slashes {
-			let slash_era = active_era.saturating_sub(T::SlashDeferDuration::get());
-			slashing::apply_slash::<T>(slash, slash_era);
+			slashing::apply_slash::<T>(slash, slash_era, offence_era);
 		}
 	}
 
@@ -1339,7 +1493,7 @@ where
 				unapplied.reporters = details.reporters.clone();
 				if slash_defer_duration == 0 {
 					// Apply right away.
-					slashing::apply_slash::<T>(unapplied, slash_era);
+					slashing::apply_slash::<T>(unapplied, slash_era, slash_era);
 					{
 						let slash_cost = (6, 5);
 						let reward_cost = (2, 2);
@@ -1560,16 +1714,32 @@ impl<T: Config> StakingInterface for Pallet<T> {
 		MinValidatorBond::<T>::get()
 	}
 
+	fn minimum_active_stake() -> Self::Balance {
+		MinimumActiveStake::<T>::get()
+	}
+
 	fn desired_validator_count() -> u32 {
 		ValidatorCount::<T>::get()
 	}
 
+	fn max_nominations() -> u32 {
+		T::MaxNominations::get()
+	}
+
+	fn nominators_count() -> u32 {
+		Nominators::<T>::count()
+	}
+
+	fn max_nominators() -> Option<u32> {
+		MaxNominatorsCount::<T>::get()
+	}
+
 	fn election_ongoing() -> bool {
 		T::ElectionProvider::ongoing()
 	}
 
 	fn force_unstake(who: Self::AccountId) -> sp_runtime::DispatchResult {
-		let num_slashing_spans = Self::slashing_spans(&who).map_or(0, |s| s.iter().count() as u32);
+		let num_slashing_spans = Self::slashing_spans_count(&who);
 		Self::force_unstake(RawOrigin::Root.into(), who.clone(), num_slashing_spans)
 	}
 
@@ -1611,6 +1781,40 @@ impl<T: Config> StakingInterface for Pallet<T> {
 			.map(|_| ())
 	}
 
+	fn unbond_instantly(who: &Self::AccountId, value: Self::Balance) -> DispatchResult {
+		let ctrl = Self::bonded(who).ok_or(Error::<T>::NotStash)?;
+		let mut ledger = Self::ledger(&ctrl).ok_or(Error::<T>::NotController)?;
+		ensure!(value <= ledger.active, Error::<T>::InsufficientBond);
+
+		// funds that can still be slashed must not be released early.
+		let current_era = Self::current_era().unwrap_or(Zero::zero());
+		let from = current_era.saturating_sub(T::BondingDuration::get());
+		ensure!(
+			Self::eras_of_exposure(who.clone(), from, current_era).is_empty(),
+			Error::<T>::RecentlyExposed
+		);
+
+		ledger.active -= value;
+		ledger.total -= value;
+		let min_active_bond = if Nominators::<T>::contains_key(&ledger.stash) {
+			MinNominatorBond::<T>::get()
+		} else if Validators::<T>::contains_key(&ledger.stash) {
+			MinValidatorBond::<T>::get()
+		} else {
+			Zero::zero()
+		};
+		ensure!(ledger.active >= min_active_bond, Error::<T>::InsufficientBond);
+
+		// NOTE: ledger must be updated prior to calling `Self::weight_of`.
+		Self::update_ledger(&ctrl, &ledger);
+		if T::VoterList::contains(&ledger.stash) {
+			let _ =
+				T::VoterList::on_update(&ledger.stash, Self::weight_of(&ledger.stash)).defensive();
+		}
+		Self::deposit_event(Event::<T>::Withdrawn { stash: ledger.stash, amount: value });
+		Ok(())
+	}
+
 	fn chill(who: &Self::AccountId) -> DispatchResult {
 		// defensive-only: any account bonded via this interface has the stash set as the
 		// controller, but we have to be sure. Same comment anywhere else that we read this.
@@ -1628,6 +1832,20 @@ impl<T: Config> StakingInterface for Pallet<T> {
 			.map_err(|with_post| with_post.error)
 	}
 
+	fn slashing_spans_count(stash: &Self::AccountId) -> u32 {
+		Self::slashing_spans_count(stash)
+	}
+
+	fn payee(stash: &Self::AccountId) -> Option<Self::AccountId> {
+		let controller = Self::bonded(stash)?;
+		match Payee::<T>::get(stash) {
+			RewardDestination::Staked | RewardDestination::Stash => Some(stash.clone()),
+			RewardDestination::Controller => Some(controller),
+			RewardDestination::Account(account) => Some(account),
+			RewardDestination::None => None,
+		}
+	}
+
 	fn bond(
 		who: &Self::AccountId,
 		value: Self::Balance,
@@ -1647,11 +1865,11 @@ impl<T: Config> StakingInterface for Pallet<T> {
 		Self::nominate(RawOrigin::Signed(ctrl).into(), targets)
 	}
 
-	sp_staking::runtime_benchmarks_enabled! {
-		fn nominations(who: Self::AccountId) -> Option<Vec<T::AccountId>> {
-			Nominators::<T>::get(who).map(|n| n.targets.into_inner())
-		}
+	fn nominations(who: Self::AccountId) -> Option<Vec<T::AccountId>> {
+		Nominators::<T>::get(who).map(|n| n.targets.into_inner())
+	}
 
+	sp_staking::runtime_benchmarks_enabled! {
 		fn add_era_stakers(
 			current_era: &EraIndex,
 			stash: &T::AccountId,
@@ -1683,6 +1901,7 @@ impl<T: Config> Pallet<T> {
 		Self::check_nominators()?;
 		Self::check_exposures()?;
 		Self::check_ledgers()?;
+		Self::check_stash_mappings()?;
 		Self::check_count()
 	}
 
@@ -1706,10 +1925,30 @@ impl<T: Config> Pallet<T> {
 
 	fn check_ledgers() -> Result<(), &'static str> {
 		Bonded::<T>::iter()
-			.map(|(_, ctrl)| Self::ensure_ledger_consistent(ctrl))
+			.map(|(stash, ctrl)| Self::ensure_ledger_consistent(stash, ctrl))
 			.collect::<Result<_, _>>()
 	}
 
+	/// The invariants that [`Call::repair_ledger`] restores, complementing `check_ledgers`.
+	fn check_stash_mappings() -> Result<(), &'static str> {
+		// every ledger is the one of the stash that is bonded to its controller.
+		for (ctrl, ledger) in Ledger::<T>::iter() {
+			ensure!(Self::bonded(&ledger.stash) == Some(ctrl), "ledger of unbonded controller");
+		}
+
+		// everything else that is keyed by stash only exists as long as the stash is bonded.
+		ensure!(Payee::<T>::iter_keys().all(|s| Bonded::<T>::contains_key(s)), "stale payee");
+		ensure!(
+			Validators::<T>::iter_keys().all(|s| Bonded::<T>::contains_key(s)),
+			"stale validator"
+		);
+		ensure!(
+			Nominators::<T>::iter_keys().all(|s| Bonded::<T>::contains_key(s)),
+			"stale nominator"
+		);
+		Ok(())
+	}
+
 	fn check_exposures() -> Result<(), &'static str> {
 		// a check per validator to ensure the exposure struct is always sane.
 		let era = Self::active_era().unwrap().index;
@@ -1771,9 +2010,13 @@ impl<T: Config> Pallet<T> {
 		Ok(())
 	}
 
-	fn ensure_ledger_consistent(ctrl: T::AccountId) -> Result<(), &'static str> {
+	fn ensure_ledger_consistent(
+		stash: T::AccountId,
+		ctrl: T::AccountId,
+	) -> Result<(), &'static str> {
 		// ensures ledger.total == ledger.active + sum(ledger.unlocking).
 		let ledger = Self::ledger(ctrl.clone()).ok_or("Not a controller.")?;
+		ensure!(ledger.stash == stash, "ledger of another stash");
 		let real_total: BalanceOf<T> =
 			ledger.unlocking.iter().fold(ledger.active, |a, c| a + c.value);
 		ensure!(real_total == ledger.total, "ledger.total corrupt");
@@ -25,8 +25,8 @@ use frame_support::{
 	pallet_prelude::*,
 	traits::{
 		Currency, CurrencyToVote, Defensive, DefensiveResult, DefensiveSaturating, EnsureOrigin,
-		EstimateNextNewSession, Get, LockIdentifier, LockableCurrency, OnUnbalanced, TryCollect,
-		UnixTime,
+		EstimateNextNewSession, ExistenceRequirement, Get, LockIdentifier, LockableCurrency,
+		OnUnbalanced, TryCollect, UnixTime,
 	},
 	weights::Weight,
 	BoundedVec,
@@ -36,7 +36,7 @@ use sp_runtime::{
 	traits::{CheckedSub, SaturatedConversion, StaticLookup, Zero},
 	ArithmeticError, Perbill, Percent,
 };
-use sp_staking::{EraIndex, SessionIndex};
+use sp_staking::{EraIndex, OnStakingOperation, SessionIndex, StakingOperation};
 use sp_std::prelude::*;
 
 mod impls;
@@ -45,9 +45,9 @@ pub use impls::*;
 
 use crate::{
 	slashing, weights::WeightInfo, AccountIdLookupOf, ActiveEraInfo, BalanceOf, EraPayout,
-	EraRewardPoints, Exposure, Forcing, NegativeImbalanceOf, Nominations, PositiveImbalanceOf,
-	Releases, RewardDestination, SessionInterface, StakingLedger, UnappliedSlash, UnlockChunk,
-	ValidatorPrefs,
+	EraRewardPoints, Exposure, Forcing, MaxExposurePagesOf, NegativeImbalanceOf, Nominations, Page,
+	PositiveImbalanceOf, Releases, RewardDestination, SessionInterface, StakingLedger,
+	UnappliedSlash, UnlockChunk, ValidatorPrefs,
 };
 
 const STAKING_ID: LockIdentifier = *b"staking ";
@@ -135,7 +135,7 @@ pub mod pallet {
 		/// Following information is kept for eras in `[current_era -
 		/// HistoryDepth, current_era]`: `ErasStakers`, `ErasStakersClipped`,
 		/// `ErasValidatorPrefs`, `ErasValidatorReward`, `ErasRewardPoints`,
-		/// `ErasTotalStake`, `ErasStartSessionIndex`,
+		/// `ErasTotalStake`, `ErasStartSessionIndex`, `ClaimedRewards`,
 		/// `StakingLedger.claimed_rewards`.
 		///
 		/// Must be more than the number of eras delayed by session.
@@ -206,6 +206,13 @@ pub mod pallet {
 		#[pallet::constant]
 		type MaxNominatorRewardedPerValidator: Get<u32>;
 
+		/// The maximum number of nominators paid out in a single page.
+		///
+		/// The clipped exposure of each validator is split into pages of this size, which can be
+		/// paid out in separate calls to [`Call::payout_stakers_by_page`].
+		#[pallet::constant]
+		type MaxExposurePageSize: Get<u32>;
+
 		/// The fraction of the validator set that is safe to be offending.
 		/// After the threshold is reached a new era will be forced.
 		type OffendingValidatorsThreshold: Get<Perbill>;
@@ -262,6 +269,26 @@ pub mod pallet {
 		/// other pallets exist that are affected by slashing per-staker.
 		type OnStakerSlash: sp_staking::OnStakerSlash<Self::AccountId, BalanceOf<Self>>;
 
+		/// A hook that can veto `bond_extra`, `unbond`, `nominate` and `validate` of a stash.
+		///
+		/// Set it to `()` to allow all operations.
+		type OnStakingOperation: OnStakingOperation<Self::AccountId>;
+
+		/// The account that rewards callers of [`Call::kick_below_minimum`], if any.
+		type KickRewardPool: Get<Option<Self::AccountId>>;
+
+		/// The reward paid out of the `KickRewardPool` for each stash kicked by
+		/// [`Call::kick_below_minimum`].
+		///
+		/// Nothing is paid if the pool can't afford it.
+		#[pallet::constant]
+		type KickReward: Get<BalanceOf<Self>>;
+
+		/// The maximum number of validators that [`Call::force_apply_min_commission_batch`] can
+		/// be called with.
+		#[pallet::constant]
+		type MaxCommissionBatch: Get<u32>;
+
 		/// Some parameters of the benchmarking.
 		type BenchmarkingConfig: BenchmarkingConfig;
 
@@ -301,7 +328,11 @@ pub mod pallet {
 	pub type MinValidatorBond<T: Config> = StorageValue<_, BalanceOf<T>, ValueQuery>;
 
 	/// The minimum active nominator stake of the last successful election.
+	///
+	/// Nominators with less active stake than this are unlikely to be part of the next election
+	/// snapshot, and thus to earn rewards.
 	#[pallet::storage]
+	#[pallet::getter(fn minimum_active_stake)]
 	pub type MinimumActiveStake<T> = StorageValue<_, BalanceOf<T>, ValueQuery>;
 
 	/// The minimum amount of commission that validators can set.
@@ -460,6 +491,25 @@ pub mod pallet {
 	pub type ErasRewardPoints<T: Config> =
 		StorageMap<_, Twox64Concat, EraIndex, EraRewardPoints<T::AccountId>, ValueQuery>;
 
+	/// The pages of a validator's clipped exposure at era that have already been paid out.
+	///
+	/// This is keyed first by the era index to allow bulk deletion and then the stash account.
+	/// Once all pages of an exposure are paid out, the entry is removed and the era is recorded
+	/// in `StakingLedger.claimed_rewards` instead.
+	///
+	/// Is it removed after `HISTORY_DEPTH` eras.
+	#[pallet::storage]
+	#[pallet::getter(fn claimed_rewards)]
+	pub type ClaimedRewards<T: Config> = StorageDoubleMap<
+		_,
+		Twox64Concat,
+		EraIndex,
+		Twox64Concat,
+		T::AccountId,
+		BoundedVec<Page, MaxExposurePagesOf<T>>,
+		ValueQuery,
+	>;
+
 	/// The total amount staked for the last `HISTORY_DEPTH` eras.
 	/// If total hasn't been set or has been removed then 0 stake is returned.
 	#[pallet::storage]
@@ -680,8 +730,9 @@ pub mod pallet {
 		EraPaid { era_index: EraIndex, validator_payout: BalanceOf<T>, remainder: BalanceOf<T> },
 		/// The nominator has been rewarded by this amount.
 		Rewarded { stash: T::AccountId, amount: BalanceOf<T> },
-		/// A staker (validator or nominator) has been slashed by the given amount.
-		Slashed { staker: T::AccountId, amount: BalanceOf<T> },
+		/// A staker (validator or nominator) has been slashed by the given amount, for an offence
+		/// in `slash_era`. This matches the era of the corresponding `SlashReported`.
+		Slashed { staker: T::AccountId, amount: BalanceOf<T>, slash_era: EraIndex },
 		/// A slash for the given validator, for the given percentage of their stake, at the given
 		/// era as been reported.
 		SlashReported { validator: T::AccountId, fraction: Perbill, slash_era: EraIndex },
@@ -706,10 +757,35 @@ pub mod pallet {
 		StakingElectionFailed,
 		/// An account has stopped participating as either a validator or nominator.
 		Chilled { stash: T::AccountId },
-		/// The stakers' rewards are getting paid.
-		PayoutStarted { era_index: EraIndex, validator_stash: T::AccountId },
+		/// The stakers' rewards of the given exposure page are getting paid.
+		PayoutStarted { era_index: EraIndex, validator_stash: T::AccountId, page: Page },
 		/// A validator has set their preferences.
 		ValidatorPrefsSet { stash: T::AccountId, prefs: ValidatorPrefs },
+		/// The reward destination of a stash has been changed.
+		PayeeSet { stash: T::AccountId, payee: RewardDestination<T::AccountId> },
+		/// A nominator below `MinNominatorBond` has been fully unstaked by `kicker`, who has been
+		/// rewarded with `reward`.
+		KickedBelowMinimum { stash: T::AccountId, kicker: T::AccountId, reward: BalanceOf<T> },
+		/// The commission of a validator has been raised to `MinCommission`.
+		MinCommissionApplied { stash: T::AccountId, commission: Perbill },
+		/// The corrupted mapping of `stash` to its ledger has been repaired. It used to be bonded
+		/// to `old_controller`, and is now bonded to `controller`. If `controller` is `None`, the
+		/// leftovers of a stash without a ledger have been removed.
+		LedgerRepaired {
+			stash: T::AccountId,
+			old_controller: Option<T::AccountId>,
+			controller: Option<T::AccountId>,
+		},
+		/// The staking configurations have been updated through `set_staking_configs`. All of
+		/// them are reported with their new value, whether they have changed or not.
+		StakingConfigsUpdated {
+			min_nominator_bond: BalanceOf<T>,
+			min_validator_bond: BalanceOf<T>,
+			max_nominators_count: Option<u32>,
+			max_validators_count: Option<u32>,
+			chill_threshold: Option<Percent>,
+			min_commission: Perbill,
+		},
 	}
 
 	#[pallet::error]
@@ -746,6 +822,8 @@ pub mod pallet {
 		NotSortedAndUnique,
 		/// Rewards for this era have already been claimed for this validator.
 		AlreadyClaimed,
+		/// The exposure of this validator has no such page.
+		InvalidPage,
 		/// Incorrect previous history depth input provided.
 		IncorrectHistoryDepth,
 		/// Incorrect number of slashing spans provided.
@@ -768,6 +846,18 @@ pub mod pallet {
 		CommissionTooLow,
 		/// Some bound is not met.
 		BoundNotMet,
+		/// The account of a [`RewardDestination::Account`] does not exist.
+		PayeeDoesNotExist,
+		/// The stash is not a nominator below `MinNominatorBond` and thus cannot be kicked by
+		/// another account.
+		CannotKick,
+		/// The stash has been exposed within the last `BondingDuration` eras and could still be
+		/// slashed.
+		RecentlyExposed,
+		/// The stash, its controller and its ledger are consistent, there is nothing to repair.
+		LedgerNotCorrupted,
+		/// The ledger of the stash cannot be found, so its mapping cannot be repaired.
+		CannotRepairLedger,
 	}
 
 	#[pallet::hooks]
@@ -800,6 +890,9 @@ pub mod pallet {
 			// and that MaxNominations is always greater than 1, since we count on this.
 			assert!(!T::MaxNominations::get().is_zero());
 
+			// exposures are split into pages of at least one nominator.
+			assert!(!T::MaxExposurePageSize::get().is_zero());
+
 			// ensure election results are always bounded with the same value
 			assert!(
 				<T::ElectionProvider as ElectionProviderBase>::MaxWinners::get() ==
@@ -833,6 +926,8 @@ pub mod pallet {
 		///
 		/// The dispatch origin for this call must be _Signed_ by the stash account.
 		///
+		/// If `payee` is a [`RewardDestination::Account`], that account must already exist.
+		///
 		/// Emits `Bonded`.
 		/// # <weight>
 		/// - Independent of the arguments. Moderate complexity.
@@ -868,6 +963,8 @@ pub mod pallet {
 				return Err(Error::<T>::InsufficientBond.into())
 			}
 
+			Self::ensure_payee_exists(&payee)?;
+
 			frame_system::Pallet::<T>::inc_consumers(&stash).map_err(|_| Error::<T>::BadState)?;
 
 			// You're auto-bonded forever, here. We might improve this by only bonding when
@@ -923,6 +1020,7 @@ pub mod pallet {
 
 			let controller = Self::bonded(&stash).ok_or(Error::<T>::NotStash)?;
 			let mut ledger = Self::ledger(&controller).ok_or(Error::<T>::NotController)?;
+			T::OnStakingOperation::ensure_allowed(&stash, StakingOperation::BondExtra)?;
 
 			let stash_balance = T::Currency::free_balance(&stash);
 			if let Some(extra) = stash_balance.checked_sub(&ledger.total) {
@@ -976,9 +1074,9 @@ pub mod pallet {
 			#[pallet::compact] value: BalanceOf<T>,
 		) -> DispatchResultWithPostInfo {
 			let controller = ensure_signed(origin)?;
-			let unlocking = Self::ledger(&controller)
-				.map(|l| l.unlocking.len())
-				.ok_or(Error::<T>::NotController)?;
+			let ledger = Self::ledger(&controller).ok_or(Error::<T>::NotController)?;
+			T::OnStakingOperation::ensure_allowed(&ledger.stash, StakingOperation::Unbond)?;
+			let unlocking = ledger.unlocking.len();
 
 			// if there are no unlocking chunks available, try to withdraw chunks older than
 			// `BondingDuration` to proceed with the unbonding.
@@ -1097,6 +1195,7 @@ pub mod pallet {
 			let controller = ensure_signed(origin)?;
 
 			let ledger = Self::ledger(&controller).ok_or(Error::<T>::NotController)?;
+			T::OnStakingOperation::ensure_allowed(&ledger.stash, StakingOperation::Validate)?;
 
 			ensure!(ledger.active >= MinValidatorBond::<T>::get(), Error::<T>::InsufficientBond);
 			let stash = &ledger.stash;
@@ -1144,6 +1243,7 @@ pub mod pallet {
 			let controller = ensure_signed(origin)?;
 
 			let ledger = Self::ledger(&controller).ok_or(Error::<T>::NotController)?;
+			T::OnStakingOperation::ensure_allowed(&ledger.stash, StakingOperation::Nominate)?;
 			ensure!(ledger.active >= MinNominatorBond::<T>::get(), Error::<T>::InsufficientBond);
 			let stash = &ledger.stash;
 
@@ -1219,6 +1319,10 @@ pub mod pallet {
 		///
 		/// The dispatch origin for this call must be _Signed_ by the controller, not the stash.
 		///
+		/// If `payee` is a [`RewardDestination::Account`], that account must already exist.
+		///
+		/// Emits `PayeeSet`.
+		///
 		/// # <weight>
 		/// - Independent of the arguments. Insignificant complexity.
 		/// - Contains a limited number of reads.
@@ -1226,7 +1330,7 @@ pub mod pallet {
 		/// ---------
 		/// - Weight: O(1)
 		/// - DB Weight:
-		///     - Read: Ledger
+		///     - Read: Ledger, System Account
 		///     - Write: Payee
 		/// # </weight>
 		#[pallet::call_index(7)]
@@ -1237,8 +1341,10 @@ pub mod pallet {
 		) -> DispatchResult {
 			let controller = ensure_signed(origin)?;
 			let ledger = Self::ledger(&controller).ok_or(Error::<T>::NotController)?;
-			let stash = &ledger.stash;
-			<Payee<T>>::insert(stash, payee);
+			Self::ensure_payee_exists(&payee)?;
+			let stash = ledger.stash;
+			<Payee<T>>::insert(&stash, &payee);
+			Self::deposit_event(Event::<T>::PayeeSet { stash, payee });
 			Ok(())
 		}
 
@@ -1488,6 +1594,9 @@ pub mod pallet {
 		///   `T::MaxNominatorRewardedPerValidator`, will also receive their rewards.
 		/// - `era` may be any era between `[current_era - history_depth; current_era]`.
 		///
+		/// This pays out all pages of the exposure that have not been paid out yet through
+		/// [`Call::payout_stakers_by_page`].
+		///
 		/// The origin of this call must be _Signed_. Any account can call this function, even if
 		/// it is not one of the stakers.
 		///
@@ -1513,7 +1622,7 @@ pub mod pallet {
 			era: EraIndex,
 		) -> DispatchResultWithPostInfo {
 			ensure_signed(origin)?;
-			Self::do_payout_stakers(validator_stash, era)
+			Self::do_payout_stakers(validator_stash, era, None)
 		}
 
 		/// Rebond a portion of the stash scheduled to be unlocked.
@@ -1649,6 +1758,8 @@ pub mod pallet {
 		///
 		/// NOTE: Existing nominators and validators will not be affected by this update.
 		/// to kick people under the new limits, `chill_other` should be called.
+		///
+		/// Emits `StakingConfigsUpdated`, unless all operations are `Noop`.
 		// We assume the worst case for this call is either: all items are set or all items are
 		// removed.
 		#[pallet::call_index(22)]
@@ -1667,22 +1778,41 @@ pub mod pallet {
 		) -> DispatchResult {
 			ensure_root(origin)?;
 
+			// evaluates to whether the storage item has been written.
 			macro_rules! config_op_exp {
 				($storage:ty, $op:ident) => {
 					match $op {
-						ConfigOp::Noop => (),
-						ConfigOp::Set(v) => <$storage>::put(v),
-						ConfigOp::Remove => <$storage>::kill(),
+						ConfigOp::Noop => false,
+						ConfigOp::Set(v) => {
+							<$storage>::put(v);
+							true
+						},
+						ConfigOp::Remove => {
+							<$storage>::kill();
+							true
+						},
 					}
 				};
 			}
 
-			config_op_exp!(MinNominatorBond<T>, min_nominator_bond);
-			config_op_exp!(MinValidatorBond<T>, min_validator_bond);
-			config_op_exp!(MaxNominatorsCount<T>, max_nominator_count);
-			config_op_exp!(MaxValidatorsCount<T>, max_validator_count);
-			config_op_exp!(ChillThreshold<T>, chill_threshold);
-			config_op_exp!(MinCommission<T>, min_commission);
+			let updated = [
+				config_op_exp!(MinNominatorBond<T>, min_nominator_bond),
+				config_op_exp!(MinValidatorBond<T>, min_validator_bond),
+				config_op_exp!(MaxNominatorsCount<T>, max_nominator_count),
+				config_op_exp!(MaxValidatorsCount<T>, max_validator_count),
+				config_op_exp!(ChillThreshold<T>, chill_threshold),
+				config_op_exp!(MinCommission<T>, min_commission),
+			];
+			if updated.contains(&true) {
+				Self::deposit_event(Event::<T>::StakingConfigsUpdated {
+					min_nominator_bond: MinNominatorBond::<T>::get(),
+					min_validator_bond: MinValidatorBond::<T>::get(),
+					max_nominators_count: MaxNominatorsCount::<T>::get(),
+					max_validators_count: MaxValidatorsCount::<T>::get(),
+					chill_threshold: ChillThreshold::<T>::get(),
+					min_commission: MinCommission::<T>::get(),
+				});
+			}
 			Ok(())
 		}
 		/// Declare a `controller` to stop participating as either a validator or nominator.
@@ -1794,6 +1924,58 @@ pub mod pallet {
 			Ok(())
 		}
 
+		/// Fully unstake a nominator whose active bond is below `MinNominatorBond`, releasing all
+		/// of its funds. Any account can call this.
+		///
+		/// The stash must not have been exposed in any of the last `BondingDuration` eras, so that
+		/// it can't be slashed anymore. This is the same check that fast-unstake applies.
+		///
+		/// The caller is rewarded with `KickReward` out of the `KickRewardPool`, if the pool can
+		/// afford it.
+		///
+		/// - `num_slashing_spans`: the number of slashing spans of the stash, see `force_unstake`.
+		#[pallet::call_index(26)]
+		#[pallet::weight(T::WeightInfo::kick_below_minimum(
+			*num_slashing_spans,
+			<T::ElectionProvider as ElectionProviderBase>::MaxWinners::get(),
+		))]
+		pub fn kick_below_minimum(
+			origin: OriginFor<T>,
+			stash: T::AccountId,
+			num_slashing_spans: u32,
+		) -> DispatchResult {
+			// Anyone can call this function.
+			let caller = ensure_signed(origin)?;
+			let controller = Self::bonded(&stash).ok_or(Error::<T>::NotStash)?;
+			let ledger = Self::ledger(&controller).ok_or(Error::<T>::NotController)?;
+			ensure!(
+				Nominators::<T>::contains_key(&stash) &&
+					ledger.active < MinNominatorBond::<T>::get(),
+				Error::<T>::CannotKick
+			);
+			T::OnStakingOperation::ensure_allowed(&stash, StakingOperation::Unbond)?;
+
+			let current_era = Self::current_era().unwrap_or(Zero::zero());
+			let from = current_era.saturating_sub(T::BondingDuration::get());
+			ensure!(
+				Self::eras_of_exposure(stash.clone(), from, current_era).is_empty(),
+				Error::<T>::RecentlyExposed
+			);
+
+			// Remove all staking-related information and the lock.
+			Self::kill_stash(&stash, num_slashing_spans)?;
+			T::Currency::remove_lock(STAKING_ID, &stash);
+
+			let reward = T::KickReward::get();
+			let rewarded = T::KickRewardPool::get().map_or(false, |pool| {
+				T::Currency::transfer(&pool, &caller, reward, ExistenceRequirement::KeepAlive)
+					.is_ok()
+			});
+			let reward = if rewarded { reward } else { Zero::zero() };
+			Self::deposit_event(Event::<T>::KickedBelowMinimum { stash, kicker: caller, reward });
+			Ok(())
+		}
+
 		/// Sets the minimum amount of commission that each validators must maintain.
 		///
 		/// This call has lower privilege requirements than `set_staking_config` and can be called
@@ -1805,6 +1987,137 @@ pub mod pallet {
 			MinCommission::<T>::put(new);
 			Ok(())
 		}
+
+		/// Pay out a single page of the stakers behind a single validator for a single era.
+		///
+		/// The clipped exposure of the validator is split into pages of at most
+		/// `T::MaxExposurePageSize` nominators, and the validator itself is paid out along with
+		/// page `0`. Every page can be paid out once, in any order.
+		///
+		/// - `validator_stash` is the stash account of the validator.
+		/// - `era` may be any era between `[current_era - history_depth; current_era]`.
+		/// - `page` is the index of the page to pay out.
+		///
+		/// The origin of this call must be _Signed_. Any account can call this function, even if
+		/// it is not one of the stakers.
+		#[pallet::call_index(27)]
+		#[pallet::weight(T::WeightInfo::payout_stakers_by_page(T::MaxExposurePageSize::get()))]
+		pub fn payout_stakers_by_page(
+			origin: OriginFor<T>,
+			validator_stash: T::AccountId,
+			era: EraIndex,
+			page: Page,
+		) -> DispatchResultWithPostInfo {
+			ensure_signed(origin)?;
+			Self::do_payout_stakers(validator_stash, era, Some(page))
+		}
+
+		/// Force each of the given validators to have at least the minimum commission, like
+		/// [`Call::force_apply_min_commission`]. Any account can call this.
+		///
+		/// Accounts that are not validators, or whose commission is already greater than or equal
+		/// to the minimum, are skipped and the weight charged for them is refunded. An event is
+		/// emitted for every validator whose commission has been raised.
+		#[pallet::call_index(28)]
+		#[pallet::weight(T::WeightInfo::force_apply_min_commission_batch(validators.len() as u32))]
+		pub fn force_apply_min_commission_batch(
+			origin: OriginFor<T>,
+			validators: BoundedVec<T::AccountId, T::MaxCommissionBatch>,
+		) -> DispatchResultWithPostInfo {
+			ensure_signed(origin)?;
+			let min_commission = MinCommission::<T>::get();
+			let mut adjusted = 0u32;
+			let mut skipped = 0u64;
+			for stash in validators {
+				let raised = Validators::<T>::try_mutate_exists(&stash, |maybe_prefs| {
+					match maybe_prefs.as_mut() {
+						Some(prefs) if prefs.commission < min_commission => {
+							prefs.commission = min_commission;
+							Ok(())
+						},
+						_ => Err(()),
+					}
+				})
+				.is_ok();
+				if raised {
+					adjusted.saturating_inc();
+					Self::deposit_event(Event::<T>::MinCommissionApplied {
+						stash,
+						commission: min_commission,
+					});
+				} else {
+					skipped.saturating_inc();
+				}
+			}
+
+			let actual_weight = T::WeightInfo::force_apply_min_commission_batch(adjusted)
+				.saturating_add(T::DbWeight::get().reads(skipped));
+			Ok(Some(actual_weight).into())
+		}
+
+		/// Repair the mapping between `stash`, its controller and its ledger, if it has been
+		/// corrupted. Any account can call this.
+		///
+		/// The following corruptions are repaired, emitting [`Event::LedgerRepaired`]:
+		///
+		/// - `stash` is bonded to a controller without a ledger, or whose ledger belongs to
+		///   another stash, while the ledger of `stash` is stored under `stash` itself. The stash
+		///   is bonded to itself again.
+		/// - `stash` is not bonded, but its ledger is stored under `stash` itself. The stash is
+		///   bonded to itself again.
+		/// - `stash` is neither bonded nor does it have a ledger, but it is still left with a
+		///   payee, or as a validator or nominator. These leftovers are removed and the staking
+		///   lock of `stash` is released.
+		///
+		/// The last case cannot tell a lost ledger from one stored under an unknown controller,
+		/// so only `stash` itself can repair it. Any account can repair the other cases.
+		///
+		/// Fails with:
+		/// - [`Error::LedgerNotCorrupted`] if the mapping of `stash` is consistent.
+		/// - [`Error::CannotRepairLedger`] if `stash` is bonded, but its ledger cannot be found.
+		/// - [`Error::NotStash`] if `stash` is neither bonded nor left with anything to remove.
+		/// - `BadOrigin` if leftovers of `stash` are to be removed and the origin is not `stash`.
+		#[pallet::call_index(29)]
+		#[pallet::weight(T::WeightInfo::repair_ledger())]
+		pub fn repair_ledger(origin: OriginFor<T>, stash: T::AccountId) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			let old_controller = Self::bonded(&stash);
+			let ledger_of = |controller: &T::AccountId| {
+				Self::ledger(controller).filter(|ledger| ledger.stash == stash).is_some()
+			};
+
+			let controller = match old_controller {
+				Some(ref controller) if ledger_of(controller) =>
+					return Err(Error::<T>::LedgerNotCorrupted.into()),
+				Some(_) => {
+					ensure!(ledger_of(&stash), Error::<T>::CannotRepairLedger);
+					<Bonded<T>>::insert(&stash, &stash);
+					Some(stash.clone())
+				},
+				None if ledger_of(&stash) => {
+					<Bonded<T>>::insert(&stash, &stash);
+					Some(stash.clone())
+				},
+				None => {
+					let is_validator = Validators::<T>::contains_key(&stash);
+					let is_nominator = Nominators::<T>::contains_key(&stash);
+					ensure!(
+						<Payee<T>>::contains_key(&stash) || is_validator || is_nominator,
+						Error::<T>::NotStash
+					);
+					ensure!(who == stash, DispatchError::BadOrigin);
+					<Payee<T>>::remove(&stash);
+					Self::do_remove_validator(&stash);
+					Self::do_remove_nominator(&stash);
+					T::Currency::remove_lock(STAKING_ID, &stash);
+					None
+				},
+			};
+
+			crate::log!(warn, "repaired the corrupted ledger of {:?}", stash);
+			Self::deposit_event(Event::<T>::LedgerRepaired { stash, old_controller, controller });
+			Ok(())
+		}
 	}
 }
 
@@ -591,12 +591,16 @@ pub(crate) fn clear_stash_metadata<T: Config>(
 // apply the slash to a stash account, deducting any missing funds from the reward
 // payout, saturating at 0. this is mildly unfair but also an edge-case that
 // can only occur when overlapping locked funds have been slashed.
+//
+// `slash_era` determines the unlocking chunks that are slashed, while `offence_era` is only
+// reported in the events.
 pub fn do_slash<T: Config>(
 	stash: &T::AccountId,
 	value: BalanceOf<T>,
 	reward_payout: &mut BalanceOf<T>,
 	slashed_imbalance: &mut NegativeImbalanceOf<T>,
 	slash_era: EraIndex,
+	offence_era: EraIndex,
 ) {
 	let controller = match <Pallet<T>>::bonded(stash).defensive() {
 		None => return,
@@ -608,7 +612,8 @@ pub fn do_slash<T: Config>(
 		None => return, // nothing to do.
 	};
 
-	let value = ledger.slash(value, T::Currency::minimum_balance(), slash_era);
+	let value =
+		ledger.slash_for_offence(value, T::Currency::minimum_balance(), slash_era, offence_era);
 
 	if !value.is_zero() {
 		let (imbalance, missing) = T::Currency::slash(stash, value);
@@ -625,6 +630,7 @@ pub fn do_slash<T: Config>(
 		<Pallet<T>>::deposit_event(super::Event::<T>::Slashed {
 			staker: stash.clone(),
 			amount: value,
+			slash_era: offence_era,
 		});
 	}
 }
@@ -633,6 +639,7 @@ pub fn do_slash<T: Config>(
 pub(crate) fn apply_slash<T: Config>(
 	unapplied_slash: UnappliedSlash<T::AccountId, BalanceOf<T>>,
 	slash_era: EraIndex,
+	offence_era: EraIndex,
 ) {
 	let mut slashed_imbalance = NegativeImbalanceOf::<T>::zero();
 	let mut reward_payout = unapplied_slash.payout;
@@ -643,6 +650,7 @@ pub(crate) fn apply_slash<T: Config>(
 		&mut reward_payout,
 		&mut slashed_imbalance,
 		slash_era,
+		offence_era,
 	);
 
 	for &(ref nominator, nominator_slash) in &unapplied_slash.others {
@@ -652,6 +660,7 @@ pub(crate) fn apply_slash<T: Config>(
 			&mut reward_payout,
 			&mut slashed_imbalance,
 			slash_era,
+			offence_era,
 		);
 	}
 
@@ -58,6 +58,17 @@ fn set_staking_configs_works() {
 		assert_eq!(MaxValidatorsCount::<Test>::get(), Some(20));
 		assert_eq!(ChillThreshold::<Test>::get(), Some(Percent::from_percent(75)));
 		assert_eq!(MinCommission::<Test>::get(), Perbill::from_percent(0));
+		assert_eq!(
+			*staking_events().last().unwrap(),
+			Event::StakingConfigsUpdated {
+				min_nominator_bond: 1_500,
+				min_validator_bond: 2_000,
+				max_nominators_count: Some(10),
+				max_validators_count: Some(20),
+				chill_threshold: Some(Percent::from_percent(75)),
+				min_commission: Zero::zero(),
+			}
+		);
 
 		// noop does nothing
 		assert_storage_noop!(assert_ok!(Staking::set_staking_configs(
@@ -86,6 +97,47 @@ fn set_staking_configs_works() {
 		assert_eq!(MaxValidatorsCount::<Test>::get(), None);
 		assert_eq!(ChillThreshold::<Test>::get(), None);
 		assert_eq!(MinCommission::<Test>::get(), Perbill::from_percent(0));
+		assert_eq!(
+			*staking_events().last().unwrap(),
+			Event::StakingConfigsUpdated {
+				min_nominator_bond: 0,
+				min_validator_bond: 0,
+				max_nominators_count: None,
+				max_validators_count: None,
+				chill_threshold: None,
+				min_commission: Zero::zero(),
+			}
+		);
+	});
+}
+
+#[test]
+fn set_staking_configs_reports_unchanged_values() {
+	ExtBuilder::default().build_and_execute(|| {
+		MaxNominatorsCount::<Test>::put(100);
+		let _ = staking_events_since_last_call();
+
+		// only the minimum nominator bond is touched, the rest is reported as is.
+		assert_ok!(Staking::set_staking_configs(
+			RuntimeOrigin::root(),
+			ConfigOp::Set(1_500),
+			ConfigOp::Noop,
+			ConfigOp::Noop,
+			ConfigOp::Noop,
+			ConfigOp::Noop,
+			ConfigOp::Noop
+		));
+		assert_eq!(
+			staking_events_since_last_call(),
+			vec![Event::StakingConfigsUpdated {
+				min_nominator_bond: 1_500,
+				min_validator_bond: MinValidatorBond::<Test>::get(),
+				max_nominators_count: Some(100),
+				max_validators_count: None,
+				chill_threshold: None,
+				min_commission: Zero::zero(),
+			}]
+		);
 	});
 }
 
@@ -1812,6 +1864,108 @@ fn reap_stash_works() {
 		});
 }
 
+#[test]
+fn kick_below_minimum_works() {
+	ExtBuilder::default().build_and_execute(|| {
+		// given a nominator that has never been exposed.
+		KickRewardPool::set(Some(999));
+		KickReward::set(10);
+		bond_nominator(61, 60, 50, vec![11]);
+		let _ = staking_events_since_last_call();
+		let pool_balance = Balances::free_balance(999);
+		let kicker_balance = Balances::free_balance(1);
+
+		// when the minimum is raised above its bond.
+		MinNominatorBond::<Test>::put(100);
+		assert_ok!(Staking::kick_below_minimum(RuntimeOrigin::signed(1), 61, 0));
+
+		// then it is fully unstaked.
+		assert!(!<Ledger<Test>>::contains_key(&60));
+		assert!(!<Bonded<Test>>::contains_key(&61));
+		assert!(!<Nominators<Test>>::contains_key(&61));
+		assert!(Balances::locks(&61).is_empty());
+		assert_eq!(Balances::usable_balance(&61), 50);
+
+		// and the kicker is rewarded.
+		assert_eq!(Balances::free_balance(1), kicker_balance + 10);
+		assert_eq!(Balances::free_balance(999), pool_balance - 10);
+		assert_eq!(
+			staking_events_since_last_call(),
+			vec![Event::KickedBelowMinimum { stash: 61, kicker: 1, reward: 10 }]
+		);
+	});
+}
+
+#[test]
+fn kick_below_minimum_pays_nothing_if_the_pool_cannot_afford_it() {
+	ExtBuilder::default().build_and_execute(|| {
+		KickReward::set(10);
+		bond_nominator(61, 60, 50, vec![11]);
+		bond_nominator(71, 70, 50, vec![11]);
+		MinNominatorBond::<Test>::put(100);
+		let _ = staking_events_since_last_call();
+		let kicker_balance = Balances::free_balance(1);
+
+		// no pool at all.
+		assert_ok!(Staking::kick_below_minimum(RuntimeOrigin::signed(1), 61, 0));
+
+		// a pool without funds.
+		KickRewardPool::set(Some(1337));
+		assert_ok!(Staking::kick_below_minimum(RuntimeOrigin::signed(1), 71, 0));
+
+		assert!(!<Bonded<Test>>::contains_key(&61));
+		assert!(!<Bonded<Test>>::contains_key(&71));
+		assert_eq!(Balances::free_balance(1), kicker_balance);
+		assert_eq!(
+			staking_events_since_last_call(),
+			vec![
+				Event::KickedBelowMinimum { stash: 61, kicker: 1, reward: 0 },
+				Event::KickedBelowMinimum { stash: 71, kicker: 1, reward: 0 },
+			]
+		);
+	});
+}
+
+#[test]
+fn kick_below_minimum_fails_for_recently_exposed_nominator() {
+	ExtBuilder::default().build_and_execute(|| {
+		bond_nominator(61, 60, 50, vec![11]);
+		mock::start_active_era(1);
+		assert!(Staking::eras_stakers(1, 11).others.iter().any(|e| e.who == 61));
+
+		MinNominatorBond::<Test>::put(100);
+		assert_noop!(
+			Staking::kick_below_minimum(RuntimeOrigin::signed(1), 61, 0),
+			Error::<Test>::RecentlyExposed
+		);
+	});
+}
+
+#[test]
+fn kick_below_minimum_fails_for_stakers_above_minimum() {
+	ExtBuilder::default().build_and_execute(|| {
+		bond_nominator(61, 60, 50, vec![11]);
+		MinNominatorBond::<Test>::put(50);
+
+		// a nominator at the minimum.
+		assert_noop!(
+			Staking::kick_below_minimum(RuntimeOrigin::signed(1), 61, 0),
+			Error::<Test>::CannotKick
+		);
+		// a validator is never kicked, even below the nominator minimum.
+		MinNominatorBond::<Test>::put(10_000);
+		assert_noop!(
+			Staking::kick_below_minimum(RuntimeOrigin::signed(1), 11, 0),
+			Error::<Test>::CannotKick
+		);
+		// a controller is not a stash.
+		assert_noop!(
+			Staking::kick_below_minimum(RuntimeOrigin::signed(1), 60, 0),
+			Error::<Test>::NotStash
+		);
+	});
+}
+
 #[test]
 fn switching_roles() {
 	// Test that it should be possible to switch between roles (nominator, validator, idle) with
@@ -2916,8 +3070,8 @@ fn deferred_slashes_are_deferred() {
 				Event::Chilled { stash: 11 },
 				Event::SlashReported { validator: 11, slash_era: 1, .. },
 				..,
-				Event::Slashed { staker: 11, amount: 100 },
-				Event::Slashed { staker: 101, amount: 12 }
+				Event::Slashed { staker: 11, amount: 100, slash_era: 1 },
+				Event::Slashed { staker: 101, amount: 12, slash_era: 1 }
 			]
 		));
 	})
@@ -2951,8 +3105,8 @@ fn retroactive_deferred_slashes_two_eras_before() {
 				Event::Chilled { stash: 11 },
 				Event::SlashReported { validator: 11, slash_era: 1, .. },
 				..,
-				Event::Slashed { staker: 11, amount: 100 },
-				Event::Slashed { staker: 101, amount: 12 }
+				Event::Slashed { staker: 11, amount: 100, slash_era: 1 },
+				Event::Slashed { staker: 101, amount: 12, slash_era: 1 }
 			]
 		));
 	})
@@ -2991,8 +3145,8 @@ fn retroactive_deferred_slashes_one_before() {
 			&[
 				Event::SlashReported { validator: 11, slash_era: 2, .. },
 				..,
-				Event::Slashed { staker: 11, amount: 100 },
-				Event::Slashed { staker: 101, amount: 12 }
+				Event::Slashed { staker: 11, amount: 100, slash_era: 2 },
+				Event::Slashed { staker: 101, amount: 12, slash_era: 2 }
 			]
 		));
 
@@ -3003,6 +3157,86 @@ fn retroactive_deferred_slashes_one_before() {
 	})
 }
 
+#[test]
+fn deferred_slash_events_report_the_offence_era() {
+	ExtBuilder::default().slash_defer_duration(2).build_and_execute(|| {
+		let slash_events = || {
+			staking_events_since_last_call()
+				.into_iter()
+				.filter(|e| matches!(e, Event::SlashReported { .. } | Event::Slashed { .. }))
+				.collect::<Vec<_>>()
+		};
+
+		mock::start_active_era(1);
+		let exposure_11_at_era1 = Staking::eras_stakers(active_era(), 11);
+		mock::start_active_era(2);
+		let _ = slash_events();
+
+		// an offence of era 1 is only reported in era 2.
+		on_offence_in_era(
+			&[OffenceDetails { offender: (11, exposure_11_at_era1), reporters: vec![] }],
+			&[Perbill::from_percent(10)],
+			1,
+			DisableStrategy::Never,
+		);
+		assert_eq!(
+			slash_events(),
+			vec![Event::SlashReported {
+				validator: 11,
+				fraction: Perbill::from_percent(10),
+				slash_era: 1
+			}]
+		);
+
+		mock::start_active_era(3);
+		assert_eq!(slash_events(), vec![]);
+
+		// it is applied at the beginning of era 4 and still attributed to era 1.
+		mock::start_active_era(4);
+		assert_eq!(
+			slash_events(),
+			vec![
+				Event::Slashed { staker: 11, amount: 100, slash_era: 1 },
+				Event::Slashed { staker: 101, amount: 12, slash_era: 1 }
+			]
+		);
+	})
+}
+
+#[test]
+fn deferred_slash_applies_to_chunks_unlocking_after_the_application_era() {
+	ExtBuilder::default().slash_defer_duration(2).build_and_execute(|| {
+		mock::start_active_era(1);
+		let exposure_11_at_era1 = Staking::eras_stakers(active_era(), 11);
+		// unlocks at era 1 + 3.
+		assert_ok!(Staking::unbond(RuntimeOrigin::signed(10), 500));
+		mock::start_active_era(2);
+		// unlocks at era 2 + 3.
+		assert_ok!(Staking::unbond(RuntimeOrigin::signed(10), 100));
+
+		on_offence_in_era(
+			&[OffenceDetails { offender: (11, exposure_11_at_era1), reporters: vec![] }],
+			&[Perbill::from_percent(10)],
+			1,
+			DisableStrategy::Never,
+		);
+		mock::start_active_era(4);
+
+		// The deferred slash is applied as of era `4 - SlashDeferDuration`, so only the chunk
+		// unlocking at era 5 is slashed along with the active balance.
+		let ledger = Staking::ledger(10).unwrap();
+		assert_eq!(ledger.active, 400 - 80);
+		assert_eq!(
+			ledger.unlocking.into_inner(),
+			vec![UnlockChunk { value: 500, era: 4 }, UnlockChunk { value: 100 - 20, era: 5 }]
+		);
+		assert!(matches!(
+			staking_events_since_last_call().as_slice(),
+			&[.., Event::Slashed { staker: 11, amount: 100, slash_era: 1 }, _]
+		));
+	})
+}
+
 #[test]
 fn staker_cannot_bail_deferred_slash() {
 	// as long as SlashDeferDuration is less than BondingDuration, this should not be possible.
@@ -3137,8 +3371,8 @@ fn remove_deferred() {
 			&[
 				Event::SlashReported { validator: 11, slash_era: 1, .. },
 				..,
-				Event::Slashed { staker: 11, amount: 50 },
-				Event::Slashed { staker: 101, amount: 7 }
+				Event::Slashed { staker: 11, amount: 50, slash_era: 1 },
+				Event::Slashed { staker: 101, amount: 7, slash_era: 1 }
 			]
 		));
 
@@ -3256,8 +3490,8 @@ fn slash_kicks_validators_not_nominators_and_disables_nominator_for_kicked_valid
 					fraction: Perbill::from_percent(10),
 					slash_era: 1
 				},
-				Event::Slashed { staker: 11, amount: 100 },
-				Event::Slashed { staker: 101, amount: 12 },
+				Event::Slashed { staker: 11, amount: 100, slash_era: 1 },
+				Event::Slashed { staker: 101, amount: 12, slash_era: 1 },
 			]
 		);
 
@@ -3329,8 +3563,8 @@ fn non_slashable_offence_doesnt_disable_validator() {
 					fraction: Perbill::from_percent(25),
 					slash_era: 1
 				},
-				Event::Slashed { staker: 21, amount: 250 },
-				Event::Slashed { staker: 101, amount: 94 }
+				Event::Slashed { staker: 21, amount: 250, slash_era: 1 },
+				Event::Slashed { staker: 101, amount: 94, slash_era: 1 }
 			]
 		);
 
@@ -3391,8 +3625,8 @@ fn slashing_independent_of_disabling_validator() {
 					fraction: Perbill::from_percent(25),
 					slash_era: 1
 				},
-				Event::Slashed { staker: 21, amount: 250 },
-				Event::Slashed { staker: 101, amount: 94 }
+				Event::Slashed { staker: 21, amount: 250, slash_era: 1 },
+				Event::Slashed { staker: 101, amount: 94, slash_era: 1 }
 			]
 		);
 
@@ -3868,6 +4102,83 @@ fn test_payout_stakers() {
 	});
 }
 
+#[test]
+fn payout_stakers_by_page_pays_each_page_once() {
+	ExtBuilder::default().has_stakers(false).build_and_execute(|| {
+		// 6 nominators in pages of 2 make up 3 pages.
+		MaxExposurePageSize::set(2);
+		let balance = 1000;
+		bond_validator(11, 10, balance);
+		for i in 0..6 {
+			bond_nominator(1000 + i, 100 + i, balance + i as Balance, vec![11]);
+		}
+
+		mock::start_active_era(1);
+		Staking::reward_by_ids(vec![(11, 1)]);
+		let payout = current_total_payout_for_duration(reward_time_per_era());
+		mock::start_active_era(2);
+
+		let exposure = Staking::eras_stakers_clipped(1, 11);
+		assert_eq!(exposure.page_count(2), 3);
+		// controllers of the nominators on each page.
+		let page_controllers =
+			|page| exposure.page(page, 2).iter().map(|e| e.who - 900).collect::<Vec<_>>();
+		let is_rewarded = |controller: AccountId| {
+			Balances::free_balance(&controller) > Staking::ledger(&controller).unwrap().total
+		};
+		let err_weight = <Test as Config>::WeightInfo::payout_stakers_alive_staked(0);
+		let pre_payout_total_issuance = Balances::total_issuance();
+		let _ = staking_events_since_last_call();
+
+		assert_noop!(
+			Staking::payout_stakers_by_page(RuntimeOrigin::signed(1337), 11, 1, 3),
+			Error::<Test>::InvalidPage.with_weight(err_weight)
+		);
+
+		// pages can be paid out in any order, the validator is only paid along with page 0.
+		assert_ok!(Staking::payout_stakers_by_page(RuntimeOrigin::signed(1337), 11, 1, 1));
+		assert!(!is_rewarded(10));
+		assert!(page_controllers(1).into_iter().all(is_rewarded));
+		assert!(!page_controllers(0).into_iter().chain(page_controllers(2)).any(is_rewarded));
+		assert_eq!(ClaimedRewards::<Test>::get(1, 11).into_inner(), vec![1]);
+		assert!(Staking::ledger(&10).unwrap().claimed_rewards.is_empty());
+		assert_noop!(
+			Staking::payout_stakers_by_page(RuntimeOrigin::signed(1337), 11, 1, 1),
+			Error::<Test>::AlreadyClaimed.with_weight(err_weight)
+		);
+
+		// `payout_stakers` pays out all remaining pages, which moves the era into the ledger.
+		assert_ok!(Staking::payout_stakers(RuntimeOrigin::signed(1337), 11, 1));
+		assert!(is_rewarded(10));
+		assert!(page_controllers(0).into_iter().chain(page_controllers(2)).all(is_rewarded));
+		assert!(!ClaimedRewards::<Test>::contains_key(1, 11));
+		assert_eq!(Staking::ledger(&10).unwrap().claimed_rewards.into_inner(), vec![1]);
+		assert_eq!(
+			staking_events_since_last_call()
+				.into_iter()
+				.filter_map(|e| match e {
+					Event::PayoutStarted { era_index: 1, validator_stash: 11, page } => Some(page),
+					_ => None,
+				})
+				.collect::<Vec<_>>(),
+			vec![1, 0, 2]
+		);
+
+		// nothing is paid twice, and all pages together pay out the full reward.
+		for page in 0..3 {
+			assert_noop!(
+				Staking::payout_stakers_by_page(RuntimeOrigin::signed(1337), 11, 1, page),
+				Error::<Test>::AlreadyClaimed.with_weight(err_weight)
+			);
+		}
+		assert_noop!(
+			Staking::payout_stakers(RuntimeOrigin::signed(1337), 11, 1),
+			Error::<Test>::AlreadyClaimed.with_weight(err_weight)
+		);
+		assert_eq_error_rate!(Balances::total_issuance(), pre_payout_total_issuance + payout, 7);
+	});
+}
+
 #[test]
 fn payout_stakers_handles_basic_errors() {
 	// Here we will test payouts handle all errors.
@@ -4210,12 +4521,13 @@ fn payout_to_any_account_works() {
 		// Create a stash/controller pair
 		bond_nominator(1234, 1337, 100, vec![11]);
 
+		// Reward Destination account needs to exist
+		let ed = ExistentialDeposit::get();
+		let _ = Balances::make_free_balance_be(&42, ed);
+
 		// Update payout location
 		assert_ok!(Staking::set_payee(RuntimeOrigin::signed(1337), RewardDestination::Account(42)));
 
-		// Reward Destination account doesn't exist
-		assert_eq!(Balances::free_balance(42), 0);
-
 		mock::start_active_era(1);
 		Staking::reward_by_ids(vec![(11, 1)]);
 		// compute and ensure the reward amount is greater than zero.
@@ -4224,7 +4536,47 @@ fn payout_to_any_account_works() {
 		assert_ok!(Staking::payout_stakers(RuntimeOrigin::signed(1337), 11, 1));
 
 		// Payment is successful
-		assert!(Balances::free_balance(42) > 0);
+		assert!(Balances::free_balance(42) > ed);
+	})
+}
+
+#[test]
+fn payee_account_must_exist() {
+	ExtBuilder::default().build_and_execute(|| {
+		assert_eq!(Balances::free_balance(42), 0);
+		let _ = Balances::make_free_balance_be(&3, 2000);
+
+		// cannot bond or set the payee to a dead account.
+		assert_noop!(
+			Staking::bond(RuntimeOrigin::signed(3), 4, 1500, RewardDestination::Account(42)),
+			Error::<Test>::PayeeDoesNotExist,
+		);
+		assert_noop!(
+			Staking::set_payee(RuntimeOrigin::signed(10), RewardDestination::Account(42)),
+			Error::<Test>::PayeeDoesNotExist,
+		);
+
+		// the other destinations are always fine.
+		assert_ok!(Staking::set_payee(RuntimeOrigin::signed(10), RewardDestination::Controller));
+		assert_eq!(
+			*staking_events().last().unwrap(),
+			Event::PayeeSet { stash: 11, payee: RewardDestination::Controller }
+		);
+
+		// once the account exists, it can be used.
+		let _ = Balances::make_free_balance_be(&42, ExistentialDeposit::get());
+		assert_ok!(Staking::bond(
+			RuntimeOrigin::signed(3),
+			4,
+			1500,
+			RewardDestination::Account(42)
+		));
+		assert_eq!(Staking::payee(3), RewardDestination::Account(42));
+		assert_ok!(Staking::set_payee(RuntimeOrigin::signed(10), RewardDestination::Account(42)));
+		assert_eq!(
+			*staking_events().last().unwrap(),
+			Event::PayeeSet { stash: 11, payee: RewardDestination::Account(42) }
+		);
 	})
 }
 
@@ -4513,6 +4865,26 @@ mod election_data_provider {
 			});
 	}
 
+	#[test]
+	fn minimum_active_stake_only_accounts_for_nominators() {
+		ExtBuilder::default()
+			.nominate(false)
+			.add_staker(61, 60, 2_000, StakerStatus::<AccountId>::Nominator(vec![21]))
+			.add_staker(71, 70, 30, StakerStatus::<AccountId>::Nominator(vec![21]))
+			.build_and_execute(|| {
+				// validators have less than 2_000, but they are not counted.
+				assert_ok!(<Staking as ElectionDataProvider>::electing_voters(None));
+				assert_eq!(Staking::minimum_active_stake(), 30);
+				assert_eq!(<Staking as sp_staking::StakingInterface>::minimum_active_stake(), 30);
+
+				// once the smallest nominator leaves, the next snapshot only sees the other one.
+				assert_ok!(Staking::chill(RuntimeOrigin::signed(70)));
+				assert_eq!(Staking::minimum_active_stake(), 30);
+				assert_ok!(<Staking as ElectionDataProvider>::electing_voters(None));
+				assert_eq!(Staking::minimum_active_stake(), 2_000);
+			});
+	}
+
 	#[test]
 	fn set_minimum_active_stake_zero_correct() {
 		ExtBuilder::default().has_stakers(false).build_and_execute(|| {
@@ -4668,6 +5040,88 @@ mod election_data_provider {
 			assert_eq!(ForceEra::<Test>::get(), Forcing::NotForcing);
 		})
 	}
+
+	#[test]
+	fn eras_remaining_blocks_converges_to_reality() {
+		ExtBuilder::default().session_per_era(5).period(5).build_and_execute(|| {
+			assert_eq!(Staking::expected_era_duration(), 25);
+			assert_eq!(Staking::eras_remaining_blocks(0), Some(0));
+
+			// first session is always length 0, hence era 1 starts at block 25.
+			for b in 1..50 {
+				run_to_block(b);
+				let now = System::block_number();
+				if b < 25 {
+					assert_eq!(Staking::eras_remaining_blocks(1), Some(25 - now));
+				} else {
+					assert_eq!(Staking::eras_remaining_blocks(1), Some(0));
+				}
+				assert_eq!(Staking::eras_remaining_blocks(2), Some(50 - now));
+			}
+
+			// the estimate matches the block at which the era actually starts.
+			while active_era() < 2 {
+				run_to_block(System::block_number() + 1);
+			}
+			assert_eq!(System::block_number(), 50);
+
+			for b in 50..75 {
+				run_to_block(b + 1);
+				let now = System::block_number();
+				assert_eq!(Staking::eras_remaining_blocks(3).map(|r| now + r), Some(75));
+				assert_eq!(Staking::eras_remaining_blocks(4).map(|r| now + r), Some(100));
+			}
+			assert_eq!(active_era(), 3);
+		})
+	}
+
+	#[test]
+	fn eras_remaining_blocks_respects_forcing() {
+		ExtBuilder::default().session_per_era(5).period(5).build_and_execute(|| {
+			Staking::force_no_eras(RuntimeOrigin::root()).unwrap();
+			assert_eq!(Staking::eras_remaining_blocks(1), None);
+			assert_eq!(Staking::eras_remaining_blocks(0), Some(0));
+
+			// an era that is already planned still starts.
+			ForceEra::<Test>::put(Forcing::NotForcing);
+			run_to_block(20);
+			assert_eq!(current_era(), 1);
+			Staking::force_no_eras(RuntimeOrigin::root()).unwrap();
+			assert_eq!(Staking::eras_remaining_blocks(1), Some(5));
+			assert_eq!(Staking::eras_remaining_blocks(2), None);
+
+			// when forcing new eras always, every session is an era.
+			Staking::force_new_era_always(RuntimeOrigin::root()).unwrap();
+			assert_eq!(Staking::eras_remaining_blocks(2), Some(10));
+			run_to_block(30);
+			assert_eq!(active_era(), 2);
+		})
+	}
+
+	#[test]
+	fn unbonding_blocks_remaining_works() {
+		ExtBuilder::default().session_per_era(5).period(5).build_and_execute(|| {
+			assert_eq!(Staking::unbonding_blocks_remaining(&11), Some(0));
+			assert_eq!(Staking::unbonding_blocks_remaining(&1), None);
+
+			// unbonding in era 0 unlocks the funds in era 3, which starts at block 75.
+			run_to_block(10);
+			assert_ok!(Staking::unbond(RuntimeOrigin::signed(10), 100));
+			assert_eq!(Staking::unbonding_blocks_remaining(&11), Some(65));
+
+			// the latest chunk counts.
+			run_to_block(30);
+			assert_eq!(active_era(), 1);
+			assert_ok!(Staking::unbond(RuntimeOrigin::signed(10), 100));
+			assert_eq!(Staking::unbonding_blocks_remaining(&11), Some(70));
+
+			run_to_block(100);
+			assert_eq!(active_era(), 4);
+			assert_eq!(Staking::unbonding_blocks_remaining(&11), Some(0));
+			assert_ok!(Staking::withdraw_unbonded(RuntimeOrigin::signed(10), 0));
+			assert!(Staking::ledger(&10).unwrap().unlocking.is_empty());
+		})
+	}
 }
 
 #[test]
@@ -5232,6 +5686,177 @@ fn force_apply_min_commission_works() {
 	});
 }
 
+#[test]
+fn force_apply_min_commission_batch_works() {
+	let prefs = |c| ValidatorPrefs { commission: Perbill::from_percent(c), blocked: false };
+	let validators = || Validators::<Test>::iter().collect::<Vec<_>>();
+	ExtBuilder::default().build_and_execute(|| {
+		assert_ok!(Staking::validate(RuntimeOrigin::signed(30), prefs(10)));
+		assert_ok!(Staking::validate(RuntimeOrigin::signed(20), prefs(5)));
+		assert_eq!(validators(), vec![(31, prefs(10)), (21, prefs(5)), (11, prefs(0))]);
+		MinCommission::<Test>::set(Perbill::from_percent(7));
+		let _ = staking_events_since_last_call();
+
+		// a mix of compliant, non-compliant and non-validator accounts.
+		let batch: BoundedVec<_, <Test as Config>::MaxCommissionBatch> =
+			bounded_vec![11, 31, 21, 420];
+		let call = || {
+			let call = TestCall::Staking(StakingCall::force_apply_min_commission_batch {
+				validators: batch.clone(),
+			});
+			let info = call.get_dispatch_info();
+			let result = call.dispatch(RuntimeOrigin::signed(1));
+			assert_ok!(result);
+			extract_actual_weight(&result, &info)
+		};
+		let read = <Test as frame_system::Config>::DbWeight::get().reads(1);
+
+		// only the validators below the minimum are raised, the rest is refunded.
+		assert_eq!(
+			call(),
+			<Test as Config>::WeightInfo::force_apply_min_commission_batch(2) + read * 2
+		);
+		assert_eq!(validators(), vec![(31, prefs(10)), (21, prefs(7)), (11, prefs(7))]);
+		assert_eq!(
+			staking_events_since_last_call(),
+			vec![
+				Event::MinCommissionApplied { stash: 11, commission: Perbill::from_percent(7) },
+				Event::MinCommissionApplied { stash: 21, commission: Perbill::from_percent(7) },
+			]
+		);
+
+		// applying it again is a no-op.
+		assert_eq!(
+			call(),
+			<Test as Config>::WeightInfo::force_apply_min_commission_batch(0) + read * 4
+		);
+		assert_eq!(validators(), vec![(31, prefs(10)), (21, prefs(7)), (11, prefs(7))]);
+		assert!(staking_events_since_last_call().is_empty());
+	});
+}
+
+#[test]
+fn repair_ledger_bonds_stash_to_its_ledger() {
+	ExtBuilder::default().build_and_execute(|| {
+		let _ = staking_events_since_last_call();
+
+		// the ledger of 11 ended up under the stash, while 11 is still bonded to 10.
+		let ledger = Ledger::<Test>::take(10).unwrap();
+		Ledger::<Test>::insert(11, ledger.clone());
+		assert!(Staking::do_try_state(System::block_number()).is_err());
+
+		assert_ok!(Staking::repair_ledger(RuntimeOrigin::signed(1), 11));
+		assert_eq!(Staking::bonded(11), Some(11));
+		assert_eq!(Staking::ledger(11), Some(ledger));
+		assert_ok!(Staking::do_try_state(System::block_number()));
+
+		// 21 is bonded to the controller of another stash, and its ledger is under the stash.
+		let ledger = Ledger::<Test>::take(20).unwrap();
+		Ledger::<Test>::insert(21, ledger);
+		Bonded::<Test>::insert(21, 100);
+		assert!(Staking::do_try_state(System::block_number()).is_err());
+
+		assert_ok!(Staking::repair_ledger(RuntimeOrigin::signed(1), 21));
+		assert_eq!(Staking::bonded(21), Some(21));
+		assert_eq!(Staking::ledger(100).unwrap().stash, 101);
+		assert_ok!(Staking::do_try_state(System::block_number()));
+
+		// 31 lost its bond entirely, but its ledger is under the stash.
+		let ledger = Ledger::<Test>::take(30).unwrap();
+		Ledger::<Test>::insert(31, ledger);
+		Bonded::<Test>::remove(31);
+
+		assert_ok!(Staking::repair_ledger(RuntimeOrigin::signed(1), 31));
+		assert_eq!(Staking::bonded(31), Some(31));
+
+		assert_eq!(
+			staking_events_since_last_call(),
+			vec![
+				Event::LedgerRepaired { stash: 11, old_controller: Some(10), controller: Some(11) },
+				Event::LedgerRepaired {
+					stash: 21,
+					old_controller: Some(100),
+					controller: Some(21)
+				},
+				Event::LedgerRepaired { stash: 31, old_controller: None, controller: Some(31) },
+			]
+		);
+
+		// the repaired stashes are fully functional again.
+		assert_ok!(Staking::validate(RuntimeOrigin::signed(11), Default::default()));
+		assert_ok!(Staking::unbond(RuntimeOrigin::signed(21), 100));
+		assert_ok!(Staking::chill(RuntimeOrigin::signed(31)));
+		assert_noop!(
+			Staking::repair_ledger(RuntimeOrigin::signed(1), 11),
+			Error::<Test>::LedgerNotCorrupted
+		);
+	});
+}
+
+#[test]
+fn repair_ledger_removes_leftovers_of_stash_without_ledger() {
+	ExtBuilder::default().build_and_execute(|| {
+		let _ = staking_events_since_last_call();
+
+		// the nominator 101 lost both its bond and its ledger.
+		Bonded::<Test>::remove(101);
+		Ledger::<Test>::remove(100);
+		assert!(Staking::do_try_state(System::block_number()).is_err());
+		assert!(!Balances::locks(&101).is_empty());
+
+		// the ledger might still be stored under some unknown controller, so only the stash
+		// itself can give up on it.
+		assert_noop!(Staking::repair_ledger(RuntimeOrigin::signed(1), 101), BadOrigin);
+
+		assert_ok!(Staking::repair_ledger(RuntimeOrigin::signed(101), 101));
+		assert!(!Payee::<Test>::contains_key(101));
+		assert!(!Nominators::<Test>::contains_key(101));
+		assert!(!<Test as Config>::VoterList::contains(&101));
+		// the funds of the stash are no longer locked.
+		assert!(Balances::locks(&101).is_empty());
+		assert_eq!(
+			staking_events_since_last_call(),
+			vec![Event::LedgerRepaired { stash: 101, old_controller: None, controller: None }]
+		);
+
+		// there is nothing left to repair.
+		assert_noop!(
+			Staking::repair_ledger(RuntimeOrigin::signed(1), 101),
+			Error::<Test>::NotStash
+		);
+	});
+}
+
+#[test]
+fn repair_ledger_fails_if_ledger_cannot_be_found() {
+	ExtBuilder::default().build_and_execute(|| {
+		// a consistent stash and an account that never staked.
+		assert_noop!(
+			Staking::repair_ledger(RuntimeOrigin::signed(1), 11),
+			Error::<Test>::LedgerNotCorrupted
+		);
+		assert_noop!(Staking::repair_ledger(RuntimeOrigin::signed(1), 1), Error::<Test>::NotStash);
+
+		// the ledger of 11 is gone.
+		let ledger = Ledger::<Test>::take(10).unwrap();
+		assert_noop!(
+			Staking::repair_ledger(RuntimeOrigin::signed(1), 11),
+			Error::<Test>::CannotRepairLedger
+		);
+		Ledger::<Test>::insert(10, ledger);
+
+		// 21 is bonded to the controller of 11 and its own ledger is gone.
+		let ledger = Ledger::<Test>::take(20).unwrap();
+		Bonded::<Test>::insert(21, 10);
+		assert_noop!(
+			Staking::repair_ledger(RuntimeOrigin::signed(1), 21),
+			Error::<Test>::CannotRepairLedger
+		);
+		Bonded::<Test>::insert(21, 20);
+		Ledger::<Test>::insert(20, ledger);
+	});
+}
+
 #[test]
 fn proportional_slash_stop_slashing_if_remaining_zero() {
 	let c = |era, value| UnlockChunk::<Balance> { era, value };
@@ -5815,4 +6440,136 @@ mod staking_interface {
 			));
 		});
 	}
+
+	#[test]
+	fn payee_works() {
+		ExtBuilder::default().build_and_execute(|| {
+			// stash 11 is controlled by 10 and its rewards are staked.
+			assert_eq!(<Staking as StakingInterface>::payee(&11), Some(11));
+
+			assert_ok!(Staking::set_payee(
+				RuntimeOrigin::signed(10),
+				RewardDestination::Controller
+			));
+			assert_eq!(<Staking as StakingInterface>::payee(&11), Some(10));
+
+			let _ = Balances::make_free_balance_be(&42, ExistentialDeposit::get());
+			assert_ok!(Staking::set_payee(
+				RuntimeOrigin::signed(10),
+				RewardDestination::Account(42)
+			));
+			assert_eq!(<Staking as StakingInterface>::payee(&11), Some(42));
+
+			assert_ok!(Staking::set_payee(RuntimeOrigin::signed(10), RewardDestination::None));
+			assert_eq!(<Staking as StakingInterface>::payee(&11), None);
+
+			// not bonded.
+			assert_eq!(<Staking as StakingInterface>::payee(&1), None);
+		});
+	}
+
+	#[test]
+	fn unbond_instantly_works() {
+		ExtBuilder::default().build_and_execute(|| {
+			// a validator that has not been elected yet.
+			bond_validator(61, 60, 1000);
+			assert_ok!(<Staking as StakingInterface>::unbond_instantly(&61, 400));
+			let ledger = Staking::ledger(&60).unwrap();
+			assert_eq!((ledger.total, ledger.active), (600, 600));
+			assert!(ledger.unlocking.is_empty());
+			// the funds are unlocked right away.
+			assert_eq!(Balances::locks(&61)[0].amount, 600);
+
+			// cannot unbond more than what is active.
+			assert_noop!(
+				<Staking as StakingInterface>::unbond_instantly(&61, 601),
+				Error::<Test>::InsufficientBond
+			);
+			// nor go below the minimum bond of the role of the staker.
+			MinValidatorBond::<Test>::put(500);
+			assert_noop!(
+				<Staking as StakingInterface>::unbond_instantly(&61, 101),
+				Error::<Test>::InsufficientBond
+			);
+			assert_ok!(<Staking as StakingInterface>::unbond_instantly(&61, 100));
+		});
+	}
+
+	#[test]
+	fn unbond_instantly_fails_for_recently_exposed_stakers() {
+		ExtBuilder::default().build_and_execute(|| {
+			// 11 and its nominator 101 are exposed in the genesis era.
+			assert!(Staking::exposed_in_era(11, 0));
+			assert_noop!(
+				<Staking as StakingInterface>::unbond_instantly(&11, 100),
+				Error::<Test>::RecentlyExposed
+			);
+			assert_noop!(
+				<Staking as StakingInterface>::unbond_instantly(&101, 100),
+				Error::<Test>::RecentlyExposed
+			);
+
+			// the exposure no longer counts once the bonding duration has passed.
+			bond_validator(61, 60, 2000);
+			mock::start_active_era(1);
+			assert!(Staking::exposed_in_era(61, 1));
+			assert_ok!(Staking::chill(RuntimeOrigin::signed(60)));
+			mock::start_active_era(1 + BondingDuration::get());
+			assert_noop!(
+				<Staking as StakingInterface>::unbond_instantly(&61, 100),
+				Error::<Test>::RecentlyExposed
+			);
+			mock::start_active_era(2 + BondingDuration::get());
+			assert_ok!(<Staking as StakingInterface>::unbond_instantly(&61, 100));
+		});
+	}
+
+	#[test]
+	fn eras_of_exposure_matches_is_exposed_in_era() {
+		ExtBuilder::default().build_and_execute(|| {
+			mock::start_active_era(5);
+
+			// validator 11 and its nominator 101 are exposed in every era, 1 is not staking.
+			for who in [11, 101, 1] {
+				let expected = (0..=5)
+					.filter(|era| <Staking as StakingInterface>::is_exposed_in_era(&who, era))
+					.collect::<Vec<_>>();
+				assert_eq!(Staking::eras_of_exposure(who, 0, 5), expected);
+				for era in 0..=5 {
+					assert_eq!(Staking::exposed_in_era(who, era), expected.contains(&era));
+				}
+			}
+			assert_eq!(Staking::eras_of_exposure(11, 0, 5), vec![0, 1, 2, 3, 4, 5]);
+			assert!(Staking::eras_of_exposure(1, 0, 5).is_empty());
+
+			// eras that are not planned yet are ignored.
+			assert_eq!(Staking::eras_of_exposure(11, 4, 100), vec![4, 5]);
+			assert!(!Staking::exposed_in_era(11, 6));
+
+			// eras older than `HistoryDepth` are ignored, even if still in storage.
+			HistoryDepth::set(2);
+			assert!(ErasStakers::<Test>::contains_key(0, 11));
+			assert_eq!(Staking::eras_of_exposure(11, 0, 5), vec![3, 4, 5]);
+			assert!(!Staking::exposed_in_era(11, 0));
+		});
+	}
+}
+
+#[test]
+fn era_payout_can_be_capped() {
+	ExtBuilder::default().build_and_execute(|| {
+		// by default the mock caps the reward curve above its maximum.
+		assert!(maximum_payout_for_duration(reward_time_per_era()) > 0);
+
+		MaxInflation::set(Perbill::zero());
+		assert_eq!(maximum_payout_for_duration(reward_time_per_era()), 0);
+
+		mock::start_active_era(1);
+		assert_eq!(
+			*mock::staking_events().last().unwrap(),
+			Event::EraPaid { era_index: 0, validator_payout: 0, remainder: 0 }
+		);
+		assert_eq!(ErasValidatorReward::<Test>::get(0), Some(0));
+		assert_eq!(mock::RewardRemainderUnbalanced::get(), 0);
+	});
 }
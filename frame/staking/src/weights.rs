@@ -68,6 +68,7 @@ pub trait WeightInfo {
 	fn cancel_deferred_slash(s: u32, ) -> Weight;
 	fn payout_stakers_dead_controller(n: u32, ) -> Weight;
 	fn payout_stakers_alive_staked(n: u32, ) -> Weight;
+	fn payout_stakers_by_page(n: u32, ) -> Weight;
 	fn rebond(l: u32, ) -> Weight;
 	fn reap_stash(s: u32, ) -> Weight;
 	fn new_era(v: u32, n: u32, ) -> Weight;
@@ -76,8 +77,11 @@ pub trait WeightInfo {
 	fn set_staking_configs_all_set() -> Weight;
 	fn set_staking_configs_all_remove() -> Weight;
 	fn chill_other() -> Weight;
+	fn kick_below_minimum(s: u32, v: u32, ) -> Weight;
 	fn force_apply_min_commission() -> Weight;
+	fn force_apply_min_commission_batch(v: u32, ) -> Weight;
 	fn set_min_commission() -> Weight;
+	fn repair_ledger() -> Weight;
 }
 
 /// Weights for pallet_staking using the Substrate node and recommended hardware.
@@ -86,12 +90,13 @@ impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
 	// Storage: Staking Bonded (r:1 w:1)
 	// Storage: Staking Ledger (r:1 w:1)
 	// Storage: Staking CurrentEra (r:1 w:0)
+	// Storage: System Account (r:1 w:0)
 	// Storage: Balances Locks (r:1 w:1)
 	// Storage: Staking Payee (r:0 w:1)
 	fn bond() -> Weight {
 		// Minimum execution time: 54_884 nanoseconds.
 		Weight::from_ref_time(55_487_000)
-			.saturating_add(T::DbWeight::get().reads(4))
+			.saturating_add(T::DbWeight::get().reads(5))
 			.saturating_add(T::DbWeight::get().writes(4))
 	}
 	// Storage: Staking Bonded (r:1 w:0)
@@ -221,11 +226,12 @@ impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
 			.saturating_add(T::DbWeight::get().writes(6))
 	}
 	// Storage: Staking Ledger (r:1 w:0)
+	// Storage: System Account (r:1 w:0)
 	// Storage: Staking Payee (r:0 w:1)
 	fn set_payee() -> Weight {
 		// Minimum execution time: 19_237 nanoseconds.
 		Weight::from_ref_time(19_534_000)
-			.saturating_add(T::DbWeight::get().reads(1))
+			.saturating_add(T::DbWeight::get().reads(2))
 			.saturating_add(T::DbWeight::get().writes(1))
 	}
 	// Storage: Staking Bonded (r:1 w:1)
@@ -343,6 +349,28 @@ impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
 			.saturating_add(T::DbWeight::get().writes(3))
 			.saturating_add(T::DbWeight::get().writes((3_u64).saturating_mul(n.into())))
 	}
+	// Storage: Staking CurrentEra (r:1 w:0)
+	// Storage: Staking ErasValidatorReward (r:1 w:0)
+	// Storage: Staking Bonded (r:1 w:0)
+	// Storage: Staking Ledger (r:1 w:1)
+	// Storage: Staking ErasStakersClipped (r:1 w:0)
+	// Storage: Staking ClaimedRewards (r:1 w:1)
+	// Storage: Staking ErasRewardPoints (r:1 w:0)
+	// Storage: Staking ErasValidatorPrefs (r:1 w:0)
+	// Storage: Staking Payee (r:1 w:0)
+	// Storage: System Account (r:1 w:1)
+	// Storage: Balances Locks (r:1 w:1)
+	/// The range of component `n` is `[0, 64]`.
+	fn payout_stakers_by_page(n: u32, ) -> Weight {
+		// Minimum execution time: 164_719 nanoseconds.
+		Weight::from_ref_time(226_304_276)
+			// Standard Error: 31_675
+			.saturating_add(Weight::from_ref_time(32_622_427).saturating_mul(n.into()))
+			.saturating_add(T::DbWeight::get().reads(11))
+			.saturating_add(T::DbWeight::get().reads((5_u64).saturating_mul(n.into())))
+			.saturating_add(T::DbWeight::get().writes(4))
+			.saturating_add(T::DbWeight::get().writes((3_u64).saturating_mul(n.into())))
+	}
 	// Storage: Staking Ledger (r:1 w:1)
 	// Storage: Balances Locks (r:1 w:1)
 	// Storage: System Account (r:1 w:1)
@@ -484,6 +512,66 @@ impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
 			.saturating_add(T::DbWeight::get().reads(11))
 			.saturating_add(T::DbWeight::get().writes(6))
 	}
+	// Storage: Staking Bonded (r:1 w:1)
+	// Storage: Staking Ledger (r:1 w:1)
+	// Storage: Staking Nominators (r:1 w:1)
+	// Storage: Staking MinNominatorBond (r:1 w:0)
+	// Storage: Staking CurrentEra (r:1 w:0)
+	// Storage: Staking ErasStakers (r:85000 w:0)
+	// Storage: System Account (r:3 w:2)
+	// Storage: Staking SlashingSpans (r:1 w:1)
+	// Storage: Staking Validators (r:1 w:0)
+	// Storage: Staking CounterForNominators (r:1 w:1)
+	// Storage: VoterList ListNodes (r:2 w:2)
+	// Storage: VoterList ListBags (r:1 w:1)
+	// Storage: VoterList CounterForListNodes (r:1 w:1)
+	// Storage: Balances Locks (r:1 w:1)
+	// Storage: Staking Payee (r:0 w:1)
+	// Storage: Staking SpanSlash (r:0 w:1)
+	/// The range of component `s` is `[1, 100]`.
+	/// The range of component `v` is `[1, 1000]`.
+	fn kick_below_minimum(s: u32, v: u32, ) -> Weight {
+		// Minimum execution time: 131_802 nanoseconds.
+		Weight::from_ref_time(102_365_171)
+			// Standard Error: 2_841
+			.saturating_add(Weight::from_ref_time(1_112_903).saturating_mul(s.into()))
+			// Standard Error: 285
+			.saturating_add(Weight::from_ref_time(5_730_412).saturating_mul(v.into()))
+			.saturating_add(T::DbWeight::get().reads(20))
+			.saturating_add(T::DbWeight::get().reads((85_u64).saturating_mul(v.into())))
+			.saturating_add(T::DbWeight::get().writes(15))
+			.saturating_add(T::DbWeight::get().writes((1_u64).saturating_mul(s.into())))
+	}
+	// Storage: Staking Bonded (r:1 w:1)
+	// Storage: Staking Ledger (r:1 w:1)
+	// Storage: Staking Nominators (r:1 w:1)
+	// Storage: Staking MinNominatorBond (r:1 w:0)
+	// Storage: Staking CurrentEra (r:1 w:0)
+	// Storage: Staking ErasStakers (r:85000 w:0)
+	// Storage: System Account (r:3 w:2)
+	// Storage: Staking SlashingSpans (r:1 w:1)
+	// Storage: Staking Validators (r:1 w:0)
+	// Storage: Staking CounterForNominators (r:1 w:1)
+	// Storage: VoterList ListNodes (r:2 w:2)
+	// Storage: VoterList ListBags (r:1 w:1)
+	// Storage: VoterList CounterForListNodes (r:1 w:1)
+	// Storage: Balances Locks (r:1 w:1)
+	// Storage: Staking Payee (r:0 w:1)
+	// Storage: Staking SpanSlash (r:0 w:1)
+	/// The range of component `s` is `[1, 100]`.
+	/// The range of component `v` is `[1, 1000]`.
+	fn kick_below_minimum(s: u32, v: u32, ) -> Weight {
+		// Minimum execution time: 131_802 nanoseconds.
+		Weight::from_ref_time(102_365_171)
+			// Standard Error: 2_841
+			.saturating_add(Weight::from_ref_time(1_112_903).saturating_mul(s.into()))
+			// Standard Error: 285
+			.saturating_add(Weight::from_ref_time(5_730_412).saturating_mul(v.into()))
+			.saturating_add(RocksDbWeight::get().reads(20))
+			.saturating_add(RocksDbWeight::get().reads((85_u64).saturating_mul(v.into())))
+			.saturating_add(RocksDbWeight::get().writes(15))
+			.saturating_add(RocksDbWeight::get().writes((1_u64).saturating_mul(s.into())))
+	}
 	// Storage: Staking MinCommission (r:1 w:0)
 	// Storage: Staking Validators (r:1 w:1)
 	fn force_apply_min_commission() -> Weight {
@@ -492,12 +580,39 @@ impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
 			.saturating_add(T::DbWeight::get().reads(2))
 			.saturating_add(T::DbWeight::get().writes(1))
 	}
+	// Storage: Staking MinCommission (r:1 w:0)
+	// Storage: Staking Validators (r:64 w:64)
+	fn force_apply_min_commission_batch(v: u32, ) -> Weight {
+		// Minimum execution time: 9_863 nanoseconds.
+		Weight::from_ref_time(10_358_172)
+			// Standard Error: 6_214
+			.saturating_add(Weight::from_ref_time(11_452_619).saturating_mul(v.into()))
+			.saturating_add(T::DbWeight::get().reads(1))
+			.saturating_add(T::DbWeight::get().reads((1_u64).saturating_mul(v.into())))
+			.saturating_add(T::DbWeight::get().writes((1_u64).saturating_mul(v.into())))
+	}
 	// Storage: Staking MinCommission (r:0 w:1)
 	fn set_min_commission() -> Weight {
 		// Minimum execution time: 6_995 nanoseconds.
 		Weight::from_ref_time(7_213_000)
 			.saturating_add(T::DbWeight::get().writes(1))
 	}
+	// Storage: Staking Bonded (r:1 w:0)
+	// Storage: Staking Ledger (r:1 w:0)
+	// Storage: Staking Payee (r:1 w:1)
+	// Storage: Staking Validators (r:1 w:0)
+	// Storage: Staking Nominators (r:1 w:1)
+	// Storage: Staking CounterForNominators (r:1 w:1)
+	// Storage: VoterList ListNodes (r:2 w:2)
+	// Storage: VoterList ListBags (r:1 w:1)
+	// Storage: VoterList CounterForListNodes (r:1 w:1)
+	// Storage: Balances Locks (r:1 w:1)
+	fn repair_ledger() -> Weight {
+		// Minimum execution time: 73_412 nanoseconds.
+		Weight::from_ref_time(74_208_000)
+			.saturating_add(T::DbWeight::get().reads(11))
+			.saturating_add(T::DbWeight::get().writes(8))
+	}
 }
 
 // For backwards compatibility and tests
@@ -505,12 +620,13 @@ impl WeightInfo for () {
 	// Storage: Staking Bonded (r:1 w:1)
 	// Storage: Staking Ledger (r:1 w:1)
 	// Storage: Staking CurrentEra (r:1 w:0)
+	// Storage: System Account (r:1 w:0)
 	// Storage: Balances Locks (r:1 w:1)
 	// Storage: Staking Payee (r:0 w:1)
 	fn bond() -> Weight {
 		// Minimum execution time: 54_884 nanoseconds.
 		Weight::from_ref_time(55_487_000)
-			.saturating_add(RocksDbWeight::get().reads(4))
+			.saturating_add(RocksDbWeight::get().reads(5))
 			.saturating_add(RocksDbWeight::get().writes(4))
 	}
 	// Storage: Staking Bonded (r:1 w:0)
@@ -640,11 +756,12 @@ impl WeightInfo for () {
 			.saturating_add(RocksDbWeight::get().writes(6))
 	}
 	// Storage: Staking Ledger (r:1 w:0)
+	// Storage: System Account (r:1 w:0)
 	// Storage: Staking Payee (r:0 w:1)
 	fn set_payee() -> Weight {
 		// Minimum execution time: 19_237 nanoseconds.
 		Weight::from_ref_time(19_534_000)
-			.saturating_add(RocksDbWeight::get().reads(1))
+			.saturating_add(RocksDbWeight::get().reads(2))
 			.saturating_add(RocksDbWeight::get().writes(1))
 	}
 	// Storage: Staking Bonded (r:1 w:1)
@@ -762,6 +879,28 @@ impl WeightInfo for () {
 			.saturating_add(RocksDbWeight::get().writes(3))
 			.saturating_add(RocksDbWeight::get().writes((3_u64).saturating_mul(n.into())))
 	}
+	// Storage: Staking CurrentEra (r:1 w:0)
+	// Storage: Staking ErasValidatorReward (r:1 w:0)
+	// Storage: Staking Bonded (r:1 w:0)
+	// Storage: Staking Ledger (r:1 w:1)
+	// Storage: Staking ErasStakersClipped (r:1 w:0)
+	// Storage: Staking ClaimedRewards (r:1 w:1)
+	// Storage: Staking ErasRewardPoints (r:1 w:0)
+	// Storage: Staking ErasValidatorPrefs (r:1 w:0)
+	// Storage: Staking Payee (r:1 w:0)
+	// Storage: System Account (r:1 w:1)
+	// Storage: Balances Locks (r:1 w:1)
+	/// The range of component `n` is `[0, 64]`.
+	fn payout_stakers_by_page(n: u32, ) -> Weight {
+		// Minimum execution time: 164_719 nanoseconds.
+		Weight::from_ref_time(226_304_276)
+			// Standard Error: 31_675
+			.saturating_add(Weight::from_ref_time(32_622_427).saturating_mul(n.into()))
+			.saturating_add(RocksDbWeight::get().reads(11))
+			.saturating_add(RocksDbWeight::get().reads((5_u64).saturating_mul(n.into())))
+			.saturating_add(RocksDbWeight::get().writes(4))
+			.saturating_add(RocksDbWeight::get().writes((3_u64).saturating_mul(n.into())))
+	}
 	// Storage: Staking Ledger (r:1 w:1)
 	// Storage: Balances Locks (r:1 w:1)
 	// Storage: System Account (r:1 w:1)
@@ -911,10 +1050,37 @@ impl WeightInfo for () {
 			.saturating_add(RocksDbWeight::get().reads(2))
 			.saturating_add(RocksDbWeight::get().writes(1))
 	}
+	// Storage: Staking MinCommission (r:1 w:0)
+	// Storage: Staking Validators (r:64 w:64)
+	fn force_apply_min_commission_batch(v: u32, ) -> Weight {
+		// Minimum execution time: 9_863 nanoseconds.
+		Weight::from_ref_time(10_358_172)
+			// Standard Error: 6_214
+			.saturating_add(Weight::from_ref_time(11_452_619).saturating_mul(v.into()))
+			.saturating_add(RocksDbWeight::get().reads(1))
+			.saturating_add(RocksDbWeight::get().reads((1_u64).saturating_mul(v.into())))
+			.saturating_add(RocksDbWeight::get().writes((1_u64).saturating_mul(v.into())))
+	}
 	// Storage: Staking MinCommission (r:0 w:1)
 	fn set_min_commission() -> Weight {
 		// Minimum execution time: 6_995 nanoseconds.
 		Weight::from_ref_time(7_213_000)
 			.saturating_add(RocksDbWeight::get().writes(1))
 	}
+	// Storage: Staking Bonded (r:1 w:0)
+	// Storage: Staking Ledger (r:1 w:0)
+	// Storage: Staking Payee (r:1 w:1)
+	// Storage: Staking Validators (r:1 w:0)
+	// Storage: Staking Nominators (r:1 w:1)
+	// Storage: Staking CounterForNominators (r:1 w:1)
+	// Storage: VoterList ListNodes (r:2 w:2)
+	// Storage: VoterList ListBags (r:1 w:1)
+	// Storage: VoterList CounterForListNodes (r:1 w:1)
+	// Storage: Balances Locks (r:1 w:1)
+	fn repair_ledger() -> Weight {
+		// Minimum execution time: 73_412 nanoseconds.
+		Weight::from_ref_time(74_208_000)
+			.saturating_add(RocksDbWeight::get().reads(11))
+			.saturating_add(RocksDbWeight::get().writes(8))
+	}
 }
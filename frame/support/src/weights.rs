@@ -47,6 +47,53 @@ pub mod constants {
 	};
 }
 
+/// The inclusive range of values a component of a weight function has been benchmarked with.
+///
+/// Weight functions extrapolate beyond this range, which might not reflect the actual cost.
+/// Weight files generated from the `frame-weight-template` expose one such range per component.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ComponentRange {
+	/// The lowest benchmarked value.
+	pub min: u32,
+	/// The highest benchmarked value.
+	pub max: u32,
+}
+
+impl ComponentRange {
+	/// Create a new range from `min` to `max`, both inclusive.
+	pub const fn new(min: u32, max: u32) -> Self {
+		Self { min, max }
+	}
+
+	/// Whether `value` has been covered by the benchmarks.
+	pub const fn contains(&self, value: u32) -> bool {
+		self.min <= value && value <= self.max
+	}
+
+	/// Defensively check that `value` of `component` of the weight function `function` has been
+	/// covered by the benchmarks, and return it unchanged to compute the weight with.
+	///
+	/// A value beyond the range only logs a warning, since the component may well come from user
+	/// input and the extrapolated weight is still the best estimate there is. The check is only
+	/// done in `std` or with debug assertions, such that release runtimes pay nothing for it.
+	pub fn check(&self, function: &str, component: &str, value: u32) -> u32 {
+		#[cfg(any(feature = "std", debug_assertions))]
+		if !self.contains(value) {
+			crate::log::warn!(
+				target: "runtime::weights",
+				"`{}` called with `{} = {}`, beyond the benchmarked range {:?}",
+				function,
+				component,
+				value,
+				self,
+			);
+		}
+		#[cfg(not(any(feature = "std", debug_assertions)))]
+		let _ = (function, component);
+		value
+	}
+}
+
 #[deprecated = "Function has moved to `frame_support::dispatch`"]
 pub fn extract_actual_pays_fee(
 	res: &dispatch::DispatchResultWithPostInfo,
@@ -2211,3 +2211,13 @@ implement_fixed!(
 	"_Fixed Point 128 bits unsigned, range = \
 		[0.000000000000000000, 340282366920938463463.374607431768211455]_",
 );
+
+implement_fixed!(
+	FixedU128E24,
+	test_fixed_u128_e24,
+	u128,
+	false,
+	1_000_000_000_000_000_000_000_000,
+	"_Fixed Point 128 bits unsigned with 24 decimals, range = \
+		[0.000000000000000000000000, 340282366920938.463463374607431768211455]_",
+);
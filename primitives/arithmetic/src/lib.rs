@@ -40,7 +40,9 @@ pub mod per_things;
 pub mod rational;
 pub mod traits;
 
-pub use fixed_point::{FixedI128, FixedI64, FixedPointNumber, FixedPointOperand, FixedU128};
+pub use fixed_point::{
+	FixedI128, FixedI64, FixedPointNumber, FixedPointOperand, FixedU128, FixedU128E24,
+};
 pub use per_things::{
 	InnerOf, MultiplyArg, PerThing, PerU16, Perbill, Percent, Permill, Perquintill, RationalArg,
 	ReciprocalArg, Rounding, SignedRounding, UpperOf,
@@ -94,8 +94,8 @@ pub use sp_arithmetic::helpers_128bit;
 /// Re-export top-level arithmetic stuff.
 pub use sp_arithmetic::{
 	traits::SaturatedConversion, FixedI128, FixedI64, FixedPointNumber, FixedPointOperand,
-	FixedU128, InnerOf, PerThing, PerU16, Perbill, Percent, Permill, Perquintill, Rational128,
-	Rounding, UpperOf,
+	FixedU128, FixedU128E24, InnerOf, PerThing, PerU16, Perbill, Percent, Permill, Perquintill,
+	Rational128, Rounding, UpperOf,
 };
 
 pub use either::Either;
@@ -42,19 +42,58 @@ pub trait OnStakerSlash<AccountId, Balance> {
 	/// * `slashed_active` - The new bonded balance of the staker after the slash was applied.
 	/// * `slashed_unlocking` - A map of slashed eras, and the balance of that unlocking chunk after
 	///   the slash is applied. Any era not present in the map is not affected at all.
+	/// * `slashed_total` - The aggregated balance that was slashed from the active and unlocking
+	///   balance of the staker.
+	/// * `slash_era` - The era in which the offence that caused the slash happened.
 	fn on_slash(
 		stash: &AccountId,
 		slashed_active: Balance,
 		slashed_unlocking: &BTreeMap<EraIndex, Balance>,
+		slashed_total: Balance,
+		slash_era: EraIndex,
 	);
 }
 
 impl<AccountId, Balance> OnStakerSlash<AccountId, Balance> for () {
-	fn on_slash(_: &AccountId, _: Balance, _: &BTreeMap<EraIndex, Balance>) {
+	fn on_slash(
+		_: &AccountId,
+		_: Balance,
+		_: &BTreeMap<EraIndex, Balance>,
+		_: Balance,
+		_: EraIndex,
+	) {
 		// Nothing to do here
 	}
 }
 
+/// An operation of a staker that can be vetoed through [`OnStakingOperation`].
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum StakingOperation {
+	/// Bonding extra funds.
+	BondExtra,
+	/// Unbonding funds.
+	Unbond,
+	/// Declaring the intention to nominate.
+	Nominate,
+	/// Declaring the intention to validate.
+	Validate,
+}
+
+/// Trait describing something that can veto the operations of a staker at dispatch time.
+///
+/// Unlike transaction level filtering, this also applies to calls that are dispatched through
+/// other pallets, e.g. as part of a batch or through a proxy.
+pub trait OnStakingOperation<AccountId> {
+	/// Returns an error if `stash` must not perform `operation`.
+	fn ensure_allowed(stash: &AccountId, operation: StakingOperation) -> DispatchResult;
+}
+
+impl<AccountId> OnStakingOperation<AccountId> for () {
+	fn ensure_allowed(_: &AccountId, _: StakingOperation) -> DispatchResult {
+		Ok(())
+	}
+}
+
 /// A struct that reflects stake that an account has in the staking system. Provides a set of
 /// methods to operate on it's properties. Aimed at making `StakingInterface` more concise.
 pub struct Stake<T: StakingInterface + ?Sized> {
@@ -95,6 +134,12 @@ pub trait StakingInterface {
 	/// The minimum amount required to bond in order to set validation intentions.
 	fn minimum_validator_bond() -> Self::Balance;
 
+	/// The minimum active stake of the nominators that were part of the last election snapshot.
+	///
+	/// Nominators with less active stake than this are unlikely to be elected, and thus to earn
+	/// rewards.
+	fn minimum_active_stake() -> Self::Balance;
+
 	/// Return a stash account that is controlled by a `controller`.
 	///
 	/// ## Note
@@ -131,6 +176,8 @@ pub trait StakingInterface {
 	}
 
 	/// Bond (lock) `value` of `who`'s balance, while forwarding any rewards to `payee`.
+	///
+	/// `payee` must already exist.
 	fn bond(who: &Self::AccountId, value: Self::Balance, payee: &Self::AccountId)
 		-> DispatchResult;
 
@@ -156,6 +203,15 @@ pub trait StakingInterface {
 	/// schedules have reached their unlocking era should allow more calls to this function.
 	fn unbond(stash: &Self::AccountId, value: Self::Balance) -> DispatchResult;
 
+	/// Immediately unlock `value` of the active bonded balance of `stash`, without waiting for
+	/// [`Self::bonding_duration`].
+	///
+	/// Implementations must return an error if `stash` has been exposed in the current era or any
+	/// of the [`Self::bonding_duration`] eras before it, see [`Self::is_exposed_in_era`], since
+	/// funds that can still be slashed would escape otherwise. Callers are expected to check this
+	/// beforehand to fail early, and must account for the weight of the check being done again.
+	fn unbond_instantly(stash: &Self::AccountId, value: Self::Balance) -> DispatchResult;
+
 	/// Unlock any funds schedule to unlock before or at the current era.
 	///
 	/// Returns whether the stash was killed because of this withdraw or not.
@@ -164,9 +220,28 @@ pub trait StakingInterface {
 		num_slashing_spans: u32,
 	) -> Result<bool, DispatchError>;
 
+	/// The number of slashing spans of `stash`.
+	///
+	/// This is the `num_slashing_spans` that is expected by [`Self::withdraw_unbonded`].
+	fn slashing_spans_count(stash: &Self::AccountId) -> u32;
+
+	/// The account that receives the staking rewards of `stash`.
+	///
+	/// Returns `None` if `stash` is not bonded or does not receive any rewards.
+	fn payee(stash: &Self::AccountId) -> Option<Self::AccountId>;
+
 	/// The ideal number of active validators.
 	fn desired_validator_count() -> u32;
 
+	/// The maximum number of targets a single nominator can nominate.
+	fn max_nominations() -> u32;
+
+	/// The number of nominators.
+	fn nominators_count() -> u32;
+
+	/// The maximum number of nominators, `None` if there is no limit.
+	fn max_nominators() -> Option<u32>;
+
 	/// Whether or not there is an ongoing election.
 	fn election_ongoing() -> bool;
 
@@ -177,7 +252,6 @@ pub trait StakingInterface {
 	fn is_exposed_in_era(who: &Self::AccountId, era: &EraIndex) -> bool;
 
 	/// Get the nominations of a stash, if they are a nominator, `None` otherwise.
-	#[cfg(feature = "runtime-benchmarks")]
 	fn nominations(who: Self::AccountId) -> Option<Vec<Self::AccountId>>;
 
 	#[cfg(feature = "runtime-benchmarks")]
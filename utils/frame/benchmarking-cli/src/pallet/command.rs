@@ -148,6 +148,8 @@ impl PalletCmd {
 				Err(error) =>
 					return Err(format!("Failed to deserialize {:?}: {}", json_input, error).into()),
 			};
+			let batches: Vec<_> =
+				batches.into_iter().filter(|batch| !self.is_excluded(&batch.pallet)).collect();
 			return self.output_from_results(&batches)
 		}
 
@@ -211,6 +213,7 @@ impl PalletCmd {
 		let mut benchmarks_to_run = Vec::new();
 		list.iter()
 			.filter(|item| pallet.is_empty() || pallet == &b"*"[..] || pallet == &item.pallet[..])
+			.filter(|item| !self.is_excluded(&item.pallet))
 			.for_each(|item| {
 				for benchmark in &item.benchmarks {
 					let benchmark_name = &benchmark.name;
@@ -243,6 +246,9 @@ impl PalletCmd {
 		let mut timer = time::SystemTime::now();
 		// Maps (pallet, extrinsic) to its component ranges.
 		let mut component_ranges = HashMap::<(Vec<u8>, Vec<u8>), Vec<ComponentRange>>::new();
+		// Benchmarks that failed together with the reason. They are reported once all other
+		// benchmarks ran instead of aborting on the first failure.
+		let mut failed = Vec::<(Vec<u8>, Vec<u8>, String)>::new();
 
 		for (pallet, extrinsic, components) in benchmarks_to_run {
 			log::info!(
@@ -251,185 +257,229 @@ impl PalletCmd {
 				String::from_utf8(pallet.clone()).expect("Encoded from String; qed"),
 				String::from_utf8(extrinsic.clone()).expect("Encoded from String; qed"),
 			);
-			let all_components = if components.is_empty() {
-				vec![Default::default()]
-			} else {
-				let mut all_components = Vec::new();
-				for (idx, (name, low, high)) in components.iter().enumerate() {
-					let lowest = self.lowest_range_values.get(idx).cloned().unwrap_or(*low);
-					let highest = self.highest_range_values.get(idx).cloned().unwrap_or(*high);
-
-					let diff =
-						highest.checked_sub(lowest).ok_or("`low` cannot be higher than `high`")?;
-
-					// The slope logic needs at least two points
-					// to compute a slope.
-					if self.steps < 2 {
-						return Err("`steps` must be at least 2.".into())
-					}
+			// The results of a benchmark are only kept if all of its runs succeed.
+			let mut benchmark_batches = Vec::new();
+			let mut benchmark_batches_db = Vec::new();
+			let mut benchmark_ranges = Vec::new();
+			let outcome = (|| -> Result<()> {
+				let all_components = if components.is_empty() {
+					vec![Default::default()]
+				} else {
+					let mut all_components = Vec::new();
+					for (idx, (name, low, high)) in components.iter().enumerate() {
+						let lowest = self.lowest_range_values.get(idx).cloned().unwrap_or(*low);
+						let highest = self.highest_range_values.get(idx).cloned().unwrap_or(*high);
+
+						let diff = highest
+							.checked_sub(lowest)
+							.ok_or("`low` cannot be higher than `high`")?;
+
+						// The slope logic needs at least two points
+						// to compute a slope.
+						if self.steps < 2 {
+							return Err("`steps` must be at least 2.".into())
+						}
 
-					let step_size = (diff as f32 / (self.steps - 1) as f32).max(0.0);
-
-					for s in 0..self.steps {
-						// This is the value we will be testing for component `name`
-						let component_value =
-							((lowest as f32 + step_size * s as f32) as u32).clamp(lowest, highest);
-
-						// Select the max value for all the other components.
-						let c: Vec<(BenchmarkParameter, u32)> = components
-							.iter()
-							.enumerate()
-							.map(|(idx, (n, _, h))| {
-								if n == name {
-									(*n, component_value)
-								} else {
-									(*n, *self.highest_range_values.get(idx).unwrap_or(h))
-								}
-							})
-							.collect();
-						all_components.push(c);
-					}
+						let step_size = (diff as f32 / (self.steps - 1) as f32).max(0.0);
+
+						for s in 0..self.steps {
+							// This is the value we will be testing for component `name`
+							let component_value = ((lowest as f32 + step_size * s as f32) as u32)
+								.clamp(lowest, highest);
+
+							// Select the max value for all the other components.
+							let c: Vec<(BenchmarkParameter, u32)> = components
+								.iter()
+								.enumerate()
+								.map(|(idx, (n, _, h))| {
+									if n == name {
+										(*n, component_value)
+									} else {
+										(*n, *self.highest_range_values.get(idx).unwrap_or(h))
+									}
+								})
+								.collect();
+							all_components.push(c);
+						}
 
-					component_ranges
-						.entry((pallet.clone(), extrinsic.clone()))
-						.or_default()
-						.push(ComponentRange { name: name.to_string(), min: lowest, max: highest });
-				}
-				all_components
-			};
-			for (s, selected_components) in all_components.iter().enumerate() {
-				// First we run a verification
-				if !self.no_verify {
-					let state = &state_without_tracking;
-					let result = StateMachine::new(
-						state,
-						&mut changes,
-						&executor,
-						"Benchmark_dispatch_benchmark",
-						&(
-							&pallet,
-							&extrinsic,
-							&selected_components.clone(),
-							true, // run verification code
-							1,    // no need to do internal repeats
-						)
-							.encode(),
-						extensions(),
-						&sp_state_machine::backend::BackendRuntimeCode::new(state)
-							.runtime_code()?,
-						sp_core::testing::TaskExecutor::new(),
-					)
-					.execute(strategy.into())
-					.map_err(|e| {
-						format!("Error executing and verifying runtime benchmark: {}", e)
-					})?;
-					// Dont use these results since verification code will add overhead.
-					let _batch =
-						<std::result::Result<Vec<BenchmarkBatch>, String> as Decode>::decode(
-							&mut &result[..],
+						benchmark_ranges.push(ComponentRange {
+							name: name.to_string(),
+							min: lowest,
+							max: highest,
+						});
+					}
+					all_components
+				};
+				for (s, selected_components) in all_components.iter().enumerate() {
+					// First we run a verification
+					if !self.no_verify {
+						let state = &state_without_tracking;
+						let result = StateMachine::new(
+							state,
+							&mut changes,
+							&executor,
+							"Benchmark_dispatch_benchmark",
+							&(
+								&pallet,
+								&extrinsic,
+								&selected_components.clone(),
+								true, // run verification code
+								1,    // no need to do internal repeats
+							)
+								.encode(),
+							extensions(),
+							&sp_state_machine::backend::BackendRuntimeCode::new(state)
+								.runtime_code()?,
+							sp_core::testing::TaskExecutor::new(),
 						)
-						.map_err(|e| format!("Failed to decode benchmark results: {:?}", e))?
+						.execute(strategy.into())
 						.map_err(|e| {
-							format!(
-								"Benchmark {}::{} failed: {}",
-								String::from_utf8_lossy(&pallet),
-								String::from_utf8_lossy(&extrinsic),
-								e
-							)
+							format!("Error executing and verifying runtime benchmark: {}", e)
 						})?;
-				}
-				// Do one loop of DB tracking.
-				{
-					let state = &state_with_tracking;
-					let result = StateMachine::new(
-						state, // todo remove tracking
-						&mut changes,
-						&executor,
-						"Benchmark_dispatch_benchmark",
-						&(
-							&pallet.clone(),
-							&extrinsic.clone(),
-							&selected_components.clone(),
-							false, // dont run verification code for final values
-							self.repeat,
-						)
-							.encode(),
-						extensions(),
-						&sp_state_machine::backend::BackendRuntimeCode::new(state)
-							.runtime_code()?,
-						sp_core::testing::TaskExecutor::new(),
-					)
-					.execute(strategy.into())
-					.map_err(|e| format!("Error executing runtime benchmark: {}", e))?;
-
-					let batch =
-						<std::result::Result<Vec<BenchmarkBatch>, String> as Decode>::decode(
-							&mut &result[..],
+						// Dont use these results since verification code will add overhead.
+						let _batch =
+							<std::result::Result<Vec<BenchmarkBatch>, String> as Decode>::decode(
+								&mut &result[..],
+							)
+							.map_err(|e| format!("Failed to decode benchmark results: {:?}", e))?
+							.map_err(|e| {
+								format!(
+									"Benchmark {}::{} failed: {}",
+									String::from_utf8_lossy(&pallet),
+									String::from_utf8_lossy(&extrinsic),
+									e
+								)
+							})?;
+					}
+					// Do one loop of DB tracking.
+					{
+						let state = &state_with_tracking;
+						let result = StateMachine::new(
+							state, // todo remove tracking
+							&mut changes,
+							&executor,
+							"Benchmark_dispatch_benchmark",
+							&(
+								&pallet.clone(),
+								&extrinsic.clone(),
+								&selected_components.clone(),
+								false, // dont run verification code for final values
+								self.repeat,
+							)
+								.encode(),
+							extensions(),
+							&sp_state_machine::backend::BackendRuntimeCode::new(state)
+								.runtime_code()?,
+							sp_core::testing::TaskExecutor::new(),
 						)
-						.map_err(|e| format!("Failed to decode benchmark results: {:?}", e))??;
+						.execute(strategy.into())
+						.map_err(|e| format!("Error executing runtime benchmark: {}", e))?;
 
-					batches_db.extend(batch);
-				}
-				// Finally run a bunch of loops to get extrinsic timing information.
-				for r in 0..self.external_repeat {
-					let state = &state_without_tracking;
-					let result = StateMachine::new(
-						state, // todo remove tracking
-						&mut changes,
-						&executor,
-						"Benchmark_dispatch_benchmark",
-						&(
-							&pallet.clone(),
-							&extrinsic.clone(),
-							&selected_components.clone(),
-							false, // dont run verification code for final values
-							self.repeat,
-						)
-							.encode(),
-						extensions(),
-						&sp_state_machine::backend::BackendRuntimeCode::new(state)
-							.runtime_code()?,
-						sp_core::testing::TaskExecutor::new(),
-					)
-					.execute(strategy.into())
-					.map_err(|e| format!("Error executing runtime benchmark: {}", e))?;
-
-					let batch =
-						<std::result::Result<Vec<BenchmarkBatch>, String> as Decode>::decode(
-							&mut &result[..],
+						let batch =
+							<std::result::Result<Vec<BenchmarkBatch>, String> as Decode>::decode(
+								&mut &result[..],
+							)
+							.map_err(|e| format!("Failed to decode benchmark results: {:?}", e))??;
+
+						benchmark_batches_db.extend(batch);
+					}
+					// Finally run a bunch of loops to get extrinsic timing information.
+					for r in 0..self.external_repeat {
+						let state = &state_without_tracking;
+						let result = StateMachine::new(
+							state, // todo remove tracking
+							&mut changes,
+							&executor,
+							"Benchmark_dispatch_benchmark",
+							&(
+								&pallet.clone(),
+								&extrinsic.clone(),
+								&selected_components.clone(),
+								false, // dont run verification code for final values
+								self.repeat,
+							)
+								.encode(),
+							extensions(),
+							&sp_state_machine::backend::BackendRuntimeCode::new(state)
+								.runtime_code()?,
+							sp_core::testing::TaskExecutor::new(),
 						)
-						.map_err(|e| format!("Failed to decode benchmark results: {:?}", e))??;
-
-					batches.extend(batch);
-
-					// Show progress information
-					if let Ok(elapsed) = timer.elapsed() {
-						if elapsed >= time::Duration::from_secs(5) {
-							timer = time::SystemTime::now();
-
-							log::info!(
-								target: LOG_TARGET,
-								"Running Benchmark: {}.{}({} args) {}/{} {}/{}",
-								String::from_utf8(pallet.clone())
-									.expect("Encoded from String; qed"),
-								String::from_utf8(extrinsic.clone())
-									.expect("Encoded from String; qed"),
-								components.len(),
-								s + 1, // s starts at 0.
-								all_components.len(),
-								r + 1,
-								self.external_repeat,
-							);
+						.execute(strategy.into())
+						.map_err(|e| format!("Error executing runtime benchmark: {}", e))?;
+
+						let batch =
+							<std::result::Result<Vec<BenchmarkBatch>, String> as Decode>::decode(
+								&mut &result[..],
+							)
+							.map_err(|e| format!("Failed to decode benchmark results: {:?}", e))??;
+
+						benchmark_batches.extend(batch);
+
+						// Show progress information
+						if let Ok(elapsed) = timer.elapsed() {
+							if elapsed >= time::Duration::from_secs(5) {
+								timer = time::SystemTime::now();
+
+								log::info!(
+									target: LOG_TARGET,
+									"Running Benchmark: {}.{}({} args) {}/{} {}/{}",
+									String::from_utf8(pallet.clone())
+										.expect("Encoded from String; qed"),
+									String::from_utf8(extrinsic.clone())
+										.expect("Encoded from String; qed"),
+									components.len(),
+									s + 1, // s starts at 0.
+									all_components.len(),
+									r + 1,
+									self.external_repeat,
+								);
+							}
 						}
 					}
 				}
+				Ok(())
+			})();
+
+			match outcome {
+				Ok(()) => {
+					batches.extend(benchmark_batches);
+					batches_db.extend(benchmark_batches_db);
+					if !benchmark_ranges.is_empty() {
+						component_ranges.insert((pallet, extrinsic), benchmark_ranges);
+					}
+				},
+				Err(error) => {
+					log::error!(
+						target: LOG_TARGET,
+						"Benchmark {}::{} failed: {}",
+						String::from_utf8_lossy(&pallet),
+						String::from_utf8_lossy(&extrinsic),
+						error,
+					);
+					failed.push((pallet, extrinsic, error.to_string()));
+				},
 			}
 		}
 
 		// Combine all of the benchmark results, so that benchmarks of the same pallet/function
 		// are together.
 		let batches = combine_batches(batches, batches_db);
-		self.output(&batches, &storage_info, &component_ranges)
+		self.output(&batches, &storage_info, &component_ranges)?;
+
+		if !failed.is_empty() {
+			println!("{} benchmark(s) failed:", failed.len());
+			for (pallet, extrinsic, error) in &failed {
+				println!(
+					"  {}::{}: {}",
+					String::from_utf8_lossy(pallet),
+					String::from_utf8_lossy(extrinsic),
+					error
+				);
+			}
+			return Err(format!("{} benchmark(s) failed", failed.len()).into())
+		}
+
+		Ok(())
 	}
 
 	fn output(
@@ -452,6 +502,11 @@ impl PalletCmd {
 		Ok(())
 	}
 
+	/// Whether `pallet` was excluded with `--exclude-pallets`.
+	fn is_excluded(&self, pallet: &[u8]) -> bool {
+		self.exclude_pallets.iter().any(|p| p.as_bytes() == pallet)
+	}
+
 	fn output_from_results(&self, batches: &[BenchmarkBatchSplitResults]) -> Result<()> {
 		let mut component_ranges =
 			HashMap::<(Vec<u8>, Vec<u8>), HashMap<String, (u32, u32)>>::new();
@@ -38,6 +38,13 @@ pub struct PalletCmd {
 	#[arg(short, long, value_parser = parse_pallet_name, required_unless_present_any = ["list", "json_input"])]
 	pub pallet: Option<String>,
 
+	/// Pallets that are excluded from benchmarking. Mostly useful together with `--pallet "*"`.
+	///
+	/// Pallet names are comma separated and parsed in the same way as `--pallet`. Excluded pallets
+	/// are also left out of `--list` and of the results read with `--json-input`.
+	#[arg(long, value_parser = parse_pallet_name, value_delimiter = ',')]
+	pub exclude_pallets: Vec<String>,
+
 	/// Select an extrinsic inside the pallet to benchmark, or `*` for all.
 	#[arg(short, long, required_unless_present_any = ["list", "json_input"])]
 	pub extrinsic: Option<String>,
@@ -313,6 +313,7 @@ pub(crate) fn write_results(
 	let mut handlebars = handlebars::Handlebars::new();
 	handlebars.register_helper("underscore", Box::new(UnderscoreHelper));
 	handlebars.register_helper("join", Box::new(JoinHelper));
+	handlebars.register_helper("uppercase", Box::new(UppercaseHelper));
 	// Don't HTML escape any characters.
 	handlebars.register_escape_fn(|s| -> String { s.to_string() });
 
@@ -403,10 +404,6 @@ pub(crate) fn add_storage_comments(
 
 	for result in results {
 		for (key, reads, writes, whitelisted) in &result.keys {
-			// skip keys which are whitelisted
-			if *whitelisted {
-				continue
-			}
 			let prefix_length = key.len().min(32);
 			let prefix = key[0..prefix_length].to_vec();
 			if identified.contains(&prefix) {
@@ -416,26 +413,27 @@ pub(crate) fn add_storage_comments(
 				// track newly identified keys
 				identified.insert(prefix.clone());
 			}
+			// whitelisted keys are not counted, but listed nonetheless.
+			let access = if *whitelisted {
+				"(whitelisted)".to_string()
+			} else {
+				format!("(r:{} w:{})", reads, writes)
+			};
 			match storage_info_map.get(&prefix) {
 				Some(key_info) => {
 					let comment = format!(
-						"Storage: {} {} (r:{} w:{})",
+						"Storage: {} {} {}",
 						String::from_utf8(key_info.pallet_name.clone())
 							.expect("encoded from string"),
 						String::from_utf8(key_info.storage_name.clone())
 							.expect("encoded from string"),
-						reads,
-						writes,
+						access,
 					);
 					comments.push(comment)
 				},
 				None => {
-					let comment = format!(
-						"Storage: unknown [0x{}] (r:{} w:{})",
-						HexDisplay::from(key),
-						reads,
-						writes,
-					);
+					let comment =
+						format!("Storage: unknown [0x{}] {}", HexDisplay::from(key), access);
 					comments.push(comment)
 				},
 			}
@@ -474,6 +472,25 @@ impl handlebars::HelperDef for JoinHelper {
 	}
 }
 
+// A helper to turn a string into uppercase, i.e. to derive the name of a constant.
+#[derive(Clone, Copy)]
+struct UppercaseHelper;
+impl handlebars::HelperDef for UppercaseHelper {
+	fn call<'reg: 'rc, 'rc>(
+		&self,
+		h: &handlebars::Helper,
+		_: &handlebars::Handlebars,
+		_: &handlebars::Context,
+		_rc: &mut handlebars::RenderContext,
+		out: &mut dyn handlebars::Output,
+	) -> handlebars::HelperResult {
+		use handlebars::JsonRender;
+		let param = h.param(0).unwrap();
+		out.write(&param.value().render().to_uppercase())?;
+		Ok(())
+	}
+}
+
 // u128 does not serialize well into JSON for `handlebars`, so we represent it as a string.
 fn string_serialize<S>(x: &u128, s: S) -> Result<S::Ok, S::Error>
 where
@@ -577,4 +594,34 @@ mod test {
 		assert_eq!(second_pallet_benchmark.name, "first_benchmark");
 		check_data(second_pallet_benchmark, "c", 3, 4);
 	}
+
+	#[test]
+	fn add_storage_comments_lists_whitelisted_keys() {
+		let info = |storage_name: &[u8], prefix: &[u8]| StorageInfo {
+			pallet_name: b"Pallet".to_vec(),
+			storage_name: storage_name.to_vec(),
+			prefix: prefix.to_vec(),
+			max_values: None,
+			max_size: None,
+		};
+		let storage_info = [info(b"Counted", &[1; 32]), info(b"Whitelisted", &[2; 32])];
+		let mut result =
+			test_data(b"first", b"first", BenchmarkParameter::a, 10, 3).db_results[0].clone();
+		result.keys = vec![
+			([1; 32].to_vec(), 1, 1, false),
+			([2; 32].to_vec(), 0, 0, true),
+			([3; 32].to_vec(), 0, 0, true),
+		];
+
+		let mut comments = Vec::new();
+		add_storage_comments(&mut comments, &[result], &storage_info);
+		assert_eq!(
+			comments,
+			vec![
+				"Storage: Pallet Counted (r:1 w:1)".to_string(),
+				"Storage: Pallet Whitelisted (whitelisted)".to_string(),
+				format!("Storage: unknown [0x{}] (whitelisted)", HexDisplay::from(&vec![3u8; 32])),
+			]
+		);
+	}
 }